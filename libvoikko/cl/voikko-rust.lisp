@@ -117,16 +117,21 @@
   (start-pos         :size)
   (error-len         :size)
   (short-description :pointer)  ; char*
-  (suggestions       :pointer)) ; char** NULL-terminated
+  (suggestions       :pointer)  ; char** NULL-terminated
+  (severity          :int))     ; 0=Error, 1=Warning
 
 (defcstruct voikko-grammar-error-array
   (errors :pointer) ; VoikkoGrammarError*
   (count  :size))
 
 (defcstruct voikko-token
-  (token-type :int)
-  (text       :pointer) ; char*
-  (position   :size))
+  (token-type  :int)
+  (text        :pointer) ; char*
+  (position    :size)
+  (start-byte  :size)
+  (end-byte    :size)
+  (start-utf16 :size)
+  (end-utf16   :size))
 
 (defcstruct voikko-token-array
   (tokens :pointer) ; VoikkoToken*
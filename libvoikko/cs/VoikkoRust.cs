@@ -91,6 +91,7 @@ internal struct NativeGrammarError
     public nuint ErrorLen;
     public IntPtr ShortDescription; // char*
     public IntPtr Suggestions;      // char** (NULL-terminated)
+    public int Severity;            // 0=Error, 1=Warning
 }
 
 [StructLayout(LayoutKind.Sequential)]
@@ -106,6 +107,10 @@ internal struct NativeToken
     public int TokenType;
     public IntPtr Text;    // char*
     public nuint Position;
+    public nuint StartByte;
+    public nuint EndByte;
+    public nuint StartUtf16;
+    public nuint EndUtf16;
 }
 
 [StructLayout(LayoutKind.Sequential)]
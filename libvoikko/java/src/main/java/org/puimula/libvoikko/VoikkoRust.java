@@ -105,16 +105,23 @@ public class VoikkoRust implements AutoCloseable {
      *     size_t error_len;
      *     char *short_description;
      *     char **suggestions;  // NULL-terminated
+     *     int severity;        // 0=Error, 1=Warning
+     *     VoikkoReplacement *replacements;
+     *     size_t replacement_count;
      * } VoikkoGrammarError;
      * </pre>
      */
-    @Structure.FieldOrder({"error_code", "start_pos", "error_len", "short_description", "suggestions"})
+    @Structure.FieldOrder({"error_code", "start_pos", "error_len", "short_description", "suggestions",
+                           "severity", "replacements", "replacement_count"})
     public static class NativeVoikkoGrammarError extends Structure {
         public int error_code;
         public long start_pos;
         public long error_len;
         public Pointer short_description;
         public Pointer suggestions;
+        public int severity;
+        public Pointer replacements;
+        public long replacement_count;
 
         public NativeVoikkoGrammarError() { super(); }
         public NativeVoikkoGrammarError(Pointer p) { super(p); read(); }
@@ -142,14 +149,23 @@ public class VoikkoRust implements AutoCloseable {
      *     int token_type;
      *     char *text;
      *     size_t position;
+     *     size_t start_byte;
+     *     size_t end_byte;
+     *     size_t start_utf16;
+     *     size_t end_utf16;
      * } VoikkoToken;
      * </pre>
      */
-    @Structure.FieldOrder({"token_type", "text", "position"})
+    @Structure.FieldOrder({"token_type", "text", "position",
+                           "start_byte", "end_byte", "start_utf16", "end_utf16"})
     public static class NativeVoikkoToken extends Structure {
         public int token_type;
         public Pointer text;
         public long position;
+        public long start_byte;
+        public long end_byte;
+        public long start_utf16;
+        public long end_utf16;
 
         public NativeVoikkoToken() { super(); }
         public NativeVoikkoToken(Pointer p) { super(p); read(); }
@@ -176,13 +192,15 @@ public class VoikkoRust implements AutoCloseable {
      * typedef struct {
      *     int sentence_type;
      *     size_t sentence_len;
+     *     size_t trimmed_len;
      * } VoikkoSentence;
      * </pre>
      */
-    @Structure.FieldOrder({"sentence_type", "sentence_len"})
+    @Structure.FieldOrder({"sentence_type", "sentence_len", "trimmed_len"})
     public static class NativeVoikkoSentence extends Structure {
         public int sentence_type;
         public long sentence_len;
+        public long trimmed_len;
 
         public NativeVoikkoSentence() { super(); }
         public NativeVoikkoSentence(Pointer p) { super(p); read(); }
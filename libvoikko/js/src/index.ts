@@ -2,6 +2,7 @@ import type {
   Token,
   Sentence,
   GrammarError,
+  Replacement,
   Analysis,
   SuggestionStrategy,
   TokenType,
@@ -15,6 +16,7 @@ export type {
   Token,
   Sentence,
   GrammarError,
+  Replacement,
   Analysis,
   TokenType,
   SentenceStartType,
@@ -31,6 +33,7 @@ interface WasmGrammarError {
   startPos: number;
   errorLen: number;
   suggestions: string[];
+  replacements: Replacement[];
   shortDescription: string;
 }
 
@@ -96,7 +99,7 @@ export class Voikko {
   /**
    * Initialize a Voikko instance for the given language.
    *
-   * Pipeline: loadWasm ∥ loadDict → new WasmVoikko(morData, autocorrData)
+   * Pipeline: loadWasm ∥ loadDict → new WasmVoikko(morData, autocorrData, { profile })
    * WASM module is cached after first call.
    *
    * @param language - BCP 47 language tag (default: 'fi')
@@ -117,7 +120,9 @@ export class Voikko {
     }
     const autocorrData = dict.get('autocorr.vfst') ?? null;
 
-    const handle = new WasmVoikko(morData, autocorrData);
+    const handle = new WasmVoikko(morData, autocorrData, {
+      profile: options.profile ?? 'standard',
+    });
     return new Voikko(handle);
   }
 
@@ -156,6 +161,7 @@ export class Voikko {
       startPos: e.startPos,
       errorLen: e.errorLen,
       suggestions: e.suggestions,
+      replacements: e.replacements,
       shortDescription: e.shortDescription,
     }));
   }
@@ -26,10 +26,24 @@ export interface GrammarError {
   errorLen: number;
   /** List of suggested replacements for the marked error */
   suggestions: string[];
+  /**
+   * For each suggestion, the characters to replace to apply it, in the
+   * order of `suggestions`
+   */
+  replacements: Replacement[];
   /** Human readable short description for the error. */
   shortDescription: string;
 }
 
+export interface Replacement {
+  /** Start of the replaced characters within the text */
+  start: number;
+  /** Number of replaced characters; 0 for an insertion */
+  len: number;
+  /** The new text; empty for a deletion */
+  text: string;
+}
+
 export interface Analysis {
   /**
    * Base form of the given word.
@@ -135,4 +149,11 @@ export interface VoikkoInitOptions {
    * If omitted, defaults to the unpkg CDN.
    */
   wasmUrl?: string;
+  /**
+   * Resource profile. 'lite' uses less memory on constrained devices such
+   * as mobile browsers: a smaller speller cache, no grammar checking
+   * (grammarErrors() returns an empty array) and analyze() returning only
+   * BASEFORM and CLASS. Default: 'standard'.
+   */
+  profile?: 'standard' | 'lite';
 }
@@ -0,0 +1,134 @@
+/**
+ * Lite profile under a capped wasm memory.
+ *
+ * The module is instantiated with the `maximum` of its `WebAssembly.Memory`
+ * set to the lite memory envelope documented in voikko-wasm/CLAUDE.md:
+ * the static data and stack the module starts with, plus twice the size of
+ * mor.vfst (the copy of the input bytes and the transducer), plus 2 MiB.
+ * An allocation past the cap makes `memory.grow` fail, which aborts the
+ * module, so construction or use beyond the envelope fails the test.
+ *
+ * Each instantiation re-imports the wasm-bindgen glue, which keeps one
+ * instance per module evaluation.
+ */
+import { describe, it, expect, vi } from 'vitest';
+import { existsSync } from 'node:fs';
+import { readFile } from 'node:fs/promises';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const DICT_PATH = process.env['VOIKKO_DICT_PATH'];
+const WASM_PATH = join(
+  dirname(fileURLToPath(import.meta.url)),
+  '..',
+  'wasm',
+  'voikko_wasm_bg.wasm',
+);
+const HAS_WASM = !!DICT_PATH && existsSync(WASM_PATH);
+
+const PAGE_SIZE = 64 * 1024;
+const ENVELOPE_EXTRA = 2 * 1024 * 1024;
+
+/** Read a LEB128 unsigned integer at `pos`; returns `[value, nextPos]`. */
+function readU32(bytes: Uint8Array, pos: number): [number, number] {
+  let value = 0;
+  let shift = 0;
+  for (;;) {
+    const byte = bytes[pos++]!;
+    value += (byte & 0x7f) * 2 ** shift;
+    if ((byte & 0x80) === 0) return [value, pos];
+    shift += 7;
+  }
+}
+
+/** Encode `value` as LEB128. */
+function writeU32(value: number): number[] {
+  const out: number[] = [];
+  do {
+    let byte = value & 0x7f;
+    value = Math.floor(value / 128);
+    if (value !== 0) byte |= 0x80;
+    out.push(byte);
+  } while (value !== 0);
+  return out;
+}
+
+/** Position of the memory section of `module` and its initial page count. */
+function memorySection(module: Uint8Array) {
+  let pos = 8; // magic and version
+  while (pos < module.length) {
+    const id = module[pos]!;
+    const [size, contentStart] = readU32(module, pos + 1);
+    const end = contentStart + size;
+    if (id === 5) {
+      const [count, limitsPos] = readU32(module, contentStart);
+      expect(count).toBe(1);
+      const [initialPages] = readU32(module, limitsPos + 1);
+      return { start: pos, end, initialPages };
+    }
+    pos = end;
+  }
+  throw new Error('the wasm module defines no memory');
+}
+
+/** Copy of `module` whose memory grows to at most `maxPages` pages. */
+function capMemory(module: Uint8Array, maxPages: number): Uint8Array {
+  const { start, end, initialPages } = memorySection(module);
+  expect(maxPages).toBeGreaterThanOrEqual(initialPages);
+  // One memory, flag 0x01: minimum and maximum
+  const content = [1, 0x01, ...writeU32(initialPages), ...writeU32(maxPages)];
+  const section = [5, ...writeU32(content.length), ...content];
+  const bytes = new Uint8Array(start + section.length + module.length - end);
+  bytes.set(module.subarray(0, start));
+  bytes.set(section, start);
+  bytes.set(module.subarray(end), start + section.length);
+  return bytes;
+}
+
+async function readMor(): Promise<Uint8Array> {
+  const flat = join(DICT_PATH!, 'mor.vfst');
+  const path = existsSync(flat) ? flat : join(DICT_PATH!, '5', 'mor-standard', 'mor.vfst');
+  return new Uint8Array(await readFile(path));
+}
+
+/**
+ * Instantiate the module with its memory capped at `capBytes(initialBytes)`,
+ * where `initialBytes` is the memory the module starts with.
+ */
+async function instantiate(capBytes: (initialBytes: number) => number) {
+  const module = new Uint8Array(await readFile(WASM_PATH));
+  const { initialPages } = memorySection(module);
+  const maxPages = Math.ceil(capBytes(initialPages * PAGE_SIZE) / PAGE_SIZE);
+  const bytes = capMemory(module, maxPages);
+  vi.resetModules();
+  const glue = await import('../wasm/voikko_wasm.js');
+  const exports = await glue.default({ module_or_path: bytes });
+  return { glue, memory: exports.memory, maxPages };
+}
+
+describe.skipIf(!HAS_WASM)('Lite profile memory envelope', () => {
+  it('constructs and checks words within the documented envelope', async () => {
+    const mor = await readMor();
+    const { glue, memory, maxPages } = await instantiate(
+      (initialBytes) => initialBytes + 2 * mor.length + ENVELOPE_EXTRA,
+    );
+
+    const voikko = new glue.WasmVoikko(mor, null, { profile: 'lite' });
+    expect(voikko.profile()).toBe('lite');
+    for (const word of ['koira', 'kissa', 'määrä', 'kuorma-auto']) {
+      expect(voikko.spell(word)).toBe(true);
+    }
+    expect(voikko.suggest('koari')).toContain('koira');
+    expect(voikko.hyphenate('kissa')).toBe('   - ');
+    voikko.terminate();
+
+    expect(memory.buffer.byteLength).toBeLessThanOrEqual(maxPages * PAGE_SIZE);
+  });
+
+  it('fails to construct when the cap is below the envelope', async () => {
+    const mor = await readMor();
+    const { glue } = await instantiate((initialBytes) => initialBytes + mor.length);
+
+    expect(() => new glue.WasmVoikko(mor, null, { profile: 'lite' })).toThrow();
+  });
+});
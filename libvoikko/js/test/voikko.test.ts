@@ -66,6 +66,7 @@ describe.skipIf(!HAS_DICTIONARY)('Voikko integration', () => {
       startPos: 10,
       errorLen: 11,
       suggestions: ['jotenkuten'],
+      replacements: [{ start: 15, len: 1, text: '' }],
       errorCode: 1,
       shortDescription: 'Virheellinen kirjoitusasu',
     }]);
@@ -324,3 +325,29 @@ describe.skipIf(!HAS_DICTIONARY)('Voikko option setters', () => {
     expect(voikko.suggest('koari')).toContain('koira');
   });
 });
+
+// -- Tier 2: Lite profile --
+
+describe.skipIf(!HAS_DICTIONARY)('Voikko lite profile', () => {
+  let voikko: VoikkoType;
+
+  beforeAll(async () => {
+    const { Voikko } = await import('../src/index.js');
+    voikko = await Voikko.init('fi', { dictionaryPath: DICT_PATH!, profile: 'lite' });
+  });
+
+  afterAll(() => {
+    voikko.terminate();
+  });
+
+  it('spells and analyzes with BASEFORM and CLASS only', () => {
+    expect(voikko.spell('koira')).toBe(true);
+    const analysis = voikko.analyze('koiran')[0]!;
+    expect(Object.keys(analysis).sort()).toEqual(['BASEFORM', 'CLASS']);
+    expect(analysis.BASEFORM).toBe('koira');
+  });
+
+  it('reports no grammar errors', () => {
+    expect(voikko.grammarErrors('Koira juoksi juoksi pihalla.')).toEqual([]);
+  });
+});
@@ -8,13 +8,16 @@
 //
 // Options:
 //   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   -0, --print0            NUL-delimited input and output records
+//   --json, --json-lines    One JSON object per word
 //   -h, --help              Print help
 
-use std::io::{self, BufRead, Write};
+use voikko_cli::RecordWriter;
 
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (mode, args) = voikko_cli::parse_output_mode(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-analyze: Morphological analysis of Finnish words.");
@@ -26,6 +29,8 @@ fn main() {
         println!();
         println!("Options:");
         println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  -0, --print0            Read and write NUL-delimited records");
+        println!("  --json, --json-lines    Print one JSON object per word");
         println!("  -h, --help              Print this help");
         return;
     }
@@ -36,27 +41,36 @@ fn main() {
         .cloned()
         .collect();
 
-    let handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
+    let mut out = RecordWriter::stdout(mode);
 
-    let analyze_word = |word: &str,
-                        handle: &voikko_fi::handle::VoikkoHandle,
-                        out: &mut io::BufWriter<io::StdoutLock<'_>>| {
+    let mut analyze_word = |word: &str| {
         let analyses = handle.analyze(word);
-        if analyses.is_empty() {
-            let _ = writeln!(out, "{word}: (no analysis)");
+        if out.is_json() {
+            let analyses: Vec<serde_json::Map<String, serde_json::Value>> = analyses
+                .iter()
+                .map(|analysis| {
+                    analysis
+                        .keys()
+                        .into_iter()
+                        .filter_map(|key| Some((key.to_string(), analysis.get(key)?.into())))
+                        .collect()
+                })
+                .collect();
+            out.json(&serde_json::json!({ "word": word, "analyses": analyses }));
+        } else if analyses.is_empty() {
+            out.line(&format!("{word}: (no analysis)"));
         } else {
-            let _ = writeln!(out, "{word}:");
+            out.line(&format!("{word}:"));
             for (i, analysis) in analyses.iter().enumerate() {
-                let _ = writeln!(out, "  Analysis {}:", i + 1);
+                out.line(&format!("  Analysis {}:", i + 1));
                 let mut keys: Vec<&str> = analysis.keys();
                 keys.sort();
                 for key in keys {
                     if let Some(val) = analysis.get(key) {
-                        let _ = writeln!(out, "    {key}={val}");
+                        out.line(&format!("    {key}={val}"));
                     }
                 }
             }
@@ -64,24 +78,10 @@ fn main() {
     };
 
     if words.is_empty() {
-        let stdin = io::stdin();
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("error reading stdin: {e}");
-                    break;
-                }
-            };
-            let word = line.trim();
-            if word.is_empty() {
-                continue;
-            }
-            analyze_word(word, &handle, &mut out);
-        }
+        voikko_cli::for_each_stdin_word(mode, analyze_word);
     } else {
         for word in &words {
-            analyze_word(word, &handle, &mut out);
+            analyze_word(word);
         }
     }
 }
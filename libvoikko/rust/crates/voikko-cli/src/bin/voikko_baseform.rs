@@ -38,8 +38,8 @@ fn main() {
         return;
     }
 
-    let handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
     let stdin = io::stdin();
     let mut known_freqs: HashMap<String, f64> = HashMap::new();
@@ -0,0 +1,137 @@
+// voikko-doctor: Check dictionary files for problems.
+//
+// Finds mor.vfst and autocorr.vfst in the dictionary search paths (or takes
+// the files given with -f), loads each with the strict loader, which walks
+// the transition table, and validates its flag diacritics. Prints one line
+// per file and one per problem. Exits with status 1 if a file is missing or
+// damaged, or has a flag diacritic that makes paths impossible.
+//
+// Usage:
+//   voikko-doctor [-d DICT_PATH] [-f FILE]...
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   -f, --file FILE        Check FILE instead of the dictionary (repeatable)
+//   -h, --help             Print help
+
+use std::path::PathBuf;
+
+use voikko_fi::dict_path::{AUTOCORR_VFST, MOR_VFST};
+use voikko_fst::flags::FlagDiagnostic;
+use voikko_fst::format;
+use voikko_fst::symbols::SymbolTableStats;
+use voikko_fst::unweighted::UnweightedTransducer;
+use voikko_fst::weighted::WeightedTransducer;
+
+/// What a checked file contained.
+struct Report {
+    weighted: bool,
+    stats: SymbolTableStats,
+    diagnostics: Vec<FlagDiagnostic>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-doctor: Check dictionary files for problems.");
+        println!();
+        println!("Usage: voikko-doctor [-d DICT_PATH] [-f FILE]...");
+        println!();
+        println!("Loads mor.vfst and autocorr.vfst (or each FILE) with the strict");
+        println!("loader and validates their flag diacritics. Exits with status 1 if");
+        println!("a file is missing or damaged or a flag diacritic can never pass.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  -f, --file FILE        Check FILE instead of the dictionary (repeatable)");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-f" || arg == "--file" {
+            match iter.next() {
+                Some(value) => files.push(value.into()),
+                None => voikko_cli::fatal(&format!("{arg} requires a value")),
+            }
+        } else if let Some(value) = arg.strip_prefix("--file=") {
+            files.push(value.into());
+        } else {
+            voikko_cli::fatal(&format!("unknown argument: {arg}"));
+        }
+    }
+
+    let mut failed = false;
+    if files.is_empty() {
+        match voikko_fi::dict_path::find_dict_file(dict_path.as_deref(), MOR_VFST) {
+            Ok(path) => files.push(path),
+            Err(e) => {
+                println!("mor.vfst: error: {e}");
+                failed = true;
+            }
+        }
+        match voikko_fi::dict_path::find_dict_file(dict_path.as_deref(), AUTOCORR_VFST) {
+            Ok(path) => files.push(path),
+            Err(_) => println!("autocorr.vfst: not found, grammar autocorrection is disabled"),
+        }
+    }
+
+    for path in &files {
+        let report = std::fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| check(&data).map_err(|e| e.to_string()));
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                println!("{}: error: {e}", path.display());
+                failed = true;
+                continue;
+            }
+        };
+        println!(
+            "{}: ok, {} transducer with {} symbols and {} flag diacritic features",
+            path.display(),
+            if report.weighted {
+                "weighted"
+            } else {
+                "unweighted"
+            },
+            report.stats.count,
+            report.stats.flag_features,
+        );
+        for diagnostic in &report.diagnostics {
+            let level = if diagnostic.is_error() {
+                failed = true;
+                "error"
+            } else {
+                "warning"
+            };
+            println!("{}: {level}: {diagnostic}", path.display());
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Load `data` strictly and validate its flag diacritics.
+fn check(data: &[u8]) -> Result<Report, voikko_fst::VfstError> {
+    let weighted = format::parse_header(data)?.weighted;
+    let (stats, diagnostics) = if weighted {
+        let t = WeightedTransducer::from_bytes_strict(data)?;
+        (t.symbols().stats(), t.validate_flags())
+    } else {
+        let t = UnweightedTransducer::from_bytes_strict(data)?;
+        (t.symbols().stats(), t.validate_flags())
+    };
+    Ok(Report {
+        weighted,
+        stats,
+        diagnostics,
+    })
+}
@@ -0,0 +1,128 @@
+// voikko-dump: List the words accepted by a VFST transducer.
+//
+// Walks an unweighted transducer (mor.vfst by default) from its start state
+// and prints the accepted input strings, sorted and without duplicates, so
+// that the word lists of two dictionary versions can be compared with diff.
+// With --outputs, prints every accepted path as input<TAB>output instead.
+// Compounding makes the lists of mor.vfst endless, so the walk stops after
+// --limit paths; a summary goes to stderr.
+//
+// Usage:
+//   voikko-dump [-d DICT_PATH] [-f FILE] [--prefix PREFIX] [--limit N] [--outputs]
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   -f, --file FILE        VFST file to use instead of mor.vfst
+//   --prefix PREFIX        List only inputs beginning with PREFIX
+//   --limit N              Stop after N accepted paths (default 1000000)
+//   --outputs              Print input<TAB>output for every path
+//   -h, --help             Print help
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use voikko_fi::dict_path::MOR_VFST;
+use voikko_fst::unweighted::UnweightedTransducer;
+
+/// Default maximum number of accepted paths.
+const DEFAULT_LIMIT: usize = 1_000_000;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-dump: List the words accepted by a VFST transducer.");
+        println!();
+        println!(
+            "Usage: voikko-dump [-d DICT_PATH] [-f FILE] [--prefix PREFIX] [--limit N] [--outputs]"
+        );
+        println!();
+        println!("Prints the accepted input strings, sorted and without duplicates,");
+        println!("one per line. Only unweighted transducers (mor.vfst, autocorr.vfst)");
+        println!("can be listed. A summary is printed to stderr.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  -f, --file FILE        VFST file to use instead of mor.vfst");
+        println!("  --prefix PREFIX        List only inputs beginning with PREFIX");
+        println!("  --limit N              Stop after N accepted paths (default {DEFAULT_LIMIT})");
+        println!("  --outputs              Print input<TAB>output for every path");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let mut file = None;
+    let mut prefix = String::new();
+    let mut limit = DEFAULT_LIMIT;
+    let mut outputs = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| match iter.next() {
+            Some(value) => value.clone(),
+            None => voikko_cli::fatal(&format!("{name} requires a value")),
+        };
+        if arg == "--outputs" {
+            outputs = true;
+        } else if arg == "-f" || arg == "--file" {
+            file = Some(value(arg));
+        } else if let Some(v) = arg.strip_prefix("--file=") {
+            file = Some(v.to_string());
+        } else if arg == "--prefix" {
+            prefix = value(arg);
+        } else if let Some(v) = arg.strip_prefix("--prefix=") {
+            prefix = v.to_string();
+        } else if arg == "--limit" {
+            limit = parse_limit(&value(arg));
+        } else if let Some(v) = arg.strip_prefix("--limit=") {
+            limit = parse_limit(v);
+        } else {
+            voikko_cli::fatal(&format!("unknown argument: {arg}"));
+        }
+    }
+
+    let path = match file {
+        Some(file) => file.into(),
+        None => voikko_fi::dict_path::find_dict_file(dict_path.as_deref(), MOR_VFST)
+            .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string())),
+    };
+    let data = std::fs::read(&path)
+        .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to read {}: {e}", path.display())));
+    let transducer = UnweightedTransducer::from_bytes(&data)
+        .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to load {}: {e}", path.display())));
+
+    let paths = transducer.enumerate_outputs(limit, &prefix);
+    let path_count = paths.len();
+    let lines: BTreeSet<String> = if outputs {
+        paths
+            .into_iter()
+            .map(|(input, output)| format!("{input}\t{output}"))
+            .collect()
+    } else {
+        paths.into_iter().map(|(input, _)| input).collect()
+    };
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    for line in &lines {
+        let _ = writeln!(out, "{line}");
+    }
+    let _ = out.flush();
+    eprintln!(
+        "{} {} from {path_count} accepted paths{}",
+        lines.len(),
+        if outputs { "paths" } else { "words" },
+        if path_count == limit {
+            " (limit reached, the list is incomplete)"
+        } else {
+            ""
+        }
+    );
+}
+
+/// Parse the value of --limit.
+fn parse_limit(value: &str) -> usize {
+    value
+        .parse()
+        .unwrap_or_else(|_| voikko_cli::fatal(&format!("invalid --limit: {value}")))
+}
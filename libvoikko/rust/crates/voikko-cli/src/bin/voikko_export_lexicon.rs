@@ -0,0 +1,240 @@
+// voikko-export-lexicon: Export the analyses of a word list as a lexicon.
+//
+// Analyzes each distinct word of a list and writes one (surface, lemma,
+// tags) row per analysis, as TSV or JSON lines, or compiles the rows into
+// a VFST transducer that maps each surface form to its "lemma<TAB>tags"
+// outputs, for search analyzers and other systems that cannot run the
+// Finnish morphology. Tags are the analysis attributes as sorted KEY=value
+// pairs, without the internal BASEFORM, FSTOUTPUT, STRUCTURE, WORDBASES
+// and WORDIDS. Unknown words are skipped; a summary goes to stderr.
+//
+// Usage:
+//   voikko-export-lexicon [-d DICT_PATH] [--words FILE] [--format FORMAT] [-o FILE]
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   --words FILE           Word list, one word per line (default: stdin)
+//   --format FORMAT        tsv (default), jsonl or fst
+//   -o, --output FILE      Output file (default: stdout)
+//   -h, --help             Print help
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use voikko_core::analysis::{
+    ATTR_BASEFORM, ATTR_FSTOUTPUT, ATTR_STRUCTURE, ATTR_WORDBASES, ATTR_WORDIDS, Analysis,
+};
+use voikko_fi::handle::VoikkoHandle;
+
+/// Attributes that describe how the analysis was built rather than the word.
+const INTERNAL_ATTRIBUTES: [&str; 5] = [
+    ATTR_BASEFORM,
+    ATTR_FSTOUTPUT,
+    ATTR_STRUCTURE,
+    ATTR_WORDBASES,
+    ATTR_WORDIDS,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Tsv,
+    JsonLines,
+    Fst,
+}
+
+/// One analysis of a word.
+struct Row {
+    surface: String,
+    lemma: String,
+    tags: Vec<(String, String)>,
+}
+
+impl Row {
+    fn tags_text(&self) -> String {
+        let tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        tags.join(",")
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-export-lexicon: Export the analyses of a word list.");
+        println!();
+        println!(
+            "Usage: voikko-export-lexicon [-d DICT_PATH] [--words FILE] [--format FORMAT] [-o FILE]"
+        );
+        println!();
+        println!("Analyzes each distinct word of the list and writes one row per");
+        println!("analysis:");
+        println!("  tsv    surface<TAB>lemma<TAB>KEY=value,...");
+        println!("  jsonl  {{\"surface\":...,\"lemma\":...,\"tags\":{{...}}}}");
+        println!("  fst    a VFST transducer from each surface form to its");
+        println!("         lemma<TAB>KEY=value,... outputs (voikko-fst-lookup -f)");
+        println!("Unknown words are skipped. A summary is printed to stderr.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  --words FILE           Word list, one word per line (default: stdin)");
+        println!("  --format FORMAT        tsv (default), jsonl or fst");
+        println!("  -o, --output FILE      Output file (default: stdout)");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let mut words_file = None;
+    let mut output_file = None;
+    let mut format = Format::Tsv;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (option, inline) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => (option, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        if !matches!(option, "--words" | "--format" | "-o" | "--output") {
+            voikko_cli::fatal(&format!("unknown argument: {arg}"));
+        }
+        let value = inline
+            .or_else(|| iter.next().cloned())
+            .unwrap_or_else(|| voikko_cli::fatal(&format!("{option} requires a value")));
+        match option {
+            "--words" => words_file = Some(value),
+            "--format" => {
+                format = match value.as_str() {
+                    "tsv" => Format::Tsv,
+                    "jsonl" => Format::JsonLines,
+                    "fst" => Format::Fst,
+                    _ => voikko_cli::fatal(&format!(
+                        "unknown format {value:?} (expected tsv, jsonl or fst)"
+                    )),
+                }
+            }
+            _ => output_file = Some(value),
+        }
+    }
+
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
+
+    let input: Box<dyn BufRead> = match &words_file {
+        Some(path) => Box::new(io::BufReader::new(
+            std::fs::File::open(path)
+                .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to open {path}: {e}"))),
+        )),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut out: Box<dyn Write> = match &output_file {
+        Some(path) => Box::new(io::BufWriter::new(
+            std::fs::File::create(path)
+                .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to create {path}: {e}"))),
+        )),
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut rows = Vec::new();
+    let mut words = 0;
+    let mut unknown = 0;
+    let mut analyses = 0;
+    for line in input.lines() {
+        let line = line.unwrap_or_else(|e| voikko_cli::fatal(&format!("error reading words: {e}")));
+        let word = line.trim();
+        if word.is_empty() || !seen.insert(word.to_string()) {
+            continue;
+        }
+        words += 1;
+        let word_rows = analyze(&handle, word);
+        if word_rows.is_empty() {
+            unknown += 1;
+        }
+        analyses += word_rows.len();
+        if format == Format::Fst {
+            rows.extend(word_rows);
+            continue;
+        }
+        for row in &word_rows {
+            write_row(&mut out, row, format);
+        }
+    }
+
+    if format == Format::Fst {
+        let outputs: Vec<String> = rows
+            .iter()
+            .map(|row| format!("{}\t{}", row.lemma, row.tags_text()))
+            .collect();
+        let entries = rows
+            .iter()
+            .zip(&outputs)
+            .map(|(row, output)| (row.surface.as_str(), output.as_str()));
+        let data = voikko_fst::compile::compile_unweighted(entries)
+            .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to compile lexicon: {e}")));
+        if let Err(e) = out.write_all(&data) {
+            voikko_cli::fatal(&format!("failed to write lexicon: {e}"));
+        }
+    }
+    if let Err(e) = out.flush() {
+        voikko_cli::fatal(&format!("failed to write lexicon: {e}"));
+    }
+
+    eprintln!("{words} words ({unknown} unknown): {analyses} analyses exported");
+}
+
+/// The rows of the distinct analyses of `word`.
+fn analyze(handle: &VoikkoHandle, word: &str) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+    for analysis in handle.analyze(word) {
+        let row = Row {
+            surface: word.to_string(),
+            lemma: analysis.get(ATTR_BASEFORM).unwrap_or(word).to_string(),
+            tags: tags(&analysis),
+        };
+        // Analyses that differ only in internal attributes give one row
+        if !rows
+            .iter()
+            .any(|r| r.lemma == row.lemma && r.tags == row.tags)
+        {
+            rows.push(row);
+        }
+    }
+    rows
+}
+
+fn tags(analysis: &Analysis) -> Vec<(String, String)> {
+    let mut tags: Vec<(String, String)> = analysis
+        .attributes()
+        .iter()
+        .filter(|(key, _)| !INTERNAL_ATTRIBUTES.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    tags.sort();
+    tags
+}
+
+fn write_row(out: &mut dyn Write, row: &Row, format: Format) {
+    let result = if format == Format::JsonLines {
+        let tags: serde_json::Map<String, serde_json::Value> = row
+            .tags
+            .iter()
+            .map(|(key, value)| (key.clone(), value.as_str().into()))
+            .collect();
+        let record = serde_json::json!({
+            "surface": row.surface,
+            "lemma": row.lemma,
+            "tags": tags,
+        });
+        serde_json::to_writer(&mut *out, &record)
+            .map_err(io::Error::from)
+            .and_then(|()| out.write_all(b"\n"))
+    } else {
+        writeln!(out, "{}\t{}\t{}", row.surface, row.lemma, row.tags_text())
+    };
+    if let Err(e) = result {
+        voikko_cli::fatal(&format!("failed to write lexicon: {e}"));
+    }
+}
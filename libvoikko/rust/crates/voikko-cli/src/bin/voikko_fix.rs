@@ -0,0 +1,319 @@
+// voikko-fix: Apply confident spelling corrections to a text.
+//
+// Reads running text, applies autocorrect replacements and unambiguous
+// spelling corrections, and writes the corrected text. Each line is treated
+// as a paragraph. A misspelled word is replaced only if its best suggestion
+// is one edit away from it and reaches the confidence threshold (see
+// VoikkoHandle::suggest_scored); a suggestion with no rivals is certain.
+// Words that merely are not in the dictionary, such as colloquial forms with
+// several near matches, are left alone.
+//
+// With --dry-run, nothing is written; instead a line-based diff of the
+// changes is printed to stdout.
+//
+// Usage:
+//   voikko-fix [-d DICT_PATH] [--in FILE] [--out FILE] [OPTIONS]
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   --in FILE              Input file (default: stdin)
+//   --out FILE             Output file (default: stdout)
+//   --threshold VALUE      Minimum confidence 0.0-1.0 (default: 0.8)
+//   --no-autocorrect       Do not apply autocorrect replacements
+//   --dry-run              Print a diff instead of the corrected text
+//   -h, --help             Print help
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::GCERR_INVALID_SPELLING;
+use voikko_fi::handle::VoikkoHandle;
+use voikko_fi::suggestion::edit_distance;
+
+/// A replacement of `len` characters starting at character offset `start`.
+struct Edit {
+    start: usize,
+    len: usize,
+    replacement: String,
+}
+
+struct FixOptions {
+    threshold: f64,
+    autocorrect: bool,
+}
+
+/// Compute the edits for one paragraph (a line without its line ending).
+fn paragraph_edits(paragraph: &str, handle: &VoikkoHandle, options: &FixOptions) -> Vec<Edit> {
+    let mut edits: Vec<Edit> = Vec::new();
+
+    if options.autocorrect {
+        for error in handle.grammar_errors(paragraph) {
+            if error.error_code == GCERR_INVALID_SPELLING {
+                if let Some(replacement) = error.suggestions.into_iter().next() {
+                    edits.push(Edit {
+                        start: error.start_pos,
+                        len: error.error_len,
+                        replacement,
+                    });
+                }
+            }
+        }
+    }
+
+    for token in handle.tokens(paragraph) {
+        if token.token_type != TokenType::Word {
+            continue;
+        }
+        let overlaps = edits
+            .iter()
+            .any(|e| token.pos < e.start + e.len && e.start < token.pos + token.token_len);
+        if overlaps || handle.spell(&token.text) {
+            continue;
+        }
+        if let Some(best) = handle.suggest_scored(&token.text).into_iter().next() {
+            if best.confidence >= options.threshold && is_one_edit_away(&token.text, &best.word) {
+                edits.push(Edit {
+                    start: token.pos,
+                    len: token.token_len,
+                    replacement: best.word,
+                });
+            }
+        }
+    }
+
+    edits.sort_by_key(|e| e.start);
+    edits
+}
+
+/// Whether `suggestion` differs from `word` by at most one edit, ignoring
+/// case.
+fn is_one_edit_away(word: &str, suggestion: &str) -> bool {
+    let lower = |s: &str| {
+        s.chars()
+            .flat_map(char::to_lowercase)
+            .collect::<Vec<char>>()
+    };
+    edit_distance(&lower(word), &lower(suggestion)) <= 1
+}
+
+/// Apply non-overlapping edits (sorted by start) to a paragraph.
+fn apply_edits(paragraph: &str, edits: &[Edit]) -> String {
+    let chars: Vec<char> = paragraph.chars().collect();
+    let mut result = String::with_capacity(paragraph.len());
+    let mut pos = 0;
+    for edit in edits {
+        if edit.start < pos {
+            continue;
+        }
+        result.extend(&chars[pos..edit.start]);
+        result.push_str(&edit.replacement);
+        pos = edit.start + edit.len;
+    }
+    result.extend(&chars[pos.min(chars.len())..]);
+    result
+}
+
+/// Split a line read with `read_line` into content and line ending.
+fn split_line_ending(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content, &line[content.len()..])
+}
+
+fn parse_value<'a>(args: &'a [String], i: usize, name: &str) -> &'a str {
+    args.get(i + 1)
+        .map(String::as_str)
+        .unwrap_or_else(|| voikko_cli::fatal(&format!("{name} requires a value")))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-fix: Apply confident spelling corrections to a text.");
+        println!();
+        println!("Usage: voikko-fix [-d DICT_PATH] [--in FILE] [--out FILE] [OPTIONS]");
+        println!();
+        println!("Each line is a paragraph. Autocorrect replacements are applied, and");
+        println!("misspelled words are replaced by their best suggestion if it is");
+        println!("one edit away and its confidence reaches the threshold.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  --in FILE              Input file (default: stdin)");
+        println!("  --out FILE             Output file (default: stdout)");
+        println!("  --threshold VALUE      Minimum confidence 0.0-1.0 (default: 0.8)");
+        println!("  --no-autocorrect       Do not apply autocorrect replacements");
+        println!("  --dry-run              Print a diff instead of the corrected text");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let mut in_path = None;
+    let mut out_path = None;
+    let mut dry_run = false;
+    let mut options = FixOptions {
+        threshold: 0.8,
+        autocorrect: true,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--in" => {
+                in_path = Some(parse_value(&args, i, "--in").to_string());
+                i += 1;
+            }
+            "--out" => {
+                out_path = Some(parse_value(&args, i, "--out").to_string());
+                i += 1;
+            }
+            "--threshold" => {
+                let value = parse_value(&args, i, "--threshold");
+                options.threshold = value
+                    .parse()
+                    .ok()
+                    .filter(|t: &f64| (0.0..=1.0).contains(t))
+                    .unwrap_or_else(|| voikko_cli::fatal(&format!("invalid threshold: {value}")));
+                i += 1;
+            }
+            "--no-autocorrect" => options.autocorrect = false,
+            "--dry-run" => dry_run = true,
+            other => voikko_cli::fatal(&format!("unknown option: {other}")),
+        }
+        i += 1;
+    }
+
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
+
+    let mut input: Box<dyn BufRead> = match &in_path {
+        Some(path) => {
+            Box::new(BufReader::new(File::open(path).unwrap_or_else(|e| {
+                voikko_cli::fatal(&format!("failed to open {path}: {e}"))
+            })))
+        }
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut out: Box<dyn Write> = match (&out_path, dry_run) {
+        (Some(path), false) => {
+            Box::new(BufWriter::new(File::create(path).unwrap_or_else(|e| {
+                voikko_cli::fatal(&format!("failed to create {path}: {e}"))
+            })))
+        }
+        _ => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut fix_count = 0;
+    loop {
+        line.clear();
+        match input.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => voikko_cli::fatal(&format!("error reading input: {e}")),
+        }
+        line_number += 1;
+
+        let (content, ending) = split_line_ending(&line);
+        let edits = paragraph_edits(content, &handle, &options);
+        fix_count += edits.len();
+        let fixed = apply_edits(content, &edits);
+
+        let result = if dry_run {
+            if edits.is_empty() {
+                Ok(())
+            } else {
+                writeln!(out, "@@ line {line_number} @@\n-{content}\n+{fixed}")
+            }
+        } else {
+            write!(out, "{fixed}{ending}")
+        };
+        if let Err(e) = result {
+            voikko_cli::fatal(&format!("error writing output: {e}"));
+        }
+    }
+    if let Err(e) = out.flush() {
+        voikko_cli::fatal(&format!("error writing output: {e}"));
+    }
+    eprintln!("{fix_count} correction(s) in {line_number} line(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_fst::compile::compile_unweighted;
+
+    /// A dictionary that knows only `words`, as nouns.
+    fn nouns_vfst(words: &[&str]) -> Vec<u8> {
+        let analyses: Vec<String> = words.iter().map(|w| format!("[Ln]{w}")).collect();
+        compile_unweighted(
+            words
+                .iter()
+                .copied()
+                .zip(analyses.iter().map(String::as_str)),
+        )
+        .unwrap()
+    }
+
+    fn fix(handle: &VoikkoHandle, paragraph: &str) -> String {
+        let options = FixOptions {
+            threshold: 0.8,
+            autocorrect: true,
+        };
+        apply_edits(paragraph, &paragraph_edits(paragraph, handle, &options))
+    }
+
+    fn edit(start: usize, len: usize, replacement: &str) -> Edit {
+        Edit {
+            start,
+            len,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn apply_edits_counts_characters() {
+        let edits = [edit(0, 3, "Yö"), edit(7, 5, "päivä")];
+        assert_eq!(apply_edits("Yöö ja päävä.", &edits), "Yö ja päivä.");
+        assert_eq!(apply_edits("talo", &[]), "talo");
+        assert_eq!(apply_edits("talo", &[edit(4, 0, "t")]), "talot");
+    }
+
+    #[test]
+    fn apply_edits_skips_overlapping_edits() {
+        let edits = [edit(0, 6, "koira"), edit(2, 4, "x")];
+        assert_eq!(apply_edits("koiraa", &edits), "koira");
+    }
+
+    #[test]
+    fn paragraph_edits_correct_unambiguous_misspellings() {
+        let handle = VoikkoHandle::from_bytes(&nouns_vfst(&["koira"]), None, "fi").unwrap();
+        assert_eq!(fix(&handle, "koirra ja koira"), "koira ja koira");
+        assert_eq!(fix(&handle, "Koirra."), "Koira.");
+    }
+
+    #[test]
+    fn paragraph_edits_leave_ambiguous_and_distant_words() {
+        let handle = VoikkoHandle::from_bytes(&nouns_vfst(&["kala", "kela"]), None, "fi").unwrap();
+        assert_eq!(fix(&handle, "kxla"), "kxla");
+        assert_eq!(fix(&handle, "qwerty"), "qwerty");
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn correct_unknown_words_are_left_alone() {
+        let path = std::env::var("VOIKKO_MOR_VFST")
+            .unwrap_or_else(|_| "../../test-data/mor.vfst".to_string());
+        let handle = voikko_fi::dict_path::load_handle_from(std::path::Path::new(&path)).unwrap();
+        assert_eq!(
+            fix(&handle, "Hän kirjotti kirjeen."),
+            "Hän kirjotti kirjeen."
+        );
+        assert_eq!(
+            fix(&handle, "Helsinkki on kaupunki."),
+            "Helsinki on kaupunki."
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// voikko-fst-lookup: Look up words directly in a VFST transducer.
+//
+// Prints the raw transducer outputs for each word, without the Finnish
+// analysis layer. With --trace, also prints the path of every accepted
+// output: the states, consumed and emitted symbols, flag diacritic
+// operations with the feature values they set, and weights. With --flags,
+// lists the flag diacritic features of the transducer and their values;
+// with --symbols, the symbol table.
+// Works with both unweighted (mor.vfst, autocorr.vfst) and weighted
+// (spl.vfst, err.vfst) transducers.
+//
+// Usage:
+//   voikko-fst-lookup [-d DICT_PATH] [-f FILE] [--trace] [WORD...]
+//   voikko-fst-lookup [-d DICT_PATH] [-f FILE] --flags
+//   voikko-fst-lookup [-d DICT_PATH] [-f FILE] --symbols
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   -f, --file FILE        VFST file to use instead of mor.vfst
+//   --trace                Print the path of each output
+//   --flags                List flag diacritic features and values
+//   --symbols              List the symbol table, statistics on stderr
+//   -h, --help             Print help
+
+use std::io::{self, BufRead, Write};
+
+use voikko_fi::dict_path::MOR_VFST;
+use voikko_fst::Transducer;
+use voikko_fst::flags::{FlagEvent, FlagFeature};
+use voikko_fst::format;
+use voikko_fst::symbols::{SymbolKind, SymbolTable};
+use voikko_fst::trace::PathTrace;
+use voikko_fst::unweighted::UnweightedTransducer;
+use voikko_fst::weighted::{WeightedResult, WeightedTransducer};
+
+/// Traversal depth used by the analyzer, which limits words to 255 characters.
+const BUFFER_SIZE: usize = 2000;
+
+/// Stop after this many outputs for one word.
+const MAX_OUTPUTS: usize = 100;
+
+enum Fst {
+    Unweighted(UnweightedTransducer),
+    Weighted(WeightedTransducer),
+}
+
+/// One accepted output and, with --trace, its path.
+struct Output {
+    text: String,
+    weight: Option<i32>,
+    trace: Option<PathTrace>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-fst-lookup: Look up words in a VFST transducer.");
+        println!();
+        println!("Usage: voikko-fst-lookup [-d DICT_PATH] [-f FILE] [--trace] [WORD...]");
+        println!("       voikko-fst-lookup [-d DICT_PATH] [-f FILE] --flags");
+        println!("       voikko-fst-lookup [-d DICT_PATH] [-f FILE] --symbols");
+        println!();
+        println!("If WORD arguments are given, looks up each word.");
+        println!("Otherwise reads words from stdin (one per line).");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  -f, --file FILE        VFST file to use instead of mor.vfst");
+        println!("  --trace                Print the path of each output: states,");
+        println!("                         input:output symbols, flag diacritics, weights");
+        println!("  --flags                List the flag diacritic features and their values");
+        println!("  --symbols              List the symbols: index<TAB>kind<TAB>symbol,");
+        println!("                         with statistics on stderr");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let mut file = None;
+    let mut trace = false;
+    let mut list_flags = false;
+    let mut list_symbols = false;
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--trace" {
+            trace = true;
+        } else if arg == "--flags" {
+            list_flags = true;
+        } else if arg == "--symbols" {
+            list_symbols = true;
+        } else if arg == "-f" || arg == "--file" {
+            match iter.next() {
+                Some(value) => file = Some(value.clone()),
+                None => voikko_cli::fatal(&format!("{arg} requires a value")),
+            }
+        } else if let Some(value) = arg.strip_prefix("--file=") {
+            file = Some(value.to_string());
+        } else if !arg.starts_with('-') {
+            words.push(arg.clone());
+        }
+    }
+
+    let path = match file {
+        Some(file) => file.into(),
+        None => voikko_fi::dict_path::find_dict_file(dict_path.as_deref(), MOR_VFST)
+            .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string())),
+    };
+    let data = std::fs::read(&path)
+        .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to read {}: {e}", path.display())));
+    let fst = load(&data)
+        .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to load {}: {e}", path.display())));
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+
+    if list_flags {
+        let features = match &fst {
+            Fst::Unweighted(t) => t.flag_features(),
+            Fst::Weighted(t) => t.flag_features(),
+        };
+        write_flag_features(&mut out, &features);
+        return;
+    }
+    if list_symbols {
+        let symbols = match &fst {
+            Fst::Unweighted(t) => t.symbols(),
+            Fst::Weighted(t) => t.symbols(),
+        };
+        write_symbols(&mut out, symbols);
+        return;
+    }
+
+    let mut lookup_word = |word: &str| {
+        let outputs = lookup(&fst, word, trace);
+        if outputs.is_empty() {
+            let _ = writeln!(out, "{word}: (no output)");
+            return;
+        }
+        let _ = writeln!(out, "{word}:");
+        for output in &outputs {
+            match output.weight {
+                Some(weight) => {
+                    let _ = writeln!(out, "  {} (weight {weight})", output.text);
+                }
+                None => {
+                    let _ = writeln!(out, "  {}", output.text);
+                }
+            }
+            if let Some(trace) = &output.trace {
+                write_trace(&mut out, trace, output.weight.is_some());
+            }
+        }
+    };
+
+    if words.is_empty() {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("error reading stdin: {e}");
+                    break;
+                }
+            };
+            let word = line.trim();
+            if word.is_empty() {
+                continue;
+            }
+            lookup_word(word);
+        }
+    } else {
+        for word in &words {
+            lookup_word(word);
+        }
+    }
+}
+
+/// Load an unweighted or weighted transducer, as the header says.
+fn load(data: &[u8]) -> Result<Fst, voikko_fst::VfstError> {
+    if format::parse_header(data)?.weighted {
+        Ok(Fst::Weighted(WeightedTransducer::from_bytes(data)?))
+    } else {
+        Ok(Fst::Unweighted(UnweightedTransducer::from_bytes(data)?))
+    }
+}
+
+/// Collect the outputs of `word`, with their paths if `trace` is set.
+fn lookup(fst: &Fst, word: &str, trace: bool) -> Vec<Output> {
+    let mut outputs = Vec::new();
+    let mut text = String::new();
+    match fst {
+        Fst::Unweighted(t) => {
+            let mut config = t.new_config(buffer_size(word));
+            // Unknown characters match nothing, but traversal is still valid
+            t.prepare_str(&mut config, word);
+            while outputs.len() < MAX_OUTPUTS && t.next(&mut config, &mut text) {
+                outputs.push(Output {
+                    text: text.clone(),
+                    weight: None,
+                    trace: trace.then(|| t.trace(&config)),
+                });
+            }
+        }
+        Fst::Weighted(t) => {
+            let mut config = t.new_config(buffer_size(word));
+            if !t.prepare_str(&mut config, word) {
+                return outputs;
+            }
+            let mut result = WeightedResult {
+                weight: 0,
+                first_not_reached_position: 0,
+            };
+            while outputs.len() < MAX_OUTPUTS
+                && t.next_weighted(&mut config, &mut text, &mut result)
+            {
+                outputs.push(Output {
+                    text: text.clone(),
+                    weight: Some(i32::from(result.weight)),
+                    trace: trace.then(|| t.trace(&config)),
+                });
+            }
+        }
+    }
+    outputs
+}
+
+/// Traversal depth for `word`: the analyzer's depth on top of one level per
+/// character, so that a word of any length can be looked up.
+fn buffer_size(word: &str) -> usize {
+    BUFFER_SIZE + word.chars().count()
+}
+
+/// Show the empty (epsilon) symbol as `@0@`.
+fn symbol(s: &str) -> &str {
+    if s.is_empty() { "@0@" } else { s }
+}
+
+/// Print a path, one transition per line.
+fn write_trace(out: &mut impl Write, trace: &PathTrace, weighted: bool) {
+    let mut total: i32 = 0;
+    for step in &trace.steps {
+        let arc = if let Some(event) = &step.flag {
+            format!("{}  {}", step.input, describe_flag(event))
+        } else if step.is_flag {
+            format!("{} (flag)", step.input)
+        } else {
+            format!("{}:{}", symbol(&step.input), symbol(&step.output))
+        };
+        let _ = write!(
+            out,
+            "    {} -> {}  {arc}",
+            step.source_state, step.target_state
+        );
+        if weighted {
+            total += i32::from(step.weight);
+            let _ = write!(out, "  w={} (sum {total})", step.weight);
+        }
+        let _ = writeln!(out);
+    }
+    if weighted {
+        let _ = writeln!(
+            out,
+            "    {} final  w={} (total {})",
+            trace.final_state,
+            trace.final_weight,
+            trace.weight()
+        );
+    } else {
+        let _ = writeln!(out, "    {} final", trace.final_state);
+    }
+}
+
+/// Describe the effect of a flag operation, e.g. `CASE: (neutral) -> NOM`.
+fn describe_flag(event: &FlagEvent) -> String {
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "(neutral)".to_string());
+    if event.before == event.after {
+        format!("{}: {}", event.feature, value(&event.after))
+    } else {
+        format!(
+            "{}: {} -> {}",
+            event.feature,
+            value(&event.before),
+            value(&event.after)
+        )
+    }
+}
+
+/// Print one feature per line with the values its operations name.
+fn write_flag_features(out: &mut impl Write, features: &[FlagFeature]) {
+    if features.is_empty() {
+        let _ = writeln!(out, "(no flag diacritics)");
+    }
+    for feature in features {
+        let _ = writeln!(out, "{}\t{}", feature.name, feature.values.join(" "));
+    }
+}
+
+/// Print one symbol per line, and the table statistics to stderr.
+fn write_symbols(out: &mut impl Write, symbols: &SymbolTable) {
+    for info in symbols.iter() {
+        let kind = match info.kind {
+            SymbolKind::Epsilon => "epsilon",
+            SymbolKind::FlagDiacritic => "flag",
+            SymbolKind::Char => "char",
+            SymbolKind::MultiChar => "multichar",
+        };
+        let _ = writeln!(out, "{}\t{kind}\t{}", info.index, symbol(info.text));
+    }
+    let stats = symbols.stats();
+    eprintln!(
+        "{} symbols: {} flag diacritics ({} features), {} characters, {} multi-character; \
+         longest {} bytes ({} characters)",
+        stats.count,
+        stats.flag_diacritics,
+        stats.flag_features,
+        stats.chars,
+        stats.multi_chars,
+        stats.max_utf8_len,
+        stats.max_char_len
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_fst::compile::compile_unweighted;
+
+    #[test]
+    fn words_longer_than_the_analyzer_depth() {
+        let word = "a".repeat(BUFFER_SIZE + 500);
+        let data = compile_unweighted([("aaa", "aaa"), (word.as_str(), "long")]).unwrap();
+        let fst = load(&data).unwrap();
+        assert_eq!(lookup(&fst, "aaa", false)[0].text, "aaa");
+        let outputs = lookup(&fst, &word, false);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].text, "long");
+    }
+}
@@ -2,30 +2,141 @@
 //
 // Ported from Python tools/bin/voikko-gc-pretty.
 //
-// Reads running text from stdin, checks grammar, and prints errors in a
-// human-readable, diff-able format. Each paragraph is checked independently.
+// Reads running text from stdin or from the files given as arguments,
+// checks grammar, and prints errors in a human-readable, diff-able format.
+// Each paragraph is checked independently. With --format sarif, grammar
+// errors and misspelled words of all inputs are printed as one SARIF 2.1.0
+// log for code-review and CI systems.
 //
 // Usage:
-//   voikko-gc-pretty [-d DICT_PATH] [OPTIONS]
+//   voikko-gc-pretty [-d DICT_PATH] [OPTIONS] [FILE...]
 //
 // Options:
 //   -d, --dict-path PATH   Dictionary directory containing mor.vfst
 //   --empty-line            Paragraphs are separated by empty lines
 //                           (default: each line is a paragraph)
+//   --format FORMAT         text (default), json or sarif
+//   --json, --json-lines    Same as --format json: one JSON object per paragraph
+//   --rule-stats            Print per-rule timing to stderr at the end
 //   -h, --help              Print help
 
-use std::io::{self, BufRead, Write};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
 
-fn handle_paragraph(
-    paragraph: &str,
-    handle: &voikko_fi::handle::VoikkoHandle,
-    out: &mut io::BufWriter<io::StdoutLock<'_>>,
+use serde_json::{Value, json};
+use voikko_cli::{OutputMode, RecordWriter};
+use voikko_core::grammar_error::{self, GCERR_INVALID_SPELLING, GrammarErrorSeverity};
+use voikko_fi::handle::VoikkoHandle;
+
+/// Output format selected with --format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+/// Parse the value of --format.
+fn parse_format(value: &str) -> Format {
+    match value {
+        "text" => Format::Text,
+        "json" => Format::Json,
+        "sarif" => Format::Sarif,
+        _ => voikko_cli::fatal(&format!(
+            "invalid --format: {value} (expected text, json or sarif)"
+        )),
+    }
+}
+
+/// A paragraph and the input lines it was joined from.
+struct Paragraph {
+    text: String,
+    /// Where each input line starts in `text`: (character offset in `text`,
+    /// 1-based line number, 1-based column of that character in the line).
+    lines: Vec<(usize, usize, usize)>,
+}
+
+impl Paragraph {
+    /// 1-based line and column of the character at `offset` in `text`.
+    ///
+    /// An end offset (`is_end`) that falls exactly at the start of a later
+    /// line is reported as the end of the previous line instead.
+    fn position(&self, offset: usize, is_end: bool) -> (usize, usize) {
+        let &(start, line, column) = self
+            .lines
+            .iter()
+            .rev()
+            .find(|&&(start, _, _)| start < offset || (!is_end && start == offset))
+            .unwrap_or(&self.lines[0]);
+        (line, column + offset.saturating_sub(start))
+    }
+}
+
+/// Read paragraphs from `reader` and call `f` for each. Lines are trimmed;
+/// with `empty_line_separates`, the lines of a paragraph are joined with a
+/// space and paragraphs end at empty lines, otherwise every line is a
+/// paragraph.
+fn read_paragraphs(
+    reader: impl BufRead,
+    name: &str,
+    empty_line_separates: bool,
+    mut f: impl FnMut(&Paragraph),
 ) {
+    let mut paragraph = Paragraph {
+        text: String::new(),
+        lines: Vec::new(),
+    };
+    let mut paragraph_len = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("error reading {name}: {e}");
+                break;
+            }
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || !empty_line_separates {
+            if !paragraph.text.is_empty() {
+                f(&paragraph);
+                paragraph.text.clear();
+                paragraph.lines.clear();
+                paragraph_len = 0;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+        }
+        if !paragraph.text.is_empty() {
+            paragraph.text.push(' ');
+            paragraph_len += 1;
+        }
+        let indent = line.chars().count() - line.trim_start().chars().count();
+        paragraph.lines.push((paragraph_len, index + 1, indent + 1));
+        paragraph.text.push_str(trimmed);
+        paragraph_len += trimmed.chars().count();
+    }
+    // Handle trailing paragraph
+    if !paragraph.text.is_empty() {
+        f(&paragraph);
+    }
+}
+
+fn handle_paragraph<W: Write>(paragraph: &str, handle: &VoikkoHandle, out: &mut RecordWriter<W>) {
     let errors = handle.grammar_errors(paragraph);
+    if out.is_json() {
+        let errors: Vec<_> = errors
+            .iter()
+            .map(voikko_cli::grammar_error_record)
+            .collect();
+        out.json(&json!({ "paragraph": paragraph, "errors": errors }));
+        return;
+    }
     let para_chars: Vec<char> = paragraph.chars().collect();
 
     for error in &errors {
-        let _ = writeln!(out, "{paragraph}");
+        out.line(paragraph);
 
         let error_range: String = para_chars
             .iter()
@@ -33,92 +144,310 @@ fn handle_paragraph(
             .take(error.error_len)
             .collect();
 
-        let _ = writeln!(
-            out,
+        out.line(&format!(
             "E: {} (start={})",
             error.short_description, error.start_pos
-        );
-        let _ = writeln!(out, "E: \"{error_range}\"");
+        ));
+        out.line(&format!("E: \"{error_range}\""));
 
         for suggestion in &error.suggestions {
-            let _ = writeln!(out, "S:  \"{suggestion}\"");
+            out.line(&format!("S:  \"{suggestion}\""));
         }
-        let _ = writeln!(out, "=================================================");
+        out.line("=================================================");
     }
 }
 
+/// Collects SARIF results and the rules they refer to.
+#[derive(Default)]
+struct SarifLog {
+    /// Rules by error code; only codes that were reported are listed.
+    rules: BTreeMap<i32, Value>,
+    results: Vec<Value>,
+}
+
+impl SarifLog {
+    /// Add the grammar errors and misspelled words of a paragraph read
+    /// from the artifact `uri`.
+    fn add_paragraph(&mut self, paragraph: &Paragraph, uri: &str, handle: &VoikkoHandle) {
+        let errors = handle.grammar_errors(&paragraph.text);
+        for error in &errors {
+            let fixes: Vec<_> = if error.replacements.is_empty() {
+                error
+                    .suggestions
+                    .iter()
+                    .map(|s| (s.as_str(), error.start_pos, error.error_len, s.as_str()))
+                    .collect()
+            } else {
+                error
+                    .suggestions
+                    .iter()
+                    .zip(&error.replacements)
+                    .map(|(s, r)| (s.as_str(), r.start, r.len, r.text.as_str()))
+                    .collect()
+            };
+            self.add_result(
+                error.error_code,
+                &error.short_description,
+                paragraph,
+                uri,
+                (error.start_pos, error.error_len),
+                &fixes,
+            );
+        }
+        for token in handle.misspelled_tokens(&paragraph.text) {
+            // The autocorrect rule may already have reported the word.
+            if errors.iter().any(|e| {
+                e.error_code == GCERR_INVALID_SPELLING
+                    && e.start_pos == token.pos
+                    && e.error_len == token.token_len
+            }) {
+                continue;
+            }
+            let suggestions = handle.suggest(&token.text);
+            let fixes: Vec<_> = suggestions
+                .iter()
+                .map(|s| (s.as_str(), token.pos, token.token_len, s.as_str()))
+                .collect();
+            self.add_result(
+                GCERR_INVALID_SPELLING,
+                &format!("Unknown word: {}", token.text),
+                paragraph,
+                uri,
+                (token.pos, token.token_len),
+                &fixes,
+            );
+        }
+    }
+
+    /// Add one result for `span` (character offset and length in the
+    /// paragraph). Each fix is a suggestion and the edit that applies it:
+    /// a span of the paragraph and its new text.
+    fn add_result(
+        &mut self,
+        code: i32,
+        message: &str,
+        paragraph: &Paragraph,
+        uri: &str,
+        span: (usize, usize),
+        fixes: &[(&str, usize, usize, &str)],
+    ) {
+        let rule = self.rules.entry(code).or_insert_with(|| sarif_rule(code));
+        let level = rule["defaultConfiguration"]["level"].clone();
+        let location = |start: usize, len: usize| {
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri },
+                    "region": sarif_region(paragraph, start, len),
+                }
+            })
+        };
+        let fixes: Vec<_> = fixes
+            .iter()
+            .map(|&(suggestion, start, len, text)| {
+                json!({
+                    "description": { "text": suggestion },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri },
+                        "replacements": [{
+                            "deletedRegion": sarif_region(paragraph, start, len),
+                            "insertedContent": { "text": text },
+                        }],
+                    }],
+                })
+            })
+            .collect();
+        let mut result = json!({
+            "ruleId": rule_id(code),
+            "level": level,
+            "message": { "text": message },
+            "locations": [location(span.0, span.1)],
+        });
+        if !fixes.is_empty() {
+            result["fixes"] = Value::Array(fixes);
+        }
+        self.results.push(result);
+    }
+
+    /// The complete SARIF 2.1.0 log with a single run.
+    fn into_value(self) -> Value {
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "voikko-gc-pretty",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://voikko.puimula.org/",
+                        "rules": self.rules.into_values().collect::<Vec<_>>(),
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }]
+        })
+    }
+}
+
+/// SARIF rule id of an error code, after the `GCERR_*` constants.
+fn rule_id(code: i32) -> String {
+    format!("GCERR{code}")
+}
+
+/// SARIF rule metadata of an error code. Errors are reported at level
+/// "warning" and style remarks at level "note"; "error" is left for
+/// problems that should fail a build.
+fn sarif_rule(code: i32) -> Value {
+    let level = match grammar_error::error_code_severity(code) {
+        GrammarErrorSeverity::Error => "warning",
+        GrammarErrorSeverity::Warning => "note",
+    };
+    json!({
+        "id": rule_id(code),
+        "shortDescription": { "text": grammar_error::error_code_description_en(code) },
+        "defaultConfiguration": { "level": level },
+        "properties": {
+            "category": format!("{:?}", grammar_error::error_code_category(code)),
+        },
+    })
+}
+
+/// SARIF region of `len` characters from `start` in a paragraph. The end
+/// column is exclusive.
+fn sarif_region(paragraph: &Paragraph, start: usize, len: usize) -> Value {
+    let (start_line, start_column) = paragraph.position(start, false);
+    let (end_line, end_column) = if len == 0 {
+        (start_line, start_column)
+    } else {
+        paragraph.position(start + len, true)
+    };
+    json!({
+        "startLine": start_line,
+        "startColumn": start_column,
+        "endLine": end_line,
+        "endColumn": end_column,
+    })
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (json, args) = voikko_cli::parse_json_flag(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-gc-pretty: Pretty-print grammar check results.");
         println!();
-        println!("Usage: voikko-gc-pretty [-d DICT_PATH] [OPTIONS]");
+        println!("Usage: voikko-gc-pretty [-d DICT_PATH] [OPTIONS] [FILE...]");
         println!();
-        println!("Checks grammar of text read from stdin and prints errors.");
+        println!("Checks grammar of text read from the files, or from stdin if");
+        println!("none are given, and prints errors.");
         println!("Normally paragraphs are separated by line feeds. Use option");
         println!("--empty-line if paragraphs are separated by empty lines.");
         println!();
         println!("Options:");
         println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
         println!("  --empty-line            Paragraphs separated by empty lines");
+        println!("  --format FORMAT         text (default), json, or sarif: one SARIF 2.1.0");
+        println!("                          log of grammar errors and misspelled words");
+        println!("  --json, --json-lines    Same as --format json: one JSON object per paragraph");
+        println!("  --rule-stats            Print per-rule timing to stderr at the end");
         println!("  -h, --help              Print this help");
         return;
     }
 
-    let empty_line_separates = args.iter().any(|a| a == "--empty-line");
-
-    let handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
-
-    let stdin = io::stdin();
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
-
-    if !empty_line_separates {
-        // Each line is a paragraph
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("error reading stdin: {e}");
-                    break;
-                }
-            };
-            let paragraph = line.trim();
-            if paragraph.is_empty() {
-                continue;
+    let mut empty_line_separates = false;
+    let mut rule_stats = false;
+    let mut format = None;
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--empty-line" {
+            empty_line_separates = true;
+        } else if arg == "--rule-stats" {
+            rule_stats = true;
+        } else if arg == "--format" {
+            match iter.next() {
+                Some(value) => format = Some(parse_format(value)),
+                None => voikko_cli::fatal("--format requires a value"),
             }
-            handle_paragraph(paragraph, &handle, &mut out);
+        } else if let Some(v) = arg.strip_prefix("--format=") {
+            format = Some(parse_format(v));
+        } else if arg.starts_with('-') && arg != "-" {
+            voikko_cli::fatal(&format!("unknown argument: {arg}"));
+        } else {
+            files.push(arg.clone());
         }
+    }
+    let format = match (format, json) {
+        (Some(format), true) if format != Format::Json => {
+            voikko_cli::fatal("--json conflicts with --format")
+        }
+        (Some(format), _) => format,
+        (None, true) => Format::Json,
+        (None, false) => Format::Text,
+    };
+
+    let mut handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
+    handle.set_grammar_rule_timing(rule_stats);
+
+    let mode = if format == Format::Json {
+        OutputMode::JsonLines
     } else {
-        // Paragraphs separated by empty lines
-        let mut paragraph = String::new();
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("error reading stdin: {e}");
-                    break;
-                }
-            };
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                if !paragraph.is_empty() {
-                    handle_paragraph(&paragraph, &handle, &mut out);
-                    paragraph.clear();
-                }
-                continue;
-            }
-            if !paragraph.is_empty() {
-                paragraph.push(' ');
+        OutputMode::Text
+    };
+    let mut out = RecordWriter::stdout(mode);
+    let mut sarif = SarifLog::default();
+
+    let mut check = |reader: &mut dyn BufRead, uri: &str| {
+        read_paragraphs(reader, uri, empty_line_separates, |paragraph| {
+            if format == Format::Sarif {
+                sarif.add_paragraph(paragraph, uri, &handle);
+            } else {
+                handle_paragraph(&paragraph.text, &handle, &mut out);
             }
-            paragraph.push_str(trimmed);
+        });
+    };
+    if files.is_empty() {
+        check(&mut io::stdin().lock(), "stdin");
+    }
+    for file in &files {
+        if file == "-" {
+            check(&mut io::stdin().lock(), "stdin");
+            continue;
         }
-        // Handle trailing paragraph
-        if !paragraph.is_empty() {
-            handle_paragraph(&paragraph, &handle, &mut out);
+        match File::open(file) {
+            Ok(f) => check(&mut BufReader::new(f), file),
+            Err(e) => eprintln!("error: failed to open {file}: {e}"),
         }
     }
+
+    if format == Format::Sarif {
+        let mut out = io::stdout().lock();
+        let _ = serde_json::to_writer_pretty(&mut out, &sarif.into_value());
+        let _ = writeln!(out);
+    }
+
+    if rule_stats {
+        drop(out);
+        print_rule_stats(&handle);
+    }
+}
+
+/// Print the per-rule timing statistics, slowest first.
+fn print_rule_stats(handle: &VoikkoHandle) {
+    eprintln!(
+        "{:<26} {:>8} {:>12} {:>10} {:>10}",
+        "rule", "runs", "total ms", "mean us", "max us"
+    );
+    for stats in handle.grammar_rule_stats() {
+        eprintln!(
+            "{:<26} {:>8} {:>12.3} {:>10.1} {:>10.1}",
+            stats.rule,
+            stats.runs,
+            stats.total.as_secs_f64() * 1e3,
+            stats.mean().as_secs_f64() * 1e6,
+            stats.max.as_secs_f64() * 1e6
+        );
+    }
 }
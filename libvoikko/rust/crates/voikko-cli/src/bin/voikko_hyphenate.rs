@@ -10,15 +10,19 @@
 //   -d, --dict-path PATH   Dictionary directory containing mor.vfst
 //   --separator SEP         Hyphen separator character (default: -)
 //   --pattern               Show raw hyphenation pattern instead of inserting hyphens
+//   --explain               Explain which rule allowed or suppressed each break
 //   --no-ugly               Suppress ugly hyphenation points
 //   --min-length N          Minimum word length for hyphenation (default: 2)
+//   -0, --print0            NUL-delimited input and output records
+//   --json, --json-lines    One JSON object per word
 //   -h, --help              Print help
 
-use std::io::{self, BufRead, Write};
+use voikko_cli::RecordWriter;
 
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (mode, args) = voikko_cli::parse_output_mode(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-hyphenate: Hyphenate Finnish words.");
@@ -32,14 +36,18 @@ fn main() {
         println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
         println!("  --separator SEP         Hyphen separator character (default: -)");
         println!("  --pattern               Show raw pattern instead of inserting hyphens");
+        println!("  --explain               Explain which rule allowed or suppressed each break");
         println!("  --no-ugly               Suppress ugly hyphenation points");
         println!("  --min-length N          Minimum word length for hyphenation (default: 2)");
+        println!("  -0, --print0            Read and write NUL-delimited records");
+        println!("  --json, --json-lines    Print one JSON object per word");
         println!("  -h, --help              Print this help");
         return;
     }
 
     let mut separator = "-".to_string();
     let mut show_pattern = false;
+    let mut explain = false;
     let mut no_ugly = false;
     let mut min_length: usize = 2;
     let mut words: Vec<String> = Vec::new();
@@ -51,69 +59,96 @@ fn main() {
             continue;
         }
         match arg.as_str() {
-            "--separator" => {
-                if i + 1 < args.len() {
-                    separator = args[i + 1].clone();
-                    skip_next = true;
-                }
+            "--separator" if i + 1 < args.len() => {
+                separator = args[i + 1].clone();
+                skip_next = true;
             }
             "--pattern" => show_pattern = true,
+            "--explain" => explain = true,
             "--no-ugly" => no_ugly = true,
-            "--min-length" => {
-                if i + 1 < args.len() {
-                    min_length = args[i + 1]
-                        .parse()
-                        .unwrap_or_else(|_| voikko_cli::fatal("invalid number for --min-length"));
-                    skip_next = true;
-                }
+            "--min-length" if i + 1 < args.len() => {
+                min_length = args[i + 1]
+                    .parse()
+                    .unwrap_or_else(|_| voikko_cli::fatal("invalid number for --min-length"));
+                skip_next = true;
             }
             s if !s.starts_with('-') => words.push(arg.clone()),
             _ => {}
         }
     }
 
-    let mut handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let mut handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
     if no_ugly {
         handle.set_no_ugly_hyphenation(true);
     }
     handle.set_min_hyphenated_word_length(min_length);
 
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
+    let mut out = RecordWriter::stdout(mode);
 
-    let hyphenate_word = |word: &str,
-                          handle: &voikko_fi::handle::VoikkoHandle,
-                          out: &mut io::BufWriter<io::StdoutLock<'_>>| {
-        if show_pattern {
+    let mut hyphenate_word = |word: &str| {
+        if explain {
+            let explanation = handle.explain_hyphenation(word);
+            let chars: Vec<char> = word.chars().collect();
+            let points: Vec<char> = explanation.pattern.chars().collect();
+            let mut lines = Vec::new();
+            let mut breaks = Vec::new();
+            for (i, reason) in explanation.reasons.iter().enumerate().skip(1) {
+                let is_break = points.get(i).is_some_and(|&p| p != ' ');
+                if !is_break && *reason == voikko_fi::hyphenator::HyphenReason::NoRule {
+                    continue;
+                }
+                if out.is_json() {
+                    breaks.push(serde_json::json!({
+                        "position": i,
+                        "break": is_break,
+                        "reason": reason.description(),
+                    }));
+                } else {
+                    let before: String = chars[..i].iter().collect();
+                    let after: String = chars[i..].iter().collect();
+                    let verdict = if is_break { "break" } else { "no break" };
+                    lines.push(format!(
+                        "  {before}|{after}: {verdict} ({})",
+                        reason.description()
+                    ));
+                }
+            }
+            if out.is_json() {
+                out.json(&serde_json::json!({
+                    "word": word,
+                    "pattern": explanation.pattern,
+                    "points": breaks,
+                }));
+            } else {
+                out.line(&format!("{word} {}", explanation.pattern));
+                for line in &lines {
+                    out.line(line);
+                }
+            }
+        } else if show_pattern {
             let pattern = handle.hyphenate(word);
-            let _ = writeln!(out, "{word} {pattern}");
+            if out.is_json() {
+                out.json(&serde_json::json!({ "word": word, "pattern": pattern }));
+            } else {
+                out.line(&format!("{word} {pattern}"));
+            }
         } else {
             let result = handle.insert_hyphens(word, &separator, true);
-            let _ = writeln!(out, "{result}");
+            if out.is_json() {
+                out.json(&serde_json::json!({ "word": word, "hyphenated": result }));
+            } else {
+                out.line(&result);
+            }
         }
     };
 
     if words.is_empty() {
-        let stdin = io::stdin();
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("error reading stdin: {e}");
-                    break;
-                }
-            };
-            let word = line.trim();
-            if word.is_empty() {
-                continue;
-            }
-            hyphenate_word(word, &handle, &mut out);
-        }
+        voikko_cli::for_each_stdin_word(mode, hyphenate_word);
     } else {
         for word in &words {
-            hyphenate_word(word, &handle, &mut out);
+            hyphenate_word(word);
         }
     }
 }
@@ -0,0 +1,415 @@
+// voikko-lsp: Language Server Protocol server for spelling and grammar.
+//
+// Speaks LSP (JSON-RPC with Content-Length framing) over stdin/stdout.
+// Open documents are checked line by line, each line being one paragraph
+// as in voikko-gc-pretty: words found by the tokenizer are spell checked
+// and the line is grammar checked. Problems are published as diagnostics,
+// and code actions offer replacements from suggest() and the grammar
+// checker. Documents use incremental sync; check results are cached by
+// line text, so an edit only re-checks the lines it changed.
+//
+// Usage:
+//   voikko-lsp [-d DICT_PATH] [--stdio]
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   --stdio                Accepted for editor compatibility (stdio is the only transport)
+//   -h, --help             Print help
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use serde_json::{Value, json};
+use voikko_core::grammar_error::GrammarErrorSeverity;
+use voikko_fi::handle::VoikkoHandle;
+
+/// LSP `DiagnosticSeverity.Warning`, used for misspellings and grammar errors.
+const SEVERITY_WARNING: u8 = 2;
+
+/// LSP `DiagnosticSeverity.Information`, used for stylistic remarks.
+const SEVERITY_INFORMATION: u8 = 3;
+
+/// LSP `TextDocumentSyncKind.Incremental`.
+const SYNC_INCREMENTAL: u8 = 2;
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Diagnostic source name, also used to pick our diagnostics in code actions.
+const SOURCE: &str = "voikko";
+
+/// A problem found on one line, with UTF-16 column offsets.
+struct LineIssue {
+    start: usize,
+    end: usize,
+    severity: u8,
+    code: Value,
+    message: String,
+    /// Replacements known up front (grammar errors). Misspellings carry the
+    /// word instead and are looked up with suggest() only on request.
+    data: Value,
+}
+
+struct Document {
+    text: String,
+    /// Issues of each distinct line text at the last check.
+    cache: HashMap<String, Vec<LineIssue>>,
+}
+
+struct Server {
+    handle: VoikkoHandle,
+    documents: HashMap<String, Document>,
+    shutdown_requested: bool,
+}
+
+impl Server {
+    /// Handle one incoming message. Returns the process exit code once an
+    /// `exit` notification arrives.
+    fn handle_message(&mut self, message: &Value, out: &mut impl Write) -> Option<i32> {
+        let Some(method) = message["method"].as_str() else {
+            // A response to a request we never send
+            return None;
+        };
+        let params = &message["params"];
+
+        if let Some(id) = message.get("id") {
+            let response = match self.request(method, params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, error)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": code, "message": error },
+                }),
+            };
+            send(out, &response);
+            return None;
+        }
+
+        match method {
+            "exit" => return Some(if self.shutdown_requested { 0 } else { 1 }),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let uri = document["uri"].as_str()?;
+                self.documents.insert(
+                    uri.to_string(),
+                    Document {
+                        text: document["text"].as_str().unwrap_or_default().to_string(),
+                        cache: HashMap::new(),
+                    },
+                );
+                self.publish(uri, out);
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str()?;
+                let document = self.documents.get_mut(uri)?;
+                for change in params["contentChanges"].as_array().into_iter().flatten() {
+                    apply_change(&mut document.text, change);
+                }
+                self.publish(uri, out);
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str()?;
+                self.documents.remove(uri);
+                send(out, &publish_notification(uri, Vec::new()));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        if self.shutdown_requested {
+            return Err((INVALID_REQUEST, "server is shutting down".to_string()));
+        }
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": SYNC_INCREMENTAL },
+                    "codeActionProvider": { "codeActionKinds": ["quickfix"] },
+                },
+                "serverInfo": { "name": "voikko-lsp", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/codeAction" => Ok(self.code_actions(params)),
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported method: {method}"))),
+        }
+    }
+
+    /// Re-check a document and publish its diagnostics.
+    fn publish(&mut self, uri: &str, out: &mut impl Write) {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return;
+        };
+        let mut cache: HashMap<String, Vec<LineIssue>> = HashMap::new();
+        let mut diagnostics = Vec::new();
+
+        for (line_number, line) in document.text.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if !cache.contains_key(line) {
+                let issues = document
+                    .cache
+                    .remove(line)
+                    .unwrap_or_else(|| check_line(&self.handle, line));
+                cache.insert(line.to_string(), issues);
+            }
+            diagnostics.extend(cache[line].iter().map(|issue| {
+                json!({
+                    "range": {
+                        "start": { "line": line_number, "character": issue.start },
+                        "end": { "line": line_number, "character": issue.end },
+                    },
+                    "severity": issue.severity,
+                    "code": issue.code,
+                    "source": SOURCE,
+                    "message": issue.message,
+                    "data": issue.data,
+                })
+            }));
+        }
+
+        document.cache = cache;
+        send(out, &publish_notification(uri, diagnostics));
+    }
+
+    /// Offer one quick fix per suggestion for each of our diagnostics in
+    /// the request context.
+    fn code_actions(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let mut actions = Vec::new();
+
+        for diagnostic in params["context"]["diagnostics"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            if diagnostic["source"] != SOURCE {
+                continue;
+            }
+            let data = &diagnostic["data"];
+            let suggestions: Vec<String> = if let Some(list) = data["suggestions"].as_array() {
+                list.iter()
+                    .filter_map(|s| s.as_str().map(str::to_string))
+                    .collect()
+            } else if let Some(word) = data["word"].as_str() {
+                self.handle.suggest(word)
+            } else if let Some(document) = self.documents.get(uri) {
+                // Clients that do not echo `data` back: use the range text
+                let start = position_to_offset(&document.text, &diagnostic["range"]["start"]);
+                let end = position_to_offset(&document.text, &diagnostic["range"]["end"]);
+                self.handle.suggest(&document.text[start..end.max(start)])
+            } else {
+                Vec::new()
+            };
+
+            for (i, suggestion) in suggestions.iter().enumerate() {
+                actions.push(json!({
+                    "title": format!("Replace with \"{suggestion}\""),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic],
+                    "isPreferred": i == 0,
+                    "edit": {
+                        "changes": {
+                            uri: [{ "range": diagnostic["range"], "newText": suggestion }],
+                        },
+                    },
+                }));
+            }
+        }
+
+        Value::Array(actions)
+    }
+}
+
+/// Spell check the words of one line and grammar check the line.
+fn check_line(handle: &VoikkoHandle, line: &str) -> Vec<LineIssue> {
+    let mut issues = Vec::new();
+
+    for token in handle.misspelled_tokens(line) {
+        issues.push(LineIssue {
+            start: token.start_utf16,
+            end: token.end_utf16,
+            severity: SEVERITY_WARNING,
+            code: json!("spelling"),
+            message: format!("Unknown word: {}", token.text),
+            data: json!({ "word": token.text }),
+        });
+    }
+
+    let errors = handle.grammar_errors(line);
+    if !errors.is_empty() {
+        // Grammar error positions are character offsets
+        let utf16_offsets: Vec<usize> = std::iter::once(0)
+            .chain(line.chars().scan(0, |units, ch| {
+                *units += ch.len_utf16();
+                Some(*units)
+            }))
+            .collect();
+        let column = |chars: usize| utf16_offsets[chars.min(utf16_offsets.len() - 1)];
+
+        for error in errors {
+            issues.push(LineIssue {
+                start: column(error.start_pos),
+                end: column(error.start_pos + error.error_len),
+                severity: match error.severity {
+                    GrammarErrorSeverity::Error => SEVERITY_WARNING,
+                    GrammarErrorSeverity::Warning => SEVERITY_INFORMATION,
+                },
+                code: json!(error.error_code),
+                message: error.short_description,
+                data: json!({ "suggestions": error.suggestions }),
+            });
+        }
+    }
+
+    issues
+}
+
+/// Apply one `TextDocumentContentChangeEvent`: a range replacement, or the
+/// full text when no range is given.
+fn apply_change(text: &mut String, change: &Value) {
+    let new_text = change["text"].as_str().unwrap_or_default();
+    match change.get("range") {
+        Some(range) => {
+            let start = position_to_offset(text, &range["start"]);
+            let end = position_to_offset(text, &range["end"]).max(start);
+            text.replace_range(start..end, new_text);
+        }
+        None => *text = new_text.to_string(),
+    }
+}
+
+/// Convert an LSP position (line, UTF-16 column) to a byte offset in
+/// `text`, clamping past-the-end lines and columns.
+fn position_to_offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+
+    let mut line_start = 0;
+    for _ in 0..line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+    for (i, ch) in text[line_start..].char_indices() {
+        if ch == '\n' || units >= character {
+            return line_start + i;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+fn publish_notification(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Read the body of one message framed by a `Content-Length` header, or
+/// `None` at end of input.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Write one message with its `Content-Length` header.
+fn send(out: &mut impl Write, message: &Value) {
+    let body = message.to_string();
+    let result =
+        write!(out, "Content-Length: {}\r\n\r\n{body}", body.len()).and_then(|_| out.flush());
+    if let Err(e) = result {
+        voikko_cli::fatal(&format!("failed to write message: {e}"));
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-lsp: Language Server Protocol server for Finnish spelling and grammar.");
+        println!();
+        println!("Usage: voikko-lsp [-d DICT_PATH] [--stdio]");
+        println!();
+        println!("Speaks LSP over stdin/stdout. Each line of a document is checked");
+        println!("as a paragraph; misspellings and grammar errors are published as");
+        println!("diagnostics, with quick fixes for the suggested replacements.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  --stdio                Accepted for editor compatibility");
+        println!("  -h, --help             Print this help");
+        return;
+    }
+
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
+    let mut server = Server {
+        handle,
+        documents: HashMap::new(),
+        shutdown_requested: false,
+    };
+
+    let mut input = io::stdin().lock();
+    let mut out = io::stdout().lock();
+
+    loop {
+        let body = match read_message(&mut input) {
+            Ok(Some(body)) => body,
+            Ok(None) => break,
+            Err(e) => voikko_cli::fatal(&format!("failed to read message: {e}")),
+        };
+        let message: Value = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                send(
+                    &mut out,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": null,
+                        "error": { "code": PARSE_ERROR, "message": e.to_string() },
+                    }),
+                );
+                continue;
+            }
+        };
+        if let Some(code) = server.handle_message(&message, &mut out) {
+            process::exit(code);
+        }
+    }
+
+    // Input closed without an exit notification
+    process::exit(if server.shutdown_requested { 0 } else { 1 });
+}
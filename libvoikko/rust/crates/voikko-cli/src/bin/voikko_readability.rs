@@ -64,8 +64,8 @@ fn main() {
         return;
     }
 
-    let mut handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let mut handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
     // Match the Python tool: no_ugly=false, hyphenate_unknown=true
     handle.set_no_ugly_hyphenation(false);
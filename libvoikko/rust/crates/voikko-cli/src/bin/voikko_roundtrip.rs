@@ -0,0 +1,172 @@
+// voikko-roundtrip: Verify analysis against generation over a corpus.
+//
+// Reads running text from stdin (or words from the command line), analyzes
+// each distinct word and generates the surface form back from the base
+// form and attributes of every analysis. Analyses that do not give the
+// word back are printed; a summary goes to stderr. Compounds, derived
+// words and forms with an unmarked clitic are counted as unsupported,
+// since the generator does not produce them.
+//
+// Usage:
+//   voikko-roundtrip [-d DICT_PATH] [--json] [WORD...]
+//
+// Options:
+//   -d, --dict-path PATH   Dictionary directory containing mor.vfst
+//   --json, --json-lines    One JSON object per mismatch
+//   -h, --help              Print help
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use voikko_cli::RecordWriter;
+use voikko_core::analysis::{ATTR_BASEFORM, Analysis};
+use voikko_core::enums::TokenType;
+use voikko_fi::handle::VoikkoHandle;
+use voikko_fi::morphology::{RoundTrip, RoundTripStatus};
+
+#[derive(Default)]
+struct Summary {
+    words: usize,
+    unknown: usize,
+    matches: usize,
+    mismatches: usize,
+    unsupported: usize,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (json, args) = voikko_cli::parse_json_flag(&args);
+
+    if voikko_cli::wants_help(&args) {
+        println!("voikko-roundtrip: Verify analysis against generation.");
+        println!();
+        println!("Usage: voikko-roundtrip [-d DICT_PATH] [--json] [WORD...]");
+        println!();
+        println!("Analyzes each distinct word of the text on stdin (or each");
+        println!("WORD argument), generates the word back from every analysis");
+        println!("and prints the analyses that do not give the word back:");
+        println!("  word<TAB>baseform<TAB>attributes<TAB>generated forms");
+        println!("A summary is printed to stderr.");
+        println!();
+        println!("Options:");
+        println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
+        println!("  --json, --json-lines    Print one JSON object per mismatch");
+        println!("  -h, --help              Print this help");
+        return;
+    }
+
+    let words: Vec<String> = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .cloned()
+        .collect();
+
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
+
+    let mode = if json {
+        voikko_cli::OutputMode::JsonLines
+    } else {
+        voikko_cli::OutputMode::Text
+    };
+    let mut out = RecordWriter::stdout(mode);
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut summary = Summary::default();
+
+    let mut check = |word: &str| {
+        if seen.insert(word.to_string()) {
+            check_word(&handle, word, &mut out, &mut summary);
+        }
+    };
+
+    if words.is_empty() {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("error reading stdin: {e}");
+                    break;
+                }
+            };
+            for token in handle.tokens(&line) {
+                if token.token_type == TokenType::Word {
+                    check(&token.text);
+                }
+            }
+        }
+    } else {
+        for word in &words {
+            check(word);
+        }
+    }
+
+    drop(out);
+    eprintln!(
+        "{} words ({} unknown): {} analyses match, {} mismatch, {} unsupported",
+        summary.words, summary.unknown, summary.matches, summary.mismatches, summary.unsupported
+    );
+    if summary.mismatches > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Round-trip `word` and print its mismatching analyses.
+fn check_word<W: Write>(
+    handle: &VoikkoHandle,
+    word: &str,
+    out: &mut RecordWriter<W>,
+    summary: &mut Summary,
+) {
+    let results = handle.round_trip(word);
+    summary.words += 1;
+    if results.is_empty() {
+        summary.unknown += 1;
+    }
+    for result in &results {
+        match result.status {
+            RoundTripStatus::Match => summary.matches += 1,
+            RoundTripStatus::Unsupported => summary.unsupported += 1,
+            RoundTripStatus::Mismatch => {
+                summary.mismatches += 1;
+                print_mismatch(word, result, out);
+            }
+        }
+    }
+}
+
+fn print_mismatch<W: Write>(word: &str, result: &RoundTrip, out: &mut RecordWriter<W>) {
+    let baseform = result.analysis.get(ATTR_BASEFORM).unwrap_or("");
+    if out.is_json() {
+        let target: serde_json::Map<String, serde_json::Value> = sorted_attributes(&result.target)
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.into()))
+            .collect();
+        out.json(&serde_json::json!({
+            "word": word,
+            "baseform": baseform,
+            "attributes": target,
+            "generated": result.generated,
+        }));
+    } else {
+        let attributes: Vec<String> = sorted_attributes(&result.target)
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        out.line(&format!(
+            "{word}\t{baseform}\t{}\t{}",
+            attributes.join(","),
+            result.generated.join(",")
+        ));
+    }
+}
+
+fn sorted_attributes(analysis: &Analysis) -> Vec<(&str, &str)> {
+    let mut attributes: Vec<(&str, &str)> = analysis
+        .attributes()
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    attributes.sort();
+    attributes
+}
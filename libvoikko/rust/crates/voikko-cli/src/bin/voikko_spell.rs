@@ -13,13 +13,39 @@
 //   -s, --suggest           Also print suggestions for misspelled words
 //   --ignore-dot            Ignore trailing dot
 //   --ignore-numbers        Ignore words containing numbers
+//   -0, --print0            NUL-delimited input and output records
+//   --json, --json-lines    One JSON object per word
+//   --stream                Check in batches and answer as soon as possible
+//   -j, --threads N         Threads checking a batch in --stream mode
+//   --batch-size N          Maximum words per batch in --stream mode
 //   -h, --help              Print help
+//
+// Without --stream, results are written when the output buffer fills up or
+// the input ends. With --stream, each batch of buffered input is answered
+// and flushed before more is read, which suits long-running pipelines; see
+// `VoikkoHandle::spell_stream`.
+
+use std::io::{self, BufReader, BufWriter};
+
+use voikko_cli::{OutputMode, RecordWriter};
+use voikko_fi::stream::{SpellStreamFormat, SpellStreamOptions};
+
+/// Input buffer size in --stream mode, large enough for full batches.
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
 
-use std::io::{self, BufRead, Write};
+fn parse_count(args: &[String], i: usize, name: &str) -> usize {
+    let value = args
+        .get(i + 1)
+        .unwrap_or_else(|| voikko_cli::fatal(&format!("{name} requires a value")));
+    value
+        .parse()
+        .unwrap_or_else(|_| voikko_cli::fatal(&format!("invalid number for {name}: {value}")))
+}
 
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (mode, args) = voikko_cli::parse_output_mode(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-spell: Check spelling of words from stdin.");
@@ -35,16 +61,43 @@ fn main() {
         println!("  -s, --suggest           Also print suggestions for misspelled words");
         println!("  --ignore-dot            Ignore trailing dot in words");
         println!("  --ignore-numbers        Ignore words containing numbers");
+        println!("  -0, --print0            Read and write NUL-delimited records");
+        println!("  --json, --json-lines    Print one JSON object per word");
+        println!("  --stream                Check in batches, flushing results before");
+        println!("                          reading more input (for pipelines)");
+        println!("  -j, --threads N         Threads per batch with --stream (0: all cores)");
+        println!("  --batch-size N          Maximum words per batch with --stream (default: 1024)");
         println!("  -h, --help              Print this help");
         return;
     }
 
-    let show_suggestions = args.iter().any(|a| a == "-s" || a == "--suggest");
-    let ignore_dot = args.iter().any(|a| a == "--ignore-dot");
-    let ignore_numbers = args.iter().any(|a| a == "--ignore-numbers");
+    let mut show_suggestions = false;
+    let mut ignore_dot = false;
+    let mut ignore_numbers = false;
+    let mut stream = false;
+    let mut stream_options = SpellStreamOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-s" | "--suggest" => show_suggestions = true,
+            "--ignore-dot" => ignore_dot = true,
+            "--ignore-numbers" => ignore_numbers = true,
+            "--stream" => stream = true,
+            "-j" | "--threads" => {
+                stream_options.threads = parse_count(&args, i, "--threads");
+                i += 1;
+            }
+            "--batch-size" => {
+                stream_options.batch_size = parse_count(&args, i, "--batch-size");
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
 
-    let mut handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let mut handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
     if ignore_dot {
         handle.set_ignore_dot(true);
@@ -53,32 +106,51 @@ fn main() {
         handle.set_ignore_numbers(true);
     }
 
-    let stdin = io::stdin();
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
-
-    for line in stdin.lock().lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(e) => {
-                eprintln!("error reading stdin: {e}");
-                break;
-            }
+    if stream {
+        if stream_options.threads == 0 {
+            stream_options.threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        }
+        stream_options.suggest = show_suggestions;
+        stream_options.format = match mode {
+            OutputMode::Text => SpellStreamFormat::Text,
+            OutputMode::Print0 => SpellStreamFormat::Print0,
+            OutputMode::JsonLines => SpellStreamFormat::JsonLines,
         };
-        let word = line.trim();
-        if word.is_empty() {
-            continue;
+        let input = BufReader::with_capacity(STREAM_BUFFER_SIZE, io::stdin().lock());
+        let output = BufWriter::new(io::stdout().lock());
+        match handle.spell_stream(input, output, &stream_options) {
+            Ok(stats) if stats.invalid_records > 0 => eprintln!(
+                "warning: skipped {} input records that are not valid UTF-8",
+                stats.invalid_records
+            ),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+            Err(e) => voikko_cli::fatal(&format!("spell stream failed: {e}")),
         }
+        return;
+    }
 
-        if handle.spell(word) {
-            let _ = writeln!(out, "C: {word}");
+    let mut out = RecordWriter::stdout(mode);
+
+    voikko_cli::for_each_stdin_word(mode, |word| {
+        let correct = handle.spell(word);
+        if out.is_json() {
+            // Same fields as check_word: suggestions is null unless requested
+            let suggestions = (show_suggestions && !correct).then(|| handle.suggest(word));
+            out.json(&serde_json::json!({
+                "word": word,
+                "correct": correct,
+                "suggestions": suggestions,
+            }));
+        } else if correct {
+            out.line(&format!("C: {word}"));
         } else {
-            let _ = writeln!(out, "W: {word}");
+            out.line(&format!("W: {word}"));
             if show_suggestions {
                 for suggestion in handle.suggest(word) {
-                    let _ = writeln!(out, "S: {suggestion}");
+                    out.line(&format!("S: {suggestion}"));
                 }
             }
         }
-    }
+    });
 }
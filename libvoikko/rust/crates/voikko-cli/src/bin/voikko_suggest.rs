@@ -9,13 +9,16 @@
 // Options:
 //   -d, --dict-path PATH   Dictionary directory containing mor.vfst
 //   -n, --max-suggestions N Maximum number of suggestions (default: 5)
+//   -0, --print0            NUL-delimited input and output records
+//   --json, --json-lines    One JSON object per word
 //   -h, --help              Print help
 
-use std::io::{self, BufRead, Write};
+use voikko_cli::RecordWriter;
 
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (mode, args) = voikko_cli::parse_output_mode(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-suggest: Generate spelling suggestions.");
@@ -28,6 +31,8 @@ fn main() {
         println!("Options:");
         println!("  -d, --dict-path PATH     Dictionary directory containing mor.vfst");
         println!("  -n, --max-suggestions N  Maximum number of suggestions (default: 5)");
+        println!("  -0, --print0             Read and write NUL-delimited records");
+        println!("  --json, --json-lines     Print one JSON object per word");
         println!("  -h, --help               Print this help");
         return;
     }
@@ -55,51 +60,42 @@ fn main() {
         }
     }
 
-    let mut handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let mut handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
     handle.set_max_suggestions(max_suggestions);
 
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
+    let mut out = RecordWriter::stdout(mode);
 
-    let suggest_word = |word: &str,
-                        handle: &voikko_fi::handle::VoikkoHandle,
-                        out: &mut io::BufWriter<io::StdoutLock<'_>>| {
-        if handle.spell(word) {
-            let _ = writeln!(out, "{word} (correct)");
+    let mut suggest_word = |word: &str| {
+        let correct = handle.spell(word);
+        let suggestions = if correct {
+            Vec::new()
         } else {
-            let suggestions = handle.suggest(word);
-            if suggestions.is_empty() {
-                let _ = writeln!(out, "{word}: (no suggestions)");
-            } else {
-                let _ = writeln!(out, "{word}:");
-                for s in &suggestions {
-                    let _ = writeln!(out, "  {s}");
-                }
+            handle.suggest(word)
+        };
+        if out.is_json() {
+            out.json(&serde_json::json!({
+                "word": word,
+                "correct": correct,
+                "suggestions": suggestions,
+            }));
+        } else if correct {
+            out.line(&format!("{word} (correct)"));
+        } else if suggestions.is_empty() {
+            out.line(&format!("{word}: (no suggestions)"));
+        } else {
+            out.line(&format!("{word}:"));
+            for s in &suggestions {
+                out.line(&format!("  {s}"));
             }
         }
     };
 
     if words.is_empty() {
-        // Read from stdin
-        let stdin = io::stdin();
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("error reading stdin: {e}");
-                    break;
-                }
-            };
-            let word = line.trim();
-            if word.is_empty() {
-                continue;
-            }
-            suggest_word(word, &handle, &mut out);
-        }
+        voikko_cli::for_each_stdin_word(mode, suggest_word);
     } else {
         for word in &words {
-            suggest_word(word, &handle, &mut out);
+            suggest_word(word);
         }
     }
 }
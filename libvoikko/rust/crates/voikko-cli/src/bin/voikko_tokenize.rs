@@ -9,14 +9,17 @@
 // Options:
 //   -d, --dict-path PATH   Dictionary directory containing mor.vfst
 //   --sentences             Also show sentence boundaries
+//   --json, --json-lines    One JSON object per token and sentence
 //   -h, --help              Print help
 
-use std::io::{self, Read, Write};
-use voikko_core::enums::TokenType;
+use std::io::{self, Read};
+
+use voikko_cli::{OutputMode, RecordWriter};
 
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
     let (dict_path, args) = voikko_cli::parse_dict_path(&args);
+    let (json, args) = voikko_cli::parse_json_flag(&args);
 
     if voikko_cli::wants_help(&args) {
         println!("voikko-tokenize: Tokenize Finnish text.");
@@ -32,62 +35,67 @@ fn main() {
         println!("Options:");
         println!("  -d, --dict-path PATH   Dictionary directory containing mor.vfst");
         println!("  --sentences             Also show sentence boundaries");
+        println!("  --json, --json-lines    Print one JSON object per token and sentence");
         println!("  -h, --help              Print this help");
         return;
     }
 
     let show_sentences = args.iter().any(|a| a == "--sentences");
 
-    let handle =
-        voikko_cli::load_handle(dict_path.as_deref()).unwrap_or_else(|e| voikko_cli::fatal(&e));
+    let handle = voikko_fi::dict_path::load_handle(dict_path.as_deref())
+        .unwrap_or_else(|e| voikko_cli::fatal(&e.to_string()));
 
     let mut input = String::new();
     io::stdin()
         .read_to_string(&mut input)
         .unwrap_or_else(|e| voikko_cli::fatal(&format!("failed to read stdin: {e}")));
 
-    let stdout = io::stdout();
-    let mut out = io::BufWriter::new(stdout.lock());
+    let mode = if json {
+        OutputMode::JsonLines
+    } else {
+        OutputMode::Text
+    };
+    let mut out = RecordWriter::stdout(mode);
 
     // Print tokens
-    let _ = writeln!(out, "=== Tokens ===");
+    if !json {
+        out.line("=== Tokens ===");
+    }
     for token in handle.tokens(&input) {
-        let type_str = match token.token_type {
-            TokenType::Word => "WORD",
-            TokenType::Punctuation => "PUNCTUATION",
-            TokenType::Whitespace => "WHITESPACE",
-            TokenType::Unknown => "UNKNOWN",
-            TokenType::None => "NONE",
-        };
+        if json {
+            out.json(&voikko_cli::token_record(&token));
+            continue;
+        }
+        let type_str = token.token_type.name().to_uppercase();
         let display_text = token
             .text
             .replace('\n', "\\n")
             .replace('\r', "\\r")
             .replace('\t', "\\t");
-        let _ = writeln!(
-            out,
+        out.line(&format!(
             "{type_str:13} [{:>4}..{:>4}]: {display_text}",
             token.pos,
             token.pos + token.token_len
-        );
+        ));
     }
 
     // Print sentences if requested
     if show_sentences {
-        let _ = writeln!(out);
-        let _ = writeln!(out, "=== Sentences ===");
-        let mut offset = 0;
-        for sentence in handle.sentences(&input) {
-            let end = offset + sentence.sentence_len;
-            let snippet: String = input
-                .chars()
-                .skip(offset)
-                .take(sentence.sentence_len)
-                .collect();
-            let snippet = snippet.replace('\n', "\\n");
-            let type_str = format!("{:?}", sentence.sentence_type);
-            let _ = writeln!(out, "{type_str:8} [{offset:>4}..{end:>4}]: {snippet}");
-            offset = end;
+        if !json {
+            out.line("");
+            out.line("=== Sentences ===");
+        }
+        for span in handle.sentence_spans(&input) {
+            if json {
+                out.json(&voikko_cli::sentence_span_record(&span));
+                continue;
+            }
+            let snippet = span.text.replace('\n', "\\n");
+            let type_str = format!("{:?}", span.sentence_type);
+            out.line(&format!(
+                "{type_str:8} [{:>4}..{:>4}]: {snippet}",
+                span.start, span.end
+            ));
         }
     }
 }
@@ -0,0 +1,348 @@
+// voikkospell: Drop-in replacement for the classic libvoikko voikkospell.
+//
+// Takes the options of the C++ test program (voikkospell.1) and prints the
+// same output, so that shell scripts written against it keep working:
+//   C: word / W: word         spelling result
+//   S: suggestion             with -s, for misspelled words
+//   A(word):N:KEY=value       with -m or -M, for recognized words
+// Unlike the other tools, the dictionary directory is given with -p and -d
+// selects the dictionary variant, as in the original. The original has no
+// hyphenation option; voikko-hyphenate covers that.
+//
+// Usage:
+//   voikkospell [-p DIR] [-d VARIANT] [-s] [-m | -M] [-t | -tt] [-x[C]] [-c N] [-j N]
+//               [OPTION=0|1]...
+//   voikkospell -l | -L | --version
+//
+// Options:
+//   -p DIR                  Look for dictionaries in DIR first
+//   -d VARIANT              Dictionary variant (only Finnish, e.g. fi or fi-x-standard)
+//   -s                      Print suggestions for misspelled words
+//   -m                      Print morphological analyses for recognized words
+//   -M                      Print analyses without the spelling result
+//   -t                      Print only C or W
+//   -tt                     Print only misspelled words
+//   -xC                     One line per word: the word and its suggestions
+//                           separated by C (default: space, skipping
+//                           suggestions that contain spaces)
+//   -cN                     Speller cache size parameter (-1: no change)
+//   -j N                    Check with N threads, in blocks of 500 words
+//   -l                      List the dictionary variant
+//   -L                      List the capabilities of the dictionary
+//   OPTION=0|1              ignore_dot, ignore_numbers, ignore_nonwords,
+//                           accept_first_uppercase, accept_all_uppercase,
+//                           accept_extra_hyphens, accept_missing_hyphens,
+//                           ocr_suggestions
+//   -h, --help              Print help
+//   --version               Print version
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use voikko_fi::dict_path::MOR_VFST;
+use voikko_fi::handle::VoikkoHandle;
+
+/// Longest word that is checked, as LIBVOIKKO_MAX_WORD_CHARS.
+const MAX_WORD_CHARS: usize = 255;
+
+/// Most threads accepted by -j, as in the original.
+const MAX_THREADS: usize = 200;
+
+/// Words handed to one thread at a time with -j.
+const WORDS_PER_BLOCK: usize = 500;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CheckMode {
+    Normal,
+    OnlyCorrectWrong,
+    OnlyIncorrect,
+    None,
+}
+
+struct Options {
+    check_mode: CheckMode,
+    suggest: bool,
+    morphology: bool,
+    /// Separator of the one-line output of -x.
+    one_line: Option<char>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let mut path = None;
+    let mut variant = "fi".to_string();
+    let mut cache_size = 0i64;
+    let mut threads = 1;
+    let mut list_dicts = false;
+    let mut list_capabilities = false;
+    let mut options = Options {
+        check_mode: CheckMode::Normal,
+        suggest: false,
+        morphology: false,
+        one_line: None,
+    };
+    let mut booleans = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print_help();
+                return;
+            }
+            "--version" => {
+                println!("voikkospell version {}", VoikkoHandle::get_version());
+                println!("libvoikko version {}", VoikkoHandle::get_version());
+                return;
+            }
+            "-p" | "-d" | "-j" => {
+                let Some(value) = iter.next() else {
+                    if arg == "-j" {
+                        fail("-j must be followed by number of threads");
+                    }
+                    fail(&format!("Unknown option {arg}"));
+                };
+                match arg.as_str() {
+                    "-p" => path = Some(value.clone()),
+                    "-d" => variant = value.clone(),
+                    _ => {
+                        threads = value.parse().unwrap_or(0);
+                        if threads == 0 || threads > MAX_THREADS {
+                            fail(&format!(
+                                "Number of threads must be between 1 and {MAX_THREADS}"
+                            ));
+                        }
+                    }
+                }
+            }
+            "-l" => list_dicts = true,
+            "-L" => list_capabilities = true,
+            "-t" => options.check_mode = CheckMode::OnlyCorrectWrong,
+            "-tt" => options.check_mode = CheckMode::OnlyIncorrect,
+            "-s" => options.suggest = true,
+            "-m" => options.morphology = true,
+            "-M" => {
+                options.morphology = true;
+                options.check_mode = CheckMode::None;
+            }
+            _ => {
+                if let Some(size) = arg.strip_prefix("-c") {
+                    cache_size = size.parse().unwrap_or(0);
+                } else if let Some(separator) = arg.strip_prefix("-x") {
+                    let mut chars = separator.chars();
+                    options.one_line = match (chars.next(), chars.next()) {
+                        (Some(c), None) => Some(c),
+                        _ => Some(' '),
+                    };
+                } else if let Some((name, value)) = arg.split_once('=') {
+                    match value {
+                        "0" | "1" if boolean_setter(name).is_some() => {
+                            booleans.push((name.to_string(), value == "1"));
+                        }
+                        _ => fail(&format!("Unknown option {arg}")),
+                    }
+                } else {
+                    fail(&format!("Unknown option {arg}"));
+                }
+            }
+        }
+    }
+
+    let mor_path = voikko_fi::dict_path::find_dict_file(path.as_deref(), MOR_VFST)
+        .unwrap_or_else(|e| fail(&format!("E: Initialization of Voikko failed: {e}")));
+    let language = dictionary_language(&mor_path);
+    if list_dicts {
+        let description = index_field(&mor_path, "Description").unwrap_or_default();
+        println!("{language}: {description}");
+        return;
+    }
+    if list_capabilities {
+        // Capabilities are listed by language code without the variant
+        let code = language.split("-x-").next().unwrap_or("fi");
+        for capability in ["spell", "hyphen", "grammar"] {
+            println!("{capability}:{code}");
+        }
+        return;
+    }
+    if variant != "fi" && variant != language {
+        fail(&format!(
+            "E: Initialization of Voikko failed: No valid dictionaries were found ({variant})"
+        ));
+    }
+
+    let mut handle = voikko_fi::dict_path::load_handle_from(&mor_path)
+        .unwrap_or_else(|e| fail(&format!("E: Initialization of Voikko failed: {e}")));
+    if let Ok(size) = usize::try_from(cache_size) {
+        handle.set_speller_cache_size(size);
+    }
+    for (name, value) in booleans {
+        if let Some(set) = boolean_setter(&name) {
+            set(&mut handle, value);
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut block = Vec::with_capacity(WORDS_PER_BLOCK * threads);
+    for line in io::stdin().lock().lines() {
+        let Ok(word) = line else {
+            eprintln!("E: Error while reading from stdin");
+            break;
+        };
+        if word.is_empty() {
+            continue;
+        }
+        if word.chars().count() > MAX_WORD_CHARS {
+            eprintln!("E: Too long word");
+            continue;
+        }
+        if threads == 1 {
+            let mut text = String::new();
+            check_word(&handle, &word, &options, &mut text);
+            // Answer each word before reading the next, as the original
+            if out
+                .write_all(text.as_bytes())
+                .and_then(|()| out.flush())
+                .is_err()
+            {
+                return;
+            }
+            continue;
+        }
+        block.push(word);
+        if block.len() == WORDS_PER_BLOCK * threads {
+            write_block(&handle, &block, &options, threads, &mut out);
+            block.clear();
+        }
+    }
+    write_block(&handle, &block, &options, threads, &mut out);
+}
+
+/// Check `words` with up to `threads` threads and write the results in
+/// order.
+fn write_block(
+    handle: &VoikkoHandle,
+    words: &[String],
+    options: &Options,
+    threads: usize,
+    out: &mut impl Write,
+) {
+    let results: Vec<String> = std::thread::scope(|scope| {
+        let workers: Vec<_> = words
+            .chunks(WORDS_PER_BLOCK.max(words.len().div_ceil(threads)))
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut text = String::new();
+                    for word in chunk {
+                        check_word(handle, word, options, &mut text);
+                    }
+                    text
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    for text in results {
+        let _ = out.write_all(text.as_bytes());
+    }
+    let _ = out.flush();
+}
+
+/// Append the output for `word` to `out`.
+fn check_word(handle: &VoikkoHandle, word: &str, options: &Options, out: &mut String) {
+    let correct = options.check_mode == CheckMode::None || handle.spell(word);
+
+    if let Some(separator) = options.one_line {
+        out.push_str(word);
+        if !correct {
+            for suggestion in handle.suggest(word) {
+                if separator != ' ' || !suggestion.contains(' ') {
+                    out.push(separator);
+                    out.push_str(&suggestion);
+                }
+            }
+        }
+        out.push('\n');
+    } else {
+        match (options.check_mode, correct) {
+            (CheckMode::Normal, true) => _ = writeln!(out, "C: {word}"),
+            (CheckMode::Normal, false) => _ = writeln!(out, "W: {word}"),
+            (CheckMode::OnlyCorrectWrong, true) => out.push_str("C\n"),
+            (CheckMode::OnlyCorrectWrong, false) => out.push_str("W\n"),
+            (CheckMode::OnlyIncorrect, false) => _ = writeln!(out, "{word}"),
+            _ => {}
+        }
+    }
+
+    if options.morphology && correct {
+        for (i, analysis) in handle.analyze(word).iter().enumerate() {
+            let mut keys = analysis.keys();
+            keys.sort();
+            for key in keys {
+                let value = analysis.get(key).unwrap_or_default();
+                _ = writeln!(out, "A({word}):{}:{key}={value}", i + 1);
+            }
+        }
+    }
+    if options.one_line.is_none() && options.suggest && !correct {
+        for suggestion in handle.suggest(word) {
+            _ = writeln!(out, "S: {suggestion}");
+        }
+    }
+}
+
+/// The handle setter of a NAME=0|1 option.
+fn boolean_setter(name: &str) -> Option<fn(&mut VoikkoHandle, bool)> {
+    Some(match name {
+        "ignore_dot" => VoikkoHandle::set_ignore_dot,
+        "ignore_numbers" => VoikkoHandle::set_ignore_numbers,
+        "ignore_nonwords" => VoikkoHandle::set_ignore_nonwords,
+        "accept_first_uppercase" => VoikkoHandle::set_accept_first_uppercase,
+        "accept_all_uppercase" => VoikkoHandle::set_accept_all_uppercase,
+        "accept_extra_hyphens" => VoikkoHandle::set_accept_extra_hyphens,
+        "accept_missing_hyphens" => VoikkoHandle::set_accept_missing_hyphens,
+        "ocr_suggestions" => VoikkoHandle::set_ocr_suggestions,
+        _ => return None,
+    })
+}
+
+/// The language tag of the dictionary at `mor_path` from its index.txt,
+/// or `fi-x-standard` without one.
+fn dictionary_language(mor_path: &Path) -> String {
+    index_field(mor_path, "Language").unwrap_or_else(|| "fi-x-standard".to_string())
+}
+
+/// A field of the index.txt next to `mor_path`.
+fn index_field(mor_path: &Path, field: &str) -> Option<String> {
+    let index = std::fs::read_to_string(mor_path.with_file_name("index.txt")).ok()?;
+    index.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == field).then(|| value.trim().to_string())
+    })
+}
+
+fn print_help() {
+    println!("Usage: voikkospell [OPTION]...");
+    println!("Check spelling of words read from stdin.");
+    println!();
+    println!("    -s   Print suggestions for misspelled words");
+    println!("    -m   Print morphological analysis for recongized words");
+    println!();
+    println!("Also accepted, as in the original voikkospell: -M, -t, -tt, -xC, -cN,");
+    println!("-j N, -l, -L, -p DIR (dictionary directory), -d VARIANT, --version and");
+    println!("ignore_dot, ignore_numbers, ignore_nonwords, accept_first_uppercase,");
+    println!("accept_all_uppercase, accept_extra_hyphens, accept_missing_hyphens and");
+    println!("ocr_suggestions set to 0 or 1 (e.g. ignore_dot=1).");
+}
+
+/// Print `msg` to stderr and exit with status 1, without the "error:"
+/// prefix of the other tools: scripts may match the original messages.
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    std::process::exit(1);
+}
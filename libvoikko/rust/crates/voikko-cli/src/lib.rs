@@ -1,107 +1,11 @@
 // voikko-cli: shared utilities for CLI tools.
 
-use std::path::PathBuf;
+use std::io::{self, BufRead, Write};
 use std::process;
 
-use voikko_fi::handle::{VoikkoError, VoikkoHandle};
-
-/// Default dictionary directory name within VFST dictionary packages.
-const DICT_SUBDIR: &str = "5/mor-standard";
-
-/// Morphology transducer file name.
-const MOR_VFST: &str = "mor.vfst";
-
-/// Autocorrect transducer file name.
-const AUTOCORR_VFST: &str = "autocorr.vfst";
-
-/// Search for dictionary files and create a VoikkoHandle.
-///
-/// Search order:
-/// 1. `dict_path` argument (if provided)
-/// 2. `VOIKKO_DICT_PATH` environment variable
-/// 3. `~/.voikko/5/mor-standard`
-/// 4. Current working directory (looks for `mor.vfst` directly)
-pub fn load_handle(dict_path: Option<&str>) -> Result<VoikkoHandle, String> {
-    let search_paths = build_search_paths(dict_path);
-
-    for dir in &search_paths {
-        let mor_path = dir.join(MOR_VFST);
-        if mor_path.is_file() {
-            let mor_data = std::fs::read(&mor_path)
-                .map_err(|e| format!("failed to read {}: {}", mor_path.display(), e))?;
-
-            let autocorr_path = dir.join(AUTOCORR_VFST);
-            let autocorr_data =
-                if autocorr_path.is_file() {
-                    Some(std::fs::read(&autocorr_path).map_err(|e| {
-                        format!("failed to read {}: {}", autocorr_path.display(), e)
-                    })?)
-                } else {
-                    None
-                };
-
-            return VoikkoHandle::from_bytes(&mor_data, autocorr_data.as_deref(), "fi")
-                .map_err(|e: VoikkoError| format!("failed to create VoikkoHandle: {e}"));
-        }
-    }
-
-    Err(format!(
-        "could not find {} in any of the search paths:\n{}",
-        MOR_VFST,
-        search_paths
-            .iter()
-            .map(|p| format!("  - {}", p.display()))
-            .collect::<Vec<_>>()
-            .join("\n")
-    ))
-}
-
-/// Build the list of directories to search for dictionary files.
-fn build_search_paths(dict_path: Option<&str>) -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-
-    // 1. Explicit path from argument
-    if let Some(p) = dict_path {
-        paths.push(PathBuf::from(p));
-    }
-
-    // 2. VOIKKO_DICT_PATH environment variable
-    if let Ok(env_path) = std::env::var("VOIKKO_DICT_PATH") {
-        paths.push(PathBuf::from(&env_path));
-        // Also check the standard subdirectory within the env path
-        paths.push(PathBuf::from(&env_path).join(DICT_SUBDIR));
-    }
-
-    // 3. Home directory paths
-    if let Some(home) = home_dir() {
-        paths.push(home.join(".voikko").join(DICT_SUBDIR));
-        // macOS Library/Spelling
-        #[cfg(target_os = "macos")]
-        paths.push(
-            home.join("Library")
-                .join("Spelling")
-                .join("voikko")
-                .join(DICT_SUBDIR),
-        );
-    }
-
-    // 4. System paths
-    paths.push(PathBuf::from("/etc/voikko").join(DICT_SUBDIR));
-    paths.push(PathBuf::from("/usr/lib/voikko").join(DICT_SUBDIR));
-    paths.push(PathBuf::from("/usr/share/voikko").join(DICT_SUBDIR));
-
-    // 5. Current directory (fallback for local development)
-    if let Ok(cwd) = std::env::current_dir() {
-        paths.push(cwd);
-    }
-
-    paths
-}
-
-/// Get the user's home directory.
-fn home_dir() -> Option<PathBuf> {
-    std::env::var("HOME").ok().map(PathBuf::from)
-}
+use serde_json::{Value, json};
+use voikko_core::grammar_error::GrammarError;
+use voikko_core::token::{Sentence, SentenceSpan, Token};
 
 /// Parse a `--dict-path=PATH` or `-d PATH` argument from command line args.
 ///
@@ -134,6 +38,181 @@ pub fn parse_dict_path(args: &[String]) -> (Option<String>, Vec<String>) {
     (dict_path, remaining)
 }
 
+/// Record framing for the tool output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Newline-delimited input and human-readable output lines.
+    #[default]
+    Text,
+    /// NUL-delimited input; every output line is NUL-terminated instead of
+    /// newline-terminated, so words may contain spaces and newlines.
+    Print0,
+    /// Newline-delimited input; one JSON object per output line.
+    JsonLines,
+}
+
+/// Parse `-0`/`--print0` and `--json`/`--json-lines` from command line args.
+///
+/// Returns `(mode, remaining_args)`. Exits if both modes are requested.
+pub fn parse_output_mode(args: &[String]) -> (OutputMode, Vec<String>) {
+    let mut mode = OutputMode::Text;
+    let mut remaining = Vec::new();
+
+    for arg in args {
+        let requested = match arg.as_str() {
+            "-0" | "--print0" => OutputMode::Print0,
+            "--json" | "--json-lines" => OutputMode::JsonLines,
+            _ => {
+                remaining.push(arg.clone());
+                continue;
+            }
+        };
+        if mode != OutputMode::Text && mode != requested {
+            fatal("--print0 and --json are mutually exclusive");
+        }
+        mode = requested;
+    }
+
+    (mode, remaining)
+}
+
+/// Parse `--json`/`--json-lines` for the tools that read running text
+/// rather than word records.
+///
+/// Returns `(json, remaining_args)`. Exits on `-0`/`--print0`, which only
+/// applies to word records.
+pub fn parse_json_flag(args: &[String]) -> (bool, Vec<String>) {
+    let (mode, remaining) = parse_output_mode(args);
+    if mode == OutputMode::Print0 {
+        fatal("--print0 is only supported by the word-oriented tools");
+    }
+    (mode == OutputMode::JsonLines, remaining)
+}
+
+/// Call `f` for each word read from stdin.
+///
+/// Records are separated by NUL in [`OutputMode::Print0`] and by newline
+/// otherwise. Surrounding whitespace is trimmed and empty records are
+/// skipped; records that are not valid UTF-8 are reported and skipped.
+pub fn for_each_stdin_word(mode: OutputMode, mut f: impl FnMut(&str)) {
+    let delimiter = if mode == OutputMode::Print0 { 0 } else { b'\n' };
+    for record in io::stdin().lock().split(delimiter) {
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("error reading stdin: {e}");
+                break;
+            }
+        };
+        let Ok(record) = std::str::from_utf8(&record) else {
+            eprintln!("warning: skipping input record that is not valid UTF-8");
+            continue;
+        };
+        let word = record.trim();
+        if !word.is_empty() {
+            f(word);
+        }
+    }
+}
+
+/// Output writer that frames records according to an [`OutputMode`].
+///
+/// Write errors are ignored, as with the `writeln!` calls in the tools.
+pub struct RecordWriter<W: Write> {
+    out: W,
+    mode: OutputMode,
+}
+
+impl RecordWriter<io::BufWriter<io::StdoutLock<'static>>> {
+    /// Create a buffered writer over stdout.
+    pub fn stdout(mode: OutputMode) -> Self {
+        Self::new(io::BufWriter::new(io::stdout().lock()), mode)
+    }
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(out: W, mode: OutputMode) -> Self {
+        Self { out, mode }
+    }
+
+    /// Whether records should be written with [`RecordWriter::json`].
+    pub fn is_json(&self) -> bool {
+        self.mode == OutputMode::JsonLines
+    }
+
+    /// Write one line of the human-readable format, terminated by NUL in
+    /// [`OutputMode::Print0`] and by newline otherwise.
+    pub fn line(&mut self, line: &str) {
+        let terminator: &[u8] = if self.mode == OutputMode::Print0 {
+            b"\0"
+        } else {
+            b"\n"
+        };
+        let _ = self.out.write_all(line.as_bytes());
+        let _ = self.out.write_all(terminator);
+    }
+
+    /// Write one JSON value on its own line. Strings are escaped by
+    /// `serde_json`, so embedded newlines never split a record.
+    pub fn json(&mut self, value: &serde_json::Value) {
+        let _ = serde_json::to_writer(&mut self.out, value);
+        let _ = self.out.write_all(b"\n");
+    }
+}
+
+// JSON records. Field names follow the voikko-wasm DTOs, so scripts can
+// consume CLI and JavaScript results alike.
+
+/// JSON record of a token (`JsToken` in voikko-wasm).
+pub fn token_record(token: &Token) -> Value {
+    json!({
+        "tokenType": token.token_type.name(),
+        "text": token.text,
+        "tokenLen": token.token_len,
+        "pos": token.pos,
+        "startByte": token.start_byte,
+        "endByte": token.end_byte,
+        "startUtf16": token.start_utf16,
+        "endUtf16": token.end_utf16,
+    })
+}
+
+/// JSON record of a sentence boundary (`JsSentence` in voikko-wasm).
+pub fn sentence_record(sentence: &Sentence) -> Value {
+    json!({
+        "sentenceType": sentence.sentence_type.name(),
+        "sentenceLen": sentence.sentence_len,
+    })
+}
+
+/// JSON record of a sentence with its offsets (`JsSentenceSpan` in
+/// voikko-wasm).
+pub fn sentence_span_record(span: &SentenceSpan) -> Value {
+    json!({
+        "sentenceType": span.sentence_type.name(),
+        "text": span.text,
+        "start": span.start,
+        "end": span.end,
+        "startByte": span.start_byte,
+        "endByte": span.end_byte,
+        "startUtf16": span.start_utf16,
+        "endUtf16": span.end_utf16,
+    })
+}
+
+/// JSON record of a grammar error (`JsGrammarError` in voikko-wasm).
+pub fn grammar_error_record(error: &GrammarError) -> Value {
+    json!({
+        "errorCode": error.error_code,
+        "startPos": error.start_pos,
+        "errorLen": error.error_len,
+        "suggestions": error.suggestions,
+        "shortDescription": error.short_description,
+        "severity": format!("{:?}", error.severity),
+        "category": format!("{:?}", error.category),
+    })
+}
+
 /// Print an error message and exit with code 1.
 pub fn fatal(msg: &str) -> ! {
     eprintln!("error: {msg}");
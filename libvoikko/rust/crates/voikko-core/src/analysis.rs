@@ -2,6 +2,7 @@
 // Origin: morphology/Analysis.hpp, Analysis.cpp
 
 use std::collections::HashMap;
+use std::ops::Range;
 
 // ---------------------------------------------------------------------------
 // Attribute key constants
@@ -30,16 +31,34 @@ pub const ATTR_WEIGHT: &str = "WEIGHT";
 pub const ATTR_WORDBASES: &str = "WORDBASES";
 pub const ATTR_WORDIDS: &str = "WORDIDS";
 
+/// One part of an analyzed word -- a compound part or a derivational
+/// morph -- and the characters of the input word it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Character range of the part in the analyzed word. A hyphen joining
+    /// two compound parts belongs to neither.
+    pub range: Range<usize>,
+    /// Base form of the part as given by the dictionary, e.g. "koira" for
+    /// the "koiran" of "koirankoppi", or "ja" for the suffix of "juoksija".
+    /// `None` for parts without one, such as the prefix "epä".
+    pub baseform: Option<String>,
+}
+
 /// Result of morphological analysis: a set of key-value attribute pairs.
 ///
 /// In the C++ code this is `voikko_mor_analysis` which stores `map<Key, wchar_t*>`.
 /// In Rust we use a simple `HashMap<String, String>` and expose typed accessors
-/// for the well-known attribute keys.
+/// for the well-known attribute keys. An analyzer may also record which input
+/// characters each part of the word covers; see [`segments`](Self::segments),
+/// and the transducer output the attributes were parsed from; see
+/// [`raw_fst_output`](Self::raw_fst_output).
 ///
 /// Origin: morphology/Analysis.hpp:42-131
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Analysis {
     attributes: HashMap<String, String>,
+    segments: Option<Vec<Segment>>,
+    raw_fst_output: Option<String>,
 }
 
 impl Analysis {
@@ -48,6 +67,8 @@ impl Analysis {
     pub fn new() -> Self {
         Self {
             attributes: HashMap::new(),
+            segments: None,
+            raw_fst_output: None,
         }
     }
 
@@ -94,6 +115,29 @@ impl Analysis {
     pub fn is_empty(&self) -> bool {
         self.attributes.is_empty()
     }
+
+    /// The parts of the word in input order, each with the character range
+    /// it covers. `None` if the analyzer was not asked to record them.
+    pub fn segments(&self) -> Option<&[Segment]> {
+        self.segments.as_deref()
+    }
+
+    /// Record the parts of the word.
+    pub fn set_segments(&mut self, segments: Vec<Segment>) {
+        self.segments = Some(segments);
+    }
+
+    /// The unparsed transducer output the attributes come from, with every
+    /// tag, including those no attribute reflects. `None` if the analyzer
+    /// was not asked to include it.
+    pub fn raw_fst_output(&self) -> Option<&str> {
+        self.raw_fst_output.as_deref()
+    }
+
+    /// Record the unparsed transducer output.
+    pub fn set_raw_fst_output(&mut self, output: impl Into<String>) {
+        self.raw_fst_output = Some(output.into());
+    }
 }
 
 impl Default for Analysis {
@@ -164,6 +208,34 @@ mod tests {
         assert!(a.is_empty());
     }
 
+    #[test]
+    fn segments_are_not_recorded_by_default() {
+        let mut a = Analysis::new();
+        assert_eq!(a.segments(), None);
+        let segments = vec![
+            Segment {
+                range: 0..6,
+                baseform: Some("koira".to_string()),
+            },
+            Segment {
+                range: 6..11,
+                baseform: Some("koppi".to_string()),
+            },
+        ];
+        a.set_segments(segments.clone());
+        assert_eq!(a.segments(), Some(segments.as_slice()));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn raw_fst_output_is_not_an_attribute() {
+        let mut a = Analysis::new();
+        assert_eq!(a.raw_fst_output(), None);
+        a.set_raw_fst_output("[Ln][Xp]koira[X]koira[Sn][Ny]");
+        assert_eq!(a.raw_fst_output(), Some("[Ln][Xp]koira[X]koira[Sn][Ny]"));
+        assert!(a.is_empty());
+    }
+
     #[test]
     fn clone_is_independent() {
         let mut a = Analysis::new();
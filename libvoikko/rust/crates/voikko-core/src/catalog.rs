@@ -0,0 +1,381 @@
+// Message catalog for grammar error descriptions and templated messages
+// A catalog maps a language to the short descriptions of the grammar error
+// codes and to message templates such as "Did you mean '{0}'?". The
+// built-in catalog has Finnish and English; hosts add their own languages
+// or override entries with a small text format:
+//
+//   # comment
+//   [sv]
+//   1 = Felstavat ord
+//   did_you_mean = Menade du '{0}'?
+//
+// A numeric key is an error code, any other key a message template.
+// Lookups fall back from a language tag ("fi-FI") to its primary subtag
+// ("fi") and then to English.
+
+use std::collections::BTreeMap;
+
+use crate::grammar_error::{
+    GCERR_CUSTOM_RULE, GCERR_INCOMPLETE_PAIRED_CONJUNCTION, GCERR_SUBJECT_VERB_AGREEMENT,
+    GrammarError, error_code_description, error_code_description_en,
+};
+
+/// Template key for proposing a replacement; `{0}` is the suggestion.
+pub const MSG_DID_YOU_MEAN: &str = "did_you_mean";
+
+/// Template key for a word missing from the dictionary; `{0}` is the word.
+pub const MSG_NOT_IN_DICTIONARY: &str = "not_in_dictionary";
+
+/// Language used when neither a language tag nor its primary subtag has
+/// an entry.
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// Highest built-in grammar error code.
+const LAST_ERROR_CODE: i32 = GCERR_SUBJECT_VERB_AGREEMENT;
+
+/// Error type for message catalog parse failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CatalogError {
+    /// A line could not be parsed. `line` is 1-based.
+    #[error("invalid message catalog entry on line {line}: {reason}")]
+    InvalidEntry { line: usize, reason: &'static str },
+}
+
+/// Descriptions and templates of one language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Messages {
+    descriptions: BTreeMap<i32, String>,
+    templates: BTreeMap<String, String>,
+}
+
+/// Localized grammar error descriptions and message templates.
+///
+/// [`MessageCatalog::default`] gives the built-in Finnish and English
+/// messages; [`MessageCatalog::new`] an empty catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageCatalog {
+    /// Keyed by the lowercased language tag.
+    languages: BTreeMap<String, Messages>,
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        let mut catalog = Self::new();
+        for code in 1..=LAST_ERROR_CODE {
+            catalog.set_description("fi", code, error_code_description(code));
+            catalog.set_description("en", code, error_code_description_en(code));
+        }
+        catalog.set_message("fi", MSG_DID_YOU_MEAN, "Tarkoititko '{0}'?");
+        catalog.set_message("en", MSG_DID_YOU_MEAN, "Did you mean '{0}'?");
+        catalog.set_message(
+            "fi",
+            MSG_NOT_IN_DICTIONARY,
+            "Sanaa '{0}' ei l\u{00f6}ydy sanastosta.",
+        );
+        catalog.set_message(
+            "en",
+            MSG_NOT_IN_DICTIONARY,
+            "The word '{0}' is not in the dictionary.",
+        );
+        catalog
+    }
+}
+
+impl MessageCatalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Self {
+            languages: BTreeMap::new(),
+        }
+    }
+
+    /// Return the language tags that have entries, sorted.
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages.keys().map(String::as_str)
+    }
+
+    /// Set the short description of error `code` in `language`.
+    pub fn set_description(&mut self, language: &str, code: i32, description: &str) {
+        self.entry(language)
+            .descriptions
+            .insert(code, description.to_string());
+    }
+
+    /// Set the message template `key` in `language`. `{0}`, `{1}`, ... in
+    /// the template stand for the arguments of [`message`](Self::message).
+    pub fn set_message(&mut self, language: &str, key: &str, template: &str) {
+        self.entry(language)
+            .templates
+            .insert(key.to_string(), template.to_string());
+    }
+
+    /// Return the short description of error `code` in `language`, or
+    /// `None` if no fallback language has one either.
+    pub fn description(&self, language: &str, code: i32) -> Option<&str> {
+        self.lookup(language, |m| m.descriptions.get(&code))
+    }
+
+    /// Return the message `key` in `language` with `{0}`, `{1}`, ...
+    /// replaced by `args`, or `None` if no fallback language has it.
+    /// Placeholders without an argument are kept as they are.
+    pub fn message(&self, language: &str, key: &str, args: &[&str]) -> Option<String> {
+        let template = self.lookup(language, |m| m.templates.get(key))?;
+        Some(fill_template(template, args))
+    }
+
+    /// Return the description of `error` in `language`.
+    ///
+    /// A grammar rule match and an incomplete paired conjunction keep their
+    /// own message, which is Finnish and names the rule or the missing word,
+    /// when `language` is Finnish; otherwise the catalog entry of the error
+    /// code is used, and the error's own description if there is none.
+    pub fn describe(&self, error: &GrammarError, language: &str) -> String {
+        let own = error.short_description.as_str();
+        if matches!(
+            error.error_code,
+            GCERR_CUSTOM_RULE | GCERR_INCOMPLETE_PAIRED_CONJUNCTION
+        ) && primary_subtag(language).eq_ignore_ascii_case("fi")
+            && !own.is_empty()
+        {
+            return own.to_string();
+        }
+        self.description(language, error.error_code)
+            .unwrap_or(own)
+            .to_string()
+    }
+
+    /// Add the entries of a catalog file, replacing existing entries with
+    /// the same language and key. Returns the number of entries in the
+    /// file; on error no entries are added.
+    ///
+    /// Empty lines and lines starting with `#` are skipped; whitespace
+    /// around keys and values is ignored.
+    pub fn load_text(&mut self, text: &str) -> Result<usize, CatalogError> {
+        let mut loaded = MessageCatalog::new();
+        let mut language: Option<String> = None;
+        let mut count = 0;
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason| CatalogError::InvalidEntry {
+                line: idx + 1,
+                reason,
+            };
+            if let Some(section) = line.strip_prefix('[') {
+                let tag = section
+                    .strip_suffix(']')
+                    .ok_or_else(|| invalid("unterminated language section"))?
+                    .trim();
+                if tag.is_empty() {
+                    return Err(invalid("empty language tag"));
+                }
+                language = Some(tag.to_string());
+                continue;
+            }
+            let language = language
+                .as_deref()
+                .ok_or_else(|| invalid("entry before a language section"))?;
+            let (key, value) = line.split_once('=').ok_or_else(|| invalid("missing '='"))?;
+            let (key, value) = (key.trim(), value.trim());
+            if key.is_empty() {
+                return Err(invalid("empty key"));
+            }
+            match key.parse::<i32>() {
+                Ok(code) => loaded.set_description(language, code, value),
+                Err(_) => loaded.set_message(language, key, value),
+            }
+            count += 1;
+        }
+        for (language, messages) in loaded.languages {
+            let entry = self.entry(&language);
+            entry.descriptions.extend(messages.descriptions);
+            entry.templates.extend(messages.templates);
+        }
+        Ok(count)
+    }
+
+    fn entry(&mut self, language: &str) -> &mut Messages {
+        self.languages
+            .entry(language.to_ascii_lowercase())
+            .or_default()
+    }
+
+    /// Look up an entry in `language`, its primary subtag and English.
+    fn lookup<'a>(
+        &'a self,
+        language: &str,
+        get: impl Fn(&'a Messages) -> Option<&'a String>,
+    ) -> Option<&'a str> {
+        let language = language.to_ascii_lowercase();
+        [
+            language.as_str(),
+            primary_subtag(&language),
+            FALLBACK_LANGUAGE,
+        ]
+        .into_iter()
+        .find_map(|tag| get(self.languages.get(tag)?))
+        .map(String::as_str)
+    }
+}
+
+/// The primary language subtag of a tag such as "fi-FI" or "fi_FI".
+fn primary_subtag(language: &str) -> &str {
+    language.split(['-', '_']).next().unwrap_or_default()
+}
+
+/// Replace `{n}` in `template` with `args[n]`.
+fn fill_template(template: &str, args: &[&str]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let arg = after.find('}').and_then(|close| {
+            let index: usize = after[..close].parse().ok()?;
+            Some((args.get(index)?, close))
+        });
+        match arg {
+            Some((arg, close)) => {
+                out.push_str(arg);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar_error::{GCERR_INVALID_SPELLING, GCERR_REPEATING_WORD};
+
+    #[test]
+    fn builtin_catalog_matches_descriptions() {
+        let catalog = MessageCatalog::default();
+        assert_eq!(catalog.languages().collect::<Vec<_>>(), ["en", "fi"]);
+        for code in 1..=LAST_ERROR_CODE {
+            assert_eq!(
+                catalog.description("fi", code),
+                Some(error_code_description(code))
+            );
+            assert_eq!(
+                catalog.description("en", code),
+                Some(error_code_description_en(code))
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_falls_back_to_primary_subtag_and_english() {
+        let catalog = MessageCatalog::default();
+        let fi = error_code_description(GCERR_INVALID_SPELLING);
+        let en = error_code_description_en(GCERR_INVALID_SPELLING);
+        assert_eq!(
+            catalog.description("fi-FI", GCERR_INVALID_SPELLING),
+            Some(fi)
+        );
+        assert_eq!(
+            catalog.description("FI_fi", GCERR_INVALID_SPELLING),
+            Some(fi)
+        );
+        assert_eq!(catalog.description("sv", GCERR_INVALID_SPELLING), Some(en));
+        assert_eq!(catalog.description("fi", 999), None);
+        assert_eq!(MessageCatalog::new().description("fi", 1), None);
+    }
+
+    #[test]
+    fn message_fills_placeholders() {
+        let catalog = MessageCatalog::default();
+        assert_eq!(
+            catalog
+                .message("en", MSG_DID_YOU_MEAN, &["koira"])
+                .as_deref(),
+            Some("Did you mean 'koira'?")
+        );
+        assert_eq!(
+            catalog.message("fi", MSG_DID_YOU_MEAN, &[]).as_deref(),
+            Some("Tarkoititko '{0}'?")
+        );
+        assert_eq!(catalog.message("en", "unknown", &["x"]), None);
+        assert_eq!(fill_template("{1}{0} {x} {", &["a", "b"]), "ba {x} {");
+    }
+
+    #[test]
+    fn load_text_adds_and_overrides_entries() {
+        let mut catalog = MessageCatalog::default();
+        let count = catalog
+            .load_text(
+                "# Swedish\n[sv]\n1 = Felstavat ord\ndid_you_mean = Menade du '{0}'?\n\n\
+                 [en]\n8 = Repeated word\n",
+            )
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            catalog.description("sv-SE", GCERR_INVALID_SPELLING),
+            Some("Felstavat ord")
+        );
+        // Entries the language lacks come from English
+        assert_eq!(
+            catalog.description("sv", GCERR_REPEATING_WORD),
+            Some("Repeated word")
+        );
+        assert_eq!(
+            catalog
+                .message("sv", MSG_DID_YOU_MEAN, &["hund"])
+                .as_deref(),
+            Some("Menade du 'hund'?")
+        );
+    }
+
+    #[test]
+    fn load_text_rejects_malformed_lines() {
+        let mut catalog = MessageCatalog::new();
+        assert_eq!(
+            catalog.load_text("1 = x"),
+            Err(CatalogError::InvalidEntry {
+                line: 1,
+                reason: "entry before a language section"
+            })
+        );
+        assert_eq!(
+            catalog.load_text("[sv]\n1 = x\n2 x"),
+            Err(CatalogError::InvalidEntry {
+                line: 3,
+                reason: "missing '='"
+            })
+        );
+        assert_eq!(
+            catalog.load_text("[sv"),
+            Err(CatalogError::InvalidEntry {
+                line: 1,
+                reason: "unterminated language section"
+            })
+        );
+        // Nothing from a failed load is kept
+        assert_eq!(catalog.languages().count(), 0);
+    }
+
+    #[test]
+    fn describe_keeps_finnish_rule_messages() {
+        let catalog = MessageCatalog::default();
+        let mut error = GrammarError::new(GCERR_CUSTOM_RULE, 0, 4);
+        error.short_description = "Vältä sanaa.".to_string();
+        assert_eq!(catalog.describe(&error, "fi"), "Vältä sanaa.");
+        assert_eq!(
+            catalog.describe(&error, "en"),
+            error_code_description_en(GCERR_CUSTOM_RULE)
+        );
+        let error = GrammarError::new(GCERR_REPEATING_WORD, 0, 4);
+        assert_eq!(
+            catalog.describe(&error, "en-GB"),
+            error_code_description_en(GCERR_REPEATING_WORD)
+        );
+    }
+}
@@ -21,23 +21,79 @@ const FINNISH_CONSONANTS: &[char] = &[
 // ---------------------------------------------------------------------------
 
 /// Character type classification.
+///
+/// Non-exhaustive with stable codes, like `TokenType` (see `enums.rs`).
+///
 /// Origin: charset.hpp:36
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum CharType {
-    Unknown,
-    Letter,
-    Digit,
-    Whitespace,
-    Punctuation,
+    Unknown = 0,
+    Letter = 1,
+    Digit = 2,
+    Whitespace = 3,
+    Punctuation = 4,
+}
+
+impl CharType {
+    /// Stable numeric code (the C++ `char_type` value).
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+}
+
+const _: () = {
+    assert!(CharType::Unknown.code() == 0);
+    assert!(CharType::Letter.code() == 1);
+    assert!(CharType::Digit.code() == 2);
+    assert!(CharType::Whitespace.code() == 3);
+    assert!(CharType::Punctuation.code() == 4);
+};
+
+/// Character types of U+0000..U+00FF, the range nearly all Finnish text is
+/// in. Must agree with `classify_char`.
+#[cfg(feature = "fast-charclass")]
+static LATIN1_CHAR_TYPES: [CharType; 256] = {
+    let mut table = [CharType::Unknown; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = latin1_char_type(i as u8);
+        i += 1;
+    }
+    table
+};
+
+/// `classify_char` for a Latin-1 character, usable in a constant.
+#[cfg(feature = "fast-charclass")]
+const fn latin1_char_type(b: u8) -> CharType {
+    match b {
+        b'A'..=b'Z' | b'a'..=b'z' | 0xC1..=0xD6 | 0xD8..=0xF6 | 0xF8..=0xFF => CharType::Letter,
+        0x09..=0x0D | b' ' | 0x85 | 0xA0 => CharType::Whitespace,
+        b'.' | b',' | b';' | b'-' | b'!' | b'?' | b':' | b'\'' | b'(' | b')' | b'[' | b']'
+        | b'{' | b'}' | b'/' | b'&' | 0xAD => CharType::Punctuation,
+        b'"' | 0xBB => CharType::Punctuation,
+        b'0'..=b'9' => CharType::Digit,
+        _ => CharType::Unknown,
+    }
 }
 
 /// Returns the character type for a given character.
 ///
 /// This classifies characters into letters, digits, whitespace, punctuation,
-/// or unknown following the same logic as the C++ `get_char_type`.
+/// or unknown following the same logic as the C++ `get_char_type`. With the
+/// `fast-charclass` feature, Latin-1 characters are looked up in a table.
 ///
 /// Origin: charset.cpp:42-74
 pub fn get_char_type(c: char) -> CharType {
+    #[cfg(feature = "fast-charclass")]
+    if let Some(&char_type) = LATIN1_CHAR_TYPES.get(c as usize) {
+        return char_type;
+    }
+    classify_char(c)
+}
+
+/// `get_char_type` without the table.
+fn classify_char(c: char) -> CharType {
     let cp = c as u32;
     if (0x41..=0x5A).contains(&cp)           // A-Z
         || (0x61..=0x7A).contains(&cp)       // a-z
@@ -159,6 +215,15 @@ pub fn is_consonant(c: char) -> bool {
 ///
 /// Origin: SimpleChar.cpp:36-97
 pub fn simple_lower(c: char) -> char {
+    #[cfg(feature = "fast-charclass")]
+    match c {
+        '\0'..='\x7F' => return c.to_ascii_lowercase(),
+        '\u{00C0}'..='\u{00DE}' if c != '\u{00D7}' => {
+            return char::from_u32(c as u32 + 0x20).unwrap_or(c);
+        }
+        '\u{0080}'..='\u{00FF}' => return c,
+        _ => {}
+    }
     let mut iter = c.to_lowercase();
     iter.next().unwrap_or(c)
 }
@@ -170,6 +235,14 @@ pub fn simple_lower(c: char) -> char {
 ///
 /// Origin: SimpleChar.cpp:99-159
 pub fn simple_upper(c: char) -> char {
+    #[cfg(feature = "fast-charclass")]
+    match c {
+        '\0'..='\x7F' => return c.to_ascii_uppercase(),
+        '\u{00E0}'..='\u{00FE}' if c != '\u{00F7}' => {
+            return char::from_u32(c as u32 - 0x20).unwrap_or(c);
+        }
+        _ => {}
+    }
     let mut iter = c.to_uppercase();
     iter.next().unwrap_or(c)
 }
@@ -340,6 +413,17 @@ mod tests {
         assert_eq!(simple_upper('\u{00F6}'), '\u{00D6}'); // ö -> Ö
     }
 
+    #[test]
+    fn latin1_fast_path_matches_unicode_mapping() {
+        for c in (0..=0x17F).filter_map(char::from_u32) {
+            assert_eq!(get_char_type(c), classify_char(c), "{c:?}");
+            assert_eq!(simple_lower(c), c.to_lowercase().next().unwrap(), "{c:?}");
+            assert_eq!(simple_upper(c), c.to_uppercase().next().unwrap(), "{c:?}");
+        }
+        assert_eq!(simple_upper('\u{00FF}'), '\u{0178}'); // ÿ -> Ÿ
+        assert_eq!(simple_upper('\u{00DF}'), 'S'); // ß -> SS
+    }
+
     #[test]
     fn is_upper_basic() {
         assert!(is_upper('A'));
@@ -1,36 +1,120 @@
 // Shared enums: TokenType, SentenceType, SpellResult, option constants
 // Origin: voikko_enums.h, voikko_defines.h
+//
+// Versioning: TokenType and SentenceType (and CharType in character.rs) are
+// `#[non_exhaustive]` and carry explicit discriminants. The discriminants
+// and names are the stable codes used by the FFI and WASM bindings, so a new
+// variant gets the next unused code and existing codes never change. The
+// bindings convert through `code()`/`name()` instead of matching on the
+// variants themselves; the const assertions below pin the mapping table.
 
 /// Token types for string tokenization.
 /// Origin: voikko_enums.h:40
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum TokenType {
     /// End of text or error.
-    None,
+    None = 0,
     /// Word token.
-    Word,
+    Word = 1,
     /// Punctuation token.
-    Punctuation,
+    Punctuation = 2,
     /// Whitespace token.
-    Whitespace,
+    Whitespace = 3,
     /// Character not used in any supported natural language.
-    Unknown,
+    Unknown = 4,
+}
+
+impl TokenType {
+    /// All token types, in code order.
+    pub const ALL: [TokenType; 5] = [
+        TokenType::None,
+        TokenType::Word,
+        TokenType::Punctuation,
+        TokenType::Whitespace,
+        TokenType::Unknown,
+    ];
+
+    /// Stable numeric code (the C `enum voikko_token_type` value).
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Token type for a numeric code, or `None` for an unknown code.
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    /// Stable name used by the bindings ("None", "Word", ...).
+    pub const fn name(self) -> &'static str {
+        match self {
+            TokenType::None => "None",
+            TokenType::Word => "Word",
+            TokenType::Punctuation => "Punctuation",
+            TokenType::Whitespace => "Whitespace",
+            TokenType::Unknown => "Unknown",
+        }
+    }
 }
 
 /// Sentence start types for sentence detection.
 /// Origin: voikko_enums.h:49
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum SentenceType {
     /// End of text reached or error.
-    None,
+    None = 0,
     /// This is not a start of a new sentence.
-    NoStart,
+    NoStart = 1,
     /// This is a probable start of a new sentence.
-    Probable,
+    Probable = 2,
     /// This may be a start of a new sentence.
-    Possible,
+    Possible = 3,
+}
+
+impl SentenceType {
+    /// All sentence types, in code order.
+    pub const ALL: [SentenceType; 4] = [
+        SentenceType::None,
+        SentenceType::NoStart,
+        SentenceType::Probable,
+        SentenceType::Possible,
+    ];
+
+    /// Stable numeric code (the C `enum voikko_sentence_type` value).
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Sentence type for a numeric code, or `None` for an unknown code.
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    /// Stable name used by the bindings ("None", "NoStart", ...).
+    pub const fn name(self) -> &'static str {
+        match self {
+            SentenceType::None => "None",
+            SentenceType::NoStart => "NoStart",
+            SentenceType::Probable => "Probable",
+            SentenceType::Possible => "Possible",
+        }
+    }
 }
 
+// The binding contract: these codes must never change.
+const _: () = {
+    assert!(TokenType::None.code() == 0);
+    assert!(TokenType::Word.code() == 1);
+    assert!(TokenType::Punctuation.code() == 2);
+    assert!(TokenType::Whitespace.code() == 3);
+    assert!(TokenType::Unknown.code() == 4);
+    assert!(SentenceType::None.code() == 0);
+    assert!(SentenceType::NoStart.code() == 1);
+    assert!(SentenceType::Probable.code() == 2);
+    assert!(SentenceType::Possible.code() == 3);
+};
+
 /// Internal spell-checker result type.
 /// Origin: voikko_defines.h:49-53 (VOIKKO_SPELL_*)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -126,6 +210,22 @@ pub const SPELLER_CACHE_SIZE: i32 = 17;
 mod tests {
     use super::*;
 
+    #[test]
+    fn codes_and_names_round_trip() {
+        for (i, t) in TokenType::ALL.into_iter().enumerate() {
+            assert_eq!(t.code(), i as i32);
+            assert_eq!(TokenType::from_code(t.code()), Some(t));
+        }
+        for (i, t) in SentenceType::ALL.into_iter().enumerate() {
+            assert_eq!(t.code(), i as i32);
+            assert_eq!(SentenceType::from_code(t.code()), Some(t));
+        }
+        assert_eq!(TokenType::from_code(5), None);
+        assert_eq!(SentenceType::from_code(-1), None);
+        assert_eq!(TokenType::Whitespace.name(), "Whitespace");
+        assert_eq!(SentenceType::NoStart.name(), "NoStart");
+    }
+
     #[test]
     fn token_type_equality() {
         assert_eq!(TokenType::Word, TokenType::Word);
@@ -25,6 +25,143 @@ pub const GCERR_MISPLACED_SIDESANA: i32 = 16;
 pub const GCERR_MISSING_MAIN_VERB: i32 = 17;
 pub const GCERR_EXTRA_MAIN_VERB: i32 = 18;
 
+/// Overlong compound word (opt-in style check, no C++ counterpart).
+pub const GCERR_OVERLONG_COMPOUND: i32 = 19;
+
+/// Match of a declarative grammar rule (rule packs, no C++ counterpart).
+/// The rule may supply its own description, severity and category.
+pub const GCERR_CUSTOM_RULE: i32 = 20;
+
+/// Malformed or impossible date (opt-in number format check, no C++
+/// counterpart).
+pub const GCERR_DATE_FORMAT: i32 = 21;
+
+/// Clock time separator not matching the number style (opt-in number
+/// format check, no C++ counterpart).
+pub const GCERR_TIME_FORMAT: i32 = 22;
+
+/// Wrong punctuation in an ordinal number (opt-in number format check, no
+/// C++ counterpart).
+pub const GCERR_ORDINAL_FORMAT: i32 = 23;
+
+/// Missing space between a number and its unit (opt-in number format
+/// check, no C++ counterpart).
+pub const GCERR_UNIT_SPACING: i32 = 24;
+
+/// Several consecutive sentences starting with the same word (opt-in
+/// discourse check, no C++ counterpart).
+pub const GCERR_REPEATED_SENTENCE_START: i32 = 25;
+
+/// Quotation mark without a matching pair in the paragraph (opt-in
+/// discourse check, no C++ counterpart).
+pub const GCERR_UNBALANCED_QUOTATION: i32 = 26;
+
+/// First member of a paired conjunction ("sekä ... että", "joko ... tai")
+/// without the second member in the sentence (opt-in paired conjunction
+/// check, no C++ counterpart).
+pub const GCERR_INCOMPLETE_PAIRED_CONJUNCTION: i32 = 27;
+
+/// Correctly spelled word in a case its context does not allow, such as a
+/// nominative after "kohti" or before "täytyy" (opt-in real-word error
+/// check, no C++ counterpart).
+pub const GCERR_WRONG_CASE: i32 = 28;
+
+/// Finite verb whose person or number does not agree with the nominative
+/// subject directly before it, such as "koirat juoksee" (opt-in
+/// experimental subject-verb agreement check, no C++ counterpart).
+pub const GCERR_SUBJECT_VERB_AGREEMENT: i32 = 29;
+
+// ---------------------------------------------------------------------------
+// Severity and category
+// ---------------------------------------------------------------------------
+
+/// How strongly a grammar error should be presented to the user.
+///
+/// The C++ `error_level` field is unused; all C++ error codes map to `Error`.
+/// Opt-in style checks report `Warning`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GrammarErrorSeverity {
+    /// A definite error.
+    #[default]
+    Error = 0,
+    /// A stylistic remark; the text is not necessarily wrong.
+    Warning = 1,
+}
+
+impl GrammarErrorSeverity {
+    /// Stable integer code used by the FFI (0 = Error, 1 = Warning).
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Severity for an FFI code, or `None` if the code is unknown.
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(Self::Error),
+            1 => Some(Self::Warning),
+            _ => None,
+        }
+    }
+}
+
+/// Broad classification of a grammar error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GrammarErrorCategory {
+    /// Misspelled words.
+    Spelling,
+    /// Punctuation and whitespace.
+    Punctuation,
+    /// Upper/lower case usage.
+    Capitalization,
+    /// Sentence structure and verb usage.
+    #[default]
+    Grammar,
+    /// Style remarks.
+    Style,
+    /// Correctly spelled words that do not fit their context.
+    RealWord,
+}
+
+/// The edit that applies one suggestion of a [`GrammarError`]: replace
+/// `len` characters of the text from `start` with `text`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Replacement {
+    /// Start of the replaced characters in the text (character offset).
+    pub start: usize,
+    /// Number of replaced characters; 0 for an insertion.
+    pub len: usize,
+    /// The new text; empty for a deletion.
+    pub text: String,
+}
+
+impl Replacement {
+    /// The smallest replacement that turns `original`, which starts at
+    /// `start` in the text, into `suggestion`: characters both share at the
+    /// beginning and at the end are kept. Replacing "koira talo" at 0 with
+    /// "koiratalo" deletes the one space at 5.
+    pub fn between(start: usize, original: &[char], suggestion: &str) -> Self {
+        let suggestion: Vec<char> = suggestion.chars().collect();
+        let prefix = original
+            .iter()
+            .zip(&suggestion)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = original[prefix..]
+            .iter()
+            .rev()
+            .zip(suggestion[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        Self {
+            start: start + prefix,
+            len: original.len() - prefix - suffix,
+            text: suggestion[prefix..suggestion.len() - suffix]
+                .iter()
+                .collect(),
+        }
+    }
+}
+
 /// A grammar error detected during grammar checking.
 ///
 /// This corresponds to the C++ `VoikkoGrammarError` / `voikko_grammar_error`
@@ -50,10 +187,21 @@ pub struct GrammarError {
     /// Origin: voikko_structs.h:56
     pub suggestions: Vec<String>,
 
+    /// The edit that applies each suggestion, in the order of
+    /// `suggestions`: which characters of the error span to replace and
+    /// with what. Empty if the checker has not filled them in.
+    pub replacements: Vec<Replacement>,
+
     /// Short human-readable description of the error (Finnish).
     /// Populated from `error_code_description()` after creation.
     /// Origin: grammar/error.cpp (voikko_error_message_cstr)
     pub short_description: String,
+
+    /// Severity of the error. Populated from `error_code_severity()`.
+    pub severity: GrammarErrorSeverity,
+
+    /// Category of the error. Populated from `error_code_category()`.
+    pub category: GrammarErrorCategory,
 }
 
 impl GrammarError {
@@ -61,10 +209,13 @@ impl GrammarError {
     pub fn new(error_code: i32, start_pos: usize, error_len: usize) -> Self {
         Self {
             short_description: error_code_description(error_code).to_string(),
+            severity: error_code_severity(error_code),
+            category: error_code_category(error_code),
             error_code,
             start_pos,
             error_len,
             suggestions: Vec::new(),
+            replacements: Vec::new(),
         }
     }
 
@@ -77,10 +228,22 @@ impl GrammarError {
     ) -> Self {
         Self {
             short_description: error_code_description(error_code).to_string(),
+            severity: error_code_severity(error_code),
+            category: error_code_category(error_code),
             error_code,
             start_pos,
             error_len,
             suggestions,
+            replacements: Vec::new(),
+        }
+    }
+
+    /// Move the error and its replacements `offset` characters further
+    /// into the text, e.g. from a paragraph to the document holding it.
+    pub fn shift(&mut self, offset: usize) {
+        self.start_pos += offset;
+        for replacement in &mut self.replacements {
+            replacement.start += offset;
         }
     }
 }
@@ -93,8 +256,52 @@ impl Default for GrammarError {
             start_pos: 0,
             error_len: 0,
             suggestions: Vec::new(),
+            replacements: Vec::new(),
             short_description: String::new(),
+            severity: GrammarErrorSeverity::default(),
+            category: GrammarErrorCategory::default(),
+        }
+    }
+}
+
+/// Map a grammar error code to its default severity.
+pub fn error_code_severity(code: i32) -> GrammarErrorSeverity {
+    match code {
+        GCERR_OVERLONG_COMPOUND
+        | GCERR_CUSTOM_RULE
+        | GCERR_TIME_FORMAT
+        | GCERR_REPEATED_SENTENCE_START
+        | GCERR_INCOMPLETE_PAIRED_CONJUNCTION
+        | GCERR_SUBJECT_VERB_AGREEMENT => GrammarErrorSeverity::Warning,
+        _ => GrammarErrorSeverity::Error,
+    }
+}
+
+/// Map a grammar error code to its category.
+pub fn error_code_category(code: i32) -> GrammarErrorCategory {
+    match code {
+        GCERR_INVALID_SPELLING => GrammarErrorCategory::Spelling,
+        GCERR_EXTRA_WHITESPACE
+        | GCERR_SPACE_BEFORE_PUNCTUATION
+        | GCERR_EXTRA_COMMA
+        | GCERR_INVALID_SENTENCE_STARTER
+        | GCERR_TERMINATING_PUNCTUATION_MISSING
+        | GCERR_INVALID_PUNCTUATION_AT_END_OF_QUOTATION
+        | GCERR_FOREIGN_QUOTATION_MARK
+        | GCERR_MISPLACED_CLOSING_PARENTHESIS
+        | GCERR_DATE_FORMAT
+        | GCERR_ORDINAL_FORMAT
+        | GCERR_UNIT_SPACING
+        | GCERR_UNBALANCED_QUOTATION => GrammarErrorCategory::Punctuation,
+        GCERR_WRITE_FIRST_LOWERCASE | GCERR_WRITE_FIRST_UPPERCASE => {
+            GrammarErrorCategory::Capitalization
         }
+        GCERR_OVERLONG_COMPOUND
+        | GCERR_CUSTOM_RULE
+        | GCERR_TIME_FORMAT
+        | GCERR_REPEATED_SENTENCE_START => GrammarErrorCategory::Style,
+        GCERR_WRONG_CASE => GrammarErrorCategory::RealWord,
+        _ => GrammarErrorCategory::Grammar,
     }
 }
 
@@ -104,7 +311,8 @@ impl Default for GrammarError {
 /// Any other language code falls back to English.
 ///
 /// Finnish descriptions match the C++ `voikko_error_message_cstr` output.
-/// English descriptions match C++ `error.cpp` English branch.
+/// English descriptions match C++ `error.cpp` English branch. For other
+/// languages and templated messages see [`MessageCatalog`](crate::catalog::MessageCatalog).
 /// Origin: grammar/error.cpp
 pub fn error_code_description_lang(code: i32, language: &str) -> &'static str {
     if language.starts_with("fi") {
@@ -156,6 +364,25 @@ pub fn error_code_description(code: i32) -> &'static str {
         GCERR_EXTRA_MAIN_VERB => {
             "Virkkeest\u{00e4} saattaa puuttua pilkku, tai siin\u{00e4} voi olla ylim\u{00e4}\u{00e4}r\u{00e4}inen verbi."
         }
+        GCERR_OVERLONG_COMPOUND => {
+            "Pitk\u{00e4} yhdyssana. Harkitse sanan jakamista tai virkkeen muotoilemista toisin."
+        }
+        GCERR_CUSTOM_RULE => "Harkitse toisenlaista sanamuotoa.",
+        GCERR_DATE_FORMAT => {
+            "Tarkista p\u{00e4}iv\u{00e4}m\u{00e4}\u{00e4}r\u{00e4}n kirjoitusasu."
+        }
+        GCERR_TIME_FORMAT => "Tarkista kellonajan erotinmerkki.",
+        GCERR_ORDINAL_FORMAT => "Tarkista j\u{00e4}rjestysluvun v\u{00e4}limerkit.",
+        GCERR_UNIT_SPACING => "Luvun ja yksik\u{00f6}n v\u{00e4}liin kuuluu v\u{00e4}li.",
+        GCERR_REPEATED_SENTENCE_START => {
+            "Per\u{00e4}kk\u{00e4}iset virkkeet alkavat samalla sanalla."
+        }
+        GCERR_UNBALANCED_QUOTATION => "Lainausmerkilt\u{00e4} puuttuu pari.",
+        GCERR_INCOMPLETE_PAIRED_CONJUNCTION => {
+            "Parikonjunktion j\u{00e4}lkimm\u{00e4}inen osa puuttuu."
+        }
+        GCERR_WRONG_CASE => "Sana on v\u{00e4}\u{00e4}r\u{00e4}ss\u{00e4} sijamuodossa.",
+        GCERR_SUBJECT_VERB_AGREEMENT => "Verbin persoona tai luku ei vastaa subjektia.",
         _ => "",
     }
 }
@@ -192,6 +419,21 @@ pub fn error_code_description_en(code: i32) -> &'static str {
         }
         GCERR_MISSING_MAIN_VERB => "Check whether a main verb is missing from the sentence.",
         GCERR_EXTRA_MAIN_VERB => "A comma may be missing, or there may be an extra verb.",
+        GCERR_OVERLONG_COMPOUND => "Long compound word. Consider splitting it or rephrasing.",
+        GCERR_CUSTOM_RULE => "Consider different wording.",
+        GCERR_DATE_FORMAT => "Check the format of the date.",
+        GCERR_TIME_FORMAT => "Check the separator in the time of day.",
+        GCERR_ORDINAL_FORMAT => "Check the punctuation of the ordinal number.",
+        GCERR_UNIT_SPACING => "Put a space between the number and the unit.",
+        GCERR_REPEATED_SENTENCE_START => "Consecutive sentences start with the same word.",
+        GCERR_UNBALANCED_QUOTATION => "The quotation mark has no matching pair.",
+        GCERR_INCOMPLETE_PAIRED_CONJUNCTION => {
+            "The second part of the paired conjunction is missing."
+        }
+        GCERR_WRONG_CASE => "The word is in the wrong case for its context.",
+        GCERR_SUBJECT_VERB_AGREEMENT => {
+            "The verb does not agree with its subject in person or number."
+        }
         _ => "",
     }
 }
@@ -218,6 +460,29 @@ mod tests {
         assert_eq!(err.suggestions[0], "word");
     }
 
+    #[test]
+    fn replacement_between_keeps_shared_ends() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        let join = Replacement::between(3, &chars("koira talo"), "koiratalo");
+        assert_eq!((join.start, join.len, join.text.as_str()), (8, 1, ""));
+        let split = Replacement::between(0, &chars("koiratalo"), "koira talo");
+        assert_eq!((split.start, split.len, split.text.as_str()), (5, 0, " "));
+        let repeat = Replacement::between(0, &chars("on on"), "on");
+        assert_eq!((repeat.start, repeat.len, repeat.text.as_str()), (2, 3, ""));
+        let case = Replacement::between(0, &chars("kissa"), "Kissa");
+        assert_eq!((case.start, case.len, case.text.as_str()), (0, 1, "K"));
+    }
+
+    #[test]
+    fn shift_moves_replacements() {
+        let mut err =
+            GrammarError::with_suggestions(GCERR_EXTRA_WHITESPACE, 2, 2, vec![" ".into()]);
+        err.replacements = vec![Replacement::between(2, &[' ', ' '], " ")];
+        err.shift(10);
+        assert_eq!(err.start_pos, 12);
+        assert_eq!(err.replacements[0].start, 13);
+    }
+
     #[test]
     fn default_error() {
         let err = GrammarError::default();
@@ -247,7 +512,7 @@ mod tests {
 
     #[test]
     fn english_descriptions_all_nonempty() {
-        for code in 1..=18 {
+        for code in 1..=29 {
             let desc = error_code_description_en(code);
             assert!(
                 !desc.is_empty(),
@@ -256,6 +521,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn severity_and_category() {
+        let err = GrammarError::new(GCERR_EXTRA_COMMA, 0, 1);
+        assert_eq!(err.severity, GrammarErrorSeverity::Error);
+        assert_eq!(err.category, GrammarErrorCategory::Punctuation);
+
+        let err = GrammarError::new(GCERR_OVERLONG_COMPOUND, 0, 30);
+        assert_eq!(err.severity, GrammarErrorSeverity::Warning);
+        assert_eq!(err.category, GrammarErrorCategory::Style);
+        assert!(!err.short_description.is_empty());
+
+        for code in 1..=18 {
+            assert_eq!(error_code_severity(code), GrammarErrorSeverity::Error);
+        }
+        assert_eq!(
+            error_code_severity(GCERR_TIME_FORMAT),
+            GrammarErrorSeverity::Warning
+        );
+        assert_eq!(
+            error_code_category(GCERR_UNIT_SPACING),
+            GrammarErrorCategory::Punctuation
+        );
+        assert_eq!(
+            error_code_severity(GCERR_REPEATED_SENTENCE_START),
+            GrammarErrorSeverity::Warning
+        );
+        assert_eq!(
+            error_code_category(GCERR_UNBALANCED_QUOTATION),
+            GrammarErrorCategory::Punctuation
+        );
+        assert_eq!(
+            error_code_severity(GCERR_INCOMPLETE_PAIRED_CONJUNCTION),
+            GrammarErrorSeverity::Warning
+        );
+        assert_eq!(
+            error_code_category(GCERR_INCOMPLETE_PAIRED_CONJUNCTION),
+            GrammarErrorCategory::Grammar
+        );
+        assert_eq!(
+            error_code_category(GCERR_WRONG_CASE),
+            GrammarErrorCategory::RealWord
+        );
+        assert_eq!(
+            error_code_severity(GCERR_SUBJECT_VERB_AGREEMENT),
+            GrammarErrorSeverity::Warning
+        );
+        assert_eq!(
+            error_code_category(GCERR_SUBJECT_VERB_AGREEMENT),
+            GrammarErrorCategory::Grammar
+        );
+        for sev in [GrammarErrorSeverity::Error, GrammarErrorSeverity::Warning] {
+            assert_eq!(GrammarErrorSeverity::from_code(sev.code()), Some(sev));
+        }
+        assert_eq!(GrammarErrorSeverity::from_code(2), None);
+        assert_eq!(
+            error_code_category(GCERR_INVALID_SPELLING),
+            GrammarErrorCategory::Spelling
+        );
+        assert_eq!(
+            error_code_category(GCERR_WRITE_FIRST_UPPERCASE),
+            GrammarErrorCategory::Capitalization
+        );
+        assert_eq!(
+            error_code_category(GCERR_MISSING_MAIN_VERB),
+            GrammarErrorCategory::Grammar
+        );
+    }
+
     #[test]
     fn lang_dispatch_fi_vs_en() {
         let fi = error_code_description_lang(GCERR_INVALID_SPELLING, "fi");
@@ -1,5 +1,6 @@
 pub mod analysis;
 pub mod case;
+pub mod catalog;
 pub mod character;
 pub mod enums;
 pub mod grammar_error;
@@ -33,21 +33,52 @@ pub struct Token {
     /// Position of this token within the paragraph (character offset).
     /// Origin: Token.hpp:109 (pos)
     pub pos: usize,
+
+    /// Start of the token in the UTF-8 encoded paragraph (byte offset).
+    pub start_byte: usize,
+
+    /// End of the token in the UTF-8 encoded paragraph (exclusive byte offset).
+    pub end_byte: usize,
+
+    /// Start of the token in UTF-16 code units, as used by JavaScript and LSP.
+    pub start_utf16: usize,
+
+    /// End of the token in UTF-16 code units (exclusive).
+    pub end_utf16: usize,
 }
 
 impl Token {
     /// Create a new token.
+    ///
+    /// The byte and UTF-16 offsets are set as if the token started the
+    /// paragraph; use [`Token::at_offsets`] to place it.
     pub fn new(token_type: TokenType, text: impl Into<String>, pos: usize) -> Self {
         let text = text.into();
         let token_len = text.chars().count();
+        let byte_len = text.len();
+        let utf16_len = text.encode_utf16().count();
         Self {
             token_type,
             text,
             token_len,
             pos,
+            start_byte: 0,
+            end_byte: byte_len,
+            start_utf16: 0,
+            end_utf16: utf16_len,
         }
     }
 
+    /// Move the token to the given byte and UTF-16 start offsets, keeping
+    /// its encoded lengths.
+    pub fn at_offsets(mut self, start_byte: usize, start_utf16: usize) -> Self {
+        self.end_byte = start_byte + (self.end_byte - self.start_byte);
+        self.end_utf16 = start_utf16 + (self.end_utf16 - self.start_utf16);
+        self.start_byte = start_byte;
+        self.start_utf16 = start_utf16;
+        self
+    }
+
     /// Create an empty `None` token at position 0, signaling end-of-text.
     pub fn none() -> Self {
         Self {
@@ -55,6 +86,10 @@ impl Token {
             text: String::new(),
             token_len: 0,
             pos: 0,
+            start_byte: 0,
+            end_byte: 0,
+            start_utf16: 0,
+            end_utf16: 0,
         }
     }
 }
@@ -82,24 +117,47 @@ pub struct Sentence {
     /// The type of sentence boundary detected.
     pub sentence_type: SentenceType,
 
-    /// Length of the sentence in characters.
+    /// Length of the sentence in characters. As detected, this includes
+    /// the whitespace up to the next sentence; see [`SentenceWhitespace`].
     pub sentence_len: usize,
+
+    /// Length of the sentence in characters without the whitespace at its
+    /// end (Rust-specific).
+    pub trimmed_len: usize,
 }
 
 impl Sentence {
-    /// Create a new sentence result.
+    /// Create a new sentence result with no whitespace at its end.
     pub fn new(sentence_type: SentenceType, sentence_len: usize) -> Self {
         Self {
             sentence_type,
             sentence_len,
+            trimmed_len: sentence_len,
         }
     }
 
     /// Create a `None` sentence (end of text / no sentence found).
     pub fn none() -> Self {
-        Self {
-            sentence_type: SentenceType::None,
-            sentence_len: 0,
+        Self::new(SentenceType::None, 0)
+    }
+
+    /// Set `trimmed_len` of `sentences`, consecutive from the start of
+    /// `text`, leaving out the whitespace at the end of each.
+    pub fn measure_trimmed(text: &str, sentences: &mut [Sentence]) {
+        let mut chars = text.chars();
+        for sentence in sentences {
+            let mut whitespace = 0;
+            for c in chars.by_ref().take(sentence.sentence_len) {
+                whitespace = if c.is_whitespace() { whitespace + 1 } else { 0 };
+            }
+            sentence.trimmed_len = sentence.sentence_len.saturating_sub(whitespace);
+        }
+    }
+
+    /// Apply `policy` to the length of the sentence.
+    pub fn apply(&mut self, policy: SentenceWhitespace) {
+        if policy == SentenceWhitespace::TrimEnd {
+            self.sentence_len = self.trimmed_len;
         }
     }
 }
@@ -110,6 +168,131 @@ impl Default for Sentence {
     }
 }
 
+/// Whether the reported sentences include the whitespace that follows
+/// them up to the next sentence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SentenceWhitespace {
+    /// A sentence runs up to the start of the next one, so the sentences
+    /// cover the whole text, as in libvoikko.
+    #[default]
+    IncludeTrailingWhitespace,
+    /// A sentence ends at its last character that is not whitespace, which
+    /// suits highlighting. The whitespace between sentences belongs to none.
+    TrimEnd,
+}
+
+// ---------------------------------------------------------------------------
+// SentenceSpan
+//
+// Rust-specific: libvoikko reports sentences only as type and length, so
+// callers had to walk the text to find where each one starts.
+// ---------------------------------------------------------------------------
+
+/// A detected sentence placed in the text it was detected in.
+///
+/// Offsets follow [`Token`]: characters, UTF-8 bytes and UTF-16 code units,
+/// each with an exclusive end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceSpan {
+    /// The type of sentence boundary detected.
+    pub sentence_type: SentenceType,
+
+    /// The text of the sentence, including trailing whitespace unless
+    /// trimmed with [`SentenceWhitespace::TrimEnd`].
+    pub text: String,
+
+    /// Start of the sentence in characters.
+    pub start: usize,
+
+    /// End of the sentence in characters (exclusive).
+    pub end: usize,
+
+    /// Start of the sentence in the UTF-8 encoded text (byte offset).
+    pub start_byte: usize,
+
+    /// End of the sentence in the UTF-8 encoded text (exclusive byte offset).
+    pub end_byte: usize,
+
+    /// Start of the sentence in UTF-16 code units.
+    pub start_utf16: usize,
+
+    /// End of the sentence in UTF-16 code units (exclusive).
+    pub end_utf16: usize,
+
+    /// End of the sentence without the whitespace at its end, in
+    /// characters (exclusive).
+    pub trimmed_end: usize,
+
+    /// End of the sentence without the whitespace at its end, in the UTF-8
+    /// encoded text (exclusive byte offset).
+    pub trimmed_end_byte: usize,
+
+    /// End of the sentence without the whitespace at its end, in UTF-16
+    /// code units (exclusive).
+    pub trimmed_end_utf16: usize,
+}
+
+impl SentenceSpan {
+    /// Place `sentences`, consecutive from the start of `text`, in `text`.
+    ///
+    /// The sentences are those detected, before any
+    /// [`SentenceWhitespace`] policy is applied. All offsets are computed
+    /// in one pass. A sentence running past the end of the text is cut at
+    /// the end.
+    pub fn locate(text: &str, sentences: &[Sentence]) -> Vec<SentenceSpan> {
+        let mut chars = text.chars();
+        let (mut pos, mut byte, mut utf16) = (0, 0, 0);
+        sentences
+            .iter()
+            .map(|sentence| {
+                let (start, start_byte, start_utf16) = (pos, byte, utf16);
+                let mut trimmed = (pos, byte, utf16);
+                for (i, c) in chars.by_ref().take(sentence.sentence_len).enumerate() {
+                    pos += 1;
+                    byte += c.len_utf8();
+                    utf16 += c.len_utf16();
+                    if i < sentence.trimmed_len {
+                        trimmed = (pos, byte, utf16);
+                    }
+                }
+                SentenceSpan {
+                    sentence_type: sentence.sentence_type,
+                    text: text[start_byte..byte].to_string(),
+                    start,
+                    end: pos,
+                    start_byte,
+                    end_byte: byte,
+                    start_utf16,
+                    end_utf16: utf16,
+                    trimmed_end: trimmed.0,
+                    trimmed_end_byte: trimmed.1,
+                    trimmed_end_utf16: trimmed.2,
+                }
+            })
+            .collect()
+    }
+
+    /// Apply `policy` to the end and the text of the sentence.
+    pub fn apply(&mut self, policy: SentenceWhitespace) {
+        if policy == SentenceWhitespace::TrimEnd {
+            self.end = self.trimmed_end;
+            self.end_byte = self.trimmed_end_byte;
+            self.end_utf16 = self.trimmed_end_utf16;
+            self.text.truncate(self.end_byte - self.start_byte);
+        }
+    }
+
+    /// Length of the sentence in characters.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Return `true` if the sentence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +322,17 @@ mod tests {
         // "äiti" is 4 characters, 5 bytes in UTF-8
         let tok = Token::new(TokenType::Word, "\u{00E4}iti", 0);
         assert_eq!(tok.token_len, 4); // character count, not byte count
+        assert_eq!(tok.end_byte, 5);
+        assert_eq!(tok.end_utf16, 4);
+    }
+
+    #[test]
+    fn token_at_offsets() {
+        // U+1D7CE is one character, four UTF-8 bytes and two UTF-16 units
+        let tok = Token::new(TokenType::Word, "a\u{1D7CE}", 3).at_offsets(4, 3);
+        assert_eq!(tok.pos, 3);
+        assert_eq!((tok.start_byte, tok.end_byte), (4, 9));
+        assert_eq!((tok.start_utf16, tok.end_utf16), (3, 6));
     }
 
     #[test]
@@ -185,6 +379,63 @@ mod tests {
         assert_eq!(s.sentence_type, SentenceType::None);
     }
 
+    #[test]
+    fn sentence_spans_have_all_offsets() {
+        // U+1D7CE is one character, four UTF-8 bytes and two UTF-16 units
+        let text = "Pii on \u{1D7CE}. Äiti";
+        let sentences = [
+            Sentence::new(SentenceType::Probable, 10),
+            Sentence::new(SentenceType::None, 4),
+        ];
+        let spans = SentenceSpan::locate(text, &sentences);
+        assert_eq!(spans[0].text, "Pii on \u{1D7CE}. ");
+        assert_eq!((spans[0].start, spans[0].end), (0, 10));
+        assert_eq!((spans[0].start_byte, spans[0].end_byte), (0, 13));
+        assert_eq!((spans[0].start_utf16, spans[0].end_utf16), (0, 11));
+        assert_eq!(spans[1].sentence_type, SentenceType::None);
+        assert_eq!(spans[1].text, "Äiti");
+        assert_eq!((spans[1].start, spans[1].end), (10, 14));
+        assert_eq!((spans[1].start_byte, spans[1].end_byte), (13, 18));
+        assert_eq!((spans[1].start_utf16, spans[1].end_utf16), (11, 15));
+        assert_eq!(&text[spans[1].start_byte..spans[1].end_byte], "Äiti");
+    }
+
+    #[test]
+    fn sentence_span_is_cut_at_end_of_text() {
+        let spans = SentenceSpan::locate("Koira.", &[Sentence::new(SentenceType::None, 10)]);
+        assert_eq!((spans[0].text.as_str(), spans[0].len()), ("Koira.", 6));
+        assert!(SentenceSpan::locate("", &[Sentence::none()])[0].is_empty());
+    }
+
+    #[test]
+    fn trim_end_leaves_out_whitespace_between_sentences() {
+        let text = "Pii on \u{1D7CE}.\n  Äiti ";
+        let mut sentences = [
+            Sentence::new(SentenceType::Probable, 12),
+            Sentence::new(SentenceType::None, 5),
+        ];
+        Sentence::measure_trimmed(text, &mut sentences);
+        assert_eq!(sentences[0].trimmed_len, 9);
+        assert_eq!(sentences[1].trimmed_len, 4);
+
+        let mut spans = SentenceSpan::locate(text, &sentences);
+        assert_eq!(spans[0].trimmed_end, 9);
+        assert_eq!(spans[0].trimmed_end_byte, 12);
+        assert_eq!(spans[0].trimmed_end_utf16, 10);
+        spans[0].apply(SentenceWhitespace::TrimEnd);
+        spans[1].apply(SentenceWhitespace::TrimEnd);
+        assert_eq!(
+            (spans[0].text.as_str(), spans[0].end),
+            ("Pii on \u{1D7CE}.", 9)
+        );
+        assert_eq!((spans[1].text.as_str(), spans[1].start), ("Äiti", 12));
+
+        sentences[0].apply(SentenceWhitespace::IncludeTrailingWhitespace);
+        assert_eq!(sentences[0].sentence_len, 12);
+        sentences[0].apply(SentenceWhitespace::TrimEnd);
+        assert_eq!(sentences[0].sentence_len, 9);
+    }
+
     #[test]
     fn sentence_clone() {
         let s = Sentence::new(SentenceType::Possible, 15);
@@ -0,0 +1,55 @@
+// Generates the C header for the FFI functions and structs into
+// `$OUT_DIR/voikko_ffi.h`. The checked-in `include/voikko_ffi.h` is compared
+// against it by `tests/header.rs`.
+//
+// Functions defined through `bool_setter!` are invisible to cbindgen, which
+// does not expand macros, so their declarations are added here.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the C header")
+        .write(&mut header);
+    let header = String::from_utf8(header).unwrap();
+
+    // The setters go at the end of the `extern "C"` block.
+    let source = fs::read_to_string(crate_dir.join("src/lib.rs")).unwrap();
+    let end = header
+        .rfind("#ifdef __cplusplus")
+        .expect("header has no extern \"C\" block");
+    let header = format!(
+        "{}{}\n{}",
+        &header[..end],
+        setter_declarations(&source),
+        &header[end..]
+    );
+    fs::write(out_dir.join("voikko_ffi.h"), header).unwrap();
+}
+
+/// Declarations of the functions defined with `bool_setter!`.
+fn setter_declarations(source: &str) -> String {
+    let mut out =
+        String::from("/* Boolean option setters: value 0 is false, anything else true. */\n");
+    for invocation in source.split("\nbool_setter!(").skip(1) {
+        let name = invocation
+            .trim_start()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .next()
+            .unwrap();
+        out.push_str(&format!("void {name}(VoikkoHandle *handle, int value);\n"));
+    }
+    out
+}
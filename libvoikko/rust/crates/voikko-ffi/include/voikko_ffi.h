@@ -0,0 +1,1051 @@
+/* voikko_ffi.h — generated by cbindgen from src/lib.rs; do not edit.
+ *
+ * Regenerate with: UPDATE_FFI_HEADER=1 cargo test -p voikko-ffi --test header
+ * Memory rules and an overview are in voikko.h, which includes this file.
+ */
+
+#ifndef VOIKKO_FFI_H
+#define VOIKKO_FFI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct VoikkoHandle VoikkoHandle;
+
+/**
+ * Returned by the `voikko_try_*` functions on success.
+ */
+#define VOIKKO_OK 0
+
+/**
+ * A NULL handle or argument, or a string that is not valid UTF-8.
+ */
+#define VOIKKO_ERROR_INVALID_ARGUMENT -1
+
+/**
+ * The word is longer than the maximum word length.
+ */
+#define VOIKKO_ERROR_WORD_TOO_LONG -2
+
+/**
+ * The operation was cancelled.
+ */
+#define VOIKKO_ERROR_CANCELLED -3
+
+/**
+ * The operation did not finish within its time limit.
+ */
+#define VOIKKO_ERROR_TIMED_OUT -4
+
+/**
+ * The dictionary of a handle created by `voikko_new_lazy` could not be
+ * built.
+ */
+#define VOIKKO_ERROR_DICTIONARY -5
+
+/**
+ * Any other error.
+ */
+#define VOIKKO_ERROR_OTHER -6
+
+/**
+ * Version of the C ABI: the function signatures and the layout of the
+ * structs. It is incremented whenever either changes incompatibly, so a
+ * binding can refuse to load a library it was not written for.
+ */
+#define VOIKKO_ABI_VERSION 3
+
+/**
+ * Opaque analysis result.
+ */
+typedef struct {
+  /**
+   * NULL-terminated array of attribute keys (C strings).
+   */
+  char **keys;
+  /**
+   * NULL-terminated array of attribute values (C strings), parallel to keys.
+   */
+  char **values;
+} VoikkoAnalysis;
+
+/**
+ * Opaque analysis array result.
+ */
+typedef struct {
+  VoikkoAnalysis *analyses;
+  size_t count;
+} VoikkoAnalysisArray;
+
+/**
+ * An analysis in a `VoikkoAnalysisArena`: a range of its `attributes`.
+ */
+typedef struct {
+  /**
+   * Index of the first attribute of the analysis.
+   */
+  size_t first_attribute;
+  /**
+   * Number of attributes of the analysis.
+   */
+  size_t attribute_count;
+} VoikkoArenaAnalysis;
+
+/**
+ * An attribute in a `VoikkoAnalysisArena`: byte offsets of its key and
+ * value in the arena's `strings`.
+ */
+typedef struct {
+  size_t key;
+  size_t value;
+} VoikkoArenaAttribute;
+
+/**
+ * Analyses of a word in a single allocation.
+ *
+ * The analysis table, the attribute table and the strings are parts of
+ * one memory block, freed with one `voikko_free_analysis_arena` call.
+ * Keys and values are NUL-terminated UTF-8 strings at `strings + offset`;
+ * keys are stored once per arena.
+ */
+typedef struct {
+  /**
+   * `count` analyses; also the start of the block.
+   */
+  const VoikkoArenaAnalysis *analyses;
+  size_t count;
+  /**
+   * The attributes of all analyses, grouped by analysis.
+   */
+  const VoikkoArenaAttribute *attributes;
+  /**
+   * The key and value strings.
+   */
+  const char *strings;
+  /**
+   * Size of the block in bytes.
+   */
+  size_t size;
+} VoikkoAnalysisArena;
+
+/**
+ * Replace `len` characters at `start` with `text`. Offsets are in
+ * characters of the checked text, like `start_pos`.
+ */
+typedef struct {
+  size_t start;
+  size_t len;
+  char *text;
+} VoikkoReplacement;
+
+/**
+ * Grammar error returned by FFI.
+ */
+typedef struct {
+  int error_code;
+  size_t start_pos;
+  size_t error_len;
+  char *short_description;
+  char **suggestions;
+  /**
+   * Severity: 0=Error, 1=Warning
+   */
+  int severity;
+  /**
+   * The edit that applies each suggestion, in the order of
+   * `suggestions`. NULL when `replacement_count` is 0.
+   */
+  VoikkoReplacement *replacements;
+  size_t replacement_count;
+} VoikkoGrammarError;
+
+/**
+ * Grammar error array.
+ */
+typedef struct {
+  VoikkoGrammarError *errors;
+  size_t count;
+} VoikkoGrammarErrorArray;
+
+/**
+ * Token returned by FFI.
+ */
+typedef struct {
+  /**
+   * Token type: 1=Word, 2=Punctuation, 3=Whitespace, 4=Unknown, 0=None
+   */
+  int token_type;
+  char *text;
+  /**
+   * Start offset in characters.
+   */
+  size_t position;
+  /**
+   * Byte range in the UTF-8 input (end exclusive).
+   */
+  size_t start_byte;
+  size_t end_byte;
+  /**
+   * Range in UTF-16 code units (end exclusive).
+   */
+  size_t start_utf16;
+  size_t end_utf16;
+} VoikkoToken;
+
+/**
+ * Token array.
+ */
+typedef struct {
+  VoikkoToken *tokens;
+  size_t count;
+} VoikkoTokenArray;
+
+/**
+ * Sentence returned by FFI.
+ */
+typedef struct {
+  /**
+   * Sentence type: 0=None, 1=NoStart, 2=Probable, 3=Possible
+   */
+  int sentence_type;
+  /**
+   * Length in characters, with or without the whitespace that follows
+   * as set with `voikko_set_sentence_whitespace`.
+   */
+  size_t sentence_len;
+  /**
+   * Length in characters without the whitespace that follows.
+   */
+  size_t trimmed_len;
+} VoikkoSentence;
+
+/**
+ * Sentence array.
+ */
+typedef struct {
+  VoikkoSentence *sentences;
+  size_t count;
+} VoikkoSentenceArray;
+
+/**
+ * Spell check callback for `voikko_sentences_with_spell_check`.
+ *
+ * Receives a NUL-terminated UTF-8 word including its trailing dot and the
+ * caller's `user_data`. Returns non-zero if the dot belongs to the word.
+ */
+typedef int (*VoikkoSpellCheckCallback)(const char *word, void *user_data);
+
+/**
+ * Sentence with its text and offsets, returned by FFI.
+ */
+typedef struct {
+  /**
+   * Sentence type: 0=None, 1=NoStart, 2=Probable, 3=Possible
+   */
+  int sentence_type;
+  char *text;
+  /**
+   * Character range (end exclusive).
+   */
+  size_t start;
+  size_t end;
+  /**
+   * Byte range in the UTF-8 input (end exclusive).
+   */
+  size_t start_byte;
+  size_t end_byte;
+  /**
+   * Range in UTF-16 code units (end exclusive).
+   */
+  size_t start_utf16;
+  size_t end_utf16;
+  /**
+   * Ends without the whitespace that follows the sentence, in
+   * characters, UTF-8 bytes and UTF-16 code units (exclusive).
+   */
+  size_t trimmed_end;
+  size_t trimmed_end_byte;
+  size_t trimmed_end_utf16;
+} VoikkoSentenceSpan;
+
+/**
+ * Sentence span array.
+ */
+typedef struct {
+  VoikkoSentenceSpan *spans;
+  size_t count;
+} VoikkoSentenceSpanArray;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a new Voikko handle from raw dictionary data.
+ *
+ * - `mor_data` + `mor_len`: contents of `mor.vfst` (required)
+ * - `autocorr_data` + `autocorr_len`: contents of `autocorr.vfst` (optional, NULL to skip)
+ *
+ * Returns an opaque pointer on success, NULL on failure.
+ * On failure, if `error_out` is non-NULL, it receives a heap-allocated error string
+ * that the caller must free with `voikko_free_str`.
+ */
+VoikkoHandle *voikko_new(const uint8_t *mor_data,
+                         size_t mor_len,
+                         const uint8_t *autocorr_data,
+                         size_t autocorr_len,
+                         char **error_out);
+
+/**
+ * Create a Voikko handle that defers building the morphology transducer.
+ *
+ * Same arguments as `voikko_new`, but only the `mor.vfst` header is
+ * validated, so it returns quickly. Call `voikko_warm_up` (e.g. on a
+ * background thread) to build the transducer before the first check.
+ */
+VoikkoHandle *voikko_new_lazy(const uint8_t *mor_data,
+                              size_t mor_len,
+                              const uint8_t *autocorr_data,
+                              size_t autocorr_len,
+                              char **error_out);
+
+/**
+ * Build the morphology transducer of a handle created by `voikko_new_lazy`.
+ *
+ * May be called from another thread while the handle is in use.
+ * Returns 0 on success, -1 on error; on failure, if `error_out` is
+ * non-NULL, it receives an error string to free with `voikko_free_str`.
+ */
+int voikko_warm_up(const VoikkoHandle *handle, char **error_out);
+
+/**
+ * Spell and analyze `count` words so that the first checks of them are
+ * answered from the caches, building the transducer of a lazily created
+ * handle first. Meant for application startup.
+ *
+ * `words` points to `count` C strings; NULL and invalid UTF-8 words are
+ * skipped. With `threads` above 1, the words are split among that many
+ * threads. Returns 0 on success, -1 on error; on failure, if `error_out`
+ * is non-NULL, it receives an error string to free with `voikko_free_str`.
+ */
+int voikko_warm_up_words(const VoikkoHandle *handle,
+                         const char *const *words,
+                         size_t count,
+                         size_t threads,
+                         char **error_out);
+
+/**
+ * `voikko_warm_up_words` with a built-in list of frequent Finnish word
+ * forms.
+ */
+int voikko_warm_up_default(const VoikkoHandle *handle, size_t threads, char **error_out);
+
+/**
+ * Return the load state of the morphology transducer:
+ * 0 = pending, 1 = ready, 2 = failed, -1 if the handle is NULL.
+ */
+int voikko_load_state(const VoikkoHandle *handle);
+
+/**
+ * Replace the dictionary of a handle with new `mor.vfst` and optional
+ * `autocorr.vfst` data (NULL to drop the autocorrect transducer).
+ *
+ * May be called from another thread while the handle is in use: calls
+ * already running finish with the old dictionary. Options are kept and
+ * the caches are emptied. Returns 0 on success, -1 on error, in which
+ * case the old dictionary stays; on failure, if `error_out` is non-NULL,
+ * it receives an error string to free with `voikko_free_str`.
+ */
+int voikko_reload(const VoikkoHandle *handle,
+                  const uint8_t *mor_data,
+                  size_t mor_len,
+                  const uint8_t *autocorr_data,
+                  size_t autocorr_len,
+                  char **error_out);
+
+/**
+ * Read the number of dictionary lookups cut short by the traversal budget
+ * since the handle was created or the counters were reset: by the step
+ * limit (the `MAX_LOOP_COUNT` safety limit by default), the output limit
+ * and the deadline. NULL output pointers are skipped. Returns 0, or -1 if
+ * the handle is NULL.
+ */
+int voikko_traversal_stats(const VoikkoHandle *handle,
+                           uint64_t *steps_out,
+                           uint64_t *outputs_out,
+                           uint64_t *deadline_out);
+
+/**
+ * List the most recent words whose lookup was cut short by the traversal
+ * budget, oldest first. Empty unless enabled with
+ * `voikko_set_truncation_diagnostics`.
+ *
+ * Returns a NULL-terminated array of C strings. Caller must free with
+ * `voikko_free_str_array`.
+ */
+char **voikko_truncated_lookups(const VoikkoHandle *handle);
+
+/**
+ * Reset the traversal counters and discard the recorded words.
+ */
+void voikko_reset_traversal_stats(const VoikkoHandle *handle);
+
+/**
+ * Free a VoikkoHandle created by `voikko_new` or `voikko_new_lazy`.
+ */
+void voikko_free(VoikkoHandle *handle);
+
+/**
+ * Check whether a word is correctly spelled.
+ * Returns 1 for correct, 0 for incorrect, -1 on error.
+ */
+int voikko_spell(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Spell check a word, telling a misspelled word from one too long to check.
+ * Returns 1 for correct, 0 for misspelled, 2 for a word longer than the
+ * maximum word length, 3 for a word rejected after a dictionary lookup ran
+ * out of its traversal budget, -1 on error.
+ */
+int voikko_spell_status(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Generate spelling suggestions.
+ *
+ * Returns a NULL-terminated array of C strings. Caller must free with
+ * `voikko_free_str_array`. Returns NULL on error.
+ */
+char **voikko_suggest(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Generate spelling suggestions within a cost and time budget.
+ *
+ * `max_cost` limits the number of spell checks (0 or less uses the default
+ * budget); `max_millis` limits the time in milliseconds (0 or less for no
+ * limit). Returns the suggestions found before the budget ran out, as a
+ * NULL-terminated array the caller must free with `voikko_free_str_array`.
+ * Returns NULL on error.
+ */
+char **voikko_suggest_with_budget(const VoikkoHandle *handle,
+                                  const char *word,
+                                  int max_cost,
+                                  int max_millis);
+
+/**
+ * Check the spelling of a word and suggest corrections if it is misspelled.
+ *
+ * Returns 1 for correct, 0 for incorrect, -1 on error. If `suggestions_out`
+ * is non-NULL, it receives NULL for a correct word and otherwise a
+ * NULL-terminated array of suggestions that the caller must free with
+ * `voikko_free_str_array`.
+ */
+int voikko_check_word(const VoikkoHandle *handle, const char *word, char ***suggestions_out);
+
+/**
+ * Check the spelling of `count` words in one call.
+ *
+ * `words` points to `count` C strings. `results_out` must point to space
+ * for `count` ints and receives, per word, 1 for correct, 0 for incorrect
+ * or -1 if the word is NULL or not valid UTF-8. Large batches are checked
+ * on several threads. Returns 0 on success, -1 on error.
+ */
+int voikko_spell_batch(const VoikkoHandle *handle,
+                       const char *const *words,
+                       size_t count,
+                       int *results_out);
+
+/**
+ * Generate suggestions for `count` words in one call.
+ *
+ * `words` points to `count` C strings. `results_out` must point to space
+ * for `count` pointers and receives, per word, a NULL-terminated string
+ * array like `voikko_suggest` returns (NULL if the word is NULL or not
+ * valid UTF-8). Free each array with `voikko_free_str_array`. Large
+ * batches are processed on several threads. Returns 0 on success, -1 on
+ * error.
+ */
+int voikko_suggest_batch(const VoikkoHandle *handle,
+                         const char *const *words,
+                         size_t count,
+                         char ***results_out);
+
+/**
+ * Perform morphological analysis.
+ *
+ * Returns a heap-allocated `VoikkoAnalysisArray`. Caller must free with
+ * `voikko_free_analyses`. Returns a struct with count=0 on error.
+ */
+VoikkoAnalysisArray voikko_analyze(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Perform morphological analysis like `voikko_analyze`, returning the
+ * result in one allocation instead of two string arrays per analysis.
+ *
+ * Caller must free with `voikko_free_analysis_arena`. Returns a struct
+ * with count=0 on error.
+ */
+VoikkoAnalysisArena voikko_analyze_arena(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Free an arena returned by `voikko_analyze_arena`.
+ */
+void voikko_free_analysis_arena(VoikkoAnalysisArena arena);
+
+/**
+ * Get the values of one analysis attribute (e.g. "BASEFORM" or "CLASS")
+ * across the analyses of a word, without duplicates.
+ *
+ * Cheaper than `voikko_analyze` when only one attribute is needed: the
+ * full-morphology attributes are computed only when `key` is one of them.
+ * Returns a NULL-terminated array; caller must free with
+ * `voikko_free_str_array`. Returns NULL on error.
+ */
+char **voikko_analyze_attribute(const VoikkoHandle *handle, const char *word, const char *key);
+
+/**
+ * Free an analysis array returned by `voikko_analyze`.
+ */
+void voikko_free_analyses(VoikkoAnalysisArray arr);
+
+/**
+ * Generate the forms of `baseform` with the given attributes.
+ *
+ * `keys` and `values` point to `count` attribute names and values, e.g.
+ * SIJAMUOTO=omanto and NUMBER=plural for the genitive plural. Returns a
+ * NULL-terminated array of forms; caller must free with
+ * `voikko_free_str_array`. Returns NULL on error.
+ */
+char **voikko_generate(const VoikkoHandle *handle,
+                       const char *baseform,
+                       const char *const *keys,
+                       const char *const *values,
+                       size_t count);
+
+/**
+ * Complete `prefix` to at most `max` dictionary words, sorted
+ * alphabetically.
+ *
+ * Returns a NULL-terminated array of words; caller must free with
+ * `voikko_free_str_array`. Returns NULL on error.
+ */
+char **voikko_complete(const VoikkoHandle *handle, const char *prefix, size_t max);
+
+/**
+ * Get the hyphenation pattern for a word.
+ *
+ * Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+ */
+char *voikko_hyphenate(const VoikkoHandle *handle, const char *word);
+
+/**
+ * Insert hyphens with a custom separator.
+ *
+ * Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+ */
+char *voikko_insert_hyphens(const VoikkoHandle *handle,
+                            const char *word,
+                            const char *separator,
+                            int allow_context_changes);
+
+/**
+ * Check text for grammar errors (multi-paragraph, splits at newlines).
+ *
+ * Returns a `VoikkoGrammarErrorArray`. Caller must free with `voikko_free_grammar_errors`.
+ */
+VoikkoGrammarErrorArray voikko_grammar_errors(const VoikkoHandle *handle,
+                                              const char *text,
+                                              const char *language);
+
+/**
+ * Free a grammar error array.
+ */
+void voikko_free_grammar_errors(VoikkoGrammarErrorArray arr);
+
+/**
+ * Spell check a word, reporting why it could not be checked.
+ *
+ * On success returns `VOIKKO_OK` and, if `correct_out` is non-NULL, stores
+ * 1 for a correct and 0 for a misspelled word in it. Otherwise returns one
+ * of the `VOIKKO_ERROR_*` codes and, if `error_out` is non-NULL, stores an
+ * error string in it that the caller must free with `voikko_free_str`.
+ */
+int voikko_try_spell(const VoikkoHandle *handle,
+                     const char *word,
+                     int *correct_out,
+                     char **error_out);
+
+/**
+ * Generate spelling suggestions, reporting why none could be generated.
+ *
+ * On success returns `VOIKKO_OK` and, if `suggestions_out` is non-NULL,
+ * stores a NULL-terminated array in it that the caller must free with
+ * `voikko_free_str_array`. Errors are reported as in `voikko_try_spell`.
+ */
+int voikko_try_suggest(const VoikkoHandle *handle,
+                       const char *word,
+                       char ***suggestions_out,
+                       char **error_out);
+
+/**
+ * Generate spelling suggestions within a cost and time budget, as in
+ * `voikko_suggest_with_budget`, but return `VOIKKO_ERROR_TIMED_OUT`
+ * instead of the suggestions found if the time limit is reached. Results
+ * and errors are reported as in `voikko_try_suggest`.
+ */
+int voikko_try_suggest_with_budget(const VoikkoHandle *handle,
+                                   const char *word,
+                                   int max_cost,
+                                   int max_millis,
+                                   char ***suggestions_out,
+                                   char **error_out);
+
+/**
+ * Analyze a word, reporting why it could not be analyzed.
+ *
+ * On success returns `VOIKKO_OK` and, if `analyses_out` is non-NULL,
+ * stores the analyses in it; the caller must free them with
+ * `voikko_free_analyses`. Errors are reported as in `voikko_try_spell`.
+ */
+int voikko_try_analyze(const VoikkoHandle *handle,
+                       const char *word,
+                       VoikkoAnalysisArray *analyses_out,
+                       char **error_out);
+
+/**
+ * Get the hyphenation pattern of a word, reporting why it could not be
+ * hyphenated.
+ *
+ * On success returns `VOIKKO_OK` and, if `pattern_out` is non-NULL, stores
+ * the pattern in it; the caller must free it with `voikko_free_str`.
+ * Errors are reported as in `voikko_try_spell`.
+ */
+int voikko_try_hyphenate(const VoikkoHandle *handle,
+                         const char *word,
+                         char **pattern_out,
+                         char **error_out);
+
+/**
+ * Check text for grammar errors as in `voikko_grammar_errors`, reporting
+ * why it could not be checked.
+ *
+ * On success returns `VOIKKO_OK` and, if `errors_out` is non-NULL, stores
+ * the errors in it; the caller must free them with
+ * `voikko_free_grammar_errors`. Errors are reported as in
+ * `voikko_try_spell`.
+ */
+int voikko_try_grammar_errors(const VoikkoHandle *handle,
+                              const char *text,
+                              const char *language,
+                              VoikkoGrammarErrorArray *errors_out,
+                              char **error_out);
+
+/**
+ * Tokenize text.
+ *
+ * Returns a `VoikkoTokenArray`. Caller must free with `voikko_free_tokens`.
+ */
+VoikkoTokenArray voikko_tokens(const VoikkoHandle *handle, const char *text);
+
+/**
+ * Free a token array.
+ */
+void voikko_free_tokens(VoikkoTokenArray arr);
+
+/**
+ * Detect sentence boundaries.
+ *
+ * Returns a `VoikkoSentenceArray`. Caller must free with `voikko_free_sentences`.
+ */
+VoikkoSentenceArray voikko_sentences(const VoikkoHandle *handle, const char *text);
+
+/**
+ * Detect sentence boundaries using a custom spell check callback for
+ * abbreviation detection.
+ *
+ * Returns a `VoikkoSentenceArray`. Caller must free with `voikko_free_sentences`.
+ */
+VoikkoSentenceArray voikko_sentences_with_spell_check(const VoikkoHandle *handle,
+                                                      const char *text,
+                                                      VoikkoSpellCheckCallback spell_check,
+                                                      void *user_data);
+
+/**
+ * Free a sentence array.
+ */
+void voikko_free_sentences(VoikkoSentenceArray arr);
+
+/**
+ * Detect sentences with their text and offsets.
+ *
+ * Returns a `VoikkoSentenceSpanArray`. Caller must free with
+ * `voikko_free_sentence_spans`.
+ */
+VoikkoSentenceSpanArray voikko_sentence_spans(const VoikkoHandle *handle, const char *text);
+
+/**
+ * Free a sentence span array.
+ */
+void voikko_free_sentence_spans(VoikkoSentenceSpanArray arr);
+
+void voikko_set_min_hyphenated_word_length(VoikkoHandle *handle, int value);
+
+void voikko_set_max_compound_parts(VoikkoHandle *handle, int value);
+
+void voikko_set_max_compound_chars(VoikkoHandle *handle, int value);
+
+void voikko_set_max_suggestions(VoikkoHandle *handle, int value);
+
+/**
+ * Set the maximum word length in characters (at most 255, the default).
+ */
+void voikko_set_max_word_chars(VoikkoHandle *handle, int value);
+
+/**
+ * Set the coefficients of the suggestion ranking costs: edit distance,
+ * weighted transducer path weight and morphological priority.
+ */
+void voikko_set_suggestion_ranking(VoikkoHandle *handle, float edit, float fst, float morph);
+
+/**
+ * Set which typographic character classes are mapped to plain characters
+ * before spell checking and analysis (nonzero = enabled).
+ */
+void voikko_set_input_mapping(VoikkoHandle *handle,
+                              int superscripts,
+                              int subscripts,
+                              int fractions,
+                              int typographic_digits);
+
+/**
+ * Set whether zero-width and control characters are removed before
+ * tokenization and checking (nonzero = strip). Positions still refer to
+ * the original text.
+ */
+void voikko_set_strip_invisible_chars(VoikkoHandle *handle, int value);
+
+/**
+ * Set whether reported sentences include the whitespace that follows
+ * them up to the next sentence (zero, the default) or end at their last
+ * non-whitespace character (nonzero). Affects `sentence_len` of
+ * `voikko_sentences` and the ends and text of `voikko_sentence_spans`.
+ */
+void voikko_set_sentence_whitespace(VoikkoHandle *handle, int trim_end);
+
+/**
+ * Set whether the number format check expects technical conventions
+ * (nonzero: "12:30", leading zeros and ISO dates) instead of standard
+ * ones (zero: "klo 12.30", "1.2.2024").
+ */
+void voikko_set_technical_number_style(VoikkoHandle *handle, int value);
+
+/**
+ * Set the severity reported for grammar errors with `code` (0=Error,
+ * 1=Warning). A negative severity restores the code's default; other
+ * values are ignored.
+ */
+void voikko_set_grammar_error_severity(VoikkoHandle *handle, int code, int severity);
+
+void voikko_set_speller_cache_size(VoikkoHandle *handle, int value);
+
+/**
+ * Set how many words the analysis cache holds; 0 or a negative value
+ * disables it.
+ */
+void voikko_set_analysis_cache_size(VoikkoHandle *handle, int value);
+
+/**
+ * Load a JSON grammar rule pack. The rules are checked in addition to the
+ * built-in grammar checks.
+ *
+ * Returns the number of rules loaded, or -1 if the pack is invalid or an
+ * argument is NULL. If the pack is invalid and `error_out` is non-NULL, it
+ * receives a heap-allocated error string that the caller must free with
+ * `voikko_free_str`.
+ */
+int voikko_load_grammar_rules(VoikkoHandle *handle, const char *json, char **error_out);
+
+/**
+ * Remove all grammar rules loaded with `voikko_load_grammar_rules`.
+ */
+void voikko_clear_grammar_rules(VoikkoHandle *handle);
+
+/**
+ * Add the entries of a message catalog file to the handle's catalog of
+ * grammar error descriptions and message templates. The file has
+ * `[language]` sections with `code = description` and `key = template`
+ * lines; see `voikko_format_message`.
+ *
+ * Returns the number of entries loaded, or -1 if the file is invalid or an
+ * argument is NULL. If the file is invalid and `error_out` is non-NULL, it
+ * receives a heap-allocated error string that the caller must free with
+ * `voikko_free_str`.
+ */
+int voikko_load_message_catalog(VoikkoHandle *handle, const char *text, char **error_out);
+
+/**
+ * Get the short description of grammar error `code` in `language` (e.g.
+ * "fi", "en-GB") from the handle's message catalog. A language without a
+ * description falls back to its primary subtag, then to English.
+ *
+ * Returns a heap-allocated C string the caller must free with
+ * `voikko_free_str`, or NULL if there is no description.
+ */
+char *voikko_error_description(const VoikkoHandle *handle, int code, const char *language);
+
+/**
+ * Format the message template `key` (e.g. "did_you_mean") in `language`
+ * from the handle's message catalog, replacing `{0}`, `{1}`, ... with the
+ * `arg_count` strings of `args`.
+ *
+ * Returns a heap-allocated C string the caller must free with
+ * `voikko_free_str`, or NULL if there is no such template or an argument
+ * is NULL or not valid UTF-8.
+ */
+char *voikko_format_message(const VoikkoHandle *handle,
+                            const char *key,
+                            const char *language,
+                            const char *const *args,
+                            size_t arg_count);
+
+/**
+ * Add an autocorrect rule replacing `from` with `to`. `from` is literal
+ * text or a pattern between slashes, such as `/(\d+)e/` with the
+ * replacement `$1 €`. User rules are checked before the autocorrect
+ * transducer; a rule with the same `from` is replaced.
+ *
+ * Returns 0 on success, or -1 if the rule is invalid or an argument is
+ * NULL. If the rule is invalid and `error_out` is non-NULL, it receives a
+ * heap-allocated error string that the caller must free with
+ * `voikko_free_str`.
+ */
+int voikko_add_autocorrect_rule(VoikkoHandle *handle,
+                                const char *from,
+                                const char *to,
+                                char **error_out);
+
+/**
+ * Remove the autocorrect rule for `from`.
+ *
+ * Returns 1 if the rule was removed, 0 if there was none, -1 on error.
+ */
+int voikko_remove_autocorrect_rule(VoikkoHandle *handle, const char *from);
+
+/**
+ * Remove all user autocorrect rules.
+ */
+void voikko_clear_autocorrect_rules(VoikkoHandle *handle);
+
+/**
+ * Load a hyphenation exception list: one word per line with its
+ * hyphenation points marked, e.g. `Voik-ko`. Listed words are hyphenated
+ * as given instead of by analysis.
+ *
+ * Returns the number of entries loaded, or -1 if the list is invalid or an
+ * argument is NULL. If the list is invalid and `error_out` is non-NULL, it
+ * receives a heap-allocated error string that the caller must free with
+ * `voikko_free_str`.
+ */
+int voikko_load_hyphenation_exceptions(VoikkoHandle *handle, const char *text, char **error_out);
+
+/**
+ * Remove all hyphenation exceptions.
+ */
+void voikko_clear_hyphenation_exceptions(VoikkoHandle *handle);
+
+/**
+ * Add a word to the user dictionary. `hint` is an optional inflection class
+ * hint (a dictionary word that inflects the same way) and may be NULL.
+ *
+ * Returns 1 on success, -1 if the handle or word is NULL.
+ */
+int voikko_add_user_word(VoikkoHandle *handle, const char *word, const char *hint);
+
+/**
+ * Remove a word from the user dictionary.
+ *
+ * Returns 1 if the word was removed, 0 if it was not present, -1 on error.
+ */
+int voikko_remove_user_word(VoikkoHandle *handle, const char *word);
+
+/**
+ * List the words in the user dictionary, in sorted order.
+ *
+ * Returns a NULL-terminated array of C strings. Caller must free with
+ * `voikko_free_str_array`.
+ */
+char **voikko_user_words(const VoikkoHandle *handle);
+
+/**
+ * Serialize the user dictionary into its text file format: one word per
+ * line, optionally followed by a tab and the inflection class hint.
+ *
+ * Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+ */
+char *voikko_export_user_dictionary(const VoikkoHandle *handle);
+
+/**
+ * Add the entries of a user dictionary file to the user dictionary.
+ *
+ * Returns the number of entries in the file, or -1 if the file is invalid
+ * or an argument is NULL. If the file is invalid and `error_out` is
+ * non-NULL, it receives a heap-allocated error string that the caller must
+ * free with `voikko_free_str`.
+ */
+int voikko_load_user_dictionary(VoikkoHandle *handle, const char *text, char **error_out);
+
+/**
+ * Export the user state (user dictionary, ignore list, accepted corrections)
+ * as a UTF-8 text blob.
+ *
+ * Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+ */
+char *voikko_export_user_state(const VoikkoHandle *handle);
+
+/**
+ * Replace the user state with a blob produced by `voikko_export_user_state`.
+ *
+ * Returns 1 on success, 0 if the blob is invalid, -1 on error.
+ * If the blob is invalid and `error_out` is non-NULL, it receives a
+ * heap-allocated error string that the caller must free with `voikko_free_str`.
+ */
+int voikko_import_user_state(VoikkoHandle *handle, const char *blob, char **error_out);
+
+/**
+ * Return `VOIKKO_ABI_VERSION` of the loaded library.
+ */
+int voikko_abi_version(void);
+
+/**
+ * Return `sizeof(VoikkoAnalysis)` as compiled into the library.
+ *
+ * The `voikko_sizeof_*` functions let bindings that declare the structs
+ * themselves (ctypes, P/Invoke) check their layouts at load time.
+ */
+size_t voikko_sizeof_analysis(void);
+
+/**
+ * Return `sizeof(VoikkoAnalysisArray)` as compiled into the library.
+ */
+size_t voikko_sizeof_analysis_array(void);
+
+/**
+ * Return `sizeof(VoikkoArenaAnalysis)` as compiled into the library.
+ */
+size_t voikko_sizeof_arena_analysis(void);
+
+/**
+ * Return `sizeof(VoikkoArenaAttribute)` as compiled into the library.
+ */
+size_t voikko_sizeof_arena_attribute(void);
+
+/**
+ * Return `sizeof(VoikkoAnalysisArena)` as compiled into the library.
+ */
+size_t voikko_sizeof_analysis_arena(void);
+
+/**
+ * Return `sizeof(VoikkoGrammarError)` as compiled into the library.
+ */
+size_t voikko_sizeof_grammar_error(void);
+
+/**
+ * Return `sizeof(VoikkoReplacement)` as compiled into the library.
+ */
+size_t voikko_sizeof_replacement(void);
+
+/**
+ * Return `sizeof(VoikkoGrammarErrorArray)` as compiled into the library.
+ */
+size_t voikko_sizeof_grammar_error_array(void);
+
+/**
+ * Return `sizeof(VoikkoToken)` as compiled into the library.
+ */
+size_t voikko_sizeof_token(void);
+
+/**
+ * Return `sizeof(VoikkoTokenArray)` as compiled into the library.
+ */
+size_t voikko_sizeof_token_array(void);
+
+/**
+ * Return `sizeof(VoikkoSentence)` as compiled into the library.
+ */
+size_t voikko_sizeof_sentence(void);
+
+/**
+ * Return `sizeof(VoikkoSentenceArray)` as compiled into the library.
+ */
+size_t voikko_sizeof_sentence_array(void);
+
+/**
+ * Return `sizeof(VoikkoSentenceSpan)` as compiled into the library.
+ */
+size_t voikko_sizeof_sentence_span(void);
+
+/**
+ * Return `sizeof(VoikkoSentenceSpanArray)` as compiled into the library.
+ */
+size_t voikko_sizeof_sentence_span_array(void);
+
+/**
+ * Return the library version string.
+ *
+ * The returned pointer is valid for the lifetime of the library (static).
+ * Do NOT free this pointer.
+ */
+const char *voikko_version(void);
+
+/**
+ * Get valid values for an enumerated attribute.
+ *
+ * Returns a NULL-terminated array. The returned pointer and its contents are
+ * static — do NOT free them.
+ * Returns NULL if the attribute is not recognized.
+ */
+const char *const *voikko_attribute_values(const char *name);
+
+/**
+ * Free a heap-allocated C string returned by voikko functions.
+ */
+void voikko_free_str(char *s);
+
+/**
+ * Free a NULL-terminated array of C strings.
+ */
+void voikko_free_str_array(char **arr);
+
+/* Boolean option setters: value 0 is false, anything else true. */
+void voikko_set_ignore_dot(VoikkoHandle *handle, int value);
+void voikko_set_ignore_numbers(VoikkoHandle *handle, int value);
+void voikko_set_ignore_uppercase(VoikkoHandle *handle, int value);
+void voikko_set_no_ugly_hyphenation(VoikkoHandle *handle, int value);
+void voikko_set_accept_first_uppercase(VoikkoHandle *handle, int value);
+void voikko_set_accept_all_uppercase(VoikkoHandle *handle, int value);
+void voikko_set_ocr_suggestions(VoikkoHandle *handle, int value);
+void voikko_set_ignore_nonwords(VoikkoHandle *handle, int value);
+void voikko_set_sentence_spell_check(VoikkoHandle *handle, int value);
+void voikko_set_sentence_closing_punctuation(VoikkoHandle *handle, int value);
+void voikko_set_accept_extra_hyphens(VoikkoHandle *handle, int value);
+void voikko_set_accept_missing_hyphens(VoikkoHandle *handle, int value);
+void voikko_set_accept_titles_in_gc(VoikkoHandle *handle, int value);
+void voikko_set_accept_unfinished_paragraphs_in_gc(VoikkoHandle *handle, int value);
+void voikko_set_hyphenate_unknown_words(VoikkoHandle *handle, int value);
+void voikko_set_accept_bulleted_lists_in_gc(VoikkoHandle *handle, int value);
+void voikko_set_check_overlong_compounds(VoikkoHandle *handle, int value);
+void voikko_set_check_number_formats(VoikkoHandle *handle, int value);
+void voikko_set_check_default_grammar_rules(VoikkoHandle *handle, int value);
+void voikko_set_check_discourse(VoikkoHandle *handle, int value);
+void voikko_set_check_paired_conjunctions(VoikkoHandle *handle, int value);
+void voikko_set_check_real_word_errors(VoikkoHandle *handle, int value);
+void voikko_set_check_subject_verb_agreement(VoikkoHandle *handle, int value);
+void voikko_set_reflow_paragraphs(VoikkoHandle *handle, int value);
+void voikko_set_truncation_diagnostics(VoikkoHandle *handle, int value);
+void voikko_set_prefer_proper_nouns(VoikkoHandle *handle, int value);
+void voikko_set_match_suggestion_case(VoikkoHandle *handle, int value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VOIKKO_FFI_H */
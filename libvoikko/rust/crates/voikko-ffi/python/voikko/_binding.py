@@ -10,9 +10,11 @@ from ctypes import (
     POINTER,
     Structure,
     c_char_p,
+    c_float,
     c_int,
     c_size_t,
     c_uint8,
+    c_uint64,
     c_void_p,
 )
 from pathlib import Path
@@ -72,6 +74,38 @@ class _VoikkoAnalysisArray(Structure):
     ]
 
 
+class _VoikkoArenaAnalysis(Structure):
+    _fields_ = [
+        ("first_attribute", c_size_t),
+        ("attribute_count", c_size_t),
+    ]
+
+
+class _VoikkoArenaAttribute(Structure):
+    _fields_ = [
+        ("key", c_size_t),
+        ("value", c_size_t),
+    ]
+
+
+class _VoikkoAnalysisArena(Structure):
+    _fields_ = [
+        ("analyses", POINTER(_VoikkoArenaAnalysis)),
+        ("count", c_size_t),
+        ("attributes", POINTER(_VoikkoArenaAttribute)),
+        ("strings", c_void_p),
+        ("size", c_size_t),
+    ]
+
+
+class _VoikkoReplacement(Structure):
+    _fields_ = [
+        ("start", c_size_t),
+        ("len", c_size_t),
+        ("text", c_char_p),
+    ]
+
+
 class _VoikkoGrammarError(Structure):
     _fields_ = [
         ("error_code", c_int),
@@ -79,6 +113,9 @@ class _VoikkoGrammarError(Structure):
         ("error_len", c_size_t),
         ("short_description", c_char_p),
         ("suggestions", POINTER(c_char_p)),
+        ("severity", c_int),
+        ("replacements", POINTER(_VoikkoReplacement)),
+        ("replacement_count", c_size_t),
     ]
 
 
@@ -94,6 +131,10 @@ class _VoikkoToken(Structure):
         ("token_type", c_int),
         ("text", c_char_p),
         ("position", c_size_t),
+        ("start_byte", c_size_t),
+        ("end_byte", c_size_t),
+        ("start_utf16", c_size_t),
+        ("end_utf16", c_size_t),
     ]
 
 
@@ -108,6 +149,7 @@ class _VoikkoSentence(Structure):
     _fields_ = [
         ("sentence_type", c_int),
         ("sentence_len", c_size_t),
+        ("trimmed_len", c_size_t),
     ]
 
 
@@ -118,6 +160,77 @@ class _VoikkoSentenceArray(Structure):
     ]
 
 
+class _VoikkoSentenceSpan(Structure):
+    _fields_ = [
+        ("sentence_type", c_int),
+        ("text", c_char_p),
+        ("start", c_size_t),
+        ("end", c_size_t),
+        ("start_byte", c_size_t),
+        ("end_byte", c_size_t),
+        ("start_utf16", c_size_t),
+        ("end_utf16", c_size_t),
+        ("trimmed_end", c_size_t),
+        ("trimmed_end_byte", c_size_t),
+        ("trimmed_end_utf16", c_size_t),
+    ]
+
+
+class _VoikkoSentenceSpanArray(Structure):
+    _fields_ = [
+        ("spans", POINTER(_VoikkoSentenceSpan)),
+        ("count", c_size_t),
+    ]
+
+
+# ── ABI check ───────────────────────────────────────────────────
+
+# VOIKKO_ABI_VERSION of the library this binding was written for.
+_ABI_VERSION = 3
+
+_STRUCTS = {
+    "analysis": _VoikkoAnalysis,
+    "analysis_array": _VoikkoAnalysisArray,
+    "arena_analysis": _VoikkoArenaAnalysis,
+    "arena_attribute": _VoikkoArenaAttribute,
+    "analysis_arena": _VoikkoAnalysisArena,
+    "grammar_error": _VoikkoGrammarError,
+    "replacement": _VoikkoReplacement,
+    "grammar_error_array": _VoikkoGrammarErrorArray,
+    "token": _VoikkoToken,
+    "token_array": _VoikkoTokenArray,
+    "sentence": _VoikkoSentence,
+    "sentence_array": _VoikkoSentenceArray,
+    "sentence_span": _VoikkoSentenceSpan,
+    "sentence_span_array": _VoikkoSentenceSpanArray,
+}
+
+
+def _check_abi() -> None:
+    """Refuse a library whose ABI version or struct layouts differ from
+    the declarations above."""
+    _lib.voikko_abi_version.argtypes = []
+    _lib.voikko_abi_version.restype = c_int
+    version = _lib.voikko_abi_version()
+    if version != _ABI_VERSION:
+        raise ImportError(
+            f"libvoikko_ffi ABI version {version} does not match the binding "
+            f"(expected {_ABI_VERSION})"
+        )
+    for name, struct in _STRUCTS.items():
+        fn = getattr(_lib, f"voikko_sizeof_{name}")
+        fn.argtypes = []
+        fn.restype = c_size_t
+        if fn() != ctypes.sizeof(struct):
+            raise ImportError(
+                f"libvoikko_ffi struct {struct.__name__[1:]} is {fn()} bytes, "
+                f"the binding declares {ctypes.sizeof(struct)}"
+            )
+
+
+_check_abi()
+
+
 # ── Function signatures ─────────────────────────────────────────
 
 _lib.voikko_new.argtypes = [
@@ -127,18 +240,81 @@ _lib.voikko_new.argtypes = [
 ]
 _lib.voikko_new.restype = c_void_p
 
+_lib.voikko_new_lazy.argtypes = _lib.voikko_new.argtypes
+_lib.voikko_new_lazy.restype = c_void_p
+
+_lib.voikko_warm_up.argtypes = [c_void_p, POINTER(c_char_p)]
+_lib.voikko_warm_up.restype = c_int
+
+_lib.voikko_warm_up_words.argtypes = [
+    c_void_p, POINTER(c_char_p), c_size_t, c_size_t, POINTER(c_char_p),
+]
+_lib.voikko_warm_up_words.restype = c_int
+
+_lib.voikko_warm_up_default.argtypes = [c_void_p, c_size_t, POINTER(c_char_p)]
+_lib.voikko_warm_up_default.restype = c_int
+
+_lib.voikko_load_state.argtypes = [c_void_p]
+_lib.voikko_load_state.restype = c_int
+
+_lib.voikko_reload.argtypes = [
+    c_void_p,
+    POINTER(c_uint8), c_size_t,
+    POINTER(c_uint8), c_size_t,
+    POINTER(c_char_p),
+]
+_lib.voikko_reload.restype = c_int
+
+_lib.voikko_traversal_stats.argtypes = [
+    c_void_p, POINTER(c_uint64), POINTER(c_uint64), POINTER(c_uint64),
+]
+_lib.voikko_traversal_stats.restype = c_int
+
+_lib.voikko_truncated_lookups.argtypes = [c_void_p]
+_lib.voikko_truncated_lookups.restype = POINTER(c_char_p)
+
+_lib.voikko_reset_traversal_stats.argtypes = [c_void_p]
+_lib.voikko_reset_traversal_stats.restype = None
+
 _lib.voikko_free.argtypes = [c_void_p]
 _lib.voikko_free.restype = None
 
 _lib.voikko_spell.argtypes = [c_void_p, c_char_p]
 _lib.voikko_spell.restype = c_int
 
+_lib.voikko_spell_status.argtypes = [c_void_p, c_char_p]
+_lib.voikko_spell_status.restype = c_int
+
 _lib.voikko_suggest.argtypes = [c_void_p, c_char_p]
 _lib.voikko_suggest.restype = POINTER(c_char_p)
 
+_lib.voikko_suggest_with_budget.argtypes = [c_void_p, c_char_p, c_int, c_int]
+_lib.voikko_suggest_with_budget.restype = POINTER(c_char_p)
+
+_lib.voikko_check_word.argtypes = [c_void_p, c_char_p, POINTER(POINTER(c_char_p))]
+_lib.voikko_check_word.restype = c_int
+
+_lib.voikko_spell_batch.argtypes = [c_void_p, POINTER(c_char_p), c_size_t, POINTER(c_int)]
+_lib.voikko_spell_batch.restype = c_int
+
+_lib.voikko_suggest_batch.argtypes = [c_void_p, POINTER(c_char_p), c_size_t, POINTER(POINTER(c_char_p))]
+_lib.voikko_suggest_batch.restype = c_int
+
 _lib.voikko_analyze.argtypes = [c_void_p, c_char_p]
 _lib.voikko_analyze.restype = _VoikkoAnalysisArray
 
+_lib.voikko_analyze_arena.argtypes = [c_void_p, c_char_p]
+_lib.voikko_analyze_arena.restype = _VoikkoAnalysisArena
+
+_lib.voikko_analyze_attribute.argtypes = [c_void_p, c_char_p, c_char_p]
+_lib.voikko_analyze_attribute.restype = POINTER(c_char_p)
+
+_lib.voikko_generate.argtypes = [c_void_p, c_char_p, POINTER(c_char_p), POINTER(c_char_p), c_size_t]
+_lib.voikko_generate.restype = POINTER(c_char_p)
+
+_lib.voikko_complete.argtypes = [c_void_p, c_char_p, c_size_t]
+_lib.voikko_complete.restype = POINTER(c_char_p)
+
 _lib.voikko_hyphenate.argtypes = [c_void_p, c_char_p]
 _lib.voikko_hyphenate.restype = c_void_p  # raw pointer, must free
 
@@ -154,6 +330,63 @@ _lib.voikko_tokens.restype = _VoikkoTokenArray
 _lib.voikko_sentences.argtypes = [c_void_p, c_char_p]
 _lib.voikko_sentences.restype = _VoikkoSentenceArray
 
+_lib.voikko_sentence_spans.argtypes = [c_void_p, c_char_p]
+_lib.voikko_sentence_spans.restype = _VoikkoSentenceSpanArray
+
+_lib.voikko_load_grammar_rules.argtypes = [c_void_p, c_char_p, POINTER(c_char_p)]
+_lib.voikko_load_grammar_rules.restype = c_int
+
+_lib.voikko_clear_grammar_rules.argtypes = [c_void_p]
+_lib.voikko_clear_grammar_rules.restype = None
+
+_lib.voikko_load_message_catalog.argtypes = [c_void_p, c_char_p, POINTER(c_char_p)]
+_lib.voikko_load_message_catalog.restype = c_int
+
+_lib.voikko_error_description.argtypes = [c_void_p, c_int, c_char_p]
+_lib.voikko_error_description.restype = c_void_p
+
+_lib.voikko_format_message.argtypes = [c_void_p, c_char_p, c_char_p, POINTER(c_char_p), c_size_t]
+_lib.voikko_format_message.restype = c_void_p
+
+_lib.voikko_add_autocorrect_rule.argtypes = [c_void_p, c_char_p, c_char_p, POINTER(c_char_p)]
+_lib.voikko_add_autocorrect_rule.restype = c_int
+
+_lib.voikko_remove_autocorrect_rule.argtypes = [c_void_p, c_char_p]
+_lib.voikko_remove_autocorrect_rule.restype = c_int
+
+_lib.voikko_clear_autocorrect_rules.argtypes = [c_void_p]
+_lib.voikko_clear_autocorrect_rules.restype = None
+
+_lib.voikko_load_hyphenation_exceptions.argtypes = [c_void_p, c_char_p, POINTER(c_char_p)]
+_lib.voikko_load_hyphenation_exceptions.restype = c_int
+
+_lib.voikko_clear_hyphenation_exceptions.argtypes = [c_void_p]
+_lib.voikko_clear_hyphenation_exceptions.restype = None
+
+_lib.voikko_add_user_word.argtypes = [c_void_p, c_char_p, c_char_p]
+_lib.voikko_add_user_word.restype = c_int
+
+_lib.voikko_remove_user_word.argtypes = [c_void_p, c_char_p]
+_lib.voikko_remove_user_word.restype = c_int
+
+_lib.voikko_user_words.argtypes = [c_void_p]
+_lib.voikko_user_words.restype = POINTER(c_char_p)
+
+_lib.voikko_export_user_dictionary.argtypes = [c_void_p]
+_lib.voikko_export_user_dictionary.restype = c_void_p  # raw pointer, must free
+
+_lib.voikko_load_user_dictionary.argtypes = [c_void_p, c_char_p, POINTER(c_char_p)]
+_lib.voikko_load_user_dictionary.restype = c_int
+
+_lib.voikko_set_suggestion_ranking.argtypes = [c_void_p, c_float, c_float, c_float]
+_lib.voikko_set_suggestion_ranking.restype = None
+
+_lib.voikko_set_input_mapping.argtypes = [c_void_p, c_int, c_int, c_int, c_int]
+_lib.voikko_set_input_mapping.restype = None
+
+_lib.voikko_set_grammar_error_severity.argtypes = [c_void_p, c_int, c_int]
+_lib.voikko_set_grammar_error_severity.restype = None
+
 _lib.voikko_version.argtypes = []
 _lib.voikko_version.restype = c_char_p
 
@@ -169,6 +402,9 @@ _lib.voikko_free_str_array.restype = None
 _lib.voikko_free_analyses.argtypes = [_VoikkoAnalysisArray]
 _lib.voikko_free_analyses.restype = None
 
+_lib.voikko_free_analysis_arena.argtypes = [_VoikkoAnalysisArena]
+_lib.voikko_free_analysis_arena.restype = None
+
 _lib.voikko_free_grammar_errors.argtypes = [_VoikkoGrammarErrorArray]
 _lib.voikko_free_grammar_errors.restype = None
 
@@ -178,19 +414,37 @@ _lib.voikko_free_tokens.restype = None
 _lib.voikko_free_sentences.argtypes = [_VoikkoSentenceArray]
 _lib.voikko_free_sentences.restype = None
 
+_lib.voikko_free_sentence_spans.argtypes = [_VoikkoSentenceSpanArray]
+_lib.voikko_free_sentence_spans.restype = None
+
 # Option setters
 for _name in [
     "voikko_set_ignore_dot", "voikko_set_ignore_numbers",
     "voikko_set_ignore_uppercase", "voikko_set_no_ugly_hyphenation",
     "voikko_set_accept_first_uppercase", "voikko_set_accept_all_uppercase",
-    "voikko_set_ocr_suggestions", "voikko_set_ignore_nonwords",
+    "voikko_set_ocr_suggestions", "voikko_set_prefer_proper_nouns",
+    "voikko_set_match_suggestion_case",
+    "voikko_set_ignore_nonwords",
+    "voikko_set_sentence_spell_check",
+    "voikko_set_sentence_closing_punctuation",
+    "voikko_set_sentence_whitespace",
     "voikko_set_accept_extra_hyphens", "voikko_set_accept_missing_hyphens",
     "voikko_set_accept_titles_in_gc",
     "voikko_set_accept_unfinished_paragraphs_in_gc",
     "voikko_set_hyphenate_unknown_words",
     "voikko_set_accept_bulleted_lists_in_gc",
+    "voikko_set_check_default_grammar_rules",
+    "voikko_set_check_discourse",
+    "voikko_set_check_paired_conjunctions",
+    "voikko_set_check_real_word_errors",
+    "voikko_set_check_subject_verb_agreement",
+    "voikko_set_check_number_formats", "voikko_set_technical_number_style",
     "voikko_set_min_hyphenated_word_length",
-    "voikko_set_max_suggestions", "voikko_set_speller_cache_size",
+    "voikko_set_max_suggestions", "voikko_set_max_word_chars",
+    "voikko_set_reflow_paragraphs", "voikko_set_speller_cache_size",
+    "voikko_set_analysis_cache_size",
+    "voikko_set_strip_invisible_chars",
+    "voikko_set_truncation_diagnostics",
 ]:
     fn = getattr(_lib, _name)
     fn.argtypes = [c_void_p, c_int]
@@ -201,6 +455,8 @@ for _name in [
 
 _TOKEN_TYPES = {0: "NONE", 1: "WORD", 2: "PUNCTUATION", 3: "WHITESPACE", 4: "UNKNOWN"}
 _SENTENCE_TYPES = {0: "NONE", 1: "NO_START", 2: "PROBABLE", 3: "POSSIBLE"}
+_SPELL_STATUSES = {0: "MISSPELLED", 1: "CORRECT", 2: "TOO_LONG", 3: "TRUNCATED"}
+_SEVERITIES = {0: "ERROR", 1: "WARNING"}
 
 
 def _read_null_terminated(ptr: POINTER(c_char_p)) -> list[str]:
@@ -221,17 +477,36 @@ def _enc(s: str) -> bytes:
 
 # ── Public API ───────────────────────────────────────────────────
 
+class Replacement:
+    """Edit that applies a grammar error suggestion: replace `len`
+    characters at `start` with `text`."""
+
+    __slots__ = ("start", "len", "text")
+
+    def __init__(self, start: int, length: int, text: str):
+        self.start = start
+        self.len = length
+        self.text = text
+
+    def __repr__(self) -> str:
+        return f"Replacement(start={self.start}, len={self.len}, text={self.text!r})"
+
+
 class GrammarError:
     """Grammar error detected by Voikko."""
 
-    __slots__ = ("error_code", "start_pos", "error_len", "short_description", "suggestions")
+    __slots__ = ("error_code", "start_pos", "error_len", "short_description", "suggestions",
+                 "severity", "replacements")
 
-    def __init__(self, code: int, start: int, length: int, desc: str, sugg: list[str]):
+    def __init__(self, code: int, start: int, length: int, desc: str, sugg: list[str],
+                 severity: str = "ERROR", replacements: list[Replacement] | None = None):
         self.error_code = code
         self.start_pos = start
         self.error_len = length
         self.short_description = desc
         self.suggestions = sugg
+        self.severity = severity
+        self.replacements = replacements if replacements is not None else []
 
     def __repr__(self) -> str:
         return (
@@ -243,12 +518,19 @@ class GrammarError:
 class Token:
     """Text token."""
 
-    __slots__ = ("type", "text", "position")
+    __slots__ = ("type", "text", "position", "start_byte", "end_byte",
+                 "start_utf16", "end_utf16")
 
-    def __init__(self, token_type: str, text: str, position: int):
+    def __init__(self, token_type: str, text: str, position: int,
+                 start_byte: int = 0, end_byte: int = 0,
+                 start_utf16: int = 0, end_utf16: int = 0):
         self.type = token_type
         self.text = text
         self.position = position
+        self.start_byte = start_byte
+        self.end_byte = end_byte
+        self.start_utf16 = start_utf16
+        self.end_utf16 = end_utf16
 
     def __repr__(self) -> str:
         return f"Token({self.type}, {self.text!r})"
@@ -257,49 +539,86 @@ class Token:
 class Sentence:
     """Detected sentence boundary."""
 
-    __slots__ = ("type", "length")
+    __slots__ = ("type", "length", "trimmed_length")
 
-    def __init__(self, sentence_type: str, length: int):
+    def __init__(self, sentence_type: str, length: int, trimmed_length: Optional[int] = None):
         self.type = sentence_type
         self.length = length
+        self.trimmed_length = length if trimmed_length is None else trimmed_length
 
     def __repr__(self) -> str:
         return f"Sentence({self.type}, len={self.length})"
 
 
+class SentenceSpan:
+    """Detected sentence with its text and offsets."""
+
+    __slots__ = ("type", "text", "start", "end", "start_byte", "end_byte",
+                 "start_utf16", "end_utf16", "trimmed_end", "trimmed_end_byte",
+                 "trimmed_end_utf16")
+
+    def __init__(self, sentence_type: str, text: str, start: int, end: int,
+                 start_byte: int = 0, end_byte: int = 0,
+                 start_utf16: int = 0, end_utf16: int = 0,
+                 trimmed_end: Optional[int] = None, trimmed_end_byte: Optional[int] = None,
+                 trimmed_end_utf16: Optional[int] = None):
+        self.type = sentence_type
+        self.text = text
+        self.start = start
+        self.end = end
+        self.start_byte = start_byte
+        self.end_byte = end_byte
+        self.start_utf16 = start_utf16
+        self.end_utf16 = end_utf16
+        self.trimmed_end = end if trimmed_end is None else trimmed_end
+        self.trimmed_end_byte = end_byte if trimmed_end_byte is None else trimmed_end_byte
+        self.trimmed_end_utf16 = end_utf16 if trimmed_end_utf16 is None else trimmed_end_utf16
+
+    def __repr__(self) -> str:
+        return f"SentenceSpan({self.type}, {self.text!r})"
+
+
+def _read_dictionary(dict_path: str) -> tuple[bytes, Optional[bytes]]:
+    """Read mor.vfst and, if present, autocorr.vfst from a dictionary directory."""
+    path = Path(dict_path)
+
+    # Auto-detect V5 structure
+    mor_path = path / "mor.vfst"
+    if not mor_path.is_file():
+        v5 = path / "5" / "mor-standard" / "mor.vfst"
+        if v5.is_file():
+            mor_path = v5
+            path = v5.parent
+        else:
+            raise FileNotFoundError(f"mor.vfst not found in {dict_path}")
+
+    mor_data = mor_path.read_bytes()
+    autocorr_path = path / "autocorr.vfst"
+    autocorr_data = autocorr_path.read_bytes() if autocorr_path.is_file() else None
+    return mor_data, autocorr_data
+
+
 class Voikko:
     """Finnish language NLP toolkit powered by Rust.
 
     Args:
         dict_path: Path to directory containing mor.vfst (and optionally autocorr.vfst).
                    Supports both flat layout and V5 structure ({path}/5/mor-standard/).
+        lazy: Defer building the morphology transducer until first use or warm_up().
     """
 
-    def __init__(self, dict_path: str):
-        path = Path(dict_path)
-
-        # Auto-detect V5 structure
-        mor_path = path / "mor.vfst"
-        if not mor_path.is_file():
-            v5 = path / "5" / "mor-standard" / "mor.vfst"
-            if v5.is_file():
-                mor_path = v5
-                path = v5.parent
-            else:
-                raise FileNotFoundError(f"mor.vfst not found in {dict_path}")
-
-        mor_data = mor_path.read_bytes()
-        autocorr_path = path / "autocorr.vfst"
-        autocorr_data = autocorr_path.read_bytes() if autocorr_path.is_file() else None
+    def __init__(self, dict_path: str, lazy: bool = False):
+        mor_data, autocorr_data = _read_dictionary(dict_path)
 
         mor_buf = (c_uint8 * len(mor_data))(*mor_data)
         error_msg = c_char_p()
+        new = _lib.voikko_new_lazy if lazy else _lib.voikko_new
 
         if autocorr_data:
             ac_buf = (c_uint8 * len(autocorr_data))(*autocorr_data)
-            handle = _lib.voikko_new(mor_buf, len(mor_data), ac_buf, len(autocorr_data), ctypes.byref(error_msg))
+            handle = new(mor_buf, len(mor_data), ac_buf, len(autocorr_data), ctypes.byref(error_msg))
         else:
-            handle = _lib.voikko_new(mor_buf, len(mor_data), None, 0, ctypes.byref(error_msg))
+            handle = new(mor_buf, len(mor_data), None, 0, ctypes.byref(error_msg))
 
         if not handle:
             msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
@@ -323,11 +642,96 @@ class Voikko:
             _lib.voikko_free(self._handle)
             self._handle = None
 
+    def warm_up(self) -> None:
+        """Build the morphology transducer of a lazily created instance."""
+        self._check_handle()
+        error_msg = c_char_p()
+        if _lib.voikko_warm_up(self._handle, ctypes.byref(error_msg)) < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise RuntimeError(f"Failed to load Voikko dictionary: {msg}")
+
+    def warm_up_words(self, words: Optional[list[str]] = None, threads: int = 1) -> None:
+        """Spell and analyze words (by default, frequent Finnish word forms)
+        so that the first checks of them are answered from the caches."""
+        self._check_handle()
+        error_msg = c_char_p()
+        if words is None:
+            status = _lib.voikko_warm_up_default(self._handle, threads, ctypes.byref(error_msg))
+        else:
+            encoded = (c_char_p * len(words))(*(_enc(w) for w in words))
+            status = _lib.voikko_warm_up_words(
+                self._handle, encoded, len(words), threads, ctypes.byref(error_msg)
+            )
+        if status < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise RuntimeError(f"Failed to load Voikko dictionary: {msg}")
+
+    def reload(self, dict_path: str) -> None:
+        """Replace the dictionary with the one in dict_path, keeping options.
+
+        May be called while other threads use the instance. On error the
+        old dictionary stays.
+        """
+        self._check_handle()
+        mor_data, autocorr_data = _read_dictionary(dict_path)
+        mor_buf = (c_uint8 * len(mor_data))(*mor_data)
+        error_msg = c_char_p()
+        if autocorr_data:
+            ac_buf = (c_uint8 * len(autocorr_data))(*autocorr_data)
+            result = _lib.voikko_reload(self._handle, mor_buf, len(mor_data), ac_buf,
+                                        len(autocorr_data), ctypes.byref(error_msg))
+        else:
+            result = _lib.voikko_reload(self._handle, mor_buf, len(mor_data), None, 0,
+                                        ctypes.byref(error_msg))
+        if result < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise RuntimeError(f"Failed to load Voikko dictionary: {msg}")
+
+    def load_state(self) -> str:
+        """Return "pending", "ready" or "failed"."""
+        self._check_handle()
+        return ("pending", "ready", "failed")[_lib.voikko_load_state(self._handle)]
+
+    def traversal_stats(self) -> dict[str, int]:
+        """Return the number of lookups cut short by the traversal budget,
+        keyed by the limit that stopped them ("steps", "outputs", "deadline").
+        """
+        self._check_handle()
+        steps, outputs, deadline = c_uint64(), c_uint64(), c_uint64()
+        _lib.voikko_traversal_stats(self._handle, ctypes.byref(steps),
+                                    ctypes.byref(outputs), ctypes.byref(deadline))
+        return {"steps": steps.value, "outputs": outputs.value, "deadline": deadline.value}
+
+    def truncated_lookups(self) -> list[str]:
+        """Return the most recent words whose lookup was cut short, oldest
+        first. Empty unless set_truncation_diagnostics(True) was called.
+        """
+        self._check_handle()
+        ptr = _lib.voikko_truncated_lookups(self._handle)
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
+        return result
+
+    def reset_traversal_stats(self) -> None:
+        """Reset the traversal counters and discard the recorded words."""
+        self._check_handle()
+        _lib.voikko_reset_traversal_stats(self._handle)
+
     def spell(self, word: str) -> bool:
         """Check spelling."""
         self._check_handle()
         return _lib.voikko_spell(self._handle, _enc(word)) == 1
 
+    def spell_status(self, word: str) -> str:
+        """Check spelling: "CORRECT", "MISSPELLED", "TOO_LONG" or "TRUNCATED"."""
+        self._check_handle()
+        return _SPELL_STATUSES.get(_lib.voikko_spell_status(self._handle, _enc(word)), "MISSPELLED")
+
     def suggest(self, word: str) -> list[str]:
         """Get spelling suggestions."""
         self._check_handle()
@@ -338,22 +742,108 @@ class Voikko:
         _lib.voikko_free_str_array(ptr)
         return result
 
+    def suggest_with_budget(self, word: str, max_cost: int = 0, max_millis: int = 0) -> list[str]:
+        """Get the spelling suggestions found within a cost and time budget.
+
+        ``max_cost`` limits the number of spell checks and ``max_millis`` the
+        time in milliseconds; 0 uses the default cost and no time limit.
+        """
+        self._check_handle()
+        ptr = _lib.voikko_suggest_with_budget(self._handle, _enc(word), max_cost, max_millis)
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
+        return result
+
+    def check_word(self, word: str) -> tuple[bool, Optional[list[str]]]:
+        """Spell check a word; also return suggestions if it is misspelled.
+
+        Returns ``(True, None)`` for a correct word and ``(False, suggestions)``
+        otherwise.
+        """
+        self._check_handle()
+        ptr = POINTER(c_char_p)()
+        result = _lib.voikko_check_word(self._handle, _enc(word), ctypes.byref(ptr))
+        if result == 1:
+            return True, None
+        suggestions = _read_null_terminated(ptr)
+        if ptr:
+            _lib.voikko_free_str_array(ptr)
+        return False, suggestions
+
+    def spell_batch(self, words: list[str]) -> list[bool]:
+        """Check the spelling of many words in one call."""
+        self._check_handle()
+        count = len(words)
+        encoded = (c_char_p * count)(*(_enc(w) for w in words))
+        results = (c_int * count)()
+        _lib.voikko_spell_batch(self._handle, encoded, count, results)
+        return [r == 1 for r in results]
+
+    def suggest_batch(self, words: list[str]) -> list[list[str]]:
+        """Get spelling suggestions for many words in one call."""
+        self._check_handle()
+        count = len(words)
+        encoded = (c_char_p * count)(*(_enc(w) for w in words))
+        results = (POINTER(c_char_p) * count)()
+        _lib.voikko_suggest_batch(self._handle, encoded, count, results)
+        out = []
+        for ptr in results:
+            if ptr:
+                out.append(_read_null_terminated(ptr))
+                _lib.voikko_free_str_array(ptr)
+            else:
+                out.append([])
+        return out
+
     def analyze(self, word: str) -> list[dict[str, str]]:
         """Morphological analysis."""
         self._check_handle()
-        arr = _lib.voikko_analyze(self._handle, _enc(word))
+        arena = _lib.voikko_analyze_arena(self._handle, _enc(word))
         result = []
-        for i in range(arr.count):
-            a = arr.analyses[i]
+        for i in range(arena.count):
+            a = arena.analyses[i]
             d = {}
-            j = 0
-            while a.keys[j]:
-                k = a.keys[j].decode("utf-8")
-                v = a.values[j].decode("utf-8")
-                d[k] = v
-                j += 1
+            for j in range(a.first_attribute, a.first_attribute + a.attribute_count):
+                attr = arena.attributes[j]
+                k = ctypes.string_at(arena.strings + attr.key).decode("utf-8")
+                d[k] = ctypes.string_at(arena.strings + attr.value).decode("utf-8")
             result.append(d)
-        _lib.voikko_free_analyses(arr)
+        _lib.voikko_free_analysis_arena(arena)
+        return result
+
+    def analyze_attribute(self, word: str, key: str) -> list[str]:
+        """Values of one analysis attribute across the analyses of a word."""
+        self._check_handle()
+        ptr = _lib.voikko_analyze_attribute(self._handle, _enc(word), _enc(key))
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
+        return result
+
+    def generate(self, baseform: str, attributes: dict[str, str]) -> list[str]:
+        """Generate the forms of a word with the given analysis attributes."""
+        self._check_handle()
+        count = len(attributes)
+        keys = (c_char_p * count)(*(_enc(k) for k in attributes))
+        values = (c_char_p * count)(*(_enc(v) for v in attributes.values()))
+        ptr = _lib.voikko_generate(self._handle, _enc(baseform), keys, values, count)
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
+        return result
+
+    def complete(self, prefix: str, max: int = 10) -> list[str]:
+        """Complete a prefix to dictionary words, sorted alphabetically."""
+        self._check_handle()
+        ptr = _lib.voikko_complete(self._handle, _enc(prefix), max)
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
         return result
 
     def hyphenate(self, word: str, separator: str = "-", allow_context_changes: bool = True) -> str:
@@ -385,10 +875,157 @@ class Voikko:
             e = arr.errors[i]
             sugg = _read_null_terminated(e.suggestions) if e.suggestions else []
             desc = e.short_description.decode("utf-8") if e.short_description else ""
-            result.append(GrammarError(e.error_code, e.start_pos, e.error_len, desc, sugg))
+            replacements = [
+                Replacement(r.start, r.len, r.text.decode("utf-8") if r.text else "")
+                for r in (e.replacements[j] for j in range(e.replacement_count))
+            ]
+            result.append(GrammarError(
+                e.error_code, e.start_pos, e.error_len, desc, sugg,
+                _SEVERITIES.get(e.severity, "ERROR"), replacements,
+            ))
         _lib.voikko_free_grammar_errors(arr)
         return result
 
+    def load_grammar_rules(self, json: str) -> int:
+        """Load a JSON grammar rule pack. Returns the number of rules loaded."""
+        self._check_handle()
+        error_msg = c_char_p()
+        count = _lib.voikko_load_grammar_rules(self._handle, _enc(json), ctypes.byref(error_msg))
+        if count < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise ValueError(msg)
+        return count
+
+    def clear_grammar_rules(self) -> None:
+        """Remove all grammar rules loaded with load_grammar_rules."""
+        self._check_handle()
+        _lib.voikko_clear_grammar_rules(self._handle)
+
+    def load_message_catalog(self, text: str) -> int:
+        """Add message catalog entries ([language] sections). Returns the number loaded."""
+        self._check_handle()
+        error_msg = c_char_p()
+        count = _lib.voikko_load_message_catalog(self._handle, _enc(text), ctypes.byref(error_msg))
+        if count < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise ValueError(msg)
+        return count
+
+    def error_description(self, code: int, language: str = "fi") -> Optional[str]:
+        """Short description of a grammar error code in the given language."""
+        self._check_handle()
+        ptr = _lib.voikko_error_description(self._handle, code, _enc(language))
+        if not ptr:
+            return None
+        result = ctypes.cast(ptr, c_char_p).value.decode("utf-8")
+        _lib.voikko_free_str(ctypes.cast(ptr, c_char_p))
+        return result
+
+    def format_message(self, key: str, language: str = "fi", *args: str) -> Optional[str]:
+        """Fill the message template key (e.g. "did_you_mean") with args."""
+        self._check_handle()
+        c_args = (c_char_p * len(args))(*[_enc(a) for a in args])
+        ptr = _lib.voikko_format_message(self._handle, _enc(key), _enc(language), c_args, len(args))
+        if not ptr:
+            return None
+        result = ctypes.cast(ptr, c_char_p).value.decode("utf-8")
+        _lib.voikko_free_str(ctypes.cast(ptr, c_char_p))
+        return result
+
+    def add_autocorrect_rule(self, from_: str, to: str) -> None:
+        """Replace from_ with to in grammar checking's autocorrect.
+
+        from_ is literal text or a pattern between slashes, e.g. "/(\\d+)e/"
+        with the replacement "$1 €".
+        """
+        self._check_handle()
+        error_msg = c_char_p()
+        result = _lib.voikko_add_autocorrect_rule(
+            self._handle, _enc(from_), _enc(to), ctypes.byref(error_msg)
+        )
+        if result < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise ValueError(msg)
+
+    def remove_autocorrect_rule(self, from_: str) -> bool:
+        """Remove the autocorrect rule for from_. Returns True if there was one."""
+        self._check_handle()
+        return _lib.voikko_remove_autocorrect_rule(self._handle, _enc(from_)) == 1
+
+    def clear_autocorrect_rules(self) -> None:
+        """Remove all rules added with add_autocorrect_rule."""
+        self._check_handle()
+        _lib.voikko_clear_autocorrect_rules(self._handle)
+
+    def load_hyphenation_exceptions(self, text: str) -> int:
+        """Load words with a fixed hyphenation, one per line (e.g. "Voik-ko").
+
+        Returns the number of entries loaded.
+        """
+        self._check_handle()
+        error_msg = c_char_p()
+        count = _lib.voikko_load_hyphenation_exceptions(
+            self._handle, _enc(text), ctypes.byref(error_msg)
+        )
+        if count < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise ValueError(msg)
+        return count
+
+    def clear_hyphenation_exceptions(self) -> None:
+        """Remove all hyphenation exceptions."""
+        self._check_handle()
+        _lib.voikko_clear_hyphenation_exceptions(self._handle)
+
+    def add_user_word(self, word: str, hint: Optional[str] = None) -> None:
+        """Add a word to the user dictionary.
+
+        hint is a dictionary word that inflects the same way (e.g. "paperi"
+        for "blogi"); with it, inflected forms of the word are accepted too.
+        """
+        self._check_handle()
+        _lib.voikko_add_user_word(self._handle, _enc(word), _enc(hint) if hint else None)
+
+    def remove_user_word(self, word: str) -> bool:
+        """Remove a word from the user dictionary. Returns True if it was present."""
+        self._check_handle()
+        return _lib.voikko_remove_user_word(self._handle, _enc(word)) == 1
+
+    def user_words(self) -> list[str]:
+        """Return the words in the user dictionary, sorted."""
+        self._check_handle()
+        ptr = _lib.voikko_user_words(self._handle)
+        if not ptr:
+            return []
+        result = _read_null_terminated(ptr)
+        _lib.voikko_free_str_array(ptr)
+        return result
+
+    def export_user_dictionary(self) -> str:
+        """Serialize the user dictionary ("word" or "word<TAB>hint" per line)."""
+        self._check_handle()
+        ptr = _lib.voikko_export_user_dictionary(self._handle)
+        if not ptr:
+            return ""
+        result = ctypes.cast(ptr, c_char_p).value.decode("utf-8")
+        _lib.voikko_free_str(ctypes.cast(ptr, c_char_p))
+        return result
+
+    def load_user_dictionary(self, text: str) -> int:
+        """Add the entries of a user dictionary file. Returns the number of entries."""
+        self._check_handle()
+        error_msg = c_char_p()
+        count = _lib.voikko_load_user_dictionary(self._handle, _enc(text), ctypes.byref(error_msg))
+        if count < 0:
+            msg = error_msg.value.decode("utf-8") if error_msg.value else "unknown error"
+            _lib.voikko_free_str(error_msg)
+            raise ValueError(msg)
+        return count
+
     def tokens(self, text: str) -> list[Token]:
         """Tokenize text."""
         self._check_handle()
@@ -400,6 +1037,10 @@ class Voikko:
                 _TOKEN_TYPES.get(t.token_type, "UNKNOWN"),
                 t.text.decode("utf-8") if t.text else "",
                 t.position,
+                t.start_byte,
+                t.end_byte,
+                t.start_utf16,
+                t.end_utf16,
             ))
         _lib.voikko_free_tokens(arr)
         return result
@@ -414,10 +1055,34 @@ class Voikko:
             result.append(Sentence(
                 _SENTENCE_TYPES.get(s.sentence_type, "NONE"),
                 s.sentence_len,
+                s.trimmed_len,
             ))
         _lib.voikko_free_sentences(arr)
         return result
 
+    def sentence_spans(self, text: str) -> list[SentenceSpan]:
+        """Detect sentences with their text and offsets."""
+        self._check_handle()
+        arr = _lib.voikko_sentence_spans(self._handle, _enc(text))
+        result = []
+        for i in range(arr.count):
+            s = arr.spans[i]
+            result.append(SentenceSpan(
+                _SENTENCE_TYPES.get(s.sentence_type, "NONE"),
+                s.text.decode("utf-8") if s.text else "",
+                s.start,
+                s.end,
+                s.start_byte,
+                s.end_byte,
+                s.start_utf16,
+                s.end_utf16,
+                s.trimmed_end,
+                s.trimmed_end_byte,
+                s.trimmed_end_utf16,
+            ))
+        _lib.voikko_free_sentence_spans(arr)
+        return result
+
     def attribute_values(self, name: str) -> Optional[list[str]]:
         """Get valid values for a morphological attribute."""
         ptr = _lib.voikko_attribute_values(_enc(name))
@@ -434,16 +1099,60 @@ class Voikko:
     def set_accept_first_uppercase(self, v: bool) -> None: _lib.voikko_set_accept_first_uppercase(self._handle, int(v))
     def set_accept_all_uppercase(self, v: bool) -> None: _lib.voikko_set_accept_all_uppercase(self._handle, int(v))
     def set_ocr_suggestions(self, v: bool) -> None: _lib.voikko_set_ocr_suggestions(self._handle, int(v))
+    def set_prefer_proper_nouns(self, v: bool) -> None: _lib.voikko_set_prefer_proper_nouns(self._handle, int(v))
+    def set_match_suggestion_case(self, v: bool) -> None: _lib.voikko_set_match_suggestion_case(self._handle, int(v))
     def set_ignore_nonwords(self, v: bool) -> None: _lib.voikko_set_ignore_nonwords(self._handle, int(v))
+    def set_sentence_spell_check(self, v: bool) -> None: _lib.voikko_set_sentence_spell_check(self._handle, int(v))
+    def set_sentence_closing_punctuation(self, v: bool) -> None: _lib.voikko_set_sentence_closing_punctuation(self._handle, int(v))
+    def set_sentence_trim_end(self, v: bool) -> None: _lib.voikko_set_sentence_whitespace(self._handle, int(v))
     def set_accept_extra_hyphens(self, v: bool) -> None: _lib.voikko_set_accept_extra_hyphens(self._handle, int(v))
     def set_accept_missing_hyphens(self, v: bool) -> None: _lib.voikko_set_accept_missing_hyphens(self._handle, int(v))
     def set_accept_titles_in_gc(self, v: bool) -> None: _lib.voikko_set_accept_titles_in_gc(self._handle, int(v))
     def set_accept_unfinished_paragraphs_in_gc(self, v: bool) -> None: _lib.voikko_set_accept_unfinished_paragraphs_in_gc(self._handle, int(v))
     def set_hyphenate_unknown_words(self, v: bool) -> None: _lib.voikko_set_hyphenate_unknown_words(self._handle, int(v))
     def set_accept_bulleted_lists_in_gc(self, v: bool) -> None: _lib.voikko_set_accept_bulleted_lists_in_gc(self._handle, int(v))
+    def set_check_default_grammar_rules(self, v: bool) -> None: _lib.voikko_set_check_default_grammar_rules(self._handle, int(v))
+    def set_check_discourse(self, v: bool) -> None: _lib.voikko_set_check_discourse(self._handle, int(v))
+    def set_check_paired_conjunctions(self, v: bool) -> None: _lib.voikko_set_check_paired_conjunctions(self._handle, int(v))
+    def set_check_real_word_errors(self, v: bool) -> None: _lib.voikko_set_check_real_word_errors(self._handle, int(v))
+    def set_check_subject_verb_agreement(self, v: bool) -> None: _lib.voikko_set_check_subject_verb_agreement(self._handle, int(v))
+    def set_check_number_formats(self, v: bool) -> None: _lib.voikko_set_check_number_formats(self._handle, int(v))
+    def set_technical_number_style(self, v: bool) -> None: _lib.voikko_set_technical_number_style(self._handle, int(v))
     def set_min_hyphenated_word_length(self, v: int) -> None: _lib.voikko_set_min_hyphenated_word_length(self._handle, v)
     def set_max_suggestions(self, v: int) -> None: _lib.voikko_set_max_suggestions(self._handle, v)
+    def set_max_word_chars(self, v: int) -> None: _lib.voikko_set_max_word_chars(self._handle, v)
+    def set_reflow_paragraphs(self, v: bool) -> None: _lib.voikko_set_reflow_paragraphs(self._handle, int(v))
+    def set_truncation_diagnostics(self, v: bool) -> None: _lib.voikko_set_truncation_diagnostics(self._handle, int(v))
     def set_speller_cache_size(self, v: int) -> None: _lib.voikko_set_speller_cache_size(self._handle, v)
+    def set_analysis_cache_size(self, v: int) -> None: _lib.voikko_set_analysis_cache_size(self._handle, v)
+    def set_strip_invisible_chars(self, v: bool) -> None: _lib.voikko_set_strip_invisible_chars(self._handle, int(v))
+
+    def set_suggestion_ranking(self, edit: float = 0.0, fst: float = 0.0, morph: float = 1.0) -> None:
+        """Set the coefficients of the suggestion ranking costs."""
+        _lib.voikko_set_suggestion_ranking(self._handle, edit, fst, morph)
+
+    def set_input_mapping(
+        self,
+        superscripts: bool = False,
+        subscripts: bool = False,
+        fractions: bool = False,
+        typographic_digits: bool = False,
+    ) -> None:
+        """Map typographic character classes to plain characters before checking."""
+        _lib.voikko_set_input_mapping(
+            self._handle, int(superscripts), int(subscripts), int(fractions), int(typographic_digits)
+        )
+
+    def set_grammar_error_severity(self, code: int, severity: str | None) -> None:
+        """Report grammar errors with `code` as "ERROR" or "WARNING"; None restores the default."""
+        if severity is None:
+            value = -1
+        else:
+            codes = {name: value for value, name in _SEVERITIES.items()}
+            if severity not in codes:
+                raise ValueError(f"unknown severity: {severity!r}")
+            value = codes[severity]
+        _lib.voikko_set_grammar_error_severity(self._handle, code, value)
 
     @staticmethod
     def version() -> str:
@@ -0,0 +1,996 @@
+// libvoikko 4.x compatible C API
+//
+// The entry points of the classic libvoikko C API (voikko.h): voikkoInit,
+// voikkoSpellCstr, voikkoSuggestCstr, voikkoFreeCstrArray and the rest, with
+// the same names, arguments, return codes and ownership rules, so that an
+// application linked against libvoikko can load this library instead.
+// Applications keep using the libvoikko headers, so this module is left out
+// of include/voikko_ffi.h. The handle is a `ClassicHandle`, not the
+// `VoikkoHandle` of the voikko_* functions; the two must not be mixed.
+//
+// voikkoInit looks for the dictionary with voikko_fi::dict_path, like the
+// command-line tools: the given directory (also in the libvoikko layout
+// `5/mor-standard`), then VOIKKO_DICT_PATH, ~/.voikko and the system
+// directories. The deprecated API of voikko_deprecated.h (voikko_init,
+// voikko_spell_cstr, ...) is not provided.
+//
+// `wchar_t` is 32 bits on Unix and 16 bits on Windows. As in libvoikko,
+// one `wchar_t` is one character: positions and lengths in the Ucs4
+// functions count `wchar_t` units.
+//
+// Origin: voikko.h, setup/setup.cpp, setup/interface.cpp,
+//         spellchecker/spell.cpp, spellchecker/suggestions.cpp,
+//         hyphenator/interface.cpp, tokenizer/interface.cpp,
+//         sentence/interface.cpp, grammar/interface.cpp, grammar/error.cpp,
+//         morphology/interface.cpp
+
+use std::ffi::{CString, c_char, c_int};
+use std::path::Path;
+use std::ptr;
+
+use voikko_core::grammar_error::{self, GrammarError};
+use voikko_fi::dict_path::{self, MOR_VFST};
+use voikko_fi::handle::{SpellStatus, VoikkoHandle};
+use voikko_fi::tokenizer;
+
+use super::{cstr_to_str, free_c_str, free_null_terminated_array, str_to_c, strings_to_c_array};
+
+#[cfg(not(windows))]
+type WChar = u32;
+#[cfg(windows)]
+type WChar = u16;
+
+// Option ids from voikko_defines.h
+const VOIKKO_OPT_IGNORE_DOT: c_int = 0;
+const VOIKKO_OPT_IGNORE_NUMBERS: c_int = 1;
+const VOIKKO_OPT_IGNORE_UPPERCASE: c_int = 3;
+const VOIKKO_OPT_NO_UGLY_HYPHENATION: c_int = 4;
+const VOIKKO_OPT_ACCEPT_FIRST_UPPERCASE: c_int = 6;
+const VOIKKO_OPT_ACCEPT_ALL_UPPERCASE: c_int = 7;
+const VOIKKO_OPT_OCR_SUGGESTIONS: c_int = 8;
+const VOIKKO_OPT_IGNORE_NONWORDS: c_int = 10;
+const VOIKKO_OPT_ACCEPT_EXTRA_HYPHENS: c_int = 11;
+const VOIKKO_OPT_ACCEPT_MISSING_HYPHENS: c_int = 12;
+const VOIKKO_OPT_ACCEPT_TITLES_IN_GC: c_int = 13;
+const VOIKKO_OPT_ACCEPT_UNFINISHED_PARAGRAPHS_IN_GC: c_int = 14;
+const VOIKKO_OPT_HYPHENATE_UNKNOWN_WORDS: c_int = 15;
+const VOIKKO_OPT_ACCEPT_BULLETED_LISTS_IN_GC: c_int = 16;
+const VOIKKO_MIN_HYPHENATED_WORD_LENGTH: c_int = 9;
+const VOIKKO_SPELLER_CACHE_SIZE: c_int = 17;
+
+const VOIKKO_SPELL_FAILED: c_int = 0;
+const VOIKKO_SPELL_OK: c_int = 1;
+const VOIKKO_CHARSET_CONVERSION_FAILED: c_int = 3;
+
+const INDEX_TXT: &str = "index.txt";
+
+/// The handle behind the classic `struct VoikkoHandle *`.
+pub struct ClassicHandle {
+    handle: VoikkoHandle,
+    /// VOIKKO_OPT_IGNORE_DOT, which the classic tokenizer also follows.
+    ignore_dot: bool,
+    /// The last paragraph given to voikkoNextGrammarError* and its errors
+    /// in position order, as callers ask for them one at a time.
+    grammar_cache: Option<(Vec<char>, Vec<GrammarError>)>,
+}
+
+/// A dictionary found by `voikko_list_dicts` (`struct voikko_dict`).
+pub struct Dict {
+    language: CString,
+    script: CString,
+    variant: CString,
+    description: CString,
+}
+
+/// A grammar error returned by voikkoNextGrammarError* (`struct
+/// VoikkoGrammarError`).
+pub struct ClassicGrammarError {
+    error: GrammarError,
+    /// NULL-terminated, or NULL if the error has no suggestions.
+    suggestions: *mut *mut c_char,
+}
+
+/// One analysis returned by voikkoAnalyzeWord* (`struct voikko_mor_analysis`).
+pub struct MorAnalysis {
+    /// Attribute names in sorted order, owning the strings of `keys`.
+    names: Vec<CString>,
+    /// NULL-terminated pointers into `names`.
+    keys: Vec<*const c_char>,
+    /// NUL-terminated values in the order of `names`.
+    values: Vec<Box<[WChar]>>,
+}
+
+// ── Initialization ──────────────────────────────────────────────
+
+/// Initialize a handle for `langcode` ("fi", "fi-FI", "fi-x-standard"),
+/// loading the dictionary from `path` or the default locations.
+///
+/// Returns NULL on failure and stores a static error message in `*error`.
+/// Free the handle with `voikkoTerminate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoInit(
+    error: *mut *const c_char,
+    langcode: *const c_char,
+    path: *const c_char,
+) -> *mut ClassicHandle {
+    let fail = |message: &'static std::ffi::CStr| {
+        if !error.is_null() {
+            unsafe { *error = message.as_ptr() };
+        }
+        ptr::null_mut()
+    };
+    let Some(langcode) = cstr_to_str(langcode) else {
+        return fail(c"Language must not be null");
+    };
+    let (language, variant) = parse_language(langcode);
+    if !language.eq_ignore_ascii_case("fi") {
+        return fail(c"No valid dictionaries were found");
+    }
+    let Ok(mor_path) = dict_path::find_dict_file(cstr_to_str(path), MOR_VFST) else {
+        return fail(c"No valid dictionaries were found");
+    };
+    if let Some(variant) = variant {
+        if !variant.eq_ignore_ascii_case(&read_dict(&mor_path).1) {
+            return fail(c"Specified dictionary variant was not found");
+        }
+    }
+    let Ok(handle) = dict_path::load_handle_from(&mor_path) else {
+        return fail(c"No valid dictionaries were found");
+    };
+    if !error.is_null() {
+        unsafe { *error = ptr::null() };
+    }
+    Box::into_raw(Box::new(ClassicHandle {
+        handle,
+        ignore_dot: false,
+        grammar_cache: None,
+    }))
+}
+
+/// Free a handle created by `voikkoInit`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoTerminate(handle: *mut ClassicHandle) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Set the boolean option `option` (a `VOIKKO_OPT_*` id).
+/// Returns 1 on success, 0 if the option is not known.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSetBooleanOption(
+    handle: *mut ClassicHandle,
+    option: c_int,
+    value: c_int,
+) -> c_int {
+    let Some(classic) = (unsafe { handle.as_mut() }) else {
+        return 0;
+    };
+    let value = value != 0;
+    let handle = &mut classic.handle;
+    match option {
+        VOIKKO_OPT_IGNORE_DOT => {
+            handle.set_ignore_dot(value);
+            classic.ignore_dot = value;
+        }
+        VOIKKO_OPT_IGNORE_NUMBERS => handle.set_ignore_numbers(value),
+        VOIKKO_OPT_IGNORE_UPPERCASE => handle.set_ignore_uppercase(value),
+        VOIKKO_OPT_NO_UGLY_HYPHENATION => handle.set_no_ugly_hyphenation(value),
+        VOIKKO_OPT_ACCEPT_FIRST_UPPERCASE => handle.set_accept_first_uppercase(value),
+        VOIKKO_OPT_ACCEPT_ALL_UPPERCASE => handle.set_accept_all_uppercase(value),
+        VOIKKO_OPT_OCR_SUGGESTIONS => handle.set_ocr_suggestions(value),
+        VOIKKO_OPT_IGNORE_NONWORDS => handle.set_ignore_nonwords(value),
+        VOIKKO_OPT_ACCEPT_EXTRA_HYPHENS => handle.set_accept_extra_hyphens(value),
+        VOIKKO_OPT_ACCEPT_MISSING_HYPHENS => handle.set_accept_missing_hyphens(value),
+        VOIKKO_OPT_ACCEPT_TITLES_IN_GC => handle.set_accept_titles_in_gc(value),
+        VOIKKO_OPT_ACCEPT_UNFINISHED_PARAGRAPHS_IN_GC => {
+            handle.set_accept_unfinished_paragraphs_in_gc(value)
+        }
+        VOIKKO_OPT_HYPHENATE_UNKNOWN_WORDS => handle.set_hyphenate_unknown_words(value),
+        VOIKKO_OPT_ACCEPT_BULLETED_LISTS_IN_GC => handle.set_accept_bulleted_lists_in_gc(value),
+        _ => return 0,
+    }
+    classic.grammar_cache = None;
+    1
+}
+
+/// Set the integer option `option` (`VOIKKO_MIN_HYPHENATED_WORD_LENGTH` or
+/// `VOIKKO_SPELLER_CACHE_SIZE`). Returns 1 on success, 0 if the option is
+/// not known.
+///
+/// A negative cache size, which turns the cache off in libvoikko, gives
+/// the smallest cache.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSetIntegerOption(
+    handle: *mut ClassicHandle,
+    option: c_int,
+    value: c_int,
+) -> c_int {
+    let Some(classic) = (unsafe { handle.as_mut() }) else {
+        return 0;
+    };
+    let value = value.max(0) as usize;
+    match option {
+        VOIKKO_MIN_HYPHENATED_WORD_LENGTH => classic.handle.set_min_hyphenated_word_length(value),
+        VOIKKO_SPELLER_CACHE_SIZE => classic.handle.set_speller_cache_size(value),
+        _ => return 0,
+    }
+    1
+}
+
+// ── Spell checking and suggestions ──────────────────────────────
+
+/// Check the spelling of a UTF-8 word. Returns `VOIKKO_SPELL_OK` (1),
+/// `VOIKKO_SPELL_FAILED` (0) or `VOIKKO_CHARSET_CONVERSION_FAILED` (3) for
+/// invalid UTF-8. An empty word is correct.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSpellCstr(handle: *mut ClassicHandle, word: *const c_char) -> c_int {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return VOIKKO_SPELL_FAILED;
+    };
+    if word.is_null() {
+        return VOIKKO_SPELL_OK;
+    }
+    let Some(word) = cstr_to_str(word) else {
+        return VOIKKO_CHARSET_CONVERSION_FAILED;
+    };
+    spell_code(&classic.handle, word)
+}
+
+/// Check the spelling of a NUL-terminated wide string word.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSpellUcs4(handle: *mut ClassicHandle, word: *const WChar) -> c_int {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return VOIKKO_SPELL_FAILED;
+    };
+    if word.is_null() {
+        return VOIKKO_SPELL_OK;
+    }
+    spell_code(&classic.handle, &wide_to_string(word))
+}
+
+/// Suggest corrections for a misspelled UTF-8 word.
+///
+/// Returns a NULL-terminated array to free with `voikkoFreeCstrArray`, or
+/// NULL if there are no suggestions.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSuggestCstr(
+    handle: *mut ClassicHandle,
+    word: *const c_char,
+) -> *mut *mut c_char {
+    let (Some(classic), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return ptr::null_mut();
+    };
+    let suggestions = classic.handle.suggest(word);
+    if suggestions.is_empty() {
+        return ptr::null_mut();
+    }
+    strings_to_c_array(&suggestions)
+}
+
+/// Suggest corrections for a misspelled wide string word.
+///
+/// Returns a NULL-terminated array to free with `voikko_free_suggest_ucs4`,
+/// or NULL if there are no suggestions.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoSuggestUcs4(
+    handle: *mut ClassicHandle,
+    word: *const WChar,
+) -> *mut *mut WChar {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    if word.is_null() {
+        return ptr::null_mut();
+    }
+    let suggestions = classic.handle.suggest(&wide_to_string(word));
+    if suggestions.is_empty() {
+        return ptr::null_mut();
+    }
+    into_null_terminated(suggestions.iter().map(|s| to_wide(s)).collect())
+}
+
+/// Free an array returned by `voikkoSuggestUcs4`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_suggest_ucs4(suggest_result: *mut *mut WChar) {
+    for s in unsafe { from_null_terminated(suggest_result) } {
+        free_wide(s);
+    }
+}
+
+// ── Hyphenation ─────────────────────────────────────────────────
+
+/// Return the hyphenation pattern of a UTF-8 word (' ' no hyphenation
+/// point, '-' hyphen before the character, '=' hyphen replacing it).
+/// Free with `voikkoFreeCstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoHyphenateCstr(
+    handle: *mut ClassicHandle,
+    word: *const c_char,
+) -> *mut c_char {
+    let (Some(classic), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return ptr::null_mut();
+    };
+    str_to_c(&classic.handle.hyphenate(word))
+}
+
+/// Return the hyphenation pattern of a wide string word. Free with
+/// `voikkoFreeCstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoHyphenateUcs4(
+    handle: *mut ClassicHandle,
+    word: *const WChar,
+) -> *mut c_char {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    if word.is_null() {
+        return ptr::null_mut();
+    }
+    str_to_c(&classic.handle.hyphenate(&wide_to_string(word)))
+}
+
+/// Return a UTF-8 word with `hyphen` inserted at its hyphenation points.
+/// Free with `voikkoFreeCstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoInsertHyphensCstr(
+    handle: *mut ClassicHandle,
+    word: *const c_char,
+    hyphen: *const c_char,
+    allow_context_changes: c_int,
+) -> *mut c_char {
+    let (Some(classic), Some(word), Some(hyphen)) = (
+        unsafe { handle.as_ref() },
+        cstr_to_str(word),
+        cstr_to_str(hyphen),
+    ) else {
+        return ptr::null_mut();
+    };
+    str_to_c(
+        &classic
+            .handle
+            .insert_hyphens(word, hyphen, allow_context_changes != 0),
+    )
+}
+
+/// Free a NULL-terminated string array returned by this API.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoFreeCstrArray(cstr_array: *mut *mut c_char) {
+    free_null_terminated_array(cstr_array);
+}
+
+/// Free a string returned by this API.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoFreeCstr(cstr: *mut c_char) {
+    free_c_str(cstr);
+}
+
+// ── Tokenization and sentence detection ─────────────────────────
+
+/// Return the type of the first token in the first `textlen` characters of
+/// `text` and store its length in characters in `*tokenlen`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextTokenUcs4(
+    handle: *mut ClassicHandle,
+    text: *const WChar,
+    textlen: usize,
+    tokenlen: *mut usize,
+) -> c_int {
+    let chars = unsafe { wide_to_chars(text, textlen) };
+    unsafe { next_token(handle, &chars, tokenlen) }
+}
+
+/// Return the type of the first token in the first `textlen` bytes of the
+/// UTF-8 `text` and store its length in characters in `*tokenlen`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextTokenCstr(
+    handle: *mut ClassicHandle,
+    text: *const c_char,
+    textlen: usize,
+    tokenlen: *mut usize,
+) -> c_int {
+    let Some(chars) = (unsafe { utf8_to_chars(text, textlen) }) else {
+        return 0;
+    };
+    unsafe { next_token(handle, &chars, tokenlen) }
+}
+
+/// Return the type of the sentence boundary that ends the first sentence
+/// in `text` and store the length of that sentence in `*sentencelen`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextSentenceStartUcs4(
+    handle: *mut ClassicHandle,
+    text: *const WChar,
+    textlen: usize,
+    sentencelen: *mut usize,
+) -> c_int {
+    let chars = unsafe { wide_to_chars(text, textlen) };
+    unsafe { next_sentence(handle, &chars, sentencelen) }
+}
+
+/// Same as `voikkoNextSentenceStartUcs4` for the first `textlen` bytes of
+/// the UTF-8 `text`; `*sentencelen` is in characters.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextSentenceStartCstr(
+    handle: *mut ClassicHandle,
+    text: *const c_char,
+    textlen: usize,
+    sentencelen: *mut usize,
+) -> c_int {
+    let Some(chars) = (unsafe { utf8_to_chars(text, textlen) }) else {
+        return 0;
+    };
+    unsafe { next_sentence(handle, &chars, sentencelen) }
+}
+
+// ── Grammar checking ────────────────────────────────────────────
+
+/// Return the first grammar error in the paragraph `text` that starts at or
+/// after `startpos` and comes after the first `skiperrors` errors, or NULL
+/// if there is none. Free with `voikkoFreeGrammarError`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextGrammarErrorUcs4(
+    handle: *mut ClassicHandle,
+    text: *const WChar,
+    wtextlen: usize,
+    startpos: usize,
+    skiperrors: c_int,
+) -> *mut ClassicGrammarError {
+    let chars = unsafe { wide_to_chars(text, wtextlen) };
+    unsafe { next_grammar_error(handle, chars, startpos, skiperrors) }
+}
+
+/// Same as `voikkoNextGrammarErrorUcs4` for the first `textlen` bytes of
+/// the UTF-8 `text`; positions are in characters.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoNextGrammarErrorCstr(
+    handle: *mut ClassicHandle,
+    text: *const c_char,
+    textlen: usize,
+    startpos: usize,
+    skiperrors: c_int,
+) -> *mut ClassicGrammarError {
+    let Some(chars) = (unsafe { utf8_to_chars(text, textlen) }) else {
+        return ptr::null_mut();
+    };
+    unsafe { next_grammar_error(handle, chars, startpos, skiperrors) }
+}
+
+/// Return the error code (a `GCERR_*` value) of a grammar error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetGrammarErrorCode(error: *const ClassicGrammarError) -> c_int {
+    unsafe { error.as_ref() }.map_or(0, |e| e.error.error_code)
+}
+
+/// Return the start position of a grammar error in characters.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetGrammarErrorStartPos(error: *const ClassicGrammarError) -> usize {
+    unsafe { error.as_ref() }.map_or(0, |e| e.error.start_pos)
+}
+
+/// Return the length of a grammar error in characters.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetGrammarErrorLength(error: *const ClassicGrammarError) -> usize {
+    unsafe { error.as_ref() }.map_or(0, |e| e.error.error_len)
+}
+
+/// Return the NULL-terminated suggestions of a grammar error, or NULL. The
+/// array belongs to the error and is freed with it.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetGrammarErrorSuggestions(
+    error: *const ClassicGrammarError,
+) -> *const *const c_char {
+    unsafe { error.as_ref() }.map_or(ptr::null(), |e| e.suggestions as *const *const c_char)
+}
+
+/// Return the description of a grammar error in `language` ("fi" for
+/// Finnish, English otherwise). Free with `voikkoFreeErrorMessageCstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetGrammarErrorShortDescription(
+    error: *const ClassicGrammarError,
+    language: *const c_char,
+) -> *mut c_char {
+    let Some(error) = (unsafe { error.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let error = &error.error;
+    let language = cstr_to_str(language).unwrap_or("en");
+    // Rule pack messages are Finnish; other languages use the generic text.
+    if error.error_code == grammar_error::GCERR_CUSTOM_RULE && language.starts_with("fi") {
+        str_to_c(&error.short_description)
+    } else {
+        str_to_c(grammar_error::error_code_description_lang(
+            error.error_code,
+            language,
+        ))
+    }
+}
+
+/// Free a grammar error returned by voikkoNextGrammarError*.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoFreeGrammarError(error: *mut ClassicGrammarError) {
+    if !error.is_null() {
+        let error = unsafe { Box::from_raw(error) };
+        free_null_terminated_array(error.suggestions);
+    }
+}
+
+/// Free a description returned by `voikkoGetGrammarErrorShortDescription`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoFreeErrorMessageCstr(message: *mut c_char) {
+    free_c_str(message);
+}
+
+// ── Dictionaries ────────────────────────────────────────────────
+
+/// List the dictionaries found in `path` or the default locations. Returns
+/// a NULL-terminated array to free with `voikko_free_dicts`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_list_dicts(path: *const c_char) -> *mut *mut Dict {
+    let dicts = match dict_path::find_dict_file(cstr_to_str(path), MOR_VFST) {
+        Ok(mor_path) => {
+            let (language, variant, description) = read_dict(&mor_path);
+            let c = |s: &str| CString::new(s).unwrap_or_default();
+            vec![Box::into_raw(Box::new(Dict {
+                language: c(&language),
+                script: CString::default(),
+                variant: c(&variant),
+                description: c(&description),
+            }))]
+        }
+        Err(_) => Vec::new(),
+    };
+    into_null_terminated(dicts)
+}
+
+/// Free an array returned by `voikko_list_dicts`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_dicts(dicts: *mut *mut Dict) {
+    for dict in unsafe { from_null_terminated(dicts) } {
+        drop(unsafe { Box::from_raw(dict) });
+    }
+}
+
+/// Return the language code of a dictionary ("fi").
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_dict_language(dict: *const Dict) -> *const c_char {
+    unsafe { dict.as_ref() }.map_or(ptr::null(), |d| d.language.as_ptr())
+}
+
+/// Return the script of a dictionary; empty for the default script.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_dict_script(dict: *const Dict) -> *const c_char {
+    unsafe { dict.as_ref() }.map_or(ptr::null(), |d| d.script.as_ptr())
+}
+
+/// Return the variant of a dictionary ("standard").
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_dict_variant(dict: *const Dict) -> *const c_char {
+    unsafe { dict.as_ref() }.map_or(ptr::null(), |d| d.variant.as_ptr())
+}
+
+/// Return the description of a dictionary from its index.txt.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_dict_description(dict: *const Dict) -> *const c_char {
+    unsafe { dict.as_ref() }.map_or(ptr::null(), |d| d.description.as_ptr())
+}
+
+/// Return the languages with spell checking support in `path` or the
+/// default locations. Free with `voikkoFreeCstrArray`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoListSupportedSpellingLanguages(
+    path: *const c_char,
+) -> *mut *mut c_char {
+    supported_languages(path)
+}
+
+/// Return the languages with hyphenation support. Free with
+/// `voikkoFreeCstrArray`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoListSupportedHyphenationLanguages(
+    path: *const c_char,
+) -> *mut *mut c_char {
+    supported_languages(path)
+}
+
+/// Return the languages with grammar checking support. Free with
+/// `voikkoFreeCstrArray`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoListSupportedGrammarCheckingLanguages(
+    path: *const c_char,
+) -> *mut *mut c_char {
+    supported_languages(path)
+}
+
+/// Return the library version. The string is static; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikkoGetVersion() -> *const c_char {
+    super::voikko_version()
+}
+
+// ── Morphological analysis ──────────────────────────────────────
+
+/// Analyze a wide string word. Returns a NULL-terminated array to free with
+/// `voikko_free_mor_analysis`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoAnalyzeWordUcs4(
+    handle: *mut ClassicHandle,
+    word: *const WChar,
+) -> *mut *mut MorAnalysis {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    if word.is_null() {
+        return ptr::null_mut();
+    }
+    analyze(&classic.handle, &wide_to_string(word))
+}
+
+/// Analyze a UTF-8 word. Returns a NULL-terminated array to free with
+/// `voikko_free_mor_analysis`, or NULL for an empty or invalid word.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoAnalyzeWordCstr(
+    handle: *mut ClassicHandle,
+    word: *const c_char,
+) -> *mut *mut MorAnalysis {
+    let (Some(classic), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return ptr::null_mut();
+    };
+    if word.is_empty() {
+        return ptr::null_mut();
+    }
+    analyze(&classic.handle, word)
+}
+
+/// Free an array returned by voikkoAnalyzeWord*.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_mor_analysis(analysis: *mut *mut MorAnalysis) {
+    for a in unsafe { from_null_terminated(analysis) } {
+        drop(unsafe { Box::from_raw(a) });
+    }
+}
+
+/// Return the NULL-terminated attribute names of an analysis. The array
+/// belongs to the analysis.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_mor_analysis_keys(
+    analysis: *const MorAnalysis,
+) -> *const *const c_char {
+    unsafe { analysis.as_ref() }.map_or(ptr::null(), |a| a.keys.as_ptr())
+}
+
+/// Return the value of the attribute `key`, or NULL if the analysis does
+/// not have it. The string belongs to the analysis.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_mor_analysis_value_ucs4(
+    analysis: *const MorAnalysis,
+    key: *const c_char,
+) -> *const WChar {
+    let (Some(analysis), Some(key)) = (unsafe { analysis.as_ref() }, cstr_to_str(key)) else {
+        return ptr::null();
+    };
+    analysis
+        .names
+        .iter()
+        .position(|name| name.to_bytes() == key.as_bytes())
+        .map_or(ptr::null(), |i| analysis.values[i].as_ptr())
+}
+
+/// Return a UTF-8 copy of the value of the attribute `key`, or NULL. Free
+/// with `voikko_free_mor_analysis_value_cstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_mor_analysis_value_cstr(
+    analysis: *const MorAnalysis,
+    key: *const c_char,
+) -> *mut c_char {
+    let value = unsafe { voikko_mor_analysis_value_ucs4(analysis, key) };
+    if value.is_null() {
+        return ptr::null_mut();
+    }
+    str_to_c(&wide_to_string(value))
+}
+
+/// Free a value returned by `voikko_mor_analysis_value_cstr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_mor_analysis_value_cstr(analysis_value: *mut c_char) {
+    free_c_str(analysis_value);
+}
+
+/// Return the values of an enumerated attribute such as "CLASS", or NULL
+/// if the attribute is not enumerated. Free with `voikkoFreeCstrArray`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikkoGetAttributeValues(
+    _handle: *mut ClassicHandle,
+    attribute_name: *const c_char,
+) -> *mut *mut c_char {
+    match cstr_to_str(attribute_name).and_then(VoikkoHandle::attribute_values) {
+        Some(values) if !values.is_empty() => {
+            strings_to_c_array(&values.iter().map(|v| v.to_string()).collect::<Vec<_>>())
+        }
+        _ => ptr::null_mut(),
+    }
+}
+
+// ── Internal helpers ────────────────────────────────────────────
+
+/// Split a language tag into its language and its private use variant:
+/// "fi-x-standard" is ("fi", Some("standard")), "fi_FI" is ("fi", None).
+fn parse_language(langcode: &str) -> (&str, Option<&str>) {
+    let language = langcode.split(['-', '_']).next().unwrap_or_default();
+    let variant = langcode
+        .split_once("-x-")
+        .map(|(_, variant)| variant)
+        .filter(|v| !v.is_empty());
+    (language, variant)
+}
+
+/// Language, variant and description of the dictionary whose `mor.vfst` is
+/// at `mor_path`, from the index.txt next to it.
+fn read_dict(mor_path: &Path) -> (String, String, String) {
+    let index = std::fs::read_to_string(mor_path.with_file_name(INDEX_TXT)).unwrap_or_default();
+    let field = |name: &str| {
+        index.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let tag = field("Language").unwrap_or_else(|| "fi".to_string());
+    let (language, variant) = parse_language(&tag);
+    (
+        language.to_string(),
+        variant.unwrap_or("standard").to_string(),
+        field("Description").unwrap_or_default(),
+    )
+}
+
+fn supported_languages(path: *const c_char) -> *mut *mut c_char {
+    match dict_path::find_dict_file(cstr_to_str(path), MOR_VFST) {
+        Ok(_) => strings_to_c_array(&["fi".to_string()]),
+        Err(_) => strings_to_c_array(&[]),
+    }
+}
+
+fn spell_code(handle: &VoikkoHandle, word: &str) -> c_int {
+    if word.is_empty() {
+        return VOIKKO_SPELL_OK;
+    }
+    match handle.spell_status(word) {
+        SpellStatus::Correct => VOIKKO_SPELL_OK,
+        SpellStatus::Misspelled | SpellStatus::TooLong | SpellStatus::Truncated => {
+            VOIKKO_SPELL_FAILED
+        }
+    }
+}
+
+unsafe fn next_token(handle: *mut ClassicHandle, chars: &[char], tokenlen: *mut usize) -> c_int {
+    let ignore_dot = unsafe { handle.as_ref() }.is_some_and(|c| c.ignore_dot);
+    let (token_type, len) = tokenizer::next_token_with_options(chars, chars.len(), 0, ignore_dot);
+    if !tokenlen.is_null() {
+        unsafe { *tokenlen = len };
+    }
+    token_type.code()
+}
+
+unsafe fn next_sentence(
+    handle: *mut ClassicHandle,
+    chars: &[char],
+    sentencelen: *mut usize,
+) -> c_int {
+    let Some(classic) = (unsafe { handle.as_ref() }) else {
+        return 0;
+    };
+    let (sentence_type, len) =
+        tokenizer::next_sentence_with_speller(chars, chars.len(), 0, &classic.handle);
+    if !sentencelen.is_null() {
+        unsafe { *sentencelen = len };
+    }
+    sentence_type.code()
+}
+
+unsafe fn next_grammar_error(
+    handle: *mut ClassicHandle,
+    text: Vec<char>,
+    startpos: usize,
+    skiperrors: c_int,
+) -> *mut ClassicGrammarError {
+    let Some(classic) = (unsafe { handle.as_mut() }) else {
+        return ptr::null_mut();
+    };
+    if text.is_empty() {
+        return ptr::null_mut();
+    }
+    let cached = matches!(&classic.grammar_cache, Some((paragraph, _)) if *paragraph == text);
+    if !cached {
+        let mut errors = classic
+            .handle
+            .grammar_errors(&text.iter().collect::<String>());
+        errors.sort_by_key(|e| e.start_pos);
+        classic.grammar_cache = Some((text, errors));
+    }
+    let Some((_, errors)) = &classic.grammar_cache else {
+        return ptr::null_mut();
+    };
+    let skip = usize::try_from(skiperrors).unwrap_or(0);
+    let Some(error) = errors
+        .iter()
+        .enumerate()
+        .find(|(i, e)| *i >= skip && e.start_pos >= startpos)
+        .map(|(_, e)| e.clone())
+    else {
+        return ptr::null_mut();
+    };
+    let suggestions = if error.suggestions.is_empty() {
+        ptr::null_mut()
+    } else {
+        strings_to_c_array(&error.suggestions)
+    };
+    Box::into_raw(Box::new(ClassicGrammarError { error, suggestions }))
+}
+
+fn analyze(handle: &VoikkoHandle, word: &str) -> *mut *mut MorAnalysis {
+    let analyses = handle
+        .analyze(word)
+        .into_iter()
+        .map(|analysis| {
+            let mut attributes: Vec<(&String, &String)> = analysis.attributes().iter().collect();
+            attributes.sort();
+            let names: Vec<CString> = attributes
+                .iter()
+                .map(|(k, _)| CString::new(k.as_str()).unwrap_or_default())
+                .collect();
+            let mut keys: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+            keys.push(ptr::null());
+            let values = attributes.iter().map(|(_, v)| wide(v)).collect();
+            Box::into_raw(Box::new(MorAnalysis {
+                names,
+                keys,
+                values,
+            }))
+        })
+        .collect();
+    into_null_terminated(analyses)
+}
+
+/// Decode the first `len` bytes of `text` as UTF-8.
+unsafe fn utf8_to_chars(text: *const c_char, len: usize) -> Option<Vec<char>> {
+    if text.is_null() {
+        return None;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(text.cast::<u8>(), len) };
+    std::str::from_utf8(bytes).ok().map(|s| s.chars().collect())
+}
+
+/// Read the first `len` units of a wide string, one character per unit.
+#[allow(clippy::useless_conversion)] // `WChar` is u16 on Windows
+unsafe fn wide_to_chars(text: *const WChar, len: usize) -> Vec<char> {
+    if text.is_null() {
+        return Vec::new();
+    }
+    unsafe { std::slice::from_raw_parts(text, len) }
+        .iter()
+        .map(|&unit| char::from_u32(unit.into()).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Read a NUL-terminated wide string.
+fn wide_to_string(text: *const WChar) -> String {
+    let mut len = 0;
+    while unsafe { *text.add(len) } != 0 {
+        len += 1;
+    }
+    unsafe { wide_to_chars(text, len) }.into_iter().collect()
+}
+
+/// Encode `s` as a NUL-terminated wide string.
+fn wide(s: &str) -> Box<[WChar]> {
+    #[cfg(not(windows))]
+    let units = s.chars().map(u32::from);
+    #[cfg(windows)]
+    let units = s.encode_utf16();
+    units.chain([0]).collect()
+}
+
+/// Encode `s` as a NUL-terminated wide string to free with `free_wide`.
+fn to_wide(s: &str) -> *mut WChar {
+    Box::into_raw(wide(s)).cast()
+}
+
+fn free_wide(s: *mut WChar) {
+    if s.is_null() {
+        return;
+    }
+    let mut len = 0;
+    while unsafe { *s.add(len) } != 0 {
+        len += 1;
+    }
+    drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(s, len + 1)) });
+}
+
+/// Turn `items` into a NULL-terminated array to take back with
+/// `from_null_terminated`.
+fn into_null_terminated<T>(items: Vec<*mut T>) -> *mut *mut T {
+    let array: Box<[*mut T]> = items.into_iter().chain([ptr::null_mut()]).collect();
+    Box::into_raw(array).cast()
+}
+
+/// Take back an array made by `into_null_terminated`, without the NULL.
+unsafe fn from_null_terminated<T>(array: *mut *mut T) -> Vec<*mut T> {
+    if array.is_null() {
+        return Vec::new();
+    }
+    let mut len = 0;
+    while !unsafe { *array.add(len) }.is_null() {
+        len += 1;
+    }
+    let array = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(array, len + 1)) };
+    array[..len].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn language_tags() {
+        assert_eq!(parse_language("fi"), ("fi", None));
+        assert_eq!(parse_language("fi_FI"), ("fi", None));
+        assert_eq!(parse_language("fi-FI"), ("fi", None));
+        assert_eq!(parse_language("fi-x-standard"), ("fi", Some("standard")));
+        assert_eq!(parse_language("fi-FI-x-morpho"), ("fi", Some("morpho")));
+        assert_eq!(parse_language("fi-x-"), ("fi", None));
+    }
+
+    #[test]
+    fn null_terminated_arrays_round_trip() {
+        let words = ["koira", "kissa"];
+        let array = into_null_terminated(words.iter().map(|w| to_wide(w)).collect());
+        let items = unsafe { from_null_terminated(array) };
+        let decoded: Vec<String> = items.iter().map(|&w| wide_to_string(w)).collect();
+        assert_eq!(decoded, words);
+        for w in items {
+            free_wide(w);
+        }
+        assert!(unsafe { from_null_terminated::<WChar>(ptr::null_mut()) }.is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_classic_api_with_real_dict() {
+        let mor =
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into());
+        let dir = CString::new(Path::new(&mor).parent().unwrap().to_str().unwrap()).unwrap();
+        let mut error = ptr::null();
+        let handle = unsafe { voikkoInit(&mut error, c"fi".as_ptr(), dir.as_ptr()) };
+        assert!(!handle.is_null());
+        assert!(error.is_null());
+
+        unsafe {
+            assert_eq!(voikkoSpellCstr(handle, c"koira".as_ptr()), VOIKKO_SPELL_OK);
+            assert_eq!(
+                voikkoSpellCstr(handle, c"koirra".as_ptr()),
+                VOIKKO_SPELL_FAILED
+            );
+            assert_eq!(voikkoSpellCstr(handle, c"".as_ptr()), VOIKKO_SPELL_OK);
+
+            let suggestions = voikkoSuggestCstr(handle, c"koirra".as_ptr());
+            assert!(!suggestions.is_null());
+            assert_eq!(CStr::from_ptr(*suggestions), c"koira");
+            voikkoFreeCstrArray(suggestions);
+
+            let text = "Tämä on on virhe.";
+            let error = voikkoNextGrammarErrorCstr(handle, text.as_ptr().cast(), text.len(), 0, 0);
+            assert!(!error.is_null());
+            assert_eq!(
+                voikkoGetGrammarErrorCode(error),
+                grammar_error::GCERR_REPEATING_WORD
+            );
+            assert_eq!(voikkoGetGrammarErrorStartPos(error), 5);
+            voikkoFreeGrammarError(error);
+            let next = voikkoNextGrammarErrorCstr(handle, text.as_ptr().cast(), text.len(), 0, 1);
+            assert!(next.is_null());
+
+            voikkoTerminate(handle);
+        }
+    }
+}
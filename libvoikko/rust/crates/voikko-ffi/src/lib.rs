@@ -14,12 +14,26 @@
 // - Returned analysis/grammar/token/sentence arrays: caller frees with dedicated functions.
 // - All input strings are UTF-8 encoded, null-terminated C strings.
 
-use std::ffi::{CStr, CString, c_char, c_int};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char, c_float, c_int, c_void};
 use std::ptr;
 use std::slice;
-
-use voikko_core::grammar_error;
-use voikko_fi::handle::VoikkoHandle;
+use std::time::Duration;
+
+use voikko_core::analysis::Analysis;
+use voikko_core::catalog::MessageCatalog;
+use voikko_core::grammar_error::{self, GrammarErrorSeverity};
+use voikko_core::token::SentenceWhitespace;
+use voikko_fi::grammar::numbers::NumberStyle;
+use voikko_fi::handle::{SpellStatus, VoikkoError, VoikkoHandle};
+use voikko_fi::input_map::InputMapping;
+use voikko_fi::invisible::InvisibleChars;
+use voikko_fi::morphology::LoadState;
+use voikko_fi::suggestion::{RankingWeights, SuggestionBudget};
+
+/// cbindgen:ignore
+#[cfg(feature = "compat")]
+mod compat;
 
 // ── Handle lifecycle ─────────────────────────────────────────────
 
@@ -60,7 +74,233 @@ pub unsafe extern "C" fn voikko_new(
     }
 }
 
-/// Free a VoikkoHandle created by `voikko_new`.
+/// Create a Voikko handle that defers building the morphology transducer.
+///
+/// Same arguments as `voikko_new`, but only the `mor.vfst` header is
+/// validated, so it returns quickly. Call `voikko_warm_up` (e.g. on a
+/// background thread) to build the transducer before the first check.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_new_lazy(
+    mor_data: *const u8,
+    mor_len: usize,
+    autocorr_data: *const u8,
+    autocorr_len: usize,
+    error_out: *mut *mut c_char,
+) -> *mut VoikkoHandle {
+    if mor_data.is_null() || mor_len == 0 {
+        set_error(error_out, "mor_data is null or empty");
+        return ptr::null_mut();
+    }
+
+    let mor = unsafe { slice::from_raw_parts(mor_data, mor_len) };
+    let autocorr = if autocorr_data.is_null() || autocorr_len == 0 {
+        None
+    } else {
+        Some(unsafe { slice::from_raw_parts(autocorr_data, autocorr_len) })
+    };
+
+    match VoikkoHandle::from_bytes_lazy(mor, autocorr, "fi") {
+        Ok(handle) => Box::into_raw(Box::new(handle)),
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Build the morphology transducer of a handle created by `voikko_new_lazy`.
+///
+/// May be called from another thread while the handle is in use.
+/// Returns 0 on success, -1 on error; on failure, if `error_out` is
+/// non-NULL, it receives an error string to free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_warm_up(
+    handle: *const VoikkoHandle,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    match handle.warm_up() {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Spell and analyze `count` words so that the first checks of them are
+/// answered from the caches, building the transducer of a lazily created
+/// handle first. Meant for application startup.
+///
+/// `words` points to `count` C strings; NULL and invalid UTF-8 words are
+/// skipped. With `threads` above 1, the words are split among that many
+/// threads. Returns 0 on success, -1 on error; on failure, if `error_out`
+/// is non-NULL, it receives an error string to free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_warm_up_words(
+    handle: *const VoikkoHandle,
+    words: *const *const c_char,
+    count: usize,
+    threads: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    if words.is_null() && count > 0 {
+        set_error(error_out, "words is null");
+        return -1;
+    }
+    let words: Vec<&str> = if count == 0 {
+        Vec::new()
+    } else {
+        batch_words(unsafe { slice::from_raw_parts(words, count) })
+            .into_iter()
+            .flatten()
+            .collect()
+    };
+    match handle.warm_up_words(&words, threads) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// `voikko_warm_up_words` with a built-in list of frequent Finnish word
+/// forms.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_warm_up_default(
+    handle: *const VoikkoHandle,
+    threads: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    match handle.warm_up_default(threads) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Return the load state of the morphology transducer:
+/// 0 = pending, 1 = ready, 2 = failed, -1 if the handle is NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_load_state(handle: *const VoikkoHandle) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    match handle.load_state() {
+        LoadState::Pending => 0,
+        LoadState::Ready => 1,
+        LoadState::Failed => 2,
+    }
+}
+
+/// Replace the dictionary of a handle with new `mor.vfst` and optional
+/// `autocorr.vfst` data (NULL to drop the autocorrect transducer).
+///
+/// May be called from another thread while the handle is in use: calls
+/// already running finish with the old dictionary. Options are kept and
+/// the caches are emptied. Returns 0 on success, -1 on error, in which
+/// case the old dictionary stays; on failure, if `error_out` is non-NULL,
+/// it receives an error string to free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_reload(
+    handle: *const VoikkoHandle,
+    mor_data: *const u8,
+    mor_len: usize,
+    autocorr_data: *const u8,
+    autocorr_len: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    if mor_data.is_null() || mor_len == 0 {
+        set_error(error_out, "mor_data is null or empty");
+        return -1;
+    }
+
+    let mor = unsafe { slice::from_raw_parts(mor_data, mor_len) };
+    let autocorr = if autocorr_data.is_null() || autocorr_len == 0 {
+        None
+    } else {
+        Some(unsafe { slice::from_raw_parts(autocorr_data, autocorr_len) })
+    };
+
+    match handle.reload_dictionary(mor, autocorr) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Read the number of dictionary lookups cut short by the traversal budget
+/// since the handle was created or the counters were reset: by the step
+/// limit (the `MAX_LOOP_COUNT` safety limit by default), the output limit
+/// and the deadline. NULL output pointers are skipped. Returns 0, or -1 if
+/// the handle is NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_traversal_stats(
+    handle: *const VoikkoHandle,
+    steps_out: *mut u64,
+    outputs_out: *mut u64,
+    deadline_out: *mut u64,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    let stats = handle.traversal_stats();
+    for (out, value) in [
+        (steps_out, stats.steps),
+        (outputs_out, stats.outputs),
+        (deadline_out, stats.deadline),
+    ] {
+        if !out.is_null() {
+            unsafe { *out = value };
+        }
+    }
+    0
+}
+
+/// List the most recent words whose lookup was cut short by the traversal
+/// budget, oldest first. Empty unless enabled with
+/// `voikko_set_truncation_diagnostics`.
+///
+/// Returns a NULL-terminated array of C strings. Caller must free with
+/// `voikko_free_str_array`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_truncated_lookups(handle: *const VoikkoHandle) -> *mut *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let words: Vec<String> = handle
+        .truncated_lookups()
+        .into_iter()
+        .map(|lookup| lookup.word)
+        .collect();
+    strings_to_c_array(&words)
+}
+
+/// Reset the traversal counters and discard the recorded words.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_reset_traversal_stats(handle: *const VoikkoHandle) {
+    if let Some(handle) = unsafe { handle.as_ref() } {
+        handle.reset_traversal_stats();
+    }
+}
+
+/// Free a VoikkoHandle created by `voikko_new` or `voikko_new_lazy`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn voikko_free(handle: *mut VoikkoHandle) {
     if !handle.is_null() {
@@ -83,6 +323,29 @@ pub unsafe extern "C" fn voikko_spell(handle: *const VoikkoHandle, word: *const
     if handle.spell(word) { 1 } else { 0 }
 }
 
+/// Spell check a word, telling a misspelled word from one too long to check.
+/// Returns 1 for correct, 0 for misspelled, 2 for a word longer than the
+/// maximum word length, 3 for a word rejected after a dictionary lookup ran
+/// out of its traversal budget, -1 on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_spell_status(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return -1;
+    };
+    match handle.spell_status(word) {
+        SpellStatus::Correct => 1,
+        SpellStatus::Misspelled => 0,
+        SpellStatus::TooLong => 2,
+        SpellStatus::Truncated => 3,
+    }
+}
+
 /// Generate spelling suggestions.
 ///
 /// Returns a NULL-terminated array of C strings. Caller must free with
@@ -102,6 +365,137 @@ pub unsafe extern "C" fn voikko_suggest(
     strings_to_c_array(&suggestions)
 }
 
+/// Generate spelling suggestions within a cost and time budget.
+///
+/// `max_cost` limits the number of spell checks (0 or less uses the default
+/// budget); `max_millis` limits the time in milliseconds (0 or less for no
+/// limit). Returns the suggestions found before the budget ran out, as a
+/// NULL-terminated array the caller must free with `voikko_free_str_array`.
+/// Returns NULL on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_suggest_with_budget(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    max_cost: c_int,
+    max_millis: c_int,
+) -> *mut *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return ptr::null_mut();
+    };
+    let budget = SuggestionBudget {
+        max_cost: (max_cost > 0).then_some(max_cost as usize),
+        max_duration: (max_millis > 0).then(|| Duration::from_millis(max_millis as u64)),
+        cancel_token: None,
+    };
+    let suggestions = handle.suggest_with_budget(word, &budget);
+    strings_to_c_array(&suggestions)
+}
+
+/// Check the spelling of a word and suggest corrections if it is misspelled.
+///
+/// Returns 1 for correct, 0 for incorrect, -1 on error. If `suggestions_out`
+/// is non-NULL, it receives NULL for a correct word and otherwise a
+/// NULL-terminated array of suggestions that the caller must free with
+/// `voikko_free_str_array`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_check_word(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    suggestions_out: *mut *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return -1;
+    };
+    let check = handle.check_word(word);
+    if !suggestions_out.is_null() {
+        let suggestions = match &check.suggestions {
+            Some(s) => strings_to_c_array(s),
+            None => ptr::null_mut(),
+        };
+        unsafe { *suggestions_out = suggestions };
+    }
+    if check.correct { 1 } else { 0 }
+}
+
+/// Check the spelling of `count` words in one call.
+///
+/// `words` points to `count` C strings. `results_out` must point to space
+/// for `count` ints and receives, per word, 1 for correct, 0 for incorrect
+/// or -1 if the word is NULL or not valid UTF-8. Large batches are checked
+/// on several threads. Returns 0 on success, -1 on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_spell_batch(
+    handle: *const VoikkoHandle,
+    words: *const *const c_char,
+    count: usize,
+    results_out: *mut c_int,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    if count == 0 {
+        return 0;
+    }
+    if words.is_null() || results_out.is_null() {
+        return -1;
+    }
+    let words = batch_words(unsafe { slice::from_raw_parts(words, count) });
+    let results = unsafe { slice::from_raw_parts_mut(results_out, count) };
+    for_each_batch_chunk(&words, results, |word, result| {
+        *result = match word {
+            Some(word) if handle.spell(word) => 1,
+            Some(_) => 0,
+            None => -1,
+        };
+    });
+    0
+}
+
+/// Generate suggestions for `count` words in one call.
+///
+/// `words` points to `count` C strings. `results_out` must point to space
+/// for `count` pointers and receives, per word, a NULL-terminated string
+/// array like `voikko_suggest` returns (NULL if the word is NULL or not
+/// valid UTF-8). Free each array with `voikko_free_str_array`. Large
+/// batches are processed on several threads. Returns 0 on success, -1 on
+/// error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_suggest_batch(
+    handle: *const VoikkoHandle,
+    words: *const *const c_char,
+    count: usize,
+    results_out: *mut *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    if count == 0 {
+        return 0;
+    }
+    if words.is_null() || results_out.is_null() {
+        return -1;
+    }
+    let words = batch_words(unsafe { slice::from_raw_parts(words, count) });
+    let mut suggestions: Vec<Option<Vec<String>>> = vec![None; count];
+    for_each_batch_chunk(&words, &mut suggestions, |word, result| {
+        *result = word.map(|word| handle.suggest(word));
+    });
+    let results = unsafe { slice::from_raw_parts_mut(results_out, count) };
+    for (result, suggestions) in results.iter_mut().zip(&suggestions) {
+        *result = match suggestions {
+            Some(s) => strings_to_c_array(s),
+            None => ptr::null_mut(),
+        };
+    }
+    0
+}
+
 // ── Morphological analysis ──────────────────────────────────────
 
 /// Opaque analysis result.
@@ -141,14 +535,21 @@ pub unsafe extern "C" fn voikko_analyze(
         return empty;
     };
 
-    let analyses = handle.analyze(word);
+    analyses_to_c(&handle.analyze(word))
+}
+
+/// Convert analyses to a `VoikkoAnalysisArray` for `voikko_free_analyses`.
+fn analyses_to_c(analyses: &[Analysis]) -> VoikkoAnalysisArray {
     let count = analyses.len();
     if count == 0 {
-        return empty;
+        return VoikkoAnalysisArray {
+            analyses: ptr::null_mut(),
+            count: 0,
+        };
     }
 
     let mut c_analyses: Vec<VoikkoAnalysis> = Vec::with_capacity(count);
-    for a in &analyses {
+    for a in analyses {
         let attrs: Vec<(&str, &str)> = a
             .attributes()
             .iter()
@@ -183,61 +584,268 @@ pub unsafe extern "C" fn voikko_analyze(
     }
 }
 
-/// Free an analysis array returned by `voikko_analyze`.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn voikko_free_analyses(arr: VoikkoAnalysisArray) {
-    if arr.analyses.is_null() || arr.count == 0 {
-        return;
-    }
-    let analyses = unsafe { Vec::from_raw_parts(arr.analyses, arr.count, arr.count) };
-    for a in analyses {
-        free_null_terminated_array(a.keys);
-        free_null_terminated_array(a.values);
-    }
+/// An analysis in a `VoikkoAnalysisArena`: a range of its `attributes`.
+#[repr(C)]
+pub struct VoikkoArenaAnalysis {
+    /// Index of the first attribute of the analysis.
+    pub first_attribute: usize,
+    /// Number of attributes of the analysis.
+    pub attribute_count: usize,
 }
 
-// ── Hyphenation ─────────────────────────────────────────────────
+/// An attribute in a `VoikkoAnalysisArena`: byte offsets of its key and
+/// value in the arena's `strings`.
+#[repr(C)]
+pub struct VoikkoArenaAttribute {
+    pub key: usize,
+    pub value: usize,
+}
 
-/// Get the hyphenation pattern for a word.
+/// Analyses of a word in a single allocation.
 ///
-/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+/// The analysis table, the attribute table and the strings are parts of
+/// one memory block, freed with one `voikko_free_analysis_arena` call.
+/// Keys and values are NUL-terminated UTF-8 strings at `strings + offset`;
+/// keys are stored once per arena.
+#[repr(C)]
+pub struct VoikkoAnalysisArena {
+    /// `count` analyses; also the start of the block.
+    pub analyses: *const VoikkoArenaAnalysis,
+    pub count: usize,
+    /// The attributes of all analyses, grouped by analysis.
+    pub attributes: *const VoikkoArenaAttribute,
+    /// The key and value strings.
+    pub strings: *const c_char,
+    /// Size of the block in bytes.
+    pub size: usize,
+}
+
+/// Perform morphological analysis like `voikko_analyze`, returning the
+/// result in one allocation instead of two string arrays per analysis.
+///
+/// Caller must free with `voikko_free_analysis_arena`. Returns a struct
+/// with count=0 on error.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn voikko_hyphenate(
+pub unsafe extern "C" fn voikko_analyze_arena(
     handle: *const VoikkoHandle,
     word: *const c_char,
-) -> *mut c_char {
+) -> VoikkoAnalysisArena {
     let Some(handle) = (unsafe { handle.as_ref() }) else {
-        return ptr::null_mut();
+        return analyses_to_arena(&[]);
     };
     let Some(word) = cstr_to_str(word) else {
-        return ptr::null_mut();
+        return analyses_to_arena(&[]);
     };
-    str_to_c(&handle.hyphenate(word))
+    analyses_to_arena(&handle.analyze(word))
 }
 
-/// Insert hyphens with a custom separator.
+/// Lay analyses out as a `VoikkoAnalysisArena`: the analysis table, the
+/// attribute table and the strings, in a block of `usize` words.
+fn analyses_to_arena(analyses: &[Analysis]) -> VoikkoAnalysisArena {
+    if analyses.is_empty() {
+        return VoikkoAnalysisArena {
+            analyses: ptr::null(),
+            count: 0,
+            attributes: ptr::null(),
+            strings: ptr::null(),
+            size: 0,
+        };
+    }
+
+    let mut strings: Vec<u8> = Vec::new();
+    let mut key_offsets: HashMap<&str, usize> = HashMap::new();
+    fn push_str(strings: &mut Vec<u8>, s: &str) -> usize {
+        let offset = strings.len();
+        strings.extend(s.bytes().filter(|&b| b != 0));
+        strings.push(0);
+        offset
+    }
+    let mut table: Vec<usize> = Vec::with_capacity(analyses.len() * 2);
+    let mut attributes: Vec<usize> = Vec::new();
+    for a in analyses {
+        table.extend([attributes.len() / 2, a.attributes().len()]);
+        for (k, v) in a.attributes() {
+            let key = match key_offsets.get(k.as_str()) {
+                Some(&offset) => offset,
+                None => {
+                    let offset = push_str(&mut strings, k);
+                    key_offsets.insert(k.as_str(), offset);
+                    offset
+                }
+            };
+            attributes.extend([key, push_str(&mut strings, v)]);
+        }
+    }
+
+    let word = size_of::<usize>();
+    let attributes_start = table.len();
+    let strings_start = attributes_start + attributes.len();
+    table.extend(attributes);
+    table.resize(strings_start + strings.len().div_ceil(word), 0);
+    let size = table.len() * word;
+    let base = Box::into_raw(table.into_boxed_slice()).cast::<usize>();
+    unsafe {
+        ptr::copy_nonoverlapping(
+            strings.as_ptr(),
+            base.add(strings_start).cast::<u8>(),
+            strings.len(),
+        );
+    }
+
+    VoikkoAnalysisArena {
+        analyses: base.cast(),
+        count: analyses.len(),
+        attributes: unsafe { base.add(attributes_start).cast() },
+        strings: unsafe { base.add(strings_start).cast() },
+        size,
+    }
+}
+
+/// Free an arena returned by `voikko_analyze_arena`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_analysis_arena(arena: VoikkoAnalysisArena) {
+    if arena.analyses.is_null() || arena.size == 0 {
+        return;
+    }
+    let words = arena.size / size_of::<usize>();
+    let block = ptr::slice_from_raw_parts_mut(arena.analyses.cast_mut().cast::<usize>(), words);
+    drop(unsafe { Box::from_raw(block) });
+}
+
+/// Get the values of one analysis attribute (e.g. "BASEFORM" or "CLASS")
+/// across the analyses of a word, without duplicates.
 ///
-/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+/// Cheaper than `voikko_analyze` when only one attribute is needed: the
+/// full-morphology attributes are computed only when `key` is one of them.
+/// Returns a NULL-terminated array; caller must free with
+/// `voikko_free_str_array`. Returns NULL on error.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn voikko_insert_hyphens(
+pub unsafe extern "C" fn voikko_analyze_attribute(
     handle: *const VoikkoHandle,
     word: *const c_char,
-    separator: *const c_char,
-    allow_context_changes: c_int,
-) -> *mut c_char {
+    key: *const c_char,
+) -> *mut *mut c_char {
     let Some(handle) = (unsafe { handle.as_ref() }) else {
         return ptr::null_mut();
     };
-    let Some(word) = cstr_to_str(word) else {
-        return ptr::null_mut();
-    };
-    let Some(sep) = cstr_to_str(separator) else {
+    let (Some(word), Some(key)) = (cstr_to_str(word), cstr_to_str(key)) else {
         return ptr::null_mut();
     };
-    str_to_c(&handle.insert_hyphens(word, sep, allow_context_changes != 0))
+    strings_to_c_array(&handle.analyze_attribute(word, key))
 }
 
-// ── Grammar checking ────────────────────────────────────────────
+/// Free an analysis array returned by `voikko_analyze`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_analyses(arr: VoikkoAnalysisArray) {
+    if arr.analyses.is_null() || arr.count == 0 {
+        return;
+    }
+    let analyses = unsafe { Vec::from_raw_parts(arr.analyses, arr.count, arr.count) };
+    for a in analyses {
+        free_null_terminated_array(a.keys);
+        free_null_terminated_array(a.values);
+    }
+}
+
+/// Generate the forms of `baseform` with the given attributes.
+///
+/// `keys` and `values` point to `count` attribute names and values, e.g.
+/// SIJAMUOTO=omanto and NUMBER=plural for the genitive plural. Returns a
+/// NULL-terminated array of forms; caller must free with
+/// `voikko_free_str_array`. Returns NULL on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_generate(
+    handle: *const VoikkoHandle,
+    baseform: *const c_char,
+    keys: *const *const c_char,
+    values: *const *const c_char,
+    count: usize,
+) -> *mut *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(baseform) = cstr_to_str(baseform) else {
+        return ptr::null_mut();
+    };
+    let mut target = Analysis::new();
+    if count > 0 {
+        if keys.is_null() || values.is_null() {
+            return ptr::null_mut();
+        }
+        let keys = unsafe { slice::from_raw_parts(keys, count) };
+        let values = unsafe { slice::from_raw_parts(values, count) };
+        for (&key, &value) in keys.iter().zip(values) {
+            let (Some(key), Some(value)) = (cstr_to_str(key), cstr_to_str(value)) else {
+                return ptr::null_mut();
+            };
+            target.set(key, value);
+        }
+    }
+    strings_to_c_array(&handle.generate(baseform, &target))
+}
+
+/// Complete `prefix` to at most `max` dictionary words, sorted
+/// alphabetically.
+///
+/// Returns a NULL-terminated array of words; caller must free with
+/// `voikko_free_str_array`. Returns NULL on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_complete(
+    handle: *const VoikkoHandle,
+    prefix: *const c_char,
+    max: usize,
+) -> *mut *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(prefix) = cstr_to_str(prefix) else {
+        return ptr::null_mut();
+    };
+    strings_to_c_array(&handle.complete(prefix, max))
+}
+
+// ── Hyphenation ─────────────────────────────────────────────────
+
+/// Get the hyphenation pattern for a word.
+///
+/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_hyphenate(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return ptr::null_mut();
+    };
+    str_to_c(&handle.hyphenate(word))
+}
+
+/// Insert hyphens with a custom separator.
+///
+/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_insert_hyphens(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    separator: *const c_char,
+    allow_context_changes: c_int,
+) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return ptr::null_mut();
+    };
+    let Some(sep) = cstr_to_str(separator) else {
+        return ptr::null_mut();
+    };
+    str_to_c(&handle.insert_hyphens(word, sep, allow_context_changes != 0))
+}
+
+// ── Grammar checking ────────────────────────────────────────────
 
 /// Grammar error returned by FFI.
 #[repr(C)]
@@ -247,6 +855,21 @@ pub struct VoikkoGrammarError {
     pub error_len: usize,
     pub short_description: *mut c_char,
     pub suggestions: *mut *mut c_char,
+    /// Severity: 0=Error, 1=Warning
+    pub severity: c_int,
+    /// The edit that applies each suggestion, in the order of
+    /// `suggestions`. NULL when `replacement_count` is 0.
+    pub replacements: *mut VoikkoReplacement,
+    pub replacement_count: usize,
+}
+
+/// Replace `len` characters at `start` with `text`. Offsets are in
+/// characters of the checked text, like `start_pos`.
+#[repr(C)]
+pub struct VoikkoReplacement {
+    pub start: usize,
+    pub len: usize,
+    pub text: *mut c_char,
 }
 
 /// Grammar error array.
@@ -277,22 +900,39 @@ pub unsafe extern "C" fn voikko_grammar_errors(
         return empty;
     };
     let lang = cstr_to_str(language).unwrap_or("fi");
+    grammar_errors_to_c(
+        &handle.grammar_errors_from_text(text),
+        handle.message_catalog(),
+        lang,
+    )
+}
 
-    let errors = handle.grammar_errors_from_text(text);
+/// Convert grammar errors to a `VoikkoGrammarErrorArray` for
+/// `voikko_free_grammar_errors`, with descriptions in `lang` from `catalog`.
+fn grammar_errors_to_c(
+    errors: &[grammar_error::GrammarError],
+    catalog: &MessageCatalog,
+    lang: &str,
+) -> VoikkoGrammarErrorArray {
     let count = errors.len();
     if count == 0 {
-        return empty;
+        return VoikkoGrammarErrorArray {
+            errors: ptr::null_mut(),
+            count: 0,
+        };
     }
 
     let mut c_errors: Vec<VoikkoGrammarError> = Vec::with_capacity(count);
-    for e in &errors {
-        let desc = grammar_error::error_code_description_lang(e.error_code, lang);
+    for e in errors {
         c_errors.push(VoikkoGrammarError {
             error_code: e.error_code,
             start_pos: e.start_pos,
             error_len: e.error_len,
-            short_description: str_to_c(desc),
+            short_description: str_to_c(&catalog.describe(e, lang)),
             suggestions: strings_to_c_array(&e.suggestions),
+            severity: e.severity.code(),
+            replacements: replacements_to_c(&e.replacements),
+            replacement_count: e.replacements.len(),
         });
     }
 
@@ -302,6 +942,23 @@ pub unsafe extern "C" fn voikko_grammar_errors(
     VoikkoGrammarErrorArray { errors: ptr, count }
 }
 
+/// Convert replacement spans to an array of `replacements.len()` entries,
+/// or NULL when there are none.
+fn replacements_to_c(replacements: &[grammar_error::Replacement]) -> *mut VoikkoReplacement {
+    if replacements.is_empty() {
+        return ptr::null_mut();
+    }
+    let c_replacements: Box<[VoikkoReplacement]> = replacements
+        .iter()
+        .map(|r| VoikkoReplacement {
+            start: r.start,
+            len: r.len,
+            text: str_to_c(&r.text),
+        })
+        .collect();
+    Box::into_raw(c_replacements).cast()
+}
+
 /// Free a grammar error array.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn voikko_free_grammar_errors(arr: VoikkoGrammarErrorArray) {
@@ -312,9 +969,183 @@ pub unsafe extern "C" fn voikko_free_grammar_errors(arr: VoikkoGrammarErrorArray
     for e in errors {
         free_c_str(e.short_description);
         free_null_terminated_array(e.suggestions);
+        if !e.replacements.is_null() {
+            let replacements = unsafe {
+                Box::from_raw(ptr::slice_from_raw_parts_mut(
+                    e.replacements,
+                    e.replacement_count,
+                ))
+            };
+            for r in replacements {
+                free_c_str(r.text);
+            }
+        }
     }
 }
 
+// ── Fallible variants ───────────────────────────────────────────
+
+/// Returned by the `voikko_try_*` functions on success.
+pub const VOIKKO_OK: c_int = 0;
+/// A NULL handle or argument, or a string that is not valid UTF-8.
+pub const VOIKKO_ERROR_INVALID_ARGUMENT: c_int = -1;
+/// The word is longer than the maximum word length.
+pub const VOIKKO_ERROR_WORD_TOO_LONG: c_int = -2;
+/// The operation was cancelled.
+pub const VOIKKO_ERROR_CANCELLED: c_int = -3;
+/// The operation did not finish within its time limit.
+pub const VOIKKO_ERROR_TIMED_OUT: c_int = -4;
+/// The dictionary of a handle created by `voikko_new_lazy` could not be
+/// built.
+pub const VOIKKO_ERROR_DICTIONARY: c_int = -5;
+/// Any other error.
+pub const VOIKKO_ERROR_OTHER: c_int = -6;
+
+/// Spell check a word, reporting why it could not be checked.
+///
+/// On success returns `VOIKKO_OK` and, if `correct_out` is non-NULL, stores
+/// 1 for a correct and 0 for a misspelled word in it. Otherwise returns one
+/// of the `VOIKKO_ERROR_*` codes and, if `error_out` is non-NULL, stores an
+/// error string in it that the caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_spell(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    correct_out: *mut c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return invalid_argument(error_out);
+    };
+    try_out(handle.try_spell(word), correct_out, c_int::from, error_out)
+}
+
+/// Generate spelling suggestions, reporting why none could be generated.
+///
+/// On success returns `VOIKKO_OK` and, if `suggestions_out` is non-NULL,
+/// stores a NULL-terminated array in it that the caller must free with
+/// `voikko_free_str_array`. Errors are reported as in `voikko_try_spell`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_suggest(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    suggestions_out: *mut *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return invalid_argument(error_out);
+    };
+    try_out(
+        handle.try_suggest(word),
+        suggestions_out,
+        |s| strings_to_c_array(&s),
+        error_out,
+    )
+}
+
+/// Generate spelling suggestions within a cost and time budget, as in
+/// `voikko_suggest_with_budget`, but return `VOIKKO_ERROR_TIMED_OUT`
+/// instead of the suggestions found if the time limit is reached. Results
+/// and errors are reported as in `voikko_try_suggest`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_suggest_with_budget(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    max_cost: c_int,
+    max_millis: c_int,
+    suggestions_out: *mut *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return invalid_argument(error_out);
+    };
+    let budget = SuggestionBudget {
+        max_cost: (max_cost > 0).then_some(max_cost as usize),
+        max_duration: (max_millis > 0).then(|| Duration::from_millis(max_millis as u64)),
+        cancel_token: None,
+    };
+    try_out(
+        handle.try_suggest_with_budget(word, &budget),
+        suggestions_out,
+        |s| strings_to_c_array(&s),
+        error_out,
+    )
+}
+
+/// Analyze a word, reporting why it could not be analyzed.
+///
+/// On success returns `VOIKKO_OK` and, if `analyses_out` is non-NULL,
+/// stores the analyses in it; the caller must free them with
+/// `voikko_free_analyses`. Errors are reported as in `voikko_try_spell`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_analyze(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    analyses_out: *mut VoikkoAnalysisArray,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return invalid_argument(error_out);
+    };
+    try_out(
+        handle.try_analyze(word),
+        analyses_out,
+        |a| analyses_to_c(&a),
+        error_out,
+    )
+}
+
+/// Get the hyphenation pattern of a word, reporting why it could not be
+/// hyphenated.
+///
+/// On success returns `VOIKKO_OK` and, if `pattern_out` is non-NULL, stores
+/// the pattern in it; the caller must free it with `voikko_free_str`.
+/// Errors are reported as in `voikko_try_spell`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_hyphenate(
+    handle: *const VoikkoHandle,
+    word: *const c_char,
+    pattern_out: *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(word)) = (unsafe { handle.as_ref() }, cstr_to_str(word)) else {
+        return invalid_argument(error_out);
+    };
+    try_out(
+        handle.try_hyphenate(word),
+        pattern_out,
+        |p| str_to_c(&p),
+        error_out,
+    )
+}
+
+/// Check text for grammar errors as in `voikko_grammar_errors`, reporting
+/// why it could not be checked.
+///
+/// On success returns `VOIKKO_OK` and, if `errors_out` is non-NULL, stores
+/// the errors in it; the caller must free them with
+/// `voikko_free_grammar_errors`. Errors are reported as in
+/// `voikko_try_spell`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_try_grammar_errors(
+    handle: *const VoikkoHandle,
+    text: *const c_char,
+    language: *const c_char,
+    errors_out: *mut VoikkoGrammarErrorArray,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let (Some(handle), Some(text)) = (unsafe { handle.as_ref() }, cstr_to_str(text)) else {
+        return invalid_argument(error_out);
+    };
+    let lang = cstr_to_str(language).unwrap_or("fi");
+    try_out(
+        handle.try_grammar_errors_from_text(text),
+        errors_out,
+        |e| grammar_errors_to_c(&e, handle.message_catalog(), lang),
+        error_out,
+    )
+}
+
 // ── Tokenization ────────────────────────────────────────────────
 
 /// Token returned by FFI.
@@ -323,7 +1154,14 @@ pub struct VoikkoToken {
     /// Token type: 1=Word, 2=Punctuation, 3=Whitespace, 4=Unknown, 0=None
     pub token_type: c_int,
     pub text: *mut c_char,
+    /// Start offset in characters.
     pub position: usize,
+    /// Byte range in the UTF-8 input (end exclusive).
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// Range in UTF-16 code units (end exclusive).
+    pub start_utf16: usize,
+    pub end_utf16: usize,
 }
 
 /// Token array.
@@ -362,9 +1200,13 @@ pub unsafe extern "C" fn voikko_tokens(
     let mut c_tokens: Vec<VoikkoToken> = Vec::with_capacity(count);
     for t in &tokens {
         c_tokens.push(VoikkoToken {
-            token_type: token_type_to_int(t.token_type),
+            token_type: t.token_type.code(),
             text: str_to_c(&t.text),
             position: t.pos,
+            start_byte: t.start_byte,
+            end_byte: t.end_byte,
+            start_utf16: t.start_utf16,
+            end_utf16: t.end_utf16,
         });
     }
 
@@ -393,7 +1235,11 @@ pub unsafe extern "C" fn voikko_free_tokens(arr: VoikkoTokenArray) {
 pub struct VoikkoSentence {
     /// Sentence type: 0=None, 1=NoStart, 2=Probable, 3=Possible
     pub sentence_type: c_int,
+    /// Length in characters, with or without the whitespace that follows
+    /// as set with `voikko_set_sentence_whitespace`.
     pub sentence_len: usize,
+    /// Length in characters without the whitespace that follows.
+    pub trimmed_len: usize,
 }
 
 /// Sentence array.
@@ -423,17 +1269,62 @@ pub unsafe extern "C" fn voikko_sentences(
         return empty;
     };
 
-    let sentences = handle.sentences(text);
+    sentences_to_c(handle.sentences(text))
+}
+
+/// Spell check callback for `voikko_sentences_with_spell_check`.
+///
+/// Receives a NUL-terminated UTF-8 word including its trailing dot and the
+/// caller's `user_data`. Returns non-zero if the dot belongs to the word.
+pub type VoikkoSpellCheckCallback =
+    Option<unsafe extern "C" fn(word: *const c_char, user_data: *mut c_void) -> c_int>;
+
+/// Detect sentence boundaries using a custom spell check callback for
+/// abbreviation detection.
+///
+/// Returns a `VoikkoSentenceArray`. Caller must free with `voikko_free_sentences`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_sentences_with_spell_check(
+    handle: *const VoikkoHandle,
+    text: *const c_char,
+    spell_check: VoikkoSpellCheckCallback,
+    user_data: *mut c_void,
+) -> VoikkoSentenceArray {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return sentences_to_c(Vec::new());
+    };
+    let Some(text) = cstr_to_str(text) else {
+        return sentences_to_c(Vec::new());
+    };
+    let Some(callback) = spell_check else {
+        return sentences_to_c(handle.sentences(text));
+    };
+
+    let check = |word: &str| {
+        let Ok(c_word) = CString::new(word) else {
+            return false;
+        };
+        unsafe { callback(c_word.as_ptr(), user_data) != 0 }
+    };
+    sentences_to_c(handle.sentences_with_spell_check(text, &check))
+}
+
+/// Convert sentences into a C array owned by the caller.
+fn sentences_to_c(sentences: Vec<voikko_core::token::Sentence>) -> VoikkoSentenceArray {
     let count = sentences.len();
     if count == 0 {
-        return empty;
+        return VoikkoSentenceArray {
+            sentences: ptr::null_mut(),
+            count: 0,
+        };
     }
 
     let mut c_sentences: Vec<VoikkoSentence> = Vec::with_capacity(count);
     for s in &sentences {
         c_sentences.push(VoikkoSentence {
-            sentence_type: sentence_type_to_int(s.sentence_type),
+            sentence_type: s.sentence_type.code(),
             sentence_len: s.sentence_len,
+            trimmed_len: s.trimmed_len,
         });
     }
 
@@ -455,6 +1346,97 @@ pub unsafe extern "C" fn voikko_free_sentences(arr: VoikkoSentenceArray) {
     drop(unsafe { Vec::from_raw_parts(arr.sentences, arr.count, arr.count) });
 }
 
+/// Sentence with its text and offsets, returned by FFI.
+#[repr(C)]
+pub struct VoikkoSentenceSpan {
+    /// Sentence type: 0=None, 1=NoStart, 2=Probable, 3=Possible
+    pub sentence_type: c_int,
+    pub text: *mut c_char,
+    /// Character range (end exclusive).
+    pub start: usize,
+    pub end: usize,
+    /// Byte range in the UTF-8 input (end exclusive).
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// Range in UTF-16 code units (end exclusive).
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+    /// Ends without the whitespace that follows the sentence, in
+    /// characters, UTF-8 bytes and UTF-16 code units (exclusive).
+    pub trimmed_end: usize,
+    pub trimmed_end_byte: usize,
+    pub trimmed_end_utf16: usize,
+}
+
+/// Sentence span array.
+#[repr(C)]
+pub struct VoikkoSentenceSpanArray {
+    pub spans: *mut VoikkoSentenceSpan,
+    pub count: usize,
+}
+
+/// Detect sentences with their text and offsets.
+///
+/// Returns a `VoikkoSentenceSpanArray`. Caller must free with
+/// `voikko_free_sentence_spans`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_sentence_spans(
+    handle: *const VoikkoHandle,
+    text: *const c_char,
+) -> VoikkoSentenceSpanArray {
+    let empty = VoikkoSentenceSpanArray {
+        spans: ptr::null_mut(),
+        count: 0,
+    };
+
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return empty;
+    };
+    let Some(text) = cstr_to_str(text) else {
+        return empty;
+    };
+
+    let spans = handle.sentence_spans(text);
+    let count = spans.len();
+    if count == 0 {
+        return empty;
+    }
+
+    let mut c_spans: Vec<VoikkoSentenceSpan> = Vec::with_capacity(count);
+    for s in &spans {
+        c_spans.push(VoikkoSentenceSpan {
+            sentence_type: s.sentence_type.code(),
+            text: str_to_c(&s.text),
+            start: s.start,
+            end: s.end,
+            start_byte: s.start_byte,
+            end_byte: s.end_byte,
+            start_utf16: s.start_utf16,
+            end_utf16: s.end_utf16,
+            trimmed_end: s.trimmed_end,
+            trimmed_end_byte: s.trimmed_end_byte,
+            trimmed_end_utf16: s.trimmed_end_utf16,
+        });
+    }
+
+    let ptr = c_spans.as_mut_ptr();
+    std::mem::forget(c_spans);
+
+    VoikkoSentenceSpanArray { spans: ptr, count }
+}
+
+/// Free a sentence span array.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_free_sentence_spans(arr: VoikkoSentenceSpanArray) {
+    if arr.spans.is_null() || arr.count == 0 {
+        return;
+    }
+    let spans = unsafe { Vec::from_raw_parts(arr.spans, arr.count, arr.count) };
+    for s in spans {
+        free_c_str(s.text);
+    }
+}
+
 // ── Option setters ──────────────────────────────────────────────
 
 macro_rules! bool_setter {
@@ -479,6 +1461,11 @@ bool_setter!(
 bool_setter!(voikko_set_accept_all_uppercase, set_accept_all_uppercase);
 bool_setter!(voikko_set_ocr_suggestions, set_ocr_suggestions);
 bool_setter!(voikko_set_ignore_nonwords, set_ignore_nonwords);
+bool_setter!(voikko_set_sentence_spell_check, set_sentence_spell_check);
+bool_setter!(
+    voikko_set_sentence_closing_punctuation,
+    set_sentence_closing_punctuation
+);
 bool_setter!(voikko_set_accept_extra_hyphens, set_accept_extra_hyphens);
 bool_setter!(
     voikko_set_accept_missing_hyphens,
@@ -497,6 +1484,35 @@ bool_setter!(
     voikko_set_accept_bulleted_lists_in_gc,
     set_accept_bulleted_lists_in_gc
 );
+bool_setter!(
+    voikko_set_check_overlong_compounds,
+    set_check_overlong_compounds
+);
+bool_setter!(voikko_set_check_number_formats, set_check_number_formats);
+bool_setter!(
+    voikko_set_check_default_grammar_rules,
+    set_check_default_grammar_rules
+);
+bool_setter!(voikko_set_check_discourse, set_check_discourse);
+bool_setter!(
+    voikko_set_check_paired_conjunctions,
+    set_check_paired_conjunctions
+);
+bool_setter!(
+    voikko_set_check_real_word_errors,
+    set_check_real_word_errors
+);
+bool_setter!(
+    voikko_set_check_subject_verb_agreement,
+    set_check_subject_verb_agreement
+);
+bool_setter!(voikko_set_reflow_paragraphs, set_reflow_paragraphs);
+bool_setter!(
+    voikko_set_truncation_diagnostics,
+    set_truncation_diagnostics
+);
+bool_setter!(voikko_set_prefer_proper_nouns, set_prefer_proper_nouns);
+bool_setter!(voikko_set_match_suggestion_case, set_match_suggestion_case);
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn voikko_set_min_hyphenated_word_length(
@@ -508,6 +1524,20 @@ pub unsafe extern "C" fn voikko_set_min_hyphenated_word_length(
     }
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_max_compound_parts(handle: *mut VoikkoHandle, value: c_int) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_max_compound_parts(value as usize);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_max_compound_chars(handle: *mut VoikkoHandle, value: c_int) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_max_compound_chars(value as usize);
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn voikko_set_max_suggestions(handle: *mut VoikkoHandle, value: c_int) {
     if let Some(handle) = unsafe { handle.as_mut() } {
@@ -515,34 +1545,617 @@ pub unsafe extern "C" fn voikko_set_max_suggestions(handle: *mut VoikkoHandle, v
     }
 }
 
+/// Set the maximum word length in characters (at most 255, the default).
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn voikko_set_speller_cache_size(handle: *mut VoikkoHandle, value: c_int) {
+pub unsafe extern "C" fn voikko_set_max_word_chars(handle: *mut VoikkoHandle, value: c_int) {
     if let Some(handle) = unsafe { handle.as_mut() } {
-        handle.set_speller_cache_size(value as usize);
+        handle.set_max_word_chars(value.max(0) as usize);
     }
 }
 
-// ── Utility functions ───────────────────────────────────────────
-
-/// Return the library version string.
-///
-/// The returned pointer is valid for the lifetime of the library (static).
-/// Do NOT free this pointer.
+/// Set the coefficients of the suggestion ranking costs: edit distance,
+/// weighted transducer path weight and morphological priority.
 #[unsafe(no_mangle)]
-pub extern "C" fn voikko_version() -> *const c_char {
-    static VERSION: std::sync::LazyLock<CString> =
-        std::sync::LazyLock::new(|| CString::new(VoikkoHandle::get_version()).unwrap());
-    VERSION.as_ptr()
+pub unsafe extern "C" fn voikko_set_suggestion_ranking(
+    handle: *mut VoikkoHandle,
+    edit: c_float,
+    fst: c_float,
+    morph: c_float,
+) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_suggestion_ranking(RankingWeights { edit, fst, morph });
+    }
 }
 
-/// Get valid values for an enumerated attribute.
-///
-/// Returns a NULL-terminated array. The returned pointer and its contents are
-/// static — do NOT free them.
-/// Returns NULL if the attribute is not recognized.
+/// Set which typographic character classes are mapped to plain characters
+/// before spell checking and analysis (nonzero = enabled).
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn voikko_attribute_values(name: *const c_char) -> *const *const c_char {
-    let Some(name) = cstr_to_str(name) else {
+pub unsafe extern "C" fn voikko_set_input_mapping(
+    handle: *mut VoikkoHandle,
+    superscripts: c_int,
+    subscripts: c_int,
+    fractions: c_int,
+    typographic_digits: c_int,
+) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_input_mapping(InputMapping {
+            superscripts: superscripts != 0,
+            subscripts: subscripts != 0,
+            fractions: fractions != 0,
+            typographic_digits: typographic_digits != 0,
+        });
+    }
+}
+
+/// Set whether zero-width and control characters are removed before
+/// tokenization and checking (nonzero = strip). Positions still refer to
+/// the original text.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_strip_invisible_chars(handle: *mut VoikkoHandle, value: c_int) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_invisible_chars(if value != 0 {
+            InvisibleChars::Strip
+        } else {
+            InvisibleChars::Keep
+        });
+    }
+}
+
+/// Set whether reported sentences include the whitespace that follows
+/// them up to the next sentence (zero, the default) or end at their last
+/// non-whitespace character (nonzero). Affects `sentence_len` of
+/// `voikko_sentences` and the ends and text of `voikko_sentence_spans`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_sentence_whitespace(
+    handle: *mut VoikkoHandle,
+    trim_end: c_int,
+) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_sentence_whitespace(if trim_end != 0 {
+            SentenceWhitespace::TrimEnd
+        } else {
+            SentenceWhitespace::IncludeTrailingWhitespace
+        });
+    }
+}
+
+/// Set whether the number format check expects technical conventions
+/// (nonzero: "12:30", leading zeros and ISO dates) instead of standard
+/// ones (zero: "klo 12.30", "1.2.2024").
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_technical_number_style(
+    handle: *mut VoikkoHandle,
+    value: c_int,
+) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_number_style(if value != 0 {
+            NumberStyle::Technical
+        } else {
+            NumberStyle::Standard
+        });
+    }
+}
+
+/// Set the severity reported for grammar errors with `code` (0=Error,
+/// 1=Warning). A negative severity restores the code's default; other
+/// values are ignored.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_grammar_error_severity(
+    handle: *mut VoikkoHandle,
+    code: c_int,
+    severity: c_int,
+) {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return;
+    };
+    if severity < 0 {
+        handle.reset_grammar_error_severity(code);
+    } else if let Some(severity) = GrammarErrorSeverity::from_code(severity) {
+        handle.set_grammar_error_severity(code, severity);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_speller_cache_size(handle: *mut VoikkoHandle, value: c_int) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_speller_cache_size(value as usize);
+    }
+}
+
+/// Set how many words the analysis cache holds; 0 or a negative value
+/// disables it.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_set_analysis_cache_size(handle: *mut VoikkoHandle, value: c_int) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.set_analysis_cache_size(usize::try_from(value).unwrap_or(0));
+    }
+}
+
+// ── Grammar rule packs ──────────────────────────────────────────
+
+/// Load a JSON grammar rule pack. The rules are checked in addition to the
+/// built-in grammar checks.
+///
+/// Returns the number of rules loaded, or -1 if the pack is invalid or an
+/// argument is NULL. If the pack is invalid and `error_out` is non-NULL, it
+/// receives a heap-allocated error string that the caller must free with
+/// `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_load_grammar_rules(
+    handle: *mut VoikkoHandle,
+    json: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(json) = cstr_to_str(json) else {
+        return -1;
+    };
+    match handle.load_grammar_rules(json) {
+        Ok(count) => c_int::try_from(count).unwrap_or(c_int::MAX),
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Remove all grammar rules loaded with `voikko_load_grammar_rules`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_clear_grammar_rules(handle: *mut VoikkoHandle) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.clear_grammar_rules();
+    }
+}
+
+// ── Message catalog ─────────────────────────────────────────────
+
+/// Add the entries of a message catalog file to the handle's catalog of
+/// grammar error descriptions and message templates. The file has
+/// `[language]` sections with `code = description` and `key = template`
+/// lines; see `voikko_format_message`.
+///
+/// Returns the number of entries loaded, or -1 if the file is invalid or an
+/// argument is NULL. If the file is invalid and `error_out` is non-NULL, it
+/// receives a heap-allocated error string that the caller must free with
+/// `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_load_message_catalog(
+    handle: *mut VoikkoHandle,
+    text: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(text) = cstr_to_str(text) else {
+        return -1;
+    };
+    match handle.load_message_catalog(text) {
+        Ok(count) => c_int::try_from(count).unwrap_or(c_int::MAX),
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Get the short description of grammar error `code` in `language` (e.g.
+/// "fi", "en-GB") from the handle's message catalog. A language without a
+/// description falls back to its primary subtag, then to English.
+///
+/// Returns a heap-allocated C string the caller must free with
+/// `voikko_free_str`, or NULL if there is no description.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_error_description(
+    handle: *const VoikkoHandle,
+    code: c_int,
+    language: *const c_char,
+) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let Some(language) = cstr_to_str(language) else {
+        return ptr::null_mut();
+    };
+    match handle.message_catalog().description(language, code) {
+        Some(description) => str_to_c(description),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Format the message template `key` (e.g. "did_you_mean") in `language`
+/// from the handle's message catalog, replacing `{0}`, `{1}`, ... with the
+/// `arg_count` strings of `args`.
+///
+/// Returns a heap-allocated C string the caller must free with
+/// `voikko_free_str`, or NULL if there is no such template or an argument
+/// is NULL or not valid UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_format_message(
+    handle: *const VoikkoHandle,
+    key: *const c_char,
+    language: *const c_char,
+    args: *const *const c_char,
+    arg_count: usize,
+) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let (Some(key), Some(language)) = (cstr_to_str(key), cstr_to_str(language)) else {
+        return ptr::null_mut();
+    };
+    let args: Vec<&str> = if arg_count == 0 {
+        Vec::new()
+    } else if args.is_null() {
+        return ptr::null_mut();
+    } else {
+        let args = unsafe { slice::from_raw_parts(args, arg_count) };
+        let Some(args) = args.iter().map(|&arg| cstr_to_str(arg)).collect() else {
+            return ptr::null_mut();
+        };
+        args
+    };
+    match handle.message_catalog().message(language, key, &args) {
+        Some(message) => str_to_c(&message),
+        None => ptr::null_mut(),
+    }
+}
+
+// ── User autocorrect rules ──────────────────────────────────────
+
+/// Add an autocorrect rule replacing `from` with `to`. `from` is literal
+/// text or a pattern between slashes, such as `/(\d+)e/` with the
+/// replacement `$1 €`. User rules are checked before the autocorrect
+/// transducer; a rule with the same `from` is replaced.
+///
+/// Returns 0 on success, or -1 if the rule is invalid or an argument is
+/// NULL. If the rule is invalid and `error_out` is non-NULL, it receives a
+/// heap-allocated error string that the caller must free with
+/// `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_add_autocorrect_rule(
+    handle: *mut VoikkoHandle,
+    from: *const c_char,
+    to: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let (Some(from), Some(to)) = (cstr_to_str(from), cstr_to_str(to)) else {
+        return -1;
+    };
+    match handle.add_autocorrect_rule(from, to) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Remove the autocorrect rule for `from`.
+///
+/// Returns 1 if the rule was removed, 0 if there was none, -1 on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_remove_autocorrect_rule(
+    handle: *mut VoikkoHandle,
+    from: *const c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(from) = cstr_to_str(from) else {
+        return -1;
+    };
+    c_int::from(handle.remove_autocorrect_rule(from))
+}
+
+/// Remove all user autocorrect rules.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_clear_autocorrect_rules(handle: *mut VoikkoHandle) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.clear_autocorrect_rules();
+    }
+}
+
+// ── Hyphenation exceptions ──────────────────────────────────────
+
+/// Load a hyphenation exception list: one word per line with its
+/// hyphenation points marked, e.g. `Voik-ko`. Listed words are hyphenated
+/// as given instead of by analysis.
+///
+/// Returns the number of entries loaded, or -1 if the list is invalid or an
+/// argument is NULL. If the list is invalid and `error_out` is non-NULL, it
+/// receives a heap-allocated error string that the caller must free with
+/// `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_load_hyphenation_exceptions(
+    handle: *mut VoikkoHandle,
+    text: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(text) = cstr_to_str(text) else {
+        return -1;
+    };
+    match handle.load_hyphenation_exceptions(text) {
+        Ok(count) => c_int::try_from(count).unwrap_or(c_int::MAX),
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+/// Remove all hyphenation exceptions.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_clear_hyphenation_exceptions(handle: *mut VoikkoHandle) {
+    if let Some(handle) = unsafe { handle.as_mut() } {
+        handle.clear_hyphenation_exceptions();
+    }
+}
+
+// ── User dictionary ─────────────────────────────────────────────
+
+/// Add a word to the user dictionary. `hint` is an optional inflection class
+/// hint (a dictionary word that inflects the same way) and may be NULL.
+///
+/// Returns 1 on success, -1 if the handle or word is NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_add_user_word(
+    handle: *mut VoikkoHandle,
+    word: *const c_char,
+    hint: *const c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return -1;
+    };
+    handle.add_user_word(word, cstr_to_str(hint));
+    1
+}
+
+/// Remove a word from the user dictionary.
+///
+/// Returns 1 if the word was removed, 0 if it was not present, -1 on error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_remove_user_word(
+    handle: *mut VoikkoHandle,
+    word: *const c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(word) = cstr_to_str(word) else {
+        return -1;
+    };
+    c_int::from(handle.remove_user_word(word))
+}
+
+/// List the words in the user dictionary, in sorted order.
+///
+/// Returns a NULL-terminated array of C strings. Caller must free with
+/// `voikko_free_str_array`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_user_words(handle: *const VoikkoHandle) -> *mut *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let words: Vec<String> = handle
+        .user_dictionary()
+        .iter()
+        .map(|(word, _)| word.to_string())
+        .collect();
+    strings_to_c_array(&words)
+}
+
+/// Serialize the user dictionary into its text file format: one word per
+/// line, optionally followed by a tab and the inflection class hint.
+///
+/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_export_user_dictionary(handle: *const VoikkoHandle) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    str_to_c(&handle.export_user_dictionary())
+}
+
+/// Add the entries of a user dictionary file to the user dictionary.
+///
+/// Returns the number of entries in the file, or -1 if the file is invalid
+/// or an argument is NULL. If the file is invalid and `error_out` is
+/// non-NULL, it receives a heap-allocated error string that the caller must
+/// free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_load_user_dictionary(
+    handle: *mut VoikkoHandle,
+    text: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(text) = cstr_to_str(text) else {
+        return -1;
+    };
+    match handle.load_user_dictionary(text) {
+        Ok(count) => c_int::try_from(count).unwrap_or(c_int::MAX),
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            -1
+        }
+    }
+}
+
+// ── User state ──────────────────────────────────────────────────
+
+/// Export the user state (user dictionary, ignore list, accepted corrections)
+/// as a UTF-8 text blob.
+///
+/// Returns a heap-allocated C string. Caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_export_user_state(handle: *const VoikkoHandle) -> *mut c_char {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    str_to_c(&handle.export_user_state())
+}
+
+/// Replace the user state with a blob produced by `voikko_export_user_state`.
+///
+/// Returns 1 on success, 0 if the blob is invalid, -1 on error.
+/// If the blob is invalid and `error_out` is non-NULL, it receives a
+/// heap-allocated error string that the caller must free with `voikko_free_str`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_import_user_state(
+    handle: *mut VoikkoHandle,
+    blob: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return -1;
+    };
+    let Some(blob) = cstr_to_str(blob) else {
+        return -1;
+    };
+    match handle.import_user_state(blob) {
+        Ok(()) => 1,
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            0
+        }
+    }
+}
+
+// ── ABI versioning ──────────────────────────────────────────────
+
+/// Version of the C ABI: the function signatures and the layout of the
+/// structs. It is incremented whenever either changes incompatibly, so a
+/// binding can refuse to load a library it was not written for.
+pub const VOIKKO_ABI_VERSION: c_int = 3;
+
+/// Return `VOIKKO_ABI_VERSION` of the loaded library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_abi_version() -> c_int {
+    VOIKKO_ABI_VERSION
+}
+
+/// Return `sizeof(VoikkoAnalysis)` as compiled into the library.
+///
+/// The `voikko_sizeof_*` functions let bindings that declare the structs
+/// themselves (ctypes, P/Invoke) check their layouts at load time.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_analysis() -> usize {
+    size_of::<VoikkoAnalysis>()
+}
+
+/// Return `sizeof(VoikkoAnalysisArray)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_analysis_array() -> usize {
+    size_of::<VoikkoAnalysisArray>()
+}
+
+/// Return `sizeof(VoikkoArenaAnalysis)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_arena_analysis() -> usize {
+    size_of::<VoikkoArenaAnalysis>()
+}
+
+/// Return `sizeof(VoikkoArenaAttribute)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_arena_attribute() -> usize {
+    size_of::<VoikkoArenaAttribute>()
+}
+
+/// Return `sizeof(VoikkoAnalysisArena)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_analysis_arena() -> usize {
+    size_of::<VoikkoAnalysisArena>()
+}
+
+/// Return `sizeof(VoikkoGrammarError)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_grammar_error() -> usize {
+    size_of::<VoikkoGrammarError>()
+}
+
+/// Return `sizeof(VoikkoReplacement)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_replacement() -> usize {
+    size_of::<VoikkoReplacement>()
+}
+
+/// Return `sizeof(VoikkoGrammarErrorArray)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_grammar_error_array() -> usize {
+    size_of::<VoikkoGrammarErrorArray>()
+}
+
+/// Return `sizeof(VoikkoToken)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_token() -> usize {
+    size_of::<VoikkoToken>()
+}
+
+/// Return `sizeof(VoikkoTokenArray)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_token_array() -> usize {
+    size_of::<VoikkoTokenArray>()
+}
+
+/// Return `sizeof(VoikkoSentence)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_sentence() -> usize {
+    size_of::<VoikkoSentence>()
+}
+
+/// Return `sizeof(VoikkoSentenceArray)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_sentence_array() -> usize {
+    size_of::<VoikkoSentenceArray>()
+}
+
+/// Return `sizeof(VoikkoSentenceSpan)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_sentence_span() -> usize {
+    size_of::<VoikkoSentenceSpan>()
+}
+
+/// Return `sizeof(VoikkoSentenceSpanArray)` as compiled into the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_sizeof_sentence_span_array() -> usize {
+    size_of::<VoikkoSentenceSpanArray>()
+}
+
+// ── Utility functions ───────────────────────────────────────────
+
+/// Return the library version string.
+///
+/// The returned pointer is valid for the lifetime of the library (static).
+/// Do NOT free this pointer.
+#[unsafe(no_mangle)]
+pub extern "C" fn voikko_version() -> *const c_char {
+    static VERSION: std::sync::LazyLock<CString> =
+        std::sync::LazyLock::new(|| CString::new(VoikkoHandle::get_version()).unwrap());
+    VERSION.as_ptr()
+}
+
+/// Get valid values for an enumerated attribute.
+///
+/// Returns a NULL-terminated array. The returned pointer and its contents are
+/// static — do NOT free them.
+/// Returns NULL if the attribute is not recognized.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn voikko_attribute_values(name: *const c_char) -> *const *const c_char {
+    let Some(name) = cstr_to_str(name) else {
         return ptr::null();
     };
     // We use a static cache to avoid repeated allocation.
@@ -602,12 +2215,86 @@ fn set_error(out: *mut *mut c_char, msg: &str) {
     }
 }
 
+/// Report a NULL or invalid argument of a `voikko_try_*` function.
+fn invalid_argument(error_out: *mut *mut c_char) -> c_int {
+    set_error(error_out, "handle or argument is null or not valid UTF-8");
+    VOIKKO_ERROR_INVALID_ARGUMENT
+}
+
+/// The `VOIKKO_ERROR_*` code of `error`.
+fn error_code(error: &VoikkoError) -> c_int {
+    match error {
+        VoikkoError::WordTooLong { .. } => VOIKKO_ERROR_WORD_TOO_LONG,
+        VoikkoError::Cancelled => VOIKKO_ERROR_CANCELLED,
+        VoikkoError::TimedOut => VOIKKO_ERROR_TIMED_OUT,
+        VoikkoError::MorphologyLoad(_) => VOIKKO_ERROR_DICTIONARY,
+        _ => VOIKKO_ERROR_OTHER,
+    }
+}
+
+/// Finish a `voikko_try_*` function: store the converted value in `out` (if
+/// non-NULL) on success, or the error message in `error_out` on failure,
+/// and return the status code.
+fn try_out<T, C>(
+    result: Result<T, VoikkoError>,
+    out: *mut C,
+    convert: impl FnOnce(T) -> C,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    match result {
+        Ok(value) => {
+            if !out.is_null() {
+                unsafe { out.write(convert(value)) };
+            }
+            VOIKKO_OK
+        }
+        Err(e) => {
+            set_error(error_out, &e.to_string());
+            error_code(&e)
+        }
+    }
+}
+
 fn free_c_str(s: *mut c_char) {
     if !s.is_null() {
         drop(unsafe { CString::from_raw(s) });
     }
 }
 
+/// Minimum batch size that is split across threads.
+const PARALLEL_BATCH_MIN: usize = 1024;
+
+fn batch_words<'a>(words: &[*const c_char]) -> Vec<Option<&'a str>> {
+    words.iter().map(|&w| cstr_to_str(w)).collect()
+}
+
+/// Call `f` for each word and its result slot, splitting large batches
+/// into one chunk per available CPU.
+fn for_each_batch_chunk<T, F>(words: &[Option<&str>], results: &mut [T], f: F)
+where
+    T: Send,
+    F: Fn(Option<&str>, &mut T) + Sync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if words.len() < PARALLEL_BATCH_MIN || threads == 1 {
+        for (word, result) in words.iter().zip(results.iter_mut()) {
+            f(*word, result);
+        }
+        return;
+    }
+    let chunk = words.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        for (words, results) in words.chunks(chunk).zip(results.chunks_mut(chunk)) {
+            let f = &f;
+            scope.spawn(move || {
+                for (word, result) in words.iter().zip(results.iter_mut()) {
+                    f(*word, result);
+                }
+            });
+        }
+    });
+}
+
 fn strings_to_c_array(strings: &[String]) -> *mut *mut c_char {
     let mut ptrs: Vec<*mut c_char> = strings.iter().map(|s| str_to_c(s)).collect();
     ptrs.push(ptr::null_mut()); // NULL terminator
@@ -632,24 +2319,3 @@ fn free_null_terminated_array(arr: *mut *mut c_char) {
     // Free the array itself — we know it was allocated as Vec with capacity i+1
     drop(unsafe { Vec::from_raw_parts(arr, i + 1, i + 1) });
 }
-
-fn token_type_to_int(tt: voikko_core::enums::TokenType) -> c_int {
-    use voikko_core::enums::TokenType;
-    match tt {
-        TokenType::None => 0,
-        TokenType::Word => 1,
-        TokenType::Punctuation => 2,
-        TokenType::Whitespace => 3,
-        TokenType::Unknown => 4,
-    }
-}
-
-fn sentence_type_to_int(st: voikko_core::enums::SentenceType) -> c_int {
-    use voikko_core::enums::SentenceType;
-    match st {
-        SentenceType::None => 0,
-        SentenceType::NoStart => 1,
-        SentenceType::Probable => 2,
-        SentenceType::Possible => 3,
-    }
-}
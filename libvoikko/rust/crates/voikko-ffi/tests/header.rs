@@ -0,0 +1,52 @@
+//! C header snapshot: compares `include/voikko_ffi.h` with the header that
+//! build.rs generates from `src/lib.rs`, so that the checked-in header
+//! cannot drift from the exported functions and structs.
+//!
+//! After a change to the FFI, regenerate the header:
+//! UPDATE_FFI_HEADER=1 cargo test -p voikko-ffi --test header
+
+use std::fs;
+use std::path::Path;
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/voikko_ffi.h"));
+
+#[test]
+fn header_matches_generated() {
+    let header_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/voikko_ffi.h");
+    if std::env::var_os("UPDATE_FFI_HEADER").is_some() {
+        fs::write(&header_path, GENERATED).unwrap();
+        return;
+    }
+    let header = fs::read_to_string(&header_path).unwrap_or_default();
+    assert!(
+        header == GENERATED,
+        "include/voikko_ffi.h is out of date; rerun with UPDATE_FFI_HEADER=1 and review the diff"
+    );
+}
+
+#[test]
+fn header_declares_abi_checks() {
+    assert!(GENERATED.contains("#define VOIKKO_ABI_VERSION "));
+    assert!(GENERATED.contains("int voikko_abi_version(void);"));
+    for name in [
+        "analysis",
+        "analysis_array",
+        "grammar_error",
+        "replacement",
+        "grammar_error_array",
+        "token",
+        "token_array",
+        "sentence",
+        "sentence_array",
+        "sentence_span",
+        "sentence_span_array",
+    ] {
+        let declaration = format!("size_t voikko_sizeof_{name}(void);");
+        assert!(GENERATED.contains(&declaration), "{declaration}");
+    }
+}
+
+#[test]
+fn header_declares_macro_generated_setters() {
+    assert!(GENERATED.contains("void voikko_set_ignore_dot(VoikkoHandle *handle, int value);"));
+}
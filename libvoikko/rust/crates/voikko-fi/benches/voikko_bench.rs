@@ -67,6 +67,34 @@ fn bench_spell_words(c: &mut Criterion) {
     });
 }
 
+/// Spell-check the wordlist through spell_stream(), as `voikko-spell
+/// --stream` does, including record parsing and output formatting.
+fn bench_spell_stream(c: &mut Criterion) {
+    let Some(dict_path) = find_mor_vfst() else {
+        eprintln!("[bench_spell_stream] mor.vfst not found — skipping (set VOIKKO_DICT_PATH)");
+        c.bench_function("spell_stream (skipped)", |b| b.iter(|| {}));
+        return;
+    };
+
+    let mor_data = std::fs::read(&dict_path).expect("failed to read mor.vfst");
+    let handle =
+        voikko_fi::handle::VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("VoikkoHandle");
+    let input = load_wordlist().join("\n");
+    let options = voikko_fi::stream::SpellStreamOptions::default();
+    let mut output = Vec::new();
+
+    c.bench_function("spell_stream_246_words", |b| {
+        b.iter(|| {
+            output.clear();
+            std::hint::black_box(
+                handle
+                    .spell_stream(input.as_bytes(), &mut output, &options)
+                    .unwrap(),
+            );
+        });
+    });
+}
+
 /// Run analyze() on the first 100 words from the wordlist.
 fn bench_analyze_words(c: &mut Criterion) {
     let Some(dict_path) = find_mor_vfst() else {
@@ -269,14 +297,51 @@ fn bench_tokenize(c: &mut Criterion) {
     });
 }
 
+/// Tokenize and grammar-check one long paragraph of clean text, where
+/// per-character classification dominates.
+fn bench_long_paragraph(c: &mut Criterion) {
+    let Some(dict_path) = find_mor_vfst() else {
+        eprintln!("[bench_long_paragraph] mor.vfst not found — skipping (set VOIKKO_DICT_PATH)");
+        c.bench_function("long_paragraph (skipped)", |b| b.iter(|| {}));
+        return;
+    };
+
+    let mor_data = std::fs::read(&dict_path).expect("failed to read mor.vfst");
+    let handle =
+        voikko_fi::handle::VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("VoikkoHandle");
+
+    let text = "Koira juoksi nopeasti metsässä ja näki jäniksen. \
+                Jänis pakeni koloonsa, mutta koira jäi odottamaan. \
+                Lopulta molemmat väsyivät ja menivät nukkumaan. "
+        .repeat(40);
+
+    c.bench_function("tokenize_long_paragraph", |b| {
+        b.iter(|| {
+            std::hint::black_box(handle.tokens(&text));
+        });
+    });
+
+    // Change the text each round so the grammar cache does not answer.
+    let mut round = 0usize;
+    c.bench_function("grammar_long_paragraph", |b| {
+        b.iter(|| {
+            round += 1;
+            let text = format!("{text}{round}.");
+            std::hint::black_box(handle.grammar_errors(&text));
+        });
+    });
+}
+
 criterion_group!(
     benches,
     bench_spell_words,
+    bench_spell_stream,
     bench_analyze_words,
     bench_fst_traverse,
     bench_suggest_misspelled,
     bench_hyphenate_words,
     bench_grammar_check,
     bench_tokenize,
+    bench_long_paragraph,
 );
 criterion_main!(benches);
@@ -12,7 +12,7 @@ fn main() {
     let data = fs::read(&dict_path).expect("Failed to read mor.vfst");
     println!("Loaded mor.vfst: {} bytes\n", data.len());
 
-    let mut analyzer = voikko_fi::morphology::FinnishVfstAnalyzer::from_bytes(&data)
+    let analyzer = voikko_fi::morphology::FinnishVfstAnalyzer::from_bytes(&data)
         .expect("Failed to create analyzer");
 
     let test_words = [
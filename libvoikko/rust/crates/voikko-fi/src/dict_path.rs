@@ -0,0 +1,139 @@
+// Dictionary discovery: find mor.vfst and autocorr.vfst in the standard
+// dictionary directories and load a VoikkoHandle from them.
+//
+// Used by the command-line tools and by the libvoikko-compatible C API, so
+// both search the same directories in the same order.
+//
+// Origin: setup/DictionaryFactory.cpp (getDefaultLocations)
+
+use std::path::{Path, PathBuf};
+
+use crate::handle::{VoikkoError, VoikkoHandle};
+
+/// Dictionary directory within VFST dictionary packages.
+pub const DICT_SUBDIR: &str = "5/mor-standard";
+
+/// Morphology transducer file name.
+pub const MOR_VFST: &str = "mor.vfst";
+
+/// Autocorrect transducer file name.
+pub const AUTOCORR_VFST: &str = "autocorr.vfst";
+
+/// Search for `mor.vfst` and create a [`VoikkoHandle`] from it.
+///
+/// Search order:
+/// 1. `dict_path` (if provided), and its `5/mor-standard` subdirectory
+/// 2. `VOIKKO_DICT_PATH` environment variable
+/// 3. `~/.voikko/5/mor-standard`
+/// 4. The system dictionary directories
+/// 5. Current working directory (looks for `mor.vfst` directly)
+pub fn load_handle(dict_path: Option<&str>) -> Result<VoikkoHandle, VoikkoError> {
+    load_handle_from(&find_dict_file(dict_path, MOR_VFST)?)
+}
+
+/// Create a [`VoikkoHandle`] from the `mor.vfst` file at `mor_path`, with
+/// the `autocorr.vfst` next to it if there is one.
+pub fn load_handle_from(mor_path: &Path) -> Result<VoikkoHandle, VoikkoError> {
+    let mor_data = read(mor_path)?;
+    let autocorr_path = mor_path.with_file_name(AUTOCORR_VFST);
+    let autocorr_data = if autocorr_path.is_file() {
+        Some(read(&autocorr_path)?)
+    } else {
+        None
+    };
+    VoikkoHandle::from_bytes(&mor_data, autocorr_data.as_deref(), "fi")
+}
+
+/// Find the dictionary file `file_name` (e.g. `mor.vfst`), searching the
+/// same directories as [`load_handle`].
+pub fn find_dict_file(dict_path: Option<&str>, file_name: &str) -> Result<PathBuf, VoikkoError> {
+    let searched = search_paths(dict_path);
+    match searched
+        .iter()
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_file())
+    {
+        Some(path) => Ok(path),
+        None => Err(VoikkoError::DictionaryNotFound {
+            file_name: file_name.to_string(),
+            searched,
+        }),
+    }
+}
+
+/// The directories searched for dictionary files, in order.
+pub fn search_paths(dict_path: Option<&str>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    // 1. Explicit path from argument, also in the libvoikko layout
+    if let Some(p) = dict_path {
+        paths.push(PathBuf::from(p));
+        paths.push(PathBuf::from(p).join(DICT_SUBDIR));
+    }
+
+    // 2. VOIKKO_DICT_PATH environment variable
+    if let Ok(env_path) = std::env::var("VOIKKO_DICT_PATH") {
+        paths.push(PathBuf::from(&env_path));
+        // Also check the standard subdirectory within the env path
+        paths.push(PathBuf::from(&env_path).join(DICT_SUBDIR));
+    }
+
+    // 3. Home directory paths
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        paths.push(home.join(".voikko").join(DICT_SUBDIR));
+        // macOS Library/Spelling
+        #[cfg(target_os = "macos")]
+        paths.push(
+            home.join("Library")
+                .join("Spelling")
+                .join("voikko")
+                .join(DICT_SUBDIR),
+        );
+    }
+
+    // 4. System paths
+    paths.push(PathBuf::from("/etc/voikko").join(DICT_SUBDIR));
+    paths.push(PathBuf::from("/usr/lib/voikko").join(DICT_SUBDIR));
+    paths.push(PathBuf::from("/usr/share/voikko").join(DICT_SUBDIR));
+
+    // 5. Current directory (fallback for local development)
+    if let Ok(cwd) = std::env::current_dir() {
+        paths.push(cwd);
+    }
+
+    paths
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, VoikkoError> {
+    std::fs::read(path).map_err(|source| VoikkoError::DictionaryRead {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_path_is_searched_first_also_in_libvoikko_layout() {
+        let paths = search_paths(Some("/opt/dict"));
+        assert_eq!(paths[0], PathBuf::from("/opt/dict"));
+        assert_eq!(paths[1], PathBuf::from("/opt/dict/5/mor-standard"));
+    }
+
+    #[test]
+    fn missing_file_lists_the_searched_directories() {
+        let err = find_dict_file(Some("/nonexistent"), "no-such-file.vfst").unwrap_err();
+        let VoikkoError::DictionaryNotFound {
+            file_name,
+            searched,
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(file_name, "no-such-file.vfst");
+        assert_eq!(searched[0], PathBuf::from("/nonexistent"));
+        assert!(err.to_string().contains("  - /nonexistent\n"));
+    }
+}
@@ -0,0 +1,372 @@
+// Dictionary packs: a dictionary in one zip archive
+//
+// A dictionary pack is a zip archive with the files of one dictionary:
+// `mor.vfst`, optionally `autocorr.vfst`, and the `index.txt` metadata,
+// either at the top level or in a directory such as `5/mor-standard/`.
+// The files are taken from the directory of the `mor.vfst` nearest to the
+// top, so a pack of a whole dictionary tree yields its standard variant.
+// `.zhfst` spellers use the same container; an archive that holds only
+// HFST transducers is reported as such, as there is no HFST backend.
+//
+// Only what dictionary archives use is supported: stored and deflated
+// entries, without zip64 or encryption. The sizes of each extracted file
+// are checked against the central directory.
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+const MOR_VFST: &str = "mor.vfst";
+const AUTOCORR_VFST: &str = "autocorr.vfst";
+const INDEX_TXT: &str = "index.txt";
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_ENTRY: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+/// Size of the end of central directory record without its comment.
+const END_RECORD_LEN: usize = 22;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+/// Error type for dictionary pack failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DictPackError {
+    /// The data is not a zip archive.
+    #[error("not a zip archive")]
+    NotZip,
+
+    /// The archive structure is damaged or truncated.
+    #[error("corrupt zip archive: {0}")]
+    Corrupt(String),
+
+    /// An entry uses a compression method, zip64 or encryption.
+    #[error("unsupported zip entry {name}: {reason}")]
+    Unsupported { name: String, reason: String },
+
+    /// The archive contains HFST transducers but no `mor.vfst`.
+    #[error("HFST transducers are not supported; the archive must contain mor.vfst")]
+    Hfst,
+
+    /// The archive contains no `mor.vfst`.
+    #[error("no mor.vfst in the archive")]
+    MissingMorphology,
+}
+
+/// The dictionary files read from a pack.
+#[derive(Debug, Clone)]
+pub struct DictPack {
+    mor_vfst: Vec<u8>,
+    autocorr_vfst: Option<Vec<u8>>,
+    /// `Key: value` lines of `index.txt`, in file order.
+    metadata: Vec<(String, String)>,
+}
+
+/// A file in the central directory.
+struct Entry<'a> {
+    name: &'a str,
+    method: u16,
+    flags: u16,
+    compressed_size: u32,
+    size: u32,
+    header_offset: u32,
+}
+
+impl DictPack {
+    /// Read the dictionary from the zip archive `archive`.
+    pub fn from_bytes(archive: &[u8]) -> Result<Self, DictPackError> {
+        let entries = central_directory(archive)?;
+        let Some(mor) = entries
+            .iter()
+            .filter(|e| file_name(e.name) == MOR_VFST)
+            .min_by_key(|e| e.name.matches('/').count())
+        else {
+            if entries.iter().any(|e| e.name.ends_with(".hfst")) {
+                return Err(DictPackError::Hfst);
+            }
+            return Err(DictPackError::MissingMorphology);
+        };
+        let dir = &mor.name[..mor.name.len() - MOR_VFST.len()];
+        let sibling = |name: &str| {
+            entries
+                .iter()
+                .find(|e| e.name.strip_prefix(dir) == Some(name))
+        };
+
+        let autocorr_vfst = sibling(AUTOCORR_VFST)
+            .map(|e| extract(archive, e))
+            .transpose()?;
+        let metadata = match sibling(INDEX_TXT) {
+            Some(e) => parse_index(&String::from_utf8_lossy(&extract(archive, e)?)),
+            None => Vec::new(),
+        };
+        Ok(Self {
+            mor_vfst: extract(archive, mor)?,
+            autocorr_vfst,
+            metadata,
+        })
+    }
+
+    /// Contents of `mor.vfst`.
+    pub fn mor_vfst(&self) -> &[u8] {
+        &self.mor_vfst
+    }
+
+    /// Contents of `autocorr.vfst`, if the pack has one.
+    pub fn autocorr_vfst(&self) -> Option<&[u8]> {
+        self.autocorr_vfst.as_deref()
+    }
+
+    /// The value of `key` in `index.txt`, such as "Language" or
+    /// "Description".
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The language tag of the dictionary ("fi-x-standard"), if `index.txt`
+    /// has one.
+    pub fn language(&self) -> Option<&str> {
+        self.metadata("Language")
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parse the `Key: value` lines of `index.txt`.
+fn parse_index(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn corrupt(what: &str) -> DictPackError {
+    DictPackError::Corrupt(what.to_string())
+}
+
+fn u16_at(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// Read the entries of the central directory.
+fn central_directory(archive: &[u8]) -> Result<Vec<Entry<'_>>, DictPackError> {
+    // The end record is followed only by a comment of at most 64 KiB.
+    let search_start = archive
+        .len()
+        .saturating_sub(END_RECORD_LEN + usize::from(u16::MAX));
+    let end = (search_start..=archive.len().saturating_sub(END_RECORD_LEN))
+        .rev()
+        .find(|&pos| u32_at(archive, pos) == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or(DictPackError::NotZip)?;
+    let count = u16_at(archive, end + 10).ok_or(DictPackError::NotZip)?;
+    let offset = u32_at(archive, end + 16).ok_or(DictPackError::NotZip)?;
+    if offset == u32::MAX || count == u16::MAX {
+        return Err(DictPackError::Unsupported {
+            name: String::new(),
+            reason: "zip64 archive".to_string(),
+        });
+    }
+
+    let mut entries = Vec::with_capacity(usize::from(count));
+    let mut pos = offset as usize;
+    for _ in 0..count {
+        if u32_at(archive, pos) != Some(CENTRAL_DIRECTORY_ENTRY) {
+            return Err(corrupt("bad central directory entry"));
+        }
+        let field16 = |at| u16_at(archive, pos + at).ok_or_else(|| corrupt("truncated"));
+        let field32 = |at| u32_at(archive, pos + at).ok_or_else(|| corrupt("truncated"));
+        let name_len = usize::from(field16(28)?);
+        let extra_len = usize::from(field16(30)?);
+        let comment_len = usize::from(field16(32)?);
+        let name = archive
+            .get(pos + 46..pos + 46 + name_len)
+            .ok_or_else(|| corrupt("truncated"))?;
+        entries.push(Entry {
+            name: std::str::from_utf8(name).map_err(|_| corrupt("file name is not UTF-8"))?,
+            flags: field16(8)?,
+            method: field16(10)?,
+            compressed_size: field32(20)?,
+            size: field32(24)?,
+            header_offset: field32(42)?,
+        });
+        pos += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(entries)
+}
+
+/// Extract and decompress the contents of `entry`.
+fn extract(archive: &[u8], entry: &Entry<'_>) -> Result<Vec<u8>, DictPackError> {
+    let unsupported = |reason: &str| DictPackError::Unsupported {
+        name: entry.name.to_string(),
+        reason: reason.to_string(),
+    };
+    if entry.flags & 1 != 0 {
+        return Err(unsupported("encrypted"));
+    }
+    if entry.size == u32::MAX || entry.compressed_size == u32::MAX {
+        return Err(unsupported("zip64 entry"));
+    }
+    let header = entry.header_offset as usize;
+    if u32_at(archive, header) != Some(LOCAL_FILE_HEADER) {
+        return Err(corrupt("bad local file header"));
+    }
+    let name_len = u16_at(archive, header + 26).ok_or_else(|| corrupt("truncated"))?;
+    let extra_len = u16_at(archive, header + 28).ok_or_else(|| corrupt("truncated"))?;
+    let start = header + 30 + usize::from(name_len) + usize::from(extra_len);
+    let data = archive
+        .get(start..start + entry.compressed_size as usize)
+        .ok_or_else(|| corrupt("truncated file data"))?;
+
+    let size = entry.size as usize;
+    let contents = match entry.method {
+        METHOD_STORED => data.to_vec(),
+        METHOD_DEFLATED => decompress_to_vec_with_limit(data, size)
+            .map_err(|_| corrupt(&format!("cannot inflate {}", entry.name)))?,
+        method => return Err(unsupported(&format!("compression method {method}"))),
+    };
+    if contents.len() != size {
+        return Err(corrupt(&format!("size mismatch in {}", entry.name)));
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec;
+
+    /// Build a zip archive; entries with `deflate` set are compressed.
+    fn zip(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut central = Vec::new();
+        for &(name, contents, deflate) in files {
+            let (method, data) = if deflate {
+                (METHOD_DEFLATED, compress_to_vec(contents, 6))
+            } else {
+                (METHOD_STORED, contents.to_vec())
+            };
+            let offset = out.len() as u32;
+            let mut fields = Vec::new();
+            fields.extend(20u16.to_le_bytes()); // version needed
+            fields.extend(0u16.to_le_bytes()); // flags
+            fields.extend(method.to_le_bytes());
+            fields.extend([0; 8]); // time, date, crc
+            fields.extend((data.len() as u32).to_le_bytes());
+            fields.extend((contents.len() as u32).to_le_bytes());
+            fields.extend((name.len() as u16).to_le_bytes());
+            fields.extend(0u16.to_le_bytes()); // extra length
+
+            out.extend(LOCAL_FILE_HEADER.to_le_bytes());
+            out.extend(&fields);
+            out.extend(name.as_bytes());
+            out.extend(&data);
+
+            central.extend(CENTRAL_DIRECTORY_ENTRY.to_le_bytes());
+            central.extend(20u16.to_le_bytes()); // version made by
+            central.extend(&fields);
+            central.extend([0; 10]); // comment length, disk, attributes
+            central.extend(offset.to_le_bytes());
+            central.extend(name.as_bytes());
+        }
+        let central_offset = out.len() as u32;
+        out.extend(&central);
+        out.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        out.extend([0; 4]); // disk numbers
+        out.extend((files.len() as u16).to_le_bytes());
+        out.extend((files.len() as u16).to_le_bytes());
+        out.extend((central.len() as u32).to_le_bytes());
+        out.extend(central_offset.to_le_bytes());
+        out.extend(0u16.to_le_bytes()); // comment length
+        out
+    }
+
+    #[test]
+    fn reads_files_next_to_mor_vfst() {
+        let archive = zip(&[
+            ("README", b"readme", false),
+            (
+                "5/mor-standard/index.txt",
+                b"Language: fi-x-standard\nDescription: suomi\n",
+                false,
+            ),
+            ("5/mor-standard/mor.vfst", b"mor data", true),
+            ("5/mor-standard/autocorr.vfst", b"autocorr data", false),
+            ("5/mor-morpho/mor.vfst/x", b"not a dictionary", false),
+            ("5/mor-standard/extra/mor.vfst", b"deeper", false),
+        ]);
+        let pack = DictPack::from_bytes(&archive).unwrap();
+        assert_eq!(pack.mor_vfst(), b"mor data");
+        assert_eq!(pack.autocorr_vfst(), Some(&b"autocorr data"[..]));
+        assert_eq!(pack.language(), Some("fi-x-standard"));
+        assert_eq!(pack.metadata("Description"), Some("suomi"));
+        assert_eq!(pack.metadata("License"), None);
+    }
+
+    #[test]
+    fn optional_files_may_be_missing() {
+        let pack = DictPack::from_bytes(&zip(&[("mor.vfst", b"mor", true)])).unwrap();
+        assert_eq!(pack.mor_vfst(), b"mor");
+        assert_eq!(pack.autocorr_vfst(), None);
+        assert_eq!(pack.language(), None);
+    }
+
+    #[test]
+    fn reports_missing_dictionary() {
+        assert_eq!(
+            DictPack::from_bytes(&zip(&[("index.txt", b"Language: fi", false)])).unwrap_err(),
+            DictPackError::MissingMorphology
+        );
+        let zhfst = zip(&[
+            ("index.xml", b"<hfstspeller/>", false),
+            ("acceptor.default.hfst", b"hfst", false),
+            ("errmodel.default.hfst", b"hfst", false),
+        ]);
+        assert_eq!(
+            DictPack::from_bytes(&zhfst).unwrap_err(),
+            DictPackError::Hfst
+        );
+    }
+
+    #[test]
+    fn rejects_damaged_archives() {
+        assert_eq!(
+            DictPack::from_bytes(b"").unwrap_err(),
+            DictPackError::NotZip
+        );
+        assert_eq!(
+            DictPack::from_bytes(b"VFST\0\0\0\0 not a zip").unwrap_err(),
+            DictPackError::NotZip
+        );
+        let archive = zip(&[("mor.vfst", b"mor data that is long enough", true)]);
+        // Cut into the central directory
+        let truncated = [&archive[..40], &archive[archive.len() - END_RECORD_LEN..]].concat();
+        assert!(matches!(
+            DictPack::from_bytes(&truncated),
+            Err(DictPackError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_entries() {
+        let mut archive = zip(&[("mor.vfst", b"mor", false)]);
+        // Compression method 12 (bzip2) in the central directory entry
+        let central = archive.len() - END_RECORD_LEN - 46 - "mor.vfst".len();
+        archive[central + 10] = 12;
+        assert_eq!(
+            DictPack::from_bytes(&archive).unwrap_err(),
+            DictPackError::Unsupported {
+                name: "mor.vfst".to_string(),
+                reason: "compression method 12".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Streaming spell checking for large documents.
+//
+// Reads a document line by line from a `BufRead`, tokenizes each line with
+// the regular tokenizer and yields the misspelled words together with their
+// positions in the whole document. Only one line is held in memory at a
+// time, so memory use is bounded by the longest line, not the document:
+// multi-megabyte inputs with ordinary line lengths can be checked from CLI
+// tools and servers. A document without line breaks is read whole.
+//
+// Word tokens never contain line breaks, so splitting the input at '\n'
+// does not change tokenization of words.
+//
+// With `InvisibleChars::Strip`, zero-width and control characters do not
+// split words; a reported word keeps them, and its offsets and length refer
+// to the document as read.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+use voikko_core::enums::TokenType;
+
+use crate::invisible::{self, InvisibleChars};
+use crate::tokenizer;
+
+/// A misspelled word found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellingIssue {
+    /// The misspelled word.
+    pub word: String,
+    /// Byte offset of the word from the start of the document (UTF-8).
+    pub byte_offset: usize,
+    /// Character offset of the word from the start of the document.
+    pub char_offset: usize,
+    /// Length of the word in characters.
+    pub char_len: usize,
+    /// Zero-based line number of the word.
+    pub line: usize,
+    /// Character offset of the word within its line.
+    pub column: usize,
+}
+
+/// Iterator over the misspelled words of a document.
+///
+/// Created by [`check_document_with`] or `VoikkoHandle::check_document()`.
+/// Yields an `Err` if reading from the underlying reader fails (including
+/// invalid UTF-8); iteration stops after the first error.
+pub struct DocumentIssues<R, F> {
+    reader: R,
+    spell: F,
+    line_buf: String,
+    pending: VecDeque<SpellingIssue>,
+    byte_offset: usize,
+    char_offset: usize,
+    line: usize,
+    invisible_chars: InvisibleChars,
+    done: bool,
+}
+
+/// Check a document read from `reader`, using `spell` to decide whether a
+/// word is correct.
+pub fn check_document_with<R, F>(reader: R, spell: F) -> DocumentIssues<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> bool,
+{
+    DocumentIssues {
+        reader,
+        spell,
+        line_buf: String::new(),
+        pending: VecDeque::new(),
+        byte_offset: 0,
+        char_offset: 0,
+        line: 0,
+        invisible_chars: InvisibleChars::default(),
+        done: false,
+    }
+}
+
+impl<R, F> DocumentIssues<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> bool,
+{
+    /// Set how zero-width and control characters are handled. With
+    /// [`InvisibleChars::Strip`], `spell` receives words without them.
+    pub fn with_invisible_chars(mut self, policy: InvisibleChars) -> Self {
+        self.invisible_chars = policy;
+        self
+    }
+
+    /// Read and check the next line. Returns `Ok(false)` at end of input.
+    fn check_next_line(&mut self) -> io::Result<bool> {
+        self.line_buf.clear();
+        if self.reader.read_line(&mut self.line_buf)? == 0 {
+            return Ok(false);
+        }
+
+        let tokens = match self.invisible_chars {
+            InvisibleChars::Keep => tokenizer::tokenize(&self.line_buf),
+            InvisibleChars::Strip => invisible::tokenize_stripped(&self.line_buf),
+        };
+        for token in tokens {
+            if token.token_type != TokenType::Word {
+                continue;
+            }
+            let correct = match self.invisible_chars {
+                InvisibleChars::Keep => (self.spell)(&token.text),
+                InvisibleChars::Strip => (self.spell)(&invisible::strip_word(&token.text)),
+            };
+            if !correct {
+                self.pending.push_back(SpellingIssue {
+                    byte_offset: self.byte_offset + token.start_byte,
+                    char_offset: self.char_offset + token.pos,
+                    char_len: token.token_len,
+                    line: self.line,
+                    column: token.pos,
+                    word: token.text,
+                });
+            }
+        }
+
+        self.byte_offset += self.line_buf.len();
+        self.char_offset += self.line_buf.chars().count();
+        self.line += 1;
+        Ok(true)
+    }
+}
+
+impl<R, F> Iterator for DocumentIssues<R, F>
+where
+    R: BufRead,
+    F: Fn(&str) -> bool,
+{
+    type Item = io::Result<SpellingIssue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(issue) = self.pending.pop_front() {
+                return Some(Ok(issue));
+            }
+            if self.done {
+                return None;
+            }
+            match self.check_next_line() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accept only words listed in `known`.
+    fn issues(text: &str, known: &[&str]) -> Vec<SpellingIssue> {
+        check_document_with(text.as_bytes(), |w: &str| known.contains(&w))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn empty_document() {
+        assert!(issues("", &[]).is_empty());
+    }
+
+    #[test]
+    fn all_words_correct() {
+        assert!(issues("koira ja kissa.\n", &["koira", "ja", "kissa"]).is_empty());
+    }
+
+    #[test]
+    fn positions_on_single_line() {
+        let found = issues("koira kisssa.", &["koira"]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].word, "kisssa");
+        assert_eq!(found[0].char_offset, 6);
+        assert_eq!(found[0].byte_offset, 6);
+        assert_eq!(found[0].char_len, 6);
+        assert_eq!(found[0].line, 0);
+        assert_eq!(found[0].column, 6);
+    }
+
+    #[test]
+    fn offsets_accumulate_across_lines() {
+        // "äiti" has 4 chars and 5 bytes; "\r\n" is two chars.
+        let text = "äiti\r\nkoira vääärä\nhölmö";
+        let found = issues(text, &["äiti", "koira"]);
+        assert_eq!(found.len(), 2);
+
+        assert_eq!(found[0].word, "vääärä");
+        assert_eq!(found[0].line, 1);
+        assert_eq!(found[0].column, 6);
+        assert_eq!(found[0].char_offset, 12);
+        assert_eq!(found[0].byte_offset, text.find("vääärä").unwrap());
+
+        assert_eq!(found[1].word, "hölmö");
+        assert_eq!(found[1].line, 2);
+        assert_eq!(found[1].column, 0);
+        assert_eq!(found[1].byte_offset, text.find("hölmö").unwrap());
+        assert_eq!(
+            found[1].char_offset,
+            text[..found[1].byte_offset].chars().count()
+        );
+    }
+
+    #[test]
+    fn small_buffer_reader() {
+        // A tiny BufReader capacity must not split words.
+        let text = "pitkäsana toinenpitkäsana\nkolmas";
+        let reader = io::BufReader::with_capacity(3, text.as_bytes());
+        let found: Vec<_> = check_document_with(reader, |_: &str| false)
+            .map(|r| r.unwrap().word)
+            .collect();
+        assert_eq!(found, vec!["pitkäsana", "toinenpitkäsana", "kolmas"]);
+    }
+
+    #[test]
+    fn zero_width_space_splits_word_unless_stripped() {
+        // Copied from a web page: a BOM and a soft line break opportunity
+        let text = "\u{FEFF}Linja-auto\u{200B}asema on kiinni.\n";
+        let known = ["Linja-autoasema", "on", "kiinni"];
+        let kept = issues(text, &known);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].word, "Linja-auto");
+
+        let stripped: Vec<_> = check_document_with(text.as_bytes(), |w: &str| known.contains(&w))
+            .with_invisible_chars(InvisibleChars::Strip)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert!(stripped.is_empty());
+
+        let unknown: Vec<_> = check_document_with(text.as_bytes(), |w: &str| w == "on")
+            .with_invisible_chars(InvisibleChars::Strip)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(unknown[0].word, "\u{FEFF}Linja-auto\u{200B}asema");
+        assert_eq!(unknown[0].char_offset, 0);
+        assert_eq!(unknown[0].char_len, 17);
+        assert_eq!(unknown[1].column, 21);
+        assert_eq!(unknown[1].byte_offset, text.find("kiinni").unwrap());
+    }
+
+    #[test]
+    fn invalid_utf8_yields_error_and_stops() {
+        let data: &[u8] = b"koira\n\xff\xfe\nkissa\n";
+        let mut iter = check_document_with(data, |_: &str| false);
+        assert_eq!(iter.next().unwrap().unwrap().word, "koira");
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+}
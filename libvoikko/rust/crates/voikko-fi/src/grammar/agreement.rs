@@ -0,0 +1,590 @@
+// Subject-verb agreement check
+//
+// Opt-in, experimental check for a finite verb whose person or number does
+// not agree with the nominative subject directly before it: "koirat juoksee"
+// instead of "koirat juoksevat", "he on" instead of "he ovat". Only the
+// simplest clause is checked, a subject that starts the sentence or follows
+// punctuation and is followed by the verb, and the check is conservative:
+// - the subject must be a common noun or a personal or demonstrative
+//   pronoun in every analysis, so proper names ("Yhdysvallat on") and
+//   numerals are skipped;
+// - every analysis of the verb must be a finite form with a person and a
+//   number other than the imperative, so homographs such as "juoksevat"
+//   (also a participle) are skipped;
+// - after a noun or a demonstrative, only 3rd person verbs are reported,
+//   since a fronted nominative object may precede a 1st or 2nd person verb
+//   ("kirjat ostin");
+// - a verb followed by a past passive participle is skipped, since the
+//   nominative is then the object of a passive ("talot on rakennettu").
+// Suggestions are the verb generated in the person and number of the
+// subject.
+
+use voikko_core::analysis::{
+    ATTR_BASEFORM, ATTR_CLASS, ATTR_MOOD, ATTR_NUMBER, ATTR_PARTICIPLE, ATTR_PERSON,
+    ATTR_SIJAMUOTO, ATTR_TENSE, Analysis,
+};
+use voikko_core::character::{is_upper, simple_upper};
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{GCERR_SUBJECT_VERB_AGREEMENT, GrammarError};
+
+use super::checks::{GrammarOptions, GrammarSentence, GrammarToken};
+use crate::morphology::Analyzer;
+
+/// Personal pronouns and their person.
+const PERSONAL_PRONOUNS: &[(&str, u8)] = &[
+    ("minä", 1),
+    ("sinä", 2),
+    ("hän", 3),
+    ("me", 1),
+    ("te", 2),
+    ("he", 3),
+];
+
+/// Demonstrative pronouns, which take a 3rd person verb like nouns.
+const DEMONSTRATIVE_PRONOUNS: &[&str] = &["se", "ne", "tämä", "nämä", "tuo", "nuo"];
+
+/// Word classes of a finite verb form; "ei" is a "kieltosana".
+const VERB_CLASSES: &[&str] = &["teonsana", "kieltosana"];
+
+/// Most words after the verb searched for a past passive participle
+/// ("ei ole rakennettu").
+const MAX_PARTICIPLE_DISTANCE: usize = 2;
+
+/// Person and number of a subject or of a finite verb form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PersonNumber {
+    person: u8,
+    plural: bool,
+}
+
+impl PersonNumber {
+    /// The person and number of `analysis`, if it has both. Passive forms
+    /// (PERSON=4) have none.
+    fn of(analysis: &Analysis) -> Option<Self> {
+        let person = match analysis.get(ATTR_PERSON)? {
+            "1" => 1,
+            "2" => 2,
+            "3" => 3,
+            _ => return None,
+        };
+        let plural = match analysis.get(ATTR_NUMBER)? {
+            "singular" => false,
+            "plural" => true,
+            _ => return None,
+        };
+        Some(Self { person, plural })
+    }
+
+    fn number(self) -> &'static str {
+        if self.plural { "plural" } else { "singular" }
+    }
+}
+
+/// One reading of a subject.
+#[derive(Debug, Clone, Copy)]
+struct Subject {
+    agreement: PersonNumber,
+    /// A personal pronoun, whose verb must have its person; otherwise only
+    /// 3rd person verbs are compared.
+    personal: bool,
+}
+
+impl Subject {
+    /// Whether `verb` certainly does not agree with this subject.
+    fn conflicts_with(self, verb: PersonNumber) -> bool {
+        if self.personal {
+            verb != self.agreement
+        } else {
+            verb.person == 3 && verb.plural != self.agreement.plural
+        }
+    }
+}
+
+/// Report finite verbs that disagree with the nominative subject directly
+/// before them. Needs `analyzer`; without one nothing is reported.
+pub(crate) fn gc_subject_verb_agreement(
+    sentence: &GrammarSentence,
+    analyzer: Option<&dyn Analyzer>,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_subject_verb_agreement {
+        return Vec::new();
+    }
+    let Some(analyzer) = analyzer else {
+        return Vec::new();
+    };
+    let tokens = &sentence.tokens;
+    let mut errors = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.token_type == TokenType::Word && starts_clause(tokens, i) {
+            errors.extend(agreement_error(tokens, i, analyzer));
+        }
+    }
+    errors
+}
+
+/// Whether the word at `index` starts the sentence or follows punctuation.
+fn starts_clause(tokens: &[GrammarToken], index: usize) -> bool {
+    tokens[..index]
+        .iter()
+        .rev()
+        .find(|t| t.token_type != TokenType::Whitespace)
+        .is_none_or(|t| t.token_type == TokenType::Punctuation)
+}
+
+/// The error for the verb after the subject at `subject`, if they
+/// disagree.
+fn agreement_error(
+    tokens: &[GrammarToken],
+    subject: usize,
+    analyzer: &dyn Analyzer,
+) -> Option<GrammarError> {
+    let readings = subject_readings(&analyze(&tokens[subject], analyzer))?;
+    let verb = next_word(tokens, subject)?;
+    let verb_analyses = analyze(&tokens[verb], analyzer);
+    if verb_analyses.is_empty() {
+        return None;
+    }
+    let mut forms = Vec::with_capacity(verb_analyses.len());
+    for analysis in &verb_analyses {
+        let finite = analysis
+            .get(ATTR_CLASS)
+            .is_some_and(|c| VERB_CLASSES.contains(&c))
+            && analysis.get(ATTR_MOOD) != Some("imperative");
+        let form = PersonNumber::of(analysis).filter(|_| finite)?;
+        if !readings.iter().all(|r| r.conflicts_with(form)) {
+            return None;
+        }
+        forms.push(analysis);
+    }
+    if followed_by_passive_participle(tokens, verb, analyzer) {
+        return None;
+    }
+
+    // A personal pronoun reading decides the suggested person ("minä" is
+    // also a noun).
+    let reading = readings.iter().find(|r| r.personal).unwrap_or(&readings[0]);
+    let token = &tokens[verb];
+    let mut suggestions: Vec<String> = Vec::new();
+    for analysis in forms {
+        for form in reinflect(token, analysis, reading.agreement, analyzer) {
+            if !suggestions.contains(&form) {
+                suggestions.push(form);
+            }
+        }
+    }
+    Some(GrammarError::with_suggestions(
+        GCERR_SUBJECT_VERB_AGREEMENT,
+        token.pos,
+        token.token_len(),
+        suggestions,
+    ))
+}
+
+/// The readings of a word as a subject, if every analysis is a nominative
+/// common noun or personal or demonstrative pronoun.
+fn subject_readings(analyses: &[Analysis]) -> Option<Vec<Subject>> {
+    if analyses.is_empty() {
+        return None;
+    }
+    analyses
+        .iter()
+        .map(|analysis| {
+            if analysis.get(ATTR_SIJAMUOTO) != Some("nimento") {
+                return None;
+            }
+            let plural = match analysis.get(ATTR_NUMBER)? {
+                "singular" => false,
+                "plural" => true,
+                _ => return None,
+            };
+            let (person, personal) = match analysis.get(ATTR_CLASS)? {
+                "nimisana" => (3, false),
+                "asemosana" => {
+                    let baseform = analysis.get(ATTR_BASEFORM)?;
+                    if let Some(&(_, person)) =
+                        PERSONAL_PRONOUNS.iter().find(|(b, _)| *b == baseform)
+                    {
+                        (person, true)
+                    } else if DEMONSTRATIVE_PRONOUNS.contains(&baseform) {
+                        (3, false)
+                    } else {
+                        return None;
+                    }
+                }
+                _ => return None,
+            };
+            Some(Subject {
+                agreement: PersonNumber { person, plural },
+                personal,
+            })
+        })
+        .collect()
+}
+
+/// Whether a past passive participle follows the verb at `verb` within
+/// `MAX_PARTICIPLE_DISTANCE` words.
+fn followed_by_passive_participle(
+    tokens: &[GrammarToken],
+    verb: usize,
+    analyzer: &dyn Analyzer,
+) -> bool {
+    let mut last = verb;
+    for _ in 0..MAX_PARTICIPLE_DISTANCE {
+        let Some(next) = next_word(tokens, last) else {
+            return false;
+        };
+        if analyze(&tokens[next], analyzer)
+            .iter()
+            .any(|a| a.get(ATTR_PARTICIPLE) == Some("past_passive"))
+        {
+            return true;
+        }
+        last = next;
+    }
+    false
+}
+
+/// The word token after the token at `index`, if only whitespace is
+/// between them.
+fn next_word(tokens: &[GrammarToken], index: usize) -> Option<usize> {
+    let whitespace = tokens.get(index + 1)?;
+    let word = tokens.get(index + 2)?;
+    (whitespace.token_type == TokenType::Whitespace && word.token_type == TokenType::Word)
+        .then_some(index + 2)
+}
+
+/// The forms of the verb of `analysis` in the person and number of
+/// `agreement`, in the same mood and tense, capitalized as `token` is.
+fn reinflect(
+    token: &GrammarToken,
+    analysis: &Analysis,
+    agreement: PersonNumber,
+    analyzer: &dyn Analyzer,
+) -> Vec<String> {
+    let Some(baseform) = analysis.get(ATTR_BASEFORM) else {
+        return Vec::new();
+    };
+    let mut target = Analysis::new();
+    for key in [ATTR_CLASS, ATTR_MOOD, ATTR_TENSE] {
+        if let Some(value) = analysis.get(key) {
+            target.set(key, value);
+        }
+    }
+    target.set(ATTR_PERSON, agreement.person.to_string());
+    target.set(ATTR_NUMBER, agreement.number());
+    let capitalize = token.text.first().is_some_and(|&c| is_upper(c));
+    analyzer
+        .generate(baseform, &target)
+        .into_iter()
+        .map(|form| {
+            if !capitalize {
+                return form;
+            }
+            let mut chars = form.chars();
+            chars
+                .next()
+                .map(|first| std::iter::once(simple_upper(first)).chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn analyze(token: &GrammarToken, analyzer: &dyn Analyzer) -> Vec<Analysis> {
+    analyzer.analyze_any_case(&token.text, token.text.len()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::test_util::sentence;
+
+    type Entry = (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+    );
+
+    /// Analyses and generated forms of a handful of words. Each entry is
+    /// (word, base form, class, case, number, person, mood, participle);
+    /// `generate` returns the word of the verb entry with the requested
+    /// base form, person, number and mood.
+    const LEXICON: &[Entry] = &[
+        (
+            "koira", "koira", "nimisana", "nimento", "singular", "", "", "",
+        ),
+        (
+            "koirat", "koira", "nimisana", "nimento", "plural", "", "", "",
+        ),
+        (
+            "kirjat", "kirja", "nimisana", "nimento", "plural", "", "", "",
+        ),
+        (
+            "Pekka", "Pekka", "etunimi", "nimento", "singular", "", "", "",
+        ),
+        (
+            "minä", "minä", "nimisana", "nimento", "singular", "", "", "",
+        ),
+        (
+            "minä",
+            "minä",
+            "asemosana",
+            "nimento",
+            "singular",
+            "",
+            "",
+            "",
+        ),
+        ("ne", "ne", "asemosana", "nimento", "plural", "", "", ""),
+        (
+            "juoksee",
+            "juosta",
+            "teonsana",
+            "",
+            "singular",
+            "3",
+            "indicative",
+            "",
+        ),
+        (
+            "juoksen",
+            "juosta",
+            "teonsana",
+            "",
+            "singular",
+            "1",
+            "indicative",
+            "",
+        ),
+        (
+            "juoksevat",
+            "juosta",
+            "teonsana",
+            "",
+            "plural",
+            "3",
+            "indicative",
+            "",
+        ),
+        (
+            "juoksevat",
+            "juokseva",
+            "laatusana",
+            "nimento",
+            "plural",
+            "",
+            "",
+            "present_active",
+        ),
+        (
+            "on",
+            "olla",
+            "teonsana",
+            "",
+            "singular",
+            "3",
+            "indicative",
+            "",
+        ),
+        (
+            "ovat",
+            "olla",
+            "teonsana",
+            "",
+            "plural",
+            "3",
+            "indicative",
+            "",
+        ),
+        (
+            "olen",
+            "olla",
+            "teonsana",
+            "",
+            "singular",
+            "1",
+            "indicative",
+            "",
+        ),
+        (
+            "ostin",
+            "ostaa",
+            "teonsana",
+            "",
+            "singular",
+            "1",
+            "indicative",
+            "",
+        ),
+        (
+            "ei",
+            "ei",
+            "kieltosana",
+            "",
+            "singular",
+            "3",
+            "indicative",
+            "",
+        ),
+        (
+            "eivät",
+            "ei",
+            "kieltosana",
+            "",
+            "plural",
+            "3",
+            "indicative",
+            "",
+        ),
+        ("ole", "olla", "teonsana", "", "", "", "", ""),
+        (
+            "tule",
+            "tulla",
+            "teonsana",
+            "",
+            "singular",
+            "2",
+            "imperative",
+            "",
+        ),
+        (
+            "luettu",
+            "luettu",
+            "laatusana",
+            "nimento",
+            "singular",
+            "",
+            "",
+            "past_passive",
+        ),
+        ("ja", "ja", "sidesana", "", "", "", "", ""),
+    ];
+
+    struct Lexicon;
+
+    impl Analyzer for Lexicon {
+        fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
+            let word: String = word[..word_len].iter().collect();
+            LEXICON
+                .iter()
+                .filter(|entry| entry.0 == word)
+                .map(
+                    |&(_, baseform, class, case, number, person, mood, participle)| {
+                        let mut analysis = Analysis::new();
+                        analysis.set(ATTR_BASEFORM, baseform);
+                        analysis.set(ATTR_CLASS, class);
+                        for (key, value) in [
+                            (ATTR_SIJAMUOTO, case),
+                            (ATTR_NUMBER, number),
+                            (ATTR_PERSON, person),
+                            (ATTR_MOOD, mood),
+                            (ATTR_PARTICIPLE, participle),
+                        ] {
+                            if !value.is_empty() {
+                                analysis.set(key, value);
+                            }
+                        }
+                        analysis
+                    },
+                )
+                .collect()
+        }
+
+        fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+            LEXICON
+                .iter()
+                .filter(|entry| {
+                    entry.1 == baseform
+                        && target.get(ATTR_CLASS) == Some(entry.2)
+                        && target.get(ATTR_NUMBER) == Some(entry.4)
+                        && target.get(ATTR_PERSON) == Some(entry.5)
+                        && target.get(ATTR_MOOD) == Some(entry.6)
+                })
+                .map(|entry| entry.0.to_string())
+                .collect()
+        }
+    }
+
+    fn options() -> GrammarOptions {
+        GrammarOptions {
+            check_subject_verb_agreement: true,
+            ..GrammarOptions::default()
+        }
+    }
+
+    fn check(texts: &[&str]) -> Vec<GrammarError> {
+        gc_subject_verb_agreement(&sentence(texts), Some(&Lexicon), &options())
+    }
+
+    #[test]
+    fn plural_noun_before_singular_verb() {
+        let errors = check(&["koirat", " ", "juoksee", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code, GCERR_SUBJECT_VERB_AGREEMENT);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (7, 7));
+        assert_eq!(errors[0].suggestions, ["juoksevat"]);
+        assert!(check(&["koira", " ", "juoksee", "."]).is_empty());
+    }
+
+    #[test]
+    fn singular_noun_before_plural_verb() {
+        let errors = check(&["koira", " ", "ovat", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestions, ["on"]);
+    }
+
+    #[test]
+    fn negative_verb_agrees_too() {
+        let errors = check(&["ne", " ", "ei", " ", "juokse", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (3, 2));
+        assert_eq!(errors[0].suggestions, ["eivät"]);
+    }
+
+    #[test]
+    fn personal_pronoun_needs_its_person() {
+        let errors = check(&["Minä", " ", "on", " ", "koira", "."]);
+        assert!(errors.is_empty(), "the noun minä agrees with on");
+        let errors = check(&["Minä", " ", "ovat", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestions, ["olen"]);
+    }
+
+    #[test]
+    fn ambiguous_words_are_skipped() {
+        // "juoksevat" is also a participle, "tule" an imperative and
+        // "Pekka" a proper name.
+        assert!(check(&["koira", " ", "juoksevat"]).is_empty());
+        assert!(check(&["koirat", " ", "tule"]).is_empty());
+        assert!(check(&["Pekka", " ", "ovat"]).is_empty());
+    }
+
+    #[test]
+    fn fronted_object_and_passive_are_skipped() {
+        assert!(check(&["kirjat", " ", "ostin"]).is_empty());
+        assert!(check(&["kirjat", " ", "on", " ", "luettu"]).is_empty());
+        assert!(check(&["kirjat", " ", "ei", " ", "ole", " ", "luettu"]).is_empty());
+    }
+
+    #[test]
+    fn subject_must_start_a_clause() {
+        assert!(check(&["koira", " ", "ja", " ", "koirat", " ", "juoksee"]).is_empty());
+        let errors = check(&["koira", ",", " ", "koirat", " ", "juoksee"]);
+        assert_eq!(errors.len(), 1);
+        assert!(check(&["koirat", ",", " ", "juoksee"]).is_empty());
+    }
+
+    #[test]
+    fn disabled_or_without_analyzer() {
+        let s = sentence(&["koirat", " ", "juoksee"]);
+        assert!(
+            gc_subject_verb_agreement(&s, Some(&Lexicon), &GrammarOptions::default()).is_empty()
+        );
+        assert!(gc_subject_verb_agreement(&s, None, &options()).is_empty());
+    }
+}
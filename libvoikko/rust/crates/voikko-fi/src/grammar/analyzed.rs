@@ -0,0 +1,144 @@
+// Analyzed paragraphs for client-side grammar checks
+//
+// The built-in checks work on a tokenized paragraph whose word tokens are
+// annotated from their morphological analyses. This module exposes the
+// same data -- sentences, tokens with their analyses and annotation flags,
+// and the autocorrect matches -- so that an application can implement its
+// own one-off checks without re-tokenizing and re-analyzing the text.
+
+use voikko_core::analysis::Analysis;
+use voikko_core::enums::{SentenceType, TokenType};
+use voikko_core::grammar_error::GrammarError;
+
+use super::paragraph::{GrammarSentence, GrammarToken, Paragraph, strip_soft_hyphens};
+use crate::morphology::Analyzer;
+
+/// A paragraph as the grammar checks see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzedParagraph {
+    /// The sentences of the paragraph, in order.
+    pub sentences: Vec<AnalyzedSentence>,
+}
+
+/// A sentence of an [`AnalyzedParagraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzedSentence {
+    /// How the next sentence starts, as reported by the sentence splitter.
+    pub sentence_type: SentenceType,
+    /// Character offset of the sentence in the paragraph.
+    pub start: usize,
+    /// The tokens of the sentence, including whitespace and punctuation.
+    pub tokens: Vec<AnalyzedToken>,
+    /// Autocorrect matches in the sentence, as the grammar errors the
+    /// autocorrect check reports (the replacement is the suggestion).
+    pub autocorrections: Vec<GrammarError>,
+}
+
+/// A token of an [`AnalyzedSentence`] with its analyses and the flags the
+/// built-in checks derive from them.
+///
+/// The flags of a token that is not a word are all `false`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzedToken {
+    /// The type of the token.
+    pub token_type: TokenType,
+    /// The token text.
+    pub text: String,
+    /// Character offset of the token in the paragraph.
+    pub start: usize,
+    /// Morphological analyses of a word token (soft hyphens removed, in any
+    /// case). Empty for other tokens and unknown words.
+    pub analyses: Vec<Analysis>,
+    /// The word has at least one analysis.
+    pub is_valid_word: bool,
+    /// The spell checker rejects the word. Unlike `is_valid_word`, this
+    /// follows the spelling options, user dictionary and ignore list, as
+    /// `VoikkoHandle::spell` does; it is set by
+    /// `VoikkoHandle::analyze_paragraph`.
+    pub is_misspelled: bool,
+    /// The word should start with a lowercase letter.
+    pub first_letter_lcase: bool,
+    /// The word follows punctuation that may end a sentence.
+    pub possible_sentence_start: bool,
+    /// The word may be a geographical name in the genitive.
+    pub is_geographical_name_in_genitive: bool,
+    /// The word may be a geographical name.
+    pub possible_geographical_name: bool,
+    /// The word may be the main verb of the sentence.
+    pub possible_main_verb: bool,
+    /// Every analysis of the word is a main verb.
+    pub is_main_verb: bool,
+    /// The word is a negative verb ("en", "ei", ...).
+    pub is_verb_negative: bool,
+    /// The word can only be a positive verb.
+    pub is_positive_verb: bool,
+    /// Every analysis of the word is a conjunction.
+    pub is_conjunction: bool,
+    /// The word may be a conjunction.
+    pub possible_conjunction: bool,
+    /// Smallest number of compound parts among the analyses, 0 if none.
+    pub compound_part_count: usize,
+}
+
+impl AnalyzedParagraph {
+    /// Convert the engine's paragraph, analyzing the word tokens again with
+    /// `analyzer` for their analyses. `autocorrections` gives the
+    /// autocorrect matches of a sentence.
+    pub(crate) fn new(
+        paragraph: &Paragraph,
+        analyzer: &dyn Analyzer,
+        mut autocorrections: impl FnMut(&GrammarSentence) -> Vec<GrammarError>,
+    ) -> Self {
+        let sentences = paragraph
+            .sentences
+            .iter()
+            .map(|sentence| AnalyzedSentence {
+                sentence_type: sentence.sentence_type,
+                start: sentence.pos,
+                tokens: sentence
+                    .tokens
+                    .iter()
+                    .map(|token| AnalyzedToken::new(token, analyzer))
+                    .collect(),
+                autocorrections: autocorrections(sentence),
+            })
+            .collect();
+        Self { sentences }
+    }
+
+    /// All tokens of the paragraph, in order.
+    pub fn tokens(&self) -> impl Iterator<Item = &AnalyzedToken> {
+        self.sentences.iter().flat_map(|s| &s.tokens)
+    }
+}
+
+impl AnalyzedToken {
+    fn new(token: &GrammarToken, analyzer: &dyn Analyzer) -> Self {
+        let is_word = token.token_type == TokenType::Word;
+        let analyses = if is_word {
+            let word = strip_soft_hyphens(&token.text);
+            analyzer.analyze_any_case(&word, word.len()).0
+        } else {
+            Vec::new()
+        };
+        Self {
+            token_type: token.token_type,
+            text: token.text.iter().collect(),
+            start: token.pos,
+            analyses,
+            is_valid_word: is_word && token.is_valid_word,
+            is_misspelled: false,
+            first_letter_lcase: is_word && token.first_letter_lcase,
+            possible_sentence_start: is_word && token.possible_sentence_start,
+            is_geographical_name_in_genitive: is_word && token.is_geographical_name_in_genitive,
+            possible_geographical_name: is_word && token.possible_geographical_name,
+            possible_main_verb: is_word && token.possible_main_verb,
+            is_main_verb: is_word && token.is_main_verb,
+            is_verb_negative: is_word && token.is_verb_negative,
+            is_positive_verb: is_word && token.is_positive_verb,
+            is_conjunction: is_word && token.is_conjunction,
+            possible_conjunction: is_word && token.possible_conjunction,
+            compound_part_count: token.compound_part_count,
+        }
+    }
+}
@@ -2,16 +2,24 @@
 //
 // For each word in a sentence, checks if the autocorrect transducer
 // produces a correction. If it does, returns a GCERR_INVALID_SPELLING
-// error with the correction as suggestion.
+// error with the correction as suggestion. Entries may span several
+// tokens ("ab cd"); the longest entry ending at a word boundary wins.
+//
+// User rules (see `autocorrect_rules`) are layered on top: they are tried
+// first at each word, and a matching rule takes precedence over the
+// transducer.
 //
 // Origin: grammar/FinnishRuleEngine/VfstAutocorrectCheck.cpp
 
+use std::sync::{Arc, PoisonError, RwLock};
+
 use voikko_core::character::{is_upper, simple_lower, simple_upper};
 use voikko_core::enums::TokenType;
 use voikko_core::grammar_error::{GCERR_INVALID_SPELLING, GrammarError};
 use voikko_fst::Transducer;
 use voikko_fst::unweighted::UnweightedTransducer;
 
+use super::autocorrect_rules::{AutocorrectRuleError, AutocorrectRules};
 use super::paragraph::GrammarSentence;
 
 /// Maximum buffer size for autocorrect transducer traversal.
@@ -24,7 +32,70 @@ const BUFFER_SIZE: usize = 20000;
 /// Origin: VfstAutocorrectCheck.cpp:103
 const SOFT_HYPHEN: char = '\u{00AD}';
 
-/// Run autocorrect check on a sentence using the given transducer.
+/// The autocorrect subsystem: the autocorr.vfst transducer, if loaded, and
+/// the user rules layered on top of it.
+#[derive(Default)]
+pub(crate) struct Autocorrect {
+    /// Replaceable while checks run; a check keeps the transducer it
+    /// started with.
+    transducer: RwLock<Option<Arc<UnweightedTransducer>>>,
+    rules: AutocorrectRules,
+}
+
+impl Autocorrect {
+    /// Create the subsystem with an optional transducer and no user rules.
+    pub(crate) fn new(transducer: Option<UnweightedTransducer>) -> Self {
+        Self {
+            transducer: RwLock::new(transducer.map(Arc::new)),
+            rules: AutocorrectRules::new(),
+        }
+    }
+
+    /// Return `true` if there is neither a transducer nor a user rule, so
+    /// the check can be skipped.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.transducer().is_none() && self.rules.is_empty()
+    }
+
+    /// The current transducer.
+    fn transducer(&self) -> Option<Arc<UnweightedTransducer>> {
+        self.transducer
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replace the transducer. Checks already running finish with the old
+    /// one.
+    pub(crate) fn set_transducer(&self, transducer: Option<UnweightedTransducer>) {
+        *self
+            .transducer
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = transducer.map(Arc::new);
+    }
+
+    /// The user rules.
+    pub(crate) fn rules(&self) -> &AutocorrectRules {
+        &self.rules
+    }
+
+    /// Add a user rule (see [`AutocorrectRules::add`]).
+    pub(crate) fn add_rule(&mut self, from: &str, to: &str) -> Result<(), AutocorrectRuleError> {
+        self.rules.add(from, to)
+    }
+
+    /// Remove the user rule for `from`. Returns `true` if there was one.
+    pub(crate) fn remove_rule(&mut self, from: &str) -> bool {
+        self.rules.remove(from)
+    }
+
+    /// Remove all user rules.
+    pub(crate) fn clear_rules(&mut self) {
+        self.rules = AutocorrectRules::new();
+    }
+}
+
+/// Run autocorrect check on a sentence using the transducer and user rules.
 ///
 /// First tries the sentence as-is. If the first word starts with uppercase
 /// and no match is found at position 0, re-tries with the first letter
@@ -34,12 +105,12 @@ const SOFT_HYPHEN: char = '\u{00AD}';
 /// Origin: VfstAutocorrectCheck.cpp:59-63
 pub(crate) fn gc_autocorrect(
     sentence: &GrammarSentence,
-    transducer: &UnweightedTransducer,
+    autocorrect: &Autocorrect,
 ) -> Vec<GrammarError> {
-    let need_lowering = gc_autocorrect_inner(sentence, transducer, false);
+    let need_lowering = gc_autocorrect_inner(sentence, autocorrect, false);
     let mut errors = need_lowering.errors;
     if need_lowering.need_lowering {
-        let lowered = gc_autocorrect_inner(sentence, transducer, true);
+        let lowered = gc_autocorrect_inner(sentence, autocorrect, true);
         errors.extend(lowered.errors);
     }
     errors
@@ -58,10 +129,19 @@ struct AutocorrectResult {
 /// start positions, then runs the transducer's `next_prefix` at each
 /// word start position.
 ///
+/// Unlike the C++ check, which only considers the first prefix match,
+/// all prefix matches are scanned and the longest one ending at a word
+/// boundary wins, so multi-word phrase entries are found even when a
+/// shorter entry matches the same start.
+///
+/// User rules are tried before the transducer in the first pass; they
+/// handle a capitalized first letter themselves, so the lowered pass only
+/// uses the transducer.
+///
 /// Origin: VfstAutocorrectCheck.cpp:65-171
 fn gc_autocorrect_inner(
     sentence: &GrammarSentence,
-    transducer: &UnweightedTransducer,
+    autocorrect: &Autocorrect,
     lower_first: bool,
 ) -> AutocorrectResult {
     let mut errors = Vec::new();
@@ -141,31 +221,70 @@ fn gc_autocorrect_inner(
         ucs_original_positions.push(sentence_length_ucs);
     }
 
-    // Run the transducer at each word start position.
-    let mut config = transducer.new_config(BUFFER_SIZE);
+    // Run the transducer at each word start position, keeping the longest
+    // match that ends at a word boundary. Word starts inside a matched
+    // phrase are skipped so that phrase and word corrections do not overlap.
+    let transducer = autocorrect.transducer();
+    let mut config = transducer
+        .as_ref()
+        .map(|transducer| transducer.new_config(BUFFER_SIZE));
+    let mut covered_until: usize = 0;
 
     for (&position, &ucs_position) in lookup_positions_utf.iter().zip(lookup_positions_ucs.iter()) {
         if lower_first && position > 0 {
             break;
         }
+        if position < covered_until {
+            continue;
+        }
 
         let remaining_input = &input_buffer[position..];
-        transducer.prepare(&mut config, remaining_input);
+        // Check that a match ends at a word boundary
+        let at_boundary = |length: usize| {
+            ucs_normalized_positions
+                .iter()
+                .any(|&p| ucs_position + length == p)
+        };
 
-        let mut output = String::new();
-        let mut prefix_length: usize = 0;
+        let mut longest: Option<(usize, String)> = None;
+        let mut any_match = false;
 
-        if transducer.next_prefix(&mut config, &mut output, &mut prefix_length) && prefix_length > 0
-        {
-            // Check that the match ends at a word boundary
-            let end_at_boundary = ucs_normalized_positions
+        let rule_match = if lower_first {
+            None
+        } else {
+            autocorrect.rules.find(remaining_input, &at_boundary)
+        };
+        if let Some((length, replacement)) = rule_match {
+            // A rule mapping the text to itself only suppresses the
+            // transducer's correction.
+            if remaining_input[..length]
                 .iter()
-                .any(|&p| ucs_position + prefix_length == p);
-
-            if !end_at_boundary {
+                .copied()
+                .eq(replacement.chars())
+            {
+                covered_until = position + length;
                 continue;
             }
+            longest = Some((length, replacement));
+        } else if let (Some(transducer), Some(config)) = (&transducer, &mut config) {
+            transducer.prepare(config, remaining_input);
+            let mut output = String::new();
+            let mut prefix_length: usize = 0;
+            while transducer.next_prefix(config, &mut output, &mut prefix_length) {
+                if prefix_length == 0 {
+                    continue;
+                }
+                any_match = true;
+                if at_boundary(prefix_length)
+                    && longest.as_ref().is_none_or(|(len, _)| prefix_length > *len)
+                {
+                    longest = Some((prefix_length, output.clone()));
+                }
+            }
+        }
 
+        if let Some((prefix_length, output)) = longest {
+            covered_until = position + prefix_length;
             let start_pos = sentence.pos + ucs_position;
 
             // Calculate length correction for soft hyphens etc.
@@ -204,7 +323,8 @@ fn gc_autocorrect_inner(
                 error_len,
                 vec![suggestion],
             ));
-        } else if !lower_first
+        } else if !any_match
+            && !lower_first
             && position == 0
             && !tokens.is_empty()
             && tokens[0].text.first().copied().is_some_and(is_upper)
@@ -250,7 +370,7 @@ mod tests {
         // Build a minimal transducer that accepts nothing.
         let data = build_minimal_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
         assert!(errs.is_empty());
     }
 
@@ -259,7 +379,7 @@ mod tests {
         let s = sentence(vec![word("koira", 0), ws(" ", 5), word("kissa", 6)], 0);
         let data = build_minimal_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
         assert!(errs.is_empty());
     }
 
@@ -393,6 +513,42 @@ mod tests {
         data
     }
 
+    /// Build a VFST with overlapping entries "ab" -> "ef", "ab cd" -> "ef gh"
+    /// and "cd" -> "xx".
+    ///
+    /// Symbol table: ["", "a", "b", " ", "c", "d", "e", "f", "g", "h", "x"]
+    ///   index:        0    1    2    3    4    5    6    7    8    9    10
+    fn build_phrase_vfst() -> Vec<u8> {
+        let symbols: &[&str] = &["", "a", "b", " ", "c", "d", "e", "f", "g", "h", "x"];
+        let mut data = Vec::new();
+        data.extend_from_slice(&build_header());
+        data.extend_from_slice(&build_symbol_table(symbols));
+        align_to_8(&mut data);
+
+        let transitions = [
+            // State 0: 'a' -> state 2 (output 'e'), 'c' -> state 8 (output 'x')
+            make_transition(1, 6, 2, 1),
+            make_transition(4, 10, 8, 0),
+            // State 2: 'b' -> state 3, output 'f'
+            make_transition(2, 7, 3, 0),
+            // State 3: final ("ab"), ' ' -> state 5
+            make_transition(0xFFFF, 0, 0, 1),
+            make_transition(3, 3, 5, 0),
+            // State 5: 'c' -> state 6, output 'g'
+            make_transition(4, 8, 6, 0),
+            // State 6: 'd' -> state 7, output 'h'
+            make_transition(5, 9, 7, 0),
+            // State 7: final ("ab cd" and "cd")
+            make_transition(0xFFFF, 0, 0, 0),
+            // State 8: 'd' -> state 7, output 'x'
+            make_transition(5, 10, 7, 0),
+        ];
+        for t in &transitions {
+            data.extend_from_slice(bytemuck::bytes_of(t));
+        }
+        data
+    }
+
     // ====================================================================
     // Successful match tests
     // ====================================================================
@@ -405,7 +561,7 @@ mod tests {
         let s = sentence(vec![word("ab", 0)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].error_code, GCERR_INVALID_SPELLING);
@@ -421,7 +577,7 @@ mod tests {
         let s = sentence(vec![word("abc", 0)], 0);
         let data = build_abc_to_xyz_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].error_code, GCERR_INVALID_SPELLING);
@@ -438,7 +594,7 @@ mod tests {
         let s = sentence(vec![word("zz", 0), ws(" ", 2), word("ab", 3)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].start_pos, 3);
@@ -455,7 +611,7 @@ mod tests {
         let s = sentence(vec![word("abc", 0)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert!(errs.is_empty());
     }
@@ -468,7 +624,7 @@ mod tests {
         let s = sentence(vec![word("ab", 0), ws(" ", 2), word("cd", 3)], 0);
         let data = build_ab_cd_to_ef_gh_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].error_code, GCERR_INVALID_SPELLING);
@@ -477,6 +633,117 @@ mod tests {
         assert_eq!(errs[0].suggestions, vec!["ef gh"]);
     }
 
+    #[test]
+    fn longest_phrase_match_wins() {
+        // "ab" alone would match, but the phrase "ab cd" is longer. The
+        // word start "cd" lies inside the phrase and is not checked again.
+        let s = sentence(vec![word("ab", 0), ws(" ", 2), word("cd", 3)], 0);
+        let t = UnweightedTransducer::from_bytes(&build_phrase_vfst()).unwrap();
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].start_pos, 0);
+        assert_eq!(errs[0].error_len, 5);
+        assert_eq!(errs[0].suggestions, vec!["ef gh"]);
+    }
+
+    #[test]
+    fn longest_match_must_end_at_word_boundary() {
+        // "ab cd" ends inside "cdd", so the shorter "ab" is used instead.
+        let s = sentence(vec![word("ab", 0), ws(" ", 2), word("cdd", 3)], 0);
+        let t = UnweightedTransducer::from_bytes(&build_phrase_vfst()).unwrap();
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].start_pos, 0);
+        assert_eq!(errs[0].error_len, 2);
+        assert_eq!(errs[0].suggestions, vec!["ef"]);
+    }
+
+    #[test]
+    fn word_after_phrase_checked_separately() {
+        let s = sentence(
+            vec![
+                word("ab", 0),
+                ws(" ", 2),
+                word("cd", 3),
+                ws(" ", 5),
+                word("cd", 6),
+            ],
+            0,
+        );
+        let t = UnweightedTransducer::from_bytes(&build_phrase_vfst()).unwrap();
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
+
+        let spans: Vec<(usize, usize, &str)> = errs
+            .iter()
+            .map(|e| (e.start_pos, e.error_len, e.suggestions[0].as_str()))
+            .collect();
+        assert_eq!(spans, [(0, 5, "ef gh"), (6, 2, "xx")]);
+    }
+
+    // ====================================================================
+    // User rule tests
+    // ====================================================================
+
+    /// Autocorrect with the "ab" -> "xy" transducer and the given rules.
+    fn with_rules(rules: &[(&str, &str)]) -> Autocorrect {
+        let t = UnweightedTransducer::from_bytes(&build_ab_to_xy_vfst()).unwrap();
+        let mut autocorrect = Autocorrect::new(Some(t));
+        for (from, to) in rules {
+            autocorrect.add_rule(from, to).unwrap();
+        }
+        autocorrect
+    }
+
+    fn corrections(errs: &[GrammarError]) -> Vec<(usize, usize, &str)> {
+        errs.iter()
+            .map(|e| (e.start_pos, e.error_len, e.suggestions[0].as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn user_rules_without_transducer() {
+        let s = sentence(vec![word("zz", 0), ws("  ", 2), word("qq", 4)], 10);
+        let mut autocorrect = Autocorrect::default();
+        assert!(autocorrect.is_empty());
+        autocorrect.add_rule("zz qq", "ZQ").unwrap();
+        assert!(!autocorrect.is_empty());
+        let errs = gc_autocorrect(&s, &autocorrect);
+        assert_eq!(corrections(&errs), [(10, 6, "ZQ")]);
+        assert_eq!(errs[0].error_code, GCERR_INVALID_SPELLING);
+    }
+
+    #[test]
+    fn user_rule_takes_precedence_over_transducer() {
+        let s = sentence(vec![word("ab", 0), ws(" ", 2), word("ab", 3)], 0);
+        let errs = gc_autocorrect(&s, &with_rules(&[("ab", "AB")]));
+        assert_eq!(corrections(&errs), [(0, 2, "AB"), (3, 2, "AB")]);
+        // A rule mapping the word to itself suppresses the transducer.
+        let errs = gc_autocorrect(&s, &with_rules(&[("ab", "ab")]));
+        assert!(errs.is_empty());
+        // Other words are still corrected by the transducer.
+        let errs = gc_autocorrect(&s, &with_rules(&[("cd", "x")]));
+        assert_eq!(corrections(&errs), [(0, 2, "xy"), (3, 2, "xy")]);
+    }
+
+    #[test]
+    fn user_pattern_rule_and_capitalization() {
+        let s = sentence(
+            vec![word("Teh", 0), ws(" ", 3), word("15", 4), word("e", 6)],
+            0,
+        );
+        let errs = gc_autocorrect(&s, &with_rules(&[("teh", "the"), (r"/(\d+)e/", "$1 €")]));
+        assert_eq!(corrections(&errs), [(0, 3, "The"), (4, 3, "15 €")]);
+    }
+
+    #[test]
+    fn user_rule_must_end_at_token_boundary() {
+        let s = sentence(vec![word("tehdas", 0)], 0);
+        let errs = gc_autocorrect(&s, &with_rules(&[("teh", "the"), ("/te./", "x")]));
+        assert!(errs.is_empty());
+    }
+
     // ====================================================================
     // Uppercase lowering / re-uppercasing tests
     // ====================================================================
@@ -491,7 +758,7 @@ mod tests {
         let s = sentence(vec![word("Ab", 0)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].error_code, GCERR_INVALID_SPELLING);
@@ -509,7 +776,7 @@ mod tests {
         let s = sentence(vec![word("ab", 0)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].suggestions, vec!["xy"]);
@@ -526,7 +793,7 @@ mod tests {
         let s = sentence(vec![word("ab", 10)], 10);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert_eq!(errs.len(), 1);
         assert_eq!(errs[0].start_pos, 10);
@@ -544,7 +811,7 @@ mod tests {
         let s = sentence(vec![word("abcd", 0)], 0);
         let data = build_abc_to_xyz_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert!(errs.is_empty());
     }
@@ -555,7 +822,7 @@ mod tests {
         let s = sentence(vec![word("zzz", 0)], 0);
         let data = build_ab_to_xy_vfst();
         let t = UnweightedTransducer::from_bytes(&data).unwrap();
-        let errs = gc_autocorrect(&s, &t);
+        let errs = gc_autocorrect(&s, &Autocorrect::new(Some(t)));
 
         assert!(errs.is_empty());
     }
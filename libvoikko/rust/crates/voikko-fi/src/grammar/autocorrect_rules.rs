@@ -0,0 +1,350 @@
+// User autocorrect rules layered on the autocorr.vfst transducer.
+//
+// A rule maps text to its replacement. `from` is either literal text
+// ("teh" -> "the", "ab cd" -> "ef gh") or a pattern between slashes
+// ("/(\d+) ?e/" -> "$1 €") in the regex-lite syntax of `pattern`; `$1`..`$9`
+// in the replacement of a pattern rule insert its groups, `$0` the whole
+// match and `$$` a dollar sign.
+//
+// Rules are tried at each word start of a sentence, like the transducer,
+// and a match must end at a token boundary. A word starting with an
+// uppercase letter also matches a rule written in lowercase; the
+// replacement is then capitalized. The longest match wins; of equally long
+// ones, the rule added first. A rule that matches takes precedence over the
+// transducer, so a rule mapping a word to itself suppresses the
+// transducer's correction of it.
+
+use voikko_core::character::{is_upper, simple_lower, simple_upper};
+
+use super::pattern::{Captures, MAX_MATCH_CHARS, Pattern};
+
+/// Error type for invalid autocorrect rules.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AutocorrectRuleError {
+    /// The text or pattern to replace is empty.
+    #[error("autocorrect rule has nothing to replace")]
+    Empty,
+
+    /// A pattern between slashes is not valid.
+    #[error("invalid autocorrect pattern {pattern:?}: {reason}")]
+    InvalidPattern {
+        pattern: String,
+        reason: &'static str,
+    },
+
+    /// The replacement refers to a group the pattern does not have.
+    #[error("autocorrect replacement {replacement:?} refers to missing group {group}")]
+    MissingGroup { replacement: String, group: usize },
+}
+
+/// What a rule matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Matcher {
+    /// Literal text, with whitespace runs collapsed to one space.
+    Literal(Vec<char>),
+    /// A pattern and its replacement split into text and group references.
+    Pattern(Pattern, Vec<ReplacementPart>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReplacementPart {
+    Text(String),
+    Group(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    from: String,
+    to: String,
+    matcher: Matcher,
+}
+
+/// User-defined autocorrect rules, checked before the autocorrect
+/// transducer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutocorrectRules {
+    rules: Vec<Rule>,
+}
+
+impl AutocorrectRules {
+    /// Create an empty rule list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Return `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Add a rule replacing `from` with `to`. `from` is literal text or a
+    /// pattern between slashes; surrounding whitespace is ignored. A rule
+    /// with the same `from` is replaced.
+    pub fn add(&mut self, from: &str, to: &str) -> Result<(), AutocorrectRuleError> {
+        let from = from.trim();
+        let matcher = match from.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            Some(pattern) => {
+                if pattern.is_empty() {
+                    return Err(AutocorrectRuleError::Empty);
+                }
+                let pattern = Pattern::parse(pattern).map_err(|reason| {
+                    AutocorrectRuleError::InvalidPattern {
+                        pattern: pattern.to_string(),
+                        reason,
+                    }
+                })?;
+                let replacement = parse_replacement(to, pattern.groups())?;
+                Matcher::Pattern(pattern, replacement)
+            }
+            None => {
+                let text = collapse_whitespace(from);
+                if text.is_empty() {
+                    return Err(AutocorrectRuleError::Empty);
+                }
+                Matcher::Literal(text)
+            }
+        };
+        let rule = Rule {
+            from: from.to_string(),
+            to: to.to_string(),
+            matcher,
+        };
+        match self.rules.iter_mut().find(|r| r.from == rule.from) {
+            Some(existing) => *existing = rule,
+            None => self.rules.push(rule),
+        }
+        Ok(())
+    }
+
+    /// Remove the rule for `from`. Returns `true` if there was one.
+    pub fn remove(&mut self, from: &str) -> bool {
+        let from = from.trim();
+        let before = self.rules.len();
+        self.rules.retain(|r| r.from != from);
+        self.rules.len() != before
+    }
+
+    /// Iterate over the rules as `(from, to)` pairs, in the order added.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.rules.iter().map(|r| (r.from.as_str(), r.to.as_str()))
+    }
+
+    /// Find the rule match at the start of `text`, a sentence normalized as
+    /// for the transducer, whose length `at_boundary` accepts.
+    ///
+    /// Returns the length of the longest match and its replacement.
+    pub(crate) fn find(
+        &self,
+        text: &[char],
+        at_boundary: &dyn Fn(usize) -> bool,
+    ) -> Option<(usize, String)> {
+        if let Some(found) = self.find_exact(text, at_boundary) {
+            return Some(found);
+        }
+        let first = *text.first()?;
+        if !is_upper(first) {
+            return None;
+        }
+        let mut lowered = text[..text.len().min(MAX_MATCH_CHARS)].to_vec();
+        lowered[0] = simple_lower(first);
+        let (length, replacement) = self.find_exact(&lowered, at_boundary)?;
+        let mut replacement = replacement.chars();
+        let capitalized = replacement
+            .next()
+            .map(|c| {
+                std::iter::once(simple_upper(c))
+                    .chain(replacement)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some((length, capitalized))
+    }
+
+    fn find_exact(
+        &self,
+        text: &[char],
+        at_boundary: &dyn Fn(usize) -> bool,
+    ) -> Option<(usize, String)> {
+        let mut best: Option<(usize, String)> = None;
+        for rule in &self.rules {
+            let found = match &rule.matcher {
+                Matcher::Literal(literal) => (text.starts_with(literal)
+                    && at_boundary(literal.len()))
+                .then(|| (literal.len(), rule.to.clone())),
+                Matcher::Pattern(pattern, replacement) => pattern
+                    .match_at(text, at_boundary)
+                    .map(|captures| expand(replacement, text, &captures)),
+            };
+            if let Some((length, replacement)) = found {
+                if best.as_ref().is_none_or(|(best, _)| length > *best) {
+                    best = Some((length, replacement));
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Collapse whitespace runs to one space, as in the text the rules are
+/// matched against.
+fn collapse_whitespace(text: &str) -> Vec<char> {
+    let mut chars = Vec::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !chars.is_empty() {
+            chars.push(' ');
+        }
+        chars.extend(word.chars());
+    }
+    chars
+}
+
+/// Split a pattern rule's replacement into text and group references.
+fn parse_replacement(
+    replacement: &str,
+    groups: usize,
+) -> Result<Vec<ReplacementPart>, AutocorrectRuleError> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('$', Some('$')) => {
+                chars.next();
+                text.push('$');
+            }
+            ('$', Some(d)) if d.is_ascii_digit() => {
+                chars.next();
+                let group = d as usize - '0' as usize;
+                if group > groups {
+                    return Err(AutocorrectRuleError::MissingGroup {
+                        replacement: replacement.to_string(),
+                        group,
+                    });
+                }
+                if !text.is_empty() {
+                    parts.push(ReplacementPart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(ReplacementPart::Group(group));
+            }
+            (c, _) => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(ReplacementPart::Text(text));
+    }
+    Ok(parts)
+}
+
+/// Build the replacement of a pattern match. Returns the match length and
+/// the replacement.
+fn expand(parts: &[ReplacementPart], text: &[char], captures: &Captures) -> (usize, String) {
+    let mut replacement = String::new();
+    for part in parts {
+        match part {
+            ReplacementPart::Text(t) => replacement.push_str(t),
+            ReplacementPart::Group(group) => {
+                if let Some((start, end)) = captures[*group] {
+                    replacement.extend(&text[start..end]);
+                }
+            }
+        }
+    }
+    let length = captures[0].map_or(0, |(_, end)| end);
+    (length, replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    /// Find a match in `text` that ends at a space or at the end.
+    fn find(rules: &AutocorrectRules, text: &str) -> Option<(usize, String)> {
+        let text = chars(text);
+        let at_boundary = |end: usize| end == text.len() || text[end] == ' ';
+        rules.find(&text, &at_boundary)
+    }
+
+    #[test]
+    fn literal_rules() {
+        let mut rules = AutocorrectRules::new();
+        rules.add("teh", "the").unwrap();
+        rules.add("  ab   cd ", "ef gh").unwrap();
+        assert_eq!(find(&rules, "teh cat"), Some((3, "the".to_string())));
+        assert_eq!(find(&rules, "tehdas"), None);
+        assert_eq!(find(&rules, "ab cd ef"), Some((5, "ef gh".to_string())));
+        assert_eq!(
+            rules.iter().collect::<Vec<_>>(),
+            [("teh", "the"), ("ab   cd", "ef gh")]
+        );
+    }
+
+    #[test]
+    fn capitalized_word_matches_lowercase_rule() {
+        let mut rules = AutocorrectRules::new();
+        rules.add("teh", "the").unwrap();
+        assert_eq!(find(&rules, "Teh cat"), Some((3, "The".to_string())));
+        rules.add("Teh", "TEH").unwrap();
+        assert_eq!(find(&rules, "Teh cat"), Some((3, "TEH".to_string())));
+    }
+
+    #[test]
+    fn pattern_rules_expand_groups() {
+        let mut rules = AutocorrectRules::new();
+        rules.add(r"/(\d+) ?e/", "$1 €").unwrap();
+        rules.add("/(a+)(b)/", "$2$1$$").unwrap();
+        assert_eq!(find(&rules, "15e"), Some((3, "15 €".to_string())));
+        assert_eq!(find(&rules, "15 e kpl"), Some((4, "15 €".to_string())));
+        assert_eq!(find(&rules, "aab"), Some((3, "baa$".to_string())));
+        assert_eq!(find(&rules, "aabc"), None);
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        let mut rules = AutocorrectRules::new();
+        rules.add("ab", "x").unwrap();
+        rules.add("ab cd", "y").unwrap();
+        rules.add("/a./", "z").unwrap();
+        assert_eq!(find(&rules, "ab cd"), Some((5, "y".to_string())));
+        assert_eq!(find(&rules, "ab ef"), Some((2, "x".to_string())));
+    }
+
+    #[test]
+    fn add_replaces_and_remove() {
+        let mut rules = AutocorrectRules::new();
+        rules.add("teh", "the").unwrap();
+        rules.add("teh", "tea").unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(find(&rules, "teh"), Some((3, "tea".to_string())));
+        assert!(rules.remove(" teh "));
+        assert!(!rules.remove("teh"));
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let mut rules = AutocorrectRules::new();
+        assert_eq!(rules.add("  ", "x"), Err(AutocorrectRuleError::Empty));
+        assert_eq!(rules.add("//", "x"), Err(AutocorrectRuleError::Empty));
+        assert!(matches!(
+            rules.add("/(ab/", "x"),
+            Err(AutocorrectRuleError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            rules.add("/(a)b/", "$2"),
+            Err(AutocorrectRuleError::MissingGroup { group: 2, .. })
+        ));
+        // A single slash and the replacement of a literal rule are literal.
+        rules.add("/", "$2").unwrap();
+        assert_eq!(find(&rules, "/"), Some((1, "$2".to_string())));
+        assert_eq!(rules.len(), 1);
+    }
+}
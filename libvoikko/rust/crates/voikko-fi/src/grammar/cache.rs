@@ -1,8 +1,35 @@
 // Grammar checker cache for paragraph-level results
 // Origin: grammar/GcCache.hpp, GcCache.cpp, CacheEntry.hpp, CacheEntry.cpp
+//
+// The C++ cache holds the errors of the last checked paragraph only. An
+// editor re-checks every paragraph of a document after each edit, so this
+// version holds many paragraphs, keyed by the paragraph text and a
+// fingerprint of the grammar options, and evicts the least recently used
+// one when full. Changing the options does not empty it: results checked
+// with other options stay until they are evicted.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 use voikko_core::grammar_error::GrammarError;
 
+use crate::morphology::CacheStats;
+
+/// Number of paragraphs the cache holds by default.
+pub(crate) const DEFAULT_GC_CACHE_PARAGRAPHS: usize = 256;
+
+/// The cached errors of one paragraph.
+struct CacheEntry {
+    /// The paragraph text, compared on lookup since keys are hashes.
+    paragraph: Box<[char]>,
+    /// Fingerprint of the options the paragraph was checked with.
+    fingerprint: u64,
+    /// Errors sorted by `start_pos`.
+    errors: Vec<GrammarError>,
+    /// Tick of the last lookup or store, for LRU eviction.
+    last_used: u64,
+}
+
 // ---------------------------------------------------------------------------
 // GcCache
 // Origin: grammar/GcCache.hpp:39-57, GcCache.cpp:34-75
@@ -10,89 +37,195 @@ use voikko_core::grammar_error::GrammarError;
 
 /// Grammar checker cache.
 ///
-/// Caches grammar check results for a single paragraph. If the same paragraph
-/// text is checked again, the cached errors are returned without re-running
-/// the grammar rules.
+/// Caches grammar check results per paragraph. If a paragraph is checked
+/// again with the same options, the cached errors are returned without
+/// re-running the grammar rules.
 ///
 /// The C++ implementation stores a single paragraph as a linked list of
-/// `CacheEntry` nodes sorted by start position. In Rust we use a `Vec` of
-/// `GrammarError` sorted by `start_pos`, and store the paragraph text as a
-/// `Vec<char>` for direct comparison.
+/// `CacheEntry` nodes sorted by start position. In Rust each paragraph has
+/// a `Vec` of `GrammarError` sorted by `start_pos`, found by a hash of its
+/// text and options fingerprint. Eviction scans the entries for the least
+/// recently used one, which is cheap next to a grammar check.
 ///
 /// Origin: grammar/GcCache.hpp:39-57
 pub(crate) struct GcCache {
-    /// The cached paragraph text, or `None` if the cache is empty.
-    paragraph: Option<Vec<char>>,
+    /// Maximum number of paragraphs; 0 disables the cache.
+    capacity: usize,
 
-    /// Cached grammar errors for the paragraph, sorted by `start_pos`.
-    errors: Vec<GrammarError>,
+    /// Cached paragraphs by hash of text and fingerprint.
+    entries: HashMap<u64, CacheEntry>,
+
+    /// Incremented on every lookup and store.
+    tick: u64,
+
+    /// Hit, miss and eviction counts.
+    stats: CacheStats,
+
+    /// Number of times the cache has been cleared. A check that started
+    /// before a clear must not store its (possibly stale) results.
+    generation: u64,
 }
 
 impl GcCache {
-    /// Create an empty cache.
+    /// Create an empty cache holding [`DEFAULT_GC_CACHE_PARAGRAPHS`]
+    /// paragraphs.
     ///
     /// Origin: GcCache.cpp:34-37
     pub fn new() -> Self {
         Self {
-            paragraph: None,
-            errors: Vec::new(),
+            capacity: DEFAULT_GC_CACHE_PARAGRAPHS,
+            entries: HashMap::new(),
+            tick: 0,
+            stats: CacheStats::default(),
+            generation: 0,
         }
     }
 
-    /// Clear the cache, discarding the stored paragraph and errors.
+    /// Clear the cache, discarding the stored paragraphs and errors. The
+    /// hit and miss counts are kept.
     ///
     /// Origin: GcCache.cpp:39-49
     pub fn clear(&mut self) {
-        self.paragraph = None;
-        self.errors.clear();
+        self.entries.clear();
+        self.generation += 1;
     }
 
-    /// Check whether the cache contains results for the given paragraph text.
-    ///
-    /// Returns `Some(&[GrammarError])` if the cached text matches, `None`
-    /// otherwise.
-    pub fn check_cache(&self, text: &[char]) -> Option<&[GrammarError]> {
-        match &self.paragraph {
-            Some(cached) if cached.as_slice() == text => Some(&self.errors),
-            _ => None,
+    /// Return the number of times the cache has been cleared.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Set the maximum number of paragraphs, evicting the least recently
+    /// used ones that no longer fit.
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict_one();
         }
     }
 
-    /// Store grammar check results for a paragraph.
+    /// Hit, miss and eviction counts, with the number of cached paragraphs
+    /// as the length.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.entries.len(),
+            capacity: self.capacity,
+            ..self.stats
+        }
+    }
+
+    /// Look up the errors of a paragraph checked with the options of
+    /// `fingerprint`, marking it as recently used. Counts a hit or a miss.
+    pub fn check_cache(&mut self, text: &[char], fingerprint: u64) -> Option<&[GrammarError]> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self
+            .entries
+            .get_mut(&key(text, fingerprint))
+            .filter(|e| e.fingerprint == fingerprint && *e.paragraph == *text)
+        {
+            Some(entry) => {
+                self.stats.hits += 1;
+                entry.last_used = tick;
+                Some(&entry.errors)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store grammar check results for a paragraph, evicting the least
+    /// recently used paragraph if the cache is full.
     ///
-    /// Replaces any previously cached paragraph. The errors are stored sorted
-    /// by `start_pos`.
-    pub fn store_cache(&mut self, text: &[char], mut errors: Vec<GrammarError>) {
+    /// Replaces the errors stored for the same paragraph and options. The
+    /// errors are stored sorted by `start_pos`.
+    pub fn store_cache(&mut self, text: &[char], fingerprint: u64, mut errors: Vec<GrammarError>) {
+        if self.capacity == 0 {
+            return;
+        }
         errors.sort_by_key(|e| e.start_pos);
-        self.paragraph = Some(text.to_vec());
-        self.errors = errors;
+        let key = key(text, fingerprint);
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict_one();
+        }
+        self.tick += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                paragraph: text.into(),
+                fingerprint,
+                errors,
+                last_used: self.tick,
+            },
+        );
     }
 
-    /// Append a single error to the cached results, maintaining sorted order
-    /// by `start_pos`.
+    /// Remove the results of a paragraph under all options. Returns `true`
+    /// if there were any. Checks already running may not store their
+    /// results afterwards, as after [`clear`](Self::clear).
+    pub fn invalidate(&mut self, text: &[char]) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|_, e| *e.paragraph != *text);
+        self.generation += 1;
+        self.entries.len() != before
+    }
+
+    /// Append a single error to the cached results of a paragraph,
+    /// maintaining sorted order by `start_pos`. Does nothing if the
+    /// paragraph is not cached.
     ///
     /// This mirrors the C++ `GcCache::appendError` which inserts into a
     /// sorted linked list.
     ///
     /// Origin: GcCache.cpp:51-75
-    pub fn append_error(&mut self, error: GrammarError) {
-        let insert_pos = self
+    pub fn append_error(&mut self, text: &[char], fingerprint: u64, error: GrammarError) {
+        let Some(entry) = self
+            .entries
+            .get_mut(&key(text, fingerprint))
+            .filter(|e| e.fingerprint == fingerprint && *e.paragraph == *text)
+        else {
+            return;
+        };
+        let insert_pos = entry
             .errors
             .partition_point(|e| e.start_pos <= error.start_pos);
-        self.errors.insert(insert_pos, error);
+        entry.errors.insert(insert_pos, error);
     }
 
-    /// Return the number of cached errors.
+    /// Return the number of cached errors in all paragraphs.
     pub fn error_count(&self) -> usize {
-        self.errors.len()
+        self.entries.values().map(|e| e.errors.len()).sum()
     }
 
-    /// Return whether the cache has a stored paragraph.
+    /// Return whether the cache has no stored paragraphs.
     pub fn is_empty(&self) -> bool {
-        self.paragraph.is_none()
+        self.entries.is_empty()
+    }
+
+    /// Drop the least recently used paragraph.
+    fn evict_one(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, e)| e.last_used)
+            .map(|(&k, _)| k);
+        if let Some(k) = oldest {
+            self.entries.remove(&k);
+            self.stats.evictions += 1;
+        }
     }
 }
 
+/// The map key of a paragraph checked with the options of `fingerprint`.
+fn key(text: &[char], fingerprint: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    fingerprint.hash(&mut hasher);
+    hasher.finish()
+}
+
 // ===========================================================================
 // Tests
 // ===========================================================================
@@ -101,6 +234,9 @@ impl GcCache {
 mod tests {
     use super::*;
 
+    /// Fingerprint of the options in most tests.
+    const F: u64 = 0;
+
     fn chars(s: &str) -> Vec<char> {
         s.chars().collect()
     }
@@ -114,8 +250,8 @@ mod tests {
 
     #[test]
     fn check_empty_cache_returns_none() {
-        let cache = GcCache::new();
-        assert!(cache.check_cache(&chars("hello")).is_none());
+        let mut cache = GcCache::new();
+        assert!(cache.check_cache(&chars("hello"), F).is_none());
     }
 
     #[test]
@@ -123,9 +259,9 @@ mod tests {
         let mut cache = GcCache::new();
         let text = chars("Koira juoksi.");
         let errors = vec![GrammarError::new(1, 0, 5)];
-        cache.store_cache(&text, errors.clone());
+        cache.store_cache(&text, F, errors.clone());
 
-        let result = cache.check_cache(&text);
+        let result = cache.check_cache(&text, F);
         assert!(result.is_some());
         let cached_errors = result.unwrap();
         assert_eq!(cached_errors.len(), 1);
@@ -138,36 +274,95 @@ mod tests {
         let mut cache = GcCache::new();
         let text1 = chars("Koira juoksi.");
         let text2 = chars("Kissa nukkui.");
-        cache.store_cache(&text1, vec![]);
+        cache.store_cache(&text1, F, vec![]);
 
-        assert!(cache.check_cache(&text2).is_none());
+        assert!(cache.check_cache(&text2, F).is_none());
     }
 
     #[test]
-    fn store_replaces_previous() {
+    fn store_keeps_other_paragraphs() {
         let mut cache = GcCache::new();
         let text1 = chars("First.");
         let text2 = chars("Second.");
-        cache.store_cache(&text1, vec![GrammarError::new(1, 0, 5)]);
-        cache.store_cache(&text2, vec![GrammarError::new(2, 0, 6)]);
+        cache.store_cache(&text1, F, vec![GrammarError::new(1, 0, 5)]);
+        cache.store_cache(&text2, F, vec![GrammarError::new(2, 0, 6)]);
 
-        assert!(cache.check_cache(&text1).is_none());
-        let result = cache.check_cache(&text2).unwrap();
+        assert_eq!(cache.check_cache(&text1, F).unwrap()[0].error_code, 1);
+        let result = cache.check_cache(&text2, F).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].error_code, 2);
     }
 
+    #[test]
+    fn store_replaces_same_paragraph() {
+        let mut cache = GcCache::new();
+        let text = chars("First.");
+        cache.store_cache(&text, F, vec![GrammarError::new(1, 0, 5)]);
+        cache.store_cache(&text, F, vec![GrammarError::new(2, 0, 6)]);
+        assert_eq!(cache.stats().len, 1);
+        assert_eq!(cache.check_cache(&text, F).unwrap()[0].error_code, 2);
+    }
+
+    #[test]
+    fn options_fingerprint_is_part_of_the_key() {
+        let mut cache = GcCache::new();
+        let text = chars("Koira.");
+        cache.store_cache(&text, 1, vec![GrammarError::new(1, 0, 5)]);
+        cache.store_cache(&text, 2, vec![]);
+        assert!(cache.check_cache(&text, 3).is_none());
+        assert_eq!(cache.check_cache(&text, 1).unwrap().len(), 1);
+        assert!(cache.check_cache(&text, 2).unwrap().is_empty());
+
+        assert!(cache.invalidate(&text));
+        assert!(cache.is_empty());
+        assert!(!cache.invalidate(&text));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_paragraph() {
+        let mut cache = GcCache::new();
+        cache.resize(2);
+        let (a, b, c) = (chars("A."), chars("B."), chars("C."));
+        cache.store_cache(&a, F, vec![]);
+        cache.store_cache(&b, F, vec![]);
+        // A is used again, so B is the oldest
+        assert!(cache.check_cache(&a, F).is_some());
+        cache.store_cache(&c, F, vec![]);
+        assert!(cache.check_cache(&b, F).is_none());
+        assert!(cache.check_cache(&a, F).is_some());
+        assert!(cache.check_cache(&c, F).is_some());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 3,
+                misses: 1,
+                evictions: 1,
+                len: 2,
+                capacity: 2,
+            }
+        );
+
+        cache.resize(1);
+        assert!(cache.check_cache(&a, F).is_none());
+        assert!(cache.check_cache(&c, F).is_some());
+        cache.resize(0);
+        cache.store_cache(&a, F, vec![]);
+        assert!(cache.is_empty());
+    }
+
     #[test]
     fn clear_empties_cache() {
         let mut cache = GcCache::new();
         let text = chars("Koira.");
-        cache.store_cache(&text, vec![GrammarError::new(1, 0, 5)]);
+        cache.store_cache(&text, F, vec![GrammarError::new(1, 0, 5)]);
         assert!(!cache.is_empty());
 
+        assert_eq!(cache.generation(), 0);
         cache.clear();
         assert!(cache.is_empty());
         assert_eq!(cache.error_count(), 0);
-        assert!(cache.check_cache(&text).is_none());
+        assert!(cache.check_cache(&text, F).is_none());
+        assert_eq!(cache.generation(), 1);
     }
 
     #[test]
@@ -179,9 +374,9 @@ mod tests {
             GrammarError::new(2, 0, 5),
             GrammarError::new(3, 6, 6),
         ];
-        cache.store_cache(&text, errors);
+        cache.store_cache(&text, F, errors);
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert_eq!(result[0].start_pos, 0);
         assert_eq!(result[1].start_pos, 6);
         assert_eq!(result[2].start_pos, 15);
@@ -193,13 +388,14 @@ mod tests {
         let text = chars("Koira juoksi.");
         cache.store_cache(
             &text,
+            F,
             vec![GrammarError::new(1, 0, 5), GrammarError::new(3, 10, 3)],
         );
 
         // Insert in the middle.
-        cache.append_error(GrammarError::new(2, 6, 4));
+        cache.append_error(&text, F, GrammarError::new(2, 6, 4));
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert_eq!(result.len(), 3);
         assert_eq!(result[0].start_pos, 0);
         assert_eq!(result[1].start_pos, 6);
@@ -210,11 +406,11 @@ mod tests {
     fn append_error_at_beginning() {
         let mut cache = GcCache::new();
         let text = chars("Hello.");
-        cache.store_cache(&text, vec![GrammarError::new(1, 5, 1)]);
+        cache.store_cache(&text, F, vec![GrammarError::new(1, 5, 1)]);
 
-        cache.append_error(GrammarError::new(2, 0, 3));
+        cache.append_error(&text, F, GrammarError::new(2, 0, 3));
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].start_pos, 0);
         assert_eq!(result[1].start_pos, 5);
@@ -224,11 +420,11 @@ mod tests {
     fn append_error_at_end() {
         let mut cache = GcCache::new();
         let text = chars("Hello.");
-        cache.store_cache(&text, vec![GrammarError::new(1, 0, 3)]);
+        cache.store_cache(&text, F, vec![GrammarError::new(1, 0, 3)]);
 
-        cache.append_error(GrammarError::new(2, 5, 1));
+        cache.append_error(&text, F, GrammarError::new(2, 5, 1));
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].start_pos, 0);
         assert_eq!(result[1].start_pos, 5);
@@ -238,11 +434,11 @@ mod tests {
     fn append_error_with_same_start_pos() {
         let mut cache = GcCache::new();
         let text = chars("Hello.");
-        cache.store_cache(&text, vec![GrammarError::new(1, 0, 3)]);
+        cache.store_cache(&text, F, vec![GrammarError::new(1, 0, 3)]);
 
-        cache.append_error(GrammarError::new(2, 0, 5));
+        cache.append_error(&text, F, GrammarError::new(2, 0, 5));
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert_eq!(result.len(), 2);
         // Both at position 0; order is: existing first, then appended.
         assert_eq!(result[0].error_code, 1);
@@ -253,9 +449,9 @@ mod tests {
     fn check_cache_empty_text() {
         let mut cache = GcCache::new();
         let empty: Vec<char> = Vec::new();
-        cache.store_cache(&empty, vec![]);
+        cache.store_cache(&empty, F, vec![]);
 
-        let result = cache.check_cache(&empty);
+        let result = cache.check_cache(&empty, F);
         assert!(result.is_some());
         assert!(result.unwrap().is_empty());
     }
@@ -264,9 +460,9 @@ mod tests {
     fn store_with_no_errors() {
         let mut cache = GcCache::new();
         let text = chars("Clean paragraph.");
-        cache.store_cache(&text, vec![]);
+        cache.store_cache(&text, F, vec![]);
 
-        let result = cache.check_cache(&text).unwrap();
+        let result = cache.check_cache(&text, F).unwrap();
         assert!(result.is_empty());
         assert!(!cache.is_empty());
     }
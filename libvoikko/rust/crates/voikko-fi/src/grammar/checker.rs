@@ -5,25 +5,29 @@
 //
 // Origin: grammar/FinnishGrammarChecker.cpp, grammar/GrammarChecker.hpp
 
-use std::cell::RefCell;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use voikko_core::enums::TokenType;
 use voikko_core::grammar_error::GrammarError;
 
 use super::GrammarChecker;
+use super::analyzed::AnalyzedParagraph;
+use super::autocorrect_rules::{AutocorrectRuleError, AutocorrectRules};
 use super::cache::GcCache;
 use super::checks::GrammarOptions;
 use super::engine::FinnishRuleEngine;
-use super::finnish_analysis::analyse_token;
+use super::finnish_analysis::TokenMemo;
 use super::paragraph::{self, GrammarSentence, GrammarToken, Paragraph};
+use super::rules::RuleSet;
+use super::timing::{RuleStats, SlowRule};
 use crate::morphology::Analyzer;
 use crate::tokenizer;
 
 /// Top-level Finnish grammar checker.
 ///
-/// Owns the rule engine and the grammar cache. The cache uses `RefCell` for
-/// interior mutability so that the `GrammarChecker` trait (`&self`) can
-/// read and update the cache.
+/// Owns the rule engine and the grammar cache. The cache is behind a `Mutex`
+/// so that the `GrammarChecker` trait (`&self`) can read and update it, and
+/// the checker can be shared between threads.
 ///
 /// Optionally holds a reference to a morphological analyzer. When an analyzer
 /// is available, `analyse_paragraph` is used instead of `tokenize_paragraph`,
@@ -34,9 +38,9 @@ pub(crate) struct FinnishGrammarChecker<'a> {
     /// The rule engine that orchestrates all individual checks.
     engine: FinnishRuleEngine,
     /// Cache for grammar checking results (interior mutability for &self).
-    cache: RefCell<GcCache>,
+    cache: Mutex<GcCache>,
     /// Optional morphological analyzer for enriched grammar analysis.
-    analyzer: Option<&'a dyn Analyzer>,
+    analyzer: Option<&'a (dyn Analyzer + Sync)>,
 }
 
 impl<'a> FinnishGrammarChecker<'a> {
@@ -50,27 +54,96 @@ impl<'a> FinnishGrammarChecker<'a> {
     pub(crate) fn new(
         options: GrammarOptions,
         autocorrect_transducer: Option<voikko_fst::unweighted::UnweightedTransducer>,
-        analyzer: Option<&'a dyn Analyzer>,
+        analyzer: Option<&'a (dyn Analyzer + Sync)>,
     ) -> Self {
         Self {
             engine: FinnishRuleEngine::new(options, autocorrect_transducer),
-            cache: RefCell::new(GcCache::new()),
+            cache: Mutex::new(GcCache::new()),
             analyzer,
         }
     }
 
     /// Update the grammar checker options.
     ///
-    /// Also clears the cache, since changing options (e.g.,
-    /// `accept_titles_in_gc`) may produce different results for the same text.
+    /// The cache is keyed by the options fingerprint, so results checked
+    /// with the previous options are not returned but stay cached, for
+    /// when the options are set back.
     pub(crate) fn set_options(&mut self, options: GrammarOptions) {
         self.engine.set_options(options);
-        self.cache.borrow_mut().clear();
     }
 
-    /// Access the cache (for error retrieval).
-    pub(crate) fn cache(&self) -> &RefCell<GcCache> {
-        &self.cache
+    /// Replace the autocorrect transducer. Clears the cache.
+    pub(crate) fn set_autocorrect_transducer(
+        &self,
+        transducer: Option<voikko_fst::unweighted::UnweightedTransducer>,
+    ) {
+        self.engine.set_autocorrect_transducer(transducer);
+        self.cache().clear();
+    }
+
+    /// Add the rules of a runtime-loaded rule pack. Clears the cache.
+    pub(crate) fn add_rules(&mut self, rules: RuleSet) {
+        self.engine.add_rules(rules);
+        self.cache().clear();
+    }
+
+    /// Remove all runtime-loaded rules. Clears the cache.
+    pub(crate) fn clear_rules(&mut self) {
+        self.engine.clear_rules();
+        self.cache().clear();
+    }
+
+    /// The user autocorrect rules.
+    pub(crate) fn autocorrect_rules(&self) -> &AutocorrectRules {
+        self.engine.autocorrect_rules()
+    }
+
+    /// Add a user autocorrect rule. Clears the cache.
+    pub(crate) fn add_autocorrect_rule(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<(), AutocorrectRuleError> {
+        self.engine.add_autocorrect_rule(from, to)?;
+        self.cache().clear();
+        Ok(())
+    }
+
+    /// Remove the user autocorrect rule for `from`. Clears the cache if
+    /// there was one.
+    pub(crate) fn remove_autocorrect_rule(&mut self, from: &str) -> bool {
+        let removed = self.engine.remove_autocorrect_rule(from);
+        if removed {
+            self.cache().clear();
+        }
+        removed
+    }
+
+    /// Remove all user autocorrect rules. Clears the cache.
+    pub(crate) fn clear_autocorrect_rules(&mut self) {
+        self.engine.clear_autocorrect_rules();
+        self.cache().clear();
+    }
+
+    /// Per-rule timing statistics, slowest first.
+    pub(crate) fn rule_stats(&self) -> Vec<RuleStats> {
+        self.engine.rule_stats()
+    }
+
+    /// Recent rules that exceeded the time cap.
+    pub(crate) fn slow_rules(&self) -> Vec<SlowRule> {
+        self.engine.slow_rules()
+    }
+
+    /// Discard the timing statistics.
+    pub(crate) fn reset_rule_stats(&self) {
+        self.engine.reset_rule_stats();
+    }
+
+    /// Lock the cache. A poisoned lock is recovered, since the cache only
+    /// holds finished results.
+    pub(crate) fn cache(&self) -> MutexGuard<'_, GcCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Build a `Paragraph` from text, using `analyse_paragraph` with
@@ -80,14 +153,7 @@ impl<'a> FinnishGrammarChecker<'a> {
         if let Some(analyzer) = self.analyzer {
             // Use analyse_paragraph with morphological token annotation.
             // Origin: FinnishAnalysis.cpp:analyseParagraph
-            let mut analyse_fn = |token: &mut GrammarToken| {
-                analyse_token(token, analyzer);
-            };
-            match paragraph::analyse_paragraph(text, text_len, &mut analyse_fn) {
-                Some(p) => p,
-                // Sentence too long; fall back to structural tokenization.
-                None => Self::tokenize_paragraph(text, text_len),
-            }
+            Self::analyse_paragraph(text, text_len, analyzer)
         } else {
             Self::tokenize_paragraph(text, text_len)
         }
@@ -180,27 +246,53 @@ impl<'a> FinnishGrammarChecker<'a> {
         analyzer: &dyn Analyzer,
     ) -> Vec<GrammarError> {
         // Check cache first
-        {
-            let cache = self.cache.borrow();
-            if let Some(cached) = cache.check_cache(text) {
+        let fingerprint = self.engine.options().fingerprint();
+        let generation = {
+            let mut cache = self.cache();
+            if let Some(cached) = cache.check_cache(text, fingerprint) {
                 return cached.to_vec();
             }
+            cache.generation()
+        };
+
+        let paragraph = Self::analyse_paragraph(text, text_len, analyzer);
+        let errors = self.engine.check(&paragraph, Some(analyzer));
+
+        // Store in cache, unless it was cleared meanwhile
+        let mut cache = self.cache();
+        if cache.generation() == generation {
+            cache.store_cache(text, fingerprint, errors.clone());
         }
 
-        // Build paragraph with morphological analysis
+        errors
+    }
+
+    /// Tokenize and analyze a paragraph as `check_with_analyzer` does, and
+    /// return it with the analyses and autocorrect matches instead of
+    /// checking it. Not cached.
+    pub(crate) fn analyze_with_analyzer(
+        &self,
+        text: &[char],
+        text_len: usize,
+        analyzer: &dyn Analyzer,
+    ) -> AnalyzedParagraph {
+        let paragraph = Self::analyse_paragraph(text, text_len, analyzer);
+        AnalyzedParagraph::new(&paragraph, analyzer, |sentence| {
+            self.engine.autocorrections(sentence)
+        })
+    }
+
+    /// Build a `Paragraph` with morphological annotation by `analyzer`,
+    /// falling back to structural tokenization for overlong sentences.
+    fn analyse_paragraph(text: &[char], text_len: usize, analyzer: &dyn Analyzer) -> Paragraph {
+        let mut memo = TokenMemo::default();
         let mut analyse_fn = |token: &mut GrammarToken| {
-            analyse_token(token, analyzer);
+            memo.analyse(token, analyzer);
         };
-        let paragraph = match paragraph::analyse_paragraph(text, text_len, &mut analyse_fn) {
+        match paragraph::analyse_paragraph(text, text_len, &mut analyse_fn) {
             Some(p) => p,
             None => Self::tokenize_paragraph(text, text_len),
-        };
-        let errors = self.engine.check(&paragraph);
-
-        // Store in cache
-        self.cache.borrow_mut().store_cache(text, errors.clone());
-
-        errors
+        }
     }
 }
 
@@ -214,18 +306,24 @@ impl GrammarChecker for FinnishGrammarChecker<'_> {
     /// Origin: grammar/GrammarChecker.cpp:paragraphToCache + errorFromCache
     fn check(&self, text: &[char], text_len: usize) -> Vec<GrammarError> {
         // Check cache first
-        {
-            let cache = self.cache.borrow();
-            if let Some(cached) = cache.check_cache(text) {
+        let fingerprint = self.engine.options().fingerprint();
+        let generation = {
+            let mut cache = self.cache();
+            if let Some(cached) = cache.check_cache(text, fingerprint) {
                 return cached.to_vec();
             }
-        }
+            cache.generation()
+        };
 
         let paragraph = self.build_paragraph(text, text_len);
-        let errors = self.engine.check(&paragraph);
+        let analyzer = self.analyzer.map(|a| a as &dyn Analyzer);
+        let errors = self.engine.check(&paragraph, analyzer);
 
-        // Store in cache
-        self.cache.borrow_mut().store_cache(text, errors.clone());
+        // Store in cache, unless it was cleared meanwhile
+        let mut cache = self.cache();
+        if cache.generation() == generation {
+            cache.store_cache(text, fingerprint, errors.clone());
+        }
 
         errors
     }
@@ -293,7 +391,7 @@ mod tests {
         let chars: Vec<char> = text.chars().collect();
         let paragraph = FinnishGrammarChecker::tokenize_paragraph(&chars, chars.len());
         // Should detect at least one sentence boundary
-        assert!(paragraph.sentences.len() >= 1);
+        assert!(!paragraph.sentences.is_empty());
     }
 
     #[test]
@@ -388,6 +486,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn analyze_with_analyzer_exposes_tokens_and_analyses() {
+        use voikko_core::analysis::{ATTR_CLASS, ATTR_MOOD, ATTR_PERSON, ATTR_STRUCTURE};
+
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add(
+            "koira",
+            vec![make_analysis(&[
+                (ATTR_STRUCTURE, "=ppppp"),
+                (ATTR_CLASS, "nimisana"),
+            ])],
+        );
+        analyzer.add(
+            "juoksee",
+            vec![make_analysis(&[
+                (ATTR_STRUCTURE, "=ppppppp"),
+                (ATTR_CLASS, "teonsana"),
+                (ATTR_MOOD, "indicative"),
+                (ATTR_PERSON, "3"),
+            ])],
+        );
+
+        let checker = FinnishGrammarChecker::new(GrammarOptions::default(), None, None);
+        let text = "Koira juoksee. Xyz.";
+        let chars: Vec<char> = text.chars().collect();
+        let paragraph = checker.analyze_with_analyzer(&chars, chars.len(), &analyzer);
+
+        assert_eq!(paragraph.sentences.len(), 2);
+        assert_eq!(paragraph.sentences[1].start, 15);
+        let words: Vec<_> = paragraph
+            .tokens()
+            .filter(|t| t.token_type == TokenType::Word)
+            .collect();
+        assert_eq!(words.len(), 3);
+        assert_eq!((words[0].text.as_str(), words[0].start), ("Koira", 0));
+        assert_eq!(words[0].analyses.len(), 1);
+        assert_eq!(words[0].analyses[0].get(ATTR_CLASS), Some("nimisana"));
+        assert!(words[0].is_valid_word);
+        assert!(words[1].is_main_verb);
+        assert!(!words[2].is_valid_word);
+        assert!(words[2].analyses.is_empty());
+        assert!(paragraph.sentences[0].autocorrections.is_empty());
+
+        let punctuation = paragraph
+            .tokens()
+            .find(|t| t.token_type == TokenType::Punctuation)
+            .unwrap();
+        assert!(!punctuation.is_main_verb && !punctuation.is_positive_verb);
+    }
+
     #[test]
     fn checker_without_analyzer_uses_tokenize_paragraph() {
         // Without analyzer, words should NOT be marked as valid
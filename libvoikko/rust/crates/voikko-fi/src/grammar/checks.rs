@@ -11,6 +11,9 @@
 //         grammar/FinnishRuleEngine/CompoundVerbCheck.cpp
 //         grammar/FinnishRuleEngine/SidesanaCheck.cpp
 
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
 use voikko_core::character::{
     equals_ignore_case, is_finnish_quotation_mark, is_lower, is_upper, simple_lower, simple_upper,
 };
@@ -20,13 +23,17 @@ use voikko_core::grammar_error::{
     GCERR_FOREIGN_QUOTATION_MARK, GCERR_INVALID_PUNCTUATION_AT_END_OF_QUOTATION,
     GCERR_INVALID_SENTENCE_STARTER, GCERR_MA_INFINITIVE_REQUIRED,
     GCERR_MISPLACED_CLOSING_PARENTHESIS, GCERR_MISPLACED_SIDESANA, GCERR_MISSING_MAIN_VERB,
-    GCERR_NEGATIVE_VERB_MISMATCH, GCERR_REPEATING_WORD, GCERR_SPACE_BEFORE_PUNCTUATION,
-    GCERR_TERMINATING_PUNCTUATION_MISSING, GCERR_WRITE_FIRST_LOWERCASE,
-    GCERR_WRITE_FIRST_UPPERCASE, GrammarError,
+    GCERR_NEGATIVE_VERB_MISMATCH, GCERR_OVERLONG_COMPOUND, GCERR_REPEATING_WORD,
+    GCERR_SPACE_BEFORE_PUNCTUATION, GCERR_TERMINATING_PUNCTUATION_MISSING,
+    GCERR_WRITE_FIRST_LOWERCASE, GCERR_WRITE_FIRST_UPPERCASE, GrammarError, GrammarErrorSeverity,
+    Replacement,
 };
 
 use voikko_core::case::{CaseType, detect_case};
 
+use super::numbers::NumberStyle;
+use super::timing::TimingOptions;
+
 // Re-export types from paragraph module for use by other grammar submodules.
 pub(crate) use super::paragraph::{FollowingVerbType, GrammarSentence, GrammarToken, Paragraph};
 
@@ -42,7 +49,7 @@ pub(crate) type GrammarParagraph = Paragraph;
 /// Grammar checker options relevant to individual checks.
 ///
 /// Origin: setup/setup.hpp (VoikkoHandle boolean options)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub(crate) struct GrammarOptions {
     /// Accept incomplete sentences in titles. Default: false.
     /// Origin: voikko_defines.h:117
@@ -55,6 +62,125 @@ pub(crate) struct GrammarOptions {
     /// Accept paragraphs valid within bulleted lists. Default: false.
     /// Origin: voikko_defines.h:131
     pub accept_bulleted_lists_in_gc: bool,
+
+    /// Report overlong compound words (opt-in style check). Default: false.
+    pub check_overlong_compounds: bool,
+
+    /// A compound with more components than this is overlong. 0 disables
+    /// the component limit. Default: 4.
+    pub max_compound_parts: usize,
+
+    /// A compound with more characters than this is overlong. 0 disables
+    /// the length limit. Default: 30.
+    pub max_compound_chars: usize,
+
+    /// Report dates, clock times, ordinals and units written against the
+    /// number style (opt-in check, see `numbers`). Default: false.
+    pub check_number_formats: bool,
+
+    /// Conventions used by the number format check. Default: standard.
+    pub number_style: NumberStyle,
+
+    /// Run the built-in default rule pack (see `rules`). Default: false.
+    pub check_default_rules: bool,
+
+    /// Report repeated sentence starts and unpaired quotation marks across
+    /// the sentences of a paragraph (opt-in check, see `discourse`).
+    /// Default: false.
+    pub check_discourse: bool,
+
+    /// Report "sekä" without "että" and "joko" without "tai" in a sentence
+    /// (opt-in check, see `conjunctions`). Default: false.
+    pub check_paired_conjunctions: bool,
+
+    /// Report correctly spelled words in a case their context does not
+    /// allow, such as "kohti kaupunki" (opt-in check, see `real_word`).
+    /// Default: false.
+    pub check_real_word_errors: bool,
+
+    /// Report finite verbs that do not agree in person or number with the
+    /// nominative subject directly before them, such as "koirat juoksee"
+    /// (opt-in experimental check, see `agreement`). Default: false.
+    pub check_subject_verb_agreement: bool,
+
+    /// Severity reported for an error code instead of the code's default.
+    /// Set by the host application. Default: empty.
+    pub severity_overrides: HashMap<i32, GrammarErrorSeverity>,
+
+    /// Per-rule timing and time cap (see `timing`). Default: off.
+    pub timing: TimingOptions,
+}
+
+impl Default for GrammarOptions {
+    fn default() -> Self {
+        Self {
+            accept_titles_in_gc: false,
+            accept_unfinished_paragraphs_in_gc: false,
+            accept_bulleted_lists_in_gc: false,
+            check_overlong_compounds: false,
+            max_compound_parts: 4,
+            max_compound_chars: 30,
+            check_number_formats: false,
+            number_style: NumberStyle::Standard,
+            check_default_rules: false,
+            check_discourse: false,
+            check_paired_conjunctions: false,
+            check_real_word_errors: false,
+            check_subject_verb_agreement: false,
+            severity_overrides: HashMap::new(),
+            timing: TimingOptions::default(),
+        }
+    }
+}
+
+impl GrammarOptions {
+    /// A hash of the options, for the grammar cache: results checked with
+    /// options of another fingerprint are not reused.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        // Destructured so that a new option cannot be left out
+        let Self {
+            accept_titles_in_gc,
+            accept_unfinished_paragraphs_in_gc,
+            accept_bulleted_lists_in_gc,
+            check_overlong_compounds,
+            max_compound_parts,
+            max_compound_chars,
+            check_number_formats,
+            number_style,
+            check_default_rules,
+            check_discourse,
+            check_paired_conjunctions,
+            check_real_word_errors,
+            check_subject_verb_agreement,
+            severity_overrides,
+            timing,
+        } = self;
+        let mut hasher = DefaultHasher::new();
+        (
+            accept_titles_in_gc,
+            accept_unfinished_paragraphs_in_gc,
+            accept_bulleted_lists_in_gc,
+            check_overlong_compounds,
+            max_compound_parts,
+            max_compound_chars,
+            check_number_formats,
+            number_style,
+        )
+            .hash(&mut hasher);
+        (
+            check_default_rules,
+            check_discourse,
+            check_paired_conjunctions,
+            check_real_word_errors,
+            check_subject_verb_agreement,
+            timing,
+        )
+            .hash(&mut hasher);
+        let mut overrides: Vec<_> = severity_overrides.iter().collect();
+        overrides.sort_unstable_by_key(|&(code, _)| *code);
+        overrides.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 // ============================================================================
@@ -149,7 +275,7 @@ pub(crate) fn gc_local_punctuation(sentence: &GrammarSentence) -> Vec<GrammarErr
                     }
                 }
             }
-            TokenType::None | TokenType::Word | TokenType::Unknown => {}
+            _ => {}
         }
         i += 1;
     }
@@ -301,15 +427,73 @@ pub(crate) fn gc_repeating_words(sentence: &GrammarSentence) -> Vec<GrammarError
     errors
 }
 
-/// GC error for missing punctuation at the end of a paragraph.
+/// Kind of a paragraph, as far as the paragraph-level checks care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParagraphKind {
+    /// Running text.
+    Body,
+    /// A single sentence, which may be a title (C++ `accept_titles_in_gc`
+    /// treats every one-sentence paragraph so).
+    Title,
+    /// A list item written out in plain text: a bullet ("-", "•", ...) or
+    /// an enumerator ("1.", "b)", "IV.") followed by whitespace.
+    ListItem,
+}
+
+/// Characters that start a bulleted list item in plain text.
+const LIST_BULLETS: [char; 9] = [
+    '-', '\u{2013}', '\u{2014}', '\u{2022}', '*', '\u{00b7}', '\u{25aa}', '\u{25e6}', '\u{2023}',
+];
+
+/// Classify a paragraph for the paragraph-level checks.
+pub(crate) fn paragraph_kind(paragraph: &GrammarParagraph) -> ParagraphKind {
+    let mut tokens = paragraph.sentences.iter().flat_map(|s| &s.tokens);
+    let first = tokens.next();
+    let second = tokens.next();
+    let third = tokens.next();
+    let is_space =
+        |t: Option<&GrammarToken>| t.is_some_and(|t| t.token_type == TokenType::Whitespace);
+    let is_list_item = match (first, second) {
+        (Some(bullet), _)
+            if matches!(
+                bullet.token_type,
+                TokenType::Punctuation | TokenType::Unknown
+            ) && bullet.text.len() == 1
+                && LIST_BULLETS.contains(&bullet.text[0]) =>
+        {
+            is_space(second)
+        }
+        (Some(number), Some(closer))
+            if number.token_type == TokenType::Word
+                && matches!(closer.text.as_slice(), ['.'] | [')'])
+                && is_possible_list_item(&number.text) =>
+        {
+            is_space(third)
+        }
+        _ => false,
+    };
+    if is_list_item {
+        ParagraphKind::ListItem
+    } else if paragraph.sentences.len() == 1 {
+        ParagraphKind::Title
+    } else {
+        ParagraphKind::Body
+    }
+}
+
+/// GC error for missing punctuation at the end of a paragraph, with the
+/// last word followed by "." as the suggestion. List items written out in
+/// plain text are not checked.
 ///
 /// Origin: checks.cpp:225-238 (gc_end_punctuation)
 pub(crate) fn gc_end_punctuation(
     paragraph: &GrammarParagraph,
     options: &GrammarOptions,
 ) -> Vec<GrammarError> {
-    if options.accept_titles_in_gc && paragraph.sentences.len() == 1 {
-        return Vec::new();
+    match paragraph_kind(paragraph) {
+        ParagraphKind::ListItem => return Vec::new(),
+        ParagraphKind::Title if options.accept_titles_in_gc => return Vec::new(),
+        _ => {}
     }
     if options.accept_unfinished_paragraphs_in_gc {
         return Vec::new();
@@ -329,10 +513,13 @@ pub(crate) fn gc_end_punctuation(
         return Vec::new();
     }
 
-    vec![GrammarError::new(
+    let mut fixed: String = last_token.text.iter().collect();
+    fixed.push('.');
+    vec![GrammarError::with_suggestions(
         GCERR_TERMINATING_PUNCTUATION_MISSING,
         last_token.pos,
         last_token.token_len(),
+        vec![fixed],
     )]
 }
 
@@ -594,6 +781,35 @@ pub(crate) fn gc_sidesana(sentence: &GrammarSentence) -> Vec<GrammarError> {
     Vec::new()
 }
 
+// ============================================================================
+// Overlong compound check (opt-in style rule, no C++ counterpart)
+// ============================================================================
+
+/// Report compound words that have too many components or characters.
+///
+/// Only recognized compounds (at least two components in every analysis)
+/// are considered; long simple words and unknown words are never reported.
+/// The error carries no suggestions: splitting a compound usually requires
+/// rephrasing the sentence.
+pub(crate) fn gc_overlong_compound(
+    sentence: &GrammarSentence,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_overlong_compounds {
+        return Vec::new();
+    }
+    sentence
+        .tokens
+        .iter()
+        .filter(|t| t.token_type == TokenType::Word && t.compound_part_count >= 2)
+        .filter(|t| {
+            (options.max_compound_parts > 0 && t.compound_part_count > options.max_compound_parts)
+                || (options.max_compound_chars > 0 && t.token_len() > options.max_compound_chars)
+        })
+        .map(|t| GrammarError::new(GCERR_OVERLONG_COMPOUND, t.pos, t.token_len()))
+        .collect()
+}
+
 // ============================================================================
 // Capitalization check (5-state FSA)
 // Origin: CapitalizationCheck.cpp:43-377
@@ -1070,6 +1286,43 @@ pub(crate) fn gc_capitalization(
     ctx.errors
 }
 
+// ============================================================================
+// Replacement spans (no C++ counterpart)
+// ============================================================================
+
+/// Fill in the replacements of `errors`, found in `paragraph`: for each
+/// suggestion, the smallest part of the error span whose replacement gives
+/// the suggestion (see [`Replacement::between`]). Errors that already have
+/// replacements are left as they are.
+pub(crate) fn add_replacements(paragraph: &GrammarParagraph, errors: &mut [GrammarError]) {
+    if errors
+        .iter()
+        .all(|e| e.suggestions.is_empty() || !e.replacements.is_empty())
+    {
+        return;
+    }
+    let mut text: Vec<char> = Vec::new();
+    for token in paragraph.sentences.iter().flat_map(|s| &s.tokens) {
+        if token.pos >= text.len() {
+            text.resize(token.pos, ' ');
+            text.extend_from_slice(&token.text);
+        }
+    }
+    for error in errors {
+        if !error.replacements.is_empty() {
+            continue;
+        }
+        let Some(original) = text.get(error.start_pos..error.start_pos + error.error_len) else {
+            continue;
+        };
+        error.replacements = error
+            .suggestions
+            .iter()
+            .map(|suggestion| Replacement::between(error.start_pos, original, suggestion))
+            .collect();
+    }
+}
+
 // ============================================================================
 // Utility helpers
 // ============================================================================
@@ -1350,6 +1603,106 @@ mod tests {
         assert!(errs.is_empty());
     }
 
+    #[test]
+    fn end_punctuation_suggests_period() {
+        let s1 = sentence(vec![word("Koira", 0), punct(".", 5)], 0);
+        let s2 = sentence(vec![word("Kissa", 7)], 7);
+        let p = GrammarParagraph {
+            sentences: vec![s1, s2],
+        };
+        let errs = gc_end_punctuation(&p, &default_opts());
+        assert_eq!((errs[0].start_pos, errs[0].error_len), (7, 5));
+        assert_eq!(errs[0].suggestions, ["Kissa."]);
+    }
+
+    #[test]
+    fn end_punctuation_skips_list_items() {
+        let item = |first: Vec<GrammarToken>| {
+            let mut tokens = first;
+            let pos = tokens.iter().map(|t| t.token_len()).sum::<usize>();
+            tokens.push(ws(" ", pos));
+            tokens.push(word("koira", pos + 1));
+            GrammarParagraph {
+                sentences: vec![sentence(tokens, 0)],
+            }
+        };
+        for first in [
+            vec![punct("-", 0)],
+            vec![punct("\u{2022}", 0)],
+            vec![word("1", 0), punct(".", 1)],
+            vec![word("b", 0), punct(")", 1)],
+            vec![word("IV", 0), punct(".", 2)],
+        ] {
+            let p = item(first);
+            assert_eq!(paragraph_kind(&p), ParagraphKind::ListItem);
+            assert!(gc_end_punctuation(&p, &default_opts()).is_empty());
+        }
+
+        // A hyphen without a following space is not a bullet
+        let p = item(vec![punct("-", 0), word("x", 1)]);
+        assert_eq!(paragraph_kind(&p), ParagraphKind::Title);
+        assert_eq!(gc_end_punctuation(&p, &default_opts()).len(), 1);
+    }
+
+    // ---- gc_overlong_compound tests ----
+
+    fn compound(text: &str, pos: usize, parts: usize) -> GrammarToken {
+        let mut t = word(text, pos);
+        t.is_valid_word = true;
+        t.compound_part_count = parts;
+        t
+    }
+
+    fn overlong_opts() -> GrammarOptions {
+        GrammarOptions {
+            check_overlong_compounds: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn overlong_compound_disabled_by_default() {
+        let s = sentence(vec![compound("a", 0, 9)], 0);
+        assert!(gc_overlong_compound(&s, &default_opts()).is_empty());
+    }
+
+    #[test]
+    fn overlong_compound_by_part_count() {
+        let text = "lentokonesuihkuturbiinimoottoriapumekaanikko";
+        let s = sentence(
+            vec![
+                compound("kotikissa", 0, 2),
+                ws(" ", 9),
+                compound(text, 10, 6),
+            ],
+            0,
+        );
+        let errs = gc_overlong_compound(&s, &overlong_opts());
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].error_code, GCERR_OVERLONG_COMPOUND);
+        assert_eq!(errs[0].start_pos, 10);
+        assert_eq!(errs[0].error_len, text.chars().count());
+        assert!(errs[0].suggestions.is_empty());
+        assert_eq!(
+            errs[0].severity,
+            voikko_core::grammar_error::GrammarErrorSeverity::Warning
+        );
+    }
+
+    #[test]
+    fn overlong_compound_by_length() {
+        let opts = GrammarOptions {
+            max_compound_parts: 0,
+            max_compound_chars: 10,
+            ..overlong_opts()
+        };
+        let s = sentence(vec![compound("kuusipuumetsä", 0, 3)], 0);
+        assert_eq!(gc_overlong_compound(&s, &opts).len(), 1);
+        // Long non-compounds are not reported
+        let s = sentence(vec![compound("epäjärjestelmällisyys", 0, 1)], 0);
+        assert!(gc_overlong_compound(&s, &opts).is_empty());
+    }
+
     // ---- gc_missing_verb tests ----
 
     #[test]
@@ -1610,4 +1963,33 @@ mod tests {
                 .any(|e| e.error_code == GCERR_MISPLACED_CLOSING_PARENTHESIS)
         );
     }
+
+    // ---- add_replacements tests ----
+
+    #[test]
+    fn replacements_cover_the_changed_part() {
+        let s = sentence(
+            vec![
+                word("Se", 0),
+                ws(" ", 2),
+                word("koira", 3),
+                ws(" ", 8),
+                word("koira", 9),
+                punct(".", 14),
+            ],
+            0,
+        );
+        let p = GrammarParagraph { sentences: vec![s] };
+        let mut errs = gc_repeating_words(&p.sentences[0]);
+        assert_eq!(errs.len(), 1);
+        add_replacements(&p, &mut errs);
+        assert_eq!(
+            errs[0].replacements,
+            vec![Replacement {
+                start: 8,
+                len: 6,
+                text: String::new(),
+            }]
+        );
+    }
 }
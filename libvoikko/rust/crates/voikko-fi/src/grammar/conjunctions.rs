@@ -0,0 +1,233 @@
+// Paired conjunction completeness check
+//
+// Opt-in check for the first member of a paired conjunction without the
+// second member later in the same sentence: "sekä kissa koira" instead of
+// "sekä kissa että koira", "joko tänään huomenna" instead of "joko tänään
+// tai huomenna". The members are confirmed as conjunctions by their
+// analyses. "sekä" alone is also an ordinary "ja" ("kissa sekä koira"), so
+// it is taken as the first member of a pair only where it starts a
+// coordination: at the start of a sentence or clause, or after a possible
+// verb ("on sekä ..."). "joko" is also the question adverb "jo" + "-ko",
+// so questions are skipped.
+
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{GCERR_INCOMPLETE_PAIRED_CONJUNCTION, GrammarError};
+
+use super::checks::{GrammarOptions, GrammarSentence, GrammarToken};
+
+/// First members of paired conjunctions with their accepted second
+/// members.
+const PAIRED_CONJUNCTIONS: &[(&str, &[&str])] =
+    &[("sekä", &["että"]), ("joko", &["tai", "taikka"])];
+
+/// Report first members of paired conjunctions whose second member is
+/// missing from the rest of the sentence.
+pub(crate) fn gc_paired_conjunctions(
+    sentence: &GrammarSentence,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_paired_conjunctions {
+        return Vec::new();
+    }
+    let tokens = &sentence.tokens;
+    let question = tokens
+        .iter()
+        .rev()
+        .find(|t| t.token_type != TokenType::Whitespace)
+        .is_some_and(|t| t.text == ['?']);
+    let mut errors = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let Some((first, seconds)) = paired_conjunction(token) else {
+            continue;
+        };
+        if first == "joko" && question {
+            continue;
+        }
+        if first == "sekä" && !starts_coordination(&tokens[..i]) {
+            continue;
+        }
+        let completed = tokens[i + 1..].iter().any(|t| {
+            t.token_type == TokenType::Word
+                && t.possible_conjunction
+                && seconds.contains(&lowercase(t).as_str())
+        });
+        if !completed {
+            let mut error = GrammarError::new(
+                GCERR_INCOMPLETE_PAIRED_CONJUNCTION,
+                token.pos,
+                token.token_len(),
+            );
+            error.short_description = format!(
+                "Parikonjunktiosta \u{201d}{first} \u{2026} {second}\u{201d} puuttuu \u{201d}{second}\u{201d}.",
+                second = seconds[0]
+            );
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+/// The paired conjunction `token` is the first member of, if it is a word
+/// that may be a conjunction.
+fn paired_conjunction(token: &GrammarToken) -> Option<(&'static str, &'static [&'static str])> {
+    if token.token_type != TokenType::Word || !token.possible_conjunction {
+        return None;
+    }
+    let text = lowercase(token);
+    PAIRED_CONJUNCTIONS
+        .iter()
+        .find(|(first, _)| *first == text)
+        .copied()
+}
+
+/// Whether a word after `preceding` starts a coordination: nothing but
+/// whitespace precedes it in the sentence, or the previous token is
+/// punctuation or a word that may be the main verb.
+fn starts_coordination(preceding: &[GrammarToken]) -> bool {
+    match preceding
+        .iter()
+        .rev()
+        .find(|t| t.token_type != TokenType::Whitespace)
+    {
+        None => true,
+        Some(t) if t.token_type == TokenType::Punctuation => true,
+        Some(t) => t.possible_main_verb || t.is_verb_negative,
+    }
+}
+
+fn lowercase(token: &GrammarToken) -> String {
+    token.text.iter().collect::<String>().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::test_util;
+
+    /// Build a sentence from token texts (see `test_util::sentence`). The
+    /// words listed in `conjunctions` may be conjunctions and those in
+    /// `verbs` main verbs.
+    fn sentence(texts: &[&str], conjunctions: &[&str], verbs: &[&str]) -> GrammarSentence {
+        let mut sentence = test_util::sentence(texts);
+        for (token, text) in sentence.tokens.iter_mut().zip(texts) {
+            token.possible_conjunction = conjunctions.contains(text);
+            token.possible_main_verb = verbs.contains(text);
+        }
+        sentence
+    }
+
+    fn options() -> GrammarOptions {
+        GrammarOptions {
+            check_paired_conjunctions: true,
+            ..GrammarOptions::default()
+        }
+    }
+
+    fn found(sentence: &GrammarSentence) -> Vec<(usize, String)> {
+        gc_paired_conjunctions(sentence, &options())
+            .into_iter()
+            .map(|e| (e.start_pos, e.short_description))
+            .collect()
+    }
+
+    const CONJUNCTIONS: &[&str] = &["Sekä", "sekä", "että", "joko", "Joko", "tai", "taikka"];
+
+    #[test]
+    fn disabled_by_default() {
+        let s = sentence(
+            &["Sekä", " ", "kissa", " ", "koira", "."],
+            CONJUNCTIONS,
+            &[],
+        );
+        assert!(gc_paired_conjunctions(&s, &GrammarOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn missing_second_member() {
+        // "Sekä kissa koira."
+        let s = sentence(
+            &["Sekä", " ", "kissa", " ", "koira", "."],
+            CONJUNCTIONS,
+            &[],
+        );
+        assert_eq!(
+            found(&s),
+            [(
+                0,
+                "Parikonjunktiosta \u{201d}sekä \u{2026} että\u{201d} puuttuu \u{201d}että\u{201d}."
+                    .to_string()
+            )]
+        );
+
+        // "Lähden joko tänään huomenna."
+        let s = sentence(
+            &["Lähden", " ", "joko", " ", "tänään", " ", "huomenna", "."],
+            CONJUNCTIONS,
+            &["Lähden"],
+        );
+        let errors = gc_paired_conjunctions(&s, &options());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code, GCERR_INCOMPLETE_PAIRED_CONJUNCTION);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (7, 4));
+        assert!(
+            errors[0]
+                .short_description
+                .ends_with("puuttuu \u{201d}tai\u{201d}.")
+        );
+
+        // The second member must follow the first: "Tai joko kissa."
+        let s = sentence(
+            &["Tai", " ", "joko", " ", "kissa", "."],
+            &["Tai", "joko"],
+            &[],
+        );
+        assert_eq!(found(&s).len(), 1);
+    }
+
+    #[test]
+    fn complete_pairs_are_fine() {
+        // "Hän on sekä viisas että kaunis."
+        let s = sentence(
+            &[
+                "Hän", " ", "on", " ", "sekä", " ", "viisas", " ", "että", " ", "kaunis", ".",
+            ],
+            CONJUNCTIONS,
+            &["on"],
+        );
+        assert!(found(&s).is_empty());
+
+        // "Joko tänään taikka huomenna."
+        let s = sentence(
+            &["Joko", " ", "tänään", " ", "taikka", " ", "huomenna", "."],
+            CONJUNCTIONS,
+            &[],
+        );
+        assert!(found(&s).is_empty());
+    }
+
+    #[test]
+    fn seka_as_ja_is_fine() {
+        // "Ostin leipää sekä maitoa."
+        let s = sentence(
+            &["Ostin", " ", "leipää", " ", "sekä", " ", "maitoa", "."],
+            CONJUNCTIONS,
+            &["Ostin"],
+        );
+        assert!(found(&s).is_empty());
+    }
+
+    #[test]
+    fn questions_and_non_conjunctions_are_skipped() {
+        // "Joko hän tuli?"
+        let s = sentence(
+            &["Joko", " ", "hän", " ", "tuli", "?"],
+            CONJUNCTIONS,
+            &["tuli"],
+        );
+        assert!(found(&s).is_empty());
+
+        // "Sekä" without a conjunction analysis
+        let s = sentence(&["Sekä", " ", "kissa", "."], &[], &[]);
+        assert!(found(&s).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+// Discourse-level checks across the sentences of a paragraph
+//
+// Opt-in checks that look at a paragraph as a whole instead of one
+// sentence at a time: runs of sentences starting with the same word
+// ("Hän tuli. Hän istui. Hän söi.") and quotation marks that are left open
+// or closed with a different mark ("”Tule. Nyt heti\" ja lähti."). A
+// quotation may span several sentences, so the marks are paired over the
+// whole paragraph. A lowercase word after a sentence end is already
+// reported across sentences by the capitalization check (code 7).
+
+use voikko_core::character::{equals_ignore_case, is_finnish_quotation_mark};
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{
+    GCERR_REPEATED_SENTENCE_START, GCERR_UNBALANCED_QUOTATION, GrammarError,
+};
+
+use super::checks::{GrammarOptions, GrammarParagraph, GrammarToken};
+
+/// Number of consecutive sentences starting with the same word from which
+/// the repetition is reported.
+const REPEATED_START_LIMIT: usize = 3;
+
+/// Report runs of sentences starting with the same word and unpaired
+/// quotation marks in a paragraph.
+pub(crate) fn gc_discourse(
+    paragraph: &GrammarParagraph,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_discourse {
+        return Vec::new();
+    }
+    let mut errors = repeated_sentence_starts(paragraph);
+    errors.extend(unbalanced_quotations(paragraph, options));
+    errors.sort_by_key(|e| e.start_pos);
+    errors
+}
+
+/// The first word of each sentence from the third in a run of sentences
+/// starting with the same word, compared without case.
+fn repeated_sentence_starts(paragraph: &GrammarParagraph) -> Vec<GrammarError> {
+    let mut errors = Vec::new();
+    let mut previous: Option<&GrammarToken> = None;
+    let mut run = 0;
+    for sentence in &paragraph.sentences {
+        let first = sentence
+            .tokens
+            .iter()
+            .find(|t| t.token_type == TokenType::Word);
+        run = match (previous, first) {
+            (Some(p), Some(f)) if equals_ignore_case(&p.text, &f.text) => run + 1,
+            (_, Some(_)) => 1,
+            (_, None) => 0,
+        };
+        previous = first;
+        if run >= REPEATED_START_LIMIT {
+            if let Some(first) = first {
+                errors.push(GrammarError::new(
+                    GCERR_REPEATED_SENTENCE_START,
+                    first.pos,
+                    first.token_len(),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Quotation marks left without a pair at the end of the paragraph.
+///
+/// The marks are paired as in the capitalization check: a mark closes the
+/// innermost open quotation if it is the same mark, and opens a nested one
+/// otherwise. Of the marks left over, one that follows an open quotation
+/// with a different mark is reported as its mismatched closing mark, with
+/// the opening mark as the suggestion; any other is an unclosed quotation.
+/// Unclosed quotations are accepted at the end of an unfinished paragraph,
+/// as a quotation may continue in the next one.
+fn unbalanced_quotations(
+    paragraph: &GrammarParagraph,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let marks = paragraph
+        .sentences
+        .iter()
+        .flat_map(|s| &s.tokens)
+        .filter(|t| t.token_type == TokenType::Punctuation && t.text.len() == 1)
+        .filter(|t| is_finnish_quotation_mark(t.text[0]));
+    for token in marks {
+        let mark = token.text[0];
+        if open.last().is_some_and(|&(last, _)| last == mark) {
+            open.pop();
+        } else {
+            open.push((mark, token.pos));
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut i = 0;
+    while i < open.len() {
+        if let Some(&(_, pos)) = open.get(i + 1) {
+            // Adjacent leftover marks always differ: the pair is mismatched.
+            errors.push(GrammarError::with_suggestions(
+                GCERR_UNBALANCED_QUOTATION,
+                pos,
+                1,
+                vec![open[i].0.to_string()],
+            ));
+            i += 2;
+        } else {
+            if !options.accept_unfinished_paragraphs_in_gc {
+                errors.push(GrammarError::new(GCERR_UNBALANCED_QUOTATION, open[i].1, 1));
+            }
+            i += 1;
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::checks::{GrammarSentence, GrammarToken};
+    use super::*;
+
+    /// Build a paragraph from sentences given as token texts; a token
+    /// starting with a letter is a word, a space whitespace and anything
+    /// else punctuation.
+    fn paragraph(sentences: &[&[&str]]) -> GrammarParagraph {
+        let mut pos = 0;
+        let mut paragraph = GrammarParagraph::new();
+        for texts in sentences {
+            let mut sentence = GrammarSentence::new(pos);
+            for text in *texts {
+                let first = text.chars().next().unwrap();
+                let token_type = if first.is_alphabetic() {
+                    TokenType::Word
+                } else if first == ' ' {
+                    TokenType::Whitespace
+                } else {
+                    TokenType::Punctuation
+                };
+                let chars: Vec<char> = text.chars().collect();
+                let len = chars.len();
+                sentence
+                    .tokens
+                    .push(GrammarToken::new(token_type, chars, pos));
+                pos += len;
+            }
+            paragraph.sentences.push(sentence);
+        }
+        paragraph
+    }
+
+    fn options() -> GrammarOptions {
+        GrammarOptions {
+            check_discourse: true,
+            ..GrammarOptions::default()
+        }
+    }
+
+    fn found(paragraph: &GrammarParagraph, options: &GrammarOptions) -> Vec<(i32, usize)> {
+        gc_discourse(paragraph, options)
+            .into_iter()
+            .map(|e| (e.error_code, e.start_pos))
+            .collect()
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let p = paragraph(&[&["”", "Tule", "."]]);
+        assert!(gc_discourse(&p, &GrammarOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn third_sentence_with_the_same_start() {
+        // "Hän tuli. Hän istui. hän söi. Hän lähti. Se loppui."
+        let p = paragraph(&[
+            &["Hän", " ", "tuli", ".", " "],
+            &["Hän", " ", "istui", ".", " "],
+            &["hän", " ", "söi", ".", " "],
+            &["Hän", " ", "lähti", ".", " "],
+            &["Se", " ", "loppui", "."],
+        ]);
+        assert_eq!(
+            found(&p, &options()),
+            [
+                (GCERR_REPEATED_SENTENCE_START, 21),
+                (GCERR_REPEATED_SENTENCE_START, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn two_sentences_with_the_same_start_are_fine() {
+        let p = paragraph(&[
+            &["Hän", " ", "tuli", ".", " "],
+            &["Hän", " ", "istui", ".", " "],
+            &["Se", " ", "loppui", ".", " "],
+            &["Hän", " ", "lähti", "."],
+        ]);
+        assert!(found(&p, &options()).is_empty());
+    }
+
+    #[test]
+    fn quotation_spanning_sentences() {
+        // "Hän sanoi: ”Tule. Nyt.” Se loppui."
+        let p = paragraph(&[
+            &["Hän", " ", "sanoi", ":", " ", "”", "Tule", ".", " "],
+            &["Nyt", ".", "”", " "],
+            &["Se", " ", "loppui", "."],
+        ]);
+        assert!(found(&p, &options()).is_empty());
+    }
+
+    #[test]
+    fn unclosed_quotation() {
+        // "Hän sanoi: ”Tule. Nyt."
+        let p = paragraph(&[
+            &["Hän", " ", "sanoi", ":", " ", "”", "Tule", ".", " "],
+            &["Nyt", "."],
+        ]);
+        assert_eq!(found(&p, &options()), [(GCERR_UNBALANCED_QUOTATION, 11)]);
+
+        let accept_unfinished = GrammarOptions {
+            accept_unfinished_paragraphs_in_gc: true,
+            ..options()
+        };
+        assert!(found(&p, &accept_unfinished).is_empty());
+    }
+
+    #[test]
+    fn mismatched_closing_mark() {
+        // "”Tule. Nyt\" ja lähti. »Hei»."
+        let p = paragraph(&[
+            &["”", "Tule", ".", " "],
+            &["Nyt", "\"", " ", "ja", " ", "lähti", ".", " "],
+            &["»", "Hei", "»", "."],
+        ]);
+        let errors = gc_discourse(&p, &options());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code, GCERR_UNBALANCED_QUOTATION);
+        assert_eq!(errors[0].start_pos, 10);
+        assert_eq!(errors[0].suggestions, ["”"]);
+    }
+}
@@ -5,15 +5,27 @@
 //
 // Origin: grammar/FinnishRuleEngine.cpp, grammar/FinnishRuleEngine.hpp
 
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
 use voikko_core::grammar_error::GrammarError;
 use voikko_fst::unweighted::UnweightedTransducer;
 
-use super::autocorrect::gc_autocorrect;
+use super::agreement::gc_subject_verb_agreement;
+use super::autocorrect::{Autocorrect, gc_autocorrect};
+use super::autocorrect_rules::{AutocorrectRuleError, AutocorrectRules};
 use super::checks::{
-    GrammarOptions, GrammarParagraph, gc_capitalization, gc_compound_verb, gc_end_punctuation,
-    gc_local_punctuation, gc_missing_verb, gc_negative_verb_mismatch, gc_punctuation_of_quotations,
+    GrammarOptions, GrammarParagraph, GrammarSentence, add_replacements, gc_capitalization,
+    gc_compound_verb, gc_end_punctuation, gc_local_punctuation, gc_missing_verb,
+    gc_negative_verb_mismatch, gc_overlong_compound, gc_punctuation_of_quotations,
     gc_repeating_words, gc_sidesana,
 };
+use super::conjunctions::gc_paired_conjunctions;
+use super::discourse::gc_discourse;
+use super::numbers::gc_number_formats;
+use super::real_word::gc_real_word_errors;
+use super::rules::RuleSet;
+use super::timing::{ParagraphTimer, RuleStats, RuleTimings, SlowRule};
+use crate::morphology::Analyzer;
 
 /// Finnish rule engine that orchestrates all grammar checks on a paragraph.
 ///
@@ -21,15 +33,22 @@ use super::checks::{
 pub(crate) struct FinnishRuleEngine {
     /// Grammar checker options.
     options: GrammarOptions,
-    /// Optional autocorrect transducer (loaded from autocorr.vfst).
-    autocorrect_transducer: Option<UnweightedTransducer>,
+    /// Autocorrect transducer (loaded from autocorr.vfst) and user rules.
+    autocorrect: Autocorrect,
+    /// The built-in default rule pack, run if `check_default_rules` is set.
+    /// Compiled on first use.
+    default_rules: OnceLock<RuleSet>,
+    /// Rule packs loaded at runtime.
+    rules: RuleSet,
+    /// Per-rule timing statistics, collected if enabled in the options.
+    timings: Mutex<RuleTimings>,
 }
 
 impl FinnishRuleEngine {
     /// Create a new FinnishRuleEngine.
     ///
     /// The `autocorrect_transducer` is loaded from `autocorr.vfst` if available.
-    /// If `None`, autocorrect checking is skipped.
+    /// If `None`, autocorrect checking is skipped until user rules are added.
     ///
     /// Origin: FinnishRuleEngine.cpp:47-59
     pub(crate) fn new(
@@ -38,7 +57,10 @@ impl FinnishRuleEngine {
     ) -> Self {
         Self {
             options,
-            autocorrect_transducer,
+            autocorrect: Autocorrect::new(autocorrect_transducer),
+            default_rules: OnceLock::new(),
+            rules: RuleSet::new(),
+            timings: Mutex::new(RuleTimings::default()),
         }
     }
 
@@ -47,6 +69,75 @@ impl FinnishRuleEngine {
         self.options = options;
     }
 
+    /// Replace the autocorrect transducer.
+    pub(crate) fn set_autocorrect_transducer(&self, transducer: Option<UnweightedTransducer>) {
+        self.autocorrect.set_transducer(transducer);
+    }
+
+    /// Add the rules of a runtime-loaded rule pack.
+    pub(crate) fn add_rules(&mut self, rules: RuleSet) {
+        self.rules.extend(rules);
+    }
+
+    /// Remove all runtime-loaded rules.
+    pub(crate) fn clear_rules(&mut self) {
+        self.rules = RuleSet::new();
+    }
+
+    /// The user autocorrect rules.
+    pub(crate) fn autocorrect_rules(&self) -> &AutocorrectRules {
+        self.autocorrect.rules()
+    }
+
+    /// Add a user autocorrect rule.
+    pub(crate) fn add_autocorrect_rule(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<(), AutocorrectRuleError> {
+        self.autocorrect.add_rule(from, to)
+    }
+
+    /// Remove the user autocorrect rule for `from`.
+    pub(crate) fn remove_autocorrect_rule(&mut self, from: &str) -> bool {
+        self.autocorrect.remove_rule(from)
+    }
+
+    /// Remove all user autocorrect rules.
+    pub(crate) fn clear_autocorrect_rules(&mut self) {
+        self.autocorrect.clear_rules();
+    }
+
+    /// Lock the timing statistics. A poisoned lock is recovered, since the
+    /// statistics are only added to.
+    fn timings(&self) -> MutexGuard<'_, RuleTimings> {
+        self.timings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Per-rule timing statistics, slowest first.
+    pub(crate) fn rule_stats(&self) -> Vec<RuleStats> {
+        self.timings().stats()
+    }
+
+    /// Recent rules that exceeded the time cap, oldest first.
+    pub(crate) fn slow_rules(&self) -> Vec<SlowRule> {
+        self.timings().slow_rules()
+    }
+
+    /// Discard the timing statistics and slow-rule diagnostics.
+    pub(crate) fn reset_rule_stats(&self) {
+        *self.timings() = RuleTimings::default();
+    }
+
+    /// Autocorrect matches in a sentence, as reported by the autocorrect
+    /// check.
+    pub(crate) fn autocorrections(&self, sentence: &GrammarSentence) -> Vec<GrammarError> {
+        if self.autocorrect.is_empty() {
+            return Vec::new();
+        }
+        gc_autocorrect(sentence, &self.autocorrect)
+    }
+
     /// Access the current options.
     pub(crate) fn options(&self) -> &GrammarOptions {
         &self.options
@@ -61,48 +152,99 @@ impl FinnishRuleEngine {
     /// The order of checks matches the C++ FinnishRuleEngine::check:
     /// 1. Per-sentence: local punctuation, quotation punctuation, repeating words
     /// 2. Per-sentence: verb checks (missing verb, negative verb mismatch,
-    ///    compound verb, sidesana, autocorrect)
-    /// 3. Paragraph-level: capitalization, end punctuation
+    ///    compound verb, sidesana), the opt-in style checks (overlong
+    ///    compounds, number formats, paired conjunctions, real-word errors,
+    ///    subject-verb agreement), autocorrect, then declarative rules
+    /// 3. Paragraph-level: capitalization, end punctuation, then the
+    ///    discourse checks (Rust extension)
     ///
     /// Origin: FinnishRuleEngine.cpp:69-86
-    pub(crate) fn check(&self, paragraph: &GrammarParagraph) -> Vec<GrammarError> {
+    ///
+    /// `analyzer` is used by the checks that analyze words again (real-word
+    /// errors, subject-verb agreement, rule patterns with a baseform or
+    /// class); without one they report nothing.
+    pub(crate) fn check(
+        &self,
+        paragraph: &GrammarParagraph,
+        analyzer: Option<&dyn Analyzer>,
+    ) -> Vec<GrammarError> {
         let mut errors = Vec::new();
+        let mut timer = ParagraphTimer::new(self.options.timing, paragraph);
 
         // Per-sentence checks
         for sentence in &paragraph.sentences {
             // Punctuation and whitespace checks
             // Origin: FinnishRuleEngine.cpp:72
-            errors.extend(gc_local_punctuation(sentence));
+            errors.extend(timer.run("local_punctuation", || gc_local_punctuation(sentence)));
 
             // Quotation punctuation check
             // Origin: FinnishRuleEngine.cpp:73
-            errors.extend(gc_punctuation_of_quotations(sentence));
+            errors.extend(timer.run("punctuation_of_quotations", || {
+                gc_punctuation_of_quotations(sentence)
+            }));
 
             // Repeating word check
             // Origin: FinnishRuleEngine.cpp:74
-            errors.extend(gc_repeating_words(sentence));
+            errors.extend(timer.run("repeating_words", || gc_repeating_words(sentence)));
 
             // Missing verb and extra main verb check
             // Origin: FinnishRuleEngine.cpp:49 (MissingVerbCheck)
             // Note: MissingVerbCheck.cpp handles both missing and extra main verb
-            errors.extend(gc_missing_verb(sentence, &self.options));
+            errors.extend(timer.run("missing_verb", || gc_missing_verb(sentence, &self.options)));
 
             // Negative verb mismatch check
             // Origin: FinnishRuleEngine.cpp:50 (NegativeVerbCheck)
-            errors.extend(gc_negative_verb_mismatch(sentence));
+            errors.extend(timer.run("negative_verb", || gc_negative_verb_mismatch(sentence)));
 
             // Compound verb infinitive type check
             // Origin: FinnishRuleEngine.cpp:51 (CompoundVerbCheck)
-            errors.extend(gc_compound_verb(sentence));
+            errors.extend(timer.run("compound_verb", || gc_compound_verb(sentence)));
 
             // Misplaced conjunction check
             // Origin: FinnishRuleEngine.cpp:52 (SidesanaCheck)
-            errors.extend(gc_sidesana(sentence));
-
-            // Autocorrect check (if transducer available)
+            errors.extend(timer.run("sidesana", || gc_sidesana(sentence)));
+
+            // Overlong compound check (opt-in style rule)
+            errors.extend(timer.run("overlong_compound", || {
+                gc_overlong_compound(sentence, &self.options)
+            }));
+
+            // Date, time, ordinal and unit format check (opt-in)
+            errors.extend(timer.run("number_formats", || {
+                gc_number_formats(sentence, &self.options)
+            }));
+
+            // Paired conjunction completeness check (opt-in)
+            errors.extend(timer.run("paired_conjunctions", || {
+                gc_paired_conjunctions(sentence, &self.options)
+            }));
+
+            // Context-sensitive real-word error check (opt-in)
+            errors.extend(timer.run("real_word_errors", || {
+                gc_real_word_errors(sentence, analyzer, &self.options)
+            }));
+
+            // Subject-verb agreement check (opt-in, experimental)
+            errors.extend(timer.run("subject_verb_agreement", || {
+                gc_subject_verb_agreement(sentence, analyzer, &self.options)
+            }));
+
+            // Autocorrect check (if transducer or user rules available)
             // Origin: FinnishRuleEngine.cpp:54-58
-            if let Some(ref transducer) = self.autocorrect_transducer {
-                errors.extend(gc_autocorrect(sentence, transducer));
+            if !self.autocorrect.is_empty() {
+                errors.extend(timer.run("autocorrect", || {
+                    gc_autocorrect(sentence, &self.autocorrect)
+                }));
+            }
+
+            // Declarative rules (embedded defaults and loaded rule packs)
+            if self.options.check_default_rules {
+                let default_rules = self.default_rules.get_or_init(RuleSet::defaults);
+                errors
+                    .extend(timer.run("default_rules", || default_rules.check(sentence, analyzer)));
+            }
+            if !self.rules.is_empty() {
+                errors.extend(timer.run("loaded_rules", || self.rules.check(sentence, analyzer)));
             }
         }
 
@@ -110,11 +252,32 @@ impl FinnishRuleEngine {
 
         // Capitalization check (operates across sentences)
         // Origin: FinnishRuleEngine.cpp:83
-        errors.extend(gc_capitalization(paragraph, &self.options));
+        errors.extend(timer.run("capitalization", || {
+            gc_capitalization(paragraph, &self.options)
+        }));
 
         // End punctuation check
         // Origin: FinnishRuleEngine.cpp:84
-        errors.extend(gc_end_punctuation(paragraph, &self.options));
+        errors.extend(timer.run("end_punctuation", || {
+            gc_end_punctuation(paragraph, &self.options)
+        }));
+
+        // Repeated sentence starts and quotation pairing (opt-in)
+        errors.extend(timer.run("discourse", || gc_discourse(paragraph, &self.options)));
+
+        timer.finish(&mut self.timings());
+
+        add_replacements(paragraph, &mut errors);
+
+        // Host-configured severities replace the defaults
+        let overrides = &self.options.severity_overrides;
+        if !overrides.is_empty() {
+            for error in &mut errors {
+                if let Some(&severity) = overrides.get(&error.error_code) {
+                    error.severity = severity;
+                }
+            }
+        }
 
         errors
     }
@@ -125,8 +288,8 @@ mod tests {
     use super::*;
     use voikko_core::enums::TokenType;
     use voikko_core::grammar_error::{
-        GCERR_EXTRA_WHITESPACE, GCERR_REPEATING_WORD, GCERR_TERMINATING_PUNCTUATION_MISSING,
-        GCERR_WRITE_FIRST_UPPERCASE,
+        GCERR_CUSTOM_RULE, GCERR_EXTRA_WHITESPACE, GCERR_REPEATING_WORD,
+        GCERR_TERMINATING_PUNCTUATION_MISSING, GCERR_WRITE_FIRST_UPPERCASE, GrammarErrorSeverity,
     };
 
     use super::super::checks::GrammarOptions;
@@ -165,7 +328,7 @@ mod tests {
         );
         let p = GrammarParagraph { sentences: vec![s] };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(errs.iter().any(|e| e.error_code == GCERR_EXTRA_WHITESPACE));
     }
 
@@ -182,7 +345,7 @@ mod tests {
         );
         let p = GrammarParagraph { sentences: vec![s] };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(errs.iter().any(|e| e.error_code == GCERR_REPEATING_WORD));
     }
 
@@ -202,7 +365,7 @@ mod tests {
             sentences: vec![s1, s2],
         };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(
             errs.iter()
                 .any(|e| e.error_code == GCERR_TERMINATING_PUNCTUATION_MISSING)
@@ -217,7 +380,7 @@ mod tests {
         let s = sentence(vec![w1, punct(".", 5)], 0);
         let p = GrammarParagraph { sentences: vec![s] };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(
             errs.iter()
                 .any(|e| e.error_code == GCERR_WRITE_FIRST_UPPERCASE)
@@ -228,7 +391,7 @@ mod tests {
     fn engine_empty_paragraph_no_errors() {
         let p = GrammarParagraph { sentences: vec![] };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(errs.is_empty());
     }
 
@@ -241,7 +404,7 @@ mod tests {
             ..Default::default()
         };
         let engine = FinnishRuleEngine::new(opts, None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         assert!(
             !errs
                 .iter()
@@ -249,12 +412,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn engine_applies_severity_overrides() {
+        let s = sentence(
+            vec![
+                word("Koira", 0),
+                ws("  ", 5),
+                word("koira", 7),
+                punct(".", 12),
+            ],
+            0,
+        );
+        let p = GrammarParagraph { sentences: vec![s] };
+        let mut opts = GrammarOptions::default();
+        opts.severity_overrides
+            .insert(GCERR_EXTRA_WHITESPACE, GrammarErrorSeverity::Warning);
+        let engine = FinnishRuleEngine::new(opts, None);
+        let errs = engine.check(&p, None);
+        let severity_of = |code| errs.iter().find(|e| e.error_code == code).unwrap().severity;
+        assert_eq!(
+            severity_of(GCERR_EXTRA_WHITESPACE),
+            GrammarErrorSeverity::Warning
+        );
+        assert_eq!(
+            severity_of(GCERR_REPEATING_WORD),
+            GrammarErrorSeverity::Error
+        );
+    }
+
     #[test]
     fn engine_no_autocorrect_without_transducer() {
         let s = sentence(vec![word("Koira", 0), punct(".", 5)], 0);
         let p = GrammarParagraph { sentences: vec![s] };
         let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
-        let errs = engine.check(&p);
+        let errs = engine.check(&p, None);
         // No GCERR_INVALID_SPELLING expected without a transducer
         assert!(
             !errs
@@ -262,4 +453,71 @@ mod tests {
                 .any(|e| e.error_code == voikko_core::grammar_error::GCERR_INVALID_SPELLING)
         );
     }
+
+    #[test]
+    fn engine_runs_default_rules_only_when_enabled() {
+        let s = sentence(
+            vec![
+                word("Mutta", 0),
+                ws(" ", 5),
+                word("kuitenkin", 6),
+                punct(".", 15),
+            ],
+            0,
+        );
+        let p = GrammarParagraph { sentences: vec![s] };
+        let is_rule = |e: &GrammarError| e.error_code == GCERR_CUSTOM_RULE;
+
+        let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
+        assert!(!engine.check(&p, None).iter().any(is_rule));
+
+        let opts = GrammarOptions {
+            check_default_rules: true,
+            ..Default::default()
+        };
+        let mut engine = FinnishRuleEngine::new(opts, None);
+        assert!(engine.check(&p, None).iter().any(is_rule));
+
+        engine.set_options(GrammarOptions::default());
+        engine.add_rules(RuleSet::defaults());
+        let errs = engine.check(&p, None);
+        assert_eq!(errs.iter().filter(|e| is_rule(e)).count(), 1);
+        engine.clear_rules();
+        assert!(!engine.check(&p, None).iter().any(is_rule));
+    }
+
+    #[test]
+    fn engine_times_rules_and_applies_cap() {
+        let s = sentence(vec![word("Koira", 0), punct(".", 5)], 0);
+        let p = GrammarParagraph {
+            sentences: vec![s.clone(), s],
+        };
+
+        let engine = FinnishRuleEngine::new(GrammarOptions::default(), None);
+        engine.check(&p, None);
+        assert!(engine.rule_stats().is_empty());
+
+        let mut opts = GrammarOptions::default();
+        opts.timing.enabled = true;
+        let mut engine = FinnishRuleEngine::new(opts.clone(), None);
+        engine.check(&p, None);
+        let stats = engine.rule_stats();
+        let runs = |stats: &[RuleStats], rule| stats.iter().find(|s| s.rule == rule).unwrap().runs;
+        assert_eq!(runs(&stats, "missing_verb"), 2);
+        assert_eq!(runs(&stats, "end_punctuation"), 1);
+        assert!(!stats.iter().any(|s| s.rule == "loaded_rules"));
+        assert!(engine.slow_rules().is_empty());
+
+        // With a zero cap every rule is skipped after its first run
+        opts.timing.cap = Some(std::time::Duration::ZERO);
+        engine.set_options(opts);
+        engine.reset_rule_stats();
+        engine.check(&p, None);
+        let stats = engine.rule_stats();
+        assert_eq!(runs(&stats, "missing_verb"), 1);
+        assert!(stats.iter().all(|s| s.capped == 1));
+        let slow = engine.slow_rules();
+        assert_eq!(slow.len(), stats.len());
+        assert_eq!(slow[0].excerpt, "Koira.Koira.");
+    }
 }
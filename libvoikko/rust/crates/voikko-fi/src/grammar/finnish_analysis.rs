@@ -1,6 +1,8 @@
 // Finnish morphological analysis to grammar token annotation
 // Origin: grammar/FinnishAnalysis.hpp, FinnishAnalysis.cpp
 
+use std::collections::HashMap;
+
 use voikko_core::analysis::{
     ATTR_CLASS, ATTR_MOOD, ATTR_NEGATIVE, ATTR_PARTICIPLE, ATTR_PERSON,
     ATTR_POSSIBLE_GEOGRAPHICAL_NAME, ATTR_REQUIRE_FOLLOWING_VERB, ATTR_SIJAMUOTO, ATTR_STRUCTURE,
@@ -42,6 +44,7 @@ pub(crate) fn analyse_token(token: &mut GrammarToken, analyzer: &dyn Analyzer) {
 
     token.require_following_verb = FollowingVerbType::None;
     token.verb_follower_type = FollowingVerbType::None;
+    token.compound_part_count = 0;
 
     // Origin: FinnishAnalysis.cpp:66-71 — Non-word tokens get minimal flags.
     if token.token_type != TokenType::Word {
@@ -53,7 +56,7 @@ pub(crate) fn analyse_token(token: &mut GrammarToken, analyzer: &dyn Analyzer) {
 
     // Origin: FinnishAnalysis.cpp:73-78 — Strip soft hyphens and analyze.
     let word = strip_soft_hyphens(&token.text);
-    let analyses = analyzer.analyze(&word, word.len());
+    let (analyses, _) = analyzer.analyze_any_case(&word, word.len());
 
     // Origin: FinnishAnalysis.cpp:81
     token.first_letter_lcase = true;
@@ -74,6 +77,12 @@ pub(crate) fn analyse_token(token: &mut GrammarToken, analyzer: &dyn Analyzer) {
         let possible_geo_name = analysis.get(ATTR_POSSIBLE_GEOGRAPHICAL_NAME);
         let require_following = analysis.get(ATTR_REQUIRE_FOLLOWING_VERB);
 
+        // Compound component count: each component starts with '='.
+        let parts = structure.matches('=').count();
+        if i == 0 || parts < token.compound_part_count {
+            token.compound_part_count = parts;
+        }
+
         // Origin: FinnishAnalysis.cpp:94-103 — first_letter_lcase / geographical name
         let structure_chars: Vec<char> = structure.chars().collect();
         if structure_chars.len() < 2 || (structure_chars[1] != 'p' && structure_chars[1] != 'q') {
@@ -210,6 +219,46 @@ pub(crate) fn analyse_token(token: &mut GrammarToken, analyzer: &dyn Analyzer) {
     }
 }
 
+// ---------------------------------------------------------------------------
+// TokenMemo
+// ---------------------------------------------------------------------------
+
+/// Paragraph-scoped memo of token annotations, keyed by token text.
+///
+/// The annotation of a word token depends only on its text, and the same
+/// words recur within a paragraph, so each distinct word is analyzed once.
+/// A memo is created for each paragraph and dropped with it, which bounds
+/// its size independently of the speller's global cache.
+///
+/// The memo keeps the whole annotation rather than a spelling verdict:
+/// grammar checking never asks the speller, it takes `is_valid_word` from
+/// the same analyses that set the other flags, so only memoizing the
+/// annotation saves the lookups.
+#[derive(Default)]
+pub(crate) struct TokenMemo {
+    entries: HashMap<Vec<char>, GrammarToken>,
+}
+
+impl TokenMemo {
+    /// Annotate `token` like `analyse_token`, reusing the result for a word
+    /// seen earlier in the paragraph.
+    pub(crate) fn analyse(&mut self, token: &mut GrammarToken, analyzer: &dyn Analyzer) {
+        if token.token_type != TokenType::Word {
+            analyse_token(token, analyzer);
+            return;
+        }
+        if let Some(cached) = self.entries.get(&token.text) {
+            *token = GrammarToken {
+                pos: token.pos,
+                ..cached.clone()
+            };
+            return;
+        }
+        analyse_token(token, analyzer);
+        self.entries.insert(token.text.clone(), token.clone());
+    }
+}
+
 // ===========================================================================
 // Tests
 // ===========================================================================
@@ -767,6 +816,54 @@ mod tests {
         assert!(!token.first_letter_lcase);
     }
 
+    #[test]
+    fn compound_part_count_is_minimum_over_analyses() {
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add(
+            "kuusipuu",
+            vec![
+                make_analysis(&[(ATTR_STRUCTURE, "=pppp=pppp"), (ATTR_CLASS, "nimisana")]),
+                make_analysis(&[(ATTR_STRUCTURE, "=pppppppp"), (ATTR_CLASS, "nimisana")]),
+            ],
+        );
+
+        let mut token = word_token("kuusipuu");
+        analyse_token(&mut token, &analyzer);
+        assert_eq!(token.compound_part_count, 1);
+
+        let mut unknown = word_token("asdfgh");
+        analyse_token(&mut unknown, &analyzer);
+        assert_eq!(unknown.compound_part_count, 0);
+    }
+
+    #[test]
+    fn token_memo_analyzes_each_word_once() {
+        struct CountingAnalyzer(std::cell::Cell<usize>);
+        impl Analyzer for CountingAnalyzer {
+            fn analyze(&self, _word: &[char], _word_len: usize) -> Vec<Analysis> {
+                self.0.set(self.0.get() + 1);
+                vec![make_analysis(&[
+                    (ATTR_STRUCTURE, "=ppp"),
+                    (ATTR_CLASS, "sidesana"),
+                ])]
+            }
+        }
+
+        let analyzer = CountingAnalyzer(std::cell::Cell::new(0));
+        let mut memo = TokenMemo::default();
+        let mut first = word_token("ja");
+        memo.analyse(&mut first, &analyzer);
+        let mut second = GrammarToken::new(TokenType::Word, "ja".chars().collect(), 7);
+        memo.analyse(&mut second, &analyzer);
+        let mut other = word_token("tai");
+        memo.analyse(&mut other, &analyzer);
+
+        assert_eq!(analyzer.0.get(), 2);
+        assert_eq!(second.pos, 7);
+        assert!(second.is_valid_word);
+        assert!(second.is_conjunction);
+    }
+
     #[test]
     fn structure_q_means_lowercase() {
         // STRUCTURE with 'q' at position 1 means lowercase abbreviation.
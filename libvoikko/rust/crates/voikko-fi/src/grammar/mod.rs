@@ -1,20 +1,30 @@
 // Grammar checking module
 // Origin: grammar/
 
+pub(crate) mod agreement;
+pub mod analyzed;
 #[allow(dead_code)]
-pub mod autocorrect;
+pub(crate) mod autocorrect;
+pub mod autocorrect_rules;
 #[allow(dead_code)]
-pub mod cache;
+pub(crate) mod cache;
 #[allow(dead_code)]
 pub mod checker;
 #[allow(dead_code)]
 pub mod checks;
+pub(crate) mod conjunctions;
+pub(crate) mod discourse;
 #[allow(dead_code)]
-pub mod engine;
+pub(crate) mod engine;
 #[allow(dead_code)]
-pub mod finnish_analysis;
+pub(crate) mod finnish_analysis;
+pub mod numbers;
 #[allow(dead_code)]
-pub mod paragraph;
+pub(crate) mod paragraph;
+pub(crate) mod pattern;
+pub(crate) mod real_word;
+pub mod rules;
+pub mod timing;
 
 use voikko_core::grammar_error::GrammarError;
 
@@ -28,3 +38,34 @@ pub trait GrammarChecker {
     /// The text is provided as a char slice for random-access indexing.
     fn check(&self, text: &[char], text_len: usize) -> Vec<GrammarError>;
 }
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use voikko_core::enums::TokenType;
+
+    use super::checks::{GrammarSentence, GrammarToken};
+
+    /// Build a sentence from token texts; a token starting with a letter is
+    /// a word, a space whitespace and anything else punctuation.
+    pub(crate) fn sentence(texts: &[&str]) -> GrammarSentence {
+        let mut pos = 0;
+        let mut sentence = GrammarSentence::new(0);
+        for text in texts {
+            let first = text.chars().next().unwrap();
+            let token_type = if first.is_alphabetic() {
+                TokenType::Word
+            } else if first == ' ' {
+                TokenType::Whitespace
+            } else {
+                TokenType::Punctuation
+            };
+            let chars: Vec<char> = text.chars().collect();
+            let len = chars.len();
+            sentence
+                .tokens
+                .push(GrammarToken::new(token_type, chars, pos));
+            pos += len;
+        }
+        sentence
+    }
+}
@@ -0,0 +1,459 @@
+// Number, date and time format checks
+//
+// Opt-in checks for how numeric expressions are written in Finnish text:
+// dates ("24.12.2024", no dot after the year inside a sentence), clock
+// times ("klo 12.30" in standard text, "12:30" in technical text), ordinal
+// numbers ("3:nnella", "3. luokka") and the space between a number and its
+// unit ("5 km", "10 %", "20 °C"). The tokenizer keeps "24.12.2024",
+// "12.30", "3.nnella" and "5km" as single word tokens and splits "12:30"
+// and "10%" at the punctuation, so the checks look at word tokens and the
+// tokens right after them. Every error carries the corrected form as its
+// suggestion, except for impossible dates.
+
+use voikko_core::character::{equals_ignore_case, is_upper};
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{
+    GCERR_DATE_FORMAT, GCERR_ORDINAL_FORMAT, GCERR_TIME_FORMAT, GCERR_UNIT_SPACING, GrammarError,
+};
+
+use super::checks::{GrammarOptions, GrammarSentence, GrammarToken};
+
+/// How numeric expressions are expected to be written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NumberStyle {
+    /// General text: dates without leading zeros ("1.2.2024") and clock
+    /// times with a period ("klo 9.05").
+    #[default]
+    Standard,
+    /// Technical text: leading zeros and ISO 8601 dates ("2024-02-01") are
+    /// accepted and clock times use a colon ("klo 9:05").
+    Technical,
+}
+
+/// Inflectional endings written after an ordinal number with a colon
+/// ("3:nnella", "1:sen").
+const ORDINAL_ENDINGS: &[&str] = &[
+    "s", "nen", "sen", "nnen", "nnessa", "nnessä", "nnesta", "nnestä", "nteen", "nnella", "nnellä",
+    "nnelta", "nneltä", "nnelle", "nneksi", "ntena", "ntenä", "tta", "ttä", "nnet", "nsien",
+    "nsia", "nsiä", "nsissa", "nsissä", "nsille", "nsiksi",
+];
+
+/// Units written as a separate word after a number.
+const UNITS: &[&str] = &[
+    "mm", "cm", "dm", "m", "km", "mg", "g", "kg", "ml", "cl", "dl", "l", "ms", "min", "h", "W",
+    "kW", "MW", "kWh", "MWh", "V", "mV", "kV", "A", "mA", "Hz", "kHz", "MHz", "GHz", "kB", "MB",
+    "GB", "TB", "ha",
+];
+
+/// Signs that the tokenizer splits from the number but that still take a
+/// space before them.
+const SIGNS: &[char] = &['%', '\u{2030}', '\u{20ac}', '$', '\u{00a3}'];
+
+/// Report dates, clock times, ordinal numbers and units written against
+/// the Finnish conventions of the configured [`NumberStyle`].
+pub(crate) fn gc_number_formats(
+    sentence: &GrammarSentence,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_number_formats {
+        return Vec::new();
+    }
+    let tokens = &sentence.tokens;
+    (0..tokens.len())
+        .filter(|&i| tokens[i].token_type == TokenType::Word)
+        .filter_map(|i| {
+            check_date(tokens, i, options.number_style)
+                .or_else(|| check_time(tokens, i, options.number_style))
+                .or_else(|| check_ordinal(&tokens[i]))
+                .or_else(|| check_unit(tokens, i))
+        })
+        .collect()
+}
+
+/// Dates "D.M.YYYY" and "YYYY-MM-DD": impossible dates, a dot after the
+/// year inside a sentence and, in standard style, leading zeros and ISO
+/// dates.
+fn check_date(tokens: &[GrammarToken], i: usize, style: NumberStyle) -> Option<GrammarError> {
+    let token = &tokens[i];
+    let text = token_text(token);
+    let (day, month, year) =
+        if let Some([day, month, year]) = digit_groups(&text, '.', [(1, 2), (1, 2), (4, 4)]) {
+            (day, month, year)
+        } else {
+            let [year, month, day] = digit_groups(&text, '-', [(4, 4), (2, 2), (2, 2)])?;
+            (day, month, year)
+        };
+    let (d, m, y): (u32, u32, u32) = (day.parse().ok()?, month.parse().ok()?, year.parse().ok()?);
+    if d == 0 || d > days_in_month(m, y) {
+        return Some(GrammarError::new(
+            GCERR_DATE_FORMAT,
+            token.pos,
+            token.token_len(),
+        ));
+    }
+
+    // "24.12.2024. klo 12" -- the dot belongs only to dates without a year
+    let extra_dot = tokens.get(i + 1).is_some_and(|t| is_punctuation(t, '.'))
+        && tokens[i + 2..]
+            .iter()
+            .find(|t| t.token_type == TokenType::Word)
+            .is_some_and(|t| !t.text.first().copied().is_some_and(is_upper));
+    let fixed = match style {
+        NumberStyle::Standard => format!("{d}.{m}.{year}"),
+        NumberStyle::Technical => text.clone(),
+    };
+    if !extra_dot && fixed == text {
+        return None;
+    }
+    Some(GrammarError::with_suggestions(
+        GCERR_DATE_FORMAT,
+        token.pos,
+        token.token_len() + usize::from(extra_dot),
+        vec![fixed],
+    ))
+}
+
+/// Clock times: "12:30" in standard style and "klo 12.30" in technical
+/// style. A colon time counts only after "klo" or "kello" or with a
+/// two-digit hour, so that ratios such as "1:2" are left alone.
+fn check_time(tokens: &[GrammarToken], i: usize, style: NumberStyle) -> Option<GrammarError> {
+    let token = &tokens[i];
+    match style {
+        NumberStyle::Standard => {
+            if i > 0 && is_punctuation(&tokens[i - 1], ':') {
+                return None;
+            }
+            let hours = token_text(token);
+            if !hours.bytes().all(|b| b.is_ascii_digit())
+                || !(hours.len() == 2 || hours.len() == 1 && after_clock_word(tokens, i))
+            {
+                return None;
+            }
+            let mut fixed = hours;
+            let mut end = i;
+            // Minutes and optional seconds
+            while end - i < 4
+                && tokens.get(end + 1).is_some_and(|t| is_punctuation(t, ':'))
+                && tokens.get(end + 2).is_some_and(is_minutes)
+            {
+                fixed.push('.');
+                fixed.extend(&tokens[end + 2].text);
+                end += 2;
+            }
+            if end == i || !is_time(&fixed, '.') {
+                return None;
+            }
+            let last = &tokens[end];
+            Some(GrammarError::with_suggestions(
+                GCERR_TIME_FORMAT,
+                token.pos,
+                last.pos + last.token_len() - token.pos,
+                vec![fixed],
+            ))
+        }
+        NumberStyle::Technical => {
+            let text = token_text(token);
+            if !after_clock_word(tokens, i) || !is_time(&text, '.') {
+                return None;
+            }
+            Some(GrammarError::with_suggestions(
+                GCERR_TIME_FORMAT,
+                token.pos,
+                token.token_len(),
+                vec![text.replace('.', ":")],
+            ))
+        }
+    }
+}
+
+/// Ordinal numbers: an inflectional ending is attached with a colon
+/// ("3.nnella" -> "3:nnella") and a word after the ordinal dot is separated
+/// by a space ("3.luokka" -> "3. luokka").
+fn check_ordinal(token: &GrammarToken) -> Option<GrammarError> {
+    let text = token_text(token);
+    let (number, rest) = text.split_once('.')?;
+    if number.is_empty()
+        || !number.bytes().all(|b| b.is_ascii_digit())
+        || !rest.chars().all(char::is_lowercase)
+    {
+        return None;
+    }
+    let fixed = if ORDINAL_ENDINGS.contains(&rest) {
+        format!("{number}:{rest}")
+    } else if rest.chars().count() >= 3 {
+        format!("{number}. {rest}")
+    } else {
+        return None;
+    };
+    Some(GrammarError::with_suggestions(
+        GCERR_ORDINAL_FORMAT,
+        token.pos,
+        token.token_len(),
+        vec![fixed],
+    ))
+}
+
+/// A number followed directly by a unit ("5km") or by one of `SIGNS`
+/// ("10%") or a temperature ("20°C").
+fn check_unit(tokens: &[GrammarToken], i: usize) -> Option<GrammarError> {
+    let token = &tokens[i];
+    let text = token_text(token);
+    let number_len = number_prefix_len(&text);
+    if number_len == 0 {
+        return None;
+    }
+    let (number, unit) = text.split_at(number_len);
+    let (unit, len) = if unit.is_empty() {
+        let sign = tokens.get(i + 1)?;
+        match sign.text.as_slice() {
+            [c] if SIGNS.contains(c) => (c.to_string(), token.token_len() + 1),
+            ['\u{00b0}'] => {
+                let scale = tokens.get(i + 2)?;
+                if !matches!(scale.text.as_slice(), ['C'] | ['F']) {
+                    return None;
+                }
+                (format!("\u{00b0}{}", scale.text[0]), token.token_len() + 2)
+            }
+            _ => return None,
+        }
+    } else if UNITS.contains(&unit) {
+        (unit.to_string(), token.token_len())
+    } else {
+        return None;
+    };
+    Some(GrammarError::with_suggestions(
+        GCERR_UNIT_SPACING,
+        token.pos,
+        len,
+        vec![format!("{number} {unit}")],
+    ))
+}
+
+fn token_text(token: &GrammarToken) -> String {
+    token.text.iter().collect()
+}
+
+fn is_punctuation(token: &GrammarToken, c: char) -> bool {
+    token.token_type == TokenType::Punctuation && token.text == [c]
+}
+
+/// Two-digit minutes or seconds after a colon.
+fn is_minutes(token: &GrammarToken) -> bool {
+    token.token_type == TokenType::Word
+        && token.text.len() == 2
+        && token.text.iter().all(char::is_ascii_digit)
+}
+
+/// Returns `true` if the word two tokens back is "klo" or "kello".
+fn after_clock_word(tokens: &[GrammarToken], i: usize) -> bool {
+    i >= 2
+        && tokens[i - 1].token_type == TokenType::Whitespace
+        && ["klo", "kello"].iter().any(|w| {
+            let w: Vec<char> = w.chars().collect();
+            equals_ignore_case(&tokens[i - 2].text, &w)
+        })
+}
+
+/// Returns `true` if `text` is "H.MM" or "H.MM.SS" (with `separator`)
+/// within a day.
+fn is_time(text: &str, separator: char) -> bool {
+    let valid = |hours: &str, rest: &[&str]| {
+        hours.parse::<u32>().is_ok_and(|h| h < 24)
+            && rest.iter().all(|m| m.parse::<u32>().is_ok_and(|m| m < 60))
+    };
+    if let Some([h, m, s]) = digit_groups(text, separator, [(1, 2), (2, 2), (2, 2)]) {
+        valid(h, &[m, s])
+    } else if let Some([h, m]) = digit_groups(text, separator, [(1, 2), (2, 2)]) {
+        valid(h, &[m])
+    } else {
+        false
+    }
+}
+
+/// Split `text` at `separator` into exactly `N` groups of ASCII digits
+/// whose lengths are within the given inclusive ranges.
+fn digit_groups<const N: usize>(
+    text: &str,
+    separator: char,
+    lengths: [(usize, usize); N],
+) -> Option<[&str; N]> {
+    let mut parts = text.split(separator);
+    let mut groups = [""; N];
+    for (group, (min, max)) in groups.iter_mut().zip(lengths) {
+        let part = parts.next()?;
+        if part.len() < min || part.len() > max || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *group = part;
+    }
+    parts.next().is_none().then_some(groups)
+}
+
+/// Byte length of the number ("5", "2,5", "0.75") at the start of `text`,
+/// or 0 if `text` does not start with a digit.
+fn number_prefix_len(text: &str) -> usize {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+    let len = digits(text);
+    if len == 0 {
+        return 0;
+    }
+    match text[len..].strip_prefix([',', '.']) {
+        Some(rest) if digits(rest) > 0 => len + 1 + digits(rest),
+        _ => len,
+    }
+}
+
+fn days_in_month(month: u32, year: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a sentence from tokens given as (type, text) pairs.
+    fn sentence(tokens: &[(TokenType, &str)]) -> GrammarSentence {
+        let mut s = GrammarSentence::new(0);
+        let mut pos = 0;
+        for &(token_type, text) in tokens {
+            let text: Vec<char> = text.chars().collect();
+            let len = text.len();
+            s.tokens.push(GrammarToken::new(token_type, text, pos));
+            pos += len;
+        }
+        s
+    }
+
+    fn check(
+        tokens: &[(TokenType, &str)],
+        style: NumberStyle,
+    ) -> Vec<(i32, usize, usize, Vec<String>)> {
+        let options = GrammarOptions {
+            check_number_formats: true,
+            number_style: style,
+            ..Default::default()
+        };
+        gc_number_formats(&sentence(tokens), &options)
+            .into_iter()
+            .map(|e| (e.error_code, e.start_pos, e.error_len, e.suggestions))
+            .collect()
+    }
+
+    use TokenType::{Punctuation as P, Unknown as U, Whitespace as S, Word as W};
+
+    #[test]
+    fn disabled_by_default() {
+        let s = sentence(&[(W, "5km")]);
+        assert!(gc_number_formats(&s, &GrammarOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn date_formats() {
+        let std = NumberStyle::Standard;
+        assert!(check(&[(W, "24.12.2024"), (S, " "), (W, "klo")], std).is_empty());
+        assert!(check(&[(W, "Se"), (S, " "), (W, "24.12.2024"), (P, ".")], std).is_empty());
+        assert_eq!(
+            check(&[(W, "24.12.2024"), (P, "."), (S, " "), (W, "klo")], std),
+            [(GCERR_DATE_FORMAT, 0, 11, vec!["24.12.2024".to_string()])]
+        );
+        // A capital letter may start a sentence the splitter did not see
+        assert!(check(&[(W, "24.12.2024"), (P, "."), (S, " "), (W, "Se")], std).is_empty());
+        assert_eq!(
+            check(&[(W, "01.02.2024")], std),
+            [(GCERR_DATE_FORMAT, 0, 10, vec!["1.2.2024".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "2024-02-01")], std),
+            [(GCERR_DATE_FORMAT, 0, 10, vec!["1.2.2024".to_string()])]
+        );
+        assert!(check(&[(W, "01.02.2024")], NumberStyle::Technical).is_empty());
+        assert!(check(&[(W, "2024-02-01")], NumberStyle::Technical).is_empty());
+    }
+
+    #[test]
+    fn impossible_dates_have_no_suggestion() {
+        for date in ["30.2.2024", "32.1.2024", "1.13.2024", "2023-02-29"] {
+            assert_eq!(
+                check(&[(W, date)], NumberStyle::Technical),
+                [(GCERR_DATE_FORMAT, 0, date.len(), vec![])],
+                "{date}"
+            );
+        }
+        assert!(check(&[(W, "29.2.2024")], NumberStyle::Standard).is_empty());
+    }
+
+    #[test]
+    fn colon_time_in_standard_style() {
+        let std = NumberStyle::Standard;
+        assert_eq!(
+            check(&[(W, "klo"), (S, " "), (W, "9"), (P, ":"), (W, "05")], std),
+            [(GCERR_TIME_FORMAT, 4, 4, vec!["9.05".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "12"), (P, ":"), (W, "30"), (P, ":"), (W, "15")], std),
+            [(GCERR_TIME_FORMAT, 0, 8, vec!["12.30.15".to_string()])]
+        );
+        // Ratios and scores are not times
+        assert!(check(&[(W, "1"), (P, ":"), (W, "50")], std).is_empty());
+        assert!(check(&[(W, "25"), (P, ":"), (W, "30")], std).is_empty());
+        assert!(check(&[(W, "klo"), (S, " "), (W, "12.30")], std).is_empty());
+    }
+
+    #[test]
+    fn period_time_in_technical_style() {
+        let tech = NumberStyle::Technical;
+        assert_eq!(
+            check(&[(W, "kello"), (S, " "), (W, "12.30")], tech),
+            [(GCERR_TIME_FORMAT, 6, 5, vec!["12:30".to_string()])]
+        );
+        // Without "klo" a period number is a decimal
+        assert!(check(&[(W, "noin"), (S, " "), (W, "12.30")], tech).is_empty());
+        assert!(check(&[(W, "12"), (P, ":"), (W, "30")], tech).is_empty());
+    }
+
+    #[test]
+    fn ordinal_punctuation() {
+        let std = NumberStyle::Standard;
+        assert_eq!(
+            check(&[(W, "3.nnella")], std),
+            [(GCERR_ORDINAL_FORMAT, 0, 8, vec!["3:nnella".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "3.luokka")], std),
+            [(GCERR_ORDINAL_FORMAT, 0, 8, vec!["3. luokka".to_string()])]
+        );
+        assert!(check(&[(W, "3:nnella")], std).is_empty());
+        assert!(check(&[(W, "3"), (P, "."), (S, " "), (W, "luokka")], std).is_empty());
+        assert!(check(&[(W, "1.a")], std).is_empty());
+    }
+
+    #[test]
+    fn unit_spacing() {
+        let std = NumberStyle::Standard;
+        assert_eq!(
+            check(&[(W, "5km"), (P, "/"), (W, "h")], std),
+            [(GCERR_UNIT_SPACING, 0, 3, vec!["5 km".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "2,5kg")], std),
+            [(GCERR_UNIT_SPACING, 0, 5, vec!["2,5 kg".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "10"), (U, "%"), (P, ",")], std),
+            [(GCERR_UNIT_SPACING, 0, 3, vec!["10 %".to_string()])]
+        );
+        assert_eq!(
+            check(&[(W, "20"), (U, "\u{00b0}"), (W, "C")], std),
+            [(GCERR_UNIT_SPACING, 0, 4, vec!["20 \u{00b0}C".to_string()])]
+        );
+        assert!(check(&[(W, "5"), (S, " "), (W, "km")], std).is_empty());
+        assert!(check(&[(W, "90"), (U, "\u{00b0}")], std).is_empty());
+        assert!(check(&[(W, "3D")], std).is_empty());
+    }
+}
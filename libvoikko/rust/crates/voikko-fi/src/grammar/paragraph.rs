@@ -118,6 +118,10 @@ pub(crate) struct GrammarToken {
     /// compound verb constructs. `None` if this word is not a verb.
     /// Origin: Token.hpp:100
     pub verb_follower_type: FollowingVerbType,
+
+    /// Smallest number of compound components (`=` marks in STRUCTURE)
+    /// among the analyses of this word. 0 if the word has no analyses.
+    pub compound_part_count: usize,
 }
 
 impl GrammarToken {
@@ -143,6 +147,7 @@ impl GrammarToken {
             possible_conjunction: false,
             require_following_verb: FollowingVerbType::None,
             verb_follower_type: FollowingVerbType::None,
+            compound_part_count: 0,
         }
     }
 
@@ -0,0 +1,471 @@
+// Regex-lite patterns for user autocorrect rules.
+//
+// A small backtracking matcher for the `/.../` patterns of autocorrect
+// rules (see `autocorrect_rules`). The syntax is a subset of the usual
+// regular expressions:
+//
+//   abc              literal characters
+//   .                any character
+//   [abc] [a-z] [^0-9]   character classes; `]` first in a class is literal
+//   \d \w \s         ASCII digit, word character, whitespace
+//   \. \( \\ ...     a backslash makes any other character literal
+//   ( )              capture group, numbered from 1 by its `(`
+//   |                alternation, inside a group or for the whole pattern
+//   ? * +            greedy repetition of the preceding item
+//
+// There are no anchors: the caller decides where a match starts and which
+// ends it accepts. Matching is case-sensitive. A match spans at most
+// MAX_MATCH_CHARS characters and an attempt gives up after MAX_STEPS
+// steps, which bounds the cost of a pathological pattern.
+
+/// Longest text a pattern can match, in characters.
+pub(crate) const MAX_MATCH_CHARS: usize = 200;
+
+/// Steps after which a match attempt gives up.
+const MAX_STEPS: usize = 100_000;
+
+/// Maximum number of capture groups, so that `$1`..`$9` can refer to all.
+pub(crate) const MAX_GROUPS: usize = 9;
+
+/// Character spans of the whole match (index 0) and of the capture groups.
+pub(crate) type Captures = Vec<Option<(usize, usize)>>;
+
+/// A compiled pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Pattern {
+    alternatives: Vec<Vec<Node>>,
+    groups: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    /// A capture group with its index and alternatives.
+    Group(usize, Vec<Vec<Node>>),
+    /// An item repeated at least `min` and at most `max` times.
+    Repeat {
+        item: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassItem {
+    Char(char),
+    Range(char, char),
+    Digit,
+    Word,
+    Space,
+}
+
+impl ClassItem {
+    fn matches(self, c: char) -> bool {
+        match self {
+            Self::Char(x) => c == x,
+            Self::Range(start, end) => (start..=end).contains(&c),
+            Self::Digit => c.is_ascii_digit(),
+            Self::Word => c.is_alphanumeric() || c == '_',
+            Self::Space => c.is_whitespace(),
+        }
+    }
+}
+
+impl Class {
+    fn matches(&self, c: char) -> bool {
+        self.items.iter().any(|item| item.matches(c)) != self.negated
+    }
+}
+
+impl Pattern {
+    /// Compile a pattern. The error is a short description of the problem.
+    pub(crate) fn parse(pattern: &str) -> Result<Self, &'static str> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut parser = Parser {
+            chars: &chars,
+            pos: 0,
+            groups: 0,
+        };
+        let alternatives = parser.alternatives()?;
+        if parser.pos < chars.len() {
+            return Err("unmatched )");
+        }
+        Ok(Self {
+            alternatives,
+            groups: parser.groups,
+        })
+    }
+
+    /// Return the number of capture groups.
+    pub(crate) fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// Match the pattern at the start of `text`.
+    ///
+    /// Returns the captures of the first match, in backtracking order,
+    /// whose non-zero length `accept` accepts.
+    pub(crate) fn match_at(
+        &self,
+        text: &[char],
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Option<Captures> {
+        let mut matcher = Matcher {
+            text: &text[..text.len().min(MAX_MATCH_CHARS)],
+            steps: 0,
+        };
+        let mut captures = vec![None; self.groups + 1];
+        let mut result = None;
+        alternatives_at(
+            &mut matcher,
+            &self.alternatives,
+            0,
+            &mut captures,
+            &mut |_, end, captures| {
+                if end == 0 || !accept(end) {
+                    return false;
+                }
+                let mut found = captures.clone();
+                found[0] = Some((0, end));
+                result = Some(found);
+                true
+            },
+        );
+        result
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser<'a> {
+    chars: &'a [char],
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, &'static str> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, &'static str> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let item = self.atom()?;
+            let (min, max) = match self.peek() {
+                Some('?') => (0, Some(1)),
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                _ => {
+                    nodes.push(item);
+                    continue;
+                }
+            };
+            self.pos += 1;
+            if matches!(self.peek(), Some('?' | '*' | '+')) {
+                return Err("repetition of a repetition");
+            }
+            nodes.push(Node::Repeat {
+                item: Box::new(item),
+                min,
+                max,
+            });
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, &'static str> {
+        match self.next() {
+            Some('.') => Ok(Node::Any),
+            Some('(') => {
+                self.groups += 1;
+                if self.groups > MAX_GROUPS {
+                    return Err("more than 9 groups");
+                }
+                let index = self.groups;
+                let alternatives = self.alternatives()?;
+                if self.next() != Some(')') {
+                    return Err("unclosed group");
+                }
+                Ok(Node::Group(index, alternatives))
+            }
+            Some('[') => self.class(),
+            Some('\\') => Ok(match self.escape()? {
+                ClassItem::Char(c) => Node::Char(c),
+                item => Node::Class(Class {
+                    negated: false,
+                    items: vec![item],
+                }),
+            }),
+            Some('?' | '*' | '+') => Err("repetition without an item to repeat"),
+            Some(c) => Ok(Node::Char(c)),
+            None => Err("unexpected end of pattern"),
+        }
+    }
+
+    fn escape(&mut self) -> Result<ClassItem, &'static str> {
+        match self.next() {
+            Some('d') => Ok(ClassItem::Digit),
+            Some('w') => Ok(ClassItem::Word),
+            Some('s') => Ok(ClassItem::Space),
+            Some(c) => Ok(ClassItem::Char(c)),
+            None => Err("pattern ends with a backslash"),
+        }
+    }
+
+    fn class(&mut self) -> Result<Node, &'static str> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+        let mut items = Vec::new();
+        loop {
+            let item = match self.next() {
+                None => return Err("unclosed character class"),
+                Some(']') if !items.is_empty() => break,
+                Some('\\') => self.escape()?,
+                Some(c) => ClassItem::Char(c),
+            };
+            let is_range =
+                self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']');
+            match item {
+                ClassItem::Char(start) if is_range => {
+                    self.pos += 1;
+                    let end = match self.next() {
+                        Some('\\') => self.escape()?,
+                        Some(c) => ClassItem::Char(c),
+                        None => return Err("unclosed character class"),
+                    };
+                    let ClassItem::Char(end) = end else {
+                        return Err("invalid character range");
+                    };
+                    if end < start {
+                        return Err("invalid character range");
+                    }
+                    items.push(ClassItem::Range(start, end));
+                }
+                item => items.push(item),
+            }
+        }
+        Ok(Node::Class(Class { negated, items }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Matcher
+// ---------------------------------------------------------------------------
+
+struct Matcher<'t> {
+    text: &'t [char],
+    steps: usize,
+}
+
+/// What to do after an item matched: called with the end position, returns
+/// `true` to accept the match or `false` to backtrack.
+type Next<'k, 't> = &'k mut dyn FnMut(&mut Matcher<'t>, usize, &mut Captures) -> bool;
+
+fn alternatives_at<'t>(
+    m: &mut Matcher<'t>,
+    alternatives: &[Vec<Node>],
+    pos: usize,
+    captures: &mut Captures,
+    next: Next<'_, 't>,
+) -> bool {
+    alternatives
+        .iter()
+        .any(|nodes| sequence_at(m, nodes, pos, captures, &mut *next))
+}
+
+fn sequence_at<'t>(
+    m: &mut Matcher<'t>,
+    nodes: &[Node],
+    pos: usize,
+    captures: &mut Captures,
+    next: Next<'_, 't>,
+) -> bool {
+    let Some((node, rest)) = nodes.split_first() else {
+        return next(m, pos, captures);
+    };
+    node_at(m, node, pos, captures, &mut |m, end, captures| {
+        sequence_at(m, rest, end, captures, &mut *next)
+    })
+}
+
+fn node_at<'t>(
+    m: &mut Matcher<'t>,
+    node: &Node,
+    pos: usize,
+    captures: &mut Captures,
+    next: Next<'_, 't>,
+) -> bool {
+    m.steps += 1;
+    if m.steps > MAX_STEPS {
+        return false;
+    }
+    match node {
+        Node::Char(c) => m.text.get(pos) == Some(c) && next(m, pos + 1, captures),
+        Node::Any => pos < m.text.len() && next(m, pos + 1, captures),
+        Node::Class(class) => {
+            m.text.get(pos).is_some_and(|&c| class.matches(c)) && next(m, pos + 1, captures)
+        }
+        Node::Group(index, alternatives) => {
+            let index = *index;
+            alternatives_at(m, alternatives, pos, captures, &mut |m, end, captures| {
+                let saved = captures[index];
+                captures[index] = Some((pos, end));
+                if next(m, end, captures) {
+                    return true;
+                }
+                captures[index] = saved;
+                false
+            })
+        }
+        Node::Repeat { item, min, max } => repeat_at(m, item, *min, *max, 0, pos, captures, next),
+    }
+}
+
+/// Match `item` repeated greedily, `count` times so far.
+#[allow(clippy::too_many_arguments)]
+fn repeat_at<'t>(
+    m: &mut Matcher<'t>,
+    item: &Node,
+    min: usize,
+    max: Option<usize>,
+    count: usize,
+    pos: usize,
+    captures: &mut Captures,
+    next: Next<'_, 't>,
+) -> bool {
+    if max.is_none_or(|max| count < max) {
+        let matched = node_at(m, item, pos, captures, &mut |m, end, captures| {
+            if end == pos {
+                // Repeating an empty match cannot make progress.
+                return count + 1 >= min && next(m, end, captures);
+            }
+            repeat_at(m, item, min, max, count + 1, end, captures, &mut *next)
+        });
+        if matched {
+            return true;
+        }
+    }
+    count >= min && next(m, pos, captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    /// The longest accepted match of `pattern` at the start of `text`.
+    fn find(pattern: &str, text: &str) -> Option<String> {
+        let text = chars(text);
+        let captures = Pattern::parse(pattern)
+            .unwrap()
+            .match_at(&text, &|_| true)?;
+        let (start, end) = captures[0]?;
+        Some(text[start..end].iter().collect())
+    }
+
+    #[test]
+    fn literals_and_wildcards() {
+        assert_eq!(find("koira", "koiranne").as_deref(), Some("koira"));
+        assert_eq!(find("ko.ra", "kolra").as_deref(), Some("kolra"));
+        assert_eq!(find("kissa", "koira"), None);
+        assert_eq!(find(r"a\.b", "a.b").as_deref(), Some("a.b"));
+        assert_eq!(find(r"a\.b", "axb"), None);
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(find("[kt]alo", "talo").as_deref(), Some("talo"));
+        assert_eq!(find("[a-c]+", "abcd").as_deref(), Some("abc"));
+        assert_eq!(find("[^0-9]+", "ab1").as_deref(), Some("ab"));
+        assert_eq!(find(r"\d+", "2024a").as_deref(), Some("2024"));
+        assert_eq!(find(r"\w+\s\w+", "ab cd!").as_deref(), Some("ab cd"));
+        assert_eq!(find("[]a]+", "a]a").as_deref(), Some("a]a"));
+        assert_eq!(find("[a-]+", "a-a").as_deref(), Some("a-a"));
+    }
+
+    #[test]
+    fn repetition_is_greedy_and_backtracks() {
+        assert_eq!(find("a*", "aaab").as_deref(), Some("aaa"));
+        assert_eq!(find("a*ab", "aaab").as_deref(), Some("aaab"));
+        assert_eq!(find("ko?ira", "kira").as_deref(), Some("kira"));
+        assert_eq!(find("a+", "b"), None);
+    }
+
+    #[test]
+    fn groups_and_alternation() {
+        let pattern = Pattern::parse("(ab|a)(c+)").unwrap();
+        assert_eq!(pattern.groups(), 2);
+        let captures = pattern.match_at(&chars("abcc"), &|_| true).unwrap();
+        assert_eq!(captures, [Some((0, 4)), Some((0, 2)), Some((2, 4))]);
+        assert_eq!(find("kissa|koira", "koira").as_deref(), Some("koira"));
+        assert_eq!(find("(a|b)+", "abba").as_deref(), Some("abba"));
+    }
+
+    #[test]
+    fn accept_selects_the_end() {
+        let pattern = Pattern::parse("a+").unwrap();
+        let text = chars("aaaa");
+        let captures = pattern.match_at(&text, &|end| end == 2).unwrap();
+        assert_eq!(captures[0], Some((0, 2)));
+        // An empty match is never accepted.
+        let pattern = Pattern::parse("b*").unwrap();
+        assert_eq!(pattern.match_at(&text, &|_| true), None);
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        for pattern in [
+            "(ab",
+            "ab)",
+            "[ab",
+            "*a",
+            "a**",
+            "a\\",
+            "[z-a]",
+            "(a)(b)(c)(d)(e)(f)(g)(h)(i)(j)",
+        ] {
+            assert!(Pattern::parse(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn pathological_pattern_gives_up() {
+        let pattern = Pattern::parse("(a*)*b").unwrap();
+        let text = chars(&"a".repeat(100));
+        assert_eq!(pattern.match_at(&text, &|_| true), None);
+    }
+}
@@ -0,0 +1,344 @@
+// Context-sensitive real-word error check
+//
+// Opt-in check for correctly spelled words in a case their context does not
+// allow, after Lindén's context-sensitive detection of real-word errors: the
+// analyses of the neighbouring words tell which forms fit, and a word whose
+// every analysis is some other form is reported. Two contexts are checked:
+// - a preposition that takes the partitive ("kohti", "vailla", "vastoin",
+//   "pitkin") followed by nouns or adjectives that can only be nominative:
+//   "kohti kaupunki" instead of "kohti kaupunkia";
+// - a nominative personal pronoun before a form of "täytyä", whose subject
+//   is in the genitive: "minä täytyy" instead of "minun täytyy".
+// Suggestions are generated from the analysis of each reported word with
+// the required case. Words are analyzed again here, since `GrammarToken`
+// keeps only the derived flags; only words after a trigger are analyzed.
+
+use voikko_core::analysis::{ATTR_BASEFORM, ATTR_CLASS, ATTR_NUMBER, ATTR_SIJAMUOTO, Analysis};
+use voikko_core::character::{is_upper, simple_upper};
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{GCERR_WRONG_CASE, GrammarError};
+
+use super::checks::{GrammarOptions, GrammarSentence, GrammarToken};
+use crate::morphology::Analyzer;
+
+/// Prepositions that take the partitive. Other adpositions are left out
+/// because they are mostly postpositions ("tietä myöten") or have a common
+/// homograph in another case ("ilman" is also the genitive of "ilma").
+const PARTITIVE_PREPOSITIONS: &[&str] = &["kohti", "vailla", "vastoin", "pitkin"];
+
+/// Personal pronouns whose nominative may stand for a genitive subject.
+const PERSONAL_PRONOUNS: &[&str] = &["minä", "sinä", "hän", "me", "te", "he"];
+
+/// Base form of the necessive verb whose subject is in the genitive.
+const NECESSIVE_VERB: &str = "täytyä";
+
+/// Word classes that agree in case within a noun phrase.
+const NOMINAL_CLASSES: &[&str] = &[
+    "nimisana",
+    "laatusana",
+    "nimisana_laatusana",
+    "etunimi",
+    "sukunimi",
+    "paikannimi",
+    "nimi",
+];
+
+/// Most words of a noun phrase after a preposition that are checked.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Report words in a case that the preceding preposition or the following
+/// necessive verb does not allow. Needs `analyzer`; without one nothing is
+/// reported.
+pub(crate) fn gc_real_word_errors(
+    sentence: &GrammarSentence,
+    analyzer: Option<&dyn Analyzer>,
+    options: &GrammarOptions,
+) -> Vec<GrammarError> {
+    if !options.check_real_word_errors {
+        return Vec::new();
+    }
+    let Some(analyzer) = analyzer else {
+        return Vec::new();
+    };
+    let tokens = &sentence.tokens;
+    let mut errors = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.token_type != TokenType::Word {
+            continue;
+        }
+        let text = lowercase(token);
+        if PARTITIVE_PREPOSITIONS.contains(&text.as_str()) {
+            errors.extend(partitive_phrase_error(tokens, i, analyzer));
+        } else if PERSONAL_PRONOUNS.contains(&text.as_str()) {
+            errors.extend(necessive_subject_error(tokens, i, analyzer));
+        }
+    }
+    errors
+}
+
+/// The error for the nominative noun phrase after the preposition at
+/// `preposition`, if there is one.
+fn partitive_phrase_error(
+    tokens: &[GrammarToken],
+    preposition: usize,
+    analyzer: &dyn Analyzer,
+) -> Option<GrammarError> {
+    let mut phrase: Vec<(usize, Analysis)> = Vec::new();
+    let mut last = preposition;
+    while phrase.len() < MAX_PHRASE_WORDS {
+        let Some(next) = next_word(tokens, last) else {
+            break;
+        };
+        let analyses = analyze(&tokens[next], analyzer);
+        let nominative = !analyses.is_empty()
+            && analyses.iter().all(|a| {
+                a.get(ATTR_SIJAMUOTO) == Some("nimento")
+                    && a.get(ATTR_CLASS)
+                        .is_some_and(|c| NOMINAL_CLASSES.contains(&c))
+            });
+        if !nominative {
+            break;
+        }
+        phrase.push((next, analyses.into_iter().next()?));
+        last = next;
+    }
+    let &(first, _) = phrase.first()?;
+
+    // The suggestion is the whole phrase in the partitive, with the
+    // original whitespace between the words.
+    let mut suggestion = String::new();
+    let mut previous = None;
+    for (index, analysis) in &phrase {
+        let form = inflect(&tokens[*index], analysis, "osanto", analyzer);
+        let Some(form) = form else {
+            suggestion.clear();
+            break;
+        };
+        if let Some(previous) = previous {
+            for token in &tokens[previous + 1..*index] {
+                suggestion.extend(token.text.iter());
+            }
+        }
+        suggestion.push_str(&form);
+        previous = Some(*index);
+    }
+    let end = tokens[last].pos + tokens[last].token_len();
+    Some(error_with_suggestion(
+        tokens[first].pos,
+        end - tokens[first].pos,
+        suggestion,
+    ))
+}
+
+/// The error for the nominative personal pronoun at `pronoun` if a form of
+/// "täytyä" follows it.
+fn necessive_subject_error(
+    tokens: &[GrammarToken],
+    pronoun: usize,
+    analyzer: &dyn Analyzer,
+) -> Option<GrammarError> {
+    let verb = next_word(tokens, pronoun)?;
+    let verb_analyses = analyze(&tokens[verb], analyzer);
+    if verb_analyses.is_empty()
+        || !verb_analyses
+            .iter()
+            .all(|a| a.get(ATTR_BASEFORM) == Some(NECESSIVE_VERB))
+    {
+        return None;
+    }
+    let analysis = analyze(&tokens[pronoun], analyzer).into_iter().find(|a| {
+        a.get(ATTR_CLASS) == Some("asemosana")
+            && a.get(ATTR_SIJAMUOTO) == Some("nimento")
+            && a.get(ATTR_BASEFORM)
+                .is_some_and(|b| PERSONAL_PRONOUNS.contains(&b))
+    })?;
+    let token = &tokens[pronoun];
+    let suggestion = inflect(token, &analysis, "omanto", analyzer).unwrap_or_default();
+    Some(error_with_suggestion(
+        token.pos,
+        token.token_len(),
+        suggestion,
+    ))
+}
+
+/// The word token after the token at `index`, if only whitespace is
+/// between them.
+fn next_word(tokens: &[GrammarToken], index: usize) -> Option<usize> {
+    let whitespace = tokens.get(index + 1)?;
+    let word = tokens.get(index + 2)?;
+    (whitespace.token_type == TokenType::Whitespace && word.token_type == TokenType::Word)
+        .then_some(index + 2)
+}
+
+/// `token` generated from `analysis` in `case`, capitalized as the token is.
+fn inflect(
+    token: &GrammarToken,
+    analysis: &Analysis,
+    case: &str,
+    analyzer: &dyn Analyzer,
+) -> Option<String> {
+    let baseform = analysis.get(ATTR_BASEFORM)?;
+    let mut target = Analysis::new();
+    target.set(ATTR_SIJAMUOTO, case);
+    for key in [ATTR_CLASS, ATTR_NUMBER] {
+        if let Some(value) = analysis.get(key) {
+            target.set(key, value);
+        }
+    }
+    let form = analyzer.generate(baseform, &target).into_iter().next()?;
+    if !token.text.first().is_some_and(|&c| is_upper(c)) {
+        return Some(form);
+    }
+    let mut chars = form.chars();
+    let first = chars.next()?;
+    Some(std::iter::once(simple_upper(first)).chain(chars).collect())
+}
+
+fn error_with_suggestion(start: usize, len: usize, suggestion: String) -> GrammarError {
+    let suggestions = if suggestion.is_empty() {
+        Vec::new()
+    } else {
+        vec![suggestion]
+    };
+    GrammarError::with_suggestions(GCERR_WRONG_CASE, start, len, suggestions)
+}
+
+fn analyze(token: &GrammarToken, analyzer: &dyn Analyzer) -> Vec<Analysis> {
+    analyzer.analyze_any_case(&token.text, token.text.len()).0
+}
+
+fn lowercase(token: &GrammarToken) -> String {
+    token.text.iter().collect::<String>().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::test_util::sentence;
+
+    /// Analyses and generated forms of a handful of words. Each entry is
+    /// (word, base form, class, case, number); `generate` returns the word
+    /// of the entry with the requested base form, class, case and number.
+    const LEXICON: &[(&str, &str, &str, &str, &str)] = &[
+        ("kohti", "kohti", "suhdesana", "", ""),
+        ("kaupunki", "kaupunki", "nimisana", "nimento", "singular"),
+        ("kaupunkia", "kaupunki", "nimisana", "osanto", "singular"),
+        ("suuri", "suuri", "laatusana", "nimento", "singular"),
+        ("suurta", "suuri", "laatusana", "osanto", "singular"),
+        (
+            "rautatieasema",
+            "rautatieasema",
+            "nimisana",
+            "nimento",
+            "singular",
+        ),
+        ("minä", "minä", "asemosana", "nimento", "singular"),
+        ("minun", "minä", "asemosana", "omanto", "singular"),
+        ("täytyy", "täytyä", "teonsana", "", ""),
+        ("juosta", "juosta", "teonsana", "", ""),
+        ("ja", "ja", "sidesana", "", ""),
+    ];
+
+    struct Lexicon;
+
+    impl Analyzer for Lexicon {
+        fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
+            let word: String = word[..word_len].iter().collect();
+            LEXICON
+                .iter()
+                .filter(|entry| entry.0 == word)
+                .map(|&(_, baseform, class, case, number)| {
+                    let mut analysis = Analysis::new();
+                    analysis.set(ATTR_BASEFORM, baseform);
+                    analysis.set(ATTR_CLASS, class);
+                    if !case.is_empty() {
+                        analysis.set(ATTR_SIJAMUOTO, case);
+                        analysis.set(ATTR_NUMBER, number);
+                    }
+                    analysis
+                })
+                .collect()
+        }
+
+        fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+            // Compounds are not generated, as in FinnishVfstAnalyzer.
+            LEXICON
+                .iter()
+                .filter(|&&(word, base, class, case, number)| {
+                    base == baseform
+                        && word != "rautatieasema"
+                        && target.get(ATTR_CLASS) == Some(class)
+                        && target.get(ATTR_SIJAMUOTO) == Some(case)
+                        && target.get(ATTR_NUMBER) == Some(number)
+                })
+                .map(|entry| entry.0.to_string())
+                .collect()
+        }
+    }
+
+    fn options() -> GrammarOptions {
+        GrammarOptions {
+            check_real_word_errors: true,
+            ..GrammarOptions::default()
+        }
+    }
+
+    fn check(texts: &[&str]) -> Vec<GrammarError> {
+        gc_real_word_errors(&sentence(texts), Some(&Lexicon), &options())
+    }
+
+    #[test]
+    fn nominative_after_preposition() {
+        let errors = check(&["kohti", " ", "kaupunki", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code, GCERR_WRONG_CASE);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (6, 8));
+        assert_eq!(errors[0].suggestions, ["kaupunkia"]);
+        assert!(check(&["kohti", " ", "kaupunkia", "."]).is_empty());
+    }
+
+    #[test]
+    fn noun_phrase_after_preposition_is_inflected_whole() {
+        let errors = check(&["Kohti", " ", "suuri", " ", "kaupunki", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (6, 14));
+        assert_eq!(errors[0].suggestions, ["suurta kaupunkia"]);
+    }
+
+    #[test]
+    fn phrase_ends_at_a_word_of_another_case() {
+        let errors = check(&["kohti", " ", "kaupunki", " ", "ja", " ", "kaupunki"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (6, 8));
+    }
+
+    #[test]
+    fn word_without_generated_form_has_no_suggestion() {
+        let errors = check(&["kohti", " ", "rautatieasema"]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].suggestions.is_empty());
+    }
+
+    #[test]
+    fn nominative_pronoun_before_necessive_verb() {
+        let errors = check(&["Minä", " ", "täytyy", " ", "juosta", "."]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].start_pos, errors[0].error_len), (0, 4));
+        assert_eq!(errors[0].suggestions, ["Minun"]);
+        assert!(check(&["minun", " ", "täytyy", " ", "juosta"]).is_empty());
+        assert!(check(&["minä", " ", "juosta"]).is_empty());
+    }
+
+    #[test]
+    fn words_must_be_adjacent() {
+        assert!(check(&["kohti", ",", " ", "kaupunki"]).is_empty());
+        assert!(check(&["minä", ",", " ", "täytyy"]).is_empty());
+    }
+
+    #[test]
+    fn disabled_or_without_analyzer() {
+        let s = sentence(&["kohti", " ", "kaupunki"]);
+        assert!(gc_real_word_errors(&s, Some(&Lexicon), &GrammarOptions::default()).is_empty());
+        assert!(gc_real_word_errors(&s, None, &options()).is_empty());
+    }
+}
@@ -0,0 +1,686 @@
+// Declarative grammar rules loaded from JSON rule packs.
+//
+// Simple checks -- forbidden phrases, fixed word sequences mapped to an
+// error code -- are expressed as data instead of code. A rule pack is a JSON
+// document:
+//
+//   {
+//     "rules": [
+//       {
+//         "id": "mutta-kuitenkin",
+//         "pattern": [{ "word": "mutta" }, { "word": "kuitenkin" }],
+//         "message": "Turha toisto.",
+//         "suggestions": ["mutta", "kuitenkin"]
+//       }
+//     ]
+//   }
+//
+// Each pattern element matches one non-whitespace token. An element may
+// give `word` (one word) or `words` (any of several words), compared case-
+// insensitively; `baseform` and `class`, which some analysis of the word
+// must carry as its BASEFORM and CLASS (both given: the same analysis), so
+// that one element matches every inflected form of a lemma; and `is`, a
+// list of token properties that must all hold (e.g. "main_verb",
+// "conjunction"). Whitespace between tokens is skipped; punctuation tokens
+// take part in matching like words.
+//
+// Optional rule fields: `error_code` (default GCERR_CUSTOM_RULE), `message`
+// (replaces the error description), `suggestions` (replacements for the
+// whole matched span), `severity` ("error" / "warning") and `category`.
+//
+// Packs are compiled into a `RuleSet` at load time. A default pack is
+// built into the library; applications can load more packs at runtime.
+// Loading JSON packs requires the `serde` feature.
+
+#[cfg(feature = "serde")]
+use std::collections::HashSet;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use voikko_core::analysis::{ATTR_BASEFORM, ATTR_CLASS};
+use voikko_core::character::{equals_ignore_case, is_upper, simple_upper};
+use voikko_core::enums::TokenType;
+use voikko_core::grammar_error::{
+    GCERR_CUSTOM_RULE, GrammarError, GrammarErrorCategory, GrammarErrorSeverity,
+};
+
+use super::paragraph::{GrammarSentence, GrammarToken};
+use crate::morphology::Analyzer;
+
+/// The rule pack built into the library, enabled with the
+/// `check_default_rules` grammar option.
+fn default_rules() -> Vec<RuleDef> {
+    vec![
+        RuleDef::phrase(
+            "kuten-esimerkiksi",
+            &["kuten", "esimerkiksi"],
+            "Turha toisto: kuten ja esimerkiksi tarkoittavat samaa.",
+            &["kuten", "esimerkiksi"],
+        ),
+        RuleDef::phrase(
+            "mutta-kuitenkin",
+            &["mutta", "kuitenkin"],
+            "Turha toisto: mutta ja kuitenkin tarkoittavat samaa.",
+            &["mutta", "kuitenkin"],
+        ),
+        RuleDef::phrase(
+            "seka-myos",
+            &["sekä", "myös"],
+            "Turha toisto: sekä ja myös tarkoittavat samaa.",
+            &["sekä", "ja myös"],
+        ),
+    ]
+}
+
+/// Error type for rule pack loading failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rule pack is not valid JSON or does not follow the rule format.
+    #[error("invalid rule pack: {0}")]
+    Syntax(String),
+
+    /// A rule has an empty pattern, or a pattern element matches nothing.
+    #[error("rule {rule}: {reason}")]
+    InvalidPattern { rule: String, reason: String },
+
+    /// A rule uses an error code that is not positive.
+    #[error("rule {rule}: invalid error code {code}")]
+    InvalidErrorCode { rule: String, code: i32 },
+
+    /// Two rules in the same pack share an id.
+    #[error("duplicate rule id: {0}")]
+    DuplicateId(String),
+}
+
+// ---------------------------------------------------------------------------
+// Rule pack format
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulePackDef {
+    rules: Vec<RuleDef>,
+}
+
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(deny_unknown_fields))]
+struct RuleDef {
+    id: String,
+    pattern: Vec<TokenPatternDef>,
+    error_code: Option<i32>,
+    message: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    suggestions: Vec<String>,
+    severity: Option<SeverityDef>,
+    category: Option<CategoryDef>,
+}
+
+impl RuleDef {
+    /// A rule matching the fixed word sequence `words`.
+    fn phrase(id: &str, words: &[&str], message: &str, suggestions: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            pattern: words
+                .iter()
+                .map(|word| TokenPatternDef {
+                    word: Some(word.to_string()),
+                    ..TokenPatternDef::default()
+                })
+                .collect(),
+            error_code: None,
+            message: Some(message.to_string()),
+            suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
+            severity: None,
+            category: None,
+        }
+    }
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(deny_unknown_fields))]
+struct TokenPatternDef {
+    word: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    words: Vec<String>,
+    baseform: Option<String>,
+    class: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    is: Vec<TokenProperty>,
+}
+
+// Without the `serde` feature only the default rules, which use none of
+// these, are compiled.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+enum SeverityDef {
+    Error,
+    Warning,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+enum CategoryDef {
+    Spelling,
+    Punctuation,
+    Capitalization,
+    Grammar,
+    Style,
+}
+
+/// Token properties a pattern element can require.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+enum TokenProperty {
+    ValidWord,
+    MainVerb,
+    PossibleMainVerb,
+    VerbNegative,
+    PositiveVerb,
+    Conjunction,
+    PossibleConjunction,
+    Compound,
+}
+
+impl TokenProperty {
+    fn holds(self, token: &GrammarToken) -> bool {
+        match self {
+            Self::ValidWord => token.is_valid_word,
+            Self::MainVerb => token.is_main_verb,
+            Self::PossibleMainVerb => token.possible_main_verb,
+            Self::VerbNegative => token.is_verb_negative,
+            Self::PositiveVerb => token.is_positive_verb,
+            Self::Conjunction => token.is_conjunction,
+            Self::PossibleConjunction => token.possible_conjunction,
+            Self::Compound => token.compound_part_count >= 2,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Compiled rules
+// ---------------------------------------------------------------------------
+
+/// One compiled pattern element.
+#[derive(Debug, Clone)]
+struct TokenPattern {
+    /// Accepted token texts; empty accepts any text.
+    words: Vec<Vec<char>>,
+    /// Required BASEFORM of some analysis of the token. Elements with a
+    /// baseform or class analyze the token again when the rest matches.
+    baseform: Option<String>,
+    /// Required CLASS of the same analysis.
+    class: Option<String>,
+    properties: Vec<TokenProperty>,
+}
+
+impl TokenPattern {
+    fn matches(&self, token: &GrammarToken, analyzer: Option<&dyn Analyzer>) -> bool {
+        (self.words.is_empty()
+            || self
+                .words
+                .iter()
+                .any(|w| equals_ignore_case(w, &token.text)))
+            && self.properties.iter().all(|p| p.holds(token))
+            && self.matches_lemma(token, analyzer)
+    }
+
+    /// Without an analyzer, an element with a baseform or class matches
+    /// nothing.
+    fn matches_lemma(&self, token: &GrammarToken, analyzer: Option<&dyn Analyzer>) -> bool {
+        if self.baseform.is_none() && self.class.is_none() {
+            return true;
+        }
+        let Some(analyzer) = analyzer else {
+            return false;
+        };
+        let (analyses, _) = analyzer.analyze_any_case(&token.text, token.text.len());
+        analyses.iter().any(|analysis| {
+            self.baseform
+                .as_deref()
+                .is_none_or(|b| analysis.get(ATTR_BASEFORM) == Some(b))
+                && self
+                    .class
+                    .as_deref()
+                    .is_none_or(|c| analysis.get(ATTR_CLASS) == Some(c))
+        })
+    }
+}
+
+/// One compiled rule.
+#[derive(Debug, Clone)]
+struct Rule {
+    id: String,
+    pattern: Vec<TokenPattern>,
+    error_code: i32,
+    message: Option<String>,
+    suggestions: Vec<String>,
+    severity: Option<GrammarErrorSeverity>,
+    category: Option<GrammarErrorCategory>,
+}
+
+impl Rule {
+    fn compile(def: RuleDef) -> Result<Self, RuleError> {
+        let invalid = |reason: &str| RuleError::InvalidPattern {
+            rule: def.id.clone(),
+            reason: reason.to_string(),
+        };
+        if def.pattern.is_empty() {
+            return Err(invalid("empty pattern"));
+        }
+        let mut pattern = Vec::with_capacity(def.pattern.len());
+        for element in &def.pattern {
+            let words: Vec<Vec<char>> = element
+                .word
+                .iter()
+                .chain(&element.words)
+                .map(|w| w.chars().collect::<Vec<char>>())
+                .collect();
+            if words.iter().any(Vec::is_empty) {
+                return Err(invalid("empty word in pattern"));
+            }
+            if element.baseform.as_deref() == Some("") || element.class.as_deref() == Some("") {
+                return Err(invalid("empty baseform or class in pattern"));
+            }
+            if words.is_empty()
+                && element.baseform.is_none()
+                && element.class.is_none()
+                && element.is.is_empty()
+            {
+                return Err(invalid("pattern element without word, lemma or properties"));
+            }
+            pattern.push(TokenPattern {
+                words,
+                baseform: element.baseform.clone(),
+                class: element.class.clone(),
+                properties: element.is.clone(),
+            });
+        }
+
+        let error_code = def.error_code.unwrap_or(GCERR_CUSTOM_RULE);
+        if error_code <= 0 {
+            return Err(RuleError::InvalidErrorCode {
+                rule: def.id,
+                code: error_code,
+            });
+        }
+
+        Ok(Self {
+            pattern,
+            error_code,
+            message: def.message,
+            suggestions: def.suggestions,
+            severity: def.severity.map(|s| match s {
+                SeverityDef::Error => GrammarErrorSeverity::Error,
+                SeverityDef::Warning => GrammarErrorSeverity::Warning,
+            }),
+            category: def.category.map(|c| match c {
+                CategoryDef::Spelling => GrammarErrorCategory::Spelling,
+                CategoryDef::Punctuation => GrammarErrorCategory::Punctuation,
+                CategoryDef::Capitalization => GrammarErrorCategory::Capitalization,
+                CategoryDef::Grammar => GrammarErrorCategory::Grammar,
+                CategoryDef::Style => GrammarErrorCategory::Style,
+            }),
+            id: def.id,
+        })
+    }
+
+    /// Build the error for a match covering `tokens`.
+    fn error(&self, tokens: &[&GrammarToken]) -> GrammarError {
+        let first = tokens[0];
+        let last = tokens[tokens.len() - 1];
+        let start = first.pos;
+        let len = last.pos + last.token_len() - start;
+
+        // Keep a capitalized phrase capitalized in the suggestions.
+        let capitalize = first.text.first().is_some_and(|&c| is_upper(c));
+        let suggestions = self
+            .suggestions
+            .iter()
+            .map(|s| {
+                let mut chars = s.chars();
+                match chars.next() {
+                    Some(c) if capitalize => {
+                        std::iter::once(simple_upper(c)).chain(chars).collect()
+                    }
+                    _ => s.clone(),
+                }
+            })
+            .collect();
+
+        let mut error = GrammarError::with_suggestions(self.error_code, start, len, suggestions);
+        if let Some(message) = &self.message {
+            error.short_description = message.clone();
+        }
+        if let Some(severity) = self.severity {
+            error.severity = severity;
+        }
+        if let Some(category) = self.category {
+            error.category = category;
+        }
+        error
+    }
+}
+
+/// A compiled set of declarative grammar rules.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Create an empty rule set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile a JSON rule pack. Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, RuleError> {
+        let pack: RulePackDef =
+            serde_json::from_str(json).map_err(|e| RuleError::Syntax(e.to_string()))?;
+        let mut ids = HashSet::new();
+        let mut rules = Vec::with_capacity(pack.rules.len());
+        for def in pack.rules {
+            if !ids.insert(def.id.clone()) {
+                return Err(RuleError::DuplicateId(def.id));
+            }
+            rules.push(Rule::compile(def)?);
+        }
+        Ok(Self { rules })
+    }
+
+    /// Compile the built-in default rule pack.
+    pub fn defaults() -> Self {
+        let rules = default_rules()
+            .into_iter()
+            .map(|def| Rule::compile(def).expect("default rule pack is valid"))
+            .collect();
+        Self { rules }
+    }
+
+    /// Number of rules in the set.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Return `true` if the set has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Ids of the rules, in load order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|r| r.id.as_str())
+    }
+
+    /// Append the rules of `other`. A rule whose id is already present
+    /// replaces the earlier rule.
+    pub fn extend(&mut self, other: RuleSet) {
+        for rule in other.rules {
+            match self.rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+    }
+
+    /// Run all rules on a sentence. Matches of one rule do not overlap.
+    /// `analyzer` is used by pattern elements with a baseform or class.
+    pub(crate) fn check(
+        &self,
+        sentence: &GrammarSentence,
+        analyzer: Option<&dyn Analyzer>,
+    ) -> Vec<GrammarError> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        let tokens: Vec<&GrammarToken> = sentence
+            .tokens
+            .iter()
+            .filter(|t| t.token_type != TokenType::Whitespace)
+            .collect();
+
+        let mut errors = Vec::new();
+        for rule in &self.rules {
+            let n = rule.pattern.len();
+            let mut i = 0;
+            while i + n <= tokens.len() {
+                let window = &tokens[i..i + n];
+                if rule
+                    .pattern
+                    .iter()
+                    .zip(window)
+                    .all(|(p, t)| p.matches(t, analyzer))
+                {
+                    errors.push(rule.error(window));
+                    i += n;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use voikko_core::analysis::Analysis;
+
+    fn token(token_type: TokenType, text: &str, pos: usize) -> GrammarToken {
+        GrammarToken::new(token_type, text.chars().collect(), pos)
+    }
+
+    /// Build a sentence from space-separated words and trailing punctuation.
+    fn sentence(text: &str) -> GrammarSentence {
+        let mut s = GrammarSentence::new(0);
+        let mut pos = 0;
+        for (i, w) in text.split(' ').enumerate() {
+            if i > 0 {
+                s.tokens.push(token(TokenType::Whitespace, " ", pos));
+                pos += 1;
+            }
+            let (word, punct) = match w.strip_suffix(['.', ',']) {
+                Some(stripped) => (stripped, &w[stripped.len()..]),
+                None => (w, ""),
+            };
+            s.tokens.push(token(TokenType::Word, word, pos));
+            pos += word.chars().count();
+            if !punct.is_empty() {
+                s.tokens.push(token(TokenType::Punctuation, punct, pos));
+                pos += 1;
+            }
+        }
+        s
+    }
+
+    #[test]
+    fn default_rules_compile() {
+        let rules = RuleSet::defaults();
+        assert!(!rules.is_empty());
+        assert!(rules.ids().any(|id| id == "mutta-kuitenkin"));
+    }
+
+    #[test]
+    fn phrase_rule_matches_case_insensitively() {
+        let rules = RuleSet::defaults();
+        let errs = rules.check(&sentence("Mutta kuitenkin koira juoksi."), None);
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].error_code, GCERR_CUSTOM_RULE);
+        assert_eq!((errs[0].start_pos, errs[0].error_len), (0, 15));
+        assert_eq!(errs[0].suggestions, vec!["Mutta", "Kuitenkin"]);
+        assert!(errs[0].short_description.starts_with("Turha toisto"));
+        assert_eq!(errs[0].severity, GrammarErrorSeverity::Warning);
+    }
+
+    #[test]
+    fn punctuation_breaks_match() {
+        let rules = RuleSet::defaults();
+        assert!(rules.check(&sentence("mutta, kuitenkin"), None).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn words_and_properties() {
+        let json = r#"{"rules": [{
+            "id": "ei-verbi",
+            "pattern": [{"words": ["ei", "en"]}, {"is": ["main_verb"]}],
+            "error_code": 13,
+            "severity": "error",
+            "category": "grammar"
+        }]}"#;
+        let rules = RuleSet::from_json(json).unwrap();
+        let mut s = sentence("hän ei juoksee");
+        assert!(rules.check(&s, None).is_empty());
+        s.tokens[4].is_main_verb = true;
+        let errs = rules.check(&s, None);
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].error_code, 13);
+        assert_eq!((errs[0].start_pos, errs[0].error_len), (4, 10));
+        assert_eq!(errs[0].category, GrammarErrorCategory::Grammar);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn invalid_packs_are_rejected() {
+        assert!(matches!(RuleSet::from_json("{"), Err(RuleError::Syntax(_))));
+        assert!(matches!(
+            RuleSet::from_json(r#"{"rules": [{"id": "x", "pattern": [], "bogus": 1}]}"#),
+            Err(RuleError::Syntax(_))
+        ));
+        assert!(matches!(
+            RuleSet::from_json(r#"{"rules": [{"id": "x", "pattern": []}]}"#),
+            Err(RuleError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            RuleSet::from_json(r#"{"rules": [{"id": "x", "pattern": [{}]}]}"#),
+            Err(RuleError::InvalidPattern { .. })
+        ));
+        assert_eq!(
+            RuleSet::from_json(
+                r#"{"rules": [{"id": "x", "pattern": [{"word": "a"}], "error_code": 0}]}"#
+            )
+            .unwrap_err(),
+            RuleError::InvalidErrorCode {
+                rule: "x".to_string(),
+                code: 0
+            }
+        );
+        assert_eq!(
+            RuleSet::from_json(
+                r#"{"rules": [{"id": "x", "pattern": [{"word": "a"}]},
+                              {"id": "x", "pattern": [{"word": "b"}]}]}"#
+            )
+            .unwrap_err(),
+            RuleError::DuplicateId("x".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn extend_replaces_rules_with_same_id() {
+        let mut rules = RuleSet::defaults();
+        let count = rules.len();
+        rules.extend(
+            RuleSet::from_json(
+                r#"{"rules": [{"id": "mutta-kuitenkin", "pattern": [{"word": "mutta"}]}]}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(rules.len(), count);
+        let errs = rules.check(&sentence("mutta kuitenkin"), None);
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].error_len, 5);
+    }
+
+    /// Analyzes "kuusi" as a numeral and a noun, "kuuden" as the genitive
+    /// of both and "hyvin" as an adverb.
+    #[cfg(feature = "serde")]
+    struct Lexicon;
+
+    #[cfg(feature = "serde")]
+    impl Analyzer for Lexicon {
+        fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
+            let word: String = word[..word_len].iter().collect();
+            let lemmas: &[(&str, &str)] = match word.as_str() {
+                "kuusi" | "kuuden" => &[("kuusi", "lukusana"), ("kuusi", "nimisana")],
+                "kuudes" => &[("kuudes", "järjestysluku")],
+                "hyvin" => &[("hyvin", "seikkasana")],
+                _ => &[],
+            };
+            lemmas
+                .iter()
+                .map(|&(baseform, class)| {
+                    let mut analysis = Analysis::new();
+                    analysis.set(ATTR_BASEFORM, baseform);
+                    analysis.set(ATTR_CLASS, class);
+                    analysis
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn baseform_and_class_match_any_form_of_a_lemma() {
+        let rules = RuleSet::from_json(
+            r#"{"rules": [{
+                "id": "kuusi-kertaa",
+                "pattern": [{"baseform": "kuusi", "class": "lukusana"}, {"word": "kertaa"}]
+            }]}"#,
+        )
+        .unwrap();
+        for text in ["kuusi kertaa", "Kuuden kertaa"] {
+            let errs = rules.check(&sentence(text), Some(&Lexicon));
+            assert_eq!(errs.len(), 1, "{text}");
+            assert_eq!(errs[0].start_pos, 0);
+        }
+        assert!(
+            rules
+                .check(&sentence("kuudes kertaa"), Some(&Lexicon))
+                .is_empty()
+        );
+        assert!(rules.check(&sentence("kuusi kertaa"), None).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn baseform_and_class_must_hold_for_the_same_analysis() {
+        let rules = RuleSet::from_json(
+            r#"{"rules": [{"id": "x", "pattern": [{"baseform": "kuusi", "class": "järjestysluku"}]},
+                          {"id": "y", "pattern": [{"class": "seikkasana"}]}]}"#,
+        )
+        .unwrap();
+        assert!(rules.check(&sentence("kuusi"), Some(&Lexicon)).is_empty());
+        let errs = rules.check(&sentence("kuusi hyvin"), Some(&Lexicon));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].start_pos, 6);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn empty_lemma_keys_are_rejected() {
+        for element in [r#"{"baseform": ""}"#, r#"{"class": ""}"#] {
+            let json = format!(r#"{{"rules": [{{"id": "x", "pattern": [{element}]}}]}}"#);
+            assert!(matches!(
+                RuleSet::from_json(&json),
+                Err(RuleError::InvalidPattern { .. })
+            ));
+        }
+    }
+}
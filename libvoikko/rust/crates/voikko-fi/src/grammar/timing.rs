@@ -0,0 +1,317 @@
+// Per-rule timing for the grammar engine
+//
+// Some checks are expensive on particular input: a long sentence of
+// unknown words, a paragraph of list items. With timing enabled, the
+// engine measures every check on every sentence and keeps per-rule
+// statistics. With a time cap, a check whose time on one paragraph reaches
+// the cap is skipped for the rest of that paragraph, and the paragraph is
+// recorded as a slow-rule diagnostic. The errors the check found before
+// the cap was reached are kept.
+//
+// Timing reads the system clock, which `wasm32-unknown-unknown` does not
+// have; it is off by default.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use voikko_core::grammar_error::GrammarError;
+
+use super::paragraph::Paragraph;
+
+/// Number of slow-rule diagnostics kept; older ones are dropped.
+const MAX_SLOW_RULES: usize = 32;
+
+/// Longest paragraph excerpt stored in a [`SlowRule`], in characters.
+const MAX_EXCERPT_CHARS: usize = 200;
+
+/// Timing statistics of one grammar rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleStats {
+    /// Rule name: a built-in check such as `missing_verb`, or
+    /// `default_rules` / `loaded_rules` for the declarative rule packs.
+    pub rule: &'static str,
+    /// Number of times the rule was run (once per sentence for
+    /// sentence-level rules, once per paragraph for paragraph-level rules).
+    pub runs: u64,
+    /// Total time spent in the rule.
+    pub total: Duration,
+    /// Longest single run.
+    pub max: Duration,
+    /// Number of paragraphs on which the rule exceeded the time cap.
+    pub capped: u64,
+}
+
+impl RuleStats {
+    fn new(rule: &'static str) -> Self {
+        Self {
+            rule,
+            runs: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            capped: 0,
+        }
+    }
+
+    /// Average time per run.
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.runs) {
+            Ok(0) => Duration::ZERO,
+            Ok(runs) => self.total / runs,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.runs as f64),
+        }
+    }
+}
+
+/// A rule that exceeded the time cap on a paragraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowRule {
+    /// Rule name, as in [`RuleStats::rule`].
+    pub rule: &'static str,
+    /// Time the rule spent on the paragraph before it was skipped.
+    pub elapsed: Duration,
+    /// The start of the paragraph, for reproducing the case.
+    pub excerpt: String,
+}
+
+/// Rule timing settings, part of the grammar options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) struct TimingOptions {
+    /// Collect per-rule statistics.
+    pub enabled: bool,
+    /// Skip a rule for the rest of a paragraph once it has spent this long
+    /// on it. Implies `enabled`.
+    pub cap: Option<Duration>,
+}
+
+impl TimingOptions {
+    fn is_active(&self) -> bool {
+        self.enabled || self.cap.is_some()
+    }
+}
+
+/// Statistics and slow-rule diagnostics collected by the engine.
+#[derive(Debug, Default)]
+pub(crate) struct RuleTimings {
+    stats: HashMap<&'static str, RuleStats>,
+    slow: VecDeque<SlowRule>,
+}
+
+impl RuleTimings {
+    /// Statistics of every rule run so far, slowest (by total time) first.
+    pub(crate) fn stats(&self) -> Vec<RuleStats> {
+        let mut stats: Vec<RuleStats> = self.stats.values().cloned().collect();
+        stats.sort_by(|a, b| b.total.cmp(&a.total).then(a.rule.cmp(b.rule)));
+        stats
+    }
+
+    /// The most recent slow-rule diagnostics, oldest first.
+    pub(crate) fn slow_rules(&self) -> Vec<SlowRule> {
+        self.slow.iter().cloned().collect()
+    }
+
+    /// Add the timings of one paragraph.
+    fn merge(&mut self, run: ParagraphTimer<'_>) {
+        for (rule, paragraph_stats) in run.stats {
+            let stats = self
+                .stats
+                .entry(rule)
+                .or_insert_with(|| RuleStats::new(rule));
+            stats.runs += paragraph_stats.runs;
+            stats.total += paragraph_stats.total;
+            stats.max = stats.max.max(paragraph_stats.max);
+            stats.capped += paragraph_stats.capped;
+        }
+        for slow in run.slow {
+            if self.slow.len() == MAX_SLOW_RULES {
+                self.slow.pop_front();
+            }
+            self.slow.push_back(slow);
+        }
+    }
+}
+
+/// Runs the checks of one paragraph, timing them if timing is enabled.
+pub(crate) struct ParagraphTimer<'a> {
+    options: TimingOptions,
+    paragraph: &'a Paragraph,
+    stats: HashMap<&'static str, RuleStats>,
+    slow: Vec<SlowRule>,
+}
+
+impl<'a> ParagraphTimer<'a> {
+    pub(crate) fn new(options: TimingOptions, paragraph: &'a Paragraph) -> Self {
+        Self {
+            options,
+            paragraph,
+            stats: HashMap::new(),
+            slow: Vec::new(),
+        }
+    }
+
+    /// Run `check` as `rule`, unless the rule has exceeded the time cap on
+    /// this paragraph.
+    pub(crate) fn run(
+        &mut self,
+        rule: &'static str,
+        check: impl FnOnce() -> Vec<GrammarError>,
+    ) -> Vec<GrammarError> {
+        if !self.options.is_active() {
+            return check();
+        }
+        let stats = self
+            .stats
+            .entry(rule)
+            .or_insert_with(|| RuleStats::new(rule));
+        if stats.capped > 0 {
+            return Vec::new();
+        }
+
+        let start = Instant::now();
+        let errors = check();
+        let elapsed = start.elapsed();
+
+        stats.runs += 1;
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        if self.options.cap.is_some_and(|cap| stats.total >= cap) {
+            stats.capped = 1;
+            let elapsed = stats.total;
+            self.slow.push(SlowRule {
+                rule,
+                elapsed,
+                excerpt: excerpt(self.paragraph),
+            });
+        }
+        errors
+    }
+
+    /// Add the timings of this paragraph to `timings`.
+    pub(crate) fn finish(self, timings: &mut RuleTimings) {
+        if self.options.is_active() {
+            timings.merge(self);
+        }
+    }
+}
+
+/// The first characters of `paragraph`.
+fn excerpt(paragraph: &Paragraph) -> String {
+    paragraph
+        .sentences
+        .iter()
+        .flat_map(|s| &s.tokens)
+        .flat_map(|t| &t.text)
+        .take(MAX_EXCERPT_CHARS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::enums::TokenType;
+
+    use super::super::paragraph::{GrammarSentence, GrammarToken};
+
+    fn paragraph(text: &str) -> Paragraph {
+        let mut sentence = GrammarSentence::new(0);
+        sentence.tokens.push(GrammarToken::new(
+            TokenType::Word,
+            text.chars().collect(),
+            0,
+        ));
+        Paragraph {
+            sentences: vec![sentence],
+        }
+    }
+
+    fn slow_check() -> Vec<GrammarError> {
+        std::thread::sleep(Duration::from_millis(2));
+        Vec::new()
+    }
+
+    #[test]
+    fn disabled_timer_records_nothing() {
+        let paragraph = paragraph("koira");
+        let mut timer = ParagraphTimer::new(TimingOptions::default(), &paragraph);
+        let mut ran = 0;
+        for _ in 0..2 {
+            timer.run("check", || {
+                ran += 1;
+                Vec::new()
+            });
+        }
+        assert_eq!(ran, 2);
+        let mut timings = RuleTimings::default();
+        timer.finish(&mut timings);
+        assert!(timings.stats().is_empty());
+    }
+
+    #[test]
+    fn stats_accumulate_over_paragraphs() {
+        let paragraph = paragraph("koira");
+        let options = TimingOptions {
+            enabled: true,
+            cap: None,
+        };
+        let mut timings = RuleTimings::default();
+        for _ in 0..2 {
+            let mut timer = ParagraphTimer::new(options, &paragraph);
+            timer.run("fast", Vec::new);
+            timer.run("slow", slow_check);
+            timer.finish(&mut timings);
+        }
+        let stats = timings.stats();
+        assert_eq!(stats[0].rule, "slow");
+        assert_eq!(stats[0].runs, 2);
+        assert!(stats[0].max >= Duration::from_millis(2));
+        assert!(stats[0].mean() >= Duration::from_millis(2));
+        assert_eq!(stats[1].rule, "fast");
+        assert_eq!(stats[1].capped, 0);
+        assert!(timings.slow_rules().is_empty());
+    }
+
+    #[test]
+    fn rule_over_cap_is_skipped_for_the_paragraph() {
+        let paragraph = paragraph("Pitkä kappale");
+        let options = TimingOptions {
+            enabled: false,
+            cap: Some(Duration::from_millis(1)),
+        };
+        let mut timer = ParagraphTimer::new(options, &paragraph);
+        let mut ran = 0;
+        for _ in 0..3 {
+            timer.run("slow", || {
+                ran += 1;
+                slow_check()
+            });
+            timer.run("fast", Vec::new);
+        }
+        assert_eq!(ran, 1);
+
+        let mut timings = RuleTimings::default();
+        timer.finish(&mut timings);
+        let slow = timings.slow_rules();
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].rule, "slow");
+        assert_eq!(slow[0].excerpt, "Pitkä kappale");
+        let stats = timings.stats();
+        assert_eq!((stats[0].rule, stats[0].capped), ("slow", 1));
+        assert_eq!((stats[1].rule, stats[1].runs), ("fast", 3));
+    }
+
+    #[test]
+    fn slow_rule_log_is_bounded() {
+        let paragraph = paragraph("koira");
+        let options = TimingOptions {
+            enabled: false,
+            cap: Some(Duration::ZERO),
+        };
+        let mut timings = RuleTimings::default();
+        for _ in 0..MAX_SLOW_RULES + 5 {
+            let mut timer = ParagraphTimer::new(options, &paragraph);
+            timer.run("slow", slow_check);
+            timer.finish(&mut timings);
+        }
+        assert_eq!(timings.slow_rules().len(), MAX_SLOW_RULES);
+        assert_eq!(timings.stats()[0].capped, MAX_SLOW_RULES as u64 + 5);
+    }
+}
@@ -14,31 +14,74 @@
 // - Suggestion strategies are created once at construction time.
 // - Options are stored directly in the handle and passed to adapters
 //   when methods are called.
+// - Mutable state used by `&self` methods (analyzer traversal configs,
+//   speller cache, grammar cache) is behind locks, so the handle is
+//   `Send + Sync`.
 //
 // Origin: setup/VoikkoHandle.hpp (C++ VoikkoHandle)
 
-use std::cell::RefCell;
+use std::borrow::Cow;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
-use voikko_core::analysis::Analysis;
-use voikko_core::enums::{SentenceType, TokenType};
-use voikko_core::grammar_error::GrammarError;
-use voikko_core::token::{Sentence, Token};
+use voikko_core::analysis::{ATTR_STRUCTURE, Analysis, Segment};
+use voikko_core::case::{CaseType, detect_case};
+use voikko_core::catalog::{CatalogError, MessageCatalog};
+use voikko_core::enums::{MAX_WORD_CHARS, SentenceType, SpellResult, TokenType};
+use voikko_core::grammar_error::{GCERR_INVALID_SPELLING, GrammarError, GrammarErrorSeverity};
+use voikko_core::token::{Sentence, SentenceSpan, SentenceWhitespace, Token};
+use voikko_fst::config::TraversalBudget;
 
+#[cfg(feature = "dictpack")]
+use crate::dictpack::{DictPack, DictPackError};
+use crate::document::{SpellingIssue, check_document_with};
+use crate::grammar::analyzed::AnalyzedParagraph;
+use crate::grammar::autocorrect_rules::{AutocorrectRuleError, AutocorrectRules};
 use crate::grammar::checker::FinnishGrammarChecker;
 use crate::grammar::checks::GrammarOptions;
-use crate::hyphenator::{FinnishHyphenator, Hyphenator, HyphenatorOptions};
-use crate::morphology::{Analyzer, FinnishVfstAnalyzer};
+use crate::grammar::numbers::NumberStyle;
+use crate::grammar::rules::RuleError;
+#[cfg(feature = "serde")]
+use crate::grammar::rules::RuleSet;
+use crate::grammar::timing::{RuleStats, SlowRule};
+use crate::hyphenator::{
+    FinnishHyphenator, HyphenExplanation, HyphenReason, HyphenationExceptionError,
+    HyphenationExceptions, Hyphenator, HyphenatorOptions,
+};
+use crate::input_map::InputMapping;
+use crate::invisible::{self, InvisibleChars};
+use crate::language_rules::LanguageRules;
+use crate::marked_text::{self, MarkedText, PlainText};
+use crate::morphology::{
+    Analyzer, AnalyzerOptions, CaseVariant, CompoundInfo, DictionaryRemovals,
+    DictionaryRemovalsError, FinnishVfstAnalyzer, LoadState, RoundTrip, TraversalStats,
+    TruncatedLookup, compound_info, round_trip,
+};
+use crate::spell_service::{TokenSpellService, TokenSpelling};
+use crate::speller::Speller;
 use crate::speller::adapter::AnalyzerToSpellerAdapter;
-use crate::speller::cache::SpellerCache;
+use crate::speller::cache::{CacheStats, SharedSpellerCache};
 use crate::speller::finnish::{FinnishSpellerOptions, FinnishSpellerTweaksWrapper};
-use crate::speller::pipeline::{SpellOptions, spell_check};
-use crate::suggestion::status::SuggestionStatus;
+use crate::speller::pipeline::SpellOptions;
+use crate::speller::user_dictionary::{UserDictionary, UserDictionaryError};
+use crate::stream::{self, SpellStreamOptions, SpellStreamStats};
+use crate::suggestion::prefilter::PrefixFilteredSpeller;
+use crate::suggestion::ranking::{RankingWeights, SuggestionRanker, proper_nouns_first};
+use crate::suggestion::status::{
+    CancelToken, ScoredSuggestion, Suggestion, SuggestionBudget, SuggestionStatus,
+    score_suggestions,
+};
 use crate::suggestion::strategy::{
-    SuggestionStrategy, default_ocr_strategy, default_typing_strategy,
+    SuggestionStrategy, default_ocr_strategy, default_typing_strategy, typing_strategy_with_rules,
 };
 use crate::tokenizer;
+use crate::user_state::{UserState, UserStateError};
+use crate::warm_up;
 
-/// Error type for VoikkoHandle construction failures.
+/// Error type for VoikkoHandle construction failures and for the `try_*`
+/// variants of the handle methods.
 #[derive(Debug, thiserror::Error)]
 pub enum VoikkoError {
     /// The mor.vfst data could not be loaded.
@@ -52,14 +95,170 @@ pub enum VoikkoError {
     /// Unsupported language.
     #[error("unsupported language: {0}")]
     UnsupportedLanguage(String),
+
+    /// A dictionary file was not found in any of the dictionary directories.
+    #[error(
+        "could not find {file_name} in any of the search paths:\n{}",
+        list_paths(searched)
+    )]
+    DictionaryNotFound {
+        /// The file that was looked for, e.g. `mor.vfst`.
+        file_name: String,
+        /// The directories searched, in order.
+        searched: Vec<PathBuf>,
+    },
+
+    /// A dictionary file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    DictionaryRead {
+        /// The file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// An exported user state blob could not be imported.
+    #[error("failed to import user state: {0}")]
+    UserState(#[from] UserStateError),
+
+    /// A grammar rule pack could not be loaded.
+    #[error("failed to load grammar rules: {0}")]
+    GrammarRules(#[from] RuleError),
+
+    /// A user autocorrect rule is not valid.
+    #[error("invalid autocorrect rule: {0}")]
+    AutocorrectRule(#[from] AutocorrectRuleError),
+
+    /// A user dictionary file could not be loaded.
+    #[error("failed to load user dictionary: {0}")]
+    UserDictionary(#[from] UserDictionaryError),
+
+    /// A hyphenation exception could not be parsed.
+    #[error("failed to load hyphenation exceptions: {0}")]
+    HyphenationExceptions(#[from] HyphenationExceptionError),
+
+    /// The removal list of a dictionary extension could not be parsed.
+    #[error("failed to load dictionary removals: {0}")]
+    DictionaryRemovals(#[from] DictionaryRemovalsError),
+
+    /// A message catalog file could not be parsed.
+    #[error("failed to load message catalog: {0}")]
+    MessageCatalog(#[from] CatalogError),
+
+    /// A dictionary pack could not be read.
+    #[cfg(feature = "dictpack")]
+    #[error("failed to read dictionary pack: {0}")]
+    DictPack(#[from] DictPackError),
+
+    /// The word is longer than the maximum word length and was not
+    /// processed.
+    #[error("word is too long: {length} characters, the maximum is {max}")]
+    WordTooLong {
+        /// Length of the word in characters.
+        length: usize,
+        /// The maximum word length in characters.
+        max: usize,
+    },
+
+    /// The operation was cancelled with a [`CancelToken`](crate::suggestion::CancelToken).
+    #[error("operation was cancelled")]
+    Cancelled,
+
+    /// The operation did not finish within its time limit.
+    #[error("operation timed out")]
+    TimedOut,
 }
 
+/// One `  - path` line per searched directory.
+fn list_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("  - {}", p.display()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// User dictionary entries at most this many edits away from a misspelled
+/// word are suggested for it.
+const MAX_USER_WORD_DISTANCE: usize = 2;
+
+/// The Finnish speller for parallel suggestion generation. The speller
+/// wrappers hold trait object references that cannot be shared between
+/// threads, so they are built for each check instead; both are cheap.
+#[cfg(feature = "parallel")]
+struct SharedFinnishSpeller<'a> {
+    analyzer: &'a FinnishVfstAnalyzer,
+    options: FinnishSpellerOptions,
+}
+
+#[cfg(feature = "parallel")]
+impl Speller for SharedFinnishSpeller<'_> {
+    fn spell(&self, word: &[char], word_len: usize) -> SpellResult {
+        let adapter = AnalyzerToSpellerAdapter::new(self.analyzer);
+        let filtered = PrefixFilteredSpeller::new(&adapter, self.analyzer);
+        FinnishSpellerTweaksWrapper::new(&filtered, self.analyzer, self.options)
+            .spell(word, word_len)
+    }
+}
+
+/// Result of [`VoikkoHandle::check_word`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordCheck {
+    /// Whether the word is correctly spelled.
+    pub correct: bool,
+    /// Suggestions, best first; `None` if the word is correct.
+    pub suggestions: Option<Vec<String>>,
+    /// Whether the word is longer than the maximum word length and was not
+    /// checked. It is then not correct and has no suggestions.
+    pub too_long: bool,
+}
+
+/// Result of [`VoikkoHandle::spell_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpellStatus {
+    /// The word is correctly spelled.
+    Correct,
+    /// The word is misspelled.
+    Misspelled,
+    /// The word is longer than the maximum word length and was not checked.
+    TooLong,
+    /// The word was not accepted, but a dictionary lookup stopped on the
+    /// traversal budget (see [`VoikkoHandle::set_traversal_budget`]), so it
+    /// may be correct.
+    Truncated,
+}
+
+/// Resource profile of a [`VoikkoHandle`], chosen when it is created (see
+/// [`VoikkoHandle::from_bytes_with_profile`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HandleProfile {
+    /// Every component, with the default cache sizes.
+    #[default]
+    Standard,
+    /// For memory-constrained hosts such as mobile browsers. The speller
+    /// cache holds at most [`LITE_SPELLER_CACHE_WORDS`] words, analyses are
+    /// not cached, suggestion candidates are not pre-filtered by their
+    /// beginning, the autocorrect transducer is not loaded and grammar
+    /// checking is disabled: the grammar methods report no errors. Spelling,
+    /// suggestions, analysis and hyphenation work as in `Standard`.
+    Lite,
+}
+
+/// Number of words the speller cache of a [`HandleProfile::Lite`] handle
+/// holds, about an eighth of the default.
+pub const LITE_SPELLER_CACHE_WORDS: usize = 128;
+
 /// Top-level handle that owns all Finnish NLP components.
 ///
 /// Provides spell checking, morphological analysis, hyphenation, grammar
 /// checking, suggestion generation, and tokenization through a single
 /// unified interface.
 ///
+/// The handle is `Send + Sync`: all checking methods take `&self`, and the
+/// internal caches are synchronized, so one loaded dictionary can serve a
+/// thread pool (e.g. behind an `Arc`). Options are set through `&mut self`
+/// before the handle is shared.
+///
 /// Origin: setup/VoikkoHandle.hpp
 pub struct VoikkoHandle {
     /// The morphological analyzer (shared by speller, hyphenator, suggestions).
@@ -86,18 +285,61 @@ pub struct VoikkoHandle {
     /// Hyphenator options.
     hyphenator_options: HyphenatorOptions,
 
+    /// Words hyphenated as listed instead of by analysis.
+    hyphenation_exceptions: Arc<HyphenationExceptions>,
+
+    /// Vowel harmony and diphthong tables of the hyphenator and the typing
+    /// suggestions.
+    language_rules: Arc<LanguageRules>,
+
     /// Grammar checker options.
     grammar_options: GrammarOptions,
 
+    /// Localized error descriptions and message templates; may be shared
+    /// with other handles.
+    message_catalog: Arc<MessageCatalog>,
+
     /// Whether to use OCR suggestions instead of typing suggestions.
     use_ocr_suggestions: bool,
 
     /// Maximum number of suggestions to return.
     max_suggestions: usize,
 
-    /// Speller cache for avoiding redundant lookups.
-    /// Wrapped in `RefCell` for interior mutability (`&self` methods need `&mut` cache access).
-    speller_cache: RefCell<SpellerCache>,
+    /// Orders the generated suggestions.
+    suggestion_ranker: SuggestionRanker,
+
+    /// Whether proper-noun suggestions come first for a capitalized word.
+    prefer_proper_nouns: bool,
+
+    /// Whether sentence detection consults the speller to recognize
+    /// abbreviations ending in a dot.
+    sentence_spell_check: bool,
+
+    /// Whether closing quotation marks and brackets after the terminal
+    /// punctuation belong to the ending sentence.
+    sentence_closing_punctuation: bool,
+
+    /// Whether reported sentences include the whitespace that follows them.
+    sentence_whitespace: SentenceWhitespace,
+
+    /// Whether `grammar_errors_from_text` joins hard-wrapped lines into
+    /// paragraphs separated by blank lines.
+    reflow_paragraphs: bool,
+
+    /// Handling of zero-width and control characters in input text.
+    invisible_chars: InvisibleChars,
+
+    /// Speller cache for avoiding redundant lookups. Internally sharded and
+    /// locked, so `&self` methods can use it from several threads.
+    speller_cache: SharedSpellerCache,
+
+    /// User personalization state (user dictionary, ignored words,
+    /// accepted corrections). Consulted before the dictionary; inflected
+    /// forms of user words are recognized after it.
+    user_state: UserState,
+
+    /// Resource profile chosen at creation.
+    profile: HandleProfile,
 }
 
 impl VoikkoHandle {
@@ -116,12 +358,120 @@ impl VoikkoHandle {
         if language != "fi" {
             return Err(VoikkoError::UnsupportedLanguage(language.to_string()));
         }
+        Self::from_bytes_with_profile(
+            mor_vfst_data,
+            autocorr_vfst_data,
+            language,
+            HandleProfile::Standard,
+        )
+    }
+
+    /// Create a VoikkoHandle with the given resource profile, e.g.
+    /// [`HandleProfile::Lite`] for a memory-constrained host. A lite handle
+    /// ignores `autocorr_vfst_data`.
+    pub fn from_bytes_with_profile(
+        mor_vfst_data: &[u8],
+        autocorr_vfst_data: Option<&[u8]>,
+        language: &str,
+        profile: HandleProfile,
+    ) -> Result<Self, VoikkoError> {
+        // A lite handle would drop the autocorrect data unused
+        let autocorr_vfst_data = autocorr_vfst_data.filter(|_| profile != HandleProfile::Lite);
+        Self::from_shared_with_profile(
+            Arc::from(mor_vfst_data),
+            autocorr_vfst_data.map(Arc::from),
+            language,
+            profile,
+        )
+    }
+
+    /// Create a VoikkoHandle from shared dictionary data without copying
+    /// it. The transducers read the data in place, so every handle created
+    /// from the same `Arc`s holds one copy of `mor.vfst` and `autocorr.vfst`
+    /// between them.
+    pub fn from_shared(
+        mor_vfst_data: Arc<[u8]>,
+        autocorr_vfst_data: Option<Arc<[u8]>>,
+        language: &str,
+    ) -> Result<Self, VoikkoError> {
+        Self::from_shared_with_profile(
+            mor_vfst_data,
+            autocorr_vfst_data,
+            language,
+            HandleProfile::Standard,
+        )
+    }
+
+    /// Create a VoikkoHandle from shared dictionary data with the given
+    /// resource profile (see [`from_shared`](Self::from_shared) and
+    /// [`from_bytes_with_profile`](Self::from_bytes_with_profile)).
+    pub fn from_shared_with_profile(
+        mor_vfst_data: Arc<[u8]>,
+        autocorr_vfst_data: Option<Arc<[u8]>>,
+        language: &str,
+        profile: HandleProfile,
+    ) -> Result<Self, VoikkoError> {
+        if language != "fi" {
+            return Err(VoikkoError::UnsupportedLanguage(language.to_string()));
+        }
+        let analyzer = FinnishVfstAnalyzer::from_shared(mor_vfst_data)?;
+        Self::with_analyzer(analyzer, autocorr_vfst_data, profile)
+    }
+
+    /// Create a VoikkoHandle that defers building the morphology transducer.
+    ///
+    /// Construction only validates the `mor.vfst` header, so it returns
+    /// quickly. The transducer is built on first use, or by
+    /// [`warm_up`](Self::warm_up), which can be called from a background
+    /// thread; [`load_state`](Self::load_state) reports progress.
+    pub fn from_bytes_lazy(
+        mor_vfst_data: &[u8],
+        autocorr_vfst_data: Option<&[u8]>,
+        language: &str,
+    ) -> Result<Self, VoikkoError> {
+        if language != "fi" {
+            return Err(VoikkoError::UnsupportedLanguage(language.to_string()));
+        }
+        let analyzer = FinnishVfstAnalyzer::from_bytes_lazy(mor_vfst_data)?;
+        Self::with_analyzer(
+            analyzer,
+            autocorr_vfst_data.map(Arc::from),
+            HandleProfile::Standard,
+        )
+    }
 
-        let analyzer = FinnishVfstAnalyzer::from_bytes(mor_vfst_data)?;
+    /// Create a VoikkoHandle from a dictionary pack, a zip archive with
+    /// `mor.vfst`, optionally `autocorr.vfst` and `index.txt` (see
+    /// [`DictPack`]).
+    ///
+    /// Fails with [`VoikkoError::UnsupportedLanguage`] if `index.txt` names
+    /// a language other than Finnish.
+    #[cfg(feature = "dictpack")]
+    pub fn from_dict_pack(archive: &[u8]) -> Result<Self, VoikkoError> {
+        let pack = DictPack::from_bytes(archive)?;
+        if let Some(language) = pack.language() {
+            let primary = language.split(['-', '_']).next().unwrap_or_default();
+            if !primary.eq_ignore_ascii_case("fi") {
+                return Err(VoikkoError::UnsupportedLanguage(language.to_string()));
+            }
+        }
+        Self::from_bytes(pack.mor_vfst(), pack.autocorr_vfst(), "fi")
+    }
 
+    fn with_analyzer(
+        mut analyzer: FinnishVfstAnalyzer,
+        autocorr_vfst_data: Option<Arc<[u8]>>,
+        profile: HandleProfile,
+    ) -> Result<Self, VoikkoError> {
+        let lite = profile == HandleProfile::Lite;
+        if lite {
+            analyzer.set_analysis_cache_capacity(0);
+            analyzer.set_prefix_filter(false);
+        }
         let autocorr_transducer = match autocorr_vfst_data {
+            Some(_) if lite => None,
             Some(data) => {
-                let t = voikko_fst::unweighted::UnweightedTransducer::from_bytes(data)
+                let t = voikko_fst::unweighted::UnweightedTransducer::from_shared(data)
                     .map_err(|e| VoikkoError::AutocorrectLoad(e.to_string()))?;
                 Some(t)
             }
@@ -139,13 +489,162 @@ impl VoikkoHandle {
             spell_options: SpellOptions::default(),
             finnish_spell_options: FinnishSpellerOptions::default(),
             hyphenator_options: HyphenatorOptions::default(),
+            hyphenation_exceptions: Arc::default(),
+            language_rules: LanguageRules::shared_default(),
+            message_catalog: Arc::default(),
             grammar_options: GrammarOptions::default(),
             use_ocr_suggestions: false,
             max_suggestions: 5,
-            speller_cache: RefCell::new(SpellerCache::new(0)),
+            suggestion_ranker: SuggestionRanker::default(),
+            prefer_proper_nouns: false,
+            sentence_spell_check: true,
+            sentence_closing_punctuation: true,
+            sentence_whitespace: SentenceWhitespace::default(),
+            reflow_paragraphs: false,
+            invisible_chars: InvisibleChars::default(),
+            speller_cache: if lite {
+                SharedSpellerCache::with_capacity(LITE_SPELLER_CACHE_WORDS)
+            } else {
+                SharedSpellerCache::new(0)
+            },
+            user_state: UserState::new(),
+            profile,
         })
     }
 
+    /// Build the morphology transducer of a lazily created handle.
+    ///
+    /// Does nothing if it is already built. Returns the load error if the
+    /// dictionary data turns out to be invalid; the handle then treats
+    /// every word as unknown.
+    pub fn warm_up(&self) -> Result<(), VoikkoError> {
+        self.analyzer.warm_up()?;
+        Ok(())
+    }
+
+    /// Spell and analyze `words` so that the first checks of them are
+    /// answered from the speller and analysis caches, building the
+    /// transducer of a lazily created handle first. Meant for application
+    /// startup, e.g. behind a splash screen. With `threads` above 1, the
+    /// words are split among that many threads.
+    ///
+    /// Returns the load error of a lazily created handle.
+    pub fn warm_up_words(&self, words: &[&str], threads: usize) -> Result<(), VoikkoError> {
+        self.warm_up()?;
+        warm_up::warm_up_words(self, words, threads);
+        Ok(())
+    }
+
+    /// [`warm_up_words`](Self::warm_up_words) with a built-in list of
+    /// frequent Finnish word forms.
+    pub fn warm_up_default(&self, threads: usize) -> Result<(), VoikkoError> {
+        self.warm_up_words(warm_up::FREQUENT_WORDS, threads)
+    }
+
+    /// Return the resource profile the handle was created with.
+    pub fn profile(&self) -> HandleProfile {
+        self.profile
+    }
+
+    /// Return whether the morphology transducer has been built.
+    pub fn load_state(&self) -> LoadState {
+        self.analyzer.load_state()
+    }
+
+    /// Replace the dictionary while the handle is in use.
+    ///
+    /// Both transducers are built from `mor_vfst_data` and
+    /// `autocorr_vfst_data` before anything is replaced, so on error the
+    /// handle keeps its dictionary. Each transducer is then swapped at once:
+    /// calls already running finish with the old one and later calls use
+    /// the new one. Without `autocorr_vfst_data` autocorrection falls back
+    /// to the user rules. Options, user state, grammar rules and
+    /// dictionary extensions are kept; the speller and grammar caches are
+    /// emptied.
+    pub fn reload_dictionary(
+        &self,
+        mor_vfst_data: &[u8],
+        autocorr_vfst_data: Option<&[u8]>,
+    ) -> Result<(), VoikkoError> {
+        let autocorr_transducer = autocorr_vfst_data
+            .map(voikko_fst::unweighted::UnweightedTransducer::from_bytes)
+            .transpose()
+            .map_err(|e| VoikkoError::AutocorrectLoad(e.to_string()))?;
+        self.analyzer.reload(mor_vfst_data)?;
+        self.grammar_checker
+            .set_autocorrect_transducer(autocorr_transducer);
+        self.speller_cache.clear();
+        self.grammar_checker.cache().clear();
+        Ok(())
+    }
+
+    /// Set the limits on each dictionary lookup: transitions per output,
+    /// outputs per lookup and a deadline (see [`TraversalBudget`]). The
+    /// default budget behaves like the C++ engine. A word whose lookup is
+    /// cut short is not accepted; [`spell_status`](Self::spell_status)
+    /// reports it as [`SpellStatus::Truncated`].
+    pub fn set_traversal_budget(&mut self, budget: TraversalBudget) {
+        self.analyzer.set_traversal_budget(budget);
+        self.speller_cache.clear();
+        self.grammar_checker.cache().clear();
+    }
+
+    /// Number of dictionary lookups cut short by the traversal budget since
+    /// the handle was created or the counters were reset, by the limit that
+    /// stopped them. With the default budget a nonzero step count means the
+    /// C++ `MAX_LOOP_COUNT` safety limit was hit, usually by a loop in the
+    /// dictionary. Words answered from the speller cache are not looked up
+    /// again and are counted once.
+    pub fn traversal_stats(&self) -> TraversalStats {
+        self.analyzer.traversal_stats()
+    }
+
+    /// Set whether lookups cut short by the traversal budget are recorded
+    /// with the word (see [`truncated_lookups`](Self::truncated_lookups)).
+    /// Off by default.
+    pub fn set_truncation_diagnostics(&mut self, value: bool) {
+        self.analyzer.set_truncation_diagnostics(value);
+    }
+
+    /// The most recent words whose lookup was cut short by the traversal
+    /// budget, oldest first, with the limit that stopped them.
+    pub fn truncated_lookups(&self) -> Vec<TruncatedLookup> {
+        self.analyzer.truncated_lookups()
+    }
+
+    /// Reset the traversal counters and discard the recorded words.
+    pub fn reset_traversal_stats(&self) {
+        self.analyzer.reset_traversal_stats();
+    }
+
+    /// Layer a dictionary extension on top of the dictionary and the
+    /// extensions added before it.
+    ///
+    /// `mor_vfst` holds the words the extension adds, in the `mor.vfst`
+    /// format. `removals` is a removal list naming the words it withdraws
+    /// from the layers below, one base form per line, optionally followed
+    /// by a tab and a word class (see [`DictionaryRemovals`]). Spelling,
+    /// suggestions, hyphenation, grammar checking and generation all see
+    /// the layered dictionary. On error no extension is added.
+    pub fn add_dictionary_extension(
+        &mut self,
+        mor_vfst: Option<&[u8]>,
+        removals: &str,
+    ) -> Result<(), VoikkoError> {
+        let removals = DictionaryRemovals::from_text(removals)?;
+        self.analyzer.add_extension(mor_vfst, removals)?;
+        self.speller_cache.clear();
+        self.grammar_checker.cache().clear();
+        Ok(())
+    }
+
+    /// Remove all dictionary extensions.
+    pub fn clear_dictionary_extensions(&mut self) {
+        self.analyzer.clear_extensions();
+        self.speller_cache.clear();
+        self.grammar_checker.cache().clear();
+    }
+
     // =========================================================================
     // Core NLP methods
     // =========================================================================
@@ -155,33 +654,270 @@ impl VoikkoHandle {
     /// Returns `true` if the word is correct (or bypassed by options like
     /// ignore_numbers, ignore_uppercase, etc.).
     ///
+    /// Words in the user dictionary or ignore list are always accepted.
+    ///
     /// Origin: voikkoSpellCstr
     pub fn spell(&self, word: &str) -> bool {
+        self.spell_service().is_correct(word)
+    }
+
+    /// Spell check a word, telling a misspelled word from one too long to
+    /// check.
+    ///
+    /// `spell()` returns `false` for both; hosts can use this to show a
+    /// message such as "word too long" instead of marking the word as
+    /// misspelled. See [`set_max_word_chars`](Self::set_max_word_chars).
+    /// A rejected word whose lookup ran out of the traversal budget is
+    /// reported as [`SpellStatus::Truncated`].
+    pub fn spell_status(&self, word: &str) -> SpellStatus {
+        match self.spell_service().classify(word) {
+            TokenSpelling::Correct => SpellStatus::Correct,
+            TokenSpelling::TooLong => SpellStatus::TooLong,
+            TokenSpelling::Misspelled => {
+                let word_chars: Vec<char> = self.visible(word).chars().collect();
+                if self
+                    .analyzer
+                    .analyze_budgeted(&word_chars, word_chars.len(), false)
+                    .1
+                {
+                    SpellStatus::Truncated
+                } else {
+                    SpellStatus::Misspelled
+                }
+            }
+        }
+    }
+
+    /// Check whether a word is correctly spelled, reporting why it could
+    /// not be checked instead of returning `false`.
+    ///
+    /// Fails with [`VoikkoError::WordTooLong`] for a word longer than the
+    /// maximum word length and with [`VoikkoError::MorphologyLoad`] if the
+    /// dictionary of a lazily created handle could not be built.
+    pub fn try_spell(&self, word: &str) -> Result<bool, VoikkoError> {
+        self.warm_up()?;
+        let word = self.visible(word);
+        let word = word.as_ref();
         let word_chars: Vec<char> = word.chars().collect();
-        let adapter = AnalyzerToSpellerAdapter::new(&self.analyzer);
-        let tweaks =
-            FinnishSpellerTweaksWrapper::new(&adapter, &self.analyzer, self.finnish_spell_options);
-        spell_check(
-            &word_chars,
-            &tweaks,
-            Some(&mut *self.speller_cache.borrow_mut()),
+        self.check_length(&word_chars)?;
+        Ok(self.spell_chars(word, &word_chars))
+    }
+
+    /// Spell check a word and, only if it is misspelled, generate
+    /// suggestions for it.
+    ///
+    /// Equivalent to calling `spell()` and then `suggest()` for a failed
+    /// word, but converts the word once and needs one call across a
+    /// language binding.
+    pub fn check_word(&self, word: &str) -> WordCheck {
+        let word = self.visible(word);
+        let word = word.as_ref();
+        let word_chars: Vec<char> = word.chars().collect();
+        if self.is_too_long(&word_chars) {
+            return WordCheck {
+                correct: false,
+                suggestions: Some(Vec::new()),
+                too_long: true,
+            };
+        }
+        let correct = self.spell_chars(word, &word_chars);
+        let suggestions = (!correct).then(|| {
+            self.ranked_suggestions(word, &word_chars, &SuggestionBudget::default())
+                .0
+                .into_iter()
+                .map(|s| s.word)
+                .collect()
+        });
+        WordCheck {
+            correct,
+            suggestions,
+            too_long: false,
+        }
+    }
+
+    /// Return whether `word` is longer than the maximum word length.
+    fn is_too_long(&self, word: &[char]) -> bool {
+        self.spell_service().is_too_long(word)
+    }
+
+    /// Fail with [`VoikkoError::WordTooLong`] if `word` is longer than the
+    /// maximum word length.
+    fn check_length(&self, word: &[char]) -> Result<(), VoikkoError> {
+        if self.is_too_long(word) {
+            return Err(VoikkoError::WordTooLong {
+                length: word.len(),
+                max: self.spell_options.max_word_chars,
+            });
+        }
+        Ok(())
+    }
+
+    /// `word` without invisible characters, if they are stripped.
+    fn visible<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        self.spell_service().visible(word)
+    }
+
+    /// `spell()` with the word already converted to characters.
+    fn spell_chars(&self, word: &str, word_chars: &[char]) -> bool {
+        self.spell_service().spell_chars(word, word_chars)
+    }
+
+    /// The spelling decisions of this handle, shared by `spell()`, the
+    /// document and text checks, sentence splitting, analyzed paragraphs
+    /// and the grammar checker, so that they classify words alike.
+    pub fn spell_service(&self) -> TokenSpellService<'_> {
+        TokenSpellService::new(
+            &self.analyzer,
+            &self.user_state,
             &self.spell_options,
-        ) == 1
+            self.finnish_spell_options,
+            &self.speller_cache,
+            self.invisible_chars,
+        )
+    }
+
+    /// The misspelled word tokens of `text`, as `tokens()` finds them and
+    /// `spell()` classifies them.
+    pub fn misspelled_tokens(&self, text: &str) -> Vec<Token> {
+        self.spell_service().misspelled_tokens(text)
+    }
+
+    /// Spell check a whole document read from `reader`.
+    ///
+    /// The document is read and tokenized one line at a time, so memory
+    /// use grows with the longest line rather than the document; each
+    /// misspelled word is yielded with its byte and character offset from
+    /// the start of the document. Reading errors (including invalid UTF-8)
+    /// are yielded as `Err` and end the iteration.
+    pub fn check_document<'a, R: BufRead + 'a>(
+        &'a self,
+        reader: R,
+    ) -> impl Iterator<Item = io::Result<SpellingIssue>> + 'a {
+        check_document_with(reader, move |word: &str| self.spell(word))
+            .with_invisible_chars(self.invisible_chars)
+    }
+
+    /// Spell check a word list read from `reader`, one word per record,
+    /// and write one result per word to `writer` in the voikko-spell
+    /// format (see [`SpellStreamFormat`](crate::stream::SpellStreamFormat)).
+    ///
+    /// Words are checked in batches of the input already buffered, and the
+    /// output is flushed before waiting for more input, so interactive
+    /// pipes get answers at once while files are checked at full speed.
+    /// Records that are not valid UTF-8 are skipped and counted. Fails only
+    /// on a read or write error.
+    pub fn spell_stream(
+        &self,
+        reader: impl BufRead,
+        writer: impl Write,
+        options: &SpellStreamOptions,
+    ) -> io::Result<SpellStreamStats> {
+        stream::spell_stream(self, reader, writer, options)
     }
 
     /// Generate spelling suggestions for a misspelled word.
     ///
     /// Returns a list of suggested corrections, sorted by priority (best first).
+    /// A correction accepted by the user for this word is always listed first.
+    /// A word longer than the maximum word length gets no suggestions.
     ///
     /// Origin: voikkoSuggestCstr
     pub fn suggest(&self, word: &str) -> Vec<String> {
+        let word = self.visible(word);
+        let word = word.as_ref();
         let word_chars: Vec<char> = word.chars().collect();
-        let adapter = AnalyzerToSpellerAdapter::new(&self.analyzer);
-        let tweaks =
-            FinnishSpellerTweaksWrapper::new(&adapter, &self.analyzer, self.finnish_spell_options);
+        self.ranked_suggestions(word, &word_chars, &SuggestionBudget::default())
+            .0
+            .into_iter()
+            .map(|s| s.word)
+            .collect()
+    }
+
+    /// Generate spelling suggestions within `budget`, e.g. with a time limit
+    /// for interactive use or a token to cancel from another thread.
+    ///
+    /// Returns the suggestions found before the budget ran out, ranked as
+    /// in `suggest()`.
+    pub fn suggest_with_budget(&self, word: &str, budget: &SuggestionBudget) -> Vec<String> {
+        let word = self.visible(word);
+        let word = word.as_ref();
+        let word_chars: Vec<char> = word.chars().collect();
+        self.ranked_suggestions(word, &word_chars, budget)
+            .0
+            .into_iter()
+            .map(|s| s.word)
+            .collect()
+    }
+
+    /// Generate spelling suggestions, reporting why none could be
+    /// generated instead of returning an empty list.
+    ///
+    /// Fails with [`VoikkoError::WordTooLong`] for a word longer than the
+    /// maximum word length and with [`VoikkoError::MorphologyLoad`] if the
+    /// dictionary of a lazily created handle could not be built.
+    pub fn try_suggest(&self, word: &str) -> Result<Vec<String>, VoikkoError> {
+        self.try_suggest_with_budget(word, &SuggestionBudget::default())
+    }
+
+    /// Generate spelling suggestions within `budget` like
+    /// `suggest_with_budget()`, but fail with [`VoikkoError::Cancelled`] or
+    /// [`VoikkoError::TimedOut`] if generation was stopped by the budget's
+    /// cancellation token or time limit. Running out of the cost budget is
+    /// not an error.
+    ///
+    /// Use `suggest_with_budget()` to keep the suggestions found before an
+    /// interruption.
+    pub fn try_suggest_with_budget(
+        &self,
+        word: &str,
+        budget: &SuggestionBudget,
+    ) -> Result<Vec<String>, VoikkoError> {
+        self.warm_up()?;
+        let word = self.visible(word);
+        let word = word.as_ref();
+        let word_chars: Vec<char> = word.chars().collect();
+        self.check_length(&word_chars)?;
+        match self.ranked_suggestions(word, &word_chars, budget) {
+            (_, Some(interruption)) => Err(interruption),
+            (suggestions, None) => Ok(suggestions.into_iter().map(|s| s.word).collect()),
+        }
+    }
+
+    /// Generate spelling suggestions with priorities and confidence scores.
+    ///
+    /// Same suggestions and order as `suggest()`. A correction accepted by
+    /// the user always gets confidence 1.0. See
+    /// [`score_suggestions`](crate::suggestion::score_suggestions) for how
+    /// confidence is computed.
+    pub fn suggest_scored(&self, word: &str) -> Vec<ScoredSuggestion> {
+        let word = self.visible(word);
+        let word = word.as_ref();
+        let word_chars: Vec<char> = word.chars().collect();
+        score_suggestions(
+            self.ranked_suggestions(word, &word_chars, &SuggestionBudget::default())
+                .0,
+        )
+    }
+
+    /// Generate, rank and truncate suggestions, putting the user's accepted
+    /// correction (priority 0) first.
+    ///
+    /// Also returns [`VoikkoError::Cancelled`] or [`VoikkoError::TimedOut`]
+    /// if the budget stopped generation early; the suggestions are then
+    /// those found before that.
+    fn ranked_suggestions(
+        &self,
+        word: &str,
+        word_chars: &[char],
+        budget: &SuggestionBudget,
+    ) -> (Vec<Suggestion>, Option<VoikkoError>) {
+        if self.is_too_long(word_chars) {
+            return (Vec::new(), None);
+        }
+        let user_correction = self.user_state.correction(word);
 
         // Collect 3x candidates (matching C++ MAX_SUGGESTIONS * 3), sort, then truncate.
-        let mut status = SuggestionStatus::new(&word_chars, self.max_suggestions * 3);
+        let mut status = SuggestionStatus::new(word_chars, self.max_suggestions * 3);
 
         let strategy = if self.use_ocr_suggestions {
             &self.ocr_strategy
@@ -189,104 +925,557 @@ impl VoikkoHandle {
             &self.typing_strategy
         };
 
-        strategy.generate(&tweaks, Some(&self.analyzer), &mut status);
-        status.sort_suggestions();
+        #[cfg(not(feature = "parallel"))]
+        {
+            let adapter = AnalyzerToSpellerAdapter::new(&self.analyzer);
+            let filtered = PrefixFilteredSpeller::new(&adapter, &self.analyzer);
+            let tweaks = FinnishSpellerTweaksWrapper::new(
+                &filtered,
+                &self.analyzer,
+                self.finnish_spell_options,
+            );
+            strategy.generate_with_budget(&tweaks, Some(&self.analyzer), &mut status, budget);
+        }
+        #[cfg(feature = "parallel")]
+        {
+            let speller = SharedFinnishSpeller {
+                analyzer: &self.analyzer,
+                options: self.finnish_spell_options,
+            };
+            strategy.generate_parallel(&speller, Some(&self.analyzer), &mut status, budget);
+        }
+        let interruption = if budget
+            .cancel_token
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+        {
+            Some(VoikkoError::Cancelled)
+        } else if status.is_interrupted() {
+            Some(VoikkoError::TimedOut)
+        } else {
+            None
+        };
+        let mut generated = status.into_suggestions();
+        // Close user dictionary entries compete with the generated
+        // suggestions, with the edit distance as priority.
+        for (entry, distance) in self
+            .user_state
+            .user_words
+            .suggestions(word, MAX_USER_WORD_DISTANCE)
+        {
+            generated.retain(|s| s.word != entry);
+            generated.push(Suggestion {
+                word: entry,
+                priority: distance as i32,
+                fst_weight: 0,
+            });
+        }
+        self.suggestion_ranker.rank(word_chars, &mut generated);
+        if self.prefer_proper_nouns && detect_case(word_chars) == CaseType::FirstUpper {
+            proper_nouns_first(&mut generated, &self.analyzer);
+        }
 
-        status
-            .into_suggestions()
-            .into_iter()
+        let generated = generated.into_iter();
+        let suggestions = match user_correction {
+            Some(correction) => std::iter::once(Suggestion {
+                word: correction.to_string(),
+                priority: 0,
+                fst_weight: 0,
+            })
+            .chain(generated.filter(|s| s.word != correction))
             .take(self.max_suggestions)
-            .map(|s| s.word)
-            .collect()
+            .collect(),
+            None => generated.take(self.max_suggestions).collect(),
+        };
+        (suggestions, interruption)
     }
 
     /// Perform morphological analysis on a word.
     ///
     /// Returns all valid analyses of the word, each containing attributes
-    /// like CLASS, BASEFORM, STRUCTURE, etc.
+    /// like CLASS, BASEFORM, STRUCTURE, etc. A word longer than the maximum
+    /// word length has none.
+    ///
+    /// The FSTOUTPUT attribute is the raw transducer output the other
+    /// attributes are parsed from. It keeps the tags that no attribute
+    /// reflects, such as the `[Ica]` of a geographical compound part or the
+    /// `[De]` of a directional prefix. With
+    /// [`AnalyzerOptions::include_raw`] set it is also in
+    /// [`Analysis::raw_fst_output`].
     ///
     /// Origin: voikkoAnalyzeWordCstr
     pub fn analyze(&self, word: &str) -> Vec<Analysis> {
-        let word_chars: Vec<char> = word.chars().collect();
-        let word_len = word_chars.len();
-        self.analyzer.analyze(&word_chars, word_len)
+        self.analyze_with(word, true, false)
     }
 
-    /// Hyphenate a word.
+    /// Perform morphological analysis on a word in any case, also reporting
+    /// the case variant the dictionary matched.
     ///
-    /// Returns a pattern string of the same character length as the input word.
-    /// Each character indicates the hyphenation status at that position:
-    /// - `' '`: no hyphenation point
-    /// - `'-'`: hyphenation point before this character
-    /// - `'='`: hyphenation point with explicit hyphen (compound boundary)
-    ///
-    /// Origin: voikkoHyphenateCstr
-    pub fn hyphenate(&self, word: &str) -> String {
-        let word_chars: Vec<char> = word.chars().collect();
-        let hyp = FinnishHyphenator::new(&self.analyzer, self.hyphenator_options);
-        hyp.hyphenate(&word_chars)
+    /// [`analyze`](Self::analyze) already ignores case ("KOIRA" and "Koira"
+    /// analyze like "koira"); this tells a caller whether the word was found
+    /// as given or only lowercased. The dictionary's own capitalization is
+    /// in the STRUCTURE attribute of each analysis.
+    pub fn analyze_any_case(&self, word: &str) -> (Vec<Analysis>, CaseVariant) {
+        let analyses = self.analyze(word);
+        let variant = if analyses.is_empty() {
+            CaseVariant::AsIs
+        } else {
+            CaseVariant::of_lowercase_lookup(&word.chars().collect::<Vec<_>>())
+        };
+        (analyses, variant)
     }
 
-    /// Check a paragraph of text for grammar errors.
+    /// Perform morphological analysis on a word, reporting why it could not
+    /// be analyzed instead of returning no analyses.
     ///
-    /// Returns a list of grammar errors found in the text.
-    ///
-    /// Origin: voikkoNextGrammarErrorCstr
-    pub fn grammar_errors(&self, text: &str) -> Vec<GrammarError> {
-        let text_chars: Vec<char> = text.chars().collect();
-        let text_len = text_chars.len();
-        self.grammar_checker
-            .check_with_analyzer(&text_chars, text_len, &self.analyzer)
+    /// Fails with [`VoikkoError::WordTooLong`] for a word longer than the
+    /// maximum word length and with [`VoikkoError::MorphologyLoad`] if the
+    /// dictionary of a lazily created handle could not be built. An unknown
+    /// word is not an error; it has no analyses.
+    pub fn try_analyze(&self, word: &str) -> Result<Vec<Analysis>, VoikkoError> {
+        self.warm_up()?;
+        let word_chars: Vec<char> = word.chars().collect();
+        self.check_length(&word_chars)?;
+        Ok(self.analyze_with(word, true, false))
     }
 
-    /// Tokenize text into a list of tokens.
+    /// Return the values of the attribute `key` (such as BASEFORM or CLASS)
+    /// in the analyses of `word`, in analysis order and without duplicates.
     ///
-    /// Each token has a type (Word, Punctuation, Whitespace, Unknown),
-    /// text content, and position.
-    ///
-    /// Origin: voikkoNextTokenCstr
-    pub fn tokens(&self, text: &str) -> Vec<Token> {
-        let text_chars: Vec<char> = text.chars().collect();
-        let text_len = text_chars.len();
-        let mut result = Vec::new();
-        let mut pos = 0;
-        while pos < text_len {
-            let (token_type, token_len) = tokenizer::next_token(&text_chars, text_len, pos);
-            if token_type == TokenType::None || token_len == 0 {
-                break;
+    /// The attributes that need full morphology (see
+    /// [`FinnishVfstAnalyzer::FULL_MORPHOLOGY_ATTRIBUTES`]) are computed only
+    /// when `key` is one of them, so asking for CLASS is cheaper than
+    /// [`analyze`](Self::analyze).
+    pub fn analyze_attribute(&self, word: &str, key: &str) -> Vec<String> {
+        let full_morphology = FinnishVfstAnalyzer::FULL_MORPHOLOGY_ATTRIBUTES.contains(&key);
+        let mut values: Vec<String> = Vec::new();
+        for analysis in self.analyze_with(word, full_morphology, false) {
+            if let Some(value) = analysis.get(key) {
+                if !values.iter().any(|v| v == value) {
+                    values.push(value.to_string());
+                }
             }
-            let token_text: String = text_chars[pos..pos + token_len].iter().collect();
-            result.push(Token::new(token_type, token_text, pos));
-            pos += token_len;
         }
-        result
+        values
     }
 
-    /// Detect sentence boundaries in text.
-    ///
-    /// Returns a list of sentences, each with its type (Probable, Possible, None)
-    /// and character length.
+    /// Perform morphological analysis on a word, also recording which
+    /// characters of `word` each compound part and derivational morph
+    /// covers, e.g. "koiran" (base form "koira") and "koppi" in
+    /// "koirankoppi".
     ///
-    /// Origin: voikkoNextSentenceStartCstr
-    pub fn sentences(&self, text: &str) -> Vec<Sentence> {
-        let text_chars: Vec<char> = text.chars().collect();
-        let text_len = text_chars.len();
-        let mut result = Vec::new();
-        let mut pos = 0;
-        while pos < text_len {
-            let (sentence_type, sentence_len) =
-                tokenizer::next_sentence(&text_chars, text_len, pos);
-            if sentence_type == SentenceType::None {
-                // Include the final segment
-                if sentence_len > 0 {
-                    result.push(Sentence::new(sentence_type, sentence_len));
-                }
-                break;
-            }
-            result.push(Sentence::new(sentence_type, sentence_len));
-            pos += sentence_len;
-        }
-        result
+    /// The analyses are those of [`analyze`](Self::analyze); each also has
+    /// [`Analysis::segments`]. The ranges are character indices into `word`
+    /// as given, also when input mapping is enabled.
+    pub fn analyze_segmented(&self, word: &str) -> Vec<Analysis> {
+        self.analyze_with(word, true, true)
+    }
+
+    /// Perform morphological analysis on a word like
+    /// [`analyze`](Self::analyze), with `budget` instead of the handle's
+    /// traversal budget for this call only. Also returns `true` if a lookup
+    /// was cut short by `budget`, so that analyses may be missing; a
+    /// word truncated under the handle's budget can be retried with a
+    /// larger one.
+    pub fn analyze_with_budget(
+        &self,
+        word: &str,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, bool) {
+        let word_chars: Vec<char> = word.chars().collect();
+        if self.is_too_long(&word_chars) {
+            return (Vec::new(), false);
+        }
+        let mut truncated = false;
+        let analyses = self.mapped_analyses(&word_chars, |chars| {
+            let (analyses, cut) =
+                self.analyzer
+                    .analyze_with_budget(chars, chars.len(), true, budget);
+            truncated = cut;
+            analyses
+        });
+        (analyses, truncated)
+    }
+
+    fn analyze_with(&self, word: &str, full_morphology: bool, segments: bool) -> Vec<Analysis> {
+        let word_chars: Vec<char> = word.chars().collect();
+        if self.is_too_long(&word_chars) {
+            return Vec::new();
+        }
+        self.mapped_analyses(&word_chars, |chars| {
+            if segments {
+                self.analyzer.analyze_segmented(chars, chars.len())
+            } else {
+                self.analyzer
+                    .analyze_full(chars, chars.len(), full_morphology)
+            }
+        })
+    }
+
+    /// Analyze `word_chars` with `analyze`, applying the input mapping to
+    /// the word and mapping STRUCTURE and segments back to the word as
+    /// given.
+    fn mapped_analyses(
+        &self,
+        word_chars: &[char],
+        analyze: impl FnOnce(&[char]) -> Vec<Analysis>,
+    ) -> Vec<Analysis> {
+        let mapping = self.spell_options.input_mapping;
+        if !mapping.is_enabled() {
+            return analyze(word_chars);
+        }
+        let mapped = mapping.apply(word_chars);
+        let mut analyses = analyze(&mapped.chars);
+        if !mapped.is_identity() {
+            for analysis in &mut analyses {
+                if let Some(structure) = analysis.get(ATTR_STRUCTURE) {
+                    let structure = mapped.original_structure(structure);
+                    analysis.set(ATTR_STRUCTURE, &structure);
+                }
+                if let Some(parts) = analysis.segments() {
+                    let parts = parts
+                        .iter()
+                        .map(|part| Segment {
+                            range: mapped.original_index(part.range.start)
+                                ..mapped.original_index(part.range.end),
+                            baseform: part.baseform.clone(),
+                        })
+                        .collect();
+                    analysis.set_segments(parts);
+                }
+            }
+        }
+        analyses
+    }
+
+    /// Generate the forms of `baseform` that have every attribute of
+    /// `target`, e.g. the genitive plural "koirien" of "koira".
+    ///
+    /// See [`FinnishVfstAnalyzer::generate`] for which forms are produced.
+    pub fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+        self.analyzer.generate(baseform, target)
+    }
+
+    /// Complete `prefix` to at most `max` dictionary words, e.g. for an
+    /// input method or a search box.
+    ///
+    /// See [`FinnishVfstAnalyzer::complete`] for which words are returned
+    /// and in which order.
+    pub fn complete(&self, prefix: &str, max: usize) -> Vec<String> {
+        self.analyzer.complete(prefix, max)
+    }
+
+    /// Analyze `word` and generate it back from each analysis, reporting
+    /// the analyses whose base form and attributes do not give the word.
+    ///
+    /// See [`round_trip`](crate::morphology::round_trip).
+    pub fn round_trip(&self, word: &str) -> Vec<RoundTrip> {
+        round_trip(&self.analyzer, word)
+    }
+
+    /// Return the compound structure of a word.
+    ///
+    /// Uses the analysis that splits the word into the fewest parts, so
+    /// callers get one canonical segmentation. Returns `None` if the word
+    /// is not recognized.
+    pub fn compound_info(&self, word: &str) -> Option<CompoundInfo> {
+        compound_info(word, &self.analyze(word))
+    }
+
+    /// Hyphenate a word.
+    ///
+    /// Returns a pattern string of the same character length as the input word.
+    /// Each character indicates the hyphenation status at that position:
+    /// - `' '`: no hyphenation point
+    /// - `'-'`: hyphenation point before this character
+    /// - `'='`: hyphenation point with explicit hyphen (compound boundary)
+    ///
+    /// A word longer than the maximum word length gets no hyphenation
+    /// points unless it is a listed exception; `explain_hyphenation` reports
+    /// it as [`HyphenReason::WordTooLong`](crate::hyphenator::HyphenReason::WordTooLong).
+    ///
+    /// Origin: voikkoHyphenateCstr
+    pub fn hyphenate(&self, word: &str) -> String {
+        let word_chars: Vec<char> = word.chars().collect();
+        self.hyphenator().hyphenate(&word_chars)
+    }
+
+    /// Hyphenate a word, reporting why it could not be hyphenated instead
+    /// of returning a pattern without hyphenation points.
+    ///
+    /// Fails with [`VoikkoError::WordTooLong`] for a word longer than the
+    /// maximum word length that is not a listed exception, and with
+    /// [`VoikkoError::MorphologyLoad`] if the dictionary of a lazily created
+    /// handle could not be built.
+    pub fn try_hyphenate(&self, word: &str) -> Result<String, VoikkoError> {
+        self.warm_up()?;
+        let word_chars: Vec<char> = word.chars().collect();
+        let hyphenator = self.hyphenator();
+        if word_chars.len() > self.hyphenator_options.max_word_chars {
+            let explanation = hyphenator.explain(&word_chars);
+            if explanation.reasons.first() == Some(&HyphenReason::WordTooLong) {
+                return Err(VoikkoError::WordTooLong {
+                    length: word_chars.len(),
+                    max: self.hyphenator_options.max_word_chars,
+                });
+            }
+            return Ok(explanation.pattern);
+        }
+        Ok(hyphenator.hyphenate(&word_chars))
+    }
+
+    /// Hyphenate a word and explain, for each position, which rule allowed
+    /// or suppressed the break.
+    pub fn explain_hyphenation(&self, word: &str) -> HyphenExplanation {
+        let word_chars: Vec<char> = word.chars().collect();
+        self.hyphenator().explain(&word_chars)
+    }
+
+    /// The hyphenator with the current options and exceptions.
+    fn hyphenator(&self) -> FinnishHyphenator<&FinnishVfstAnalyzer> {
+        FinnishHyphenator::new(&self.analyzer, self.hyphenator_options)
+            .with_exceptions(Arc::clone(&self.hyphenation_exceptions))
+            .with_rules(Arc::clone(&self.language_rules))
+    }
+
+    /// Check a paragraph of text for grammar errors.
+    ///
+    /// Returns a list of grammar errors found in the text, none with
+    /// [`HandleProfile::Lite`].
+    ///
+    /// Origin: voikkoNextGrammarErrorCstr
+    pub fn grammar_errors(&self, text: &str) -> Vec<GrammarError> {
+        if self.profile == HandleProfile::Lite {
+            return Vec::new();
+        }
+        if let Some(plain) = self.stripped(text) {
+            let mut errors = self.check_paragraph(&plain.chars);
+            map_errors(&plain, &mut errors);
+            return errors;
+        }
+        let text_chars: Vec<char> = text.chars().collect();
+        self.check_paragraph(&text_chars)
+    }
+
+    /// Check one paragraph for grammar errors, without the autocorrect
+    /// matches on words the user accepted, which `spell()` accepts too.
+    fn check_paragraph(&self, paragraph: &[char]) -> Vec<GrammarError> {
+        let mut errors =
+            self.grammar_checker
+                .check_with_analyzer(paragraph, paragraph.len(), &self.analyzer);
+        self.drop_user_accepted(paragraph, &mut errors);
+        errors
+    }
+
+    /// Remove the autocorrect errors (`GCERR_INVALID_SPELLING`) whose span
+    /// in `paragraph` is a word the user accepted.
+    fn drop_user_accepted(&self, paragraph: &[char], errors: &mut Vec<GrammarError>) {
+        if self.user_state.is_empty() {
+            return;
+        }
+        let service = self.spell_service();
+        errors.retain(|e| {
+            e.error_code != GCERR_INVALID_SPELLING
+                || !paragraph
+                    .get(e.start_pos..e.start_pos + e.error_len)
+                    .is_some_and(|span| service.is_user_accepted(&span.iter().collect::<String>()))
+        });
+    }
+
+    /// Check a paragraph of text for grammar errors like `grammar_errors()`,
+    /// failing with [`VoikkoError::MorphologyLoad`] if the dictionary of a
+    /// lazily created handle could not be built instead of checking the
+    /// text as if every word were unknown.
+    pub fn try_grammar_errors(&self, text: &str) -> Result<Vec<GrammarError>, VoikkoError> {
+        self.warm_up()?;
+        Ok(self.grammar_errors(text))
+    }
+
+    /// Tokenize and analyze a paragraph as the grammar checker does, and
+    /// return its sentences and tokens instead of checking it.
+    ///
+    /// Each word token carries its morphological analyses and the flags the
+    /// built-in checks derive from them (valid word, main verb, conjunction
+    /// and so on); each sentence carries its autocorrect matches. An
+    /// application can run its own checks on the result without analyzing
+    /// the text again. Offsets are characters in `text`, also when invisible
+    /// characters are stripped; token texts are then without them.
+    pub fn analyze_paragraph(&self, text: &str) -> AnalyzedParagraph {
+        if let Some(plain) = self.stripped(text) {
+            let mut paragraph = self.analyze_paragraph_chars(&plain.chars);
+            for sentence in &mut paragraph.sentences {
+                sentence.start = plain.original_range(sentence.start, 0).0;
+                for token in &mut sentence.tokens {
+                    token.start = plain.original_range(token.start, 0).0;
+                }
+                map_errors(&plain, &mut sentence.autocorrections);
+            }
+            return paragraph;
+        }
+        let text_chars: Vec<char> = text.chars().collect();
+        self.analyze_paragraph_chars(&text_chars)
+    }
+
+    /// `analyze_paragraph()` with the text already converted to characters
+    /// and invisible characters removed: the grammar checker's paragraph,
+    /// with the speller's verdict on each word and without the autocorrect
+    /// matches on words the user accepted.
+    fn analyze_paragraph_chars(&self, text_chars: &[char]) -> AnalyzedParagraph {
+        let mut paragraph = self.grammar_checker.analyze_with_analyzer(
+            text_chars,
+            text_chars.len(),
+            &self.analyzer,
+        );
+        let service = self.spell_service();
+        for sentence in &mut paragraph.sentences {
+            for token in &mut sentence.tokens {
+                token.is_misspelled = token.token_type == TokenType::Word
+                    && service.classify(&token.text).is_misspelled();
+            }
+            self.drop_user_accepted(text_chars, &mut sentence.autocorrections);
+        }
+        paragraph
+    }
+
+    /// Tokenize text into a list of tokens.
+    ///
+    /// Each token has a type (Word, Punctuation, Whitespace, Unknown),
+    /// text content, and its position as a character, UTF-8 byte and UTF-16
+    /// offset.
+    ///
+    /// Origin: voikkoNextTokenCstr
+    pub fn tokens(&self, text: &str) -> Vec<Token> {
+        match self.invisible_chars {
+            InvisibleChars::Keep => tokenizer::tokenize(text),
+            InvisibleChars::Strip => invisible::tokenize_stripped(text),
+        }
+    }
+
+    /// Detect sentence boundaries in text.
+    ///
+    /// Returns a list of sentences, each with its type (Probable, Possible, None)
+    /// and character length.
+    ///
+    /// Unless disabled with `set_sentence_spell_check(false)`, a word followed
+    /// by a dot is checked with the speller. If the word with the dot is
+    /// accepted (an abbreviation such as "esim."), the boundary after it is
+    /// reported as `Possible` rather than `Probable`, as in libvoikko.
+    ///
+    /// Closing quotation marks and brackets right after the terminal
+    /// punctuation (`?”`, `.)`) end the sentence with it, unless disabled
+    /// with `set_sentence_closing_punctuation(false)`.
+    ///
+    /// Lengths include the whitespace up to the next sentence unless set
+    /// otherwise with [`set_sentence_whitespace`](Self::set_sentence_whitespace);
+    /// `trimmed_len` is the length without it in either case.
+    ///
+    /// Origin: voikkoNextSentenceStartCstr
+    pub fn sentences(&self, text: &str) -> Vec<Sentence> {
+        let mut sentences = self.detect_sentences(text);
+        for sentence in &mut sentences {
+            sentence.apply(self.sentence_whitespace);
+        }
+        sentences
+    }
+
+    /// Detect sentences in text and return each with its text and its
+    /// character, UTF-8 byte and UTF-16 offsets.
+    ///
+    /// The sentences are those of [`sentences`](Self::sentences); this saves
+    /// callers from walking the text to slice them. The starts do not depend
+    /// on the [`SentenceWhitespace`] policy, so the spans can be placed in
+    /// the text with either.
+    pub fn sentence_spans(&self, text: &str) -> Vec<SentenceSpan> {
+        let mut spans = SentenceSpan::locate(text, &self.detect_sentences(text));
+        for span in &mut spans {
+            span.apply(self.sentence_whitespace);
+        }
+        spans
+    }
+
+    /// The sentences of `text` as detected, covering the whole text.
+    fn detect_sentences(&self, text: &str) -> Vec<Sentence> {
+        let mut sentences = if self.sentence_spell_check {
+            let check_fn = |word: &[char]| self.spell(&word.iter().collect::<String>());
+            self.split_sentences(text, Some(&check_fn))
+        } else {
+            self.split_sentences(text, None)
+        };
+        Sentence::measure_trimmed(text, &mut sentences);
+        sentences
+    }
+
+    /// Detect sentence boundaries in text using a custom spell check callback
+    /// for abbreviation detection.
+    ///
+    /// `spell_check` receives a word including its trailing dot and returns
+    /// `true` if the dot belongs to the word (an abbreviation).
+    pub fn sentences_with_spell_check(
+        &self,
+        text: &str,
+        spell_check: &dyn Fn(&str) -> bool,
+    ) -> Vec<Sentence> {
+        let check_fn = |word: &[char]| spell_check(&word.iter().collect::<String>());
+        let mut sentences = self.split_sentences(text, Some(&check_fn));
+        Sentence::measure_trimmed(text, &mut sentences);
+        for sentence in &mut sentences {
+            sentence.apply(self.sentence_whitespace);
+        }
+        sentences
+    }
+
+    /// `split_sentences()` with the handle's options, skipping invisible
+    /// characters if they are stripped.
+    fn split_sentences(
+        &self,
+        text: &str,
+        spell_check: tokenizer::SpellCheckFn<'_>,
+    ) -> Vec<Sentence> {
+        let include_closing = self.sentence_closing_punctuation;
+        match self.stripped(text) {
+            Some(plain) => {
+                let plain_text: String = plain.chars.iter().collect();
+                let sentences = split_sentences(&plain_text, spell_check, include_closing);
+                invisible::map_sentences(&plain, sentences)
+            }
+            None => split_sentences(text, spell_check, include_closing),
+        }
+    }
+
+    /// `text` without invisible characters, if they are stripped and `text`
+    /// contains any.
+    fn stripped(&self, text: &str) -> Option<PlainText> {
+        match self.invisible_chars {
+            InvisibleChars::Keep => None,
+            InvisibleChars::Strip => invisible::strip(text),
+        }
+    }
+
+    // =========================================================================
+    // Component access
+    // =========================================================================
+
+    /// The loaded speller, for reuse by other Rust code (custom grammar
+    /// engines, linguistic tools) without the string-based API.
+    ///
+    /// Like other [`Speller`] implementations it expects a normalized word:
+    /// it applies the dictionary, the Finnish speller tweaks and the user
+    /// dictionary, but not the option bypasses, case checks and cache of
+    /// [`spell`](Self::spell). The result tells whether the capitalization
+    /// must change.
+    ///
+    /// The speller borrows the handle and is `Sync`: any number of threads
+    /// may use it at once. Option setters take `&mut self`, so options cannot
+    /// change while it is borrowed.
+    pub fn speller(&self) -> &(dyn Speller + Sync) {
+        self
+    }
+
+    /// The loaded morphological analyzer. Thread safety is as for
+    /// [`speller`](Self::speller). Analyses are not affected by the input
+    /// mapping of [`analyze`](Self::analyze).
+    pub fn analyzer(&self) -> &(dyn Analyzer + Sync) {
+        &self.analyzer
     }
 
     // =========================================================================
@@ -344,6 +1533,35 @@ impl VoikkoHandle {
         self.spell_options.accept_missing_hyphens = value;
     }
 
+    /// Set which typographic character classes (superscripts, subscripts,
+    /// fractions, typographic digits) are mapped to plain characters before
+    /// spell checking and analysis. Analysis STRUCTURE refers to the
+    /// original word.
+    pub fn set_input_mapping(&mut self, mapping: InputMapping) {
+        self.spell_options.input_mapping = mapping;
+    }
+
+    /// Set how zero-width and control characters (see
+    /// [`is_invisible`](crate::invisible::is_invisible)) are handled in
+    /// tokenization, sentence detection, spell checking, suggestions and
+    /// grammar checking. With [`InvisibleChars::Strip`] they are removed
+    /// before checking, and all positions still refer to the original text.
+    pub fn set_invisible_chars(&mut self, policy: InvisibleChars) {
+        self.invisible_chars = policy;
+    }
+
+    /// Set what [`analyze`](Self::analyze) includes besides the attributes,
+    /// such as the raw transducer output (see [`AnalyzerOptions`]).
+    pub fn set_analyzer_options(&mut self, options: AnalyzerOptions) {
+        self.analyzer.set_options(options);
+    }
+
+    /// Return the options set with
+    /// [`set_analyzer_options`](Self::set_analyzer_options).
+    pub fn analyzer_options(&self) -> AnalyzerOptions {
+        self.analyzer.options()
+    }
+
     /// Set whether to accept incomplete sentences in titles (grammar checking).
     pub fn set_accept_titles_in_gc(&mut self, value: bool) {
         self.grammar_options.accept_titles_in_gc = value;
@@ -370,6 +1588,174 @@ impl VoikkoHandle {
             .set_options(self.grammar_options.clone());
     }
 
+    /// Set whether to report overlong compound words (opt-in style check).
+    pub fn set_check_overlong_compounds(&mut self, value: bool) {
+        self.grammar_options.check_overlong_compounds = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set the maximum number of components in a compound word before it is
+    /// reported as overlong. 0 disables the component limit.
+    pub fn set_max_compound_parts(&mut self, value: usize) {
+        self.grammar_options.max_compound_parts = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set the maximum length (in characters) of a compound word before it
+    /// is reported as overlong. 0 disables the length limit.
+    pub fn set_max_compound_chars(&mut self, value: usize) {
+        self.grammar_options.max_compound_chars = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to report dates, clock times, ordinal numbers and units
+    /// written against the number style (opt-in check).
+    pub fn set_check_number_formats(&mut self, value: bool) {
+        self.grammar_options.check_number_formats = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set the conventions checked by
+    /// [`set_check_number_formats`](Self::set_check_number_formats):
+    /// [`NumberStyle::Standard`] (the default) or [`NumberStyle::Technical`].
+    pub fn set_number_style(&mut self, style: NumberStyle) {
+        self.grammar_options.number_style = style;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to run the embedded default grammar rule pack (redundant
+    /// phrases and similar style remarks).
+    pub fn set_check_default_grammar_rules(&mut self, value: bool) {
+        self.grammar_options.check_default_rules = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to report runs of sentences starting with the same word
+    /// and quotation marks left unpaired across sentences (opt-in check).
+    pub fn set_check_discourse(&mut self, value: bool) {
+        self.grammar_options.check_discourse = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to report "sekä" without "että" and "joko" without "tai"
+    /// in a sentence (opt-in check).
+    pub fn set_check_paired_conjunctions(&mut self, value: bool) {
+        self.grammar_options.check_paired_conjunctions = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to report correctly spelled words in a case their context
+    /// does not allow, such as "kohti kaupunki" or "minä täytyy" (opt-in
+    /// check). Errors have the `RealWord` category.
+    pub fn set_check_real_word_errors(&mut self, value: bool) {
+        self.grammar_options.check_real_word_errors = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to report finite verbs that do not agree in person or
+    /// number with the nominative subject directly before them, such as
+    /// "koirat juoksee" (opt-in, experimental check). Errors are warnings.
+    pub fn set_check_subject_verb_agreement(&mut self, value: bool) {
+        self.grammar_options.check_subject_verb_agreement = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Report grammar errors with `code` at `severity` instead of the code's
+    /// default (for example, downgrade a check to a warning).
+    pub fn set_grammar_error_severity(&mut self, code: i32, severity: GrammarErrorSeverity) {
+        self.grammar_options
+            .severity_overrides
+            .insert(code, severity);
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Restore the default severity of grammar errors with `code`.
+    pub fn reset_grammar_error_severity(&mut self, code: i32) {
+        self.grammar_options.severity_overrides.remove(&code);
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set whether to collect per-rule timing statistics during grammar
+    /// checking (see [`grammar_rule_stats`](Self::grammar_rule_stats)).
+    /// Timing needs a system clock and is not available on
+    /// `wasm32-unknown-unknown`.
+    pub fn set_grammar_rule_timing(&mut self, value: bool) {
+        self.grammar_options.timing.enabled = value;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Set a time cap for grammar rules. A rule that has spent `cap` on one
+    /// paragraph is skipped for the rest of that paragraph and
+    /// recorded in [`slow_grammar_rules`](Self::slow_grammar_rules); errors it
+    /// found before are kept. A cap enables timing. `None` removes the cap.
+    pub fn set_grammar_rule_time_cap(&mut self, cap: Option<Duration>) {
+        self.grammar_options.timing.cap = cap;
+        self.grammar_checker
+            .set_options(self.grammar_options.clone());
+    }
+
+    /// Per-rule timing statistics of grammar checking, slowest (by total
+    /// time) first. Empty unless timing or a time cap is set. Paragraphs
+    /// answered from the grammar cache are not timed.
+    pub fn grammar_rule_stats(&self) -> Vec<RuleStats> {
+        self.grammar_checker.rule_stats()
+    }
+
+    /// The most recent grammar rules that exceeded the time cap, oldest
+    /// first, with an excerpt of the paragraph.
+    pub fn slow_grammar_rules(&self) -> Vec<SlowRule> {
+        self.grammar_checker.slow_rules()
+    }
+
+    /// Discard the grammar rule timing statistics and slow-rule records.
+    pub fn reset_grammar_rule_stats(&self) {
+        self.grammar_checker.reset_rule_stats();
+    }
+
+    /// Set whether sentence detection uses the speller to recognize
+    /// abbreviations. Disabling it is faster but splits sentences at
+    /// abbreviations not covered by the built-in heuristics.
+    pub fn set_sentence_spell_check(&mut self, value: bool) {
+        self.sentence_spell_check = value;
+    }
+
+    /// Set whether closing quotation marks and brackets that follow the
+    /// terminal punctuation are included in the ending sentence. Disabling
+    /// it places the boundary before them, as libvoikko does.
+    pub fn set_sentence_closing_punctuation(&mut self, value: bool) {
+        self.sentence_closing_punctuation = value;
+    }
+
+    /// Set whether the lengths of [`sentences`](Self::sentences) and the
+    /// ends of [`sentence_spans`](Self::sentence_spans) include the
+    /// whitespace up to the next sentence. With
+    /// [`SentenceWhitespace::TrimEnd`] the lengths no longer add up to the
+    /// text length; place the sentences with the spans instead.
+    pub fn set_sentence_whitespace(&mut self, policy: SentenceWhitespace) {
+        self.sentence_whitespace = policy;
+    }
+
+    /// Set whether `grammar_errors_from_text()` treats the text as hard
+    /// wrapped: paragraphs are separated by blank lines, and a line break
+    /// inside a paragraph is checked as a space. Disabled by default, when
+    /// each line is a paragraph.
+    pub fn set_reflow_paragraphs(&mut self, value: bool) {
+        self.reflow_paragraphs = value;
+    }
+
     /// Set the minimum word length for hyphenation.
     pub fn set_min_hyphenated_word_length(&mut self, value: usize) {
         self.hyphenator_options.min_hyphenated_word_length = value;
@@ -380,6 +1766,48 @@ impl VoikkoHandle {
         self.max_suggestions = value;
     }
 
+    /// Set the maximum word length in characters. Longer words are not
+    /// spell checked, analyzed, hyphenated or given suggestions; see
+    /// [`spell_status`](Self::spell_status).
+    ///
+    /// The default, and the largest accepted value, is [`MAX_WORD_CHARS`],
+    /// the longest word the analyzers accept (their output buffer is sized
+    /// for it). Larger values are clamped to it.
+    pub fn set_max_word_chars(&mut self, value: usize) {
+        let value = value.min(MAX_WORD_CHARS);
+        self.spell_options.max_word_chars = value;
+        self.hyphenator_options.max_word_chars = value;
+    }
+
+    /// The maximum word length in characters.
+    pub fn max_word_chars(&self) -> usize {
+        self.spell_options.max_word_chars
+    }
+
+    /// Set the weights used to rank suggestions. The default weights order
+    /// suggestions by morphological priority only.
+    pub fn set_suggestion_ranking(&mut self, weights: RankingWeights) {
+        self.suggestion_ranker = SuggestionRanker::new(weights);
+    }
+
+    /// Set whether suggestions for a word with only its first letter
+    /// capitalized list proper nouns (CLASS etunimi, sukunimi, paikannimi or
+    /// nimi) before common words. Enable it for a word capitalized in the
+    /// middle of a sentence, which is most likely a name; at the start of a
+    /// sentence the capital says nothing. Disabled by default.
+    pub fn set_prefer_proper_nouns(&mut self, value: bool) {
+        self.prefer_proper_nouns = value;
+    }
+
+    /// Set whether suggestions follow the case of the misspelled word:
+    /// "Koirra" gets "Koira" and "KOIRRA" gets "KOIRA". Capitals that the
+    /// dictionary requires (as in "ABC-kirja") are kept either way. Enabled
+    /// by default.
+    pub fn set_match_suggestion_case(&mut self, value: bool) {
+        self.typing_strategy.set_match_word_case(value);
+        self.ocr_strategy.set_match_word_case(value);
+    }
+
     // =========================================================================
     // Extended API methods (ported from TS wrapper layer)
     // =========================================================================
@@ -503,15 +1931,67 @@ impl VoikkoHandle {
 
     /// Check text for grammar errors, splitting at newline boundaries.
     ///
-    /// Each line is treated as a separate paragraph. Error positions
-    /// (`start_pos`) are relative to the full input text.
+    /// Each line is treated as a separate paragraph, or with
+    /// [`set_reflow_paragraphs`](Self::set_reflow_paragraphs) each block of
+    /// lines between blank lines. Error positions (`start_pos`) are relative
+    /// to the full input text.
     ///
     /// Origin: voikkoNextGrammarErrorCstr (called per-paragraph by the C API),
     ///         Voikko.grammarErrors() in libvoikko/js/src/index.ts
     pub fn grammar_errors_from_text(&self, text: &str) -> Vec<GrammarError> {
+        if self.reflow_paragraphs {
+            let mut marked = MarkedText::new(text);
+            marked_text::mark_line_breaks(&mut marked);
+            return self.grammar_errors_marked(&marked);
+        }
+        if let Some(plain) = self.stripped(text) {
+            let mut errors = self.grammar_errors_by_line(&plain.chars);
+            map_errors(&plain, &mut errors);
+            return errors;
+        }
+        let text_chars: Vec<char> = text.chars().collect();
+        self.grammar_errors_by_line(&text_chars)
+    }
+
+    /// Check text for grammar errors like `grammar_errors_from_text()`,
+    /// failing with [`VoikkoError::MorphologyLoad`] if the dictionary of a
+    /// lazily created handle could not be built.
+    pub fn try_grammar_errors_from_text(
+        &self,
+        text: &str,
+    ) -> Result<Vec<GrammarError>, VoikkoError> {
+        self.warm_up()?;
+        Ok(self.grammar_errors_from_text(text))
+    }
+
+    /// Check marked-up text, such as HTML or Markdown, for grammar errors.
+    ///
+    /// The markup spans of `text` are skipped or replaced by their stand-ins
+    /// and the result is checked as in `grammar_errors_from_text()`, one
+    /// paragraph per line. Error positions are character offsets into the
+    /// original text; an error that encloses markup covers it.
+    pub fn grammar_errors_marked(&self, text: &MarkedText) -> Vec<GrammarError> {
+        let plain = match self.invisible_chars {
+            InvisibleChars::Keep => text.plain_text(),
+            InvisibleChars::Strip => {
+                let mut text = text.clone();
+                invisible::mark_invisible(&mut text);
+                text.plain_text()
+            }
+        };
+        let mut errors = self.grammar_errors_by_line(&plain.chars);
+        map_errors(&plain, &mut errors);
+        errors
+    }
+
+    /// `grammar_errors_from_text()` with the text already converted to
+    /// characters.
+    fn grammar_errors_by_line(&self, text_chars: &[char]) -> Vec<GrammarError> {
         let mut result = Vec::new();
+        if self.profile == HandleProfile::Lite {
+            return result;
+        }
         let mut pos = 0;
-        let text_chars: Vec<char> = text.chars().collect();
         let text_len = text_chars.len();
 
         while pos < text_len {
@@ -532,14 +2012,11 @@ impl VoikkoHandle {
             // Check non-empty paragraph
             if para_end > pos {
                 let para = &text_chars[pos..para_end];
-                let para_len = para.len();
-                let mut errors =
-                    self.grammar_checker
-                        .check_with_analyzer(para, para_len, &self.analyzer);
+                let mut errors = self.check_paragraph(para);
 
-                // Adjust start_pos to be relative to the full text
+                // Adjust positions to be relative to the full text
                 for error in &mut errors {
-                    error.start_pos += pos;
+                    error.shift(pos);
                 }
                 result.extend(errors);
             }
@@ -561,30 +2038,401 @@ impl VoikkoHandle {
         env!("CARGO_PKG_VERSION")
     }
 
-    /// Replace the speller cache with a new one of the given size.
+    /// Resize the speller cache.
     ///
     /// `size` is the size parameter (power-of-two scaling factor).
-    /// A value of 0 gives the base cache size.
+    /// A value of 0 gives the base cache size. The most recently used
+    /// words that fit in the new size stay cached.
     ///
     /// Origin: voikkoSetIntegerOption VOIKKO_SPELLER_CACHE_SIZE
     pub fn set_speller_cache_size(&mut self, size: usize) {
-        self.speller_cache = RefCell::new(SpellerCache::new(size));
+        self.speller_cache.resize(size);
     }
 
-    /// Release resources held by this handle. After calling this,
-    /// the handle should not be used for any NLP operations.
+    /// Hit, miss and eviction counts of the speller cache, summed over its
+    /// shards.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.speller_cache.stats()
+    }
+
+    /// Set how many words the analysis cache holds; 0 disables it.
     ///
-    /// In Rust, this is a no-op since resources are released when the handle
-    /// is dropped. This method exists to match the C++ API pattern where
-    /// `voikkoTerminate` explicitly frees resources.
-    pub fn terminate(self) {
-        // Resources are released by Drop
+    /// Spelling, suggestions, hyphenation, grammar checking and `analyze()`
+    /// share the cache, so a word they all look at is looked up in the
+    /// dictionary once. It holds
+    /// [`DEFAULT_ANALYSIS_CACHE_WORDS`](crate::morphology::DEFAULT_ANALYSIS_CACHE_WORDS)
+    /// words by default and is disabled with [`HandleProfile::Lite`]. The
+    /// most recently used words that fit in the new size stay cached.
+    pub fn set_analysis_cache_size(&mut self, words: usize) {
+        self.analyzer.set_analysis_cache_capacity(words);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Hit, miss and eviction counts of the analysis cache, summed over its
+    /// shards.
+    pub fn analysis_cache_stats(&self) -> CacheStats {
+        self.analyzer.analysis_cache_stats()
+    }
+
+    /// Set how many paragraphs the grammar cache holds; 0 disables it.
+    ///
+    /// `grammar_errors()` caches the errors of each paragraph by its text
+    /// and the grammar options, so an editor re-checking a document after
+    /// an edit only checks the paragraphs that changed. The cache holds 256
+    /// paragraphs by default; the most recently used ones that fit in the
+    /// new size stay cached.
+    pub fn set_grammar_cache_size(&mut self, paragraphs: usize) {
+        self.grammar_checker.cache().resize(paragraphs);
+    }
+
+    /// Hit, miss and eviction counts of the grammar cache; `len` is the
+    /// number of cached paragraphs.
+    pub fn grammar_cache_stats(&self) -> CacheStats {
+        self.grammar_checker.cache().stats()
+    }
+
+    /// Discard all cached grammar results. The handle clears the cache
+    /// itself when the dictionary or the rules change.
+    pub fn clear_grammar_cache(&self) {
+        self.grammar_checker.cache().clear();
+    }
+
+    /// Discard the cached grammar results of one paragraph, under all
+    /// grammar options. Returns `true` if it was cached.
+    pub fn invalidate_grammar_paragraph(&self, text: &str) -> bool {
+        let mut cache = self.grammar_checker.cache();
+        match self.stripped(text) {
+            Some(plain) => cache.invalidate(&plain.chars),
+            None => cache.invalidate(&text.chars().collect::<Vec<_>>()),
+        }
+    }
+
+    // =========================================================================
+    // Grammar rule packs
+    // =========================================================================
+
+    /// Load a JSON grammar rule pack (see `grammar::rules` for the format).
+    /// Requires the `serde` feature.
+    ///
+    /// The rules are checked in addition to the built-in checks. A rule with
+    /// the same id as an already loaded rule replaces it. Returns the number
+    /// of rules in the pack; on error no rules are added.
+    #[cfg(feature = "serde")]
+    pub fn load_grammar_rules(&mut self, json: &str) -> Result<usize, VoikkoError> {
+        let rules = RuleSet::from_json(json)?;
+        let count = rules.len();
+        self.grammar_checker.add_rules(rules);
+        Ok(count)
+    }
+
+    /// Remove all grammar rules loaded with `load_grammar_rules`.
+    pub fn clear_grammar_rules(&mut self) {
+        self.grammar_checker.clear_rules();
+    }
+
+    // =========================================================================
+    // User autocorrect rules
+    // =========================================================================
+
+    /// Add an autocorrect rule replacing `from` with `to` (see
+    /// `grammar::autocorrect_rules` for the pattern syntax).
+    ///
+    /// User rules are checked before the autocorrect transducer and work
+    /// without one. A rule with the same `from` is replaced.
+    pub fn add_autocorrect_rule(&mut self, from: &str, to: &str) -> Result<(), VoikkoError> {
+        self.grammar_checker.add_autocorrect_rule(from, to)?;
+        Ok(())
+    }
+
+    /// Remove the autocorrect rule for `from`. Returns `true` if there was one.
+    pub fn remove_autocorrect_rule(&mut self, from: &str) -> bool {
+        self.grammar_checker.remove_autocorrect_rule(from)
+    }
+
+    /// Remove all user autocorrect rules.
+    pub fn clear_autocorrect_rules(&mut self) {
+        self.grammar_checker.clear_autocorrect_rules();
+    }
+
+    /// The user autocorrect rules.
+    pub fn autocorrect_rules(&self) -> &AutocorrectRules {
+        self.grammar_checker.autocorrect_rules()
+    }
+
+    // =========================================================================
+    // Message catalog
+    // =========================================================================
+
+    /// The catalog of localized error descriptions and message templates,
+    /// for rendering grammar errors (see [`MessageCatalog::describe`]).
+    /// Initially the built-in Finnish and English catalog.
+    pub fn message_catalog(&self) -> &MessageCatalog {
+        &self.message_catalog
+    }
+
+    /// Use `catalog` as the message catalog, e.g. one catalog shared by
+    /// all handles of an application.
+    pub fn set_message_catalog(&mut self, catalog: Arc<MessageCatalog>) {
+        self.message_catalog = catalog;
+    }
+
+    /// Add the entries of a message catalog file (see
+    /// [`MessageCatalog::load_text`]) to this handle's catalog. A shared
+    /// catalog is copied first. Returns the number of entries in the file;
+    /// on error no entries are added.
+    pub fn load_message_catalog(&mut self, text: &str) -> Result<usize, VoikkoError> {
+        Ok(Arc::make_mut(&mut self.message_catalog).load_text(text)?)
+    }
+
+    // =========================================================================
+    // Hyphenation exceptions
+    // =========================================================================
+
+    /// Hyphenate a word as given, e.g. `"Voik-ko"`, instead of by analysis
+    /// and rules (see `hyphenator::HyphenationExceptions` for the notation).
+    /// The word is matched ignoring case; its inflected forms are not.
+    pub fn add_hyphenation_exception(&mut self, entry: &str) -> Result<(), VoikkoError> {
+        Arc::make_mut(&mut self.hyphenation_exceptions).add(entry)?;
+        Ok(())
+    }
+
+    /// Remove the hyphenation exception for `word`.
+    /// Returns `true` if there was one.
+    pub fn remove_hyphenation_exception(&mut self, word: &str) -> bool {
+        Arc::make_mut(&mut self.hyphenation_exceptions).remove(word)
+    }
+
+    /// Add the entries of a hyphenation exception list, one entry per line.
+    /// Returns the number of entries in the list; on error no entries are
+    /// added.
+    pub fn load_hyphenation_exceptions(&mut self, text: &str) -> Result<usize, VoikkoError> {
+        let exceptions = HyphenationExceptions::from_text(text)?;
+        let count = exceptions.len();
+        Arc::make_mut(&mut self.hyphenation_exceptions).merge(exceptions);
+        Ok(count)
+    }
+
+    /// Remove all hyphenation exceptions.
+    pub fn clear_hyphenation_exceptions(&mut self) {
+        self.hyphenation_exceptions = Arc::default();
+    }
+
+    /// Return the hyphenation exceptions.
+    pub fn hyphenation_exceptions(&self) -> &HyphenationExceptions {
+        &self.hyphenation_exceptions
+    }
+
+    // =========================================================================
+    // Language rules
+    // =========================================================================
+
+    /// Replace the vowel harmony, diphthong and consonant cluster tables
+    /// used by hyphenation and typing suggestions. The OCR suggestions do
+    /// not use them.
+    pub fn set_language_rules(&mut self, rules: LanguageRules) {
+        let rules = Arc::new(rules);
+        let match_word_case = self.typing_strategy.match_word_case();
+        self.typing_strategy = typing_strategy_with_rules(800, Arc::clone(&rules));
+        self.typing_strategy.set_match_word_case(match_word_case);
+        self.language_rules = rules;
+    }
+
+    /// Return the vowel harmony, diphthong and consonant cluster tables.
+    pub fn language_rules(&self) -> &LanguageRules {
+        &self.language_rules
+    }
+
+    // =========================================================================
+    // User state (personalization)
+    // =========================================================================
+
+    /// Add a word to the user dictionary.
+    ///
+    /// `hint` is an optional inflection class hint: a dictionary word that
+    /// inflects the same way, such as "paperi" for "blogi". With a hint,
+    /// inflected forms of the word are accepted too. Adding a word again
+    /// replaces its hint.
+    pub fn add_user_word(&mut self, word: &str, hint: Option<&str>) {
+        self.user_state.user_words.add(word, hint);
+    }
+
+    /// Remove a word from the user dictionary.
+    /// Returns `true` if the word was present.
+    pub fn remove_user_word(&mut self, word: &str) -> bool {
+        self.user_state.user_words.remove(word)
+    }
+
+    /// Return the user dictionary.
+    pub fn user_dictionary(&self) -> &UserDictionary {
+        &self.user_state.user_words
+    }
+
+    /// Serialize the user dictionary into its text file format (one word
+    /// per line, optionally followed by a tab and the hint).
+    pub fn export_user_dictionary(&self) -> String {
+        self.user_state.user_words.to_text()
+    }
+
+    /// Add the entries of a user dictionary file produced by
+    /// `export_user_dictionary()` or written by hand. Returns the number of
+    /// entries in the file; on error no entries are added.
+    pub fn load_user_dictionary(&mut self, text: &str) -> Result<usize, VoikkoError> {
+        let dictionary = UserDictionary::from_text(text)?;
+        let count = dictionary.len();
+        self.user_state.user_words.merge(dictionary);
+        Ok(count)
+    }
+
+    /// Add a word to the ignore list.
+    pub fn ignore_word(&mut self, word: &str) {
+        self.user_state.ignored_words.insert(word.to_string());
+    }
+
+    /// Remove a word from the ignore list.
+    /// Returns `true` if the word was present.
+    pub fn unignore_word(&mut self, word: &str) -> bool {
+        self.user_state.ignored_words.remove(word)
+    }
+
+    /// Record a correction accepted by the user. The replacement is offered
+    /// as the first suggestion for `word` from now on.
+    pub fn add_correction(&mut self, word: &str, replacement: &str) {
+        self.user_state
+            .corrections
+            .insert(word.to_string(), replacement.to_string());
+    }
+
+    /// Forget a recorded correction.
+    /// Returns `true` if a correction for `word` was present.
+    pub fn remove_correction(&mut self, word: &str) -> bool {
+        self.user_state.corrections.remove(word).is_some()
+    }
+
+    /// Return the current user state.
+    pub fn user_state(&self) -> &UserState {
+        &self.user_state
+    }
+
+    /// Serialize the user state (user dictionary, ignore list and accepted
+    /// corrections) into a text blob suitable for storing or syncing.
+    pub fn export_user_state(&self) -> String {
+        self.user_state.to_blob()
+    }
+
+    /// Replace the user state with one previously produced by
+    /// `export_user_state()`. On error the current state is left untouched.
+    pub fn import_user_state(&mut self, blob: &str) -> Result<(), VoikkoError> {
+        self.user_state = UserState::from_blob(blob)?;
+        Ok(())
+    }
+
+    /// Release resources held by this handle. After calling this,
+    /// the handle should not be used for any NLP operations.
+    ///
+    /// In Rust, this is a no-op since resources are released when the handle
+    /// is dropped. This method exists to match the C++ API pattern where
+    /// `voikkoTerminate` explicitly frees resources.
+    pub fn terminate(self) {
+        // Resources are released by Drop
+    }
+}
+
+/// Map the positions of grammar errors found in `plain` onto its original
+/// text.
+fn map_errors(plain: &PlainText, errors: &mut [GrammarError]) {
+    for error in errors {
+        (error.start_pos, error.error_len) = plain.original_range(error.start_pos, error.error_len);
+        for replacement in &mut error.replacements {
+            (replacement.start, replacement.len) =
+                plain.original_range(replacement.start, replacement.len);
+        }
+    }
+}
+
+/// Split text into sentences, passing `spell_check` to the sentence detector.
+fn split_sentences(
+    text: &str,
+    spell_check: tokenizer::SpellCheckFn<'_>,
+    include_closing: bool,
+) -> Vec<Sentence> {
+    let text_chars: Vec<char> = text.chars().collect();
+    let text_len = text_chars.len();
+    let mut result = Vec::new();
+    let mut pos = 0;
+    while pos < text_len {
+        let (sentence_type, sentence_len) = tokenizer::next_sentence_with_options(
+            &text_chars,
+            text_len,
+            pos,
+            spell_check,
+            include_closing,
+        );
+        if sentence_type == SentenceType::None {
+            // Include the final segment
+            if sentence_len > 0 {
+                result.push(Sentence::new(sentence_type, sentence_len));
+            }
+            break;
+        }
+        result.push(Sentence::new(sentence_type, sentence_len));
+        pos += sentence_len;
+    }
+    result
+}
+
+/// The handle is its own speller component; see [`VoikkoHandle::speller`].
+impl Speller for VoikkoHandle {
+    fn spell(&self, word: &[char], word_len: usize) -> SpellResult {
+        let word = &word[..word_len];
+        let text: String = word.iter().collect();
+        if self.user_state.accepts(&text) {
+            return SpellResult::Ok;
+        }
+        let adapter = AnalyzerToSpellerAdapter::new(&self.analyzer);
+        let tweaks =
+            FinnishSpellerTweaksWrapper::new(&adapter, &self.analyzer, self.finnish_spell_options);
+        match tweaks.spell(word, word_len) {
+            SpellResult::Failed if self.spell_service().is_user_word_form(&text) => SpellResult::Ok,
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{empty_handle, empty_vfst, vfst, word_vfst};
+    use voikko_core::analysis::ATTR_BASEFORM;
+
+    #[test]
+    fn handle_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<VoikkoHandle>();
+    }
+
+    #[test]
+    fn split_sentences_marks_abbreviation_boundary_as_possible() {
+        let text = "Osta esim. omenoita. Kiitos.";
+        let plain = split_sentences(text, None, true);
+        let abbreviations = |word: &[char]| word.iter().collect::<String>() == "esim.";
+        let with_check = split_sentences(text, Some(&abbreviations), true);
+        // A recognized abbreviation only makes the boundary after it uncertain.
+        assert_eq!(plain[0].sentence_type, SentenceType::Probable);
+        assert_eq!(with_check[0].sentence_type, SentenceType::Possible);
+        assert_eq!(plain[1].sentence_type, with_check[1].sentence_type);
+    }
+
+    #[test]
+    fn split_sentences_closing_punctuation_option() {
+        let text = "Tule (heti!) Hyvä.";
+        let lengths = |include_closing| -> Vec<usize> {
+            split_sentences(text, None, include_closing)
+                .iter()
+                .map(|s| s.sentence_len)
+                .collect()
+        };
+        assert_eq!(lengths(true), [13, 5]);
+        assert_eq!(lengths(false), [11, 7]);
+    }
 
     #[test]
     fn unsupported_language_returns_error() {
@@ -598,229 +2446,1663 @@ mod tests {
     }
 
     #[test]
-    fn invalid_mor_data_returns_error() {
-        let result = VoikkoHandle::from_bytes(&[0, 1, 2, 3], None, "fi");
-        assert!(result.is_err());
-    }
+    fn invalid_mor_data_returns_error() {
+        let result = VoikkoHandle::from_bytes(&[0, 1, 2, 3], None, "fi");
+        assert!(result.is_err());
+        let result = VoikkoHandle::from_bytes_lazy(&[0, 1, 2, 3], None, "fi");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn raw_fst_output_is_opt_in() {
+        let mut handle = VoikkoHandle::from_bytes(&word_vfst("koira"), None, "fi").unwrap();
+        assert_eq!(handle.analyzer_options(), AnalyzerOptions::default());
+        let analyses = handle.analyze("koira");
+        assert!(!analyses.is_empty());
+        assert!(analyses.iter().all(|a| a.raw_fst_output().is_none()));
+
+        handle.set_analyzer_options(AnalyzerOptions { include_raw: true });
+        let analyses = handle.analyze("koira");
+        assert!(!analyses.is_empty());
+        for analysis in &analyses {
+            assert_eq!(analysis.raw_fst_output(), Some("[Ln]koira"));
+        }
+        // Kept without full morphology, unlike FSTOUTPUT
+        let word: Vec<char> = "koira".chars().collect();
+        let analyses = handle.analyzer.analyze_full(&word, word.len(), false);
+        assert!(!analyses.is_empty());
+        for analysis in &analyses {
+            assert_eq!(analysis.raw_fst_output(), Some("[Ln]koira"));
+            assert_eq!(analysis.get(voikko_core::analysis::ATTR_FSTOUTPUT), None);
+        }
+
+        handle.set_analyzer_options(AnalyzerOptions::default());
+        assert!(
+            handle
+                .analyze("koira")
+                .iter()
+                .all(|a| a.raw_fst_output().is_none())
+        );
+    }
+
+    #[test]
+    fn try_methods_report_word_too_long() {
+        let mut handle = empty_handle();
+        handle.set_max_word_chars(5);
+        fn too_long<T>(result: Result<T, VoikkoError>) -> bool {
+            matches!(result, Err(VoikkoError::WordTooLong { length: 6, max: 5 }))
+        }
+        assert!(too_long(handle.try_spell("abcdef")));
+        assert!(too_long(handle.try_suggest("abcdef")));
+        assert!(too_long(handle.try_analyze("abcdef")));
+        assert!(too_long(handle.try_hyphenate("abcdef")));
+        assert!(!handle.try_spell("abcde").unwrap());
+        assert!(handle.try_analyze("abcde").unwrap().is_empty());
+
+        handle.load_hyphenation_exceptions("abc-def").unwrap();
+        assert_eq!(handle.try_hyphenate("abcdef").unwrap(), "   -  ");
+    }
+
+    #[test]
+    fn try_methods_report_failed_lazy_load() {
+        // Symbol table claims two symbols but the data ends.
+        let handle = VoikkoHandle::from_bytes_lazy(&vfst(&[2, 0]), None, "fi").unwrap();
+        fn load_failed<T>(result: Result<T, VoikkoError>) -> bool {
+            matches!(result, Err(VoikkoError::MorphologyLoad(_)))
+        }
+        assert!(load_failed(handle.try_spell("koira")));
+        assert!(load_failed(handle.try_suggest("koira")));
+        assert!(load_failed(handle.try_analyze("koira")));
+        assert!(load_failed(handle.try_hyphenate("koira")));
+        assert!(load_failed(handle.try_grammar_errors("Koira.")));
+        assert!(load_failed(handle.try_grammar_errors_from_text("Koira.")));
+        // The infallible methods treat every word as unknown.
+        assert!(!handle.spell("koira"));
+    }
+
+    #[test]
+    fn user_autocorrect_rules() {
+        let mut handle = empty_handle();
+        handle.add_autocorrect_rule("teh", "the").unwrap();
+        handle.add_autocorrect_rule(r"/(\d+)e/", "$1 €").unwrap();
+        assert!(matches!(
+            handle.add_autocorrect_rule("/(a/", "b"),
+            Err(VoikkoError::AutocorrectRule(_))
+        ));
+        assert_eq!(handle.autocorrect_rules().len(), 2);
+
+        let corrections = |handle: &VoikkoHandle| -> Vec<(usize, String)> {
+            handle
+                .grammar_errors_from_text("Teh hinta on 15e.")
+                .iter()
+                .filter(|e| e.error_code == voikko_core::grammar_error::GCERR_INVALID_SPELLING)
+                .map(|e| (e.start_pos, e.suggestions[0].clone()))
+                .collect()
+        };
+        assert_eq!(
+            corrections(&handle),
+            [(0, "The".to_string()), (13, "15 €".to_string())]
+        );
+        // Changing the rules invalidates cached results.
+        assert!(handle.remove_autocorrect_rule("teh"));
+        assert_eq!(corrections(&handle), [(13, "15 €".to_string())]);
+        handle.clear_autocorrect_rules();
+        assert!(corrections(&handle).is_empty());
+    }
+
+    #[test]
+    fn user_accepted_words_are_not_autocorrected() {
+        let mut handle = empty_handle();
+        handle.add_autocorrect_rule("teh", "the").unwrap();
+        let flagged = |handle: &VoikkoHandle| {
+            let paragraph = handle.analyze_paragraph("Teh koira.");
+            let autocorrected = paragraph
+                .sentences
+                .iter()
+                .any(|s| !s.autocorrections.is_empty());
+            let reported = handle
+                .grammar_errors_from_text("Teh koira.")
+                .iter()
+                .any(|e| e.error_code == voikko_core::grammar_error::GCERR_INVALID_SPELLING);
+            assert_eq!(autocorrected, reported);
+            reported
+        };
+        assert!(flagged(&handle));
+        assert!(!handle.spell("Teh"));
+
+        // Spelling and grammar checking both accept an ignored word
+        handle.ignore_word("teh");
+        assert!(handle.spell("Teh"));
+        assert!(!flagged(&handle));
+        handle.unignore_word("teh");
+        handle.add_user_word("teh", None);
+        assert!(handle.spell("teh"));
+        assert!(!flagged(&handle));
+    }
+
+    #[test]
+    fn grammar_cache_is_keyed_by_paragraph_and_options() {
+        let mut handle = empty_handle();
+        let first = "Koira  juoksi";
+        let second = "Kissa nukkui.";
+        let errors = handle.grammar_errors(first);
+        handle.grammar_errors(second);
+        assert_eq!(handle.grammar_errors(first), errors);
+        let stats = handle.grammar_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 2));
+
+        // Other options are another key; the old results stay cached
+        handle.set_accept_unfinished_paragraphs_in_gc(true);
+        assert_ne!(handle.grammar_errors(first), errors);
+        handle.set_accept_unfinished_paragraphs_in_gc(false);
+        assert_eq!(handle.grammar_errors(first), errors);
+        let stats = handle.grammar_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (2, 3, 3));
+
+        assert!(handle.invalidate_grammar_paragraph(first));
+        assert!(!handle.invalidate_grammar_paragraph(first));
+        assert_eq!(handle.grammar_cache_stats().len, 1);
+        handle.clear_grammar_cache();
+        assert_eq!(handle.grammar_cache_stats().len, 0);
+        handle.set_grammar_cache_size(0);
+        handle.grammar_errors(second);
+        assert_eq!(handle.grammar_cache_stats().len, 0);
+    }
+
+    #[test]
+    fn spell_service_classifies_like_spell() {
+        let mut handle = empty_handle();
+        handle.set_max_word_chars(5);
+        handle.add_user_word("voikko", None);
+        let service = handle.spell_service();
+        assert_eq!(service.classify("abc"), TokenSpelling::Misspelled);
+        assert_eq!(service.classify("abcdef"), TokenSpelling::TooLong);
+        assert!(service.classify("abcdef").is_misspelled());
+        // The user dictionary is consulted after the length limit
+        assert_eq!(service.classify("voikko"), TokenSpelling::TooLong);
+        let words: Vec<String> = handle
+            .misspelled_tokens("abc, 12 voikko")
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(words, ["abc", "12", "voikko"]);
+        handle.set_ignore_numbers(true);
+        assert!(handle.spell("12"));
+        assert_eq!(handle.misspelled_tokens("abc, 12").len(), 1);
+    }
+
+    #[test]
+    fn dictionary_extension_rejects_bad_removals() {
+        let mut handle = empty_handle();
+        let result = handle.add_dictionary_extension(None, "koira\tnimisana\tx");
+        assert!(matches!(result, Err(VoikkoError::DictionaryRemovals(_))));
+        let result = handle.add_dictionary_extension(Some(&[0, 1, 2, 3]), "");
+        assert!(matches!(result, Err(VoikkoError::MorphologyLoad(_))));
+    }
+
+    #[test]
+    fn reload_dictionary_keeps_dictionary_on_error() {
+        let handle = VoikkoHandle::from_bytes_lazy(&vfst(&[2, 0]), None, "fi").unwrap();
+        assert!(handle.warm_up().is_err());
+        let result = handle.reload_dictionary(&[0, 1, 2, 3], None);
+        assert!(matches!(result, Err(VoikkoError::MorphologyLoad(_))));
+        assert_eq!(handle.load_state(), LoadState::Failed);
+
+        let result = handle.reload_dictionary(&empty_vfst(), Some(&[0, 1, 2, 3]));
+        assert!(matches!(result, Err(VoikkoError::AutocorrectLoad(_))));
+        assert_eq!(handle.load_state(), LoadState::Failed);
+
+        handle.reload_dictionary(&empty_vfst(), None).unwrap();
+        assert_eq!(handle.load_state(), LoadState::Ready);
+    }
+
+    #[test]
+    fn reload_during_lookups_leaves_no_stale_results() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let koira = word_vfst("koira");
+        let kissa = word_vfst("kissa");
+        let handle = VoikkoHandle::from_bytes(&koira, None, "fi").unwrap();
+        assert!(handle.spell("koira"));
+        assert!(!handle.spell("kissa"));
+
+        let reloaded = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    loop {
+                        let done = reloaded.load(Ordering::SeqCst);
+                        let koira_known = handle.spell("koira");
+                        let koira_analyzed = !handle.analyze("koira").is_empty();
+                        if done {
+                            // Lookups that ran on the old dictionary left
+                            // nothing behind in the caches
+                            assert!(!koira_known && !koira_analyzed);
+                            assert!(handle.spell("kissa"));
+                            break;
+                        }
+                    }
+                });
+            }
+            scope.spawn(|| {
+                for i in 0..200 {
+                    let data = if i % 2 == 0 { &kissa } else { &koira };
+                    handle.reload_dictionary(data, None).unwrap();
+                }
+                handle.reload_dictionary(&kissa, None).unwrap();
+                reloaded.store(true, Ordering::SeqCst);
+            });
+        });
+    }
+
+    #[test]
+    fn message_catalog_is_shared_until_loaded() {
+        use voikko_core::catalog::MSG_DID_YOU_MEAN;
+
+        let shared = Arc::new(MessageCatalog::default());
+        let mut handle = empty_handle();
+        handle.set_message_catalog(Arc::clone(&shared));
+        assert_eq!(
+            handle
+                .load_message_catalog("[sv]\ndid_you_mean = Menade du '{0}'?\n")
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            handle
+                .message_catalog()
+                .message("sv", MSG_DID_YOU_MEAN, &["hund"])
+                .as_deref(),
+            Some("Menade du 'hund'?")
+        );
+        assert_eq!(
+            shared.message("sv", MSG_DID_YOU_MEAN, &["hund"]).as_deref(),
+            Some("Did you mean 'hund'?")
+        );
+        assert!(matches!(
+            handle.load_message_catalog("did_you_mean = x"),
+            Err(VoikkoError::MessageCatalog(_))
+        ));
+    }
+
+    #[test]
+    fn try_suggest_reports_interruption() {
+        use crate::suggestion::CancelToken;
+
+        let handle = empty_handle();
+        let token = CancelToken::new();
+        let budget = SuggestionBudget {
+            cancel_token: Some(token.clone()),
+            ..SuggestionBudget::default()
+        };
+        assert!(handle.try_suggest_with_budget("koirra", &budget).is_ok());
+        token.cancel();
+        assert!(matches!(
+            handle.try_suggest_with_budget("koirra", &budget),
+            Err(VoikkoError::Cancelled)
+        ));
+        assert!(handle.suggest_with_budget("koirra", &budget).is_empty());
+
+        let expired = SuggestionBudget {
+            max_duration: Some(Duration::ZERO),
+            ..SuggestionBudget::default()
+        };
+        assert!(matches!(
+            handle.try_suggest_with_budget("koirra", &expired),
+            Err(VoikkoError::TimedOut)
+        ));
+    }
+
+    // Integration tests with real dictionary data are guarded by the
+    // VOIKKO_DICT_PATH environment variable. They are not part of the
+    // default test suite.
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_spell_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert!(handle.spell("koira"));
+        assert!(handle.spell("Helsinki"));
+        assert!(!handle.spell("xyzzyplugh"));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_compound_info_of_derived_words() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let info = handle.compound_info("kirjoittajalle").unwrap();
+        assert_eq!(info.parts, ["kirjoittajalle"]);
+        assert_eq!(info.baseforms, ["kirjoittaja"]);
+
+        let info = handle.compound_info("epäjärjestelmällisyys").unwrap();
+        assert_eq!(info.parts, ["epä", "järjestelmällisyys"]);
+        assert_eq!(info.baseforms, ["epä", "järjestelmällisyys"]);
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_spelling_surfaces_agree() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let text = "Koira juoksi 3 km:n päähän Helsinkiin, mutta kisssa ei. \
+                    ESIM. ABC123 ja NATO-maat; voikkolabs sekä koirra \
+                    ja Tekoälykäs e-kirja.";
+
+        // Misspelled words as (character offset, word) by each surface
+        let check = |handle: &VoikkoHandle| {
+            let by_spell: Vec<(usize, String)> = handle
+                .tokens(text)
+                .into_iter()
+                .filter(|t| t.token_type == TokenType::Word && !handle.spell(&t.text))
+                .map(|t| (t.pos, t.text))
+                .collect();
+            let by_text: Vec<(usize, String)> = handle
+                .misspelled_tokens(text)
+                .into_iter()
+                .map(|t| (t.pos, t.text))
+                .collect();
+            let by_grammar: Vec<(usize, String)> = handle
+                .analyze_paragraph(text)
+                .tokens()
+                .filter(|t| t.is_misspelled)
+                .map(|t| (t.start, t.text.clone()))
+                .collect();
+            let by_document: Vec<(usize, String)> = handle
+                .check_document(text.as_bytes())
+                .map(|issue| issue.map(|i| (i.char_offset, i.word)))
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(by_text, by_spell);
+            assert_eq!(by_grammar, by_spell);
+            assert_eq!(by_document, by_spell);
+            by_spell.into_iter().map(|(_, w)| w).collect::<Vec<_>>()
+        };
+
+        let default = check(&handle);
+        assert!(default.contains(&"kisssa".to_string()));
+        assert!(default.contains(&"voikkolabs".to_string()));
+        assert!(default.contains(&"koirra".to_string()));
+
+        handle.add_user_word("voikkolabs", None);
+        handle.ignore_word("koirra");
+        let personalized = check(&handle);
+        assert!(!personalized.contains(&"koirra".to_string()));
+        assert!(!personalized.contains(&"voikkolabs".to_string()));
+
+        handle.set_ignore_numbers(true);
+        handle.set_ignore_uppercase(true);
+        handle.set_accept_first_uppercase(false);
+        let strict_case = check(&handle);
+        assert!(!strict_case.contains(&"ABC123".to_string()));
+        assert!(strict_case.contains(&"Tekoälykäs".to_string()));
+
+        handle.set_ignore_dot(true);
+        handle.set_invisible_chars(InvisibleChars::Strip);
+        check(&handle);
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_analyze_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let analyses = handle.analyze("koira");
+        assert!(!analyses.is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn integration_analyze_any_case_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let baseforms = |word: &str| -> (Vec<String>, CaseVariant) {
+            let (analyses, variant) = handle.analyze_any_case(word);
+            let baseforms = analyses
+                .iter()
+                .filter_map(|a| a.get("BASEFORM").map(str::to_string))
+                .collect();
+            (baseforms, variant)
+        };
+        assert_eq!(
+            baseforms("koira"),
+            (vec!["koira".into()], CaseVariant::AsIs)
+        );
+        assert_eq!(
+            baseforms("KOIRA"),
+            (vec!["koira".into()], CaseVariant::Lowercased)
+        );
+        assert_eq!(
+            baseforms("HELSINKI"),
+            (vec!["Helsinki".into()], CaseVariant::Lowercased)
+        );
+        assert_eq!(baseforms("qwzx"), (vec![], CaseVariant::AsIs));
+        // The hyphenator relies on the analyzer for case, too
+        assert_eq!(
+            handle.hyphenate("KOIRANRUOKA"),
+            handle.hyphenate("koiranruoka")
+        );
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_analyze_attribute_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert_eq!(handle.analyze_attribute("koiran", "BASEFORM"), ["koira"]);
+        assert_eq!(handle.analyze_attribute("koiran", "CLASS"), ["nimisana"]);
+        // Each value once, in analysis order
+        let expected: Vec<String> = handle
+            .analyze("alusta")
+            .iter()
+            .filter_map(|a| a.get("BASEFORM").map(str::to_string))
+            .fold(Vec::new(), |mut values, v| {
+                if !values.contains(&v) {
+                    values.push(v);
+                }
+                values
+            });
+        assert!(expected.len() > 1);
+        assert_eq!(handle.analyze_attribute("alusta", "BASEFORM"), expected);
+        assert!(handle.analyze_attribute("koiran", "NONEXISTENT").is_empty());
+        assert!(handle.analyze_attribute("xyzzyplugh", "CLASS").is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_analyze_segmented_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let parts = |handle: &VoikkoHandle, word: &str| -> Vec<(String, Option<String>)> {
+            let analyses = handle.analyze_segmented(word);
+            let chars: Vec<char> = word.chars().collect();
+            analyses
+                .iter()
+                .find(|a| a.get("CLASS") == Some("nimisana"))
+                .expect("common noun analysis")
+                .segments()
+                .expect("segments recorded")
+                .iter()
+                .map(|s| (chars[s.range.clone()].iter().collect(), s.baseform.clone()))
+                .collect()
+        };
+        let part =
+            |surface: &str, baseform: &str| (surface.to_string(), Some(baseform.to_string()));
+
+        assert!(handle.analyze("koirankoppi")[0].segments().is_none());
+        assert_eq!(
+            parts(&handle, "koirankoppi"),
+            [part("koiran", "koira"), part("koppi", "koppi")]
+        );
+        assert_eq!(
+            parts(&handle, "Maa-autossa"),
+            [part("Maa", "maa"), part("autossa", "auto")]
+        );
+        assert_eq!(
+            parts(&handle, "juoksija"),
+            [part("juoksi", "juosta"), part("ja", "ja")]
+        );
+
+        // Ranges refer to the word as given, before input mapping
+        handle.set_input_mapping(InputMapping::ALL);
+        let analyses = handle.analyze_segmented("２０-vuotias");
+        let ranges: Vec<_> = analyses[0]
+            .segments()
+            .expect("segments recorded")
+            .iter()
+            .map(|s| s.range.clone())
+            .collect();
+        assert_eq!(ranges, [0..2, 3..10]);
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_components_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        handle.add_user_word("voikkolainen", None);
+
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        let speller = handle.speller();
+        assert_eq!(speller.spell(&chars("koira"), 5), SpellResult::Ok);
+        assert_eq!(
+            speller.spell(&chars("helsinki"), 8),
+            SpellResult::CapitalizeFirst
+        );
+        assert_eq!(speller.spell(&chars("xyzzyplugh"), 10), SpellResult::Failed);
+        assert_eq!(speller.spell(&chars("voikkolainen"), 12), SpellResult::Ok);
+
+        let analyses = handle.analyzer().analyze(&chars("koiran"), 6);
+        assert!(!analyses.is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_generate_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let mut target = Analysis::new();
+        target.set("SIJAMUOTO", "omanto");
+        target.set("NUMBER", "plural");
+        let forms = handle.generate("koira", &target);
+        assert!(forms.contains(&"koirien".to_string()), "{forms:?}");
+        assert!(
+            forms
+                .iter()
+                .all(|f| f.starts_with("koir") && !f.ends_with("han"))
+        );
+
+        let mut target = Analysis::new();
+        target.set("SIJAMUOTO", "sisaolento");
+        target.set("NUMBER", "singular");
+        assert_eq!(handle.generate("Helsinki", &target), ["Helsingissä"]);
+        assert!(handle.generate("xyzzyplugh", &target).is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_complete_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let words = handle.complete("koira", 10);
+        assert_eq!(words.len(), 10);
+        assert!(words.is_sorted(), "{words:?}");
+        assert!(words.contains(&"koira".to_string()), "{words:?}");
+        assert!(
+            words
+                .iter()
+                .all(|w| w.starts_with("koira") && handle.spell(w)),
+            "{words:?}"
+        );
+
+        let words = handle.complete("helsi", 5);
+        assert!(words.contains(&"Helsinki".to_string()), "{words:?}");
+        let words = handle.complete("Koi", 5);
+        assert!(words.iter().all(|w| w.starts_with("Koi")), "{words:?}");
+        let words = handle.complete("KOI", 5);
+        assert!(
+            words
+                .iter()
+                .all(|w| w.starts_with("KOI") && w.to_uppercase() == *w)
+        );
+        assert!(handle.complete("koira", 0).is_empty());
+        assert!(handle.complete("xqzw", 5).is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_round_trip_with_real_dict() {
+        use crate::morphology::RoundTripStatus;
+
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        for word in ["koirien", "Helsingissä", "juoksivat", "kauneimmat"] {
+            let results = handle.round_trip(word);
+            assert!(!results.is_empty(), "{word}");
+            for result in &results {
+                assert_eq!(result.status, RoundTripStatus::Match, "{word}: {result:?}");
+            }
+        }
+        assert!(
+            handle
+                .round_trip("linja-autoissa")
+                .iter()
+                .all(|r| r.status == RoundTripStatus::Unsupported)
+        );
+        assert!(
+            handle
+                .round_trip("koirienhan")
+                .iter()
+                .all(|r| r.status == RoundTripStatus::Unsupported)
+        );
+        assert!(handle.round_trip("xyzzyplugh").is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_check_word_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let check = handle.check_word("koira");
+        assert!(check.correct);
+        assert_eq!(check.suggestions, None);
+
+        let check = handle.check_word("koirra");
+        assert!(!check.correct);
+        assert_eq!(check.suggestions, Some(handle.suggest("koirra")));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_suggest_with_budget_with_real_dict() {
+        use crate::suggestion::CancelToken;
+        use std::time::{Duration, Instant};
+
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let unlimited = handle.suggest_with_budget("koirra", &SuggestionBudget::default());
+        assert_eq!(unlimited, handle.suggest("koirra"));
+
+        let token = CancelToken::new();
+        token.cancel();
+        let cancelled = SuggestionBudget {
+            cancel_token: Some(token),
+            ..SuggestionBudget::default()
+        };
+        assert!(handle.suggest_with_budget("koirra", &cancelled).is_empty());
+
+        let limited = SuggestionBudget {
+            max_duration: Some(Duration::from_millis(50)),
+            ..SuggestionBudget::default()
+        };
+        let start = Instant::now();
+        handle.suggest_with_budget("xyzzyplughxyzzyplugh", &limited);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_user_dictionary_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert!(!handle.spell("vlogi"));
+        handle.add_user_word("vlogi", Some("paperi"));
+        assert!(handle.spell("vlogi"));
+        assert!(handle.spell("Vlogeissa"));
+        assert!(handle.spell("vloginsa"));
+        assert!(!handle.spell("vlogissaa"));
+        assert!(handle.suggest("vlogu").contains(&"vlogi".to_string()));
+
+        let count = handle
+            .load_user_dictionary("# extra\nkorvapuusti\n")
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            handle.export_user_dictionary(),
+            "korvapuusti\nvlogi\tpaperi\n"
+        );
+        assert!(handle.load_user_dictionary("a\tb\tc\n").is_err());
+        assert!(handle.remove_user_word("vlogi"));
+        assert!(!handle.spell("vlogeissa"));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_input_mapping_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert!(!handle.spell("２０-vuotias"));
+        handle.set_input_mapping(InputMapping::ALL);
+        assert!(handle.spell("２０-vuotias"));
+        let analyses = handle.analyze("２０-vuotias");
+        assert!(!analyses.is_empty());
+        for analysis in &analyses {
+            let structure = analysis.get(ATTR_STRUCTURE).unwrap();
+            assert_eq!(structure.chars().filter(|&c| c != '=').count(), 10);
+        }
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_hyphenate_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let pattern = handle.hyphenate("koira");
+        assert_eq!(pattern.len(), 5); // same char count as "koira"
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_hyphenation_exceptions_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert_eq!(
+            handle.insert_hyphens("koiranruoka", "-", true),
+            "koi-ran-ruo-ka"
+        );
+        handle.add_hyphenation_exception("koiranruo-ka").unwrap();
+        assert_eq!(
+            handle.insert_hyphens("Koiranruoka", "-", true),
+            "Koiranruo-ka"
+        );
+        // Inflected forms are not covered by the exception
+        assert_eq!(
+            handle.insert_hyphens("koiranruokaa", "-", true),
+            "koi-ran-ruo-kaa"
+        );
+
+        let count = handle
+            .load_hyphenation_exceptions("# house style\nVoik-ko\nCoca=Co-la\n")
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(handle.insert_hyphens("Coca-Cola", "-", true), "Coca-Co-la");
+        assert!(handle.load_hyphenation_exceptions("-koira\n").is_err());
+        assert_eq!(handle.hyphenation_exceptions().len(), 3);
+
+        assert!(handle.remove_hyphenation_exception("KOIRANRUOKA"));
+        assert_eq!(
+            handle.insert_hyphens("koiranruoka", "-", true),
+            "koi-ran-ruo-ka"
+        );
+        handle.clear_hyphenation_exceptions();
+        assert!(handle.hyphenation_exceptions().is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_language_rules_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert_eq!(handle.insert_hyphens("teatteri", "-", true), "te-at-te-ri");
+
+        let mut rules = LanguageRules::default();
+        rules.split_vowels.retain(|&pair| pair != ['e', 'a']);
+        rules.back_vowels.retain(|&v| v != 'a');
+        rules.front_vowels.retain(|&v| v != '\u{00E4}');
+        handle.set_match_suggestion_case(false);
+        handle.set_language_rules(rules);
+        assert_eq!(handle.insert_hyphens("teatteri", "-", true), "teat-te-ri");
+        assert!(!handle.language_rules().back_vowels.contains(&'a'));
+        assert!(!handle.typing_strategy.match_word_case());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_max_word_length_with_real_dict() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        assert_eq!(handle.max_word_chars(), MAX_WORD_CHARS);
+
+        let long_word = "koira".repeat(MAX_WORD_CHARS / 5 + 1);
+        assert_eq!(handle.spell_status(&long_word), SpellStatus::TooLong);
+        assert!(!handle.spell(&long_word));
+        assert!(handle.analyze(&long_word).is_empty());
+        assert!(handle.suggest(&long_word).is_empty());
+        assert_eq!(handle.hyphenate(&long_word), " ".repeat(long_word.len()));
+        let check = handle.check_word(&long_word);
+        assert!(check.too_long && !check.correct);
+        assert_eq!(check.suggestions, Some(Vec::new()));
+
+        handle.set_max_word_chars(10);
+        assert_eq!(handle.spell_status("koira"), SpellStatus::Correct);
+        assert_eq!(handle.spell_status("koirra"), SpellStatus::Misspelled);
+        assert_eq!(handle.spell_status("koiranruoka"), SpellStatus::TooLong);
+        assert!(!handle.check_word("koira").too_long);
+        assert_eq!(
+            handle.explain_hyphenation("koiranruoka").reasons,
+            vec![HyphenReason::WordTooLong; 11]
+        );
+        handle.set_max_word_chars(usize::MAX);
+        assert_eq!(handle.max_word_chars(), MAX_WORD_CHARS);
+    }
+
+    #[test]
+    fn tokenize_simple_text() {
+        // Tokenizer doesn't need a dictionary -- we can test with any handle
+        // but we can't construct one without valid dictionary data.
+        // Instead, test the tokenizer directly through the module.
+        let tokens = tokenizer::tokenize("Koira juoksi.");
+
+        assert_eq!(tokens.len(), 4); // "Koira", " ", "juoksi", "."
+        assert_eq!(tokens[0].token_type, TokenType::Word);
+        assert_eq!(tokens[0].text, "Koira");
+        assert_eq!(tokens[3].token_type, TokenType::Punctuation);
+        assert_eq!(tokens[3].text, ".");
+    }
+
+    // =========================================================================
+    // attribute_values tests
+    // =========================================================================
+
+    #[test]
+    fn attribute_values_class() {
+        let vals = VoikkoHandle::attribute_values("CLASS").unwrap();
+        assert!(vals.contains(&"nimisana"));
+        assert!(vals.contains(&"teonsana"));
+        assert!(vals.contains(&"nimi"));
+        assert_eq!(vals.len(), 17);
+    }
+
+    #[test]
+    fn attribute_values_number() {
+        let vals = VoikkoHandle::attribute_values("NUMBER").unwrap();
+        assert_eq!(vals, &["singular", "plural"]);
+    }
+
+    #[test]
+    fn attribute_values_person() {
+        let vals = VoikkoHandle::attribute_values("PERSON").unwrap();
+        assert_eq!(vals, &["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn attribute_values_mood() {
+        let vals = VoikkoHandle::attribute_values("MOOD").unwrap();
+        assert_eq!(vals.len(), 9);
+        assert!(vals.contains(&"indicative"));
+        assert!(vals.contains(&"MAINEN-infinitive"));
+    }
+
+    #[test]
+    fn attribute_values_sijamuoto() {
+        let vals = VoikkoHandle::attribute_values("SIJAMUOTO").unwrap();
+        assert_eq!(vals.len(), 16);
+        assert!(vals.contains(&"nimento"));
+        assert!(vals.contains(&"kerrontosti"));
+    }
+
+    #[test]
+    fn attribute_values_focus() {
+        let vals = VoikkoHandle::attribute_values("FOCUS").unwrap();
+        assert_eq!(vals.len(), 6);
+        assert!(vals.contains(&"läs"));
+    }
+
+    #[test]
+    fn attribute_values_kysymysliite() {
+        let vals = VoikkoHandle::attribute_values("KYSYMYSLIITE").unwrap();
+        assert_eq!(vals, &["true"]);
+    }
+
+    #[test]
+    fn attribute_values_unknown_returns_none() {
+        assert!(VoikkoHandle::attribute_values("NONEXISTENT").is_none());
+        assert!(VoikkoHandle::attribute_values("").is_none());
+    }
+
+    #[test]
+    fn attribute_values_all_known_names() {
+        let names = [
+            "CLASS",
+            "NUMBER",
+            "PERSON",
+            "MOOD",
+            "TENSE",
+            "COMPARISON",
+            "NEGATIVE",
+            "PARTICIPLE",
+            "POSSESSIVE",
+            "SIJAMUOTO",
+            "FOCUS",
+            "KYSYMYSLIITE",
+        ];
+        for name in &names {
+            assert!(
+                VoikkoHandle::attribute_values(name).is_some(),
+                "expected Some for attribute {name}"
+            );
+        }
+    }
+
+    // =========================================================================
+    // get_version tests
+    // =========================================================================
+
+    #[test]
+    fn get_version_returns_cargo_version() {
+        let version = VoikkoHandle::get_version();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        // Should be a valid semver-like string
+        assert!(!version.is_empty());
+        assert!(version.contains('.'));
+    }
+
+    // =========================================================================
+    // insert_hyphens tests (unit tests without dictionary)
+    // =========================================================================
+
+    // NOTE: insert_hyphens depends on hyphenate() which requires a real
+    // dictionary, so full integration tests are marked #[ignore].
+    // We test the core logic through integration tests below.
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_insert_hyphens_basic() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        // "koira" should hyphenate to "koi-ra" with default separator
+        let result = handle.insert_hyphens("koira", "-", true);
+        // The result should contain the original letters
+        assert!(result.contains("koi"));
+        assert!(result.contains("ra"));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_insert_hyphens_custom_separator() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let result_dash = handle.insert_hyphens("koira", "-", true);
+        let result_dot = handle.insert_hyphens("koira", ".", true);
+        // If there are hyphenation points, the separator should differ
+        let dash_count = result_dash.matches('-').count();
+        let dot_count = result_dot.matches('.').count();
+        assert_eq!(dash_count, dot_count);
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_insert_hyphens_empty_separator() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        // With empty separator, result should be the original word
+        // (hyphenation points are inserted as empty strings)
+        let result = handle.insert_hyphens("koira", "", true);
+        // All original characters should still be present
+        assert!(result.len() <= "koira".len());
+    }
+
+    // =========================================================================
+    // set_speller_cache_size tests
+    // =========================================================================
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_set_speller_cache_size() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        // Spell a word, change cache size, spell again — should still work
+        assert!(handle.spell("koira"));
+        handle.set_speller_cache_size(2);
+        assert!(handle.spell("koira"));
+        handle.set_speller_cache_size(0);
+        assert!(handle.spell("koira"));
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_cache_stats_survive_resize() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        assert!(handle.spell("koira"));
+        assert!(handle.spell("koira"));
+        let stats = handle.cache_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.len, 1);
+
+        // Resizing keeps the cached word
+        handle.set_speller_cache_size(2);
+        assert!(handle.spell("koira"));
+        let stats = handle.cache_stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.capacity, 1008 << 2);
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_analysis_cache_is_shared() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+
+        let analyses = handle.analyze("koirankoppi");
+        assert!(!analyses.is_empty());
+        // Hyphenation and a repeat analysis reuse the cached analyses
+        let misses = handle.analysis_cache_stats().misses;
+        handle.hyphenate("koirankoppi");
+        assert_eq!(handle.analyze("koirankoppi"), analyses);
+        let stats = handle.analysis_cache_stats();
+        assert_eq!(stats.misses, misses);
+        assert!(stats.hits >= 2);
+
+        // Partial analyses are served from the cached full ones
+        handle.set_analysis_cache_size(0);
+        let uncached = handle
+            .analyzer
+            .analyze_full(&['k', 'o', 'i', 'r', 'a'], 5, false);
+        handle.set_analysis_cache_size(64);
+        handle.analyze("koira");
+        let cached = handle
+            .analyzer
+            .analyze_full(&['k', 'o', 'i', 'r', 'a'], 5, false);
+        assert_eq!(cached, uncached);
+        assert_eq!(handle.analysis_cache_stats().len, 1);
+
+        let lite =
+            VoikkoHandle::from_bytes_with_profile(&mor_data, None, "fi", HandleProfile::Lite)
+                .expect("failed to create handle");
+        lite.analyze("koira");
+        assert_eq!(lite.analysis_cache_stats(), CacheStats::default());
+    }
+
+    // =========================================================================
+    // grammar_errors_from_text tests
+    // =========================================================================
+
+    #[test]
+    #[ignore = "requires mor.vfst and autocorr.vfst dictionary files"]
+    fn integration_grammar_errors_from_text_multiline() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let autocorr_data = std::fs::read(
+            std::env::var("VOIKKO_AUTOCORR_VFST")
+                .unwrap_or_else(|_| "../../test-data/autocorr.vfst".into()),
+        )
+        .ok();
+        let handle = VoikkoHandle::from_bytes(&mor_data, autocorr_data.as_deref(), "fi")
+            .expect("failed to create handle");
+
+        // Test with multi-line text: positions should be adjusted
+        let text = "Ensimmäinen rivi.\nToinen rivi.";
+        let errors = handle.grammar_errors_from_text(text);
+        // Verify that any errors from the second paragraph have startPos >= 18
+        // (length of "Ensimmäinen rivi.\n")
+        for error in &errors {
+            // Errors can only be in one paragraph or the other
+            assert!(error.start_pos < text.chars().count());
+        }
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_analyze_paragraph() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        handle
+            .add_autocorrect_rule("koirra", "koira")
+            .expect("valid rule");
+
+        let paragraph = handle.analyze_paragraph("Koirra juoksee. Kissa nukkuu.");
+        assert_eq!(paragraph.sentences.len(), 2);
+        assert_eq!(paragraph.sentences[1].start, 16);
+        let juoksee = paragraph.tokens().find(|t| t.text == "juoksee").unwrap();
+        assert_eq!(juoksee.start, 7);
+        assert!(juoksee.is_main_verb);
+        assert!(
+            juoksee
+                .analyses
+                .iter()
+                .any(|a| a.get(ATTR_BASEFORM) == Some("juosta"))
+        );
+        let kissa = paragraph.tokens().find(|t| t.text == "Kissa").unwrap();
+        assert!(kissa.is_valid_word && kissa.first_letter_lcase);
+        let autocorrections = &paragraph.sentences[0].autocorrections;
+        assert_eq!(autocorrections.len(), 1);
+        assert_eq!(autocorrections[0].suggestions, ["Koira"]);
+        assert!(paragraph.sentences[1].autocorrections.is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_discourse_checks() {
+        use voikko_core::grammar_error::{
+            GCERR_REPEATED_SENTENCE_START, GCERR_UNBALANCED_QUOTATION,
+        };
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let text = "Hän tuli kotiin. Hän istui alas. Hän sanoi: \u{201d}Tule. Nyt heti.";
+        let found = |handle: &VoikkoHandle| -> Vec<(i32, usize)> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code > 24)
+                .map(|e| (e.error_code, e.start_pos))
+                .collect()
+        };
+        assert!(found(&handle).is_empty());
+
+        handle.set_check_discourse(true);
+        assert_eq!(
+            found(&handle),
+            [
+                (GCERR_REPEATED_SENTENCE_START, 33),
+                (GCERR_UNBALANCED_QUOTATION, 44),
+            ]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_paired_conjunctions() {
+        use voikko_core::grammar_error::GCERR_INCOMPLETE_PAIRED_CONJUNCTION;
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let found = |handle: &VoikkoHandle, text: &str| -> Vec<usize> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code == GCERR_INCOMPLETE_PAIRED_CONJUNCTION)
+                .map(|e| e.start_pos)
+                .collect()
+        };
+        let text = "Hän on sekä viisas kaunis. Lähdemme joko tänään huomenna.";
+        assert!(found(&handle, text).is_empty());
+
+        handle.set_check_paired_conjunctions(true);
+        assert_eq!(found(&handle, text), [7, 36]);
+        assert!(found(&handle, "Hän on sekä viisas että kaunis.").is_empty());
+        assert!(found(&handle, "Ostin leipää sekä maitoa.").is_empty());
+        assert!(found(&handle, "Joko hän tuli?").is_empty());
+        assert!(found(&handle, "Lähdemme joko tänään tai huomenna.").is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_real_word_errors() {
+        use voikko_core::grammar_error::{GCERR_WRONG_CASE, GrammarErrorCategory};
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let found = |handle: &VoikkoHandle, text: &str| -> Vec<(usize, usize, Vec<String>)> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code == GCERR_WRONG_CASE)
+                .inspect(|e| assert_eq!(e.category, GrammarErrorCategory::RealWord))
+                .map(|e| (e.start_pos, e.error_len, e.suggestions))
+                .collect()
+        };
+        let text = "Hän käveli kohti kaupunki. Minä täytyy lähteä.";
+        assert!(found(&handle, text).is_empty());
+
+        handle.set_check_real_word_errors(true);
+        assert_eq!(
+            found(&handle, text),
+            [
+                (17, 8, vec!["kaupunkia".to_string()]),
+                (27, 4, vec!["Minun".to_string()]),
+            ]
+        );
+        assert_eq!(
+            found(&handle, "He jäivät vailla iso raha."),
+            [(17, 8, vec!["isoa rahaa".to_string()])]
+        );
+        assert!(found(&handle, "Hän käveli kohti kaupunkia.").is_empty());
+        assert!(found(&handle, "Minun täytyy lähteä.").is_empty());
+        assert!(found(&handle, "Hän käveli taloa kohti hitaasti.").is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_subject_verb_agreement() {
+        use voikko_core::grammar_error::{GCERR_SUBJECT_VERB_AGREEMENT, GrammarErrorSeverity};
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let found = |handle: &VoikkoHandle, text: &str| -> Vec<(usize, usize, Vec<String>)> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code == GCERR_SUBJECT_VERB_AGREEMENT)
+                .inspect(|e| assert_eq!(e.severity, GrammarErrorSeverity::Warning))
+                .map(|e| (e.start_pos, e.error_len, e.suggestions))
+                .collect()
+        };
+        let text = "Koirat juoksee pihalla. Hän olen kotona. Ne ei tiedä.";
+        assert!(found(&handle, text).is_empty());
 
-    // Integration tests with real dictionary data are guarded by the
-    // VOIKKO_DICT_PATH environment variable. They are not part of the
-    // default test suite.
+        handle.set_check_subject_verb_agreement(true);
+        assert_eq!(
+            found(&handle, text),
+            [
+                (7, 7, vec!["juoksevat".to_string()]),
+                (28, 4, vec!["on".to_string()]),
+                (44, 2, vec!["eivät".to_string()]),
+            ]
+        );
+        assert_eq!(
+            found(&handle, "Kissa nukkuivat sohvalla."),
+            [(6, 9, vec!["nukkui".to_string()])]
+        );
+        for correct in [
+            "Koirat juoksevat pihalla.",
+            "Talot on rakennettu 1950-luvulla.",
+            "Kirjat ostin eilen.",
+            "Yhdysvallat on suuri maa.",
+            "Naapurin koira haukkuu.",
+        ] {
+            assert!(found(&handle, correct).is_empty(), "{correct}");
+        }
+    }
 
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_spell_with_real_dict() {
+    fn integration_prefer_proper_nouns() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
-        let handle =
+        let mut handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        assert_eq!(handle.suggest("Ville")[..2], ["Villet", "Villa"]);
 
-        assert!(handle.spell("koira"));
-        assert!(handle.spell("Helsinki"));
-        assert!(!handle.spell("xyzzyplugh"));
+        handle.set_prefer_proper_nouns(true);
+        let suggestions = handle.suggest("Ville");
+        assert_eq!(suggestions[..3], ["Villet", "Villen", "Hille"]);
+        // Common words are still offered, after the names
+        assert_eq!(suggestions.last().map(String::as_str), Some("Villa"));
+        // Only a capitalized word is affected
+        let lowercase = handle.suggest("ruija");
+        handle.set_prefer_proper_nouns(false);
+        assert_eq!(handle.suggest("ruija"), lowercase);
     }
 
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_analyze_with_real_dict() {
+    fn integration_suggestions_match_the_case_of_the_word() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
-        let handle =
+        let mut handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        assert_eq!(handle.suggest("koirra")[0], "koira");
+        assert_eq!(handle.suggest("Koirra")[0], "Koira");
+        assert_eq!(handle.suggest("KOIRRA")[0], "KOIRA");
+        // Capitals required by the dictionary are kept
+        assert_eq!(handle.suggest("abc-kirjja")[0], "ABC-kirja");
+        assert_eq!(handle.suggest("Abc-kirjja")[0], "ABC-kirja");
 
-        let analyses = handle.analyze("koira");
-        assert!(!analyses.is_empty());
+        handle.set_match_suggestion_case(false);
+        assert_eq!(handle.suggest("Koirra")[0], "koira");
+        assert_eq!(handle.suggest("KOIRRA")[0], "koira");
     }
 
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_hyphenate_with_real_dict() {
+    fn integration_fst_output_keeps_unparsed_tags() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
         let handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
-
-        let pattern = handle.hyphenate("koira");
-        assert_eq!(pattern.len(), 5); // same char count as "koira"
+        let analyses = handle.analyze("etelä-Suomi");
+        assert!(!analyses.is_empty());
+        for analysis in &analyses {
+            let fst_output = analysis
+                .get(voikko_core::analysis::ATTR_FSTOUTPUT)
+                .expect("FSTOUTPUT is set");
+            assert!(fst_output.contains("[De]"), "{fst_output}");
+            // Parsed attributes come alongside the raw output
+            assert!(analysis.get(voikko_core::analysis::ATTR_CLASS).is_some());
+        }
     }
 
     #[test]
-    fn tokenize_simple_text() {
-        // Tokenizer doesn't need a dictionary -- we can test with any handle
-        // but we can't construct one without valid dictionary data.
-        // Instead, test the tokenizer directly through the module.
-        let text = "Koira juoksi.";
-        let text_chars: Vec<char> = text.chars().collect();
-        let text_len = text_chars.len();
-
-        let mut tokens = Vec::new();
-        let mut pos = 0;
-        while pos < text_len {
-            let (tt, tlen) = tokenizer::next_token(&text_chars, text_len, pos);
-            if tt == TokenType::None || tlen == 0 {
-                break;
-            }
-            let token_text: String = text_chars[pos..pos + tlen].iter().collect();
-            tokens.push(Token::new(tt, token_text, pos));
-            pos += tlen;
-        }
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_traversal_budget() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        assert_eq!(handle.spell_status("koiraa"), SpellStatus::Correct);
+        assert_eq!(handle.spell_status("koiraax"), SpellStatus::Misspelled);
 
-        assert_eq!(tokens.len(), 4); // "Koira", " ", "juoksi", "."
-        assert_eq!(tokens[0].token_type, TokenType::Word);
-        assert_eq!(tokens[0].text, "Koira");
-        assert_eq!(tokens[3].token_type, TokenType::Punctuation);
-        assert_eq!(tokens[3].text, ".");
-    }
+        handle.set_traversal_budget(TraversalBudget {
+            max_steps: 5,
+            ..TraversalBudget::default()
+        });
+        assert!(!handle.spell("koiraa"));
+        assert_eq!(handle.spell_status("koiraa"), SpellStatus::Truncated);
 
-    // =========================================================================
-    // attribute_values tests
-    // =========================================================================
+        // An output limit keeps the first analyses
+        handle.set_traversal_budget(TraversalBudget {
+            max_outputs: 1,
+            ..TraversalBudget::default()
+        });
+        assert_eq!(handle.analyze("kuusi").len(), 1);
+        assert_eq!(handle.spell_status("koiraa"), SpellStatus::Correct);
 
-    #[test]
-    fn attribute_values_class() {
-        let vals = VoikkoHandle::attribute_values("CLASS").unwrap();
-        assert!(vals.contains(&"nimisana"));
-        assert!(vals.contains(&"teonsana"));
-        assert!(vals.contains(&"nimi"));
-        assert_eq!(vals.len(), 17);
+        handle.set_traversal_budget(TraversalBudget::default());
+        assert!(handle.analyze("kuusi").len() > 1);
     }
 
     #[test]
-    fn attribute_values_number() {
-        let vals = VoikkoHandle::attribute_values("NUMBER").unwrap();
-        assert_eq!(vals, &["singular", "plural"]);
-    }
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_traversal_stats() {
+        use crate::morphology::TruncationReason;
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        assert!(handle.spell("koiraa"));
+        assert_eq!(handle.traversal_stats(), TraversalStats::default());
 
-    #[test]
-    fn attribute_values_person() {
-        let vals = VoikkoHandle::attribute_values("PERSON").unwrap();
-        assert_eq!(vals, &["1", "2", "3", "4"]);
-    }
+        let small = TraversalBudget {
+            max_steps: 5,
+            ..TraversalBudget::default()
+        };
+        let (analyses, truncated) = handle.analyze_with_budget("koiraa", small);
+        assert!(analyses.is_empty() && truncated);
+        assert_eq!(handle.traversal_stats().steps, 1);
+        // Not recorded without diagnostics
+        assert!(handle.truncated_lookups().is_empty());
 
-    #[test]
-    fn attribute_values_mood() {
-        let vals = VoikkoHandle::attribute_values("MOOD").unwrap();
-        assert_eq!(vals.len(), 9);
-        assert!(vals.contains(&"indicative"));
-        assert!(vals.contains(&"MAINEN-infinitive"));
-    }
+        handle.set_truncation_diagnostics(true);
+        handle.set_traversal_budget(small);
+        assert!(!handle.spell("Koiraa"));
+        assert_eq!(handle.traversal_stats().steps, 2);
+        assert_eq!(
+            handle.truncated_lookups(),
+            [TruncatedLookup {
+                word: "koiraa".to_string(),
+                reason: TruncationReason::Steps,
+            }]
+        );
 
-    #[test]
-    fn attribute_values_sijamuoto() {
-        let vals = VoikkoHandle::attribute_values("SIJAMUOTO").unwrap();
-        assert_eq!(vals.len(), 16);
-        assert!(vals.contains(&"nimento"));
-        assert!(vals.contains(&"kerrontosti"));
-    }
+        // A larger budget for one call finds the analyses
+        let (analyses, truncated) =
+            handle.analyze_with_budget("koiraa", TraversalBudget::default());
+        assert!(!analyses.is_empty() && !truncated);
 
-    #[test]
-    fn attribute_values_focus() {
-        let vals = VoikkoHandle::attribute_values("FOCUS").unwrap();
-        assert_eq!(vals.len(), 6);
-        assert!(vals.contains(&"läs"));
+        handle.reset_traversal_stats();
+        assert_eq!(handle.traversal_stats().total(), 0);
+        assert!(handle.truncated_lookups().is_empty());
     }
 
     #[test]
-    fn attribute_values_kysymysliite() {
-        let vals = VoikkoHandle::attribute_values("KYSYMYSLIITE").unwrap();
-        assert_eq!(vals, &["true"]);
-    }
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_dictionary_extensions() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let kissa = handle.analyze("kissa");
+        assert!(handle.spell("koiran"));
 
-    #[test]
-    fn attribute_values_unknown_returns_none() {
-        assert!(VoikkoHandle::attribute_values("NONEXISTENT").is_none());
-        assert!(VoikkoHandle::attribute_values("").is_none());
+        handle
+            .add_dictionary_extension(None, "# withdrawn\nkoira\n")
+            .unwrap();
+        assert!(!handle.spell("koira"));
+        assert!(!handle.spell("koiran"));
+        assert!(handle.generate("koira", &Analysis::new()).is_empty());
+        // Compounds have their own base form
+        assert!(handle.spell("koirankoppi"));
+        assert!(handle.spell("kissa"));
+
+        // A layer above the removal brings the word back, and analyses
+        // that several layers give are reported once.
+        handle
+            .add_dictionary_extension(Some(&mor_data), "")
+            .unwrap();
+        assert!(handle.spell("koiran"));
+        assert_eq!(handle.analyze("kissa"), kissa);
+
+        handle.clear_dictionary_extensions();
+        assert!(handle.spell("koira"));
     }
 
     #[test]
-    fn attribute_values_all_known_names() {
-        let names = [
-            "CLASS",
-            "NUMBER",
-            "PERSON",
-            "MOOD",
-            "TENSE",
-            "COMPARISON",
-            "NEGATIVE",
-            "PARTICIPLE",
-            "POSSESSIVE",
-            "SIJAMUOTO",
-            "FOCUS",
-            "KYSYMYSLIITE",
-        ];
-        for name in &names {
-            assert!(
-                VoikkoHandle::attribute_values(name).is_some(),
-                "expected Some for attribute {name}"
-            );
-        }
-    }
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_reload_dictionary() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let mut handle = empty_handle();
+        handle.set_ignore_dot(true);
+        assert!(!handle.spell("koira."));
 
-    // =========================================================================
-    // get_version tests
-    // =========================================================================
+        // The cached rejection does not survive, and options are kept.
+        std::thread::scope(|scope| {
+            scope.spawn(|| handle.reload_dictionary(&mor_data, None).unwrap());
+            scope.spawn(|| handle.spell("kissa"));
+        });
+        assert!(handle.spell("koira."));
+        assert!(handle.spell("kissa"));
+        assert!(!handle.suggest("koirra").is_empty());
+    }
 
     #[test]
-    fn get_version_returns_cargo_version() {
-        let version = VoikkoHandle::get_version();
-        assert_eq!(version, env!("CARGO_PKG_VERSION"));
-        // Should be a valid semver-like string
-        assert!(!version.is_empty());
-        assert!(version.contains('.'));
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_end_punctuation() {
+        use voikko_core::grammar_error::GCERR_TERMINATING_PUNCTUATION_MISSING;
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let missing = |text: &str| -> Vec<(usize, usize, Vec<String>)> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code == GCERR_TERMINATING_PUNCTUATION_MISSING)
+                .map(|e| (e.start_pos, e.error_len, e.suggestions))
+                .collect()
+        };
+        assert_eq!(
+            missing("Koira haukkuu. Kissa istuu"),
+            [(21, 5, vec!["istuu.".to_string()])]
+        );
+        for item in [
+            "- koira haukkuu",
+            "\u{2022} koira haukkuu",
+            "1. Koira haukkuu",
+            "b) koira haukkuu",
+        ] {
+            assert!(missing(item).is_empty(), "{item}");
+        }
     }
 
-    // =========================================================================
-    // insert_hyphens tests (unit tests without dictionary)
-    // =========================================================================
-
-    // NOTE: insert_hyphens depends on hyphenate() which requires a real
-    // dictionary, so full integration tests are marked #[ignore].
-    // We test the core logic through integration tests below.
-
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_insert_hyphens_basic() {
+    fn integration_reflow_paragraphs() {
+        use voikko_core::grammar_error::{
+            GCERR_REPEATING_WORD, GCERR_TERMINATING_PUNCTUATION_MISSING,
+        };
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
-        let handle =
+        let mut handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let text = "Kissa istuu puussa\nja ja koira haukkuu.\n\nLintu laulaa.\n";
+        let found = |handle: &VoikkoHandle| -> Vec<(i32, usize, usize)> {
+            handle
+                .grammar_errors_from_text(text)
+                .into_iter()
+                .map(|e| (e.error_code, e.start_pos, e.error_len))
+                .collect()
+        };
+        // Line by line, the first line is an unfinished paragraph
+        assert!(
+            found(&handle)
+                .iter()
+                .any(|e| e.0 == GCERR_TERMINATING_PUNCTUATION_MISSING)
+        );
 
-        // "koira" should hyphenate to "koi-ra" with default separator
-        let result = handle.insert_hyphens("koira", "-", true);
-        // The result should contain the original letters
-        assert!(result.contains("koi"));
-        assert!(result.contains("ra"));
+        handle.set_reflow_paragraphs(true);
+        assert_eq!(found(&handle), [(GCERR_REPEATING_WORD, 19, 5)]);
     }
 
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_insert_hyphens_custom_separator() {
+    fn integration_number_format_checks() {
+        use voikko_core::grammar_error::{
+            GCERR_DATE_FORMAT, GCERR_ORDINAL_FORMAT, GCERR_TIME_FORMAT, GCERR_UNIT_SPACING,
+        };
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
-        let handle =
+        let mut handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
+        let text = "Juhla on 24.12.2024. klo 18:00 ja 3.nnella kerralla matkaa oli 5km.";
+        let found = |handle: &VoikkoHandle| -> Vec<(i32, Vec<String>)> {
+            handle
+                .grammar_errors(text)
+                .into_iter()
+                .filter(|e| e.error_code > 20)
+                .map(|e| (e.error_code, e.suggestions))
+                .collect()
+        };
+        assert!(found(&handle).is_empty());
 
-        let result_dash = handle.insert_hyphens("koira", "-", true);
-        let result_dot = handle.insert_hyphens("koira", ".", true);
-        // If there are hyphenation points, the separator should differ
-        let dash_count = result_dash.matches('-').count();
-        let dot_count = result_dot.matches('.').count();
-        assert_eq!(dash_count, dot_count);
+        handle.set_check_number_formats(true);
+        let owned = |s: &str| vec![s.to_string()];
+        assert_eq!(
+            found(&handle),
+            [
+                (GCERR_DATE_FORMAT, owned("24.12.2024")),
+                (GCERR_TIME_FORMAT, owned("18.00")),
+                (GCERR_ORDINAL_FORMAT, owned("3:nnella")),
+                (GCERR_UNIT_SPACING, owned("5 km")),
+            ]
+        );
+
+        handle.set_number_style(NumberStyle::Technical);
+        assert_eq!(
+            found(&handle),
+            [
+                (GCERR_DATE_FORMAT, owned("24.12.2024")),
+                (GCERR_ORDINAL_FORMAT, owned("3:nnella")),
+                (GCERR_UNIT_SPACING, owned("5 km")),
+            ]
+        );
     }
 
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_insert_hyphens_empty_separator() {
+    fn integration_grammar_errors_marked_maps_positions() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
@@ -828,20 +4110,33 @@ mod tests {
         let handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
 
-        // With empty separator, result should be the original word
-        // (hyphenation points are inserted as empty strings)
-        let result = handle.insert_hyphens("koira", "", true);
-        // All original characters should still be present
-        assert!(result.len() <= "koira".len());
+        // "<p>Se on <b>on</b> hyvä.</p><p>Koira haukkuu.</p>"
+        let html = "<p>Se on <b>on</b> hyvä.</p><p>Koira haukkuu.</p>";
+        let mut marked = MarkedText::new(html);
+        marked
+            .ignore(0, 3)
+            .ignore(9, 12)
+            .ignore(14, 18)
+            .ignore_as(24, 31, "\n")
+            .ignore(45, 49);
+        let errors = handle.grammar_errors_marked(&marked);
+        let repeated = errors
+            .iter()
+            .find(|e| e.error_code == 8)
+            .expect("repeated word is reported");
+        // "on <b>on" in the original
+        assert_eq!((repeated.start_pos, repeated.error_len), (6, 8));
+        let chars: Vec<char> = html.chars().collect();
+        for error in &errors {
+            let end = error.start_pos + error.error_len;
+            assert!(end <= chars.len());
+            assert!(end <= 24, "no error in the second paragraph: {error:?}");
+        }
     }
 
-    // =========================================================================
-    // set_speller_cache_size tests
-    // =========================================================================
-
     #[test]
     #[ignore = "requires mor.vfst dictionary file"]
-    fn integration_set_speller_cache_size() {
+    fn integration_invisible_chars_from_web_text() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
@@ -849,42 +4144,76 @@ mod tests {
         let mut handle =
             VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
 
-        // Spell a word, change cache size, spell again — should still work
-        assert!(handle.spell("koira"));
-        handle.set_speller_cache_size(2);
-        assert!(handle.spell("koira"));
-        handle.set_speller_cache_size(0);
-        assert!(handle.spell("koira"));
-    }
+        // Pasted from a web page: a BOM, a zero-width space left by a
+        // line breaking hint and a word joiner
+        let text = "\u{FEFF}Kaupungin\u{200B}talo on on auki\u{2060}.";
+        assert!(!handle.spell("kaupungin\u{200B}talo"));
+        assert!(
+            handle
+                .tokens(text)
+                .iter()
+                .any(|t| t.token_type == TokenType::Unknown)
+        );
 
-    // =========================================================================
-    // grammar_errors_from_text tests
-    // =========================================================================
+        handle.set_invisible_chars(InvisibleChars::Strip);
+        assert!(handle.spell("kaupungin\u{200B}talo"));
+        let suggestions = handle.suggest("kaupungin\u{200B}tallo");
+        assert!(
+            suggestions.iter().any(|s| s == "kaupungintalo"),
+            "{suggestions:?}"
+        );
+
+        let tokens = handle.tokens(text);
+        assert_eq!(tokens[0].text, "\u{FEFF}Kaupungin\u{200B}talo");
+        assert!(
+            tokens
+                .iter()
+                .filter(|t| t.token_type == TokenType::Word)
+                .all(|t| handle.spell(&t.text))
+        );
+
+        let sentences = handle.sentences(text);
+        assert_eq!(sentences.len(), 1);
+        assert_eq!(sentences[0].sentence_len, text.chars().count());
+
+        let errors = handle.grammar_errors(text);
+        let repeated = errors
+            .iter()
+            .find(|e| e.error_code == 8)
+            .expect("repeated word is reported");
+        // "on on" after "\u{FEFF}Kaupungin\u{200B}talo "
+        assert_eq!((repeated.start_pos, repeated.error_len), (16, 5));
+        assert!(errors.iter().all(|e| e.error_code != 1), "{errors:?}");
+    }
 
     #[test]
-    #[ignore = "requires mor.vfst and autocorr.vfst dictionary files"]
-    fn integration_grammar_errors_from_text_multiline() {
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_sentence_whitespace_policy() {
         let mor_data = std::fs::read(
             std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
         )
         .expect("failed to read mor.vfst");
-        let autocorr_data = std::fs::read(
-            std::env::var("VOIKKO_AUTOCORR_VFST")
-                .unwrap_or_else(|_| "../../test-data/autocorr.vfst".into()),
-        )
-        .ok();
-        let handle = VoikkoHandle::from_bytes(&mor_data, autocorr_data.as_deref(), "fi")
-            .expect("failed to create handle");
+        let mut handle =
+            VoikkoHandle::from_bytes(&mor_data, None, "fi").expect("failed to create handle");
 
-        // Test with multi-line text: positions should be adjusted
-        let text = "Ensimmäinen rivi.\nToinen rivi.";
-        let errors = handle.grammar_errors_from_text(text);
-        // Verify that any errors from the second paragraph have startPos >= 18
-        // (length of "Ensimmäinen rivi.\n")
-        for error in &errors {
-            // Errors can only be in one paragraph or the other
-            assert!(error.start_pos < text.chars().count());
-        }
+        let text = "Koira haukkuu.  Kissa nukkuu. ";
+        let lengths = |sentences: Vec<Sentence>| -> Vec<(usize, usize)> {
+            sentences
+                .iter()
+                .map(|s| (s.sentence_len, s.trimmed_len))
+                .collect()
+        };
+        assert_eq!(lengths(handle.sentences(text)), [(16, 14), (14, 13)]);
+        let spans = handle.sentence_spans(text);
+        assert_eq!(spans[0].text, "Koira haukkuu.  ");
+        assert_eq!((spans[1].trimmed_end, spans[1].end), (29, 30));
+
+        handle.set_sentence_whitespace(SentenceWhitespace::TrimEnd);
+        assert_eq!(lengths(handle.sentences(text)), [(14, 14), (13, 13)]);
+        let spans = handle.sentence_spans(text);
+        let texts: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["Koira haukkuu.", "Kissa nukkuu."]);
+        assert_eq!((spans[1].start, spans[1].end), (16, 29));
     }
 
     #[test]
@@ -0,0 +1,269 @@
+// Hyphenation exceptions: words with a fixed hyphenation.
+//
+// Brand names, foreign words and house-style choices are hyphenated as
+// listed instead of by analysis and syllable rules. An entry is the word
+// with its hyphenation points marked:
+//
+//   # comment
+//   Voik-ko
+//   Coca=Co-la
+//
+// `-` marks a hyphenation point between two characters. `=` stands for a
+// hyphen that belongs to the word and where the line may be broken. A
+// literal `-`, `=`, `#` or `\` is written as `\-`, `\=`, `\#` or `\\`.
+// Words are matched ignoring case; inflected forms are not matched.
+
+use std::collections::BTreeMap;
+
+use voikko_core::character::simple_lower;
+
+/// Error type for hyphenation exception parse failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum HyphenationExceptionError {
+    /// An entry is not valid.
+    #[error("invalid hyphenation exception {entry:?}: {reason}")]
+    InvalidEntry { entry: String, reason: &'static str },
+
+    /// A line of an exception list is not a valid entry. `line` is 1-based.
+    #[error("invalid hyphenation exception on line {line}: {reason}")]
+    InvalidLine { line: usize, reason: &'static str },
+}
+
+/// A hyphenation exception: the word and its pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Exception {
+    word: String,
+    pattern: String,
+}
+
+/// Words with a fixed hyphenation, consulted before analysis.
+///
+/// Patterns use the notation of [`Hyphenator::hyphenate`](super::Hyphenator).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HyphenationExceptions {
+    /// Keyed by the lowercased word.
+    entries: BTreeMap<String, Exception>,
+}
+
+impl HyphenationExceptions {
+    /// Create an empty exception list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add an entry such as `"Voik-ko"`, replacing an earlier entry for the
+    /// same word.
+    pub fn add(&mut self, entry: &str) -> Result<(), HyphenationExceptionError> {
+        let (word, pattern) =
+            parse_entry(entry).map_err(|reason| HyphenationExceptionError::InvalidEntry {
+                entry: entry.to_string(),
+                reason,
+            })?;
+        self.insert(word, pattern);
+        Ok(())
+    }
+
+    /// Remove the entry for `word` (given without hyphenation points).
+    /// Returns `true` if there was one.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.entries.remove(&lowercase(word.chars())).is_some()
+    }
+
+    /// Return the hyphenation pattern of `word`, if it is listed.
+    pub fn pattern(&self, word: &[char]) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries
+            .get(&lowercase(word.iter().copied()))
+            .map(|e| e.pattern.as_str())
+    }
+
+    /// Iterate over the entries as `(word, pattern)`, sorted by word.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .values()
+            .map(|e| (e.word.as_str(), e.pattern.as_str()))
+    }
+
+    /// Add all entries of `other`, replacing entries for the same words.
+    pub fn merge(&mut self, other: HyphenationExceptions) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Serialize the list into the text format, one entry per line.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for exception in self.entries.values() {
+            let chars: Vec<char> = exception.word.chars().collect();
+            for (i, (&c, p)) in chars.iter().zip(exception.pattern.chars()).enumerate() {
+                if p == '-' {
+                    out.push('-');
+                }
+                match c {
+                    '-' if p == '=' => out.push('='),
+                    '-' | '=' | '\\' => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    '#' if i == 0 => out.push_str("\\#"),
+                    _ => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the text format produced by [`HyphenationExceptions::to_text`].
+    ///
+    /// Empty lines and lines starting with `#` are skipped; surrounding
+    /// whitespace is ignored.
+    pub fn from_text(text: &str) -> Result<Self, HyphenationExceptionError> {
+        let mut exceptions = HyphenationExceptions::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, pattern) =
+                parse_entry(line).map_err(|reason| HyphenationExceptionError::InvalidLine {
+                    line: idx + 1,
+                    reason,
+                })?;
+            exceptions.insert(word, pattern);
+        }
+        Ok(exceptions)
+    }
+
+    fn insert(&mut self, word: String, pattern: String) {
+        self.entries
+            .insert(lowercase(word.chars()), Exception { word, pattern });
+    }
+}
+
+fn lowercase(word: impl Iterator<Item = char>) -> String {
+    word.map(simple_lower).collect()
+}
+
+/// Parse an entry into the word and its hyphenation pattern.
+fn parse_entry(entry: &str) -> Result<(String, String), &'static str> {
+    let mut word = String::new();
+    let mut pattern = String::new();
+    let mut point = false;
+    let mut chars = entry.chars();
+    while let Some(c) = chars.next() {
+        let (c, mark) = match c {
+            '-' => {
+                if word.is_empty() || point {
+                    return Err("misplaced hyphenation point");
+                }
+                point = true;
+                continue;
+            }
+            '=' => ('-', '='),
+            '\\' => (chars.next().ok_or("bad escape")?, ' '),
+            c => (c, ' '),
+        };
+        if mark == '=' && (word.is_empty() || point) {
+            return Err("misplaced hyphen");
+        }
+        word.push(c);
+        pattern.push(if point { '-' } else { mark });
+        point = false;
+    }
+    if word.is_empty() {
+        return Err("empty word");
+    }
+    if point || pattern.ends_with('=') {
+        return Err("hyphenation point at end of word");
+    }
+    Ok((word, pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn entry_gives_pattern() {
+        let mut exceptions = HyphenationExceptions::new();
+        exceptions.add("Voik-ko").unwrap();
+        exceptions.add("Coca=Co-la").unwrap();
+        assert_eq!(exceptions.pattern(&chars("voikko")), Some("    - "));
+        assert_eq!(exceptions.pattern(&chars("VOIKKO")), Some("    - "));
+        assert_eq!(exceptions.pattern(&chars("Coca-Cola")), Some("    =  - "));
+        assert_eq!(exceptions.pattern(&chars("voikkoa")), None);
+    }
+
+    #[test]
+    fn escapes_and_unbreakable_words() {
+        let mut exceptions = HyphenationExceptions::new();
+        exceptions.add("x\\-ray").unwrap();
+        exceptions.add("iPhone").unwrap();
+        assert_eq!(exceptions.pattern(&chars("x-ray")), Some("     "));
+        assert_eq!(exceptions.pattern(&chars("iphone")), Some("      "));
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        let mut exceptions = HyphenationExceptions::new();
+        for entry in [
+            "", "-koira", "koira-", "koi--ra", "=koira", "koi-=ra", "koira\\",
+        ] {
+            assert!(
+                matches!(
+                    exceptions.add(entry),
+                    Err(HyphenationExceptionError::InvalidEntry { .. })
+                ),
+                "{entry:?}"
+            );
+        }
+        assert!(exceptions.is_empty());
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let text = "# house style\nVoik-ko\n\n  Coca=Co-la\nx\\-ray\n\\#hash-tag\n";
+        let exceptions = HyphenationExceptions::from_text(text).unwrap();
+        assert_eq!(exceptions.len(), 4);
+        assert_eq!(
+            HyphenationExceptions::from_text(&exceptions.to_text()).unwrap(),
+            exceptions
+        );
+        assert_eq!(
+            exceptions.to_text(),
+            "\\#hash-tag\nCoca=Co-la\nVoik-ko\nx\\-ray\n"
+        );
+        let err = HyphenationExceptions::from_text("koira\n-kissa\n").unwrap_err();
+        assert!(matches!(
+            err,
+            HyphenationExceptionError::InvalidLine { line: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn later_entry_replaces_earlier() {
+        let mut exceptions = HyphenationExceptions::new();
+        exceptions.add("Voik-ko").unwrap();
+        exceptions.add("voikko").unwrap();
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions.iter().next(), Some(("voikko", "      ")));
+        assert!(exceptions.remove("VOIKKO"));
+        assert!(exceptions.is_empty());
+    }
+}
@@ -6,36 +6,19 @@
 // 2. Applying Finnish syllable rules within each morpheme component
 // 3. Intersecting (or union-ing) compound boundaries with syllable rules
 
+mod exceptions;
+
+pub use exceptions::{HyphenationExceptionError, HyphenationExceptions};
+
+use std::sync::Arc;
+
 use voikko_core::analysis::{ATTR_STRUCTURE, Analysis};
 use voikko_core::character::{is_consonant, is_vowel, simple_lower};
+use voikko_core::enums::MAX_WORD_CHARS;
 
 use crate::morphology::Analyzer;
 
-use crate::finnish::constants::SPLIT_VOWELS;
-
-/// Long consonant sequences treated as indivisible units.
-/// A hyphen should be moved before the entire cluster rather than splitting it.
-/// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:45 (LONG_CONSONANTS)
-const LONG_CONSONANTS: &[&[char]] = &[
-    &['\u{0161}', 't', '\u{0161}'], // štš  (C++ "shtsh" uses š and tš differently)
-    // Note: C++ uses L"shtsh" and L"\u0161t\u0161" separately. The C++ wchar_t values
-    // for "shtsh" are actually 's','h','t','s','h' (ASCII), not š. Let me re-check.
-    // Actually looking at the C++ more carefully:
-    //   L"shtsh" -> 's','h','t','s','h' (5 chars)
-    //   L"\u0161t\u0161" -> 'š','t','š' (3 chars)
-    //   L"tsh" -> 't','s','h' (3 chars)
-    //   L"t\u0161" -> 't','š' (2 chars)
-    //   L"zh" -> 'z','h' (2 chars)
-    &['s', 'h', 't', 's', 'h'], // shtsh
-    &['t', 's', 'h'],           // tsh
-    &['t', '\u{0161}'],         // tš
-    &['z', 'h'],                // zh
-];
-
-/// Vowel pair patterns after which a following vowel may be split (VV-V rule).
-/// Only applied in ugly hyphenation mode.
-/// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:46 (SPLIT_AFTER)
-const SPLIT_AFTER: &[[char; 2]] = &[['i', 'e'], ['a', 'i']];
+use crate::language_rules::LanguageRules;
 
 /// Special characters that block a hyphenation point after them.
 /// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:418 (the wcschr check)
@@ -71,6 +54,10 @@ pub struct HyphenatorOptions {
     /// those analyses instead.
     /// Origin: AnalyzerToFinnishHyphenatorAdapter.hpp:62 (ignoreDot)
     pub ignore_dot: bool,
+
+    /// Longer words get no hyphenation points, as they cannot be analyzed.
+    /// Listed exceptions still apply.
+    pub max_word_chars: usize,
 }
 
 impl Default for HyphenatorOptions {
@@ -82,6 +69,7 @@ impl Default for HyphenatorOptions {
             hyphenate_unknown: true,
             min_hyphenated_word_length: 2,
             ignore_dot: false,
+            max_word_chars: MAX_WORD_CHARS,
         }
     }
 }
@@ -118,6 +106,132 @@ pub trait Hyphenator {
     fn all_possible_hyphen_positions(&self, word: &[char]) -> String;
 }
 
+// ---------------------------------------------------------------------------
+// Hyphenation explanations (debug mode)
+// ---------------------------------------------------------------------------
+
+/// The rule that allowed or suppressed a hyphenation point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HyphenReason {
+    /// No rule proposed a hyphenation point here.
+    #[default]
+    NoRule,
+    /// The word or compound component is shorter than the minimum length.
+    TooShort,
+    /// Rule hyphenation is disabled for the word (URL, e-mail address, or
+    /// a word ending in a digit).
+    NotAWord,
+    /// The word is unknown and unknown words are not hyphenated.
+    UnknownWord,
+    /// The STRUCTURE attribute forbids hyphenation (abbreviation marker).
+    StructureForbidden,
+    /// Compound word boundary from the STRUCTURE attribute.
+    CompoundBoundary,
+    /// Existing hyphen or apostrophe in the word.
+    ExplicitHyphen,
+    /// Break before a consonant followed by a vowel (-CV).
+    ConsonantVowel,
+    /// Break before or after a long vowel.
+    LongVowel,
+    /// Break between two vowels that do not form a diphthong.
+    VowelPair,
+    /// Break moved before an indivisible consonant cluster.
+    LongConsonant,
+    /// Break leaving a single letter at the start or end of a component
+    /// removed (no ugly hyphenation).
+    UglyEdge,
+    /// Break between two vowels removed (no ugly hyphenation).
+    UglyVowelPair,
+    /// Break after "ie" or "ai" before a vowel (ugly hyphenation only).
+    SplitAfterDiphthong,
+    /// Analyses of the word disagree on this point, so it is not used.
+    AnalysesDisagree,
+    /// The word is in the hyphenation exception list.
+    Exception,
+    /// The word is longer than the maximum word length.
+    WordTooLong,
+}
+
+impl HyphenReason {
+    /// Short English description of the reason.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NoRule => "no rule applies",
+            Self::TooShort => "word or compound part too short",
+            Self::NotAWord => "not a word (URL, e-mail or trailing digit)",
+            Self::UnknownWord => "unknown word",
+            Self::StructureForbidden => "forbidden by STRUCTURE (abbreviation)",
+            Self::CompoundBoundary => "compound boundary",
+            Self::ExplicitHyphen => "hyphen or apostrophe in the word",
+            Self::ConsonantVowel => "consonant-vowel rule",
+            Self::LongVowel => "long vowel boundary",
+            Self::VowelPair => "vowels not forming a diphthong",
+            Self::LongConsonant => "indivisible consonant cluster",
+            Self::UglyEdge => "single letter at part edge (no ugly hyphenation)",
+            Self::UglyVowelPair => "vowel pair kept together (no ugly hyphenation)",
+            Self::SplitAfterDiphthong => "split after ie/ai before vowel",
+            Self::AnalysesDisagree => "analyses disagree",
+            Self::Exception => "hyphenation exception",
+            Self::WordTooLong => "word too long",
+        }
+    }
+}
+
+/// A hyphenation pattern together with the reason for each position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyphenExplanation {
+    /// The pattern, identical to `Hyphenator::hyphenate`.
+    pub pattern: String,
+    /// Reason for each position of the pattern: the rule that made it a
+    /// hyphenation point, or the rule that suppressed it.
+    pub reasons: Vec<HyphenReason>,
+}
+
+/// Set a hyphenation point and record the reason. `reasons` is empty when
+/// no explanation is requested. A reason for clearing a point is recorded
+/// only if the point was set.
+fn set_point(
+    points: &mut [u8],
+    reasons: &mut [HyphenReason],
+    i: usize,
+    value: u8,
+    reason: HyphenReason,
+) {
+    let changed = points[i] != value;
+    points[i] = value;
+    if let Some(r) = reasons.get_mut(i) {
+        if value != b' ' || changed {
+            *r = reason;
+        }
+    }
+}
+
+/// Record `reason` for positions `from..to` that have no reason yet.
+fn mark_unset(reasons: &mut [HyphenReason], from: usize, to: usize, reason: HyphenReason) {
+    for r in reasons.iter_mut().take(to).skip(from) {
+        if *r == HyphenReason::NoRule {
+            *r = reason;
+        }
+    }
+}
+
+/// The reasons slice for a component starting at `start`.
+fn reasons_from(reasons: &mut [HyphenReason], start: usize) -> &mut [HyphenReason] {
+    if reasons.is_empty() {
+        reasons
+    } else {
+        &mut reasons[start..]
+    }
+}
+
+/// Result of compound splitting: one hyphenation buffer per analysis.
+struct CompoundSplit {
+    hyphenations: Vec<Vec<u8>>,
+    dot_removed: bool,
+    /// `true` if the word has no analyses.
+    unknown: bool,
+}
+
 // ---------------------------------------------------------------------------
 // FinnishHyphenator
 // Origin: AnalyzerToFinnishHyphenatorAdapter
@@ -130,13 +244,41 @@ pub trait Hyphenator {
 pub struct FinnishHyphenator<A: Analyzer> {
     analyzer: A,
     options: HyphenatorOptions,
+    /// Words hyphenated as listed, looked up before analysis.
+    exceptions: Option<Arc<HyphenationExceptions>>,
+    /// Vowel and consonant tables of the syllable rules.
+    rules: Arc<LanguageRules>,
 }
 
 impl<A: Analyzer> FinnishHyphenator<A> {
     /// Create a new Finnish hyphenator wrapping the given analyzer.
     /// Origin: AnalyzerToFinnishHyphenatorAdapter::AnalyzerToFinnishHyphenatorAdapter
     pub fn new(analyzer: A, options: HyphenatorOptions) -> Self {
-        Self { analyzer, options }
+        Self {
+            analyzer,
+            options,
+            exceptions: None,
+            rules: LanguageRules::shared_default(),
+        }
+    }
+
+    /// Apply the syllable rules with the vowel pairs and consonant clusters
+    /// of `rules` instead of the standard Finnish ones.
+    pub fn with_rules(mut self, rules: Arc<LanguageRules>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Hyphenate the words in `exceptions` as listed. Exceptions apply
+    /// regardless of the options, including the minimum word length.
+    pub fn with_exceptions(mut self, exceptions: Arc<HyphenationExceptions>) -> Self {
+        self.exceptions = Some(exceptions);
+        self
+    }
+
+    /// The exception pattern of `word`, if it is listed.
+    fn exception(&self, word: &[char]) -> Option<&str> {
+        self.exceptions.as_ref()?.pattern(word)
     }
 
     /// Update hyphenator options.
@@ -164,19 +306,15 @@ impl<A: Analyzer> FinnishHyphenator<A> {
     ///   `'X'` = hyphenation forbidden at this position
     ///
     /// Origin: AnalyzerToFinnishHyphenatorAdapter::splitCompounds
-    fn split_compounds(&self, word: &[char]) -> Option<(Vec<Vec<u8>>, bool)> {
+    fn split_compounds(&self, word: &[char]) -> Option<CompoundSplit> {
         let len = word.len();
 
-        // Convert to lowercase string for the analyzer
-        let word_lower: Vec<char> = word.iter().map(|&c| simple_lower(c)).collect();
-
-        let mut analyses = self.analyzer.analyze(&word_lower, len);
+        let (mut analyses, _) = self.analyzer.analyze_any_case(word, len);
 
         // Try removing trailing dot if no analyses found
         let mut dot_removed = false;
         if analyses.is_empty() && self.options.ignore_dot && len > 1 && word[len - 1] == '.' {
-            let trimmed: Vec<char> = word_lower[..len - 1].to_vec();
-            analyses = self.analyzer.analyze(&trimmed, len - 1);
+            (analyses, _) = self.analyzer.analyze_any_case(word, len - 1);
             if !analyses.is_empty() {
                 dot_removed = true;
             }
@@ -223,7 +361,11 @@ impl<A: Analyzer> FinnishHyphenator<A> {
 
         remove_extra_hyphenations(&mut all_results, len);
 
-        Some((all_results, dot_removed))
+        Some(CompoundSplit {
+            hyphenations: all_results,
+            dot_removed,
+            unknown: analyses.is_empty(),
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -236,8 +378,16 @@ impl<A: Analyzer> FinnishHyphenator<A> {
     /// The `hyphenation` buffer already has compound boundaries marked.
     /// This function fills in syllable break points within each component.
     ///
+    /// `reasons` receives the reason for each position (empty to skip).
+    ///
     /// Origin: AnalyzerToFinnishHyphenatorAdapter::compoundHyphenation
-    fn compound_hyphenation(&self, word: &[char], hyphenation: &mut [u8], len: usize) {
+    fn compound_hyphenation(
+        &self,
+        word: &[char],
+        hyphenation: &mut [u8],
+        len: usize,
+        reasons: &mut [HyphenReason],
+    ) {
         let mut start = 0;
 
         // Skip leading '=' markers
@@ -255,7 +405,11 @@ impl<A: Analyzer> FinnishHyphenator<A> {
                         &mut hyphenation[start..],
                         end - start,
                         self.options.ugly_hyphenation,
+                        &self.rules,
+                        reasons_from(reasons, start),
                     );
+                } else {
+                    mark_unset(reasons, start + 1, end, HyphenReason::TooShort);
                 }
                 if hyphenation[end] == b'=' {
                     start = end + 1;
@@ -269,13 +423,19 @@ impl<A: Analyzer> FinnishHyphenator<A> {
         }
 
         // Handle the last component
-        if end == len && start < end && end >= start + self.options.min_hyphenated_word_length {
-            rule_hyphenation(
-                &word[start..],
-                &mut hyphenation[start..],
-                end - start,
-                self.options.ugly_hyphenation,
-            );
+        if end == len && start < end {
+            if end >= start + self.options.min_hyphenated_word_length {
+                rule_hyphenation(
+                    &word[start..],
+                    &mut hyphenation[start..],
+                    end - start,
+                    self.options.ugly_hyphenation,
+                    &self.rules,
+                    reasons_from(reasons, start),
+                );
+            } else {
+                mark_unset(reasons, start + 1, end, HyphenReason::TooShort);
+            }
         }
     }
 
@@ -289,21 +449,29 @@ impl<A: Analyzer> FinnishHyphenator<A> {
     ///
     /// Origin: AnalyzerToFinnishHyphenatorAdapter::hyphenate / allPossibleHyphenPositions
     fn hyphenate_internal(&self, word: &[char], use_intersection: bool) -> String {
+        if let Some(pattern) = self.exception(word) {
+            return pattern.to_string();
+        }
         let wlen = word.len();
 
-        // Short words: no hyphenation
-        if wlen < self.options.min_hyphenated_word_length {
+        // Short words and words too long to analyze: no hyphenation
+        if wlen < self.options.min_hyphenated_word_length || wlen > self.options.max_word_chars {
             return " ".repeat(wlen);
         }
 
-        let Some((mut hyphenations, dot_removed)) = self.split_compounds(word) else {
+        let Some(CompoundSplit {
+            mut hyphenations,
+            dot_removed,
+            ..
+        }) = self.split_compounds(word)
+        else {
             return " ".repeat(wlen);
         };
 
         let effective_len = if dot_removed { wlen - 1 } else { wlen };
 
         for hyph in &mut hyphenations {
-            self.compound_hyphenation(word, hyph, effective_len);
+            self.compound_hyphenation(word, hyph, effective_len, &mut []);
         }
 
         if use_intersection {
@@ -312,6 +480,77 @@ impl<A: Analyzer> FinnishHyphenator<A> {
             union_hyphenations(&hyphenations)
         }
     }
+
+    /// Hyphenate the word like `hyphenate` and report, for each position,
+    /// the rule that allowed or suppressed a hyphenation point there.
+    ///
+    /// Intended for debugging and for answering "why is this word not
+    /// hyphenated here?". Slower than `hyphenate`.
+    pub fn explain(&self, word: &[char]) -> HyphenExplanation {
+        if let Some(pattern) = self.exception(word) {
+            return HyphenExplanation {
+                pattern: pattern.to_string(),
+                reasons: vec![HyphenReason::Exception; word.len()],
+            };
+        }
+        let wlen = word.len();
+        if wlen < self.options.min_hyphenated_word_length {
+            return HyphenExplanation {
+                pattern: " ".repeat(wlen),
+                reasons: vec![HyphenReason::TooShort; wlen],
+            };
+        }
+        if wlen > self.options.max_word_chars {
+            return HyphenExplanation {
+                pattern: " ".repeat(wlen),
+                reasons: vec![HyphenReason::WordTooLong; wlen],
+            };
+        }
+        let Some(CompoundSplit {
+            mut hyphenations,
+            dot_removed,
+            unknown,
+        }) = self.split_compounds(word)
+        else {
+            return HyphenExplanation {
+                pattern: " ".repeat(wlen),
+                reasons: vec![HyphenReason::NoRule; wlen],
+            };
+        };
+        let effective_len = if dot_removed { wlen - 1 } else { wlen };
+
+        let mut all_reasons = Vec::with_capacity(hyphenations.len());
+        for hyph in &mut hyphenations {
+            let mut reasons: Vec<HyphenReason> = hyph
+                .iter()
+                .map(|&b| match b {
+                    b'-' => HyphenReason::CompoundBoundary,
+                    b'=' => HyphenReason::ExplicitHyphen,
+                    b'X' if unknown => HyphenReason::UnknownWord,
+                    b'X' => HyphenReason::StructureForbidden,
+                    _ => HyphenReason::NoRule,
+                })
+                .collect();
+            self.compound_hyphenation(word, hyph, effective_len, &mut reasons);
+            all_reasons.push(reasons);
+        }
+
+        let pattern = intersect_hyphenations(&hyphenations);
+        let reasons = pattern
+            .bytes()
+            .enumerate()
+            .map(|(i, b)| {
+                let disagree =
+                    b == b' ' && hyphenations.iter().any(|h| h[i] == b'-' || h[i] == b'=');
+                if disagree {
+                    HyphenReason::AnalysesDisagree
+                } else {
+                    all_reasons[0][i]
+                }
+            })
+            .collect();
+        HyphenExplanation { pattern, reasons }
+    }
 }
 
 impl<A: Analyzer> Hyphenator for FinnishHyphenator<A> {
@@ -499,10 +738,15 @@ fn is_nonword(word: &[char], nchars: usize) -> bool {
 /// 1. -CV: hyphen before consonant-vowel pairs
 /// 2. 'V: compound break after apostrophe before vowel
 /// 3. Long vowel boundaries (VV): split before/after long vowels
-/// 4. V-V: split specific vowel pairs (SPLIT_VOWELS table)
+/// 4. V-V: split specific vowel pairs (`rules.split_vowels`)
 /// 5. Long consonants: move hyphen before indivisible consonant clusters
+///    (`rules.long_consonants`)
 /// 6. Aesthetic cleanup (when ugly_hyphenation is false)
-/// 7. VV-V: split after "ie"/"ai" before vowel (ugly mode only)
+/// 7. VV-V: split after "ie"/"ai" (`rules.split_after`) before vowel (ugly
+///    mode only)
+///
+/// Positions changed by a rule get the rule's reason in `reasons`; pass an
+/// empty slice to skip tracing.
 ///
 /// Origin: AnalyzerToFinnishHyphenatorAdapter::ruleHyphenation
 fn rule_hyphenation(
@@ -510,8 +754,13 @@ fn rule_hyphenation(
     hyphenation_points: &mut [u8],
     nchars: usize,
     ugly_hyphenation: bool,
+    rules: &LanguageRules,
+    reasons: &mut [HyphenReason],
 ) {
+    use HyphenReason as R;
+
     if !allow_rule_hyphenation(word, nchars, ugly_hyphenation) {
+        mark_unset(reasons, 1, nchars, R::NotAWord);
         return;
     }
 
@@ -540,7 +789,7 @@ fn rule_hyphenation(
             && !SPECIAL_CHARS_BEFORE_HYPHEN.contains(&word_lower[i - 1])
             && (i <= 1 || ugly_hyphenation || word_lower[i - 2] != '\'')
         {
-            hyphenation_points[i] = b'-';
+            set_point(hyphenation_points, reasons, i, b'-', R::ConsonantVowel);
         }
         i += 1;
     }
@@ -549,7 +798,7 @@ fn rule_hyphenation(
     // Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:425-429
     for i in 1..nchars.saturating_sub(1) {
         if word_lower[i] == '\'' && is_vowel(word_lower[i + 1]) {
-            hyphenation_points[i] = b'=';
+            set_point(hyphenation_points, reasons, i, b'=', R::ExplicitHyphen);
         }
     }
 
@@ -562,13 +811,13 @@ fn rule_hyphenation(
             if is_vowel(word_lower[i - 1])
                 && is_good_hyphen_position(&word_lower, hyphenation_points, i, nchars)
             {
-                hyphenation_points[i] = b'-';
+                set_point(hyphenation_points, reasons, i, b'-', R::LongVowel);
             }
             // Split after the long vowel
             if i + 2 < nchars
                 && is_good_hyphen_position(&word_lower, hyphenation_points, i + 2, nchars)
             {
-                hyphenation_points[i + 2] = b'-';
+                set_point(hyphenation_points, reasons, i + 2, b'-', R::LongVowel);
             }
         }
     }
@@ -584,8 +833,8 @@ fn rule_hyphenation(
             continue;
         }
         let pair = [word_lower[i], word_lower[i + 1]];
-        if SPLIT_VOWELS.contains(&pair) {
-            hyphenation_points[i + 1] = b'-';
+        if rules.split_vowels.contains(&pair) {
+            set_point(hyphenation_points, reasons, i + 1, b'-', R::VowelPair);
         }
     }
 
@@ -594,7 +843,7 @@ fn rule_hyphenation(
     // from inside the cluster to before the cluster.
     // Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:463-476
     for i in 1..nchars.saturating_sub(1) {
-        for long_cons in LONG_CONSONANTS {
+        for long_cons in &rules.long_consonants {
             let clen = long_cons.len();
             if i + clen <= nchars
                 && word_lower[i..i + clen]
@@ -604,8 +853,8 @@ fn rule_hyphenation(
             {
                 for k in (i + 1)..=(i + clen).min(nchars - 1) {
                     if k < hyphenation_points.len() && hyphenation_points[k] == b'-' {
-                        hyphenation_points[k] = b' ';
-                        hyphenation_points[i] = b'-';
+                        set_point(hyphenation_points, reasons, k, b' ', R::LongConsonant);
+                        set_point(hyphenation_points, reasons, i, b'-', R::LongConsonant);
                     }
                 }
             }
@@ -618,13 +867,13 @@ fn rule_hyphenation(
     // - Forbid splitting consecutive vowels
     // Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:478-486
     if !ugly_hyphenation {
-        hyphenation_points[1] = b' ';
+        set_point(hyphenation_points, reasons, 1, b' ', R::UglyEdge);
         if nchars >= 1 {
-            hyphenation_points[nchars - 1] = b' ';
+            set_point(hyphenation_points, reasons, nchars - 1, b' ', R::UglyEdge);
         }
         for i in 0..nchars.saturating_sub(1) {
             if is_vowel(word_lower[i]) && is_vowel(word_lower[i + 1]) {
-                hyphenation_points[i + 1] = b' ';
+                set_point(hyphenation_points, reasons, i + 1, b' ', R::UglyVowelPair);
             }
         }
     } else if nchars >= 3 {
@@ -632,14 +881,20 @@ fn rule_hyphenation(
         // After "ie" or "ai" followed by a vowel, allow a split.
         // Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:487-499
         for i in 0..nchars.saturating_sub(3) {
-            for split_pair in SPLIT_AFTER {
+            for split_pair in &rules.split_after {
                 let pair = [word_lower[i], word_lower[i + 1]];
                 if hyphenation_points[i + 1] != b'-'
                     && pair == *split_pair
                     && is_vowel(word_lower[i + 2])
                     && is_good_hyphen_position(&word_lower, hyphenation_points, i + 2, nchars)
                 {
-                    hyphenation_points[i + 2] = b'-';
+                    set_point(
+                        hyphenation_points,
+                        reasons,
+                        i + 2,
+                        b'-',
+                        R::SplitAfterDiphthong,
+                    );
                 }
             }
         }
@@ -1068,7 +1323,14 @@ mod tests {
         // "koira" -> should get -CV break at 'r' (position 3): "koi-ra"
         let word = chars("koira");
         let mut hyph = vec![b' '; 5];
-        rule_hyphenation(&word, &mut hyph, 5, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            5,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         // The -CV rule fires at position 3 (r is consonant, a is vowel)
         assert_eq!(hyph[3], b'-');
     }
@@ -1083,7 +1345,14 @@ mod tests {
         // So "kis-sa"
         let word = chars("kissa");
         let mut hyph = vec![b' '; 5];
-        rule_hyphenation(&word, &mut hyph, 5, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            5,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[3], b'-');
         let rendered = render_hyphenation("kissa", &String::from_utf8(hyph).unwrap());
         assert_eq!(rendered, "kis-sa");
@@ -1097,7 +1366,14 @@ mod tests {
         // -CV at pos 2: l(2) consonant, o(3) vowel -> yes
         let word = chars("talo");
         let mut hyph = vec![b' '; 4];
-        rule_hyphenation(&word, &mut hyph, 4, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            4,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[2], b'-');
     }
 
@@ -1115,7 +1391,14 @@ mod tests {
         // So no split before. After: i+2=3 which is >= nchars, so no split after.
         let word = chars("maa");
         let mut hyph = vec![b' '; 3];
-        rule_hyphenation(&word, &mut hyph, 3, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            3,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph, vec![b' ', b' ', b' ']);
     }
 
@@ -1148,7 +1431,14 @@ mod tests {
         // Result: hyph = "    - -" -> "saip-pu-a"
         let word = chars("saippua");
         let mut hyph = vec![b' '; 7];
-        rule_hyphenation(&word, &mut hyph, 7, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            7,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[4], b'-'); // "saip-pua"
         assert_eq!(hyph[6], b'-'); // "saip-pu-a"
         let rendered = render_hyphenation("saippua", &String::from_utf8(hyph).unwrap());
@@ -1166,7 +1456,14 @@ mod tests {
         // i=4: l consonant, ö(5) vowel -> yes, hyph[4] = '-'
         let word: Vec<char> = "k\u{00E4}vel\u{00F6}".chars().collect();
         let mut hyph = vec![b' '; 6];
-        rule_hyphenation(&word, &mut hyph, 6, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            6,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[2], b'-');
         assert_eq!(hyph[4], b'-');
     }
@@ -1176,7 +1473,14 @@ mod tests {
         // "tie" -> no hyphenation (too short for meaningful splits)
         let word = chars("tie");
         let mut hyph = vec![b' '; 3];
-        rule_hyphenation(&word, &mut hyph, 3, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            3,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         // -CV: i starts at 0 (t is consonant), then i=1 (i is vowel)
         // Actually: skip leading consonants. t is consonant, so i increments.
         // i=1: starts the -CV loop. But we need i <= nchars-2 = 1.
@@ -1199,7 +1503,14 @@ mod tests {
         // But hyph[2] = '-' is not at pos 1 or nchars-1, so it stays
         let word = chars("talo");
         let mut hyph = vec![b' '; 4];
-        rule_hyphenation(&word, &mut hyph, 4, false);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            4,
+            false,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[2], b'-');
     }
 
@@ -1219,7 +1530,14 @@ mod tests {
         // So the V-V split at position 3 gets removed by the non-ugly rule.
         let word = chars("kauas");
         let mut hyph = vec![b' '; 5];
-        rule_hyphenation(&word, &mut hyph, 5, false);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            5,
+            false,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph[3], b' '); // suppressed by non-ugly rule
     }
 
@@ -1229,7 +1547,14 @@ mod tests {
         // But no -CV or V-V splits can happen in 2 chars.
         let word = chars("aa");
         let mut hyph = vec![b' '; 2];
-        rule_hyphenation(&word, &mut hyph, 2, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            2,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph, vec![b' ', b' ']);
     }
 
@@ -1238,7 +1563,14 @@ mod tests {
         // Single character: allowRuleHyphenation returns false
         let word = chars("a");
         let mut hyph = vec![b' '; 1];
-        rule_hyphenation(&word, &mut hyph, 1, true);
+        rule_hyphenation(
+            &word,
+            &mut hyph,
+            1,
+            true,
+            &LanguageRules::default(),
+            &mut [],
+        );
         assert_eq!(hyph, vec![b' ']);
     }
 
@@ -1497,6 +1829,73 @@ mod tests {
         assert_eq!(result.len(), 5);
     }
 
+    #[test]
+    fn exception_overrides_analysis_and_rules() {
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add_word("koiranruoka", &["=pppppp=ppppp"]);
+        let mut exceptions = HyphenationExceptions::new();
+        exceptions.add("koiranru-oka").unwrap();
+        exceptions.add("Ab-c").unwrap();
+        let options = HyphenatorOptions {
+            min_hyphenated_word_length: 5,
+            ..Default::default()
+        };
+        let hyp = FinnishHyphenator::new(analyzer, options).with_exceptions(Arc::new(exceptions));
+
+        let result = hyphenate_str(&hyp, "Koiranruoka");
+        assert_eq!(render_hyphenation("koiranruoka", &result), "koiranru-oka");
+        // Shorter than the minimum length, but listed
+        assert_eq!(hyphenate_str(&hyp, "abc"), "  -");
+        let explanation = hyp.explain(&chars("abc"));
+        assert_eq!(explanation.reasons, vec![HyphenReason::Exception; 3]);
+        // Other words are hyphenated as usual
+        assert_eq!(hyphenate_str(&hyp, "kissa"), "   - ");
+    }
+
+    #[test]
+    fn custom_rules_replace_the_finnish_tables() {
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add_word("teatteri", &["=pppppppp"]);
+        analyzer.add_word("pizza", &["=ppppp"]);
+        let standard = FinnishHyphenator::new(&analyzer, HyphenatorOptions::default());
+        assert_eq!(
+            render_hyphenation("teatteri", &hyphenate_str(&standard, "teatteri")),
+            "te-at-te-ri"
+        );
+        assert_eq!(
+            render_hyphenation("pizza", &hyphenate_str(&standard, "pizza")),
+            "piz-za"
+        );
+
+        let mut rules = LanguageRules::default();
+        rules.split_vowels.retain(|&pair| pair != ['e', 'a']);
+        rules.long_consonants.push(vec!['z', 'z']);
+        let custom = FinnishHyphenator::new(&analyzer, HyphenatorOptions::default())
+            .with_rules(Arc::new(rules));
+        assert_eq!(
+            render_hyphenation("teatteri", &hyphenate_str(&custom, "teatteri")),
+            "teat-te-ri"
+        );
+        assert_eq!(
+            render_hyphenation("pizza", &hyphenate_str(&custom, "pizza")),
+            "pi-zza"
+        );
+    }
+
+    #[test]
+    fn word_over_max_length_is_not_hyphenated() {
+        let options = HyphenatorOptions {
+            max_word_chars: 4,
+            ..Default::default()
+        };
+        let hyp = FinnishHyphenator::new(NullAnalyzer, options);
+        assert_eq!(hyphenate_str(&hyp, "kissa"), "     ");
+        let explanation = hyp.explain(&chars("kissa"));
+        assert_eq!(explanation.pattern, "     ");
+        assert_eq!(explanation.reasons, vec![HyphenReason::WordTooLong; 5]);
+        assert_eq!(hyphenate_str(&hyp, "kala"), "  - ");
+    }
+
     #[test]
     fn hyphenate_kissa_unknown() {
         let hyp = FinnishHyphenator::new(NullAnalyzer, HyphenatorOptions::default());
@@ -1642,4 +2041,77 @@ mod tests {
         assert_eq!(result_bytes[1], b'-'); // moved before the cluster
         assert_eq!(result_bytes[2], b' '); // cleared
     }
+
+    // -----------------------------------------------------------------------
+    // Explanations
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn explain_compound_word() {
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add_word("koiranruoka", &["=pppppp=ppppp"]);
+        let hyp = FinnishHyphenator::new(analyzer, HyphenatorOptions::default());
+        let explanation = hyp.explain(&chars("koiranruoka"));
+        assert_eq!(explanation.pattern, hyp.hyphenate(&chars("koiranruoka")));
+        assert_eq!(explanation.reasons[3], HyphenReason::ConsonantVowel);
+        assert_eq!(explanation.reasons[6], HyphenReason::CompoundBoundary);
+        assert_eq!(explanation.reasons[9], HyphenReason::ConsonantVowel);
+        assert_eq!(explanation.reasons[1], HyphenReason::NoRule);
+    }
+
+    #[test]
+    fn explain_suppressed_points() {
+        let opts = HyphenatorOptions {
+            ugly_hyphenation: false,
+            ..Default::default()
+        };
+        let hyp = FinnishHyphenator::new(NullAnalyzer, opts);
+        let explanation = hyp.explain(&chars("ovi"));
+        assert_eq!(explanation.pattern, "   ");
+        assert_eq!(explanation.reasons[1], HyphenReason::UglyEdge);
+
+        let hyp = FinnishHyphenator::new(NullAnalyzer, HyphenatorOptions::default());
+        let explanation = hyp.explain(&chars("azha"));
+        assert_eq!(explanation.reasons[1], HyphenReason::LongConsonant);
+        assert_eq!(explanation.reasons[2], HyphenReason::LongConsonant);
+    }
+
+    #[test]
+    fn explain_unknown_and_short_words() {
+        let opts = HyphenatorOptions {
+            hyphenate_unknown: false,
+            ..Default::default()
+        };
+        let hyp = FinnishHyphenator::new(NullAnalyzer, opts);
+        let explanation = hyp.explain(&chars("koira"));
+        assert_eq!(explanation.pattern, "     ");
+        assert_eq!(explanation.reasons[3], HyphenReason::UnknownWord);
+
+        let hyp = FinnishHyphenator::new(NullAnalyzer, HyphenatorOptions::default());
+        assert_eq!(
+            hyp.explain(&chars("a")).reasons,
+            vec![HyphenReason::TooShort]
+        );
+
+        let opts = HyphenatorOptions {
+            ugly_hyphenation: false,
+            ..Default::default()
+        };
+        let hyp = FinnishHyphenator::new(NullAnalyzer, opts);
+        assert_eq!(
+            hyp.explain(&chars("kissa2")).reasons[3],
+            HyphenReason::NotAWord
+        );
+    }
+
+    #[test]
+    fn explain_disagreeing_analyses() {
+        let mut analyzer = MockAnalyzer::new();
+        analyzer.add_word("koira", &["=pp=ppp", "=ppp=pp"]);
+        let hyp = FinnishHyphenator::new(analyzer, HyphenatorOptions::default());
+        let explanation = hyp.explain(&chars("koira"));
+        assert_eq!(explanation.pattern, "   - ");
+        assert_eq!(explanation.reasons[2], HyphenReason::AnalysesDisagree);
+        assert_eq!(explanation.reasons[3], HyphenReason::ConsonantVowel);
+    }
 }
@@ -0,0 +1,274 @@
+// Optional input mapping of typographic character variants
+//
+// Words containing superscripts (m²), subscripts (H₂O), vulgar fractions
+// (½) or typographic digits (full-width ２, mathematical 𝟐) are unknown to
+// the dictionary. When enabled, this stage replaces characters of the
+// selected classes with their compatibility decompositions before the
+// speller and the analyzer see the word. The mapping records which input
+// character produced each output character, so results that are indexed
+// by character (such as STRUCTURE) can be projected back onto the
+// original word.
+
+/// Character classes to map before spell checking and analysis.
+///
+/// All classes are off by default, which keeps the C++ behavior.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputMapping {
+    /// Superscript digits and signs: `²` -> `2`, `⁻` -> `−`.
+    pub superscripts: bool,
+    /// Subscript digits and signs: `₂` -> `2`.
+    pub subscripts: bool,
+    /// Vulgar fractions: `½` -> `1/2`.
+    pub fractions: bool,
+    /// Full-width and mathematical digits: `２`, `𝟐` -> `2`.
+    pub typographic_digits: bool,
+}
+
+impl InputMapping {
+    /// Map all supported classes.
+    pub const ALL: InputMapping = InputMapping {
+        superscripts: true,
+        subscripts: true,
+        fractions: true,
+        typographic_digits: true,
+    };
+
+    /// Return whether any class is enabled.
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Map `word`, recording the source of each output character.
+    pub fn apply(&self, word: &[char]) -> MappedWord {
+        let mut chars = Vec::with_capacity(word.len());
+        let mut sources = Vec::with_capacity(word.len());
+        for (i, &c) in word.iter().enumerate() {
+            match self.map_char(c) {
+                Some(Replacement::Char(r)) => {
+                    chars.push(r);
+                    sources.push(i);
+                }
+                Some(Replacement::Str(s)) => {
+                    for r in s.chars() {
+                        chars.push(r);
+                        sources.push(i);
+                    }
+                }
+                None => {
+                    chars.push(c);
+                    sources.push(i);
+                }
+            }
+        }
+        MappedWord {
+            chars,
+            sources,
+            original_len: word.len(),
+        }
+    }
+
+    fn map_char(&self, c: char) -> Option<Replacement> {
+        if self.superscripts {
+            if let Some(r) = superscript(c) {
+                return Some(Replacement::Char(r));
+            }
+        }
+        if self.subscripts {
+            if let Some(r) = subscript(c) {
+                return Some(Replacement::Char(r));
+            }
+        }
+        if self.fractions {
+            if let Some(r) = fraction(c) {
+                return Some(Replacement::Str(r));
+            }
+        }
+        if self.typographic_digits {
+            if let Some(r) = typographic_digit(c) {
+                return Some(Replacement::Char(r));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Replacement {
+    Char(char),
+    Str(&'static str),
+}
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '\u{2070}' => '0',
+        '\u{00B9}' => '1',
+        '\u{00B2}' => '2',
+        '\u{00B3}' => '3',
+        '\u{2074}'..='\u{2079}' => offset_digit(c, '\u{2074}', 4),
+        '\u{207A}' => '+',
+        '\u{207B}' => '\u{2212}',
+        '\u{207C}' => '=',
+        '\u{207D}' => '(',
+        '\u{207E}' => ')',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '\u{2080}'..='\u{2089}' => offset_digit(c, '\u{2080}', 0),
+        '\u{208A}' => '+',
+        '\u{208B}' => '\u{2212}',
+        '\u{208C}' => '=',
+        '\u{208D}' => '(',
+        '\u{208E}' => ')',
+        _ => return None,
+    })
+}
+
+/// The decompositions use U+2044 FRACTION SLASH; a plain slash is what the
+/// dictionary knows.
+fn fraction(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\u{00BC}' => "1/4",
+        '\u{00BD}' => "1/2",
+        '\u{00BE}' => "3/4",
+        '\u{2150}' => "1/7",
+        '\u{2151}' => "1/9",
+        '\u{2152}' => "1/10",
+        '\u{2153}' => "1/3",
+        '\u{2154}' => "2/3",
+        '\u{2155}' => "1/5",
+        '\u{2156}' => "2/5",
+        '\u{2157}' => "3/5",
+        '\u{2158}' => "4/5",
+        '\u{2159}' => "1/6",
+        '\u{215A}' => "5/6",
+        '\u{215B}' => "1/8",
+        '\u{215C}' => "3/8",
+        '\u{215D}' => "5/8",
+        '\u{215E}' => "7/8",
+        '\u{2189}' => "0/3",
+        _ => return None,
+    })
+}
+
+fn typographic_digit(c: char) -> Option<char> {
+    match c {
+        '\u{FF10}'..='\u{FF19}' => Some(offset_digit(c, '\u{FF10}', 0)),
+        // Mathematical bold, double-struck, sans-serif, sans-serif bold and
+        // monospace digits: five runs of ten.
+        '\u{1D7CE}'..='\u{1D7FF}' => Some(offset_digit(c, '\u{1D7CE}', 0)),
+        _ => None,
+    }
+}
+
+/// The digit `value_of_first + (c - first)`, modulo 10.
+fn offset_digit(c: char, first: char, value_of_first: u32) -> char {
+    let n = (c as u32 - first as u32 + value_of_first) % 10;
+    char::from(b'0' + n as u8)
+}
+
+/// A word after input mapping, with the map back to the original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedWord {
+    /// The mapped characters.
+    pub chars: Vec<char>,
+    /// Index of the original character each mapped character came from.
+    sources: Vec<usize>,
+    original_len: usize,
+}
+
+impl MappedWord {
+    /// Return whether the mapping changed nothing.
+    pub fn is_identity(&self) -> bool {
+        self.chars.len() == self.original_len
+            && self.sources.iter().enumerate().all(|(i, &s)| i == s)
+    }
+
+    /// Original character index of mapped position `i`. `i` may equal the
+    /// mapped length, which maps to the original length.
+    pub fn original_index(&self, i: usize) -> usize {
+        self.sources.get(i).copied().unwrap_or(self.original_len)
+    }
+
+    /// Project a STRUCTURE string of the mapped word onto the original word.
+    ///
+    /// STRUCTURE has one letter per character plus `=` boundary markers.
+    /// Each original character keeps the letter of the first character it
+    /// was mapped to; boundaries inside an expanded character are dropped.
+    pub fn original_structure(&self, structure: &str) -> String {
+        let mut result = String::with_capacity(structure.len());
+        let mut pos = 0;
+        for c in structure.chars() {
+            let starts_original = pos == 0
+                || (pos < self.sources.len() && self.sources[pos] != self.sources[pos - 1]);
+            if c == '=' {
+                if starts_original || pos >= self.sources.len() {
+                    result.push(c);
+                }
+                continue;
+            }
+            if starts_original {
+                result.push(c);
+            }
+            pos += 1;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn mapped(mapping: InputMapping, s: &str) -> String {
+        mapping.apply(&chars(s)).chars.into_iter().collect()
+    }
+
+    #[test]
+    fn default_maps_nothing() {
+        let m = InputMapping::default().apply(&chars("m² ½ ２"));
+        assert!(m.is_identity());
+        assert!(!InputMapping::default().is_enabled());
+    }
+
+    #[test]
+    fn classes_are_independent() {
+        let sup = InputMapping {
+            superscripts: true,
+            ..Default::default()
+        };
+        assert_eq!(mapped(sup, "m²H₂O"), "m2H₂O");
+        assert_eq!(mapped(InputMapping::ALL, "m²H₂O"), "m2H2O");
+        assert_eq!(mapped(InputMapping::ALL, "x⁻¹"), "x\u{2212}1");
+        assert_eq!(mapped(InputMapping::ALL, "２０２４"), "2024");
+        assert_eq!(mapped(InputMapping::ALL, "𝟐𝟘𝟮𝟾"), "2028");
+        assert_eq!(mapped(InputMapping::ALL, "koira"), "koira");
+    }
+
+    #[test]
+    fn fractions_expand_with_offsets() {
+        let m = InputMapping::ALL.apply(&chars("2½-vuotias"));
+        let text: String = m.chars.iter().collect();
+        assert_eq!(text, "21/2-vuotias");
+        assert!(!m.is_identity());
+        assert_eq!(m.original_index(0), 0);
+        assert_eq!(m.original_index(3), 1);
+        assert_eq!(m.original_index(4), 2);
+        assert_eq!(m.original_index(m.chars.len()), 10);
+    }
+
+    #[test]
+    fn structure_projects_onto_original() {
+        let m = InputMapping::ALL.apply(&chars("½-osa"));
+        // "1/2-osa" analyzed as "=ppp-=ppp"; the expanded fraction collapses.
+        assert_eq!(m.original_structure("=ppp-=ppp"), "=p-=ppp");
+        let identity = InputMapping::ALL.apply(&chars("koira"));
+        assert_eq!(identity.original_structure("=ppppp"), "=ppppp");
+    }
+}
@@ -0,0 +1,260 @@
+// Invisible format and control characters in input text
+//
+// Text copied from web pages and word processors carries characters that
+// render as nothing: zero-width spaces and joiners, byte order marks,
+// directional marks and stray control characters. The tokenizer classifies
+// them as Unknown, so a zero-width space inside "koira" splits it into two
+// words that both fail spell checking although the text looks correct.
+//
+// With `InvisibleChars::Strip` these characters are removed before
+// tokenization, spell checking and grammar checking. Positions are mapped
+// back onto the original text, and a removed character becomes part of the
+// token before it (or the first token, at the start of the text), so the
+// tokens still cover the original text without gaps.
+
+use std::borrow::Cow;
+
+#[cfg(feature = "handle")]
+use voikko_core::token::Sentence;
+use voikko_core::token::Token;
+
+use crate::marked_text::{MarkedText, PlainText};
+use crate::tokenizer;
+
+/// Handling of invisible characters (see [`is_invisible`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvisibleChars {
+    /// Tokenize them as Unknown, splitting words (the C++ behavior).
+    #[default]
+    Keep,
+    /// Remove them before checking; results refer to the original text.
+    Strip,
+}
+
+/// Return whether `c` is an invisible format or control character.
+///
+/// These are zero-width spaces, joiners and non-joiners, the byte order
+/// mark, directional marks, embeddings and isolates, and control characters
+/// other than the tab, line break and page break characters the tokenizer
+/// treats as whitespace. The soft hyphen is not included: it marks a
+/// hyphenation point and is handled by the tokenizer and the speller.
+pub fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0000}'..='\u{0008}'
+            | '\u{000E}'..='\u{001F}'
+            | '\u{007F}'..='\u{0084}'
+            | '\u{0086}'..='\u{009F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+/// Mark the runs of invisible characters in `text` as ignored.
+pub(crate) fn mark_invisible(text: &mut MarkedText) {
+    let chars: Vec<char> = text.text().chars().collect();
+    let mut pos = 0;
+    while pos < chars.len() {
+        if is_invisible(chars[pos]) {
+            let start = pos;
+            while pos < chars.len() && is_invisible(chars[pos]) {
+                pos += 1;
+            }
+            text.ignore(start, pos);
+        } else {
+            pos += 1;
+        }
+    }
+}
+
+/// Remove invisible characters from `text`, or return `None` if it has
+/// none.
+pub(crate) fn strip(text: &str) -> Option<PlainText> {
+    if !text.chars().any(is_invisible) {
+        return None;
+    }
+    let mut marked = MarkedText::new(text);
+    mark_invisible(&mut marked);
+    Some(marked.plain_text())
+}
+
+/// Remove invisible characters from a single word.
+pub(crate) fn strip_word(word: &str) -> Cow<'_, str> {
+    if word.chars().any(is_invisible) {
+        Cow::Owned(word.chars().filter(|&c| !is_invisible(c)).collect())
+    } else {
+        Cow::Borrowed(word)
+    }
+}
+
+/// Start of each item of `lens` (consecutive lengths in `plain`) in the
+/// original text. The first item starts at 0, so that removed characters
+/// before it are not lost.
+fn original_starts(plain: &PlainText, lens: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut plain_pos = 0;
+    let mut starts = Vec::new();
+    for len in lens {
+        starts.push(if plain_pos == 0 {
+            0
+        } else {
+            plain.original_range(plain_pos, 0).0
+        });
+        plain_pos += len;
+    }
+    starts
+}
+
+/// Tokenize `text`, skipping invisible characters.
+///
+/// Tokens are classified on the stripped text; each token's text, position
+/// and offsets refer to `text` and include the invisible characters that
+/// follow it.
+pub(crate) fn tokenize_stripped(text: &str) -> Vec<Token> {
+    let Some(plain) = strip(text) else {
+        return tokenizer::tokenize(text);
+    };
+    if plain.chars.is_empty() {
+        return tokenizer::tokenize(text);
+    }
+    let plain_text: String = plain.chars.iter().collect();
+    let plain_tokens = tokenizer::tokenize(&plain_text);
+    let starts = original_starts(&plain, plain_tokens.iter().map(|t| t.token_len));
+
+    let original: Vec<char> = text.chars().collect();
+    let mut result = Vec::with_capacity(plain_tokens.len());
+    let mut byte_pos = 0;
+    let mut utf16_pos = 0;
+    for (i, token) in plain_tokens.iter().enumerate() {
+        let start = starts[i];
+        let end = starts.get(i + 1).copied().unwrap_or(original.len());
+        let token_text: String = original[start..end].iter().collect();
+        let token = Token::new(token.token_type, token_text, start).at_offsets(byte_pos, utf16_pos);
+        byte_pos = token.end_byte;
+        utf16_pos = token.end_utf16;
+        result.push(token);
+    }
+    result
+}
+
+/// Map sentences found in the stripped text onto the original text.
+///
+/// Sentence lengths are extended to cover the invisible characters that
+/// follow each sentence, so the lengths still add up to the text length.
+#[cfg(feature = "handle")]
+pub(crate) fn map_sentences(plain: &PlainText, sentences: Vec<Sentence>) -> Vec<Sentence> {
+    let starts = original_starts(plain, sentences.iter().map(|s| s.sentence_len));
+    let mut total = 0;
+    let original_len = plain.original_range(plain.chars.len(), 0).0;
+    sentences
+        .into_iter()
+        .enumerate()
+        .map(|(i, sentence)| {
+            let end = starts.get(i + 1).copied().unwrap_or(original_len);
+            let len = end - total;
+            total = end;
+            Sentence::new(sentence.sentence_type, len)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::enums::{SentenceType, TokenType};
+
+    fn texts(tokens: &[Token]) -> Vec<(TokenType, &str)> {
+        tokens
+            .iter()
+            .map(|t| (t.token_type, t.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn visible_characters_are_not_invisible() {
+        for c in [
+            'a', ' ', '\t', '\n', '\r', '\u{00A0}', '\u{00AD}', '\u{2011}',
+        ] {
+            assert!(!is_invisible(c), "{c:?}");
+        }
+        for c in [
+            '\u{0}', '\u{7}', '\u{200B}', '\u{200D}', '\u{2060}', '\u{FEFF}',
+        ] {
+            assert!(is_invisible(c), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn keep_splits_word_at_zero_width_space() {
+        let tokens = tokenizer::tokenize("koi\u{200B}ra");
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[1].token_type, TokenType::Unknown);
+    }
+
+    #[test]
+    fn zero_width_space_stays_inside_word() {
+        // A long word from a web page, with a line break opportunity
+        let text = "Lentokone\u{200B}suihkumoottori lähti.";
+        let tokens = tokenize_stripped(text);
+        assert_eq!(
+            texts(&tokens),
+            [
+                (TokenType::Word, "Lentokone\u{200B}suihkumoottori"),
+                (TokenType::Whitespace, " "),
+                (TokenType::Word, "lähti"),
+                (TokenType::Punctuation, "."),
+            ]
+        );
+        assert_eq!(tokens[1].pos, 24);
+        assert_eq!(tokens[1].start_byte, 26);
+        assert_eq!(tokens[2].start_utf16, 25);
+    }
+
+    #[test]
+    fn byte_order_mark_belongs_to_first_token() {
+        let tokens = tokenize_stripped("\u{FEFF}Hei maailma");
+        assert_eq!(tokens[0].text, "\u{FEFF}Hei");
+        assert_eq!(tokens[0].token_type, TokenType::Word);
+        assert_eq!(tokens[0].token_len, 4);
+        assert_eq!(tokens[1].pos, 4);
+    }
+
+    #[test]
+    fn tokens_cover_the_original_text() {
+        let text = "\u{202A}Sana\u{200C}\u{200D} toinen\u{7}sana\u{202C}";
+        let tokens = tokenize_stripped(text);
+        let joined: String = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(joined, text);
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[2].text, "toinen\u{7}sana\u{202C}");
+    }
+
+    #[test]
+    fn only_invisible_characters_keep_unknown_tokens() {
+        let tokens = tokenize_stripped("\u{200B}\u{200B}");
+        assert!(tokens.iter().all(|t| t.token_type == TokenType::Unknown));
+    }
+
+    #[test]
+    fn strip_word_removes_invisible_characters() {
+        assert_eq!(strip_word("koi\u{00AD}ra"), "koi\u{00AD}ra");
+        assert!(matches!(strip_word("koira"), Cow::Borrowed(_)));
+        assert_eq!(strip_word("\u{FEFF}koi\u{200B}ra"), "koira");
+    }
+
+    #[cfg(feature = "handle")]
+    #[test]
+    fn sentence_lengths_cover_the_original_text() {
+        let text = "Yksi.\u{200B} Kaksi\u{200B}.";
+        let plain = strip(text).unwrap();
+        let sentences = vec![
+            Sentence::new(SentenceType::Probable, 6),
+            Sentence::new(SentenceType::None, 6),
+        ];
+        let mapped = map_sentences(&plain, sentences);
+        assert_eq!(mapped[0].sentence_len, 7);
+        assert_eq!(mapped[1].sentence_len, 7);
+    }
+}
@@ -0,0 +1,152 @@
+// Phonological tables of Finnish used by the hyphenator and the suggestion
+// generators.
+//
+// The C++ code hardcodes these tables in the hyphenator and the generators.
+// Here they live in one `LanguageRules` value whose default is the standard
+// Finnish tables; a caller can change it for dialectal or domain-specific
+// text (for example loanword clusters that must not be hyphenated) and
+// pass it to `FinnishHyphenator::with_rules` and
+// `typing_strategy_with_rules`, or to `VoikkoHandle::set_language_rules`.
+
+#[cfg(any(feature = "hyphenate", feature = "suggest"))]
+use std::sync::{Arc, LazyLock};
+
+/// Back vowels used in Finnish vowel harmony (lowercase + uppercase).
+///
+/// Origin: SuggestionGeneratorVowelChange.cpp:35, SuggestionGeneratorSwap.cpp:38
+const BACK_VOWELS: &[char] = &['a', 'o', 'u', 'A', 'O', 'U'];
+
+/// Front vowels corresponding to back vowels (same index order).
+///
+/// Origin: SuggestionGeneratorVowelChange.cpp:36, SuggestionGeneratorSwap.cpp:39
+const FRONT_VOWELS: &[char] = &['\u{00E4}', '\u{00F6}', 'y', '\u{00C4}', '\u{00D6}', 'Y'];
+
+/// Vowel pairs that may be split by a hyphen in Finnish.
+/// These are vowel combinations that do NOT form diphthongs and can be separated.
+///
+/// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:42-44 (SPLIT_VOWELS)
+const SPLIT_VOWELS: &[[char; 2]] = &[
+    ['a', 'e'],
+    ['a', 'o'],
+    ['e', 'a'],
+    ['e', 'o'],
+    ['i', 'a'],
+    ['i', 'o'],
+    ['o', 'a'],
+    ['o', 'e'],
+    ['u', 'a'],
+    ['u', 'e'],
+    ['y', 'e'],
+    ['e', '\u{00E4}'], // eä
+    ['e', '\u{00F6}'], // eö
+    ['i', '\u{00E4}'], // iä
+    ['i', '\u{00F6}'], // iö
+    ['y', '\u{00E4}'], // yä
+    ['\u{00E4}', 'e'], // äe
+    ['\u{00F6}', 'e'], // öe
+];
+
+/// Vowel pair patterns after which a following vowel may be split (VV-V rule).
+/// Only applied in ugly hyphenation mode.
+///
+/// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:46 (SPLIT_AFTER)
+const SPLIT_AFTER: &[[char; 2]] = &[['i', 'e'], ['a', 'i']];
+
+/// Long consonant sequences treated as indivisible units.
+/// A hyphen should be moved before the entire cluster rather than splitting it.
+///
+/// The C++ table has both L"shtsh" (ASCII 's','h','t','s','h') and
+/// L"štš" ('š','t','š'), and likewise "tsh" and "tš".
+///
+/// Origin: AnalyzerToFinnishHyphenatorAdapter.cpp:45 (LONG_CONSONANTS)
+const LONG_CONSONANTS: &[&str] = &["\u{0161}t\u{0161}", "shtsh", "tsh", "t\u{0161}", "zh"];
+
+/// The default rules, shared by hyphenators and strategies built without
+/// rules of their own.
+#[cfg(any(feature = "hyphenate", feature = "suggest"))]
+static DEFAULT_RULES: LazyLock<Arc<LanguageRules>> =
+    LazyLock::new(|| Arc::new(LanguageRules::default()));
+
+/// Vowel harmony, diphthong and consonant cluster tables.
+///
+/// The default is standard Finnish, as in the C++ library. Vowels and
+/// clusters are matched against the lowercased word in the hyphenator;
+/// the harmony tables are matched as given, so they list uppercase vowels
+/// too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageRules {
+    /// Back vowels of vowel harmony. The vowel at each index corresponds to
+    /// the front vowel at the same index of `front_vowels`.
+    pub back_vowels: Vec<char>,
+    /// Front vowels of vowel harmony, paired with `back_vowels`.
+    pub front_vowels: Vec<char>,
+    /// Vowel pairs that do not form a diphthong, so a hyphen may separate
+    /// them ("ea" in "te-atteri").
+    pub split_vowels: Vec<[char; 2]>,
+    /// Diphthongs after which a following vowel may be split off, with
+    /// ugly hyphenation only.
+    pub split_after: Vec<[char; 2]>,
+    /// Consonant clusters that a hyphen never splits; a break inside one
+    /// moves before it.
+    pub long_consonants: Vec<Vec<char>>,
+}
+
+impl Default for LanguageRules {
+    fn default() -> Self {
+        Self {
+            back_vowels: BACK_VOWELS.to_vec(),
+            front_vowels: FRONT_VOWELS.to_vec(),
+            split_vowels: SPLIT_VOWELS.to_vec(),
+            split_after: SPLIT_AFTER.to_vec(),
+            long_consonants: LONG_CONSONANTS
+                .iter()
+                .map(|cluster| cluster.chars().collect())
+                .collect(),
+        }
+    }
+}
+
+impl LanguageRules {
+    /// The vowel harmony counterpart of `c`: the front vowel of a back
+    /// vowel and the back vowel of a front vowel, or `None` for other
+    /// characters.
+    pub fn harmony_counterpart(&self, c: char) -> Option<char> {
+        if let Some(i) = self.back_vowels.iter().position(|&v| v == c) {
+            return self.front_vowels.get(i).copied();
+        }
+        let i = self.front_vowels.iter().position(|&v| v == c)?;
+        self.back_vowels.get(i).copied()
+    }
+
+    /// Shared instance of the default rules.
+    #[cfg(any(feature = "hyphenate", feature = "suggest"))]
+    pub(crate) fn shared_default() -> Arc<Self> {
+        Arc::clone(&DEFAULT_RULES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harmony_counterparts_go_both_ways() {
+        let rules = LanguageRules::default();
+        assert_eq!(rules.harmony_counterpart('a'), Some('ä'));
+        assert_eq!(rules.harmony_counterpart('ö'), Some('o'));
+        assert_eq!(rules.harmony_counterpart('Y'), Some('U'));
+        assert_eq!(rules.harmony_counterpart('e'), None);
+        assert_eq!(rules.harmony_counterpart('k'), None);
+    }
+
+    #[test]
+    fn unpaired_vowels_have_no_counterpart() {
+        let rules = LanguageRules {
+            back_vowels: vec!['a', 'o'],
+            front_vowels: vec!['ä'],
+            ..LanguageRules::default()
+        };
+        assert_eq!(rules.harmony_counterpart('a'), Some('ä'));
+        assert_eq!(rules.harmony_counterpart('o'), None);
+    }
+}
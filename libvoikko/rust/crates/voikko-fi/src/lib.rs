@@ -1,14 +1,36 @@
-pub(crate) mod finnish;
+#[cfg(feature = "handle")]
+pub mod dict_path;
+#[cfg(feature = "dictpack")]
+pub mod dictpack;
+#[cfg(feature = "spell")]
+pub mod document;
 #[cfg(feature = "grammar")]
 pub mod grammar;
 #[cfg(feature = "handle")]
 pub mod handle;
 #[cfg(feature = "hyphenate")]
 pub mod hyphenator;
+pub mod input_map;
+pub mod invisible;
+pub mod language_rules;
+pub mod marked_text;
 #[cfg(feature = "analyze")]
 pub mod morphology;
+pub mod prelude;
+#[cfg(feature = "handle")]
+pub mod spell_service;
+#[cfg(feature = "handle")]
+pub mod spell_session;
 #[cfg(feature = "spell")]
 pub mod speller;
+#[cfg(feature = "handle")]
+pub mod stream;
 #[cfg(feature = "suggest")]
 pub mod suggestion;
+#[cfg(test)]
+pub(crate) mod test_util;
 pub mod tokenizer;
+#[cfg(feature = "spell")]
+pub mod user_state;
+#[cfg(feature = "handle")]
+mod warm_up;
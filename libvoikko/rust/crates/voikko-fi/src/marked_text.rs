@@ -0,0 +1,265 @@
+// Rich text input: text with markup spans to skip
+//
+// HTML and Markdown contain markup that the grammar checker must not see:
+// tags, emphasis markers, link targets. A `MarkedText` is the original text
+// plus the character spans holding markup. Each span is either removed or
+// replaced by a stand-in, e.g. a line break for `<p>` so that paragraphs
+// stay apart. Checking runs on the resulting plain text, and every plain
+// character remembers the original characters it came from, so positions
+// can be projected back onto the original string.
+
+use std::ops::Range;
+
+/// A markup span of a [`MarkedText`]: the characters `start..end` of the
+/// original text, seen by the checker as `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkupSpan {
+    /// First character of the markup.
+    pub start: usize,
+    /// End of the markup (exclusive).
+    pub end: usize,
+    /// Text checked in place of the markup; empty to remove it.
+    pub replacement: String,
+}
+
+/// Text with markup spans that checking skips.
+///
+/// Positions are character offsets into the text, as in
+/// [`GrammarError::start_pos`](voikko_core::grammar_error::GrammarError).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkedText {
+    text: String,
+    spans: Vec<MarkupSpan>,
+}
+
+impl MarkedText {
+    /// Create a marked text without markup spans.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Skip the characters `start..end`, e.g. an inline tag such as `<b>`
+    /// inside a word.
+    pub fn ignore(&mut self, start: usize, end: usize) -> &mut Self {
+        self.ignore_as(start, end, "")
+    }
+
+    /// Check `replacement` in place of the characters `start..end`, e.g.
+    /// `"\n"` for a `<p>` tag or `" "` for `<br>`.
+    pub fn ignore_as(
+        &mut self,
+        start: usize,
+        end: usize,
+        replacement: impl Into<String>,
+    ) -> &mut Self {
+        self.spans.push(MarkupSpan {
+            start,
+            end,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    /// The original text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The markup spans, in the order they were added.
+    pub fn spans(&self) -> &[MarkupSpan] {
+        &self.spans
+    }
+
+    /// Build the text that is checked.
+    ///
+    /// Spans are applied in order of their start; a span that starts inside
+    /// an earlier one is cut to begin after it. Spans reaching past the end
+    /// of the text are cut at the end.
+    pub fn plain_text(&self) -> PlainText {
+        let original: Vec<char> = self.text.chars().collect();
+        let len = original.len();
+        let mut spans: Vec<&MarkupSpan> = self.spans.iter().collect();
+        spans.sort_by_key(|span| span.start);
+
+        let mut chars = Vec::with_capacity(len);
+        let mut sources = Vec::with_capacity(len);
+        let mut pos = 0;
+        for span in spans {
+            let start = span.start.clamp(pos, len);
+            let end = span.end.clamp(start, len);
+            for (i, &c) in original.iter().enumerate().take(start).skip(pos) {
+                chars.push(c);
+                sources.push(i..i + 1);
+            }
+            for c in span.replacement.chars() {
+                chars.push(c);
+                sources.push(start..end);
+            }
+            pos = end;
+        }
+        for (i, &c) in original.iter().enumerate().skip(pos) {
+            chars.push(c);
+            sources.push(i..i + 1);
+        }
+
+        PlainText {
+            chars,
+            sources,
+            original_len: len,
+        }
+    }
+}
+
+/// The plain text of a [`MarkedText`], as returned by
+/// [`MarkedText::plain_text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlainText {
+    /// Characters seen by the checker.
+    pub chars: Vec<char>,
+    /// Original characters behind each plain character: the character
+    /// itself, or the whole span for a stand-in character.
+    sources: Vec<Range<usize>>,
+    original_len: usize,
+}
+
+impl PlainText {
+    /// Map `len` plain characters starting at `start` onto the original
+    /// text, returning the original `(start, len)`.
+    ///
+    /// The result covers everything between the first and the last mapped
+    /// character, including markup inside the range. An empty range maps to
+    /// an empty range in front of the original character at `start`.
+    pub fn original_range(&self, start: usize, len: usize) -> (usize, usize) {
+        let original_start = self
+            .sources
+            .get(start)
+            .map_or(self.original_len, |source| source.start);
+        if len == 0 {
+            return (original_start, 0);
+        }
+        let last = (start + len - 1).min(self.sources.len().saturating_sub(1));
+        let original_end = self
+            .sources
+            .get(last)
+            .map_or(self.original_len, |source| source.end);
+        (original_start, original_end.saturating_sub(original_start))
+    }
+}
+
+/// Mark the line breaks of hard-wrapped text so that its paragraphs are
+/// separated by blank lines instead of line breaks.
+///
+/// A line break inside a paragraph, together with the spaces around it, is
+/// checked as a single space. A break spanning a blank line, or one at the
+/// start or end of the text, is checked as a line break, ending the
+/// paragraph.
+pub(crate) fn mark_line_breaks(text: &mut MarkedText) {
+    let chars: Vec<char> = text.text().chars().collect();
+    let is_space = |c: char| matches!(c, ' ' | '\t' | '\r');
+    let mut pos = 0;
+    while let Some(offset) = chars[pos..].iter().position(|&c| c == '\n') {
+        let mut start = pos + offset;
+        while start > pos && is_space(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = pos + offset;
+        let mut line_breaks = 0;
+        while end < chars.len() && (chars[end] == '\n' || is_space(chars[end])) {
+            if chars[end] == '\n' {
+                line_breaks += 1;
+            }
+            end += 1;
+        }
+        let replacement = if line_breaks > 1 || start == 0 || end == chars.len() {
+            "\n"
+        } else {
+            " "
+        };
+        text.ignore_as(start, end, replacement);
+        pos = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(marked: &MarkedText) -> String {
+        marked.plain_text().chars.iter().collect()
+    }
+
+    #[test]
+    fn without_spans_text_is_unchanged() {
+        let marked = MarkedText::new("Koira haukkuu.");
+        let plain_text = marked.plain_text();
+        assert_eq!(plain(&marked), "Koira haukkuu.");
+        assert_eq!(plain_text.original_range(6, 7), (6, 7));
+    }
+
+    #[test]
+    fn ignored_spans_are_removed() {
+        // "Se <b>on</b> hyvä."
+        let mut marked = MarkedText::new("Se <b>on</b> hyvä.");
+        marked.ignore(3, 6).ignore(8, 12);
+        assert_eq!(plain(&marked), "Se on hyvä.");
+
+        let plain_text = marked.plain_text();
+        // "on" is at plain 3..5 and original 6..8
+        assert_eq!(plain_text.original_range(3, 2), (6, 2));
+        // "hyvä" follows both tags
+        assert_eq!(plain_text.original_range(6, 4), (13, 4));
+    }
+
+    #[test]
+    fn range_around_markup_covers_it() {
+        // "koi<i>ra</i>": the word is split by a tag
+        let mut marked = MarkedText::new("koi<i>ra</i>");
+        marked.ignore(3, 6).ignore(8, 12);
+        assert_eq!(plain(&marked), "koira");
+        assert_eq!(marked.plain_text().original_range(0, 5), (0, 8));
+    }
+
+    #[test]
+    fn replacement_maps_to_whole_span() {
+        let mut marked = MarkedText::new("Yksi.<br>Kaksi.");
+        marked.ignore_as(5, 9, "\n");
+        assert_eq!(plain(&marked), "Yksi.\nKaksi.");
+
+        let plain_text = marked.plain_text();
+        assert_eq!(plain_text.original_range(5, 1), (5, 4));
+        assert_eq!(plain_text.original_range(6, 6), (9, 6));
+    }
+
+    #[test]
+    fn spans_are_sorted_and_clamped() {
+        let mut marked = MarkedText::new("a<x>b<y>c");
+        marked.ignore(5, 8).ignore(1, 4).ignore(2, 6).ignore(9, 100);
+        // The overlapping 2..6 is cut to 4..6, removing "b<"; 9..100 is empty
+        assert_eq!(plain(&marked), "ac");
+    }
+
+    #[test]
+    fn line_breaks_join_lines_of_a_paragraph() {
+        let mut marked = MarkedText::new("Kissa istuu  \r\n  puussa.\n\nKoira\nhaukkuu.\n");
+        mark_line_breaks(&mut marked);
+        assert_eq!(plain(&marked), "Kissa istuu puussa.\nKoira haukkuu.\n");
+
+        let plain_text = marked.plain_text();
+        // The joining space covers the whole break
+        assert_eq!(plain_text.original_range(11, 1), (11, 6));
+        // "Koira" starts after the blank line
+        assert_eq!(plain_text.original_range(20, 5), (26, 5));
+    }
+
+    #[test]
+    fn empty_range_maps_to_position() {
+        let mut marked = MarkedText::new("<p>Hei");
+        marked.ignore(0, 3);
+        let plain_text = marked.plain_text();
+        assert_eq!(plain_text.original_range(0, 0), (3, 0));
+        assert_eq!(plain_text.original_range(3, 0), (6, 0));
+    }
+}
@@ -0,0 +1,433 @@
+// LRU cache of full-morphology analyses keyed by word
+//
+// Spelling, suggestions, hyphenation and grammar checking all analyze the
+// words of a text, and within one text the same words come up again and
+// again: a grammar check analyzes every token, the hyphenator analyzes the
+// words it breaks and the speller the words it checks. The analyzer keeps
+// the analyses of recently seen words so that these repeat lookups do not
+// traverse the transducer again. Like the speller cache, the cache is split
+// into independently locked shards; a thread that finds its shard in use
+// analyzes the word without the cache.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+
+use voikko_core::analysis::Analysis;
+
+/// Number of shards in an [`AnalysisCache`].
+const SHARD_COUNT: usize = 8;
+
+/// Slot index marking the end of the recency list.
+const NIL: usize = usize::MAX;
+
+/// Hit and miss counts of a cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that had to call the speller or the analyzer.
+    pub misses: u64,
+    /// Entries dropped to make room for new ones or after shrinking.
+    pub evictions: u64,
+    /// Number of cached words.
+    pub len: usize,
+    /// Maximum number of cached words.
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache, or 0.0 before the first
+    /// lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: CacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.evictions += other.evictions;
+        self.len += other.len;
+        self.capacity += other.capacity;
+    }
+}
+
+/// One cached word, linked into the recency list by slot index.
+struct Entry {
+    word: Box<[char]>,
+    /// Generation of the dictionary the analyses come from.
+    generation: u64,
+    analyses: Vec<Analysis>,
+    prev: usize,
+    next: usize,
+}
+
+/// One shard: a bounded map with least-recently-used eviction.
+struct Shard {
+    capacity: usize,
+    index: HashMap<Box<[char]>, usize>,
+    entries: Vec<Entry>,
+    /// Slots of evicted entries not yet reused.
+    free: Vec<usize>,
+    /// Most recently used slot.
+    head: usize,
+    /// Least recently used slot.
+    tail: usize,
+    stats: CacheStats,
+}
+
+impl Shard {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            index: HashMap::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            stats: CacheStats::default(),
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.index.len(),
+            capacity: self.capacity,
+            ..self.stats
+        }
+    }
+
+    /// The analyses of `word` from dictionary `generation`, marking it as
+    /// recently used. Counts a hit or a miss.
+    fn lookup(&mut self, word: &[char], generation: u64) -> Option<Vec<Analysis>> {
+        match self.index.get(word) {
+            Some(&slot) if self.entries[slot].generation == generation => {
+                self.stats.hits += 1;
+                self.unlink(slot);
+                self.push_front(slot);
+                Some(self.entries[slot].analyses.clone())
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store the analyses of `word` from dictionary `generation`, evicting
+    /// the least recently used word if the shard is full.
+    fn store(&mut self, word: &[char], generation: u64, analyses: &[Analysis]) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(&slot) = self.index.get(word) {
+            if self.entries[slot].generation > generation {
+                return;
+            }
+            self.entries[slot].generation = generation;
+            self.entries[slot].analyses = analyses.to_vec();
+            self.unlink(slot);
+            self.push_front(slot);
+            return;
+        }
+        if self.index.len() >= self.capacity {
+            self.evict();
+        }
+        let entry = Entry {
+            word: word.into(),
+            generation,
+            analyses: analyses.to_vec(),
+            prev: NIL,
+            next: NIL,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.entries[slot] = entry;
+                slot
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        self.index.insert(word.into(), slot);
+        self.push_front(slot);
+    }
+
+    /// Drop the least recently used word.
+    fn evict(&mut self) {
+        let slot = self.tail;
+        if slot == NIL {
+            return;
+        }
+        self.unlink(slot);
+        let entry = &mut self.entries[slot];
+        self.index.remove(&entry.word);
+        entry.word = Box::default();
+        entry.analyses = Vec::new();
+        self.free.push(slot);
+        self.stats.evictions += 1;
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.free.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Change the capacity, keeping the most recently used words that fit.
+    fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.index.len() > capacity {
+            self.evict();
+        }
+        if self.index.is_empty() {
+            self.clear();
+        }
+    }
+
+    /// Detach `slot` from the recency list.
+    fn unlink(&mut self, slot: usize) {
+        let Entry { prev, next, .. } = self.entries[slot];
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.entries[prev].next = next;
+        }
+        if next == NIL {
+            self.tail = prev;
+        } else {
+            self.entries[next].prev = prev;
+        }
+    }
+
+    /// Insert a detached `slot` as the most recently used entry.
+    fn push_front(&mut self, slot: usize) {
+        self.entries[slot].prev = NIL;
+        self.entries[slot].next = self.head;
+        if self.head == NIL {
+            self.tail = slot;
+        } else {
+            self.entries[self.head].prev = slot;
+        }
+        self.head = slot;
+    }
+}
+
+/// The full-morphology analyses of recently analyzed words, shared by
+/// every user of an analyzer.
+///
+/// Each word is stored with the generation of the dictionary it was
+/// analyzed with and found only by lookups with that generation, so an
+/// analysis that runs on a dictionary being replaced cannot answer for the
+/// new one.
+pub(crate) struct AnalysisCache {
+    shards: Vec<Mutex<Shard>>,
+    /// Maximum number of cached words; 0 when the cache is disabled.
+    capacity: usize,
+    /// Number of times the cache has been cleared. An analysis that
+    /// started before a clear is not stored.
+    clears: AtomicU64,
+}
+
+impl AnalysisCache {
+    /// Create a cache that holds at most `capacity` words in total, split
+    /// over the shards. A capacity of 0 disables the cache.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| Mutex::new(Shard::new(shard_capacity(capacity))))
+                .collect(),
+            capacity,
+            clears: AtomicU64::new(0),
+        }
+    }
+
+    /// Maximum number of cached words, as set; 0 when disabled.
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The analyses of `word` with dictionary `generation` from the cache,
+    /// or those `analyze` returns. `generation` must be read before
+    /// `analyze` takes the dictionary, so that its analyses come from that
+    /// dictionary or a newer one. `analyze` also tells whether its analyses
+    /// may be stored; analyses cut short by the traversal budget are not.
+    pub(crate) fn get_or_analyze(
+        &self,
+        word: &[char],
+        generation: u64,
+        analyze: impl FnOnce() -> (Vec<Analysis>, bool),
+    ) -> (Vec<Analysis>, bool) {
+        if self.capacity == 0 {
+            return analyze();
+        }
+        let shard = &self.shards[shard_index(word)];
+        let clears = self.clears.load(Ordering::SeqCst);
+        let cached = match shard.try_lock() {
+            Ok(mut shard) => shard.lookup(word, generation),
+            // A panic while holding the lock cannot come from the analyzer,
+            // which runs without it, so the recency list is intact.
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().lookup(word, generation),
+            Err(TryLockError::WouldBlock) => return analyze(),
+        };
+        if let Some(analyses) = cached {
+            return (analyses, true);
+        }
+        let (analyses, complete) = analyze();
+        if complete {
+            let mut shard = lock(shard);
+            if self.clears.load(Ordering::SeqCst) == clears {
+                shard.store(word, generation, &analyses);
+            }
+        }
+        (analyses, complete)
+    }
+
+    /// Combined statistics of all shards.
+    pub(crate) fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats::default();
+        for shard in &self.shards {
+            stats.add(lock(shard).stats());
+        }
+        stats
+    }
+
+    /// Hold at most `capacity` words, keeping the most recently used words
+    /// that fit. A capacity of 0 disables the cache.
+    pub(crate) fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        for shard in &mut self.shards {
+            shard
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .resize(shard_capacity(capacity));
+        }
+    }
+
+    /// Drop every cached word. Analyses running during the call are not
+    /// stored.
+    pub(crate) fn clear(&self) {
+        self.clears.fetch_add(1, Ordering::SeqCst);
+        for shard in &self.shards {
+            lock(shard).clear();
+        }
+    }
+}
+
+fn lock(shard: &Mutex<Shard>) -> MutexGuard<'_, Shard> {
+    shard.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Capacity of each shard for a total of `capacity` words.
+fn shard_capacity(capacity: usize) -> usize {
+    capacity.div_ceil(SHARD_COUNT)
+}
+
+fn shard_index(word: &[char]) -> usize {
+    let hash = word.iter().fold(0usize, |hash, &c| {
+        hash.wrapping_mul(37).wrapping_add(c as usize)
+    });
+    hash % SHARD_COUNT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn analysis(baseform: &str) -> Vec<Analysis> {
+        let mut analysis = Analysis::new();
+        analysis.set("BASEFORM", baseform);
+        vec![analysis]
+    }
+
+    #[test]
+    fn second_lookup_is_a_hit() {
+        let cache = AnalysisCache::with_capacity(64);
+        let word = chars("koira");
+        let mut calls = 0;
+        for _ in 0..2 {
+            let (analyses, _) = cache.get_or_analyze(&word, 0, || {
+                calls += 1;
+                (analysis("koira"), true)
+            });
+            assert_eq!(analyses, analysis("koira"));
+        }
+        assert_eq!(calls, 1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+    }
+
+    #[test]
+    fn incomplete_analyses_are_not_stored() {
+        let cache = AnalysisCache::with_capacity(64);
+        let word = chars("koira");
+        let (_, complete) = cache.get_or_analyze(&word, 0, || (Vec::new(), false));
+        assert!(!complete);
+        assert_eq!(cache.stats().len, 0);
+    }
+
+    #[test]
+    fn least_recently_used_word_is_evicted() {
+        let mut shard = Shard::new(2);
+        shard.store(&chars("a"), 0, &analysis("a"));
+        shard.store(&chars("b"), 0, &analysis("b"));
+        assert!(shard.lookup(&chars("a"), 0).is_some());
+        shard.store(&chars("c"), 0, &analysis("c"));
+        assert!(shard.lookup(&chars("b"), 0).is_none());
+        assert!(shard.lookup(&chars("a"), 0).is_some());
+        assert!(shard.lookup(&chars("c"), 0).is_some());
+        assert_eq!(shard.stats().evictions, 1);
+    }
+
+    #[test]
+    fn resize_keeps_recent_words_and_zero_disables() {
+        let mut cache = AnalysisCache::with_capacity(8 * 3);
+        let words: Vec<Vec<char>> = (0..100).map(|i| chars(&format!("w{i}"))).collect();
+        for word in &words {
+            cache.get_or_analyze(word, 0, || (analysis("x"), true));
+        }
+        assert!(cache.stats().len <= 24);
+        cache.resize(8);
+        assert!(cache.stats().len <= 8);
+        assert!(cache.stats().len > 0);
+        cache.resize(0);
+        assert_eq!(cache.capacity(), 0);
+        assert_eq!(cache.stats().len, 0);
+        cache.get_or_analyze(&words[0], 0, || (analysis("x"), true));
+        assert_eq!(cache.stats().len, 0);
+    }
+
+    #[test]
+    fn words_from_another_dictionary_are_not_found() {
+        let cache = AnalysisCache::with_capacity(64);
+        let word = chars("koira");
+        cache.get_or_analyze(&word, 0, || (analysis("koira"), true));
+        // A lookup on the reloaded dictionary analyzes the word again
+        let (analyses, _) = cache.get_or_analyze(&word, 1, || (Vec::new(), true));
+        assert!(analyses.is_empty());
+        let (analyses, _) = cache.get_or_analyze(&word, 1, || unreachable!());
+        assert!(analyses.is_empty());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 1));
+    }
+
+    #[test]
+    fn clear_drops_words() {
+        let cache = AnalysisCache::with_capacity(64);
+        cache.get_or_analyze(&chars("koira"), 0, || (analysis("koira"), true));
+        cache.clear();
+        assert_eq!(cache.stats().len, 0);
+    }
+}
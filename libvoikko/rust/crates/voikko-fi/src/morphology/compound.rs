@@ -0,0 +1,260 @@
+// Canonical compound segmentation of a word.
+//
+// Picks one analysis out of all analyses of a word -- the one that splits
+// the word into the fewest parts, like the hyphenator does when pruning
+// extra compound readings -- and reports its parts and base forms.
+
+use voikko_core::analysis::{ATTR_STRUCTURE, ATTR_WORDBASES, Analysis};
+
+/// Compound structure of a word, derived from a single analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundInfo {
+    /// Surface forms of the parts, in order. Hyphens joining the parts are
+    /// not included. A non-compound word has exactly one part.
+    pub parts: Vec<String>,
+    /// Base forms of the parts as given by the WORDBASES attribute, one per
+    /// part. Falls back to `parts` when the analysis carries no WORDBASES or
+    /// its WORDBASES has a different number of parts.
+    pub baseforms: Vec<String>,
+    /// `true` if any two parts are joined by a hyphen (e.g. "maa-auto").
+    pub is_hyphenated: bool,
+}
+
+impl CompoundInfo {
+    /// Return `true` if the word consists of more than one part.
+    pub fn is_compound(&self) -> bool {
+        self.parts.len() > 1
+    }
+}
+
+/// Return the compound structure of `word` from its `analyses`.
+///
+/// The analysis with the fewest parts is used; on a tie the first one wins.
+/// Returns `None` if no analysis has a STRUCTURE attribute (e.g. the word is
+/// unknown).
+pub fn compound_info(word: &str, analyses: &[Analysis]) -> Option<CompoundInfo> {
+    let word_chars: Vec<char> = word.chars().collect();
+    analyses
+        .iter()
+        .filter_map(|a| {
+            let structure = a.get(ATTR_STRUCTURE)?;
+            let (parts, is_hyphenated) = split_by_structure(&word_chars, structure);
+            Some((a, parts, is_hyphenated))
+        })
+        .min_by_key(|(_, parts, _)| parts.len())
+        .map(|(analysis, parts, is_hyphenated)| {
+            let baseforms = analysis
+                .get(ATTR_WORDBASES)
+                .map(parse_wordbases)
+                .filter(|b| b.len() == parts.len())
+                .unwrap_or_else(|| parts.clone());
+            CompoundInfo {
+                parts,
+                baseforms,
+                is_hyphenated,
+            }
+        })
+}
+
+/// Split `word` into parts at the boundaries marked in `structure`.
+///
+/// A `=` starts a new part; `-=` marks a hyphen in the word that joins two
+/// parts. Every other STRUCTURE character corresponds to one word character.
+fn split_by_structure(word: &[char], structure: &str) -> (Vec<String>, bool) {
+    let s: Vec<char> = structure.chars().collect();
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut is_hyphenated = false;
+    let mut sptr = 0;
+    let mut i = 0;
+
+    while sptr < s.len() && i < word.len() {
+        if s[sptr] == '-' && s.get(sptr + 1) == Some(&'=') {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                is_hyphenated = true;
+            }
+            sptr += 2;
+            i += 1;
+            continue;
+        }
+        if s[sptr] == '=' {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            sptr += 1;
+            continue;
+        }
+        current.push(word[i]);
+        sptr += 1;
+        i += 1;
+    }
+    // Characters not covered by STRUCTURE belong to the last part.
+    current.extend(&word[i..]);
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    (parts, is_hyphenated)
+}
+
+/// Extract the base forms from a WORDBASES value such as
+/// `+rauta(rauta)+tie(tie)+asema(asema)`, one per compound part. Parts
+/// without a parenthesized base form use their surface form; hyphen parts
+/// (`+-`) are skipped.
+///
+/// A derivational suffix is a group of its own whose base starts with `+`,
+/// as in `+kirjoitta(kirjoittaa)+ja(+ja)`. It belongs to the part before it:
+/// the base form of that part is the surface forms up to the suffix followed
+/// by the base of the suffix ("kirjoittaja").
+fn parse_wordbases(wordbases: &str) -> Vec<String> {
+    // (surface forms of the part so far, base form of the part)
+    let mut parts: Vec<(String, String)> = Vec::new();
+    for group in split_wordbase_groups(wordbases) {
+        if group.is_empty() || group == "-" {
+            continue;
+        }
+        let (surface, base) = match (group.find('('), group.ends_with(')')) {
+            (Some(open), true) => (&group[..open], &group[open + 1..group.len() - 1]),
+            _ => (group, group),
+        };
+        match (base.strip_prefix('+'), parts.last_mut()) {
+            (Some(suffix), Some((stem, part_base))) => {
+                *part_base = format!("{stem}{suffix}");
+                stem.push_str(surface);
+            }
+            _ => parts.push((surface.to_string(), base.to_string())),
+        }
+    }
+    parts.into_iter().map(|(_, base)| base).collect()
+}
+
+/// Split a WORDBASES value at the `+` signs that start a group, leaving
+/// those inside parentheses (`ja(+ja)`) alone.
+fn split_wordbase_groups(wordbases: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in wordbases.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '+' if depth == 0 => {
+                groups.push(&wordbases[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    groups.push(&wordbases[start..]);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(structure: &str, wordbases: Option<&str>) -> Analysis {
+        let mut a = Analysis::new();
+        a.set(ATTR_STRUCTURE, structure);
+        if let Some(wb) = wordbases {
+            a.set(ATTR_WORDBASES, wb);
+        }
+        a
+    }
+
+    #[test]
+    fn simple_word_has_one_part() {
+        let info = compound_info("koira", &[analysis("=ppppp", None)]).unwrap();
+        assert_eq!(info.parts, vec!["koira"]);
+        assert_eq!(info.baseforms, vec!["koira"]);
+        assert!(!info.is_compound());
+        assert!(!info.is_hyphenated);
+    }
+
+    #[test]
+    fn three_part_compound() {
+        let info = compound_info(
+            "rautatieasemalla",
+            &[analysis(
+                "=ppppp=ppp=pppppppp",
+                Some("+rauta(rauta)+tie(tie)+asemalla(asema)"),
+            )],
+        )
+        .unwrap();
+        assert_eq!(info.parts, vec!["rauta", "tie", "asemalla"]);
+        assert_eq!(info.baseforms, vec!["rauta", "tie", "asema"]);
+        assert!(info.is_compound());
+    }
+
+    #[test]
+    fn hyphenated_compound() {
+        let info = compound_info(
+            "maa-auto",
+            &[analysis("=ppp-=pppp", Some("+maa(maa)+-+auto(auto)"))],
+        )
+        .unwrap();
+        assert_eq!(info.parts, vec!["maa", "auto"]);
+        assert_eq!(info.baseforms, vec!["maa", "auto"]);
+        assert!(info.is_hyphenated);
+    }
+
+    #[test]
+    fn fewest_parts_wins() {
+        let analyses = [
+            analysis("=ppppp=ppppp", Some("+kuusi(kuusi)+kolme(kolme)")),
+            analysis("=pppppppppp", Some("+kuusikolme(kuusikolme)")),
+        ];
+        let info = compound_info("kuusikolme", &analyses).unwrap();
+        assert_eq!(info.parts, vec!["kuusikolme"]);
+        assert_eq!(info.baseforms, vec!["kuusikolme"]);
+    }
+
+    #[test]
+    fn no_structure_returns_none() {
+        assert_eq!(compound_info("xyz", &[]), None);
+        assert_eq!(compound_info("xyz", &[Analysis::new()]), None);
+    }
+
+    #[test]
+    fn wordbases_without_base_uses_surface() {
+        assert_eq!(parse_wordbases("+koira(koira)+n"), vec!["koira", "n"]);
+    }
+
+    #[test]
+    fn derivational_suffix_belongs_to_its_part() {
+        let info = compound_info(
+            "kirjoittajalle",
+            &[analysis(
+                "=pppppppppppppp",
+                Some("+kirjoitta(kirjoittaa)+ja(+ja)"),
+            )],
+        )
+        .unwrap();
+        assert_eq!(info.parts, vec!["kirjoittajalle"]);
+        assert_eq!(info.baseforms, vec!["kirjoittaja"]);
+    }
+
+    #[test]
+    fn derivational_suffixes_after_a_prefix() {
+        let info = compound_info(
+            "epäjärjestelmällisyys",
+            &[analysis(
+                "=ppp=pppppppppppppppppp",
+                Some("+epä+järjestelmä(järjestelmä)+llisy(+nen)+ys(+ys)"),
+            )],
+        )
+        .unwrap();
+        assert_eq!(info.parts, vec!["epä", "järjestelmällisyys"]);
+        assert_eq!(info.baseforms, vec!["epä", "järjestelmällisyys"]);
+    }
+
+    #[test]
+    fn wordbases_with_other_part_count_falls_back_to_parts() {
+        let info = compound_info(
+            "kuusikolme",
+            &[analysis("=pppppppppp", Some("+kuusi(kuusi)+kolme(kolme)"))],
+        )
+        .unwrap();
+        assert_eq!(info.baseforms, vec!["kuusikolme"]);
+    }
+}
@@ -7,7 +7,8 @@
 //
 // Origin: FinnishVfstAnalyzer.cpp (~1,179 lines)
 
-use std::cell::RefCell;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+
 use voikko_core::analysis::{
     ATTR_BASEFORM, ATTR_CLASS, ATTR_COMPARISON, ATTR_FOCUS, ATTR_FSTOUTPUT, ATTR_KYSYMYSLIITE,
     ATTR_MALAGA_VAPAA_JALKIOSA, ATTR_MOOD, ATTR_NEGATIVE, ATTR_NUMBER, ATTR_PARTICIPLE,
@@ -17,26 +18,210 @@ use voikko_core::analysis::{
 
 use voikko_core::case::CaseType;
 use voikko_core::enums::MAX_WORD_CHARS;
-use voikko_fst::Transducer;
-use voikko_fst::config::UnweightedConfig;
+use voikko_fst::config::{TraversalBudget, UnweightedConfig};
 use voikko_fst::unweighted::UnweightedTransducer;
+use voikko_fst::{NextStatus, Transducer, VfstError};
 
-use super::Analyzer;
+use super::cache::{AnalysisCache, CacheStats};
+use super::generate::{EntryFilter, MAX_GENERATION_STEPS, matches_target, remove_unmarked_clitics};
+use super::layers::DictionaryRemovals;
+#[cfg(feature = "handle")]
+use super::prefixes::{PREFIX_FILTER_CHARS, PrefixFilter};
 use super::tag_parser::{
-    BUFFER_SIZE, BasicAttributes, MAX_ANALYSIS_COUNT, fix_structure, is_valid_analysis,
-    parse_baseform, parse_basic_attributes, parse_debug_attributes, parse_structure, starts_with,
+    BUFFER_SIZE, BasicAttributes, MAX_ANALYSIS_COUNT, apply_structure_case, fix_structure,
+    is_valid_analysis, parse_baseform, parse_basic_attributes, parse_debug_attributes,
+    parse_segments, parse_structure, starts_with,
 };
+use super::truncation::{TraversalStats, TruncatedLookup, TruncationReason, Truncations};
+use super::{Analyzer, CaseVariant, MAX_WORD_BYTES, lower_utf8};
+
+/// Readiness of a lazily loaded analyzer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadState {
+    /// The transducer has not been built yet.
+    Pending,
+    /// The transducer is built and ready for use.
+    Ready,
+    /// Building the transducer failed; every word is unknown.
+    Failed,
+}
+
+/// What a [`FinnishVfstAnalyzer`] includes in its analyses besides the
+/// attributes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalyzerOptions {
+    /// Record the unparsed transducer output of each analysis (see
+    /// [`Analysis::raw_fst_output`]), with the tags that the attributes do
+    /// not reflect, such as `[Ica]` and `[De]`. Unlike the FSTOUTPUT
+    /// attribute it is kept without full morphology.
+    pub include_raw: bool,
+}
 
 /// Finnish morphological analyzer using the VFST (Voikko Finite State Transducer) backend.
 ///
-/// Owns an unweighted transducer loaded from `mor.vfst` and its traversal configuration.
-/// The config is wrapped in `RefCell` for interior mutability so that the `Analyzer`
-/// trait (which requires `&self`) can be implemented without requiring `&mut self`.
+/// Owns an unweighted transducer loaded from `mor.vfst` and a pool of traversal
+/// configurations. Each analysis takes a configuration from the pool and returns
+/// it afterwards, so the `Analyzer` trait (which requires `&self`) can be
+/// implemented without `&mut self`, and concurrent analyses from several threads
+/// each get their own configuration.
+///
+/// An analyzer created with [`from_bytes_lazy`](Self::from_bytes_lazy) keeps
+/// the raw data and builds the transducer on first use, or when
+/// [`warm_up`](Self::warm_up) is called.
+///
+/// The transducer reads its transitions in place from the `mor.vfst` data.
+/// Analyzers created with [`from_shared`](Self::from_shared) from one
+/// `Arc<[u8]>` therefore hold a single copy of the dictionary between them.
+///
+/// [`reload`](Self::reload) replaces the transducer and its pool while the
+/// analyzer is in use. An analysis holds the transducer it started with
+/// until it finishes.
+///
+/// Extensions added with [`add_extension`](Self::add_extension) are layered
+/// on top of the dictionary (see [`DictionaryRemovals`] for the shadowing
+/// rules).
+///
+/// Analyses cut short by the traversal budget are counted (see
+/// [`traversal_stats`](Self::traversal_stats)).
+///
+/// The analyses of the most recently analyzed words are cached (see
+/// [`set_analysis_cache_capacity`](Self::set_analysis_cache_capacity)), so
+/// that the speller, hyphenator, suggestion generators and grammar checker
+/// analyzing the same word one after another look it up once.
 ///
 /// Origin: FinnishVfstAnalyzer.hpp, FinnishVfstAnalyzer.cpp
 pub struct FinnishVfstAnalyzer {
-    transducer: UnweightedTransducer,
-    config: RefCell<UnweightedConfig>,
+    /// The current dictionary. Lookups clone the `Arc`, so a reload does
+    /// not wait for them.
+    dictionary: RwLock<Arc<Dictionary>>,
+    /// Extension layers, in the order they were added.
+    extensions: Vec<Extension>,
+    /// Limits on each transducer lookup.
+    budget: TraversalBudget,
+    /// What the analyses include besides the attributes.
+    options: AnalyzerOptions,
+    /// Analyses cut short by the traversal budget.
+    truncations: Truncations,
+    /// Analyses of recently analyzed words.
+    cache: AnalysisCache,
+    /// Whether `may_analyze` checks the beginnings of the words.
+    #[cfg(feature = "handle")]
+    prefix_filter: bool,
+}
+
+/// Number of words whose analyses an analyzer caches by default.
+pub const DEFAULT_ANALYSIS_CACHE_WORDS: usize = 2048;
+
+/// Transducer paths enumerated per completion asked for: a word form is
+/// accepted along several paths, one for each analysis, and some paths
+/// end in a compound part that is not a word.
+const COMPLETION_PATHS_PER_WORD: usize = 8;
+
+/// A loaded `mor.vfst`: the transducer, built on demand for a lazy
+/// analyzer, and the configurations for traversing it.
+struct Dictionary {
+    /// Raw `mor.vfst` data of a lazy analyzer, handed to the transducer
+    /// once it is built.
+    pending: Mutex<Option<Arc<[u8]>>>,
+    transducer: OnceLock<Result<UnweightedTransducer, VfstError>>,
+    configs: Mutex<Vec<UnweightedConfig>>,
+    /// Number of reloads before this dictionary; tags the cached results
+    /// that come from it.
+    generation: u64,
+    /// Beginnings of the dictionary's words, listed on first use.
+    #[cfg(feature = "handle")]
+    prefixes: OnceLock<PrefixFilter>,
+}
+
+/// A dictionary extension: an optional transducer with the entries it adds
+/// and the entries it removes from the layers below.
+struct Extension {
+    analyzer: Option<FinnishVfstAnalyzer>,
+    removals: DictionaryRemovals,
+}
+
+impl Dictionary {
+    fn from_shared(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        let transducer = UnweightedTransducer::from_shared(data)?;
+        let configs = Mutex::new(vec![transducer.new_config(BUFFER_SIZE)]);
+        Ok(Self {
+            pending: Mutex::new(None),
+            transducer: OnceLock::from(Ok(transducer)),
+            configs,
+            generation: 0,
+            #[cfg(feature = "handle")]
+            prefixes: OnceLock::new(),
+        })
+    }
+
+    fn from_shared_lazy(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        let header = voikko_fst::format::parse_header(&data)?;
+        if header.weighted {
+            return Err(VfstError::TypeMismatch {
+                expected: false,
+                actual: true,
+            });
+        }
+        Ok(Self {
+            pending: Mutex::new(Some(data)),
+            transducer: OnceLock::new(),
+            configs: Mutex::new(Vec::new()),
+            generation: 0,
+            #[cfg(feature = "handle")]
+            prefixes: OnceLock::new(),
+        })
+    }
+
+    /// Return the transducer, building it from the pending data if needed.
+    fn build(&self) -> &Result<UnweightedTransducer, VfstError> {
+        self.transducer.get_or_init(|| {
+            let data = self
+                .pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take()
+                .unwrap_or_else(|| Arc::from([]));
+            UnweightedTransducer::from_shared(data)
+        })
+    }
+
+    /// Whether some word of the dictionary may begin like the lowercased
+    /// `word`, listing the beginnings of its words on first use. Admits
+    /// every word if the transducer cannot be built.
+    #[cfg(feature = "handle")]
+    fn admits(&self, word: &[char]) -> bool {
+        let Ok(transducer) = self.build() else {
+            return true;
+        };
+        self.prefixes
+            .get_or_init(|| PrefixFilter::new(transducer))
+            .admits(word)
+    }
+
+    /// Take a traversal configuration from the pool, creating a new one if
+    /// all are in use.
+    fn take_config(
+        &self,
+        transducer: &UnweightedTransducer,
+        budget: TraversalBudget,
+    ) -> UnweightedConfig {
+        let mut config = self
+            .configs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_else(|| transducer.new_config(BUFFER_SIZE));
+        config.set_budget(budget);
+        config
+    }
+
+    /// Return a configuration taken with `take_config` to the pool.
+    fn return_config(&self, config: UnweightedConfig) {
+        self.configs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(config);
+    }
 }
 
 impl FinnishVfstAnalyzer {
@@ -45,12 +230,270 @@ impl FinnishVfstAnalyzer {
     /// The data should be the contents of a `mor.vfst` file.
     ///
     /// Origin: FinnishVfstAnalyzer::FinnishVfstAnalyzer() -- FinnishVfstAnalyzer.cpp:51-137
-    pub fn from_bytes(data: &[u8]) -> Result<Self, voikko_fst::VfstError> {
-        let transducer = UnweightedTransducer::from_bytes(data)?;
-        let config = RefCell::new(transducer.new_config(BUFFER_SIZE));
-        Ok(Self { transducer, config })
+    pub fn from_bytes(data: &[u8]) -> Result<Self, VfstError> {
+        Self::from_shared(Arc::from(data))
+    }
+
+    /// Create an analyzer from shared `mor.vfst` data without copying it.
+    ///
+    /// The analyzer keeps `data` alive; every analyzer created from the
+    /// same `Arc` uses the one copy.
+    pub fn from_shared(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        Ok(Self::with_dictionary(Dictionary::from_shared(data)?))
+    }
+
+    /// Create an analyzer that defers building the transducer.
+    ///
+    /// Only the header is validated here; the symbol table and transition
+    /// table are built on first use or by [`warm_up`](Self::warm_up). Errors
+    /// in the rest of the data are reported by `warm_up`.
+    pub fn from_bytes_lazy(data: &[u8]) -> Result<Self, VfstError> {
+        Self::from_shared_lazy(Arc::from(data))
+    }
+
+    /// Create an analyzer from shared `mor.vfst` data that defers building
+    /// the transducer, as [`from_bytes_lazy`](Self::from_bytes_lazy) does.
+    pub fn from_shared_lazy(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        Ok(Self::with_dictionary(Dictionary::from_shared_lazy(data)?))
+    }
+
+    fn with_dictionary(dictionary: Dictionary) -> Self {
+        Self {
+            dictionary: RwLock::new(Arc::new(dictionary)),
+            extensions: Vec::new(),
+            budget: TraversalBudget::default(),
+            options: AnalyzerOptions::default(),
+            truncations: Truncations::default(),
+            cache: AnalysisCache::with_capacity(DEFAULT_ANALYSIS_CACHE_WORDS),
+            #[cfg(feature = "handle")]
+            prefix_filter: true,
+        }
+    }
+
+    /// Replace the `mor.vfst` transducer with one built from `data`.
+    ///
+    /// The new transducer is built before the swap, so a failure leaves the
+    /// analyzer unchanged. Analyses already running finish with the old
+    /// transducer, and their results are not cached for the new one; later
+    /// ones use the new one. Extensions and the traversal budget are kept.
+    pub fn reload(&self, data: &[u8]) -> Result<(), VfstError> {
+        let mut dictionary = Dictionary::from_shared(Arc::from(data))?;
+        let mut current = self
+            .dictionary
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        dictionary.generation = current.generation + 1;
+        *current = Arc::new(dictionary);
+        drop(current);
+        self.cache.clear();
+        Ok(())
+    }
+
+    /// Number of times the dictionary has been reloaded. A lookup that
+    /// starts after reading it uses that dictionary or a newer one, so
+    /// caches store results with it and answer only lookups of the same
+    /// generation.
+    pub(crate) fn dictionary_generation(&self) -> u64 {
+        self.dictionary
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .generation
+    }
+
+    /// The current dictionary.
+    fn dictionary(&self) -> Arc<Dictionary> {
+        Arc::clone(
+            &self
+                .dictionary
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Build the transducer and the first traversal configuration if that
+    /// has not been done yet. Safe to call from a background thread while
+    /// other threads use the analyzer; they wait for the build to finish.
+    pub fn warm_up(&self) -> Result<(), VfstError> {
+        let dictionary = self.dictionary();
+        let transducer = dictionary.build().as_ref().map_err(Clone::clone)?;
+        let mut configs = dictionary
+            .configs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if configs.is_empty() {
+            configs.push(transducer.new_config(BUFFER_SIZE));
+        }
+        Ok(())
+    }
+
+    /// Set what the analyses of this analyzer and its extensions include
+    /// besides the attributes.
+    pub fn set_options(&mut self, options: AnalyzerOptions) {
+        self.options = options;
+        for analyzer in self
+            .extensions
+            .iter_mut()
+            .filter_map(|e| e.analyzer.as_mut())
+        {
+            analyzer.options = options;
+        }
+        self.cache.clear();
+    }
+
+    /// Return the options set with [`set_options`](Self::set_options).
+    pub fn options(&self) -> AnalyzerOptions {
+        self.options
+    }
+
+    /// Return whether the transducer has been built.
+    pub fn load_state(&self) -> LoadState {
+        match self.dictionary().transducer.get() {
+            None => LoadState::Pending,
+            Some(Ok(_)) => LoadState::Ready,
+            Some(Err(_)) => LoadState::Failed,
+        }
+    }
+
+    /// Set the limits on each transducer lookup of an analysis, for this
+    /// analyzer and its extensions. The default budget behaves like the C++
+    /// engine. Generation is bounded separately.
+    pub fn set_traversal_budget(&mut self, budget: TraversalBudget) {
+        self.budget = budget;
+        for analyzer in self
+            .extensions
+            .iter_mut()
+            .filter_map(|e| e.analyzer.as_mut())
+        {
+            analyzer.budget = budget;
+        }
+        self.cache.clear();
+    }
+
+    /// Return the limits set with [`set_traversal_budget`](Self::set_traversal_budget).
+    pub fn traversal_budget(&self) -> TraversalBudget {
+        self.budget
+    }
+
+    /// Number of analyses cut short by the traversal budget since the
+    /// analyzer was created or [`reset_traversal_stats`](Self::reset_traversal_stats)
+    /// was called, by the limit that stopped them. An analysis is counted
+    /// once even if several layers were cut short.
+    pub fn traversal_stats(&self) -> TraversalStats {
+        self.truncations.stats()
+    }
+
+    /// Set whether analyses cut short by the traversal budget keep the word
+    /// (see [`truncated_lookups`](Self::truncated_lookups)). Off by default.
+    pub fn set_truncation_diagnostics(&mut self, value: bool) {
+        self.truncations.diagnostics = value;
+    }
+
+    /// The most recent words whose analysis was cut short by the traversal
+    /// budget, oldest first. Empty unless diagnostics are enabled with
+    /// [`set_truncation_diagnostics`](Self::set_truncation_diagnostics).
+    pub fn truncated_lookups(&self) -> Vec<TruncatedLookup> {
+        self.truncations.recent()
+    }
+
+    /// Reset the truncation counters and discard the recorded words.
+    pub fn reset_traversal_stats(&self) {
+        self.truncations.reset();
+    }
+
+    /// Add an extension layer on top of the dictionary and the extensions
+    /// added before it. `mor_vfst` holds the entries the extension adds, in
+    /// the `mor.vfst` format; `removals` the entries it withdraws from the
+    /// layers below.
+    ///
+    /// Analyses are returned from the newest layer down to the dictionary,
+    /// without analyses removed by a layer above and without duplicates of
+    /// analyses a layer above already gave.
+    pub fn add_extension(
+        &mut self,
+        mor_vfst: Option<&[u8]>,
+        removals: DictionaryRemovals,
+    ) -> Result<(), VfstError> {
+        let mut analyzer = mor_vfst.map(Self::from_bytes).transpose()?;
+        if let Some(analyzer) = &mut analyzer {
+            analyzer.budget = self.budget;
+            analyzer.options = self.options;
+        }
+        self.extensions.push(Extension { analyzer, removals });
+        self.cache.clear();
+        Ok(())
+    }
+
+    /// Return the number of extension layers.
+    pub fn extension_count(&self) -> usize {
+        self.extensions.len()
+    }
+
+    /// Remove all extension layers.
+    pub fn clear_extensions(&mut self) {
+        self.extensions.clear();
+        self.cache.clear();
+    }
+
+    /// Cache the analyses of at most `words` words, keeping the most
+    /// recently used ones that fit; 0 disables the cache. Analyses with the
+    /// analyzer's traversal budget are cached, except those cut short by
+    /// it; segmented analyses and lookups with another budget bypass the
+    /// cache. Defaults to [`DEFAULT_ANALYSIS_CACHE_WORDS`].
+    pub fn set_analysis_cache_capacity(&mut self, words: usize) {
+        self.cache.resize(words);
+    }
+
+    /// Return the capacity set with
+    /// [`set_analysis_cache_capacity`](Self::set_analysis_cache_capacity).
+    pub fn analysis_cache_capacity(&self) -> usize {
+        self.cache.capacity()
+    }
+
+    /// Hit, miss and eviction counts of the analysis cache.
+    pub fn analysis_cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Check the beginnings of the words in
+    /// [`may_analyze`](Self::may_analyze), or admit every word without
+    /// listing them, which saves their memory.
+    #[cfg(feature = "handle")]
+    pub(crate) fn set_prefix_filter(&mut self, enabled: bool) {
+        self.prefix_filter = enabled;
+    }
+
+    /// Whether `word` may have analyses, decided without a lookup from the
+    /// beginnings of the words of the dictionary and its extensions:
+    /// `false` only if no word begins like it. The beginnings of each
+    /// transducer are listed the first time it is asked.
+    #[cfg(feature = "handle")]
+    pub(crate) fn may_analyze(&self, word: &[char]) -> bool {
+        if !self.prefix_filter {
+            return true;
+        }
+        let mut beginning = word[..word.len().min(PREFIX_FILTER_CHARS)].to_vec();
+        voikko_core::case::set_case(&mut beginning, CaseType::AllLower);
+        self.dictionary().admits(&beginning)
+            || self
+                .extensions
+                .iter()
+                .filter_map(|e| e.analyzer.as_ref())
+                .any(|analyzer| analyzer.may_analyze(word))
     }
 
+    /// The layers from the newest extension down to the dictionary, each
+    /// with the extensions above it.
+    fn layers(&self) -> impl Iterator<Item = (&FinnishVfstAnalyzer, &[Extension])> {
+        let extensions = self.extensions.iter().enumerate().rev();
+        extensions
+            .filter_map(|(i, ext)| Some((ext.analyzer.as_ref()?, &self.extensions[i + 1..])))
+            .chain(std::iter::once((self, &self.extensions[..])))
+    }
+
+    /// Attributes that `analyze_full` computes only with full morphology.
+    pub const FULL_MORPHOLOGY_ATTRIBUTES: [&'static str; 4] =
+        [ATTR_FSTOUTPUT, ATTR_BASEFORM, ATTR_WORDBASES, ATTR_WORDIDS];
+
     /// Analyze a word with full or partial morphology.
     ///
     /// When `full_morphology` is true, additional attributes are computed:
@@ -63,27 +506,172 @@ impl FinnishVfstAnalyzer {
         word_len: usize,
         full_morphology: bool,
     ) -> Vec<Analysis> {
+        self.analyze_with(word, word_len, full_morphology, false, self.budget)
+            .0
+    }
+
+    /// Analyze a word like [`analyze_full`](Self::analyze_full), also
+    /// returning `true` if a lookup stopped on the traversal budget (see
+    /// [`set_traversal_budget`](Self::set_traversal_budget)), so that
+    /// analyses may be missing.
+    pub fn analyze_budgeted(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+    ) -> (Vec<Analysis>, bool) {
+        self.analyze_with(word, word_len, full_morphology, false, self.budget)
+    }
+
+    /// Analyze a word like [`analyze_budgeted`](Self::analyze_budgeted),
+    /// with `budget` instead of the analyzer's traversal budget for this
+    /// call only, e.g. a larger step limit to retry a truncated word.
+    pub fn analyze_with_budget(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, bool) {
+        self.analyze_with(word, word_len, full_morphology, false, budget)
+    }
+
+    /// Analyze a word with full morphology, also recording in each analysis
+    /// the input characters covered by each compound part and derivational
+    /// morph (see [`Analysis::segments`]).
+    pub fn analyze_segmented(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
+        self.analyze_with(word, word_len, true, true, self.budget).0
+    }
+
+    /// Analyze a word, from the cache if it has the word's analyses.
+    /// Returns whether a lookup was cut short.
+    fn analyze_with(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+        segments: bool,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, bool) {
+        if segments || budget != self.budget || word_len > MAX_WORD_CHARS {
+            return self.analyze_uncached(word, word_len, full_morphology, segments, budget);
+        }
+        // The cache holds full analyses; partial ones are the same without
+        // the full-morphology attributes.
+        let generation = self.dictionary_generation();
+        let (mut analyses, complete) =
+            self.cache
+                .get_or_analyze(&word[..word_len], generation, || {
+                    let (analyses, truncated) =
+                        self.analyze_uncached(word, word_len, true, false, budget);
+                    (analyses, !truncated)
+                });
+        if !full_morphology {
+            for analysis in &mut analyses {
+                for key in Self::FULL_MORPHOLOGY_ATTRIBUTES {
+                    analysis.remove(key);
+                }
+            }
+        }
+        (analyses, !complete)
+    }
+
+    /// Analyze a word in every layer and count the analysis if a lookup
+    /// was cut short. Returns whether one was.
+    fn analyze_uncached(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+        segments: bool,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, bool) {
+        let (analyses, truncated) =
+            self.analyze_layers(word, word_len, full_morphology, segments, budget);
+        if let Some(reason) = truncated {
+            self.truncations.record(&word[..word_len], reason);
+        }
+        (analyses, truncated.is_some())
+    }
+
+    fn analyze_layers(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+        segments: bool,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, Option<TruncationReason>) {
+        if self.extensions.is_empty() {
+            return self.analyze_layer(word, word_len, full_morphology, segments, budget);
+        }
+        // Removals match on BASEFORM, which needs full morphology.
+        let removing = self.extensions.iter().any(|e| !e.removals.is_empty());
+        let mut analyses: Vec<Analysis> = Vec::new();
+        let mut truncated = None;
+        for (layer, above) in self.layers() {
+            let (found, layer_truncated) = layer.analyze_layer(
+                word,
+                word_len,
+                full_morphology || removing,
+                segments,
+                budget,
+            );
+            truncated = truncated.or(layer_truncated);
+            for mut analysis in found {
+                if above.iter().any(|e| e.removals.removes(&analysis)) {
+                    continue;
+                }
+                if !full_morphology {
+                    for key in Self::FULL_MORPHOLOGY_ATTRIBUTES {
+                        analysis.remove(key);
+                    }
+                }
+                if !analyses.contains(&analysis) {
+                    analyses.push(analysis);
+                }
+            }
+        }
+        (analyses, truncated)
+    }
+
+    /// Analyze a word with this analyzer's own transducer only. Also
+    /// returns the limit of `budget` the lookup stopped on, if any.
+    fn analyze_layer(
+        &self,
+        word: &[char],
+        word_len: usize,
+        full_morphology: bool,
+        segments: bool,
+        budget: TraversalBudget,
+    ) -> (Vec<Analysis>, Option<TruncationReason>) {
         if word_len > MAX_WORD_CHARS {
-            return Vec::new();
+            return (Vec::new(), None);
         }
 
-        // Lowercase the input
-        let mut word_lower: Vec<char> = word[..word_len].to_vec();
-        voikko_core::case::set_case(&mut word_lower, CaseType::AllLower);
+        let mut buffer = [0; MAX_WORD_BYTES];
+        let word_lower = lower_utf8(&word[..word_len], &mut buffer);
 
+        let dictionary = self.dictionary();
+        let Ok(transducer) = dictionary.build() else {
+            return (Vec::new(), None);
+        };
         let mut analyses = Vec::new();
-        let mut config = self.config.borrow_mut();
+        let mut config = dictionary.take_config(transducer, budget);
 
-        if !self.transducer.prepare(&mut config, &word_lower) {
+        if !transducer.prepare_str(&mut config, word_lower) {
             // Unknown character in input; still try traversal (unweighted allows it)
         }
 
         let mut output_buf = String::new();
         let mut analysis_count = 0;
+        let mut status = NextStatus::Output;
 
-        while analysis_count < MAX_ANALYSIS_COUNT
-            && self.transducer.next(&mut config, &mut output_buf)
-        {
+        while analysis_count < MAX_ANALYSIS_COUNT {
+            status = transducer.next_status(&mut config, &mut output_buf);
+            if status != NextStatus::Output {
+                break;
+            }
             analysis_count += 1;
             let fst_output: Vec<char> = output_buf.chars().collect();
 
@@ -91,21 +679,7 @@ impl FinnishVfstAnalyzer {
                 continue;
             }
 
-            let mut analysis = Analysis::new();
-            let mut structure: Vec<char> = parse_structure(&fst_output, word_len).chars().collect();
-
-            // Parse basic attributes (backward scan of tags)
-            let basic = parse_basic_attributes(&fst_output);
-            apply_basic_attributes(&mut analysis, &basic);
-
-            // Fix structure based on derivation tags
-            fix_structure(&mut structure, &fst_output);
-            let structure_str: String = structure.iter().collect();
-            analysis.set(ATTR_STRUCTURE, &structure_str);
-
-            // Post-processing: adjust attributes based on cross-attribute rules
-            // Origin: FinnishVfstAnalyzer.cpp:1072-1096
-            post_process_attributes(&mut analysis);
+            let (analysis, structure) = basic_analysis(&fst_output, word_len);
 
             // Push analysis to the results list. Remember index so we can
             // modify it in place for fullMorphology attributes (matching C++
@@ -120,36 +694,249 @@ impl FinnishVfstAnalyzer {
 
             if full_morphology {
                 // Set attributes on the already-pushed analysis in place.
-                let fst_output_str: String = fst_output.iter().collect();
-                analyses[analysis_idx].set(ATTR_FSTOUTPUT, &fst_output_str);
+                add_full_morphology(&mut analyses[analysis_idx], &fst_output, &structure);
+            }
 
-                if let Some(baseform) = parse_baseform(&fst_output, &structure) {
-                    analyses[analysis_idx].set(ATTR_BASEFORM, &baseform);
+            if segments {
+                let parts = parse_segments(&fst_output, word_len);
+                for analysis in &mut analyses[analysis_idx..] {
+                    analysis.set_segments(parts.clone());
                 }
+            }
 
-                let debug = parse_debug_attributes(&fst_output);
-                if let Some(wordbases) = &debug.wordbases {
-                    analyses[analysis_idx].set(ATTR_WORDBASES, wordbases);
+            if self.options.include_raw {
+                for analysis in &mut analyses[analysis_idx..] {
+                    analysis.set_raw_fst_output(output_buf.as_str());
                 }
-                if let Some(wordids) = &debug.wordids {
-                    analyses[analysis_idx].set(ATTR_WORDIDS, wordids);
+            }
+        }
+
+        let truncated =
+            (status == NextStatus::BudgetExceeded).then(|| TruncationReason::of(&config));
+        dictionary.return_config(config);
+        (analyses, truncated)
+    }
+
+    /// Generate the forms of `baseform` that have every attribute of
+    /// `target`, e.g. SIJAMUOTO=omanto and NUMBER=plural of "koira" give
+    /// "koirien". A BASEFORM attribute in `target` is ignored.
+    ///
+    /// Possessive suffixes and clitics (POSSESSIVE, FOCUS, KYSYMYSLIITE)
+    /// are only generated when `target` asks for them; the clitics -hAn,
+    /// -pA, -pAs and the -kA of "eikä", which have no attribute, are never
+    /// generated. Compounds and
+    /// derived words are not generated; `baseform` must be a lexicon entry.
+    /// Forms are returned in the casing of the entry, without duplicates.
+    /// Entries of all layers are used, except those a layer above removes.
+    pub fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+        if baseform.is_empty() {
+            return Vec::new();
+        }
+        let mut forms: Vec<String> = Vec::new();
+        for (layer, above) in self.layers() {
+            layer.generate_layer(baseform, target, above, &mut forms);
+        }
+        remove_unmarked_clitics(&mut forms);
+        forms
+    }
+
+    /// Add the forms `generate` finds in this analyzer's own transducer to
+    /// `forms`, skipping entries removed by the extensions `above`.
+    fn generate_layer(
+        &self,
+        baseform: &str,
+        target: &Analysis,
+        above: &[Extension],
+        forms: &mut Vec<String>,
+    ) {
+        let dictionary = self.dictionary();
+        let Ok(transducer) = dictionary.build() else {
+            return;
+        };
+        let filter = EntryFilter::new(baseform);
+        let mut config = dictionary.take_config(transducer, self.budget);
+
+        transducer.generate(
+            &mut config,
+            MAX_GENERATION_STEPS,
+            |output| filter.keep(output),
+            |input, output| {
+                let fst_output: Vec<char> = output.chars().collect();
+                if !is_valid_analysis(&fst_output) {
+                    return true;
+                }
+                let word: Vec<char> = input.chars().collect();
+                let (mut analysis, structure) = basic_analysis(&fst_output, word.len());
+                add_full_morphology(&mut analysis, &fst_output, &structure);
+                if matches_target(&analysis, baseform, target)
+                    && !above.iter().any(|e| e.removals.removes(&analysis))
+                {
+                    let structure: String = structure.iter().collect();
+                    let form: String = apply_structure_case(&word, &structure)
+                        .into_iter()
+                        .collect();
+                    if !forms.contains(&form) {
+                        forms.push(form);
+                    }
                 }
+                true
+            },
+        );
+
+        dictionary.return_config(config);
+    }
+
+    /// Complete `prefix` to at most `max` words of the dictionary and its
+    /// extensions, for autocompletion.
+    ///
+    /// The words on the shortest paths of the transducers are chosen, so
+    /// the basic forms of words come before rarer forms and compounds, and
+    /// words of the extensions before those of the dictionary. `mor.vfst`
+    /// is unweighted, so the chosen words are then sorted alphabetically,
+    /// ignoring case. Completions keep the dictionary's capitalization
+    /// ("Helsinki"); a capitalized `prefix` capitalizes them and an
+    /// all-uppercase one uppercases them. Another completion with an
+    /// unmarked clitic ("koirahan") and compound parts ending in a hyphen
+    /// are left out.
+    pub fn complete(&self, prefix: &str, max: usize) -> Vec<String> {
+        let mut lower: Vec<char> = prefix.chars().collect();
+        if max == 0 || lower.len() > MAX_WORD_CHARS {
+            return Vec::new();
+        }
+        let case = voikko_core::case::detect_case(&lower);
+        voikko_core::case::set_case(&mut lower, CaseType::AllLower);
+        let lower: String = lower.into_iter().collect();
+
+        let mut words: Vec<String> = Vec::new();
+        for (layer, above) in self.layers() {
+            layer.complete_layer(&lower, max, above, &mut words);
+        }
+        remove_unmarked_clitics(&mut words);
+        words.truncate(max);
+        words.sort_by_cached_key(|word| (word.to_lowercase(), word.clone()));
+        for word in &mut words {
+            let mut chars: Vec<char> = word.chars().collect();
+            match case {
+                CaseType::AllUpper if lower.chars().count() > 1 => {
+                    voikko_core::case::set_case(&mut chars, CaseType::AllUpper);
+                }
+                CaseType::AllUpper | CaseType::FirstUpper => {
+                    chars[0] = voikko_core::character::simple_upper(chars[0]);
+                }
+                _ => continue,
             }
+            *word = chars.into_iter().collect();
         }
+        words
+    }
 
-        analyses
+    /// Add the words `complete` finds in this analyzer's own transducer to
+    /// `words`, skipping entries removed by the extensions `above`.
+    fn complete_layer(
+        &self,
+        prefix: &str,
+        max: usize,
+        above: &[Extension],
+        words: &mut Vec<String>,
+    ) {
+        let dictionary = self.dictionary();
+        let Ok(transducer) = dictionary.build() else {
+            return;
+        };
+        let limit = max.saturating_mul(COMPLETION_PATHS_PER_WORD);
+        for (input, output) in transducer.enumerate_outputs(limit, prefix) {
+            if !input.ends_with(char::is_alphabetic) {
+                continue;
+            }
+            let fst_output: Vec<char> = output.chars().collect();
+            if !is_valid_analysis(&fst_output) {
+                continue;
+            }
+            let word: Vec<char> = input.chars().collect();
+            let (mut analysis, structure) = basic_analysis(&fst_output, word.len());
+            if !above.is_empty() {
+                add_full_morphology(&mut analysis, &fst_output, &structure);
+                if above.iter().any(|e| e.removals.removes(&analysis)) {
+                    continue;
+                }
+            }
+            let structure: String = structure.iter().collect();
+            let form: String = apply_structure_case(&word, &structure)
+                .into_iter()
+                .collect();
+            if !words.contains(&form) {
+                words.push(form);
+            }
+        }
     }
 }
 
 impl Analyzer for FinnishVfstAnalyzer {
     /// Analyze a word with full morphology (BASEFORM, WORDBASES, etc.).
     ///
-    /// Uses `RefCell` interior mutability so that `&self` suffices.
+    /// Uses a pooled traversal configuration so that `&self` suffices.
     ///
     /// Origin: FinnishVfstAnalyzer::analyze -- FinnishVfstAnalyzer.cpp:1050-1112
     fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
         self.analyze_full(word, word_len, true)
     }
+
+    /// The dictionary is looked up in lowercase, so one lookup covers every
+    /// case variant; STRUCTURE still tells the dictionary's capitalization.
+    fn analyze_any_case(&self, word: &[char], word_len: usize) -> (Vec<Analysis>, CaseVariant) {
+        let analyses = self.analyze(word, word_len);
+        let variant = if analyses.is_empty() {
+            CaseVariant::AsIs
+        } else {
+            CaseVariant::of_lowercase_lookup(&word[..word_len])
+        };
+        (analyses, variant)
+    }
+
+    fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+        FinnishVfstAnalyzer::generate(self, baseform, target)
+    }
+}
+
+/// Build the analysis of one FST output for a word of `word_len` characters,
+/// without the full-morphology attributes. Also returns the STRUCTURE.
+fn basic_analysis(fst_output: &[char], word_len: usize) -> (Analysis, Vec<char>) {
+    let mut analysis = Analysis::new();
+    let mut structure: Vec<char> = parse_structure(fst_output, word_len).chars().collect();
+
+    // Parse basic attributes (backward scan of tags)
+    let basic = parse_basic_attributes(fst_output);
+    apply_basic_attributes(&mut analysis, &basic);
+
+    // Fix structure based on derivation tags
+    fix_structure(&mut structure, fst_output);
+    let structure_str: String = structure.iter().collect();
+    analysis.set(ATTR_STRUCTURE, &structure_str);
+
+    // Post-processing: adjust attributes based on cross-attribute rules
+    // Origin: FinnishVfstAnalyzer.cpp:1072-1096
+    post_process_attributes(&mut analysis);
+
+    (analysis, structure)
+}
+
+/// Set the full-morphology attributes FSTOUTPUT, BASEFORM, WORDBASES and
+/// WORDIDS.
+fn add_full_morphology(analysis: &mut Analysis, fst_output: &[char], structure: &[char]) {
+    let fst_output_str: String = fst_output.iter().collect();
+    analysis.set(ATTR_FSTOUTPUT, &fst_output_str);
+
+    if let Some(baseform) = parse_baseform(fst_output, structure) {
+        analysis.set(ATTR_BASEFORM, &baseform);
+    }
+
+    let debug = parse_debug_attributes(fst_output);
+    if let Some(wordbases) = &debug.wordbases {
+        analysis.set(ATTR_WORDBASES, wordbases);
+    }
+    if let Some(wordids) = &debug.wordids {
+        analysis.set(ATTR_WORDIDS, wordids);
+    }
 }
 
 /// Apply parsed basic attributes to an Analysis object.
@@ -331,11 +1118,43 @@ fn duplicate_org_name(analysis: &Analysis, fst_output: &[char]) -> Option<Analys
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{empty_vfst, vfst};
 
     fn chars(s: &str) -> Vec<char> {
         s.chars().collect()
     }
 
+    #[test]
+    fn lazy_analyzer_checks_header_only() {
+        assert!(FinnishVfstAnalyzer::from_bytes_lazy(&[0, 1, 2, 3]).is_err());
+        // Symbol table claims two symbols but the data ends.
+        let analyzer = FinnishVfstAnalyzer::from_bytes_lazy(&vfst(&[2, 0])).unwrap();
+        assert_eq!(analyzer.load_state(), LoadState::Pending);
+        assert!(analyzer.analyze_full(&chars("koira"), 5, false).is_empty());
+        assert_eq!(analyzer.load_state(), LoadState::Failed);
+        assert!(analyzer.warm_up().is_err());
+    }
+
+    #[test]
+    fn lazy_analyzer_warm_up() {
+        let analyzer = FinnishVfstAnalyzer::from_bytes_lazy(&empty_vfst()).unwrap();
+        assert_eq!(analyzer.load_state(), LoadState::Pending);
+        analyzer.warm_up().unwrap();
+        assert_eq!(analyzer.load_state(), LoadState::Ready);
+        analyzer.warm_up().unwrap();
+    }
+
+    #[test]
+    fn reload_replaces_failed_dictionary() {
+        let analyzer = FinnishVfstAnalyzer::from_bytes_lazy(&vfst(&[2, 0])).unwrap();
+        assert!(analyzer.warm_up().is_err());
+        assert!(analyzer.reload(&vfst(&[2, 0])).is_err());
+        assert_eq!(analyzer.load_state(), LoadState::Failed);
+        analyzer.reload(&empty_vfst()).unwrap();
+        assert_eq!(analyzer.load_state(), LoadState::Ready);
+        assert!(analyzer.analyze_full(&chars("koira"), 5, false).is_empty());
+    }
+
     #[test]
     fn post_process_removes_negative_from_noun() {
         let mut a = Analysis::new();
@@ -391,6 +1210,81 @@ mod tests {
         assert!(!a.contains_key(ATTR_COMPARISON));
     }
 
+    /// Rows of `tag_inventory.tsv`: tag, FST output and expectations.
+    fn tag_inventory() -> Vec<(&'static str, &'static str, &'static str)> {
+        include_str!("tag_inventory.tsv")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                assert_eq!(fields.len(), 3, "bad tag inventory row: {line}");
+                (fields[0], fields[1], fields[2])
+            })
+            .collect()
+    }
+
+    /// Basic attributes of `fst_output`, without STRUCTURE.
+    fn basic_attributes(fst_output: &str) -> Vec<(String, String)> {
+        let fst = chars(fst_output);
+        let (analysis, _) = basic_analysis(&fst, fst.len());
+        let mut attrs: Vec<(String, String)> = analysis
+            .attributes()
+            .iter()
+            .filter(|(k, _)| k.as_str() != ATTR_STRUCTURE)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        attrs.sort();
+        attrs
+    }
+
+    #[test]
+    fn tag_inventory_attributes() {
+        for (tag, fst_output, expected) in tag_inventory() {
+            assert!(fst_output.contains(tag), "{tag} not in {fst_output}");
+            let fst = chars(fst_output);
+            let (analysis, _) = basic_analysis(&fst, fst.len());
+            for expectation in expected.split(' ') {
+                if expectation == "none" {
+                    let without = fst_output.replacen(tag, "", 1);
+                    assert_eq!(
+                        basic_attributes(fst_output),
+                        basic_attributes(&without),
+                        "{tag} changes the attributes of {fst_output}"
+                    );
+                } else if let Some(key) = expectation.strip_prefix('!') {
+                    assert_eq!(analysis.get(key), None, "{key} set for {fst_output}");
+                } else {
+                    let (key, value) = expectation
+                        .split_once('=')
+                        .unwrap_or_else(|| panic!("bad expectation {expectation} for {tag}"));
+                    assert_eq!(analysis.get(key), Some(value), "{key} of {fst_output}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn tag_inventory_covers_dictionary_tags() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let transducer = UnweightedTransducer::from_bytes(&mor_data).unwrap();
+        let covered: Vec<&str> = tag_inventory().iter().map(|row| row.0).collect();
+        let missing: Vec<&String> = transducer
+            .symbols()
+            .symbol_strings
+            .iter()
+            .filter(|s| s.len() > 2 && s.starts_with('[') && s.ends_with(']'))
+            .filter(|s| !covered.contains(&s.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "tags missing from tag_inventory.tsv: {missing:?}"
+        );
+    }
+
     #[test]
     fn duplicate_org_name_returns_none_for_non_noun() {
         let fst = chars("[Lt][Xp]juosta[X]juoksen[Tt][Ap][P1][Ny]");
@@ -0,0 +1,171 @@
+// Morphological generation helpers.
+//
+// Generation runs the analysis transducer from the output side: the FST
+// output of every form of a word starts with its lexicon entry
+// (`[Ln][Xp]koira[X]...`), so following only the paths whose output is
+// consistent with that entry enumerates the forms of one word. The complete
+// paths are then analyzed like ordinary words and filtered by the requested
+// attributes.
+
+use voikko_core::analysis::{
+    ATTR_BASEFORM, ATTR_FOCUS, ATTR_KYSYMYSLIITE, ATTR_POSSESSIVE, Analysis,
+};
+
+/// Upper bound on transitions explored while generating the forms of one
+/// word. The paths of a single lexicon entry, including clitics and
+/// possessive suffixes, stay well below this.
+pub(crate) const MAX_GENERATION_STEPS: u32 = 2_000_000;
+
+/// Output filter that keeps the paths of one lexicon entry.
+///
+/// An output is kept while it can still become `[tags][Xp]baseform[X]...`
+/// with no further word parts: compound boundaries (`[B..]`) and the base
+/// forms of later parts or derivations (`[Xp]`, `[Xj]`) are pruned.
+pub(crate) struct EntryFilter {
+    baseform: String,
+}
+
+impl EntryFilter {
+    pub(crate) fn new(baseform: &str) -> Self {
+        Self {
+            baseform: baseform.to_lowercase(),
+        }
+    }
+
+    /// Return whether a path with output `output` (so far) may belong to
+    /// the entry.
+    pub(crate) fn keep(&self, output: &str) -> bool {
+        // Before the entry: tags and [X?]...[X] blocks only
+        let mut rest = output;
+        loop {
+            if rest.is_empty() {
+                return true;
+            }
+            if let Some(after) = rest.strip_prefix("[Xp]") {
+                rest = after;
+                break;
+            }
+            if !rest.starts_with('[') || rest.starts_with("[B") {
+                return false;
+            }
+            let end = if rest.starts_with("[X") {
+                rest.find("[X]").map(|i| i + 3)
+            } else {
+                rest.find(']').map(|i| i + 1)
+            };
+            match end {
+                Some(end) => rest = &rest[end..],
+                None => return true,
+            }
+        }
+
+        // The entry's base form
+        match rest.find("[X]") {
+            None => self.baseform.starts_with(&rest.to_lowercase()),
+            Some(end) => {
+                rest[..end].to_lowercase() == self.baseform && {
+                    let after = &rest[end + 3..];
+                    !(after.contains("[Xp]") || after.contains("[Xj]") || after.contains("[B"))
+                }
+            }
+        }
+    }
+}
+
+/// Attributes of optional suffixes. A form carries them only when the
+/// target asks for them, so the genitive plural of "koira" is "koirien"
+/// but not "koiriensa" or "koirienkin".
+const SUFFIX_ATTRIBUTES: [&str; 3] = [ATTR_POSSESSIVE, ATTR_FOCUS, ATTR_KYSYMYSLIITE];
+
+/// Return whether `analysis` is a form of `baseform` with every attribute
+/// of `target` and no optional suffix the target does not ask for.
+pub(crate) fn matches_target(analysis: &Analysis, baseform: &str, target: &Analysis) -> bool {
+    let is_form_of = analysis
+        .get(ATTR_BASEFORM)
+        .is_some_and(|b| b.to_lowercase() == baseform.to_lowercase());
+    is_form_of
+        && target
+            .attributes()
+            .iter()
+            .all(|(key, value)| key == ATTR_BASEFORM || analysis.get(key) == Some(value))
+        && SUFFIX_ATTRIBUTES
+            .iter()
+            .all(|key| target.get(key).is_some() || analysis.get(key).is_none())
+}
+
+/// Clitics the analyzer does not mark with an attribute; -kA is the clitic
+/// of the negative verb ("eikä", "eivätkä").
+pub(crate) const UNMARKED_CLITICS: [&str; 10] = [
+    "han", "hän", "pa", "pä", "pas", "päs", "pahan", "pähän", "ka", "kä",
+];
+
+/// Remove the forms that are another form in `forms` followed by an
+/// unmarked clitic ("koirienhan" when "koirien" is present).
+pub(crate) fn remove_unmarked_clitics(forms: &mut Vec<String>) {
+    let with_clitic = |form: &str| {
+        UNMARKED_CLITICS.iter().any(|clitic| {
+            form.strip_suffix(clitic)
+                .is_some_and(|stem| forms.iter().any(|f| f == stem))
+        })
+    };
+    let keep: Vec<bool> = forms.iter().map(|f| !with_clitic(f)).collect();
+    let mut keep = keep.into_iter();
+    forms.retain(|_| keep.next().unwrap_or(true));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::analysis::{ATTR_NUMBER, ATTR_SIJAMUOTO};
+
+    #[test]
+    fn entry_filter_follows_one_entry() {
+        let filter = EntryFilter::new("koira");
+        assert!(filter.keep("[Ln]"));
+        assert!(filter.keep("[Ln][Ica][Xp]ko"));
+        assert!(filter.keep("[Ln][Xp]koira[X]koir[Sg][Nm]ien"));
+        assert!(!filter.keep("[Ln][Xp]kissa"));
+        assert!(!filter.keep("[Ln][Xp]koiras[X]"));
+        assert!(!filter.keep("koira"));
+        // Compounds and derivations are other words
+        assert!(!filter.keep("[Ln][Xp]koira[X]koir[Sn][Ny]a[Bh][Bc]"));
+        assert!(!filter.keep("[Ln][Xp]koira[X]koira[Ln][Xj]"));
+
+        let proper = EntryFilter::new("Helsinki");
+        assert!(proper.keep("[Lep][Xp]Helsinki[X]helsing[Sg]"));
+    }
+
+    #[test]
+    fn target_attributes_must_all_match() {
+        let mut analysis = Analysis::new();
+        analysis.set(ATTR_BASEFORM, "koira");
+        analysis.set(ATTR_SIJAMUOTO, "omanto");
+        analysis.set(ATTR_NUMBER, "plural");
+
+        let mut target = Analysis::new();
+        target.set(ATTR_SIJAMUOTO, "omanto");
+        assert!(matches_target(&analysis, "koira", &target));
+        target.set(ATTR_NUMBER, "singular");
+        assert!(!matches_target(&analysis, "koira", &target));
+        assert!(!matches_target(&analysis, "kissa", &Analysis::new()));
+
+        target.set(ATTR_NUMBER, "plural");
+        analysis.set(ATTR_POSSESSIVE, "3");
+        assert!(!matches_target(&analysis, "koira", &target));
+        target.set(ATTR_POSSESSIVE, "3");
+        assert!(matches_target(&analysis, "koira", &target));
+    }
+
+    #[test]
+    fn unmarked_clitics_are_removed() {
+        let mut forms: Vec<String> = ["koirien", "koirienhan", "koirienpas", "lapa"]
+            .map(String::from)
+            .to_vec();
+        remove_unmarked_clitics(&mut forms);
+        assert_eq!(forms, ["koirien", "lapa"]);
+
+        let mut forms: Vec<String> = ["eivät", "eivätkä"].map(String::from).to_vec();
+        remove_unmarked_clitics(&mut forms);
+        assert_eq!(forms, ["eivät"]);
+    }
+}
@@ -0,0 +1,177 @@
+// Dictionary layers: extensions on top of the base mor.vfst.
+//
+// An organization can ship a delta to the standard dictionary instead of a
+// full modified mor.vfst: an extension VFST with the words it adds and a
+// removal list naming base words it withdraws. Layers are consulted from
+// the most recently added extension down to the base dictionary. Removals
+// shadow the layers below the extension that lists them, never the
+// extension itself or the layers above it.
+//
+// A removal list is a line-oriented UTF-8 text file:
+//
+//   # comment
+//   baseform
+//   baseform<TAB>CLASS
+//
+// An entry without a class removes every entry with that base form; with
+// a class (e.g. "paikannimi") only entries of that word class. Base forms
+// are matched exactly, including case, against BASEFORM, so compounds
+// containing a removed word are not removed.
+
+use std::collections::BTreeMap;
+
+use voikko_core::analysis::{ATTR_BASEFORM, ATTR_CLASS, Analysis};
+
+/// Error type for removal list parse failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DictionaryRemovalsError {
+    /// A line could not be parsed. `line` is 1-based.
+    #[error("invalid removal entry on line {line}: {reason}")]
+    InvalidEntry { line: usize, reason: &'static str },
+}
+
+/// Base dictionary words withdrawn by a dictionary extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DictionaryRemovals {
+    /// Keyed by base form; `None` removes every class, otherwise the
+    /// listed classes.
+    entries: BTreeMap<String, Option<Vec<String>>>,
+}
+
+impl DictionaryRemovals {
+    /// Create an empty removal list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of removed base forms.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if nothing is removed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove the entries with base form `baseform`, or only those of word
+    /// class `class`.
+    pub fn add(&mut self, baseform: &str, class: Option<&str>) {
+        match class {
+            None => {
+                self.entries.insert(baseform.to_string(), None);
+            }
+            Some(class) => {
+                let classes = self
+                    .entries
+                    .entry(baseform.to_string())
+                    .or_insert_with(|| Some(Vec::new()));
+                if let Some(classes) = classes {
+                    if !classes.iter().any(|c| c == class) {
+                        classes.push(class.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return `true` if `analysis` belongs to a removed entry. An analysis
+    /// without BASEFORM is never removed.
+    pub fn removes(&self, analysis: &Analysis) -> bool {
+        let Some(baseform) = analysis.get(ATTR_BASEFORM) else {
+            return false;
+        };
+        match self.entries.get(baseform) {
+            None => false,
+            Some(None) => true,
+            Some(Some(classes)) => analysis
+                .get(ATTR_CLASS)
+                .is_some_and(|class| classes.iter().any(|c| c == class)),
+        }
+    }
+
+    /// Parse a removal list.
+    ///
+    /// Empty lines and lines starting with `#` are skipped; whitespace
+    /// around the fields is ignored.
+    pub fn from_text(text: &str) -> Result<Self, DictionaryRemovalsError> {
+        let mut removals = DictionaryRemovals::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason| DictionaryRemovalsError::InvalidEntry {
+                line: idx + 1,
+                reason,
+            };
+            let mut fields = line.split('\t');
+            let baseform = fields.next().unwrap_or_default().trim();
+            let class = fields.next().map(str::trim);
+            if fields.next().is_some() {
+                return Err(invalid("too many fields"));
+            }
+            if baseform.is_empty() {
+                return Err(invalid("empty base form"));
+            }
+            if class == Some("") {
+                return Err(invalid("empty word class"));
+            }
+            removals.add(baseform, class);
+        }
+        Ok(removals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(baseform: &str, class: &str) -> Analysis {
+        let mut analysis = Analysis::new();
+        analysis.set(ATTR_BASEFORM, baseform);
+        analysis.set(ATTR_CLASS, class);
+        analysis
+    }
+
+    #[test]
+    fn removals_match_base_form_and_class() {
+        let removals = DictionaryRemovals::from_text(
+            "# withdrawn words\n\nkoira\nTurku\tpaikannimi\r\nTurku\tsukunimi\n",
+        )
+        .unwrap();
+        assert_eq!(removals.len(), 2);
+        assert!(removals.removes(&analysis("koira", "nimisana")));
+        assert!(removals.removes(&analysis("Turku", "paikannimi")));
+        assert!(removals.removes(&analysis("Turku", "sukunimi")));
+        assert!(!removals.removes(&analysis("Turku", "etunimi")));
+        assert!(!removals.removes(&analysis("Koira", "nimisana")));
+        assert!(!removals.removes(&analysis("koirankoppi", "nimisana")));
+        assert!(!removals.removes(&Analysis::new()));
+    }
+
+    #[test]
+    fn removals_reject_malformed_lines() {
+        assert_eq!(
+            DictionaryRemovals::from_text("koira\nTurku\t\n"),
+            Err(DictionaryRemovalsError::InvalidEntry {
+                line: 2,
+                reason: "empty word class"
+            })
+        );
+        assert_eq!(
+            DictionaryRemovals::from_text("koira\tnimisana\tx"),
+            Err(DictionaryRemovalsError::InvalidEntry {
+                line: 1,
+                reason: "too many fields"
+            })
+        );
+        assert_eq!(
+            DictionaryRemovals::from_text("\tnimisana"),
+            Err(DictionaryRemovalsError::InvalidEntry {
+                line: 1,
+                reason: "empty base form"
+            })
+        );
+    }
+}
@@ -1,14 +1,70 @@
 // Morphological analysis module
 // Origin: morphology/
 
+mod cache;
+mod compound;
 mod finnish;
+mod generate;
+mod layers;
+#[cfg(feature = "handle")]
+mod prefixes;
+mod roundtrip;
 mod tag_parser;
+mod truncation;
 mod vfst;
 
-pub use finnish::FinnishVfstAnalyzer;
+pub use cache::CacheStats;
+pub use compound::{CompoundInfo, compound_info};
+pub use finnish::{AnalyzerOptions, DEFAULT_ANALYSIS_CACHE_WORDS, FinnishVfstAnalyzer, LoadState};
+pub use layers::{DictionaryRemovals, DictionaryRemovalsError};
+pub use roundtrip::{RoundTrip, RoundTripStatus, round_trip};
+pub use truncation::{TraversalStats, TruncatedLookup, TruncationReason};
 pub use vfst::VfstAnalyzer;
 
+#[cfg(feature = "suggest")]
+pub(crate) use tag_parser::apply_structure_case;
+
 use voikko_core::analysis::Analysis;
+use voikko_core::character::simple_lower;
+use voikko_core::enums::MAX_WORD_CHARS;
+
+/// Bytes of a word of at most `MAX_WORD_CHARS` characters in UTF-8.
+pub(crate) const MAX_WORD_BYTES: usize = MAX_WORD_CHARS * 4;
+
+/// Write `word` lowercased into `buffer` as UTF-8, for looking it up with
+/// `Transducer::prepare_str` without allocating. `word` must have at most
+/// `MAX_WORD_CHARS` characters.
+pub(crate) fn lower_utf8<'a>(word: &[char], buffer: &'a mut [u8; MAX_WORD_BYTES]) -> &'a str {
+    let mut len = 0;
+    for &c in word {
+        len += simple_lower(c).encode_utf8(&mut buffer[len..]).len();
+    }
+    std::str::from_utf8(&buffer[..len]).expect("encoded from chars")
+}
+
+/// The form of a word under which [`Analyzer::analyze_any_case`] found it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaseVariant {
+    /// The word as given (also reported when it was not found).
+    AsIs,
+    /// The word with all letters lowercased ("KOIRA" -> "koira").
+    Lowercased,
+    /// The word with only its first letter lowercased ("McDonald" ->
+    /// "mcDonald").
+    FirstLowered,
+}
+
+impl CaseVariant {
+    /// The variant an analyzer that always looks words up in lowercase
+    /// finds `word` under.
+    pub(crate) fn of_lowercase_lookup(word: &[char]) -> Self {
+        if word.iter().any(|&c| simple_lower(c) != c) {
+            Self::Lowercased
+        } else {
+            Self::AsIs
+        }
+    }
+}
 
 /// Trait for morphological analyzers.
 ///
@@ -22,6 +78,46 @@ pub trait Analyzer {
     /// The word is provided as a char slice for random-access indexing
     /// (needed by FinnishVfstAnalyzer's STRUCTURE parsing).
     fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis>;
+
+    /// Analyze a word regardless of its case: as given, then lowercased,
+    /// then with only the first letter lowercased. Returns the analyses of
+    /// the first variant that has any, and which variant that was.
+    ///
+    /// Callers should use this instead of lowercasing words themselves.
+    /// Analyzers that look words up in lowercase anyway, such as
+    /// [`FinnishVfstAnalyzer`], override it to analyze the word once.
+    fn analyze_any_case(&self, word: &[char], word_len: usize) -> (Vec<Analysis>, CaseVariant) {
+        let word = &word[..word_len];
+        let analyses = self.analyze(word, word_len);
+        if !analyses.is_empty() {
+            return (analyses, CaseVariant::AsIs);
+        }
+        let lowered: Vec<char> = word.iter().map(|&c| simple_lower(c)).collect();
+        if lowered != word {
+            let analyses = self.analyze(&lowered, word_len);
+            if !analyses.is_empty() {
+                return (analyses, CaseVariant::Lowercased);
+            }
+        }
+        let mut first_lowered = word.to_vec();
+        if let Some(first) = first_lowered.first_mut() {
+            *first = simple_lower(*first);
+        }
+        if first_lowered != word && first_lowered != lowered {
+            let analyses = self.analyze(&first_lowered, word_len);
+            if !analyses.is_empty() {
+                return (analyses, CaseVariant::FirstLowered);
+            }
+        }
+        (Vec::new(), CaseVariant::AsIs)
+    }
+
+    /// Generate the forms of `baseform` that have every attribute of
+    /// `target`. Analyzers without a generator return no forms, which is
+    /// the default.
+    fn generate(&self, _baseform: &str, _target: &Analysis) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Blanket implementation: a shared reference to an analyzer also
@@ -32,4 +128,86 @@ impl<T: Analyzer + ?Sized> Analyzer for &T {
     fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
         (**self).analyze(word, word_len)
     }
+
+    fn analyze_any_case(&self, word: &[char], word_len: usize) -> (Vec<Analysis>, CaseVariant) {
+        (**self).analyze_any_case(word, word_len)
+    }
+
+    fn generate(&self, baseform: &str, target: &Analysis) -> Vec<String> {
+        (**self).generate(baseform, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::analysis::ATTR_BASEFORM;
+
+    /// A case-sensitive analyzer that knows the given words.
+    struct WordList(&'static [&'static str]);
+
+    impl Analyzer for WordList {
+        fn analyze(&self, word: &[char], word_len: usize) -> Vec<Analysis> {
+            let word: String = word[..word_len].iter().collect();
+            if !self.0.contains(&word.as_str()) {
+                return Vec::new();
+            }
+            let mut analysis = Analysis::new();
+            analysis.set(ATTR_BASEFORM, word);
+            vec![analysis]
+        }
+    }
+
+    fn any_case(analyzer: &dyn Analyzer, word: &str) -> (Option<String>, CaseVariant) {
+        let word: Vec<char> = word.chars().collect();
+        let (analyses, variant) = analyzer.analyze_any_case(&word, word.len());
+        let baseform = analyses
+            .first()
+            .and_then(|a| a.get(ATTR_BASEFORM))
+            .map(str::to_string);
+        (baseform, variant)
+    }
+
+    #[test]
+    fn analyze_any_case_tries_variants_in_order() {
+        let analyzer = WordList(&["koira", "EU", "mcDonald"]);
+        let found = |word: &str| Some(word.to_string());
+        assert_eq!(
+            any_case(&analyzer, "koira"),
+            (found("koira"), CaseVariant::AsIs)
+        );
+        assert_eq!(
+            any_case(&analyzer, "KOIRA"),
+            (found("koira"), CaseVariant::Lowercased)
+        );
+        assert_eq!(
+            any_case(&analyzer, "Koira"),
+            (found("koira"), CaseVariant::Lowercased)
+        );
+        assert_eq!(any_case(&analyzer, "EU"), (found("EU"), CaseVariant::AsIs));
+        assert_eq!(
+            any_case(&analyzer, "McDonald"),
+            (found("mcDonald"), CaseVariant::FirstLowered)
+        );
+        assert_eq!(any_case(&analyzer, "Kissa"), (None, CaseVariant::AsIs));
+        // Through a reference, as the grammar checker and hyphenator use it
+        assert_eq!(any_case(&&analyzer, "KOIRA").1, CaseVariant::Lowercased);
+    }
+
+    #[test]
+    fn lowercase_lookup_variant() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(
+            CaseVariant::of_lowercase_lookup(&chars("koira")),
+            CaseVariant::AsIs
+        );
+        assert_eq!(
+            CaseVariant::of_lowercase_lookup(&chars("Koira")),
+            CaseVariant::Lowercased
+        );
+        assert_eq!(
+            CaseVariant::of_lowercase_lookup(&chars("123")),
+            CaseVariant::AsIs
+        );
+    }
 }
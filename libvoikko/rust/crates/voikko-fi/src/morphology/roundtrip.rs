@@ -0,0 +1,192 @@
+// There-and-back verification of analysis and generation.
+//
+// Every analysis of a word names a base form and a set of inflection
+// attributes. Generating the forms of that base form with those attributes
+// must give the word back; when it does not, the tag parser or the
+// generator disagrees with the dictionary. Running this over a corpus is a
+// regression test for both and a quality check for the dictionary.
+
+use voikko_core::analysis::{
+    ATTR_BASEFORM, ATTR_FSTOUTPUT, ATTR_STRUCTURE, ATTR_WEIGHT, ATTR_WORDBASES, ATTR_WORDIDS,
+    Analysis,
+};
+
+use super::Analyzer;
+use super::finnish::FinnishVfstAnalyzer;
+use super::generate::UNMARKED_CLITICS;
+
+/// Attributes that describe how a word was analyzed rather than which form
+/// it is. They are not part of the generation target.
+const NON_INFLECTION_ATTRIBUTES: [&str; 6] = [
+    ATTR_BASEFORM,
+    ATTR_FSTOUTPUT,
+    ATTR_STRUCTURE,
+    ATTR_WEIGHT,
+    ATTR_WORDBASES,
+    ATTR_WORDIDS,
+];
+
+/// Outcome of regenerating one analysis of a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundTripStatus {
+    /// The word is among the generated forms.
+    Match,
+    /// The word is not among the generated forms.
+    Mismatch,
+    /// The generator does not produce forms like this one: compounds,
+    /// derived words, forms with an unmarked clitic (-hAn, -pA, -pAs, the
+    /// -kA of "eikä") and
+    /// analyses without a base form.
+    Unsupported,
+}
+
+/// One analysis of a word and the forms generated back from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundTrip {
+    /// The analysis of the word.
+    pub analysis: Analysis,
+    /// The inflection attributes generated from: the analysis without its
+    /// base form, STRUCTURE, FSTOUTPUT and other non-inflection attributes.
+    pub target: Analysis,
+    /// Forms generated from the base form and `target`.
+    pub generated: Vec<String>,
+    /// Whether the word was generated back.
+    pub status: RoundTripStatus,
+}
+
+/// Analyze `word`, regenerate the surface form from each analysis and
+/// report the outcome per analysis. An unknown word gives an empty list.
+///
+/// Forms are compared ignoring case, as generated forms follow the casing
+/// of the lexicon entry ("Koira" at the start of a sentence gives back
+/// "koira").
+pub fn round_trip(analyzer: &FinnishVfstAnalyzer, word: &str) -> Vec<RoundTrip> {
+    let word_chars: Vec<char> = word.chars().collect();
+    analyzer
+        .analyze(&word_chars, word_chars.len())
+        .into_iter()
+        .map(|analysis| {
+            let target = generation_target(&analysis);
+            let generated = match analysis.get(ATTR_BASEFORM) {
+                Some(baseform) if is_single_entry(&analysis) => {
+                    analyzer.generate(baseform, &target)
+                }
+                _ => Vec::new(),
+            };
+            let status = classify(word, &analysis, &generated);
+            RoundTrip {
+                analysis,
+                target,
+                generated,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The inflection attributes of `analysis`.
+pub(crate) fn generation_target(analysis: &Analysis) -> Analysis {
+    let mut target = analysis.clone();
+    for key in NON_INFLECTION_ATTRIBUTES {
+        target.remove(key);
+    }
+    target
+}
+
+/// Return whether `analysis` is a form of a single lexicon entry, i.e. not
+/// a compound or a derived word. WORDBASES lists one `+part(base)` per
+/// entry.
+fn is_single_entry(analysis: &Analysis) -> bool {
+    analysis
+        .get(ATTR_WORDBASES)
+        .is_none_or(|wordbases| wordbases.matches('+').count() <= 1)
+}
+
+/// Compare `word` with the forms generated from its `analysis`.
+pub(crate) fn classify(word: &str, analysis: &Analysis, generated: &[String]) -> RoundTripStatus {
+    if analysis.get(ATTR_BASEFORM).is_none() || !is_single_entry(analysis) {
+        return RoundTripStatus::Unsupported;
+    }
+    let word = word.to_lowercase();
+    let generated: Vec<String> = generated.iter().map(|f| f.to_lowercase()).collect();
+    if generated.contains(&word) {
+        return RoundTripStatus::Match;
+    }
+    let has_unmarked_clitic = UNMARKED_CLITICS.iter().any(|clitic| {
+        word.strip_suffix(clitic)
+            .is_some_and(|stem| generated.iter().any(|f| f == stem))
+    });
+    if has_unmarked_clitic {
+        RoundTripStatus::Unsupported
+    } else {
+        RoundTripStatus::Mismatch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::analysis::{ATTR_CLASS, ATTR_NUMBER, ATTR_SIJAMUOTO};
+
+    fn analysis(baseform: &str, wordbases: &str) -> Analysis {
+        let mut analysis = Analysis::new();
+        analysis.set(ATTR_BASEFORM, baseform);
+        analysis.set(ATTR_WORDBASES, wordbases);
+        analysis.set(ATTR_STRUCTURE, "=pppppp");
+        analysis.set(ATTR_CLASS, "nimisana");
+        analysis.set(ATTR_SIJAMUOTO, "omanto");
+        analysis.set(ATTR_NUMBER, "plural");
+        analysis
+    }
+
+    fn forms(forms: &[&str]) -> Vec<String> {
+        forms.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn target_keeps_inflection_attributes() {
+        let target = generation_target(&analysis("koira", "+koira(koira)"));
+        let mut keys = target.keys();
+        keys.sort();
+        assert_eq!(keys, [ATTR_CLASS, ATTR_NUMBER, ATTR_SIJAMUOTO]);
+    }
+
+    #[test]
+    fn generated_word_matches_ignoring_case() {
+        let koira = analysis("koira", "+koira(koira)");
+        let generated = forms(&["koirien", "koirain"]);
+        assert_eq!(
+            classify("Koirien", &koira, &generated),
+            RoundTripStatus::Match
+        );
+        assert_eq!(
+            classify("koirten", &koira, &generated),
+            RoundTripStatus::Mismatch
+        );
+        assert_eq!(classify("koirien", &koira, &[]), RoundTripStatus::Mismatch);
+    }
+
+    #[test]
+    fn unmarked_clitic_is_unsupported() {
+        let koira = analysis("koira", "+koira(koira)");
+        assert_eq!(
+            classify("koirienhan", &koira, &forms(&["koirien"])),
+            RoundTripStatus::Unsupported
+        );
+    }
+
+    #[test]
+    fn compounds_and_missing_baseform_are_unsupported() {
+        let compound = analysis("linja-auto", "+linja(linja)+auto(auto)");
+        assert_eq!(
+            classify("linja-autojen", &compound, &[]),
+            RoundTripStatus::Unsupported
+        );
+        let mut no_baseform = analysis("koira", "+koira(koira)");
+        no_baseform.remove(ATTR_BASEFORM);
+        assert_eq!(
+            classify("koirien", &no_baseform, &[]),
+            RoundTripStatus::Unsupported
+        );
+    }
+}
@@ -9,6 +9,7 @@
 //
 // Origin: FinnishVfstAnalyzer.cpp (selected parsing functions)
 
+use voikko_core::analysis::Segment;
 use voikko_core::character::{is_vowel, simple_lower, simple_upper};
 
 // ---------------------------------------------------------------------------
@@ -843,61 +844,47 @@ pub(crate) fn parse_basic_attributes(fst_output: &[char]) -> BasicAttributes {
                     let code = extract_tag_code(fst_output, j, i);
 
                     match tag_char {
-                        'L' => {
-                            if !class_set || fst_output[j + 2] == ']' {
-                                if code == "nl" {
-                                    let comp = attrs.comparison;
-                                    if convert_nimi_laatusana_to_laatusana
-                                        || matches!(comp, Some("comparative") | Some("superlative"))
-                                        || (fst_len >= 4 && starts_with(fst_output, 0, "[Lu]"))
-                                    {
-                                        attrs.class = Some("laatusana");
-                                    } else {
-                                        attrs.class = Some("nimisana_laatusana");
-                                    }
-                                } else if let Some(cls) = lookup_class(&code) {
-                                    attrs.class = Some(cls);
+                        'L' if !class_set || fst_output[j + 2] == ']' => {
+                            if code == "nl" {
+                                let comp = attrs.comparison;
+                                if convert_nimi_laatusana_to_laatusana
+                                    || matches!(comp, Some("comparative") | Some("superlative"))
+                                    || (fst_len >= 4 && starts_with(fst_output, 0, "[Lu]"))
+                                {
+                                    attrs.class = Some("laatusana");
+                                } else {
+                                    attrs.class = Some("nimisana_laatusana");
                                 }
-                                class_set = true;
+                            } else if let Some(cls) = lookup_class(&code) {
+                                attrs.class = Some(cls);
                             }
+                            class_set = true;
                         }
-                        'N' => {
+                        'N' if attrs.number.is_none() => {
                             // NUMBER -- skip for etuliite and seikkasana
-                            if attrs.number.is_none() {
-                                let skip =
-                                    matches!(attrs.class, Some("etuliite") | Some("seikkasana"));
-                                if !skip {
-                                    attrs.number = lookup_number(&code);
-                                }
+                            let skip = matches!(attrs.class, Some("etuliite") | Some("seikkasana"));
+                            if !skip {
+                                attrs.number = lookup_number(&code);
                             }
                         }
-                        'P' => {
-                            if attrs.person.is_none() {
-                                attrs.person = lookup_person(&code);
-                            }
+                        'P' if attrs.person.is_none() => {
+                            attrs.person = lookup_person(&code);
                         }
-                        'S' => {
+                        'S' if attrs.sijamuoto.is_none() => {
                             // SIJAMUOTO -- skip for etuliite and seikkasana
-                            if attrs.sijamuoto.is_none() {
-                                let skip =
-                                    matches!(attrs.class, Some("etuliite") | Some("seikkasana"));
-                                if !skip {
-                                    attrs.sijamuoto = lookup_sijamuoto(&code);
-                                    if code == "sti" {
-                                        convert_nimi_laatusana_to_laatusana = true;
-                                    }
+                            let skip = matches!(attrs.class, Some("etuliite") | Some("seikkasana"));
+                            if !skip {
+                                attrs.sijamuoto = lookup_sijamuoto(&code);
+                                if code == "sti" {
+                                    convert_nimi_laatusana_to_laatusana = true;
                                 }
                             }
                         }
-                        'T' => {
-                            if attrs.class.is_none() && attrs.mood.is_none() {
-                                attrs.mood = lookup_mood(&code);
-                            }
+                        'T' if attrs.class.is_none() && attrs.mood.is_none() => {
+                            attrs.mood = lookup_mood(&code);
                         }
-                        'A' => {
-                            if attrs.tense.is_none() {
-                                attrs.tense = lookup_tense(&code);
-                            }
+                        'A' if attrs.tense.is_none() => {
+                            attrs.tense = lookup_tense(&code);
                         }
                         'F' => {
                             if code == "ko" {
@@ -906,51 +893,41 @@ pub(crate) fn parse_basic_attributes(fst_output: &[char]) -> BasicAttributes {
                                 attrs.focus = lookup_focus(&code);
                             }
                         }
-                        'O' => {
-                            if attrs.possessive.is_none() {
-                                attrs.possessive = lookup_possessive(&code);
-                            }
+                        'O' if attrs.possessive.is_none() => {
+                            attrs.possessive = lookup_possessive(&code);
                         }
-                        'C' => {
-                            if attrs.class.is_none() && attrs.comparison.is_none() {
-                                attrs.comparison = lookup_comparison(&code);
-                            }
+                        'C' if attrs.class.is_none() && attrs.comparison.is_none() => {
+                            attrs.comparison = lookup_comparison(&code);
                         }
-                        'E' => {
-                            if attrs.negative.is_none() {
-                                attrs.negative = lookup_negative(&code);
-                            }
+                        'E' if attrs.negative.is_none() => {
+                            attrs.negative = lookup_negative(&code);
                         }
-                        'R' => {
-                            if !bc_passed && attrs.participle.is_none() {
-                                let skip = matches!(
-                                    attrs.class,
-                                    Some(c) if c != "laatusana" && {
-                                        // Also allow if fst ends with [Ln]
-                                        !starts_with(fst_output, fst_len.saturating_sub(4), "[Ln]")
-                                    }
-                                );
-                                if !skip {
-                                    attrs.participle = lookup_participle(&code);
+                        'R' if !bc_passed && attrs.participle.is_none() => {
+                            let skip = matches!(
+                                attrs.class,
+                                Some(c) if c != "laatusana" && {
+                                    // Also allow if fst ends with [Ln]
+                                    !starts_with(fst_output, fst_len.saturating_sub(4), "[Ln]")
                                 }
+                            );
+                            if !skip {
+                                attrs.participle = lookup_participle(&code);
                             }
                         }
                         'I' => {
                             // Info flags — pass tag position for position-aware scanning
                             add_info_flag(&mut attrs, &code, fst_output, j);
                         }
-                        'B' => {
-                            if j >= 5 && fst_output[j + 2] == 'c' {
-                                if !class_set
-                                    && attrs.class.is_none()
-                                    && (fst_output[j - 1] == '-'
-                                        || (j >= 5 && starts_with(fst_output, j - 5, "-[Bh]")))
-                                {
-                                    attrs.class = Some("etuliite");
-                                    class_set = true;
-                                }
-                                bc_passed = true;
+                        'B' if j >= 5 && fst_output[j + 2] == 'c' => {
+                            if !class_set
+                                && attrs.class.is_none()
+                                && (fst_output[j - 1] == '-'
+                                    || (j >= 5 && starts_with(fst_output, j - 5, "-[Bh]")))
+                            {
+                                attrs.class = Some("etuliite");
+                                class_set = true;
                             }
+                            bc_passed = true;
                         }
                         _ => {}
                     }
@@ -979,14 +956,15 @@ fn add_info_flag(attrs: &mut BasicAttributes, code: &str, fst_output: &[char], t
             attrs.malaga_vapaa_jalkiosa = true;
         }
     } else if code == "ca" {
-        // Check: no [Bc] or [Ll] AFTER this tag position, and class is nimisana or unset.
+        // Check: no [Bc] or [Ll] AFTER this tag position, and class is unset or
+        // starts with "nimisana" (so nimisana_laatusana qualifies too).
         // The C++ code scans from `outputPosition` (current position) forward, not the
         // entire string. We replicate this by scanning only from `tag_pos` forward.
         // Origin: FinnishVfstAnalyzer.cpp:442
         let suffix = &fst_output[tag_pos..];
         let has_bc = suffix.windows(4).any(|w| w == ['[', 'B', 'c', ']']);
         let has_ll = suffix.windows(4).any(|w| w == ['[', 'L', 'l', ']']);
-        if !has_bc && !has_ll && matches!(attrs.class, None | Some("nimisana")) {
+        if !has_bc && !has_ll && attrs.class.is_none_or(|c| c.starts_with("nimisana")) {
             attrs.possible_geographical_name = true;
         }
     } else if code == "ra" {
@@ -1200,6 +1178,131 @@ fn debug_content_end(
     }
 }
 
+// ---------------------------------------------------------------------------
+// parse_segments
+// ---------------------------------------------------------------------------
+
+/// Split the word behind an FST output into its parts, with the range of
+/// word characters each covers.
+///
+/// A part ends at a compound boundary (`[Bx]` other than `[Bh]`, as in
+/// STRUCTURE) and where a class tag `[Lx]` follows surface characters, which
+/// starts a derivational morph ("juoksi" + "ja"). A hyphen before a boundary
+/// tag joins two parts and belongs to neither. The base form of a part is
+/// the content of its first `[Xp]` or `[Xj]` block. If the surface
+/// characters do not add up to `word_len`, the whole word is one part
+/// without a base form.
+pub(crate) fn parse_segments(fst_output: &[char], word_len: usize) -> Vec<Segment> {
+    let len = fst_output.len();
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut baseform: Option<String> = None;
+    let mut start = 0;
+    let mut pos = 0;
+
+    // Close the part ending at `pos`. A base form seen before any surface
+    // character belongs to the next part.
+    let mut end_part = |start: usize, pos: usize, baseform: &mut Option<String>| {
+        if pos > start {
+            segments.push(Segment {
+                range: start..pos,
+                baseform: baseform.take(),
+            });
+        }
+    };
+
+    let mut i = 0;
+    while i < len {
+        if fst_output[i] == '-' && starts_with(fst_output, i + 1, "[B") {
+            end_part(start, pos, &mut baseform);
+            pos += 1;
+            start = pos;
+            i += 1;
+        } else if fst_output[i] == '[' {
+            let Some(close) = fst_output[i..].iter().position(|&c| c == ']') else {
+                break;
+            };
+            let close = i + close;
+            match &fst_output[i + 1..close] {
+                ['B', kind] if *kind != 'h' => {
+                    end_part(start, pos, &mut baseform);
+                    start = pos;
+                }
+                ['L', ..] if pos > start => {
+                    end_part(start, pos, &mut baseform);
+                    start = pos;
+                }
+                ['X', kind] => {
+                    // Skip the block content up to the closing [X]
+                    let content = close + 1;
+                    let end = fst_output[content..]
+                        .iter()
+                        .position(|&c| c == '[')
+                        .map_or(len, |p| content + p);
+                    if matches!(kind, 'p' | 'j') && baseform.is_none() {
+                        baseform = Some(fst_output[content..end].iter().collect());
+                    }
+                    i = end;
+                    continue;
+                }
+                _ => {}
+            }
+            i = close + 1;
+        } else {
+            pos += 1;
+            i += 1;
+        }
+    }
+    end_part(start, pos, &mut baseform);
+
+    if pos != word_len {
+        return vec![Segment {
+            range: 0..word_len,
+            baseform: None,
+        }];
+    }
+    segments
+}
+
+// ---------------------------------------------------------------------------
+// apply_structure_case
+// ---------------------------------------------------------------------------
+
+/// Apply case corrections to a word based on its STRUCTURE attribute.
+///
+/// The STRUCTURE attribute encodes the expected case for each letter:
+/// - `i` / `j` => the corresponding letter should be uppercase
+/// - `p` / `q` => the corresponding letter should be lowercase
+/// - `=` => compound boundary marker (skipped; does not consume a word char)
+///
+/// Origin: SuggestionGeneratorCaseChange.cpp:86-101
+pub(crate) fn apply_structure_case(word: &[char], structure: &str) -> Vec<char> {
+    let mut result: Vec<char> = word.to_vec();
+    let struct_chars: Vec<char> = structure.chars().collect();
+    let mut j = 0;
+
+    for ch in &mut result {
+        // Skip compound boundary markers.
+        while j < struct_chars.len() && struct_chars[j] == '=' {
+            j += 1;
+        }
+        if j >= struct_chars.len() {
+            break;
+        }
+        match struct_chars[j] {
+            'i' | 'j' => {
+                *ch = simple_upper(*ch);
+            }
+            'p' | 'q' => {
+                *ch = simple_lower(*ch);
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+
+    result
+}
+
 // ---------------------------------------------------------------------------
 // fix_structure
 // ---------------------------------------------------------------------------
@@ -1274,6 +1377,73 @@ mod tests {
         s.chars().collect()
     }
 
+    // -- parse_segments tests --
+
+    /// Segments as "start..end:baseform", with "-" for no base form.
+    fn segments(fst: &str, word_len: usize) -> Vec<String> {
+        parse_segments(&chars(fst), word_len)
+            .into_iter()
+            .map(|s| format!("{:?}:{}", s.range, s.baseform.as_deref().unwrap_or("-")))
+            .collect()
+    }
+
+    #[test]
+    fn segments_simple_word() {
+        assert_eq!(
+            segments("[Ln][Xp]kaunis[X]kaune[Sn][Nm][Cs]immat", 10),
+            ["0..10:kaunis"]
+        );
+    }
+
+    #[test]
+    fn segments_compound() {
+        // koiran|koppi
+        assert_eq!(
+            segments(
+                "[Ln][Xp]koira[X]koir[Sg][Ny]an[Bh][Bc][Ln][Xp]koppi[X]kopp[Sn][Ny]i",
+                11
+            ),
+            ["0..6:koira", "6..11:koppi"]
+        );
+    }
+
+    #[test]
+    fn segments_hyphenated_compound() {
+        // maa-auto: the hyphen belongs to neither part
+        assert_eq!(
+            segments(
+                "[Ln][Ica][Xp]maa[X]maa[Sn][Ny]-[Bh][Bc][Ln][Xp]auto[X]auto[Sn][Ny]",
+                8
+            ),
+            ["0..3:maa", "4..8:auto"]
+        );
+    }
+
+    #[test]
+    fn segments_derivation() {
+        // epä|järjestelmä|llisy|ys
+        assert_eq!(
+            segments(
+                "[Lp]epä[Bh][Bc][Ln][Xp]järjestelmä[X]järjestelmä[Ll]lli[Xj]nen[X]sy[Ln][Xj]ys[X]y[Sn][Ny]s",
+                21
+            ),
+            ["0..3:-", "3..14:järjestelmä", "14..19:nen", "19..21:ys",]
+        );
+    }
+
+    #[test]
+    fn segments_skip_structure_override() {
+        assert_eq!(
+            segments("[La][Xr]jjj[X][Xp]USA[X]usa[Sine][Ny]:ssa", 7),
+            ["0..7:USA"]
+        );
+    }
+
+    #[test]
+    fn segments_length_mismatch_covers_word() {
+        assert_eq!(segments("[Ln][Xp]koira[X]koira[Sn][Ny]", 7), ["0..7:-"]);
+    }
+
     // -- parse_structure tests --
 
     #[test]
@@ -1524,4 +1694,49 @@ mod tests {
         // [Dg] should force the 'i' to 'p'
         assert_eq!(structure[1], 'p');
     }
+
+    // --- apply_structure_case ---
+
+    #[test]
+    fn apply_structure_case_all_lowercase() {
+        let word = chars("koira");
+        let result = apply_structure_case(&word, "=ppppp");
+        let s: String = result.iter().collect();
+        assert_eq!(s, "koira");
+    }
+
+    #[test]
+    fn apply_structure_case_first_uppercase() {
+        let word = chars("helsinki");
+        let result = apply_structure_case(&word, "=ippppppp");
+        let s: String = result.iter().collect();
+        assert_eq!(s, "Helsinki");
+    }
+
+    #[test]
+    fn apply_structure_case_mixed() {
+        // "abc" with structure "=ipq" -> "Abc"
+        let word = chars("abc");
+        let result = apply_structure_case(&word, "=ipq");
+        let s: String = result.iter().collect();
+        assert_eq!(s, "Abc");
+    }
+
+    #[test]
+    fn apply_structure_case_compound() {
+        // Compound word: skip '=' markers
+        let word = chars("koiratalo");
+        let result = apply_structure_case(&word, "=ppppp=pppp");
+        let s: String = result.iter().collect();
+        assert_eq!(s, "koiratalo");
+    }
+
+    #[test]
+    fn apply_structure_case_uppercase_to_lowercase() {
+        // "KOIRA" with structure "=ppppp" -> "koira"
+        let word = chars("KOIRA");
+        let result = apply_structure_case(&word, "=ppppp");
+        let s: String = result.iter().collect();
+        assert_eq!(s, "koira");
+    }
 }
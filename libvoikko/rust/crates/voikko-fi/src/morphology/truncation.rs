@@ -0,0 +1,169 @@
+// Counters for dictionary lookups cut short by the traversal budget
+//
+// A lookup that reaches a limit of its `TraversalBudget` stops and returns
+// the analyses found so far. With the default budget this is the C++
+// MAX_LOOP_COUNT safety limit, which a loop in the dictionary can hit for
+// ordinary words; the word then silently misses analyses. The analyzer
+// counts these lookups by the limit that stopped them and, if enabled,
+// keeps the most recent words as diagnostics for reproducing the case.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+use voikko_fst::config::UnweightedConfig;
+
+/// Number of truncated lookups kept as diagnostics; older ones are dropped.
+const MAX_TRUNCATED_LOOKUPS: usize = 32;
+
+/// The limit of a [`TraversalBudget`](voikko_fst::config::TraversalBudget)
+/// that stopped a lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TruncationReason {
+    /// The step limit (`max_steps`, by default the C++ `MAX_LOOP_COUNT`)
+    /// or the maximum stack depth was reached.
+    Steps,
+    /// The output limit (`max_outputs`) was reached.
+    Outputs,
+    /// The deadline passed.
+    Deadline,
+}
+
+impl TruncationReason {
+    /// The limit that stopped a traversal of `config` that returned
+    /// `NextStatus::BudgetExceeded`.
+    pub(crate) fn of(config: &UnweightedConfig) -> Self {
+        let budget = config.budget;
+        if config.output_count >= budget.max_outputs {
+            Self::Outputs
+        } else if budget.deadline.is_some_and(|d| Instant::now() >= d) {
+            Self::Deadline
+        } else {
+            Self::Steps
+        }
+    }
+}
+
+/// Number of dictionary lookups stopped by each limit of the traversal
+/// budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraversalStats {
+    /// Lookups stopped by the step limit or the stack depth.
+    pub steps: u64,
+    /// Lookups stopped by the output limit.
+    pub outputs: u64,
+    /// Lookups stopped by the deadline.
+    pub deadline: u64,
+}
+
+impl TraversalStats {
+    /// Total number of truncated lookups.
+    pub fn total(&self) -> u64 {
+        self.steps + self.outputs + self.deadline
+    }
+}
+
+/// A dictionary lookup stopped by the traversal budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruncatedLookup {
+    /// The word looked up, as given to the analyzer.
+    pub word: String,
+    /// The limit that stopped the lookup.
+    pub reason: TruncationReason,
+}
+
+/// Truncation counters and diagnostics of an analyzer.
+#[derive(Debug, Default)]
+pub(crate) struct Truncations {
+    steps: AtomicU64,
+    outputs: AtomicU64,
+    deadline: AtomicU64,
+    /// Whether `record` keeps the word.
+    pub(crate) diagnostics: bool,
+    recent: Mutex<VecDeque<TruncatedLookup>>,
+}
+
+impl Truncations {
+    /// Count a lookup of `word` stopped for `reason`.
+    pub(crate) fn record(&self, word: &[char], reason: TruncationReason) {
+        let counter = match reason {
+            TruncationReason::Steps => &self.steps,
+            TruncationReason::Outputs => &self.outputs,
+            TruncationReason::Deadline => &self.deadline,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if self.diagnostics {
+            let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+            if recent.len() == MAX_TRUNCATED_LOOKUPS {
+                recent.pop_front();
+            }
+            recent.push_back(TruncatedLookup {
+                word: word.iter().collect(),
+                reason,
+            });
+        }
+    }
+
+    pub(crate) fn stats(&self) -> TraversalStats {
+        TraversalStats {
+            steps: self.steps.load(Ordering::Relaxed),
+            outputs: self.outputs.load(Ordering::Relaxed),
+            deadline: self.deadline.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn recent(&self) -> Vec<TruncatedLookup> {
+        let recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+        recent.iter().cloned().collect()
+    }
+
+    pub(crate) fn reset(&self) {
+        self.steps.store(0, Ordering::Relaxed);
+        self.outputs.store(0, Ordering::Relaxed);
+        self.deadline.store(0, Ordering::Relaxed);
+        self.recent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_by_reason_and_keeps_recent_words() {
+        let mut truncations = Truncations::default();
+        truncations.record(&['a'], TruncationReason::Steps);
+        assert!(truncations.recent().is_empty());
+
+        truncations.diagnostics = true;
+        for i in 0..MAX_TRUNCATED_LOOKUPS + 2 {
+            let word: Vec<char> = i.to_string().chars().collect();
+            truncations.record(&word, TruncationReason::Outputs);
+        }
+        truncations.record(&['b'], TruncationReason::Deadline);
+        let stats = truncations.stats();
+        assert_eq!(
+            (stats.steps, stats.outputs, stats.deadline),
+            (1, MAX_TRUNCATED_LOOKUPS as u64 + 2, 1)
+        );
+        assert_eq!(stats.total(), MAX_TRUNCATED_LOOKUPS as u64 + 4);
+        let recent = truncations.recent();
+        assert_eq!(recent.len(), MAX_TRUNCATED_LOOKUPS);
+        assert_eq!(recent[0].word, "3");
+        assert_eq!(
+            recent.last(),
+            Some(&TruncatedLookup {
+                word: "b".to_string(),
+                reason: TruncationReason::Deadline
+            })
+        );
+
+        truncations.reset();
+        assert_eq!(truncations.stats(), TraversalStats::default());
+        assert!(truncations.recent().is_empty());
+    }
+}
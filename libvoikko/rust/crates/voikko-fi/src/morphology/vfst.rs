@@ -7,14 +7,13 @@
 // Origin: VfstAnalyzer.cpp (~120 lines)
 
 use voikko_core::analysis::{ATTR_FSTOUTPUT, ATTR_WEIGHT, Analysis};
-use voikko_core::case::CaseType;
 use voikko_core::enums::MAX_WORD_CHARS;
 use voikko_fst::Transducer;
 use voikko_fst::config::WeightedConfig;
 use voikko_fst::weighted::{WeightedResult, WeightedTransducer};
 
-use super::Analyzer;
 use super::tag_parser::{BUFFER_SIZE, MAX_ANALYSIS_COUNT};
+use super::{Analyzer, MAX_WORD_BYTES, lower_utf8};
 
 /// Generic morphological analyzer using a weighted VFST transducer.
 ///
@@ -60,13 +59,12 @@ impl VfstAnalyzer {
             return Vec::new();
         }
 
-        // Lowercase the input
-        let mut word_lower: Vec<char> = word[..word_len].to_vec();
-        voikko_core::case::set_case(&mut word_lower, CaseType::AllLower);
+        let mut buffer = [0; MAX_WORD_BYTES];
+        let word_lower = lower_utf8(&word[..word_len], &mut buffer);
 
         let mut analyses = Vec::new();
 
-        if !self.transducer.prepare(&mut self.config, &word_lower) {
+        if !self.transducer.prepare_str(&mut self.config, word_lower) {
             return analyses;
         }
 
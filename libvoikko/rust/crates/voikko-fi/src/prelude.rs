@@ -0,0 +1,63 @@
+// Prelude: the supported public API in one import
+// `use voikko_fi::prelude::*;` brings in the types an application needs to
+// check, analyze, hyphenate and tokenize text. The items re-exported here
+// are the stable surface; module paths such as `speller::pipeline` or
+// `suggestion::generators` are building blocks that may change between
+// minor versions. Changes to either show up in the public API snapshot
+// (`tests/public_api.txt`).
+
+pub use voikko_core::analysis::{Analysis, Segment};
+pub use voikko_core::catalog::{CatalogError, MessageCatalog};
+pub use voikko_core::enums::{SentenceType, SpellResult, TokenType};
+pub use voikko_core::grammar_error::{
+    GrammarError, GrammarErrorCategory, GrammarErrorSeverity, Replacement,
+};
+pub use voikko_core::token::{Sentence, SentenceSpan, SentenceWhitespace, Token};
+
+pub use crate::input_map::InputMapping;
+pub use crate::invisible::InvisibleChars;
+pub use crate::language_rules::LanguageRules;
+pub use crate::marked_text::MarkedText;
+pub use crate::tokenizer::{TextEdit, TokenChange, retokenize};
+
+#[cfg(feature = "dictpack")]
+pub use crate::dictpack::{DictPack, DictPackError};
+#[cfg(feature = "spell")]
+pub use crate::document::SpellingIssue;
+#[cfg(feature = "grammar")]
+pub use crate::grammar::analyzed::{AnalyzedParagraph, AnalyzedSentence, AnalyzedToken};
+#[cfg(feature = "grammar")]
+pub use crate::grammar::autocorrect_rules::{AutocorrectRuleError, AutocorrectRules};
+#[cfg(feature = "grammar")]
+pub use crate::grammar::numbers::NumberStyle;
+#[cfg(feature = "grammar")]
+pub use crate::grammar::timing::{RuleStats, SlowRule};
+#[cfg(feature = "handle")]
+pub use crate::handle::{HandleProfile, SpellStatus, VoikkoError, VoikkoHandle, WordCheck};
+#[cfg(feature = "hyphenate")]
+pub use crate::hyphenator::{
+    HyphenExplanation, HyphenReason, HyphenationExceptionError, HyphenationExceptions,
+    HyphenatorOptions,
+};
+#[cfg(feature = "analyze")]
+pub use crate::morphology::{
+    Analyzer, AnalyzerOptions, CaseVariant, CompoundInfo, DictionaryRemovals,
+    DictionaryRemovalsError, FinnishVfstAnalyzer, LoadState, TraversalStats, TruncatedLookup,
+    TruncationReason,
+};
+#[cfg(feature = "handle")]
+pub use crate::spell_service::{TokenSpellService, TokenSpelling};
+#[cfg(feature = "handle")]
+pub use crate::spell_session::SpellSession;
+#[cfg(feature = "spell")]
+pub use crate::speller::Speller;
+#[cfg(feature = "spell")]
+pub use crate::speller::cache::CacheStats;
+#[cfg(feature = "spell")]
+pub use crate::speller::user_dictionary::{UserDictionary, UserDictionaryError};
+#[cfg(feature = "handle")]
+pub use crate::stream::{SpellStreamFormat, SpellStreamOptions, SpellStreamStats};
+#[cfg(feature = "suggest")]
+pub use crate::suggestion::{CancelToken, RankingWeights, ScoredSuggestion, SuggestionBudget};
+#[cfg(feature = "spell")]
+pub use crate::user_state::{UserState, UserStateError};
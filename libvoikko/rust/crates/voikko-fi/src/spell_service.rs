@@ -0,0 +1,170 @@
+// Shared spelling verdicts for the words of running text.
+//
+// Several surfaces of the handle decide whether a word is misspelled:
+// `spell()` and its variants, document and text checks, abbreviation
+// detection in sentence splitting, the misspelled flag of analyzed
+// paragraphs and the grammar checker's handling of autocorrect matches.
+// They all ask a `TokenSpellService`, which applies the same steps in the
+// same order -- invisible characters, the length limit, the user
+// dictionary and ignore list, the speller cache with the spelling options,
+// and inflected forms of user words -- so that for the same text and
+// options every surface classifies a word alike.
+//
+// The service borrows its state from the handle; it is created per call by
+// `VoikkoHandle::spell_service` and costs nothing to create.
+
+use std::borrow::Cow;
+
+use voikko_core::analysis::ATTR_BASEFORM;
+use voikko_core::enums::TokenType;
+use voikko_core::token::Token;
+
+use crate::invisible::{self, InvisibleChars};
+use crate::morphology::{Analyzer, FinnishVfstAnalyzer};
+use crate::speller::adapter::AnalyzerToSpellerAdapter;
+use crate::speller::cache::SharedSpellerCache;
+use crate::speller::finnish::{FinnishSpellerOptions, FinnishSpellerTweaksWrapper};
+use crate::speller::pipeline::{SpellOptions, spell_check};
+use crate::tokenizer;
+use crate::user_state::UserState;
+
+/// How the spell checker classifies a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSpelling {
+    /// The word is accepted.
+    Correct,
+    /// The word is rejected.
+    Misspelled,
+    /// The word is longer than the maximum word length and is not checked;
+    /// it counts as misspelled.
+    TooLong,
+}
+
+impl TokenSpelling {
+    /// Whether the word counts as misspelled.
+    pub fn is_misspelled(self) -> bool {
+        self != TokenSpelling::Correct
+    }
+}
+
+/// Spelling decisions shared by every surface of a [`VoikkoHandle`].
+///
+/// Obtained from [`VoikkoHandle::spell_service`]; it sees the handle's
+/// options, user dictionary, ignore list and speller cache as they are
+/// when it is created.
+///
+/// [`VoikkoHandle`]: crate::handle::VoikkoHandle
+/// [`VoikkoHandle::spell_service`]: crate::handle::VoikkoHandle::spell_service
+#[derive(Clone, Copy)]
+pub struct TokenSpellService<'a> {
+    analyzer: &'a FinnishVfstAnalyzer,
+    user_state: &'a UserState,
+    spell_options: &'a SpellOptions,
+    finnish_spell_options: FinnishSpellerOptions,
+    cache: &'a SharedSpellerCache,
+    invisible_chars: InvisibleChars,
+}
+
+impl<'a> TokenSpellService<'a> {
+    pub(crate) fn new(
+        analyzer: &'a FinnishVfstAnalyzer,
+        user_state: &'a UserState,
+        spell_options: &'a SpellOptions,
+        finnish_spell_options: FinnishSpellerOptions,
+        cache: &'a SharedSpellerCache,
+        invisible_chars: InvisibleChars,
+    ) -> Self {
+        Self {
+            analyzer,
+            user_state,
+            spell_options,
+            finnish_spell_options,
+            cache,
+            invisible_chars,
+        }
+    }
+
+    /// Classify `word`.
+    pub fn classify(&self, word: &str) -> TokenSpelling {
+        let word = self.visible(word);
+        let word = word.as_ref();
+        let word_chars: Vec<char> = word.chars().collect();
+        if self.is_too_long(&word_chars) {
+            TokenSpelling::TooLong
+        } else if self.spell_chars(word, &word_chars) {
+            TokenSpelling::Correct
+        } else {
+            TokenSpelling::Misspelled
+        }
+    }
+
+    /// Whether `word` is accepted, as `VoikkoHandle::spell` reports it.
+    pub fn is_correct(&self, word: &str) -> bool {
+        self.classify(word) == TokenSpelling::Correct
+    }
+
+    /// The word tokens of `text` that are misspelled, tokenized as
+    /// `VoikkoHandle::tokens` does.
+    pub fn misspelled_tokens(&self, text: &str) -> Vec<Token> {
+        let tokens = match self.invisible_chars {
+            InvisibleChars::Keep => tokenizer::tokenize(text),
+            InvisibleChars::Strip => invisible::tokenize_stripped(text),
+        };
+        tokens
+            .into_iter()
+            .filter(|t| t.token_type == TokenType::Word && self.classify(&t.text).is_misspelled())
+            .collect()
+    }
+
+    /// Whether the user accepted `word`: it is in the user dictionary or the
+    /// ignore list, or an inflected form of a user word. Such words are
+    /// never reported, also not by the grammar checker's autocorrection.
+    pub fn is_user_accepted(&self, word: &str) -> bool {
+        let word = self.visible(word);
+        self.user_state.accepts(&word) || self.is_user_word_form(&word)
+    }
+
+    /// Whether `word` is longer than the maximum word length.
+    pub(crate) fn is_too_long(&self, word: &[char]) -> bool {
+        word.len() > self.spell_options.max_word_chars
+    }
+
+    /// `word` without invisible characters, if they are stripped.
+    pub(crate) fn visible<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        match self.invisible_chars {
+            InvisibleChars::Keep => Cow::Borrowed(word),
+            InvisibleChars::Strip => invisible::strip_word(word),
+        }
+    }
+
+    /// Spell check a visible word already converted to characters.
+    pub(crate) fn spell_chars(&self, word: &str, word_chars: &[char]) -> bool {
+        if self.user_state.accepts(word) {
+            return true;
+        }
+        let adapter = AnalyzerToSpellerAdapter::new(self.analyzer);
+        let tweaks =
+            FinnishSpellerTweaksWrapper::new(&adapter, self.analyzer, self.finnish_spell_options);
+        let generation = self.analyzer.dictionary_generation();
+        self.cache.with_shard(word_chars, generation, |cache| {
+            spell_check(word_chars, &tweaks, cache, self.spell_options) == 1
+        }) || self.is_user_word_form(word)
+    }
+
+    /// Return whether `word` is an inflected form of a user dictionary
+    /// entry with an inflection class hint.
+    pub(crate) fn is_user_word_form(&self, word: &str) -> bool {
+        self.user_state
+            .user_words
+            .template_forms(word)
+            .iter()
+            .any(|template| {
+                let form: Vec<char> = template.form.chars().collect();
+                let hint = template.hint.to_lowercase();
+                self.analyzer.analyze(&form, form.len()).iter().any(|a| {
+                    a.get(ATTR_BASEFORM)
+                        .is_some_and(|b| b.to_lowercase() == hint)
+                })
+            })
+    }
+}
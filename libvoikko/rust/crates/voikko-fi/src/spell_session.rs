@@ -0,0 +1,193 @@
+// Spell checking of one document in an editor.
+//
+// An editor checks the same document again after every edit. A
+// `SpellSession` keeps the document's tokens and updates them with
+// `retokenize`, so only the tokens around an edit are tokenized again, and
+// it holds the words the user chose to ignore in this document ("Ignore in
+// this document"). Session words are consulted only by the session: they
+// never reach the handle's user dictionary or ignore list, and they are
+// dropped with the session.
+//
+// The session holds no reference to the handle. Each check takes the
+// handle's `TokenSpellService`, so the handle's options, user dictionary
+// and speller cache apply as they are at the time of the check.
+
+use std::collections::BTreeSet;
+
+use voikko_core::enums::TokenType;
+use voikko_core::token::Token;
+
+use crate::spell_service::{TokenSpellService, TokenSpelling};
+use crate::tokenizer::{self, TextEdit, TokenChange, retokenize};
+
+/// The tokens of one document and the words ignored in it.
+#[derive(Debug, Clone, Default)]
+pub struct SpellSession {
+    tokens: Vec<Token>,
+    session_words: BTreeSet<String>,
+}
+
+impl SpellSession {
+    /// Start a session on `text`.
+    pub fn new(text: &str) -> Self {
+        Self {
+            tokens: tokenizer::tokenize(text),
+            session_words: BTreeSet::new(),
+        }
+    }
+
+    /// Replace the whole text, keeping the session words.
+    pub fn set_text(&mut self, text: &str) {
+        self.tokens = tokenizer::tokenize(text);
+    }
+
+    /// Apply an edit to the text and return the tokens that changed.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> TokenChange {
+        let change = retokenize(&self.tokens, edit);
+        change.apply(&mut self.tokens);
+        change
+    }
+
+    /// The tokens of the current text.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// The current text.
+    pub fn text(&self) -> String {
+        self.tokens.iter().map(|t| t.text.as_str()).collect()
+    }
+
+    /// Accept `word` in this session only. As in the ignore list, an entry
+    /// written entirely in lowercase also accepts the word with other
+    /// capitalization.
+    pub fn add_session_word(&mut self, word: &str) {
+        self.session_words.insert(word.to_string());
+    }
+
+    /// Remove a session word. Returns `true` if it was there.
+    pub fn remove_session_word(&mut self, word: &str) -> bool {
+        self.session_words.remove(word)
+    }
+
+    /// Remove all session words.
+    pub fn clear_session_words(&mut self) {
+        self.session_words.clear();
+    }
+
+    /// The session words, in sorted order.
+    pub fn session_words(&self) -> impl Iterator<Item = &str> {
+        self.session_words.iter().map(String::as_str)
+    }
+
+    /// Whether `word` is accepted by a session word.
+    pub fn is_session_word(&self, word: &str) -> bool {
+        if self.session_words.contains(word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        lower != word && self.session_words.contains(&lower)
+    }
+
+    /// Classify `word` with `service`, accepting the session words first.
+    /// A session word longer than the maximum word length is still too
+    /// long, as with the user dictionary.
+    pub fn classify(&self, service: &TokenSpellService<'_>, word: &str) -> TokenSpelling {
+        let spelling = service.classify(word);
+        if spelling == TokenSpelling::Misspelled && self.is_session_word(&service.visible(word)) {
+            TokenSpelling::Correct
+        } else {
+            spelling
+        }
+    }
+
+    /// The misspelled word tokens of the current text.
+    pub fn misspelled_tokens(&self, service: &TokenSpellService<'_>) -> Vec<Token> {
+        self.tokens
+            .iter()
+            .filter(|t| {
+                t.token_type == TokenType::Word && self.classify(service, &t.text).is_misspelled()
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle::VoikkoHandle;
+    use crate::test_util::empty_handle;
+
+    fn misspelled(session: &SpellSession, handle: &VoikkoHandle) -> Vec<String> {
+        session
+            .misspelled_tokens(&handle.spell_service())
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn session_words_stay_in_the_session() {
+        let handle = empty_handle();
+        let mut session = SpellSession::new("Voikko ja libvoikko, voikko.");
+        let other = SpellSession::new("voikko");
+        assert_eq!(
+            misspelled(&session, &handle),
+            ["Voikko", "ja", "libvoikko", "voikko"]
+        );
+
+        session.add_session_word("voikko");
+        session.add_session_word("ja");
+        assert_eq!(misspelled(&session, &handle), ["libvoikko"]);
+        // Not in other sessions or the handle
+        assert_eq!(misspelled(&other, &handle), ["voikko"]);
+        assert!(!handle.spell("voikko"));
+        assert!(handle.user_state().is_empty());
+
+        assert!(session.remove_session_word("ja"));
+        assert!(!session.remove_session_word("ja"));
+        assert_eq!(session.session_words().collect::<Vec<_>>(), ["voikko"]);
+        assert_eq!(misspelled(&session, &handle), ["ja", "libvoikko"]);
+        session.clear_session_words();
+        assert_eq!(misspelled(&session, &handle).len(), 4);
+    }
+
+    #[test]
+    fn capitalized_entries_are_case_sensitive() {
+        let handle = empty_handle();
+        let mut session = SpellSession::new("Voikko voikko VOIKKO");
+        session.add_session_word("Voikko");
+        assert_eq!(misspelled(&session, &handle), ["voikko", "VOIKKO"]);
+    }
+
+    #[test]
+    fn edits_update_the_tokens() {
+        let handle = empty_handle();
+        let mut session = SpellSession::new("koira ja kissa");
+        session.add_session_word("koira");
+        session.add_session_word("kissa");
+        assert_eq!(misspelled(&session, &handle), ["ja"]);
+
+        let change = session.apply_edit(&TextEdit::new(6, 8, "tai"));
+        assert_eq!(change.char_delta, 1);
+        assert_eq!(session.text(), "koira tai kissa");
+        assert_eq!(session.tokens(), tokenizer::tokenize("koira tai kissa"));
+        let tokens = session.misspelled_tokens(&handle.spell_service());
+        assert_eq!(tokens.len(), 1);
+        assert_eq!((tokens[0].text.as_str(), tokens[0].pos), ("tai", 6));
+
+        session.set_text("kissa");
+        assert!(misspelled(&session, &handle).is_empty());
+    }
+
+    #[test]
+    fn handle_limits_still_apply() {
+        let mut handle = empty_handle();
+        handle.set_max_word_chars(5);
+        let mut session = SpellSession::new("voikko");
+        session.add_session_word("voikko");
+        let service = handle.spell_service();
+        assert_eq!(session.classify(&service, "voikko"), TokenSpelling::TooLong);
+    }
+}
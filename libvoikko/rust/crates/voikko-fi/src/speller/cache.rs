@@ -1,37 +1,30 @@
-// Hash-based spell result cache for words up to 10 characters
+// LRU spell result cache for words up to 10 characters
 // Origin: spellchecker/SpellerCache.cpp, SpellerCache.hpp
+//
+// The C++ cache is a fixed hash table whose colliding entries overwrite each
+// other. This version keeps the same API and size parameter but evicts the
+// least recently used entry, counts hits and misses, and can be resized
+// without being emptied.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError, TryLockError};
 
 use voikko_core::enums::SpellResult;
 
+pub use crate::morphology::CacheStats;
 use crate::speller::Speller;
 
 /// Maximum word length that can be cached.
 const MAX_CACHED_WORD_LEN: usize = 10;
 
-/// Hash orders for each word length (1-indexed, index 0 unused).
-///
-/// Origin: SpellerCache.cpp:49
-const HASH_ORDERS: [i32; 11] = [0, 3, 5, 6, 7, 7, 7, 7, 7, 7, 7];
-
-/// Offsets into the word storage array for each word length.
-///
-/// Origin: SpellerCache.cpp:50
-const CACHE_OFFSETS: [usize; 11] = [0, 0, 16, 80, 272, 784, 1424, 2192, 3088, 4112, 5264];
-
-/// Offsets into the result metadata array for each word length.
-///
-/// Origin: SpellerCache.cpp:51
-const META_OFFSETS: [usize; 11] = [0, 0, 16, 48, 112, 240, 368, 496, 624, 752, 880];
-
-/// Total number of characters in the word storage (before size_param scaling).
-///
-/// Origin: SpellerCache.cpp:47 comment
-const BASE_WORD_COUNT: usize = 6544;
-
-/// Total number of entries in the result metadata (before size_param scaling).
+/// Number of entries for size parameter 0 (the number of result slots in
+/// the C++ cache).
 ///
 /// Origin: SpellerCache.cpp:71
-const BASE_META_COUNT: usize = 1008;
+const BASE_CAPACITY: usize = 1008;
+
+/// Slot index marking the end of the recency list.
+const NIL: usize = usize::MAX;
 
 /// Simple string hashing algorithm.
 ///
@@ -44,57 +37,86 @@ fn voikko_hash(word: &[char], len: usize, order: i32) -> usize {
     hash as usize
 }
 
-/// A fixed-size, hash-based cache for spell results.
+/// One cached word, linked into the recency list by slot index.
+struct Entry {
+    word: Box<[char]>,
+    result: SpellResult,
+    prev: usize,
+    next: usize,
+}
+
+/// A bounded cache for spell results with least-recently-used eviction.
 ///
 /// Only caches `SpellResult::Ok` and `SpellResult::CapitalizeFirst` results
 /// for words up to 10 characters long. Failed results are NOT cached.
 ///
-/// The cache uses a simple replacement strategy: hash collisions overwrite
-/// silently (no chaining or LRU).
-///
 /// Origin: SpellerCache.hpp:40-73, SpellerCache.cpp:68-112
 pub struct SpellerCache {
-    size_param: usize,
-    /// Stores word characters in a flat array, organized by word length.
-    words: Vec<char>,
-    /// Stores spell results: 'p' = Ok, 'i' = CapitalizeFirst.
-    spell_results: Vec<u8>,
+    capacity: usize,
+    index: HashMap<Box<[char]>, usize>,
+    entries: Vec<Entry>,
+    /// Most recently used slot.
+    head: usize,
+    /// Least recently used slot.
+    tail: usize,
+    stats: CacheStats,
 }
 
 impl SpellerCache {
     /// Create a new cache with the given size parameter.
     ///
-    /// `size_param` scales the cache: total word storage is
-    /// `6544 * (1 << size_param)` characters. A value of 0 gives the base size.
+    /// `size_param` scales the cache: it holds `1008 * (1 << size_param)`
+    /// words. A value of 0 gives the base size.
     ///
     /// Origin: SpellerCache.cpp:68-73
     pub fn new(size_param: usize) -> Self {
-        let word_count = BASE_WORD_COUNT << size_param;
-        let meta_count = BASE_META_COUNT << size_param;
+        Self::with_capacity(BASE_CAPACITY << size_param)
+    }
+
+    /// Create a new cache that holds at most `capacity` words.
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            size_param,
-            words: vec!['\0'; word_count],
-            spell_results: vec![0; meta_count],
+            capacity,
+            index: HashMap::new(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Maximum number of cached words.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached words.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether the cache holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Hit, miss and eviction counts since the cache was created.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.len(),
+            capacity: self.capacity,
+            ..self.stats
         }
     }
 
     /// Check whether a word is present in the cache.
     ///
-    /// Returns `false` for words longer than 10 characters.
+    /// Returns `false` for words longer than 10 characters. Does not count
+    /// as a lookup or change the eviction order.
     ///
     /// Origin: SpellerCache.cpp:84-91
     pub fn is_in_cache(&self, word: &[char], wlen: usize) -> bool {
-        if wlen == 0 || wlen > MAX_CACHED_WORD_LEN {
-            return false;
-        }
-        let hash_code = voikko_hash(word, wlen, HASH_ORDERS[wlen] + self.size_param as i32);
-        let cache_offset = (CACHE_OFFSETS[wlen] << self.size_param) + hash_code * wlen;
-
-        // Compare the cached word with the input
-        if cache_offset + wlen > self.words.len() {
-            return false;
-        }
-        self.words[cache_offset..cache_offset + wlen] == word[..wlen]
+        cacheable(wlen) && self.index.contains_key(&word[..wlen])
     }
 
     /// Get the cached spell result for a word.
@@ -103,17 +125,35 @@ impl SpellerCache {
     ///
     /// Origin: SpellerCache.cpp:93-101
     pub fn get_spell_result(&self, word: &[char], wlen: usize) -> SpellResult {
-        let hash_code = voikko_hash(word, wlen, HASH_ORDERS[wlen] + self.size_param as i32);
-        let result_offset = (META_OFFSETS[wlen] << self.size_param) + hash_code;
+        match self.index.get(&word[..wlen]) {
+            Some(&slot) => self.entries[slot].result,
+            None => SpellResult::Ok,
+        }
+    }
 
-        if self.spell_results[result_offset] == b'i' {
-            SpellResult::CapitalizeFirst
-        } else {
-            SpellResult::Ok
+    /// Look up a word, marking it as recently used.
+    ///
+    /// Counts a hit or a miss; words that cannot be cached are not counted.
+    pub fn lookup(&mut self, word: &[char], wlen: usize) -> Option<SpellResult> {
+        if !cacheable(wlen) {
+            return None;
+        }
+        match self.index.get(&word[..wlen]) {
+            Some(&slot) => {
+                self.stats.hits += 1;
+                self.unlink(slot);
+                self.push_front(slot);
+                Some(self.entries[slot].result)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
         }
     }
 
-    /// Store a spell result in the cache.
+    /// Store a spell result in the cache, evicting the least recently used
+    /// word if the cache is full.
     ///
     /// Only `SpellResult::Ok` and `SpellResult::CapitalizeFirst` are cached.
     /// Other results (Failed, CapitalizationError) are silently ignored.
@@ -121,29 +161,40 @@ impl SpellerCache {
     ///
     /// Origin: SpellerCache.cpp:103-112
     pub fn set_spell_result(&mut self, word: &[char], wlen: usize, result: SpellResult) {
-        if wlen == 0
-            || wlen > MAX_CACHED_WORD_LEN
+        if !cacheable(wlen)
             || (result != SpellResult::Ok && result != SpellResult::CapitalizeFirst)
+            || self.capacity == 0
         {
             return;
         }
-        let hash_code = voikko_hash(word, wlen, HASH_ORDERS[wlen] + self.size_param as i32);
-        let cache_offset = (CACHE_OFFSETS[wlen] << self.size_param) + hash_code * wlen;
-        let result_offset = (META_OFFSETS[wlen] << self.size_param) + hash_code;
-
-        // Store the word characters
-        if cache_offset + wlen <= self.words.len() {
-            self.words[cache_offset..cache_offset + wlen].copy_from_slice(&word[..wlen]);
+        let word = &word[..wlen];
+        if let Some(&slot) = self.index.get(word) {
+            self.entries[slot].result = result;
+            self.unlink(slot);
+            self.push_front(slot);
+            return;
         }
 
-        // Store the result marker
-        if result_offset < self.spell_results.len() {
-            self.spell_results[result_offset] = if result == SpellResult::Ok {
-                b'p'
-            } else {
-                b'i'
-            };
-        }
+        let entry = Entry {
+            word: word.into(),
+            result,
+            prev: NIL,
+            next: NIL,
+        };
+        let slot = if self.index.len() < self.capacity {
+            self.entries.push(entry);
+            self.entries.len() - 1
+        } else {
+            // Reuse the slot of the least recently used word
+            let slot = self.tail;
+            self.unlink(slot);
+            let old = std::mem::replace(&mut self.entries[slot], entry);
+            self.index.remove(&old.word);
+            self.stats.evictions += 1;
+            slot
+        };
+        self.index.insert(word.into(), slot);
+        self.push_front(slot);
     }
 
     /// Look up a word in the cache, calling the speller on a miss.
@@ -157,13 +208,206 @@ impl SpellerCache {
         wlen: usize,
         speller: &dyn Speller,
     ) -> SpellResult {
-        if self.is_in_cache(word, wlen) {
-            return self.get_spell_result(word, wlen);
+        if let Some(result) = self.lookup(word, wlen) {
+            return result;
         }
         let result = speller.spell(word, wlen);
         self.set_spell_result(word, wlen, result);
         result
     }
+
+    /// Drop all cached words. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Change the capacity, keeping the most recently used words that fit.
+    pub fn resize(&mut self, capacity: usize) {
+        let mut kept = Vec::with_capacity(self.len().min(capacity));
+        let mut slot = self.head;
+        while slot != NIL && kept.len() < capacity {
+            kept.push(slot);
+            slot = self.entries[slot].next;
+        }
+        self.stats.evictions += (self.len() - kept.len()) as u64;
+
+        let mut entries: Vec<Option<Entry>> = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.capacity = capacity;
+        self.index.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        // Re-insert from least to most recently used to keep the order
+        for &slot in kept.iter().rev() {
+            let entry = entries[slot].take().expect("each slot is kept once");
+            let new_slot = self.entries.len();
+            self.index.insert(entry.word.clone(), new_slot);
+            self.entries.push(entry);
+            self.push_front(new_slot);
+        }
+    }
+
+    /// Detach `slot` from the recency list.
+    fn unlink(&mut self, slot: usize) {
+        let Entry { prev, next, .. } = self.entries[slot];
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.entries[prev].next = next;
+        }
+        if next == NIL {
+            self.tail = prev;
+        } else {
+            self.entries[next].prev = prev;
+        }
+    }
+
+    /// Insert a detached `slot` as the most recently used entry.
+    fn push_front(&mut self, slot: usize) {
+        self.entries[slot].prev = NIL;
+        self.entries[slot].next = self.head;
+        if self.head == NIL {
+            self.tail = slot;
+        } else {
+            self.entries[self.head].prev = slot;
+        }
+        self.head = slot;
+    }
+}
+
+/// Whether words of length `wlen` are cached.
+fn cacheable(wlen: usize) -> bool {
+    (1..=MAX_CACHED_WORD_LEN).contains(&wlen)
+}
+
+/// Number of shards in a [`SharedSpellerCache`].
+const SHARD_COUNT: usize = 8;
+
+/// A [`SpellerCache`] that can be shared between threads.
+///
+/// The cache is split into independently locked shards, selected by a hash
+/// of the word. A thread that finds its shard locked by another thread does
+/// not wait: it spells the word without the cache. Results are therefore the
+/// same as with a single cache; only the hit rate differs.
+///
+/// Each shard holds the results of one dictionary generation (see
+/// [`with_shard`](Self::with_shard)), so a word spelled with a dictionary
+/// that has since been reloaded is not answered for the new one.
+pub struct SharedSpellerCache {
+    shards: Vec<Mutex<Shard>>,
+}
+
+/// A shard of a [`SharedSpellerCache`] and the dictionary generation its
+/// words were spelled with.
+struct Shard {
+    generation: u64,
+    cache: SpellerCache,
+}
+
+impl Shard {
+    fn new(cache: SpellerCache) -> Mutex<Self> {
+        Mutex::new(Self {
+            generation: 0,
+            cache,
+        })
+    }
+}
+
+impl SharedSpellerCache {
+    /// Create a shared cache with the given size parameter (see
+    /// [`SpellerCache::new`]). The words are split evenly over the shards,
+    /// so the cache holds as many words in total as a single
+    /// [`SpellerCache`] of the same size parameter.
+    pub fn new(size_param: usize) -> Self {
+        Self::with_capacity(BASE_CAPACITY << size_param)
+    }
+
+    /// Create a shared cache that holds at most `capacity` words in total,
+    /// split evenly over the shards.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| Shard::new(SpellerCache::with_capacity(capacity / SHARD_COUNT)))
+                .collect(),
+        }
+    }
+
+    /// Run `f` with the shard for `word`, for spelling with dictionary
+    /// `generation`, or with `None` if the shard is currently in use by
+    /// another thread. `generation` must be read before the speller takes
+    /// the dictionary. A shard that holds words of an older generation is
+    /// emptied first; one that holds words of a newer generation is not
+    /// used.
+    pub fn with_shard<R>(
+        &self,
+        word: &[char],
+        generation: u64,
+        f: impl FnOnce(Option<&mut SpellerCache>) -> R,
+    ) -> R {
+        let index = voikko_hash(word, word.len(), 16) % self.shards.len();
+        let mut shard = match self.shards[index].try_lock() {
+            Ok(shard) => shard,
+            // A panic while holding the lock comes from the speller, which
+            // runs between cache operations, so the recency list is intact.
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return f(None),
+        };
+        if shard.generation < generation {
+            shard.cache.clear();
+            shard.generation = generation;
+        }
+        if shard.generation == generation {
+            f(Some(&mut shard.cache))
+        } else {
+            f(None)
+        }
+    }
+
+    /// Combined statistics of all shards.
+    pub fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats::default();
+        for shard in &self.shards {
+            stats.add(
+                shard
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .cache
+                    .stats(),
+            );
+        }
+        stats
+    }
+
+    /// Resize the cache to the capacity of the size parameter `size_param`,
+    /// split evenly over the shards as in [`new`](Self::new), keeping the
+    /// most recently used words that fit.
+    pub fn resize(&mut self, size_param: usize) {
+        let shard_capacity = (BASE_CAPACITY << size_param) / SHARD_COUNT;
+        for shard in &mut self.shards {
+            shard
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .cache
+                .resize(shard_capacity);
+        }
+    }
+
+    /// Drop the cached words of every shard. Waits for shards in use, so no
+    /// result computed before the call is kept.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .cache
+                .clear();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -336,4 +580,186 @@ mod tests {
         // Not guaranteed but very likely for these particular words
         assert_ne!(h1, h2);
     }
+
+    #[test]
+    fn least_recently_used_word_is_evicted() {
+        let mut cache = SpellerCache::with_capacity(2);
+        let (a, b, c) = (chars("koira"), chars("kissa"), chars("talo"));
+        cache.set_spell_result(&a, a.len(), SpellResult::Ok);
+        cache.set_spell_result(&b, b.len(), SpellResult::Ok);
+        // Using `a` makes `b` the least recently used word
+        assert_eq!(cache.lookup(&a, a.len()), Some(SpellResult::Ok));
+        cache.set_spell_result(&c, c.len(), SpellResult::Ok);
+
+        assert!(cache.is_in_cache(&a, a.len()));
+        assert!(!cache.is_in_cache(&b, b.len()));
+        assert!(cache.is_in_cache(&c, c.len()));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn storing_again_updates_result() {
+        let mut cache = SpellerCache::with_capacity(2);
+        let word = chars("helsinki");
+        cache.set_spell_result(&word, word.len(), SpellResult::Ok);
+        cache.set_spell_result(&word, word.len(), SpellResult::CapitalizeFirst);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            cache.get_spell_result(&word, word.len()),
+            SpellResult::CapitalizeFirst
+        );
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let mut cache = SpellerCache::with_capacity(0);
+        let word = chars("koira");
+        cache.set_spell_result(&word, word.len(), SpellResult::Ok);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn lookup_counts_hits_and_misses() {
+        let mut cache = SpellerCache::new(0);
+        let word = chars("koira");
+        let long = chars("pitkasanainen");
+        assert_eq!(cache.lookup(&word, word.len()), None);
+        cache.set_spell_result(&word, word.len(), SpellResult::Ok);
+        assert_eq!(cache.lookup(&word, word.len()), Some(SpellResult::Ok));
+        assert_eq!(cache.lookup(&long, long.len()), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+        assert_eq!(stats.capacity, BASE_CAPACITY);
+    }
+
+    #[test]
+    fn resize_keeps_most_recent_words() {
+        let mut cache = SpellerCache::with_capacity(4);
+        let words: Vec<Vec<char>> = ["yksi", "kaksi", "kolme", "nelja"]
+            .iter()
+            .map(|w| chars(w))
+            .collect();
+        for w in &words {
+            cache.set_spell_result(w, w.len(), SpellResult::Ok);
+        }
+        cache.lookup(&words[0], words[0].len());
+
+        cache.resize(2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.is_in_cache(&words[0], words[0].len()));
+        assert!(cache.is_in_cache(&words[3], words[3].len()));
+        assert_eq!(cache.stats().evictions, 2);
+
+        // The kept order survives: `nelja` is now the oldest word
+        let extra = chars("viisi");
+        cache.set_spell_result(&extra, extra.len(), SpellResult::Ok);
+        assert!(!cache.is_in_cache(&words[3], words[3].len()));
+        assert!(cache.is_in_cache(&words[0], words[0].len()));
+
+        cache.resize(8);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.capacity(), 8);
+    }
+
+    #[test]
+    fn shared_cache_stats_and_resize() {
+        let mut cache = SharedSpellerCache::new(0);
+        let w = chars("koira");
+        cache.with_shard(&w, 0, |c| {
+            let c = c.unwrap();
+            c.lookup(&w, 5);
+            c.set_spell_result(&w, 5, SpellResult::Ok);
+        });
+        cache.resize(1);
+        assert!(cache.with_shard(&w, 0, |c| c.unwrap().is_in_cache(&w, 5)));
+
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.len, 1);
+        assert_eq!(stats.capacity, BASE_CAPACITY << 1);
+
+        let cache = SharedSpellerCache::with_capacity(1024);
+        assert_eq!(cache.stats().capacity, 1024);
+    }
+
+    #[test]
+    fn shared_cache_size_param_is_the_total_capacity() {
+        assert_eq!(SharedSpellerCache::new(0).stats().capacity, BASE_CAPACITY);
+        assert_eq!(
+            SharedSpellerCache::new(2).stats().capacity,
+            SpellerCache::new(2).capacity()
+        );
+    }
+
+    #[test]
+    fn shared_cache_stores_results_in_shard() {
+        let cache = SharedSpellerCache::new(0);
+        let w = chars("koira");
+        cache.with_shard(&w, 0, |c| {
+            c.unwrap().set_spell_result(&w, 5, SpellResult::Ok)
+        });
+        assert!(cache.with_shard(&w, 0, |c| c.unwrap().is_in_cache(&w, 5)));
+    }
+
+    #[test]
+    fn shared_cache_keeps_one_dictionary_generation() {
+        let cache = SharedSpellerCache::new(0);
+        let w = chars("koira");
+        cache.with_shard(&w, 0, |c| {
+            c.unwrap().set_spell_result(&w, 5, SpellResult::Ok)
+        });
+        // The first lookup after a reload empties the shard
+        assert!(!cache.with_shard(&w, 1, |c| c.unwrap().is_in_cache(&w, 5)));
+        cache.with_shard(&w, 1, |c| {
+            c.unwrap()
+                .set_spell_result(&w, 5, SpellResult::CapitalizeFirst)
+        });
+        // A speller still using the old dictionary runs without the cache
+        assert!(cache.with_shard(&w, 0, |c| c.is_none()));
+        assert!(cache.with_shard(&w, 1, |c| c.unwrap().is_in_cache(&w, 5)));
+    }
+
+    #[test]
+    fn shared_cache_busy_shard_is_skipped() {
+        let cache = SharedSpellerCache::new(0);
+        let w = chars("koira");
+        let nested = cache.with_shard(&w, 0, |outer| {
+            assert!(outer.is_some());
+            cache.with_shard(&w, 0, |inner| inner.is_none())
+        });
+        assert!(nested);
+    }
+
+    #[test]
+    fn shared_cache_from_many_threads() {
+        let cache = SharedSpellerCache::new(0);
+        let words: Vec<Vec<char>> = ["koira", "kissa", "talo", "auto"]
+            .iter()
+            .map(|w| chars(w))
+            .collect();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for w in &words {
+                        cache.with_shard(w, 0, |c| {
+                            if let Some(c) = c {
+                                c.set_spell_result(w, w.len(), SpellResult::Ok);
+                            }
+                        });
+                    }
+                });
+            }
+        });
+        for w in &words {
+            cache.with_shard(w, 0, |c| {
+                let c = c.unwrap();
+                if c.is_in_cache(w, w.len()) {
+                    assert_eq!(c.get_spell_result(w, w.len()), SpellResult::Ok);
+                }
+            });
+        }
+    }
 }
@@ -67,6 +67,7 @@ impl<'a> FinnishSpellerTweaksWrapper<'a> {
                 hyphenate_unknown: true,
                 min_hyphenated_word_length: 3,
                 ignore_dot: true,
+                ..HyphenatorOptions::default()
             },
         );
         let hyph_pattern = hyphenator.all_possible_hyphen_positions(word);
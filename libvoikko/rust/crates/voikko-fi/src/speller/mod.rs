@@ -5,7 +5,8 @@ pub mod adapter;
 pub mod cache;
 pub mod finnish;
 pub mod pipeline;
-pub mod utils;
+pub mod user_dictionary;
+pub(crate) mod utils;
 
 use voikko_core::enums::SpellResult;
 
@@ -5,6 +5,7 @@ use voikko_core::case::{CaseType, detect_case};
 use voikko_core::character::{is_upper, simple_lower};
 use voikko_core::enums::{MAX_WORD_CHARS, SpellResult};
 
+use crate::input_map::InputMapping;
 use crate::speller::Speller;
 use crate::speller::cache::SpellerCache;
 
@@ -33,6 +34,11 @@ pub struct SpellOptions {
     pub accept_all_uppercase: bool,
     /// Accept missing hyphens at start/end of word.
     pub accept_missing_hyphens: bool,
+    /// Typographic character classes mapped before checking.
+    pub input_mapping: InputMapping,
+    /// Longer words are rejected without checking. At most
+    /// [`MAX_WORD_CHARS`], the longest word the analyzers accept.
+    pub max_word_chars: usize,
 }
 
 impl Default for SpellOptions {
@@ -46,6 +52,8 @@ impl Default for SpellOptions {
             accept_first_uppercase: true,
             accept_all_uppercase: true,
             accept_missing_hyphens: false,
+            input_mapping: InputMapping::default(),
+            max_word_chars: MAX_WORD_CHARS,
         }
     }
 }
@@ -148,8 +156,8 @@ fn cached_spell(
 ) -> SpellResult {
     match cache {
         Some(cache) => {
-            if cache.is_in_cache(buffer, len) {
-                return cache.get_spell_result(buffer, len);
+            if let Some(result) = cache.lookup(buffer, len) {
+                return result;
             }
             let result = hyphen_aware_spell(speller, buffer, len, accept_missing_hyphens);
             cache.set_spell_result(buffer, len, result);
@@ -248,7 +256,7 @@ const CONV_2TO1: [(char, char, char); 67] = [
 /// 5. passthrough
 ///
 /// Origin: charset.cpp:voikko_normalise
-fn normalize(word: &[char]) -> Vec<char> {
+pub(crate) fn normalize(word: &[char]) -> Vec<char> {
     // Worst case: every char is a 1-to-3 ligature
     let mut result = Vec::with_capacity(word.len() * 3);
     let len = word.len();
@@ -352,12 +360,16 @@ pub fn spell_check(
     if nchars == 0 {
         return VOIKKO_SPELL_OK;
     }
-    if nchars > MAX_WORD_CHARS {
+    if nchars > options.max_word_chars.min(MAX_WORD_CHARS) {
         return VOIKKO_SPELL_FAILED;
     }
 
-    // Normalize
-    let nword = normalize(word);
+    // Map typographic variants, then normalize
+    let nword = if options.input_mapping.is_enabled() {
+        normalize(&options.input_mapping.apply(word).chars)
+    } else {
+        normalize(word)
+    };
     let nchars = nword.len();
 
     // Ignore words containing digits
@@ -534,6 +546,7 @@ mod tests {
                 "koira" => vec![Self::make_analysis("=ppppp")],
                 "helsinki" => vec![Self::make_analysis("=ippppppp")],
                 "eu" => vec![Self::make_analysis("=jj")],
+                "h2o" => vec![Self::make_analysis("=jjj")],
                 "1.5" => vec![], // number, not a word
                 _ => vec![],
             }
@@ -909,6 +922,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn word_exceeding_configured_max_length_fails() {
+        let opts = SpellOptions {
+            max_word_chars: 4,
+            ..default_options()
+        };
+        assert_eq!(spell_word("koira", &opts), VOIKKO_SPELL_FAILED);
+        assert_eq!(spell_word("koira", &default_options()), VOIKKO_SPELL_OK);
+    }
+
     #[test]
     fn all_upper_abbreviation() {
         // "EU" -> lowercased "eu" -> analysis structure "=jj" expects uppercase
@@ -1028,4 +1051,12 @@ mod tests {
             SpellResult::Ok
         );
     }
+
+    #[test]
+    fn input_mapping_applies_before_checking() {
+        let mut options = default_options();
+        assert_eq!(spell_word("H₂O", &options), VOIKKO_SPELL_FAILED);
+        options.input_mapping.subscripts = true;
+        assert_eq!(spell_word("H₂O", &options), VOIKKO_SPELL_OK);
+    }
 }
@@ -0,0 +1,409 @@
+// User dictionary: words accepted in addition to the dictionary data.
+//
+// An entry is a word with an optional inflection class hint: a dictionary
+// word that inflects the same way. Without a hint only the word itself is
+// accepted. With a hint, inflected forms are recognized by analogy: the
+// entry and the hint are split at their longest common ending, and a form
+// of the entry is accepted if the same ending on the hint's stem gives a
+// form of the hint. For "blogi" with the hint "paperi", "blogeissa" is
+// accepted because "papereissa" is a form of "paperi". Hints work best
+// when they end like the entry.
+//
+// Exact entries are looked up before the VFST speller, inflected forms only
+// after the speller has rejected the word, as they need the analyzer.
+//
+// The dictionary serializes to a line-oriented UTF-8 text file:
+//
+//   # comment
+//   word
+//   word<TAB>hint
+//
+// Backslash, tab, carriage return and newline inside values are escaped
+// as `\\`, `\t`, `\r` and `\n`, and a word starting with `#` as `\#`.
+
+use std::collections::BTreeMap;
+
+/// Error type for user dictionary parse failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum UserDictionaryError {
+    /// A line could not be parsed. `line` is 1-based.
+    #[error("invalid user dictionary entry on line {line}: {reason}")]
+    InvalidEntry { line: usize, reason: String },
+}
+
+/// A form of a hint word to check with the analyzer, standing for a form
+/// of a user dictionary entry. See [`UserDictionary::template_forms`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateForm<'a> {
+    /// The corresponding form of the hint word.
+    pub form: String,
+    /// The hint word; `form` must be one of its forms.
+    pub hint: &'a str,
+}
+
+/// Words accepted in addition to the dictionary, with optional inflection
+/// class hints. Entries are kept in sorted order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserDictionary {
+    entries: BTreeMap<String, Option<String>>,
+}
+
+impl UserDictionary {
+    /// Create an empty user dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add `word`, with an optional inflection class hint. Adding a word
+    /// that is already present replaces its hint. Returns `true` if the
+    /// word was not present.
+    pub fn add(&mut self, word: &str, hint: Option<&str>) -> bool {
+        let hint = hint.filter(|h| !h.is_empty()).map(str::to_string);
+        self.entries.insert(word.to_string(), hint).is_none()
+    }
+
+    /// Remove `word`. Returns `true` if the word was present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.entries.remove(word).is_some()
+    }
+
+    /// Return `true` if `word` is an entry, as written.
+    pub fn contains(&self, word: &str) -> bool {
+        self.entries.contains_key(word)
+    }
+
+    /// Return the inflection class hint of `word`, if it is an entry and
+    /// has one.
+    pub fn hint(&self, word: &str) -> Option<&str> {
+        self.entries.get(word)?.as_deref()
+    }
+
+    /// Iterate over the entries and their hints in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.entries
+            .iter()
+            .map(|(word, hint)| (word.as_str(), hint.as_deref()))
+    }
+
+    /// Add all entries of `other`. Entries in `other` replace the hints of
+    /// existing entries.
+    pub fn merge(&mut self, other: UserDictionary) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Return `true` if `word` is an entry.
+    ///
+    /// An entry written entirely in lowercase also accepts the word with
+    /// other capitalization (e.g. the entry "voikko" accepts "Voikko" and
+    /// "VOIKKO"), mirroring how dictionary words are accepted.
+    pub fn accepts(&self, word: &str) -> bool {
+        if self.entries.contains_key(word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        lower != word && self.entries.contains_key(&lower)
+    }
+
+    /// Return the forms of hint words that `word` corresponds to, for entries
+    /// of which `word` may be an inflected form.
+    ///
+    /// `word` is a form of an entry if any of the returned forms is a form
+    /// of its hint, which the caller checks with the analyzer.
+    pub fn template_forms(&self, word: &str) -> Vec<TemplateForm<'_>> {
+        let lower = word.to_lowercase();
+        let mut forms = Vec::new();
+        for (entry, hint) in &self.entries {
+            let Some(hint) = hint else { continue };
+            // As in `accepts`, only lowercase entries ignore capitalization
+            let word = if entry.to_lowercase() == *entry {
+                lower.as_str()
+            } else {
+                word
+            };
+            let (entry_stem, hint_stem) = split_common_ending(entry, hint);
+            if word == entry {
+                continue;
+            }
+            if let Some(ending) = word.strip_prefix(entry_stem) {
+                forms.push(TemplateForm {
+                    form: format!("{hint_stem}{ending}"),
+                    hint,
+                });
+            }
+        }
+        forms
+    }
+
+    /// Return the entries within `max_distance` edits of `word`, closest
+    /// first, as spelling suggestions for it.
+    ///
+    /// Entries are compared in lowercase. A lowercase entry is capitalized
+    /// like `word` if `word` starts with an uppercase letter.
+    #[cfg(feature = "suggest")]
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        use crate::suggestion::edit_distance;
+
+        let word_chars: Vec<char> = word.to_lowercase().chars().collect();
+        let capitalize = word.chars().next().is_some_and(char::is_uppercase);
+        let mut found: Vec<(String, usize)> = self
+            .entries
+            .keys()
+            .filter_map(|entry| {
+                let entry_chars: Vec<char> = entry.to_lowercase().chars().collect();
+                if entry_chars.len().abs_diff(word_chars.len()) > max_distance {
+                    return None;
+                }
+                let distance = edit_distance(&word_chars, &entry_chars);
+                (distance <= max_distance).then(|| {
+                    let suggestion = if capitalize && entry.to_lowercase() == *entry {
+                        capitalize_first(entry)
+                    } else {
+                        entry.clone()
+                    };
+                    (suggestion, distance)
+                })
+            })
+            .collect();
+        found.sort_by_key(|&(_, distance)| distance);
+        found
+    }
+
+    /// Serialize the dictionary into the text file format.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (word, hint) in &self.entries {
+            if word.starts_with('#') {
+                out.push('\\');
+            }
+            push_escaped(&mut out, word);
+            if let Some(hint) = hint {
+                out.push('\t');
+                push_escaped(&mut out, hint);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the text file format produced by [`UserDictionary::to_text`].
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn from_text(text: &str) -> Result<Self, UserDictionaryError> {
+        let mut dictionary = UserDictionary::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, hint) = parse_entry(line.split('\t')).map_err(|reason| {
+                UserDictionaryError::InvalidEntry {
+                    line: idx + 1,
+                    reason: reason.to_string(),
+                }
+            })?;
+            dictionary.add(&word, hint.as_deref());
+        }
+        Ok(dictionary)
+    }
+}
+
+/// Parse the tab-separated fields of an entry: a word and an optional hint.
+pub(crate) fn parse_entry<'a>(
+    mut fields: impl Iterator<Item = &'a str>,
+) -> Result<(String, Option<String>), &'static str> {
+    let word = fields
+        .next()
+        .filter(|w| !w.is_empty())
+        .ok_or("empty word")?;
+    let word = unescape(word).ok_or("bad escape")?;
+    let hint = fields
+        .next()
+        .map(|h| unescape(h).ok_or("bad escape"))
+        .transpose()?;
+    if fields.next().is_some() {
+        return Err("too many fields");
+    }
+    Ok((word, hint))
+}
+
+/// Split `entry` and `hint` before their longest common ending, returning
+/// the two stems.
+fn split_common_ending<'a, 'b>(entry: &'a str, hint: &'b str) -> (&'a str, &'b str) {
+    let common: usize = entry
+        .chars()
+        .rev()
+        .zip(hint.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    (&entry[..entry.len() - common], &hint[..hint.len() - common])
+}
+
+#[cfg(feature = "suggest")]
+fn capitalize_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Append `value` to `out`, escaping backslash, tab, carriage return and
+/// newline.
+pub(crate) fn push_escaped(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Reverse `push_escaped`. Returns `None` on an unknown or dangling escape.
+pub(crate) fn unescape(value: &str) -> Option<String> {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => out.push('\\'),
+            't' => out.push('\t'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            '#' => out.push('#'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> UserDictionary {
+        let mut dictionary = UserDictionary::new();
+        dictionary.add("voikko", None);
+        dictionary.add("Kotus", None);
+        dictionary.add("blogi", Some("paperi"));
+        dictionary
+    }
+
+    #[test]
+    fn add_remove_and_list() {
+        let mut dictionary = sample();
+        assert_eq!(dictionary.len(), 3);
+        assert!(!dictionary.add("blogi", Some("tunti")));
+        assert_eq!(dictionary.hint("blogi"), Some("tunti"));
+        assert!(dictionary.add("vlogi", Some("")));
+        assert_eq!(dictionary.hint("vlogi"), None);
+        assert!(dictionary.remove("vlogi"));
+        assert!(!dictionary.remove("vlogi"));
+        let entries: Vec<_> = dictionary.iter().collect();
+        assert_eq!(
+            entries,
+            [("Kotus", None), ("blogi", Some("tunti")), ("voikko", None)]
+        );
+    }
+
+    #[test]
+    fn accepts_entries_with_lowercase_capitalization_rule() {
+        let dictionary = sample();
+        assert!(dictionary.accepts("voikko"));
+        assert!(dictionary.accepts("Voikko"));
+        assert!(dictionary.accepts("VOIKKO"));
+        assert!(dictionary.accepts("Kotus"));
+        assert!(!dictionary.accepts("kotus"));
+        assert!(!dictionary.accepts("blogeissa"));
+    }
+
+    #[test]
+    fn template_forms_follow_the_hint() {
+        let dictionary = sample();
+        let forms = dictionary.template_forms("blogeissa");
+        assert_eq!(
+            forms,
+            [TemplateForm {
+                form: "papereissa".to_string(),
+                hint: "paperi",
+            }]
+        );
+        assert_eq!(dictionary.template_forms("Blogin")[0].form, "paperin");
+        // The entry itself and words of other stems have no template
+        assert!(dictionary.template_forms("blogi").is_empty());
+        assert!(dictionary.template_forms("vlogeissa").is_empty());
+        assert!(dictionary.template_forms("voikon").is_empty());
+    }
+
+    #[test]
+    fn common_ending_split() {
+        assert_eq!(split_common_ending("blogi", "paperi"), ("blog", "paper"));
+        assert_eq!(split_common_ending("kissakala", "kala"), ("kissa", ""));
+        assert_eq!(split_common_ending("äyskä", "öljä"), ("äysk", "ölj"));
+    }
+
+    #[cfg(feature = "suggest")]
+    #[test]
+    fn suggestions_are_close_entries() {
+        let dictionary = sample();
+        assert_eq!(
+            dictionary.suggestions("vokko", 2),
+            [("voikko".to_string(), 1)]
+        );
+        assert_eq!(
+            dictionary.suggestions("Bolgi", 2),
+            [("Blogi".to_string(), 1)]
+        );
+        assert!(dictionary.suggestions("koira", 2).is_empty());
+    }
+
+    #[test]
+    fn text_roundtrip() {
+        let mut dictionary = sample();
+        dictionary.add("a\tb\\c", Some("x\ny"));
+        dictionary.add("#hashtag", None);
+        let text = dictionary.to_text();
+        assert_eq!(text.lines().count(), 5);
+        assert_eq!(UserDictionary::from_text(&text).unwrap(), dictionary);
+    }
+
+    #[test]
+    fn text_comments_and_errors() {
+        let text = "# my words\r\nvoikko\n\nblogi\tpaperi\n";
+        let dictionary = UserDictionary::from_text(text).unwrap();
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.hint("blogi"), Some("paperi"));
+
+        let err = UserDictionary::from_text("ok\na\tb\tc\n").unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::InvalidEntry { line: 2, .. }
+        ));
+        let err = UserDictionary::from_text("bad\\q\n").unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::InvalidEntry { line: 1, .. }
+        ));
+        let err = UserDictionary::from_text("\tpaperi\n").unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::InvalidEntry { line: 1, .. }
+        ));
+    }
+}
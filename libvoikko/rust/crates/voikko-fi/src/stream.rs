@@ -0,0 +1,380 @@
+// Streaming spell checking of word lists.
+//
+// `VoikkoHandle::spell_stream` reads one word per record from a `BufRead`
+// and writes one result per word, in input order, in the format of the
+// voikko-spell tool. It is meant for corpus-cleaning pipelines that would
+// otherwise start a process per word or wait for the whole input.
+//
+// Words are checked in batches taken from the data the reader already has
+// buffered: the reader is only asked for more (and may block) when all of
+// it has been checked and written. A pipe that delivers one word at a time
+// gets each answer at once, because the output is flushed whenever the
+// buffered input runs out, while a large file is checked in full batches.
+// Nothing is read while a batch is checked and written, so a slow consumer
+// slows the producer down instead of growing a queue (backpressure).
+//
+// With more than one thread, each batch is split among scoped threads that
+// share the handle; the results are written in input order.
+
+use std::io::{self, BufRead, Write};
+use std::thread;
+
+use crate::handle::VoikkoHandle;
+
+/// Batches smaller than this many words per thread are checked on the
+/// calling thread, as spawning would cost more than it saves.
+const MIN_WORDS_PER_THREAD: usize = 64;
+
+/// Record framing of [`VoikkoHandle::spell_stream`] input and output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpellStreamFormat {
+    /// One word per input line; `C: word` (correct) or `W: word`
+    /// (misspelled) lines, followed by `S: suggestion` lines if requested.
+    #[default]
+    Text,
+    /// NUL-delimited input; the `Text` lines, each terminated by NUL.
+    Print0,
+    /// One word per input line; one JSON object per word with `word`,
+    /// `correct` and `suggestions` (null unless requested and misspelled).
+    JsonLines,
+}
+
+/// Options for [`VoikkoHandle::spell_stream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellStreamOptions {
+    /// Input and output framing.
+    pub format: SpellStreamFormat,
+    /// Also write suggestions for misspelled words.
+    pub suggest: bool,
+    /// Maximum number of words checked as one batch.
+    pub batch_size: usize,
+    /// Number of threads checking a batch; 0 or 1 checks on the calling
+    /// thread.
+    pub threads: usize,
+}
+
+impl Default for SpellStreamOptions {
+    fn default() -> Self {
+        Self {
+            format: SpellStreamFormat::Text,
+            suggest: false,
+            batch_size: 1024,
+            threads: 1,
+        }
+    }
+}
+
+/// Counts of a [`VoikkoHandle::spell_stream`] run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpellStreamStats {
+    /// Words checked.
+    pub words: usize,
+    /// Words reported as misspelled.
+    pub misspelled: usize,
+    /// Input records skipped because they were not valid UTF-8.
+    pub invalid_records: usize,
+}
+
+/// The result for one word.
+struct WordResult {
+    correct: bool,
+    suggestions: Option<Vec<String>>,
+}
+
+/// Check the words read from `reader` and write the results to `writer`.
+/// See [`VoikkoHandle::spell_stream`].
+pub(crate) fn spell_stream(
+    handle: &VoikkoHandle,
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+    options: &SpellStreamOptions,
+) -> io::Result<SpellStreamStats> {
+    let delimiter = match options.format {
+        SpellStreamFormat::Print0 => 0,
+        SpellStreamFormat::Text | SpellStreamFormat::JsonLines => b'\n',
+    };
+    let batch_size = options.batch_size.max(1);
+    let mut stats = SpellStreamStats::default();
+    let mut partial: Vec<u8> = Vec::new();
+    let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            // End of input: the last record need not be terminated.
+            push_record(&mut batch, &partial, &mut stats);
+            write_batch(handle, &mut batch, &mut writer, options, &mut stats)?;
+            break;
+        }
+        let mut consumed = 0;
+        while let Some(end) = buffer[consumed..].iter().position(|&b| b == delimiter) {
+            let record = &buffer[consumed..consumed + end];
+            consumed += end + 1;
+            if partial.is_empty() {
+                push_record(&mut batch, record, &mut stats);
+            } else {
+                partial.extend_from_slice(record);
+                push_record(&mut batch, &partial, &mut stats);
+                partial.clear();
+            }
+            if batch.len() == batch_size {
+                write_batch(handle, &mut batch, &mut writer, options, &mut stats)?;
+            }
+        }
+        partial.extend_from_slice(&buffer[consumed..]);
+        let length = buffer.len();
+        reader.consume(length);
+        // The buffered input is used up; answer before waiting for more.
+        write_batch(handle, &mut batch, &mut writer, options, &mut stats)?;
+        writer.flush()?;
+    }
+    writer.flush()?;
+    Ok(stats)
+}
+
+/// Add a record to the batch: trimmed, unless empty or not valid UTF-8.
+fn push_record(batch: &mut Vec<String>, record: &[u8], stats: &mut SpellStreamStats) {
+    match std::str::from_utf8(record) {
+        Ok(record) => {
+            let word = record.trim();
+            if !word.is_empty() {
+                batch.push(word.to_string());
+            }
+        }
+        Err(_) => stats.invalid_records += 1,
+    }
+}
+
+/// Check the words of `batch`, write their results in order and empty it.
+fn write_batch(
+    handle: &VoikkoHandle,
+    batch: &mut Vec<String>,
+    writer: &mut impl Write,
+    options: &SpellStreamOptions,
+    stats: &mut SpellStreamStats,
+) -> io::Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let results = check_batch(handle, batch, options);
+    for (word, result) in batch.iter().zip(&results) {
+        stats.words += 1;
+        if !result.correct {
+            stats.misspelled += 1;
+        }
+        write_result(writer, word, result, options.format)?;
+    }
+    batch.clear();
+    Ok(())
+}
+
+/// Check the words of a batch, on several threads if it is large enough.
+fn check_batch(
+    handle: &VoikkoHandle,
+    words: &[String],
+    options: &SpellStreamOptions,
+) -> Vec<WordResult> {
+    let check = |word: &String| {
+        let correct = handle.spell(word);
+        WordResult {
+            correct,
+            suggestions: (options.suggest && !correct).then(|| handle.suggest(word)),
+        }
+    };
+    let threads = options
+        .threads
+        .min(words.len() / MIN_WORDS_PER_THREAD)
+        .max(1);
+    if threads == 1 {
+        return words.iter().map(check).collect();
+    }
+    let chunk_size = words.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let workers: Vec<_> = words
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(check).collect::<Vec<_>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("spell check thread panicked"))
+            .collect()
+    })
+}
+
+/// Write `value` as a JSON string.
+fn write_json_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+    let mut rest = value;
+    while let Some(i) = rest.find(|c: char| c == '"' || c == '\\' || c < ' ') {
+        writer.write_all(&rest.as_bytes()[..i])?;
+        let c = rest[i..].chars().next().expect("found a character");
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            _ => write!(writer, "\\u{:04x}", u32::from(c))?,
+        }
+        rest = &rest[i + 1..];
+    }
+    writer.write_all(rest.as_bytes())?;
+    writer.write_all(b"\"")
+}
+
+fn write_result(
+    writer: &mut impl Write,
+    word: &str,
+    result: &WordResult,
+    format: SpellStreamFormat,
+) -> io::Result<()> {
+    let terminator: &[u8] = match format {
+        SpellStreamFormat::JsonLines => {
+            write!(writer, "{{\"correct\":{},\"suggestions\":", result.correct)?;
+            match &result.suggestions {
+                Some(suggestions) => {
+                    writer.write_all(b"[")?;
+                    for (i, suggestion) in suggestions.iter().enumerate() {
+                        if i > 0 {
+                            writer.write_all(b",")?;
+                        }
+                        write_json_string(writer, suggestion)?;
+                    }
+                    writer.write_all(b"]")?;
+                }
+                None => writer.write_all(b"null")?,
+            }
+            writer.write_all(b",\"word\":")?;
+            write_json_string(writer, word)?;
+            return writer.write_all(b"}\n");
+        }
+        SpellStreamFormat::Print0 => b"\0",
+        SpellStreamFormat::Text => b"\n",
+    };
+    let status = if result.correct { "C" } else { "W" };
+    write!(writer, "{status}: {word}")?;
+    writer.write_all(terminator)?;
+    for suggestion in result.suggestions.iter().flatten() {
+        write!(writer, "S: {suggestion}")?;
+        writer.write_all(terminator)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::empty_handle;
+
+    fn run(input: &[u8], options: &SpellStreamOptions) -> (String, SpellStreamStats) {
+        // The dictionary knows no words, but with `ignore_numbers` words
+        // containing digits are correct, so both results occur.
+        let mut handle = empty_handle();
+        handle.set_ignore_numbers(true);
+        let mut output = Vec::new();
+        let stats = handle.spell_stream(input, &mut output, options).unwrap();
+        (String::from_utf8(output).unwrap(), stats)
+    }
+
+    #[test]
+    fn text_format() {
+        let (output, stats) = run(b"koira\n  a1 \n\nkissa", &SpellStreamOptions::default());
+        assert_eq!(output, "W: koira\nC: a1\nW: kissa\n");
+        assert_eq!(
+            stats,
+            SpellStreamStats {
+                words: 3,
+                misspelled: 2,
+                invalid_records: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn print0_and_json_formats() {
+        let options = SpellStreamOptions {
+            format: SpellStreamFormat::Print0,
+            ..SpellStreamOptions::default()
+        };
+        let (output, _) = run(b"a b\0c1\0", &options);
+        assert_eq!(output, "W: a b\0C: c1\0");
+
+        let options = SpellStreamOptions {
+            format: SpellStreamFormat::JsonLines,
+            ..SpellStreamOptions::default()
+        };
+        let (output, _) = run(b"x1\n", &options);
+        assert_eq!(
+            output,
+            "{\"correct\":true,\"suggestions\":null,\"word\":\"x1\"}\n"
+        );
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        let mut out = Vec::new();
+        write_json_string(&mut out, "a\"b\\c\nd\u{1}é").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), r#""a\"b\\c\nd\u0001é""#);
+    }
+
+    #[test]
+    fn invalid_records_are_skipped() {
+        let (output, stats) = run(b"a1\n\xff\xfe\nb1\n", &SpellStreamOptions::default());
+        assert_eq!(output, "C: a1\nC: b1\n");
+        assert_eq!(stats.invalid_records, 1);
+    }
+
+    #[test]
+    fn records_split_across_reads_and_batches() {
+        // A reader with a tiny buffer splits records between reads; a large
+        // one lets batches fill up and be split among threads.
+        let input: String = (0..500).map(|i| format!("w{i}x\n")).collect();
+        let expected: String = (0..500).map(|i| format!("C: w{i}x\n")).collect();
+        let mut handle = empty_handle();
+        handle.set_ignore_numbers(true);
+        for (capacity, batch_size, threads) in [
+            (5, 1, 1),
+            (5, 7, 1),
+            (64 * 1024, 1024, 4),
+            (64 * 1024, 200, 3),
+        ] {
+            let options = SpellStreamOptions {
+                batch_size,
+                threads,
+                ..SpellStreamOptions::default()
+            };
+            let reader = io::BufReader::with_capacity(capacity, input.as_bytes());
+            let mut output = Vec::new();
+            let stats = handle.spell_stream(reader, &mut output, &options).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), expected);
+            assert_eq!(stats.words, 500);
+        }
+    }
+
+    #[test]
+    fn answers_before_reading_more() {
+        // A reader that fails once its first chunk has been used up: the
+        // words already read must have been written and flushed by then.
+        struct OneChunk<'a>(&'a [u8]);
+
+        impl io::Read for OneChunk<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() {
+                    return Err(io::Error::other("no more input yet"));
+                }
+                let n = self.0.len().min(buf.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let mut output = Vec::new();
+        let reader = io::BufReader::new(OneChunk(b"a1\nb\n"));
+        let mut handle = empty_handle();
+        handle.set_ignore_numbers(true);
+        let result = handle.spell_stream(reader, &mut output, &SpellStreamOptions::default());
+        assert!(result.is_err());
+        assert_eq!(String::from_utf8(output).unwrap(), "C: a1\nW: b\n");
+    }
+}
@@ -3,18 +3,19 @@
 //
 // Origin: spellchecker/suggestion/SuggestionGenerator*.cpp
 
+use std::sync::Arc;
+
 use voikko_core::analysis::ATTR_STRUCTURE;
-use voikko_core::character::{is_upper, simple_lower, simple_upper};
+use voikko_core::character::{is_upper, is_vowel, simple_lower, simple_upper};
 use voikko_core::enums::SpellResult;
 
 use super::status::SuggestionStatus;
-use crate::morphology::Analyzer;
+use crate::language_rules::LanguageRules;
+use crate::morphology::{Analyzer, apply_structure_case};
 use crate::speller::Speller;
 
-use crate::finnish::constants::{BACK_VOWELS, FRONT_VOWELS};
-
 /// Soft hyphen character (U+00AD).
-const SOFT_HYPHEN: char = '\u{00AD}';
+pub(super) const SOFT_HYPHEN: char = '\u{00AD}';
 
 // ---------------------------------------------------------------------------
 // Trait
@@ -26,8 +27,11 @@ const SOFT_HYPHEN: char = '\u{00AD}';
 /// operation to the misspelled word, then validates each candidate through
 /// the speller.
 ///
+/// Generators are `Send + Sync` so that a handle holding them can be shared
+/// between threads.
+///
 /// Origin: spellchecker/suggestion/SuggestionGenerator.hpp
-pub trait SuggestionGenerator {
+pub trait SuggestionGenerator: Send + Sync {
     /// Generate suggestions for the word tracked by `status`, using `speller`
     /// to validate candidates.
     fn generate(
@@ -138,42 +142,6 @@ fn compute_priority(
     priority_from_result(result)
 }
 
-/// Apply case corrections to a word based on its STRUCTURE attribute.
-///
-/// The STRUCTURE attribute encodes the expected case for each letter:
-/// - `i` / `j` => the corresponding letter should be uppercase
-/// - `p` / `q` => the corresponding letter should be lowercase
-/// - `=` => compound boundary marker (skipped; does not consume a word char)
-///
-/// Origin: SuggestionGeneratorCaseChange.cpp:86-101
-fn apply_structure_case(word: &[char], structure: &str) -> Vec<char> {
-    let mut result: Vec<char> = word.to_vec();
-    let struct_chars: Vec<char> = structure.chars().collect();
-    let mut j = 0;
-
-    for ch in &mut result {
-        // Skip compound boundary markers.
-        while j < struct_chars.len() && struct_chars[j] == '=' {
-            j += 1;
-        }
-        if j >= struct_chars.len() {
-            break;
-        }
-        match struct_chars[j] {
-            'i' | 'j' => {
-                *ch = simple_upper(*ch);
-            }
-            'p' | 'q' => {
-                *ch = simple_lower(*ch);
-            }
-            _ => {}
-        }
-        j += 1;
-    }
-
-    result
-}
-
 /// Map a `SpellResult` to a base priority value.
 ///
 /// Lower values are better. These roughly mirror the C++ behavior in
@@ -777,10 +745,21 @@ impl SuggestionGenerator for MultiReplacement {
 /// - Longer words: `50 / word_len`
 ///
 /// Skips swaps of identical characters and front/back vowel swaps
-/// (already handled by VowelChange).
+/// (already handled by VowelChange), as given by the vowel harmony tables
+/// of `rules`.
 ///
 /// Origin: SuggestionGeneratorSwap.cpp
-pub struct Swap;
+pub struct Swap {
+    pub rules: Arc<LanguageRules>,
+}
+
+impl Default for Swap {
+    fn default() -> Self {
+        Self {
+            rules: LanguageRules::shared_default(),
+        }
+    }
+}
 
 impl SuggestionGenerator for Swap {
     /// Origin: SuggestionGeneratorSwap.cpp:44-77
@@ -818,13 +797,9 @@ impl SuggestionGenerator for Swap {
                 }
                 // Do not suggest swapping front and back vowels
                 // (already tested by VowelChange)
-                let skip = (0..3).any(|k| {
-                    (simple_lower(buffer[i]) == BACK_VOWELS[k]
-                        && simple_lower(buffer[j]) == FRONT_VOWELS[k])
-                        || (simple_lower(buffer[i]) == FRONT_VOWELS[k]
-                            && simple_lower(buffer[j]) == BACK_VOWELS[k])
-                });
-                if skip {
+                if self.rules.harmony_counterpart(simple_lower(buffer[i]))
+                    == Some(simple_lower(buffer[j]))
+                {
                     continue;
                 }
                 buffer[i] = word[j];
@@ -970,19 +945,19 @@ impl SuggestionGenerator for SplitWord {
 ///
 /// Finnish has vowel harmony: back vowels (a, o, u) correspond to front
 /// vowels (ae, oe, y). This generator enumerates all 2^n - 1 combinations
-/// of flipping vowels (up to 7 vowels in the word).
+/// of flipping vowels (up to 7 vowels in the word). The vowel pairs come
+/// from `rules`.
 ///
 /// Origin: SuggestionGeneratorVowelChange.cpp
-pub struct VowelChange;
+pub struct VowelChange {
+    pub rules: Arc<LanguageRules>,
+}
 
-impl VowelChange {
-    /// Check if a character is a back or front vowel (case-insensitive),
-    /// returning its index in the vowel arrays (0-5) or None.
-    fn vowel_index(c: char) -> Option<usize> {
-        BACK_VOWELS
-            .iter()
-            .position(|&v| v == c)
-            .or_else(|| FRONT_VOWELS.iter().position(|&v| v == c))
+impl Default for VowelChange {
+    fn default() -> Self {
+        Self {
+            rules: LanguageRules::shared_default(),
+        }
     }
 }
 
@@ -1001,7 +976,7 @@ impl SuggestionGenerator for VowelChange {
         let mut vcount: usize = 0;
         let mut mask: u32 = 0;
         for &c in &word {
-            if VowelChange::vowel_index(c).is_some() {
+            if self.rules.harmony_counterpart(c).is_some() {
                 vcount += 1;
                 mask = (mask << 1) | 1;
             }
@@ -1019,15 +994,9 @@ impl SuggestionGenerator for VowelChange {
 
             let mut vowel_idx = 0;
             for i in 0..wlen {
-                if VowelChange::vowel_index(word[i]).is_some() {
+                if let Some(flipped) = self.rules.harmony_counterpart(word[i]) {
                     if (pat & (1 << vowel_idx)) != 0 {
-                        // Flip the vowel
-                        if let Some(pos) = BACK_VOWELS.iter().position(|&v| v == buffer[i]) {
-                            buffer[i] = FRONT_VOWELS[pos];
-                        } else if let Some(pos) = FRONT_VOWELS.iter().position(|&v| v == buffer[i])
-                        {
-                            buffer[i] = BACK_VOWELS[pos];
-                        }
+                        buffer[i] = flipped;
                     }
                     vowel_idx += 1;
                 }
@@ -1087,6 +1056,128 @@ impl SuggestionGenerator for DeleteTwo {
     }
 }
 
+// ---------------------------------------------------------------------------
+// HyphenVariants
+// ---------------------------------------------------------------------------
+
+/// Maximum number of hyphen positions varied by [`HyphenVariants`]; it
+/// tries at most `2^n - 1` variants.
+const MAX_HYPHEN_POSITIONS: usize = 6;
+
+/// Try adding and removing hyphens at compound boundaries, e.g.
+/// "linjaautoasema" and "linja-auto-asema" -> "linja-autoasema".
+///
+/// The varied positions are the hyphens inside the word and the boundaries
+/// between two identical vowels, which Finnish spelling separates with a
+/// hyphen in compounds. A vowel pair counts as a boundary only if the
+/// letters after it analyze as a word, as the last part of a compound
+/// always does. Variants with fewer hyphens are
+/// tried first, so when the speller also accepts optional hyphens
+/// (`accept_extra_hyphens`) the plain spelling still ranks first. Hyphens
+/// at the start or end of the word mark a word fragment (see
+/// `accept_missing_hyphens`) and are kept as they are.
+///
+/// This is a primary generator: when only the hyphenation is wrong, the
+/// edit-based generators are skipped.
+pub struct HyphenVariants;
+
+impl HyphenVariants {
+    /// Positions in `letters` (the word without hyphens) before which a
+    /// hyphen may be placed, or `None` if the budget ran out.
+    fn positions(
+        letters: &[char],
+        hyphens: &[usize],
+        analyzer: Option<&dyn Analyzer>,
+        status: &mut SuggestionStatus<'_>,
+    ) -> Option<Vec<usize>> {
+        let mut positions = hyphens.to_vec();
+        for i in 2..letters.len().saturating_sub(1) {
+            let (before, after) = (simple_lower(letters[i - 1]), simple_lower(letters[i]));
+            if before != after || !is_vowel(before) || positions.contains(&i) {
+                continue;
+            }
+            if let Some(analyzer) = analyzer {
+                if status.should_abort() {
+                    return None;
+                }
+                status.charge();
+                if analyzer
+                    .analyze(&letters[i..], letters.len() - i)
+                    .is_empty()
+                {
+                    continue;
+                }
+            }
+            positions.push(i);
+        }
+        positions.sort_unstable();
+        positions.truncate(MAX_HYPHEN_POSITIONS);
+        Some(positions)
+    }
+}
+
+impl SuggestionGenerator for HyphenVariants {
+    fn generate(
+        &self,
+        speller: &dyn Speller,
+        analyzer: Option<&dyn Analyzer>,
+        status: &mut SuggestionStatus<'_>,
+    ) {
+        let word = status.word().to_vec();
+        let start = word.iter().take_while(|&&c| c == '-').count();
+        if start == word.len() {
+            return;
+        }
+        let end = word.len() - word.iter().rev().take_while(|&&c| c == '-').count();
+
+        // Letters of the word between the edge hyphens, and the letter
+        // indices the inner hyphens stand before
+        let mut letters = Vec::with_capacity(end - start);
+        let mut hyphens = Vec::new();
+        for &c in &word[start..end] {
+            if c != '-' {
+                letters.push(c);
+            } else if hyphens.last() != Some(&letters.len()) {
+                hyphens.push(letters.len());
+            }
+        }
+        let Some(positions) = Self::positions(&letters, &hyphens, analyzer, status) else {
+            return;
+        };
+        if positions.is_empty() {
+            return;
+        }
+
+        let mut masks: Vec<u32> = (0..1u32 << positions.len()).collect();
+        masks.sort_by_key(|mask| mask.count_ones());
+        let mut buffer = Vec::with_capacity(word.len() + positions.len());
+        for mask in masks {
+            if status.should_abort() {
+                break;
+            }
+            buffer.clear();
+            buffer.extend_from_slice(&word[..start]);
+            let mut chosen = positions
+                .iter()
+                .enumerate()
+                .filter(|&(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, &pos)| pos)
+                .peekable();
+            for (i, &c) in letters.iter().enumerate() {
+                if chosen.next_if_eq(&i).is_some() {
+                    buffer.push('-');
+                }
+                buffer.push(c);
+            }
+            buffer.extend_from_slice(&word[end..]);
+            if buffer != word {
+                let len = buffer.len();
+                suggest_for_buffer_with_analyzer(speller, status, &buffer, len, analyzer);
+            }
+        }
+    }
+}
+
 // =========================================================================
 // Tests
 // =========================================================================
@@ -1127,14 +1218,13 @@ mod tests {
     // --- CaseChange ---
 
     #[test]
-    fn case_change_finds_correct_word() {
+    fn case_change_does_not_suggest_the_word_itself() {
         let speller = MockSpeller::new(&["koira"]);
         let word = chars("koira");
         let mut status = SuggestionStatus::new(&word, 5);
         status.set_max_cost(100);
         CaseChange.generate(&speller, None, &mut status);
-        assert_eq!(status.suggestion_count(), 1);
-        assert_eq!(status.suggestions()[0].word, "koira");
+        assert_eq!(status.suggestion_count(), 0);
     }
 
     #[test]
@@ -1224,7 +1314,7 @@ mod tests {
         let word = chars("kiora"); // 'o' and 'i' swapped
         let mut status = SuggestionStatus::new(&word, 5);
         status.set_max_cost(100);
-        Swap.generate(&speller, None, &mut status);
+        Swap::default().generate(&speller, None, &mut status);
         assert!(status.suggestion_count() >= 1);
         assert!(status.suggestions().iter().any(|s| s.word == "koira"));
     }
@@ -1251,7 +1341,7 @@ mod tests {
         let word = chars("koira");
         let mut status = SuggestionStatus::new(&word, 5);
         status.set_max_cost(200);
-        VowelChange.generate(&speller, None, &mut status);
+        VowelChange::default().generate(&speller, None, &mut status);
         assert!(status.suggestion_count() >= 1);
     }
 
@@ -1261,10 +1351,31 @@ mod tests {
         let word = chars("brk");
         let mut status = SuggestionStatus::new(&word, 5);
         status.set_max_cost(100);
-        VowelChange.generate(&speller, None, &mut status);
+        VowelChange::default().generate(&speller, None, &mut status);
         assert_eq!(status.suggestion_count(), 0);
     }
 
+    #[test]
+    fn vowel_change_uses_the_given_pairs() {
+        // Only the o/ö pair: "köira" is reachable, "köirä" is not
+        let rules = Arc::new(LanguageRules {
+            back_vowels: vec!['o'],
+            front_vowels: vec!['\u{00F6}'],
+            ..LanguageRules::default()
+        });
+        let speller = MockSpeller::new(&["k\u{00F6}ira", "k\u{00F6}ir\u{00E4}"]);
+        let word = chars("koira");
+        let mut status = SuggestionStatus::new(&word, 5);
+        status.set_max_cost(200);
+        VowelChange { rules }.generate(&speller, None, &mut status);
+        let words: Vec<&str> = status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.as_str())
+            .collect();
+        assert_eq!(words, ["k\u{00F6}ira"]);
+    }
+
     // --- DeleteTwo ---
 
     #[test]
@@ -1317,6 +1428,77 @@ mod tests {
         assert!(status.suggestions().iter().any(|s| s.word == "kiissa"));
     }
 
+    // --- HyphenVariants ---
+
+    fn hyphen_variants(
+        speller: &dyn Speller,
+        analyzer: Option<&dyn Analyzer>,
+        w: &str,
+    ) -> Vec<String> {
+        let word = chars(w);
+        let mut status = SuggestionStatus::new(&word, 10);
+        status.set_max_cost(100);
+        HyphenVariants.generate(speller, analyzer, &mut status);
+        status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.clone())
+            .collect()
+    }
+
+    #[test]
+    fn hyphen_variants_fix_extra_and_missing_hyphens() {
+        let speller = MockSpeller::new(&["linja-autoasema"]);
+        assert_eq!(
+            hyphen_variants(&speller, None, "linja-auto-asema"),
+            ["linja-autoasema"]
+        );
+        assert_eq!(
+            hyphen_variants(&speller, None, "linjaautoasema"),
+            ["linja-autoasema"]
+        );
+        assert_eq!(
+            hyphen_variants(&speller, None, "linjaauto-asema"),
+            ["linja-autoasema"]
+        );
+    }
+
+    #[test]
+    fn hyphen_variants_prefer_fewer_hyphens() {
+        // As with accept_extra_hyphens: the optional hyphen is accepted too
+        let speller = MockSpeller::new(&["linja-autoasema", "linja-auto-asema"]);
+        assert_eq!(
+            hyphen_variants(&speller, None, "linjaauto-asema"),
+            ["linja-autoasema", "linja-auto-asema"]
+        );
+    }
+
+    #[test]
+    fn hyphen_variants_keep_edge_hyphens() {
+        let speller = MockSpeller::new(&["-kotimaa", "kotimaa"]);
+        assert_eq!(hyphen_variants(&speller, None, "-koti-maa"), ["-kotimaa"]);
+    }
+
+    #[test]
+    fn hyphen_variants_split_vowel_pairs_before_words() {
+        let speller = MockSpeller::new(&["ulko-ovi"]);
+        let mut analyzer = MockAnalyzer::new();
+        assert!(hyphen_variants(&speller, Some(&analyzer), "ulkoovi").is_empty());
+
+        analyzer.add("ovi", vec![make_analysis(&[(ATTR_STRUCTURE, "=ppp")])]);
+        assert_eq!(
+            hyphen_variants(&speller, Some(&analyzer), "ulkoovi"),
+            ["ulko-ovi"]
+        );
+    }
+
+    #[test]
+    fn hyphen_variants_noop_without_positions() {
+        let speller = MockSpeller::new(&["koira"]);
+        assert!(hyphen_variants(&speller, None, "koria").is_empty());
+        assert!(hyphen_variants(&speller, None, "--").is_empty());
+    }
+
     // --- MultiReplacement ---
 
     #[test]
@@ -1361,51 +1543,6 @@ mod tests {
         // Should not panic, even if budget is 0
     }
 
-    // --- apply_structure_case ---
-
-    #[test]
-    fn apply_structure_case_all_lowercase() {
-        let word = chars("koira");
-        let result = apply_structure_case(&word, "=ppppp");
-        let s: String = result.iter().collect();
-        assert_eq!(s, "koira");
-    }
-
-    #[test]
-    fn apply_structure_case_first_uppercase() {
-        let word = chars("helsinki");
-        let result = apply_structure_case(&word, "=ippppppp");
-        let s: String = result.iter().collect();
-        assert_eq!(s, "Helsinki");
-    }
-
-    #[test]
-    fn apply_structure_case_mixed() {
-        // "abc" with structure "=ipq" -> "Abc"
-        let word = chars("abc");
-        let result = apply_structure_case(&word, "=ipq");
-        let s: String = result.iter().collect();
-        assert_eq!(s, "Abc");
-    }
-
-    #[test]
-    fn apply_structure_case_compound() {
-        // Compound word: skip '=' markers
-        let word = chars("koiratalo");
-        let result = apply_structure_case(&word, "=ppppp=pppp");
-        let s: String = result.iter().collect();
-        assert_eq!(s, "koiratalo");
-    }
-
-    #[test]
-    fn apply_structure_case_uppercase_to_lowercase() {
-        // "KOIRA" with structure "=ppppp" -> "koira"
-        let word = chars("KOIRA");
-        let result = apply_structure_case(&word, "=ppppp");
-        let s: String = result.iter().collect();
-        assert_eq!(s, "koira");
-    }
-
     // --- suggest_for_buffer_with_analyzer ---
 
     /// A mock speller that returns a specific SpellResult for specific words.
@@ -1492,13 +1629,14 @@ mod tests {
 
     #[test]
     fn suggest_for_buffer_without_analyzer_adds_unchanged() {
-        // Without analyzer, CapitalizationError adds word as-is.
+        // Without analyzer, CapitalizationError adds the candidate as-is.
         let speller = CapErrorSpeller::new(&["helsinki"]);
-        let word = chars("helsinki");
+        let word = chars("helsinkki");
+        let buffer = chars("helsinki");
         let mut status = SuggestionStatus::new(&word, 5);
         status.set_max_cost(100);
 
-        suggest_for_buffer_with_analyzer(&speller, &mut status, &word, word.len(), None);
+        suggest_for_buffer_with_analyzer(&speller, &mut status, &buffer, buffer.len(), None);
 
         assert_eq!(status.suggestion_count(), 1);
         assert_eq!(status.suggestions()[0].word, "helsinki");
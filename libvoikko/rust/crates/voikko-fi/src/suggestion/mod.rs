@@ -6,22 +6,37 @@
 //
 // Architecture:
 //   - `generators`: individual edit-operation generators (SuggestionGenerator trait)
-//   - `status`: tracking object for abort conditions, cost budget, deduplication
+//   - `status`: tracking object for abort conditions, cost and time budget,
+//     cancellation, deduplication
 //   - `strategy`: orchestrator that composes generators into typing / OCR pipelines
 //   - `vfst`: VFST-based generator using error model + acceptor transducers
+//   - `ranking`: re-orders suggestions by configurable cost weights, names first
+//   - `parallel`: runs the secondary generators on a rayon pool ("parallel"
+//     feature)
+//   - `prefilter`: rejects candidates no dictionary word begins like
+//     before they are looked up ("handle" feature)
 //
 // Origin: spellchecker/suggestion/
 
 pub mod generators;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "handle")]
+pub(crate) mod prefilter;
+pub mod ranking;
 pub mod status;
 pub mod strategy;
 pub mod vfst;
 
 // Re-export key types for convenient access.
 pub use generators::SuggestionGenerator;
-pub use status::{Suggestion, SuggestionStatus};
+pub use ranking::{RankingWeights, SuggestionRanker, edit_distance, proper_nouns_first};
+pub use status::{
+    CancelToken, ScoredSuggestion, Suggestion, SuggestionBudget, SuggestionStatus,
+    score_suggestions,
+};
 pub use strategy::{
     SuggestionStrategy, default_ocr_strategy, default_typing_strategy, ocr_strategy,
-    typing_strategy,
+    typing_strategy, typing_strategy_with_rules,
 };
 pub use vfst::VfstSuggestion;
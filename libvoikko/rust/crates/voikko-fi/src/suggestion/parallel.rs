@@ -0,0 +1,221 @@
+// Parallel suggestion generation
+//
+// The secondary generators of a strategy are independent: each edits the
+// misspelled word in its own way and checks the candidates with the
+// speller. With the `parallel` feature they run at once on the rayon
+// thread pool, each on its own status that records the checks it makes and
+// the suggestions it adds. The records are then replayed in generator order
+// against the real status, so the suggestions, their priorities and the
+// cost spent are the same as when the generators run one after another:
+// a generator gets only the budget its predecessors left, and duplicates
+// are dropped by the real status.
+//
+// A generator cannot know in advance where the serial run would have
+// stopped it, so to bound the extra work each generator publishes its cost
+// and stops once its own cost plus that of the generators before it reaches
+// the doubled cost budget, the most a serial run spends.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use super::generators::SuggestionGenerator;
+use super::status::SuggestionStatus;
+use crate::morphology::Analyzer;
+use crate::speller::Speller;
+
+/// Something a generator did on its speculative status.
+pub(super) enum RunEvent {
+    /// An abort check at the given cost.
+    Check(usize),
+    /// A suggestion added with its base priority and path weight.
+    Add {
+        word: String,
+        priority: i32,
+        fst_weight: i32,
+    },
+}
+
+/// Shared cost counters and the event log of one generator's run.
+pub(super) struct Progress<'a> {
+    /// Cost spent so far by each generator, in generator order.
+    costs: &'a [AtomicUsize],
+    /// Index of this generator in `costs`.
+    index: usize,
+    /// Cost left in the doubled budget when the generators started.
+    cap: usize,
+    events: RefCell<Vec<RunEvent>>,
+}
+
+impl<'a> Progress<'a> {
+    pub(super) fn new(costs: &'a [AtomicUsize], index: usize, cap: usize) -> Self {
+        Self {
+            costs,
+            index,
+            cap,
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn record_check(&self, cost: usize) {
+        self.events.borrow_mut().push(RunEvent::Check(cost));
+    }
+
+    pub(super) fn record_add(&self, word: &str, priority: i32, fst_weight: i32) {
+        self.events.borrow_mut().push(RunEvent::Add {
+            word: word.to_string(),
+            priority,
+            fst_weight,
+        });
+    }
+
+    /// Publish this generator's cost.
+    pub(super) fn publish(&self, cost: usize) {
+        self.costs[self.index].store(cost, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the generators before this one and this one at
+    /// `cost` have together spent the whole doubled budget, so a serial run
+    /// would not get here.
+    pub(super) fn over_cap(&self, cost: usize) -> bool {
+        let before: usize = self.costs[..self.index]
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .sum();
+        before + cost >= self.cap
+    }
+
+    pub(super) fn into_events(self) -> Vec<RunEvent> {
+        self.events.into_inner()
+    }
+}
+
+/// Run `generators` in parallel and add their suggestions to `status` as
+/// if they had run in order.
+pub(super) fn generate_in_parallel(
+    generators: &[Box<dyn SuggestionGenerator>],
+    speller: &(dyn Speller + Sync),
+    analyzer: Option<&(dyn Analyzer + Sync)>,
+    status: &mut SuggestionStatus<'_>,
+) {
+    if status.should_abort() {
+        return;
+    }
+    let costs: Vec<AtomicUsize> = generators.iter().map(|_| AtomicUsize::new(0)).collect();
+    let runs: Vec<SuggestionStatus<'_>> = (0..generators.len())
+        .map(|index| status.speculative(&costs, index))
+        .collect();
+    let runs: Vec<_> = runs
+        .into_par_iter()
+        .zip(generators.par_iter())
+        .map(|(mut run, generator)| {
+            generator.generate(speller, analyzer.map(|a| a as &dyn Analyzer), &mut run);
+            run.finish_speculative()
+        })
+        .collect();
+
+    for (events, cost) in runs {
+        if status.should_abort() {
+            break;
+        }
+        status.replay(events, cost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggestion::status::SuggestionBudget;
+    use crate::suggestion::strategy::{default_ocr_strategy, default_typing_strategy};
+    use voikko_core::enums::SpellResult;
+
+    /// Accepts a fixed set of words.
+    struct MockSpeller {
+        accepted: Vec<String>,
+    }
+
+    impl Speller for MockSpeller {
+        fn spell(&self, word: &[char], word_len: usize) -> SpellResult {
+            let s: String = word[..word_len].iter().collect();
+            if self.accepted.contains(&s) {
+                SpellResult::Ok
+            } else {
+                SpellResult::Failed
+            }
+        }
+    }
+
+    fn speller(words: &[&str]) -> MockSpeller {
+        MockSpeller {
+            accepted: words.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn suggestions(status: &SuggestionStatus<'_>) -> Vec<(String, i32, i32)> {
+        status
+            .suggestions()
+            .iter()
+            .map(|s| (s.word.clone(), s.priority, s.fst_weight))
+            .collect()
+    }
+
+    #[test]
+    fn parallel_run_matches_serial_run() {
+        let speller = speller(&[
+            "koira", "koiraa", "kissa", "kiss", "talo", "taloa", "koirat", "kortti",
+        ]);
+        for strategy in [default_typing_strategy(), default_ocr_strategy()] {
+            for word in ["koirra", "kiora", "kissaa", "tlao", "koitat", "xyzzyq"] {
+                for max_cost in [None, Some(5), Some(40), Some(200)] {
+                    let budget = SuggestionBudget {
+                        max_cost,
+                        ..SuggestionBudget::default()
+                    };
+                    let chars: Vec<char> = word.chars().collect();
+                    let mut serial = SuggestionStatus::new(&chars, 5);
+                    strategy.generate_with_budget(&speller, None, &mut serial, &budget);
+                    let mut parallel = SuggestionStatus::new(&chars, 5);
+                    strategy.generate_parallel(&speller, None, &mut parallel, &budget);
+                    assert_eq!(
+                        suggestions(&parallel),
+                        suggestions(&serial),
+                        "{word} {max_cost:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn replay_stops_where_serial_run_stops() {
+        let word: Vec<char> = "koirra".chars().collect();
+        let mut status = SuggestionStatus::new(&word, 2);
+        status.set_max_cost(10);
+        let events = vec![
+            RunEvent::Check(0),
+            RunEvent::Add {
+                word: "koira".to_string(),
+                priority: 1,
+                fst_weight: 0,
+            },
+            RunEvent::Check(4),
+            RunEvent::Add {
+                word: "koiria".to_string(),
+                priority: 2,
+                fst_weight: 0,
+            },
+            RunEvent::Check(12),
+            RunEvent::Add {
+                word: "koirat".to_string(),
+                priority: 1,
+                fst_weight: 0,
+            },
+        ];
+        status.replay(events, 20);
+        assert_eq!(
+            suggestions(&status),
+            [("koira".to_string(), 5, 0), ("koiria".to_string(), 12, 0)]
+        );
+    }
+}
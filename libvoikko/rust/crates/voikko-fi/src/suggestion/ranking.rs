@@ -0,0 +1,238 @@
+// Suggestion ranking with configurable weights
+//
+// The strategies order suggestions by a fixed priority: the morphological
+// priority of the candidate multiplied by a penalty for being found late.
+// SuggestionRanker re-orders the collected suggestions by a weighted sum of
+// three costs, so applications can tune which suggestions come first:
+//
+//   score = morph * priority + fst * fst_weight + edit * edit_distance
+//
+// Lower scores are better. The default weights (morph 1, others 0) keep
+// the original order.
+//
+// A capitalized word in the middle of a sentence is most likely a name, so
+// proper_nouns_first can further move the candidates the dictionary knows
+// as proper nouns ahead of the common words.
+
+use voikko_core::analysis::ATTR_CLASS;
+
+use super::status::Suggestion;
+use crate::morphology::Analyzer;
+
+/// Word classes (CLASS attribute values) of proper nouns.
+const PROPER_NOUN_CLASSES: [&str; 4] = ["etunimi", "sukunimi", "paikannimi", "nimi"];
+
+/// Coefficients of the ranking costs.
+///
+/// The costs are used as-is, so their scales differ: `priority` grows
+/// quickly with compound parts and discovery order, `fst_weight` is a
+/// transducer path weight (0 for suggestions not found by a weighted
+/// transducer), and the edit distance is a small number of edits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingWeights {
+    /// Coefficient of the edit distance between the word and the suggestion.
+    pub edit: f32,
+    /// Coefficient of the weighted transducer path weight.
+    pub fst: f32,
+    /// Coefficient of the morphological priority.
+    pub morph: f32,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            edit: 0.0,
+            fst: 0.0,
+            morph: 1.0,
+        }
+    }
+}
+
+/// Orders suggestions by a weighted sum of their costs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SuggestionRanker {
+    weights: RankingWeights,
+}
+
+impl SuggestionRanker {
+    /// Create a ranker with the given weights.
+    pub fn new(weights: RankingWeights) -> Self {
+        Self { weights }
+    }
+
+    /// Return the ranking weights.
+    pub fn weights(&self) -> RankingWeights {
+        self.weights
+    }
+
+    /// Compute the ranking score of a suggestion for `word` (lower is better).
+    pub fn score(&self, word: &[char], suggestion: &Suggestion) -> f64 {
+        let w = &self.weights;
+        let mut score = f64::from(w.morph) * f64::from(suggestion.priority)
+            + f64::from(w.fst) * f64::from(suggestion.fst_weight);
+        if w.edit != 0.0 {
+            let candidate: Vec<char> = suggestion.word.chars().collect();
+            score += f64::from(w.edit) * edit_distance(word, &candidate) as f64;
+        }
+        score
+    }
+
+    /// Sort suggestions by score, best first. The sort is stable, so
+    /// suggestions with equal scores keep their order.
+    pub fn rank(&self, word: &[char], suggestions: &mut Vec<Suggestion>) {
+        let mut keyed: Vec<(f64, Suggestion)> = std::mem::take(suggestions)
+            .into_iter()
+            .map(|s| (self.score(word, &s), s))
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        suggestions.extend(keyed.into_iter().map(|(_, s)| s));
+    }
+}
+
+/// Move the suggestions that `analyzer` analyzes as proper nouns ahead of
+/// the others. Both groups keep their order, so common words are still
+/// offered, after the names.
+pub fn proper_nouns_first(suggestions: &mut Vec<Suggestion>, analyzer: &dyn Analyzer) {
+    let is_proper_noun = |suggestion: &Suggestion| {
+        let word: Vec<char> = suggestion.word.chars().collect();
+        analyzer
+            .analyze_any_case(&word, word.len())
+            .0
+            .iter()
+            .any(|a| {
+                a.get(ATTR_CLASS)
+                    .is_some_and(|c| PROPER_NOUN_CLASSES.contains(&c))
+            })
+    };
+    let (mut proper, common): (Vec<_>, Vec<_>) = std::mem::take(suggestions)
+        .into_iter()
+        .partition(is_proper_noun);
+    proper.extend(common);
+    *suggestions = proper;
+}
+
+/// Edit distance between two words: the number of insertions, deletions,
+/// substitutions and transpositions of adjacent characters needed to turn
+/// `a` into `b` (optimal string alignment distance).
+pub fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let n = b.len();
+    // Rows i-2, i-1 and i of the dynamic programming table.
+    let mut prev2: Vec<usize> = vec![0; n + 1];
+    let mut prev: Vec<usize> = (0..=n).collect();
+    let mut cur: Vec<usize> = vec![0; n + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=n {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voikko_core::analysis::Analysis;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn sugg(word: &str, priority: i32, fst_weight: i32) -> Suggestion {
+        Suggestion {
+            word: word.to_string(),
+            priority,
+            fst_weight,
+        }
+    }
+
+    fn words(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.word.as_str()).collect()
+    }
+
+    struct MockAnalyzer;
+
+    impl Analyzer for MockAnalyzer {
+        fn analyze(&self, word: &[char], _word_len: usize) -> Vec<Analysis> {
+            let word: String = word.iter().collect();
+            let class = match word.as_str() {
+                "Virta" => vec!["sukunimi", "nimisana"],
+                "Vilja" => vec!["etunimi"],
+                "Vihta" => vec!["nimisana"],
+                _ => vec![],
+            };
+            class
+                .into_iter()
+                .map(|c| {
+                    let mut analysis = Analysis::new();
+                    analysis.set(ATTR_CLASS, c);
+                    analysis
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn proper_nouns_move_ahead_in_order() {
+        let mut s = vec![
+            sugg("Vihta", 1, 0),
+            sugg("Virta", 2, 0),
+            sugg("Viita", 3, 0),
+            sugg("Vilja", 4, 0),
+        ];
+        proper_nouns_first(&mut s, &MockAnalyzer);
+        assert_eq!(words(&s), vec!["Virta", "Vilja", "Vihta", "Viita"]);
+    }
+
+    #[test]
+    fn edit_distance_operations() {
+        assert_eq!(edit_distance(&chars("koira"), &chars("koira")), 0);
+        assert_eq!(edit_distance(&chars("koira"), &chars("koiran")), 1);
+        assert_eq!(edit_distance(&chars("koira"), &chars("kira")), 1);
+        assert_eq!(edit_distance(&chars("koira"), &chars("kaira")), 1);
+        assert_eq!(edit_distance(&chars("koira"), &chars("kiora")), 1);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+        assert_eq!(edit_distance(&chars("käsi"), &chars("kasi")), 1);
+    }
+
+    #[test]
+    fn default_weights_keep_priority_order() {
+        let word = chars("koria");
+        let mut s = vec![sugg("b", 30, 0), sugg("a", 10, 0), sugg("c", 10, 0)];
+        SuggestionRanker::default().rank(&word, &mut s);
+        assert_eq!(words(&s), vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn edit_weight_prefers_closer_words() {
+        let word = chars("koria");
+        let mut s = vec![sugg("kortteja", 10, 0), sugg("koira", 20, 0)];
+        let ranker = SuggestionRanker::new(RankingWeights {
+            edit: 100.0,
+            fst: 0.0,
+            morph: 1.0,
+        });
+        ranker.rank(&word, &mut s);
+        assert_eq!(words(&s), vec!["koira", "kortteja"]);
+    }
+
+    #[test]
+    fn fst_weight_only() {
+        let word = chars("x");
+        let mut s = vec![sugg("a", 1, 9), sugg("b", 50, 2)];
+        let ranker = SuggestionRanker::new(RankingWeights {
+            edit: 0.0,
+            fst: 1.0,
+            morph: 0.0,
+        });
+        ranker.rank(&word, &mut s);
+        assert_eq!(words(&s), vec!["b", "a"]);
+        assert_eq!(ranker.score(&word, &s[0]), 2.0);
+    }
+}
@@ -2,6 +2,21 @@
 // Origin: spellchecker/suggestion/SuggestionStatus.hpp, SuggestionStatus.cpp
 
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use voikko_core::case::{CaseType, detect_case, set_case};
+use voikko_core::character::simple_lower;
+
+use super::generators::SOFT_HYPHEN;
+use crate::speller::pipeline::normalize;
+
+#[cfg(feature = "parallel")]
+use std::sync::atomic::AtomicUsize;
+
+#[cfg(feature = "parallel")]
+use super::parallel::{Progress, RunEvent};
 
 /// A suggestion candidate with its computed priority.
 ///
@@ -14,6 +29,51 @@ pub struct Suggestion {
     pub word: String,
     /// Priority of the suggestion (lower is better).
     pub priority: i32,
+    /// Path weight from a weighted transducer (lower is better). 0 for
+    /// suggestions from generators that do not use weights.
+    pub fst_weight: i32,
+}
+
+/// A flag for cancelling suggestion generation from another thread.
+///
+/// Clones share the flag: keep one clone and pass another in a
+/// [`SuggestionBudget`]. Generators check the flag between candidates, so
+/// generation stops shortly after `cancel` is called.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Return `true` if cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits for one suggestion generation run.
+///
+/// The default budget uses the strategy's cost limit, with no time limit
+/// and no cancellation. Suggestions found before a time limit or
+/// cancellation stops generation are still returned.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionBudget {
+    /// Maximum cost (one unit per spell check or analysis). `None` uses the
+    /// strategy's own budget (800 for typing, 2000 for OCR).
+    pub max_cost: Option<usize>,
+    /// Maximum wall-clock time. Not supported on `wasm32-unknown-unknown`,
+    /// which has no clock.
+    pub max_duration: Option<Duration>,
+    /// Token for cancelling generation from another thread.
+    pub cancel_token: Option<CancelToken>,
 }
 
 /// Tracks the state of suggestion generation: found suggestions,
@@ -36,8 +96,20 @@ pub struct SuggestionStatus<'a> {
     current_cost: usize,
     /// Collected suggestions.
     suggestions: Vec<Suggestion>,
-    /// Set of already-seen suggestion strings for deduplication.
+    /// Folded forms (see `fold`) of the collected suggestions.
     seen: HashSet<String>,
+    /// The word folded like the suggestions.
+    folded_word: String,
+    /// Reject candidates that differ from the word only in case or soft
+    /// hyphens.
+    reject_variants: bool,
+    /// Time after which generation stops.
+    deadline: Option<Instant>,
+    /// Token that stops generation when cancelled.
+    cancel_token: Option<CancelToken>,
+    /// Set when the status belongs to one generator of a parallel run.
+    #[cfg(feature = "parallel")]
+    progress: Option<Progress<'a>>,
 }
 
 impl<'a> SuggestionStatus<'a> {
@@ -52,6 +124,12 @@ impl<'a> SuggestionStatus<'a> {
             current_cost: 0,
             suggestions: Vec::with_capacity(max_suggestions),
             seen: HashSet::new(),
+            folded_word: fold(word.iter().copied()),
+            reject_variants: false,
+            deadline: None,
+            cancel_token: None,
+            #[cfg(feature = "parallel")]
+            progress: None,
         }
     }
 
@@ -59,10 +137,23 @@ impl<'a> SuggestionStatus<'a> {
     ///
     /// Abort conditions:
     /// - Maximum suggestion count reached.
+    /// - Deadline passed or generation cancelled.
     /// - Cost budget exceeded (doubled if no suggestions found yet).
     ///
     /// Origin: SuggestionStatus.cpp:50-63
     pub fn should_abort(&self) -> bool {
+        #[cfg(feature = "parallel")]
+        if let Some(progress) = &self.progress {
+            progress.record_check(self.current_cost);
+            if progress.over_cap(self.current_cost) {
+                return true;
+            }
+        }
+        self.is_interrupted() || self.is_exhausted()
+    }
+
+    /// Returns `true` if the suggestion count or the cost budget is used up.
+    fn is_exhausted(&self) -> bool {
         if self.suggestions.len() >= self.max_suggestions {
             return true;
         }
@@ -83,6 +174,10 @@ impl<'a> SuggestionStatus<'a> {
     /// Origin: SuggestionStatus.cpp:65-67
     pub fn charge(&mut self) {
         self.current_cost += 1;
+        #[cfg(feature = "parallel")]
+        if let Some(progress) = &self.progress {
+            progress.publish(self.current_cost);
+        }
     }
 
     /// Set the maximum computational cost.
@@ -92,29 +187,155 @@ impl<'a> SuggestionStatus<'a> {
         self.max_cost = max_cost;
     }
 
+    /// Stop generation at `deadline`, or never if `None`.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Stop generation when `cancel_token` is cancelled.
+    pub fn set_cancel_token(&mut self, cancel_token: Option<CancelToken>) {
+        self.cancel_token = cancel_token;
+    }
+
+    /// Apply the time limit and cancellation token of `budget`, measuring
+    /// the time limit from now. The cost limit is left to the caller.
+    pub fn apply_budget(&mut self, budget: &SuggestionBudget) {
+        self.deadline = budget
+            .max_duration
+            .and_then(|d| Instant::now().checked_add(d));
+        self.cancel_token = budget.cancel_token.clone();
+    }
+
+    /// Return `true` if generation was cancelled or its deadline has
+    /// passed.
+    pub fn is_interrupted(&self) -> bool {
+        self.cancel_token
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
     /// Add a new suggestion with the given base priority.
     ///
     /// The final priority is `priority * (suggestion_count + 5)`, which
     /// penalizes later-found suggestions to bias toward strategies executed
     /// earlier in the pipeline.
     ///
-    /// Duplicate suggestions (same string) are silently ignored.
+    /// Duplicate suggestions (same string) and the word itself are silently
+    /// ignored, as are variants of it (see [`reject_variants`](Self::reject_variants)).
     ///
     /// Origin: SuggestionStatus.cpp:73-86
     pub fn add_suggestion(&mut self, suggestion: String, priority: i32) {
+        self.push(suggestion, priority, 0);
+    }
+
+    /// Add a suggestion found by a weighted transducer. The path weight is
+    /// used as the base priority, as in `add_suggestion`, and is also kept
+    /// as `fst_weight` for ranking.
+    pub fn add_weighted_suggestion(&mut self, suggestion: String, weight: i32) {
+        self.push(suggestion, weight, weight);
+    }
+
+    /// Reject from now on candidates that differ from the word only in
+    /// case or soft hyphens. The strategy sets this after the primary
+    /// generators, which exist to make exactly those corrections; a
+    /// variant found later only repeats the misspelled word.
+    pub fn reject_variants(&mut self) {
+        self.reject_variants = true;
+    }
+
+    /// Returns `true` if `suggestion` is the word itself or, once variants
+    /// are rejected, differs from it only in case or soft hyphens.
+    fn is_trivial(&self, suggestion: &str) -> bool {
+        if suggestion.chars().eq(self.word.iter().copied()) {
+            return true;
+        }
+        self.reject_variants && fold(suggestion.chars()) == self.folded_word
+    }
+
+    /// Add a suggestion unless it is trivial or a duplicate. Candidates
+    /// that fold to the same form are duplicates: they differ only in case,
+    /// soft hyphens or Unicode composition ("a\u{308}" and "ä"). Of two
+    /// differing in case, the one cased like the word is kept, in the place
+    /// of the first.
+    fn push(&mut self, suggestion: String, priority: i32, fst_weight: i32) {
+        if self.is_trivial(&suggestion) {
+            return;
+        }
+        let folded = fold(suggestion.chars());
+        if self.seen.contains(&folded) {
+            if self.recase(&folded, &suggestion) {
+                #[cfg(feature = "parallel")]
+                if let Some(progress) = &self.progress {
+                    progress.record_add(&suggestion, priority, fst_weight);
+                }
+            }
+            return;
+        }
         if self.suggestions.len() >= self.max_suggestions {
             return;
         }
-        if !self.seen.insert(suggestion.clone()) {
-            return; // duplicate
+        self.seen.insert(folded);
+        #[cfg(feature = "parallel")]
+        if let Some(progress) = &self.progress {
+            progress.record_add(&suggestion, priority, fst_weight);
         }
         let final_priority = priority * (self.suggestions.len() as i32 + 5);
         self.suggestions.push(Suggestion {
             word: suggestion,
             priority: final_priority,
+            fst_weight,
         });
     }
 
+    /// Replace the collected duplicate of `suggestion` with it if only
+    /// `suggestion` has the case pattern of the word. Returns `true` if it
+    /// was replaced.
+    fn recase(&mut self, folded: &str, suggestion: &str) -> bool {
+        let word_case = detect_case(self.word);
+        let case_of = |s: &str| detect_case(&s.chars().collect::<Vec<_>>());
+        if case_of(suggestion) != word_case {
+            return false;
+        }
+        let Some(existing) = self
+            .suggestions
+            .iter_mut()
+            .find(|s| fold(s.word.chars()) == folded)
+        else {
+            return false;
+        };
+        if case_of(&existing.word) == word_case {
+            return false;
+        }
+        existing.word = suggestion.to_string();
+        true
+    }
+
+    /// Recase the suggestions to the case pattern of the word. For a word
+    /// with a capital first letter (also a mixed-case one), the all-lowercase
+    /// suggestions are capitalized; for an all-caps word, every suggestion
+    /// is uppercased. Suggestions that already have capitals keep them, as
+    /// the STRUCTURE of their analysis asks for them; the recasing only adds
+    /// capitals, which STRUCTURE never forbids.
+    ///
+    /// Origin: suggestions.cpp:124-143
+    pub fn match_word_case(&mut self) {
+        let target = match detect_case(self.word) {
+            CaseType::FirstUpper => CaseType::FirstUpper,
+            CaseType::Complex if self.word[0].is_uppercase() => CaseType::FirstUpper,
+            CaseType::AllUpper => CaseType::AllUpper,
+            _ => return,
+        };
+        for suggestion in &mut self.suggestions {
+            let mut chars: Vec<char> = suggestion.word.chars().collect();
+            if target == CaseType::FirstUpper && detect_case(&chars) != CaseType::AllLower {
+                continue;
+            }
+            set_case(&mut chars, target);
+            suggestion.word = chars.into_iter().collect();
+        }
+    }
+
     /// Sort suggestions by priority (ascending -- lower priority is better).
     ///
     /// Origin: SuggestionStatus.cpp:92-103
@@ -159,6 +380,111 @@ impl<'a> SuggestionStatus<'a> {
     pub fn suggestions(&self) -> &[Suggestion] {
         &self.suggestions
     }
+
+    /// Create an empty status with the same word and limits for generator
+    /// `index` of a parallel run, which publishes its cost in `costs`.
+    #[cfg(feature = "parallel")]
+    pub(super) fn speculative(&self, costs: &'a [AtomicUsize], index: usize) -> Self {
+        // No generator can spend more than what is left of the doubled
+        // budget, the most a serial run spends.
+        let cap = (2 * self.max_cost).saturating_sub(self.current_cost);
+        let mut status = Self::new(self.word, self.max_suggestions);
+        status.max_cost = self.max_cost;
+        status.deadline = self.deadline;
+        status.cancel_token = self.cancel_token.clone();
+        status.reject_variants = self.reject_variants;
+        status.progress = Some(Progress::new(costs, index, cap));
+        status
+    }
+
+    /// Finish a speculative status: return what its generator did and the
+    /// cost it spent.
+    #[cfg(feature = "parallel")]
+    pub(super) fn finish_speculative(self) -> (Vec<RunEvent>, usize) {
+        let events = self.progress.map(Progress::into_events).unwrap_or_default();
+        (events, self.current_cost)
+    }
+
+    /// Replay a generator's speculative run as if the generator had run on
+    /// this status: its checks are charged from the current cost and the
+    /// replay stops where this status would have stopped the generator.
+    /// Time limits are not re-checked; the run already observed them.
+    #[cfg(feature = "parallel")]
+    pub(super) fn replay(&mut self, events: Vec<RunEvent>, cost: usize) {
+        let start = self.current_cost;
+        for event in events {
+            match event {
+                RunEvent::Check(at) => {
+                    self.current_cost = start + at;
+                    if self.is_exhausted() {
+                        return;
+                    }
+                }
+                RunEvent::Add {
+                    word,
+                    priority,
+                    fst_weight,
+                } => self.push(word, priority, fst_weight),
+            }
+        }
+        self.current_cost = start + cost;
+    }
+}
+
+/// Drop the soft hyphens of `word`, compose combining marks with their
+/// base letters as the speller does, and lowercase it.
+fn fold(word: impl Iterator<Item = char>) -> String {
+    let word: Vec<char> = word.filter(|&c| c != SOFT_HYPHEN).collect();
+    normalize(&word).into_iter().map(simple_lower).collect()
+}
+
+/// A suggestion together with a confidence score in `0.0..=1.0`.
+///
+/// The confidence measures how clearly a suggestion beats its strongest
+/// competitor: `1.0` for a suggestion without competitors, `0.0` for one
+/// that is not strictly better than every other suggestion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredSuggestion {
+    /// The suggested word.
+    pub word: String,
+    /// Priority of the suggestion (lower is better).
+    pub priority: i32,
+    /// Confidence that this suggestion is the intended word.
+    pub confidence: f64,
+}
+
+/// Attach confidence scores to a list of suggestions.
+///
+/// For each suggestion the score is `(p_other - p) / p_other`, where `p` is
+/// its priority and `p_other` the best priority among the other suggestions.
+/// Only an unambiguous best suggestion can get a non-zero score. The input
+/// order is preserved.
+pub fn score_suggestions(suggestions: Vec<Suggestion>) -> Vec<ScoredSuggestion> {
+    let priorities: Vec<i32> = suggestions.iter().map(|s| s.priority).collect();
+    suggestions
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let best_other = priorities
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &p)| p)
+                .min();
+            let confidence = match best_other {
+                None => 1.0,
+                Some(other) if other > s.priority && other > 0 => {
+                    (other - s.priority.max(0)) as f64 / other as f64
+                }
+                Some(_) => 0.0,
+            };
+            ScoredSuggestion {
+                word: s.word,
+                priority: s.priority,
+                confidence,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -169,6 +495,67 @@ mod tests {
         s.chars().collect()
     }
 
+    fn sugg(word: &str, priority: i32) -> Suggestion {
+        Suggestion {
+            word: word.to_string(),
+            priority,
+            fst_weight: 0,
+        }
+    }
+
+    #[test]
+    fn word_itself_and_its_variants_are_rejected() {
+        let word = chars("Koi\u{00AD}ra");
+        let mut status = SuggestionStatus::new(&word, 10);
+        status.add_suggestion("Koi\u{00AD}ra".to_string(), 1);
+        status.add_suggestion("koira".to_string(), 1);
+        status.add_suggestion("KOIRA".to_string(), 1);
+        let words: Vec<&str> = status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.as_str())
+            .collect();
+        // "KOIRA" is a case duplicate of "koira", neither cased like the word
+        assert_eq!(words, ["koira"]);
+
+        status.reject_variants();
+        status.add_suggestion("Koira".to_string(), 1);
+        status.add_weighted_suggestion("koi\u{00AD}rA".to_string(), 1);
+        status.add_suggestion("koirat".to_string(), 1);
+        let words: Vec<&str> = status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.as_str())
+            .collect();
+        assert_eq!(words, ["koira", "koirat"]);
+    }
+
+    #[test]
+    fn score_single_suggestion_is_certain() {
+        let scored = score_suggestions(vec![sugg("koira", 30)]);
+        assert_eq!(scored.len(), 1);
+        assert_eq!(scored[0].confidence, 1.0);
+    }
+
+    #[test]
+    fn score_clear_winner() {
+        let scored = score_suggestions(vec![sugg("koira", 10), sugg("koiria", 40)]);
+        assert_eq!(scored[0].word, "koira");
+        assert!((scored[0].confidence - 0.75).abs() < 1e-9);
+        assert_eq!(scored[1].confidence, 0.0);
+    }
+
+    #[test]
+    fn score_tie_is_ambiguous() {
+        let scored = score_suggestions(vec![sugg("a", 10), sugg("b", 10)]);
+        assert!(scored.iter().all(|s| s.confidence == 0.0));
+    }
+
+    #[test]
+    fn score_empty() {
+        assert!(score_suggestions(Vec::new()).is_empty());
+    }
+
     #[test]
     fn new_status_has_zero_cost_and_no_suggestions() {
         let word = chars("koira");
@@ -227,6 +614,33 @@ mod tests {
         assert!(status.should_abort());
     }
 
+    #[test]
+    fn should_abort_when_cancelled() {
+        let word = chars("abc");
+        let mut status = SuggestionStatus::new(&word, 5);
+        status.set_max_cost(1000);
+        let token = CancelToken::new();
+        status.set_cancel_token(Some(token.clone()));
+        assert!(!status.should_abort());
+        token.cancel();
+        assert!(status.is_interrupted());
+        assert!(status.should_abort());
+    }
+
+    #[test]
+    fn should_abort_after_deadline() {
+        let word = chars("abc");
+        let mut status = SuggestionStatus::new(&word, 5);
+        status.set_max_cost(1000);
+        status.apply_budget(&SuggestionBudget {
+            max_duration: Some(Duration::from_secs(3600)),
+            ..SuggestionBudget::default()
+        });
+        assert!(!status.should_abort());
+        status.set_deadline(Some(Instant::now()));
+        assert!(status.should_abort());
+    }
+
     #[test]
     fn add_suggestion_computes_final_priority() {
         let word = chars("abc");
@@ -251,6 +665,63 @@ mod tests {
         assert_eq!(status.suggestion_count(), 1);
     }
 
+    #[test]
+    fn composed_and_decomposed_suggestions_are_duplicates() {
+        let word = chars("mäkki");
+        let mut status = SuggestionStatus::new(&word, 5);
+        status.set_max_cost(1000);
+        status.add_suggestion("ma\u{0308}ki".to_string(), 1);
+        status.add_suggestion("mäki".to_string(), 2);
+        status.add_suggestion("Åland".to_string(), 3);
+        status.add_suggestion("A\u{030A}land".to_string(), 4);
+        assert_eq!(status.suggestion_count(), 2);
+        assert_eq!(status.suggestions()[0].word, "ma\u{0308}ki");
+        assert_eq!(status.suggestions()[1].word, "Åland");
+    }
+
+    #[test]
+    fn case_duplicates_keep_the_case_of_the_word() {
+        let word = chars("ruotssi");
+        let mut status = SuggestionStatus::new(&word, 5);
+        status.set_max_cost(1000);
+        status.add_suggestion("Ruotsi".to_string(), 1);
+        status.add_suggestion("ruotsi".to_string(), 2);
+        status.add_suggestion("RUOTSI".to_string(), 3);
+        assert_eq!(status.suggestion_count(), 1);
+        assert_eq!(status.suggestions()[0].word, "ruotsi");
+        assert_eq!(status.suggestions()[0].priority, 5);
+
+        let word = chars("Ruotssi");
+        let mut status = SuggestionStatus::new(&word, 1);
+        status.set_max_cost(1000);
+        status.add_suggestion("ruotsi".to_string(), 1);
+        status.add_suggestion("Ruotsi".to_string(), 2);
+        assert_eq!(status.suggestions()[0].word, "Ruotsi");
+    }
+
+    #[test]
+    fn match_word_case_follows_the_case_of_the_word() {
+        let recased = |word: &str| {
+            let word = chars(word);
+            let mut status = SuggestionStatus::new(&word, 5);
+            status.set_max_cost(1000);
+            status.add_suggestion("koira".to_string(), 1);
+            status.add_suggestion("ABC-kirja".to_string(), 2);
+            status.match_word_case();
+            status
+                .into_suggestions()
+                .into_iter()
+                .map(|s| s.word)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(recased("KOIRRA"), ["KOIRA", "ABC-KIRJA"]);
+        // Capitals required by STRUCTURE are not lowercased
+        assert_eq!(recased("Koirra"), ["Koira", "ABC-kirja"]);
+        assert_eq!(recased("KoiRra"), ["Koira", "ABC-kirja"]);
+        assert_eq!(recased("koirra"), ["koira", "ABC-kirja"]);
+        assert_eq!(recased("koiRra"), ["koira", "ABC-kirja"]);
+    }
+
     #[test]
     fn sort_suggestions_by_priority() {
         let word = chars("abc");
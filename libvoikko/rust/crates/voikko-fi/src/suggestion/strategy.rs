@@ -2,8 +2,11 @@
 // Origin: spellchecker/suggestion/SuggestionStrategy.cpp,
 //         SuggestionStrategyTyping.cpp, SuggestionStrategyOcr.cpp
 
+use std::sync::Arc;
+
 use super::generators::*;
-use super::status::SuggestionStatus;
+use super::status::{SuggestionBudget, SuggestionStatus};
+use crate::language_rules::LanguageRules;
 use crate::morphology::Analyzer;
 use crate::speller::Speller;
 
@@ -99,6 +102,8 @@ pub struct SuggestionStrategy {
     primary_generators: Vec<Box<dyn SuggestionGenerator>>,
     /// Secondary generators -- run only if primaries produced nothing.
     generators: Vec<Box<dyn SuggestionGenerator>>,
+    /// Recase the suggestions to the case pattern of the word.
+    match_word_case: bool,
 }
 
 impl SuggestionStrategy {
@@ -112,7 +117,74 @@ impl SuggestionStrategy {
         analyzer: Option<&dyn Analyzer>,
         status: &mut SuggestionStatus<'_>,
     ) {
-        status.set_max_cost(self.max_cost);
+        self.generate_with_budget(speller, analyzer, status, &SuggestionBudget::default());
+    }
+
+    /// Run the strategy within `budget`. Generation stops when the cost
+    /// limit is reached, the time limit passes or the budget's token is
+    /// cancelled, whichever comes first; the suggestions found so far are
+    /// kept in `status`.
+    pub fn generate_with_budget(
+        &self,
+        speller: &dyn Speller,
+        analyzer: Option<&dyn Analyzer>,
+        status: &mut SuggestionStatus<'_>,
+        budget: &SuggestionBudget,
+    ) {
+        if self.run_primary(speller, analyzer, status, budget) {
+            for generator in &self.generators {
+                if status.should_abort() {
+                    break;
+                }
+                generator.generate(speller, analyzer, status);
+            }
+        }
+        self.finish(status);
+    }
+
+    /// Like [`generate_with_budget`](Self::generate_with_budget), but run
+    /// the secondary generators in parallel on the rayon thread pool. The
+    /// suggestions, their priorities and the cost charged to `status` are
+    /// the same as with `generate_with_budget`.
+    #[cfg(feature = "parallel")]
+    pub fn generate_parallel(
+        &self,
+        speller: &(dyn Speller + Sync),
+        analyzer: Option<&(dyn Analyzer + Sync)>,
+        status: &mut SuggestionStatus<'_>,
+        budget: &SuggestionBudget,
+    ) {
+        let serial_analyzer = analyzer.map(|a| a as &dyn Analyzer);
+        if self.run_primary(speller, serial_analyzer, status, budget) {
+            super::parallel::generate_in_parallel(&self.generators, speller, analyzer, status);
+        }
+        self.finish(status);
+    }
+
+    /// Set whether the suggestions are recased to the case pattern of the
+    /// word (see [`SuggestionStatus::match_word_case`]). Enabled by default;
+    /// when disabled, the suggestions keep the case given by the STRUCTURE
+    /// of their analyses.
+    pub fn set_match_word_case(&mut self, value: bool) {
+        self.match_word_case = value;
+    }
+
+    /// Whether the suggestions are recased to the case pattern of the word.
+    pub fn match_word_case(&self) -> bool {
+        self.match_word_case
+    }
+
+    /// Apply `budget` and run the primary generators. Returns `true` if the
+    /// secondary generators should run, i.e. the primaries found nothing.
+    fn run_primary(
+        &self,
+        speller: &dyn Speller,
+        analyzer: Option<&dyn Analyzer>,
+        status: &mut SuggestionStatus<'_>,
+        budget: &SuggestionBudget,
+    ) -> bool {
+        status.set_max_cost(budget.max_cost.unwrap_or(self.max_cost));
+        status.apply_budget(budget);
 
         for generator in &self.primary_generators {
             if status.should_abort() {
@@ -120,16 +192,18 @@ impl SuggestionStrategy {
             }
             generator.generate(speller, analyzer, status);
         }
+        // If a primary generator found something, secondaries are skipped.
         if status.suggestion_count() > 0 {
-            // Primary generator found something; skip secondaries.
-            return;
+            return false;
         }
+        status.reject_variants();
+        true
+    }
 
-        for generator in &self.generators {
-            if status.should_abort() {
-                break;
-            }
-            generator.generate(speller, analyzer, status);
+    /// Apply the casing policy to the collected suggestions.
+    fn finish(&self, status: &mut SuggestionStatus<'_>) {
+        if self.match_word_case {
+            status.match_word_case();
         }
     }
 }
@@ -141,15 +215,29 @@ impl SuggestionStrategy {
 /// Create the typing strategy for Finnish keyboard errors.
 ///
 /// The generator order and replacement tables match the C++ `SuggestionStrategyTyping`
-/// constructor exactly.
+/// constructor, with [`HyphenVariants`] added to the primary generators.
 ///
 /// Origin: SuggestionStrategyTyping.cpp:103-143
 pub fn typing_strategy(max_cost: usize) -> SuggestionStrategy {
-    let primary_generators: Vec<Box<dyn SuggestionGenerator>> =
-        vec![Box::new(CaseChange), Box::new(SoftHyphens)];
+    typing_strategy_with_rules(max_cost, LanguageRules::shared_default())
+}
+
+/// Create the typing strategy with the vowel harmony tables of `rules`
+/// instead of the standard Finnish ones.
+pub fn typing_strategy_with_rules(
+    max_cost: usize,
+    rules: Arc<LanguageRules>,
+) -> SuggestionStrategy {
+    let primary_generators: Vec<Box<dyn SuggestionGenerator>> = vec![
+        Box::new(CaseChange),
+        Box::new(SoftHyphens),
+        Box::new(HyphenVariants),
+    ];
 
     let generators: Vec<Box<dyn SuggestionGenerator>> = vec![
-        Box::new(VowelChange),
+        Box::new(VowelChange {
+            rules: Arc::clone(&rules),
+        }),
         Box::new(Replacement {
             replacements: REPLACEMENTS_1.to_vec(),
         }),
@@ -165,7 +253,7 @@ pub fn typing_strategy(max_cost: usize) -> SuggestionStrategy {
         Box::new(Insertion {
             characters: INSERTION_CHARS_PRIMARY.chars().collect(),
         }),
-        Box::new(Swap),
+        Box::new(Swap { rules }),
         Box::new(Replacement {
             replacements: REPLACEMENTS_3.to_vec(),
         }),
@@ -194,6 +282,7 @@ pub fn typing_strategy(max_cost: usize) -> SuggestionStrategy {
         max_cost,
         primary_generators,
         generators,
+        match_word_case: true,
     }
 }
 
@@ -217,6 +306,7 @@ pub fn ocr_strategy(max_cost: usize) -> SuggestionStrategy {
         max_cost,
         primary_generators,
         generators,
+        match_word_case: true,
     }
 }
 
@@ -241,6 +331,8 @@ pub fn default_ocr_strategy() -> SuggestionStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::suggestion::status::CancelToken;
+    use std::time::Duration;
     use voikko_core::enums::SpellResult;
 
     /// A mock speller that accepts a predefined set of words.
@@ -273,9 +365,9 @@ mod tests {
 
     #[test]
     fn typing_strategy_primary_short_circuits() {
-        // If CaseChange finds the word, secondaries should not run.
-        let speller = MockSpeller::new(&["koira"]);
-        let word = chars("koira");
+        // If SoftHyphens finds the word, secondaries should not run.
+        let speller = MockSpeller::new(&["koira", "koirat"]);
+        let word = chars("koi\u{00AD}ra");
         let mut status = SuggestionStatus::new(&word, 5);
         let strategy = default_typing_strategy();
         strategy.generate(&speller, None, &mut status);
@@ -283,6 +375,50 @@ mod tests {
         assert_eq!(status.suggestions()[0].word, "koira");
     }
 
+    /// Accepts a word in any case.
+    struct CaseInsensitiveSpeller(&'static str);
+
+    impl Speller for CaseInsensitiveSpeller {
+        fn spell(&self, word: &[char], word_len: usize) -> SpellResult {
+            let s: String = word[..word_len].iter().collect();
+            if s.to_lowercase() == self.0 {
+                SpellResult::Ok
+            } else {
+                SpellResult::Failed
+            }
+        }
+    }
+
+    #[test]
+    fn secondary_generators_do_not_suggest_case_variants_of_the_word() {
+        // CaseChange finds the word itself, which is not a suggestion, so
+        // the secondaries run; Replacement then finds "kiSsa" and "kisSa",
+        // mere case variants of the word. Real corrections still pass.
+        let strategy = SuggestionStrategy {
+            max_cost: 100,
+            primary_generators: vec![Box::new(CaseChange)],
+            generators: vec![Box::new(Replacement {
+                replacements: vec!['s', 'S', 's', 'l'],
+            })],
+            match_word_case: true,
+        };
+        let speller = CaseInsensitiveSpeller("kissa");
+        let word = chars("kissa");
+        let mut status = SuggestionStatus::new(&word, 5);
+        strategy.generate(&speller, None, &mut status);
+        assert_eq!(status.suggestion_count(), 0);
+
+        let speller = CaseInsensitiveSpeller("kisla");
+        let mut status = SuggestionStatus::new(&word, 5);
+        strategy.generate(&speller, None, &mut status);
+        let words: Vec<&str> = status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.as_str())
+            .collect();
+        assert_eq!(words, ["kisla"]);
+    }
+
     #[test]
     fn typing_strategy_deletion() {
         // "koiraa" -> delete 'a' -> "koira"
@@ -351,10 +487,55 @@ mod tests {
         // Should not run forever -- just verify it terminates
     }
 
+    #[test]
+    fn cancelled_budget_stops_before_secondary_generators() {
+        // "koiraa" needs Deletion, a secondary generator
+        let speller = MockSpeller::new(&["koira"]);
+        let word = chars("koiraa");
+        let mut status = SuggestionStatus::new(&word, 5);
+        let token = CancelToken::new();
+        token.cancel();
+        let budget = SuggestionBudget {
+            cancel_token: Some(token),
+            ..SuggestionBudget::default()
+        };
+        default_typing_strategy().generate_with_budget(&speller, None, &mut status, &budget);
+        assert_eq!(status.suggestion_count(), 0);
+        assert!(status.is_interrupted());
+    }
+
+    #[test]
+    fn expired_time_budget_stops_generation() {
+        let speller = MockSpeller::new(&["koira"]);
+        let word = chars("koiraa");
+        let mut status = SuggestionStatus::new(&word, 5);
+        let budget = SuggestionBudget {
+            max_duration: Some(Duration::ZERO),
+            ..SuggestionBudget::default()
+        };
+        default_typing_strategy().generate_with_budget(&speller, None, &mut status, &budget);
+        assert_eq!(status.suggestion_count(), 0);
+    }
+
+    #[test]
+    fn budget_cost_overrides_strategy_cost() {
+        // Deletion is found within the default budget but not with cost 1
+        let speller = MockSpeller::new(&["koira"]);
+        let word = chars("koiraa");
+        let mut status = SuggestionStatus::new(&word, 5);
+        let budget = SuggestionBudget {
+            max_cost: Some(1),
+            ..SuggestionBudget::default()
+        };
+        default_typing_strategy().generate_with_budget(&speller, None, &mut status, &budget);
+        assert!(!status.suggestions().iter().any(|s| s.word == "koira"));
+    }
+
     #[test]
     fn typing_strategy_has_correct_generator_counts() {
         let strategy = default_typing_strategy();
-        assert_eq!(strategy.primary_generators.len(), 2);
+        // CaseChange and SoftHyphens as in C++, plus HyphenVariants
+        assert_eq!(strategy.primary_generators.len(), 3);
         assert_eq!(strategy.generators.len(), 17);
     }
 
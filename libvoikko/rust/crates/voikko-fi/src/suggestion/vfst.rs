@@ -10,12 +10,14 @@
 
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use voikko_fst::Transducer;
+use voikko_fst::compile::CompileError;
+use voikko_fst::error_model::EditDistanceModel;
 use voikko_fst::weighted::{WeightedResult, WeightedTransducer};
 
-use super::status::SuggestionStatus;
+use super::status::{SuggestionBudget, SuggestionStatus};
 
 /// Buffer size for weighted transducer traversal configurations.
 ///
@@ -57,6 +59,19 @@ impl VfstSuggestion {
         }
     }
 
+    /// Create a generator for a dictionary without `err.vfst`: `acceptor`
+    /// is paired with the generic error model compiled from `model`, and
+    /// the two are traversed in tandem as with a shipped error model.
+    pub fn with_edit_distance(
+        model: &EditDistanceModel,
+        acceptor: WeightedTransducer,
+    ) -> Result<Self, CompileError> {
+        let data = model.compile()?;
+        let error_model =
+            WeightedTransducer::from_bytes(&data).expect("compiled error model is valid");
+        Ok(Self::new(error_model, acceptor))
+    }
+
     /// Generate suggestions for the misspelled word tracked by `status`.
     ///
     /// Algorithm:
@@ -77,10 +92,23 @@ impl VfstSuggestion {
     ///
     /// Origin: VfstSuggestion.cpp:62-101
     pub fn generate(&self, status: &mut SuggestionStatus<'_>) {
+        self.generate_with_budget(status, &SuggestionBudget::default());
+    }
+
+    /// Generate suggestions within the time limit and cancellation token of
+    /// `budget`, checked before each error model output. Candidates
+    /// accepted before the traversal stops are still added. This generator
+    /// does not track cost, so `budget.max_cost` has no effect.
+    pub fn generate_with_budget(
+        &self,
+        status: &mut SuggestionStatus<'_>,
+        budget: &SuggestionBudget,
+    ) {
         // Not actually used for cost tracking in this generator, but matches
         // the C++ behavior where setMaxCost(100) is called.
         // Origin: VfstSuggestion.cpp:63
         status.set_max_cost(100);
+        status.apply_budget(budget);
 
         let word: Vec<char> = status.word().to_vec();
         let wlen = status.word_len();
@@ -117,11 +145,11 @@ impl VfstSuggestion {
                     &mut error_model_result,
                 )
             {
-                // Convert error model output to chars for the acceptor.
-                let candidate_chars: Vec<char> = error_model_output.chars().collect();
-
                 // Origin: VfstSuggestion.cpp:70
-                if self.acceptor.prepare(&mut acceptor_conf, &candidate_chars) {
+                if self
+                    .acceptor
+                    .prepare_str(&mut acceptor_conf, &error_model_output)
+                {
                     // Origin: VfstSuggestion.cpp:72
                     if self.acceptor.next_weighted(
                         &mut acceptor_conf,
@@ -164,9 +192,70 @@ impl VfstSuggestion {
 
         while let Some(Reverse((weight, suggestion))) = heap.pop() {
             // The C++ code passes the weight directly as the priority.
-            // Our SuggestionStatus::add_suggestion takes an i32 priority.
+            // The weight is also kept separately for SuggestionRanker.
             // Origin: VfstSuggestion.cpp:100
-            status.add_suggestion(suggestion, weight);
+            status.add_weighted_suggestion(suggestion, weight);
+        }
+    }
+
+    /// Generate up to `count` suggestions from the cheapest error model
+    /// outputs, instead of enumerating them all in depth-first order.
+    ///
+    /// Error model outputs are taken in increasing weight order (see
+    /// [`BestFirstOutputs`](voikko_fst::weighted::BestFirstOutputs)) and
+    /// checked against the acceptor; traversal stops once `count` distinct
+    /// candidates are accepted. `beam_width` bounds the error model search
+    /// as in [`WeightedTransducer::best_first_outputs`]. The accepted
+    /// candidates are added to `status` sorted by combined weight, as in
+    /// [`generate_with_budget`](Self::generate_with_budget).
+    pub fn generate_cheapest(
+        &self,
+        status: &mut SuggestionStatus<'_>,
+        budget: &SuggestionBudget,
+        count: usize,
+        beam_width: Option<usize>,
+    ) {
+        status.set_max_cost(100);
+        status.apply_budget(budget);
+
+        let word: Vec<char> = status.word()[..status.word_len()].to_vec();
+        let mut acceptor_conf = self.acceptor.new_config(BUFFER_SIZE);
+        let mut acceptor_output = String::new();
+        let mut acceptor_result = WeightedResult {
+            weight: 0,
+            first_not_reached_position: 0,
+        };
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut accepted: Vec<(i32, String)> = Vec::new();
+
+        for (candidate, error_weight) in self.error_model.best_first_outputs(&word, beam_width) {
+            if accepted.len() >= count || status.should_abort() {
+                break;
+            }
+            // Outputs come cheapest first, so a repeated candidate can only
+            // be reached by a more expensive path
+            if !seen.insert(candidate.clone()) {
+                continue;
+            }
+            if self.acceptor.prepare_str(&mut acceptor_conf, &candidate)
+                && self.acceptor.next_weighted(
+                    &mut acceptor_conf,
+                    &mut acceptor_output,
+                    &mut acceptor_result,
+                )
+            {
+                let weight = error_weight + i32::from(acceptor_result.weight);
+                accepted.push((weight, candidate));
+                if accepted.len() >= count {
+                    break;
+                }
+            }
+        }
+
+        accepted.sort();
+        for (weight, suggestion) in accepted {
+            status.add_weighted_suggestion(suggestion, weight);
         }
     }
 }
@@ -174,6 +263,7 @@ impl VfstSuggestion {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::suggestion::status::CancelToken;
     use voikko_fst::transition::WeightedTransition;
     use voikko_fst::weighted::WeightedTransducer;
 
@@ -302,6 +392,37 @@ mod tests {
         // SuggestionStatus multiplies by (count + 5), so first suggestion:
         // priority = 8 * (0 + 5) = 40
         assert_eq!(status.suggestions()[0].priority, 40);
+        assert_eq!(status.suggestions()[0].fst_weight, 8);
+    }
+
+    #[test]
+    fn generate_with_cancelled_budget_stops_traversal() {
+        // Error model "a" -> "b", acceptor accepts "b".
+        let final_state = make_transition(0xFFFFFFFF, 0, 0, 0, 0);
+        let err_data = build_vfst(
+            &["", "a", "b"],
+            &[make_transition(1, 2, 1, 0, 0), final_state],
+        );
+        let acc_data = build_vfst(&["", "b"], &[make_transition(1, 1, 1, 0, 0), final_state]);
+        let sg = VfstSuggestion::new(
+            WeightedTransducer::from_bytes(&err_data).unwrap(),
+            WeightedTransducer::from_bytes(&acc_data).unwrap(),
+        );
+        let word: Vec<char> = "a".chars().collect();
+
+        let token = CancelToken::new();
+        token.cancel();
+        let budget = SuggestionBudget {
+            cancel_token: Some(token),
+            ..SuggestionBudget::default()
+        };
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate_with_budget(&mut status, &budget);
+        assert_eq!(status.suggestion_count(), 0);
+
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate_with_budget(&mut status, &SuggestionBudget::default());
+        assert_eq!(status.suggestion_count(), 1);
     }
 
     /// Build an error model that maps "x" -> "a" (weight 5) and "x" -> "b" (weight 10).
@@ -361,6 +482,89 @@ mod tests {
         assert_eq!(status.suggestions()[1].word, "b");
     }
 
+    /// Error model maps "x" to "a" (weight 10), "b" (weight 1) and "c"
+    /// (weight 5); the acceptor accepts "a" and "b" only.
+    #[test]
+    fn generate_cheapest_stops_after_count_candidates() {
+        let err_symbols: &[&str] = &["", "x", "a", "b", "c"];
+        let err_transitions = vec![
+            make_transition(1, 2, 3, 10, 2),
+            make_transition(1, 3, 3, 1, 0),
+            make_transition(1, 4, 3, 5, 0),
+            make_transition(0xFFFFFFFF, 0, 0, 0, 0),
+        ];
+        let acc_symbols: &[&str] = &["", "a", "b"];
+        let acc_transitions = vec![
+            make_transition(1, 1, 2, 0, 1),
+            make_transition(2, 2, 2, 0, 0),
+            make_transition(0xFFFFFFFF, 0, 0, 0, 0),
+        ];
+        let sg = VfstSuggestion::new(
+            WeightedTransducer::from_bytes(&build_vfst(err_symbols, &err_transitions)).unwrap(),
+            WeightedTransducer::from_bytes(&build_vfst(acc_symbols, &acc_transitions)).unwrap(),
+        );
+        let word: Vec<char> = "x".chars().collect();
+        let budget = SuggestionBudget::default();
+
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate_cheapest(&mut status, &budget, 1, None);
+        let words: Vec<&str> = status
+            .suggestions()
+            .iter()
+            .map(|s| s.word.as_str())
+            .collect();
+        assert_eq!(words, ["b"]);
+
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate_cheapest(&mut status, &budget, 5, None);
+        let weights: Vec<(&str, i32)> = status
+            .suggestions()
+            .iter()
+            .map(|s| (s.word.as_str(), s.fst_weight))
+            .collect();
+        assert_eq!(weights, [("b", 1), ("a", 10)]);
+
+        // The same candidates as the depth-first traversal
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate(&mut status);
+        assert_eq!(status.suggestion_count(), 2);
+    }
+
+    /// A generated edit-distance model finds "ab", the only word of the
+    /// acceptor, one edit away from each misspelling.
+    #[test]
+    fn generate_with_edit_distance_model() {
+        let acc_transitions = vec![
+            make_transition(1, 1, 1, 2, 0),
+            make_transition(2, 2, 2, 0, 0),
+            make_transition(0xFFFFFFFF, 0, 0, 0, 0),
+        ];
+        let acceptor =
+            WeightedTransducer::from_bytes(&build_vfst(&["", "a", "b"], &acc_transitions)).unwrap();
+        let mut model = EditDistanceModel::new("ab".chars());
+        model.transposition_weight = Some(4);
+        let sg = VfstSuggestion::with_edit_distance(&model, acceptor).unwrap();
+
+        // Transposition, insertion, deletion and two edits
+        for (misspelled, weight) in [("ba", 6), ("b", 12), ("abb", 12), ("bb", 12), ("bba", 16)] {
+            let word: Vec<char> = misspelled.chars().collect();
+            let mut status = SuggestionStatus::new(&word, 10);
+            sg.generate(&mut status);
+            let found: Vec<(&str, i32)> = status
+                .suggestions()
+                .iter()
+                .map(|s| (s.word.as_str(), s.fst_weight))
+                .collect();
+            assert_eq!(found, [("ab", weight)], "{misspelled}");
+        }
+
+        // Three edits are too many
+        let word: Vec<char> = "bbba".chars().collect();
+        let mut status = SuggestionStatus::new(&word, 10);
+        sg.generate(&mut status);
+        assert_eq!(status.suggestion_count(), 0);
+    }
+
     /// Error model produces a candidate that the acceptor rejects.
     /// Expected: no suggestions.
     #[test]
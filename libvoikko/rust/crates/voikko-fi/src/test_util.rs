@@ -0,0 +1,29 @@
+// Dictionaries and handles shared by the unit tests.
+
+use voikko_fst::compile::compile_unweighted;
+
+/// An unweighted VFST header followed by `body`, for data that the
+/// compiler does not produce, such as a truncated symbol table.
+pub(crate) fn vfst(body: &[u8]) -> Vec<u8> {
+    let mut data = compile_unweighted([("", "")]).unwrap();
+    data.truncate(voikko_fst::format::HEADER_SIZE);
+    data.extend_from_slice(body);
+    data
+}
+
+/// A dictionary that knows no words: it accepts only the empty input.
+pub(crate) fn empty_vfst() -> Vec<u8> {
+    compile_unweighted([("", "")]).unwrap()
+}
+
+/// A dictionary that knows only `word`, as a noun.
+#[cfg(feature = "handle")]
+pub(crate) fn word_vfst(word: &str) -> Vec<u8> {
+    compile_unweighted([(word, format!("[Ln]{word}").as_str())]).unwrap()
+}
+
+/// A handle whose dictionary knows no words.
+#[cfg(feature = "handle")]
+pub(crate) fn empty_handle() -> crate::handle::VoikkoHandle {
+    crate::handle::VoikkoHandle::from_bytes(&empty_vfst(), None, "fi").unwrap()
+}
@@ -0,0 +1,260 @@
+// Incremental re-tokenization for editor integrations
+//
+// A token produced by `next_token` depends only on the text from its start
+// position onwards, so after an edit the token list changes only between
+// the last token that cannot see the edit and the first token boundary after
+// the edit where the old and new tokenizations line up again. Looking for
+// URLs and email addresses, the tokenizer may read past the end of a word up
+// to the next whitespace, so re-tokenization starts after a whitespace token.
+
+use voikko_core::enums::TokenType;
+use voikko_core::token::Token;
+
+use super::next_token;
+
+/// URLs and email addresses are only recognized when at least this many
+/// characters remain in the text, so a token near the end of the text may
+/// change when the length of the text does.
+const MIN_REMAINING_CHARS: usize = 12;
+
+/// An edit of a text: the characters `start..end` are replaced by `text`.
+///
+/// Positions are character offsets into the text before the edit, as in
+/// [`Token::pos`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// First replaced character.
+    pub start: usize,
+    /// End of the replaced range (exclusive).
+    pub end: usize,
+    /// Replacement text.
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Create an edit replacing the characters `start..end` with `text`.
+    pub fn new(start: usize, end: usize, text: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+}
+
+/// The tokens that changed after an edit, as returned by [`retokenize`].
+///
+/// The tokens `start..start + removed` of the previous list are replaced by
+/// `tokens`. The tokens after the replaced range are unchanged except for
+/// their positions, which move by the deltas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenChange {
+    /// Index of the first replaced token in the previous token list.
+    pub start: usize,
+    /// Number of replaced tokens.
+    pub removed: usize,
+    /// New tokens, positioned in the edited text.
+    pub tokens: Vec<Token>,
+    /// Change in the character positions of the following tokens.
+    pub char_delta: isize,
+    /// Change in the UTF-8 byte offsets of the following tokens.
+    pub byte_delta: isize,
+    /// Change in the UTF-16 offsets of the following tokens.
+    pub utf16_delta: isize,
+}
+
+impl TokenChange {
+    /// Apply the change to the previous token list, making it the token
+    /// list of the edited text.
+    pub fn apply(&self, tokens: &mut Vec<Token>) {
+        let end = self.start + self.removed;
+        tokens.splice(self.start..end, self.tokens.iter().cloned());
+        for token in &mut tokens[self.start + self.tokens.len()..] {
+            token.pos = token.pos.saturating_add_signed(self.char_delta);
+            token.start_byte = token.start_byte.saturating_add_signed(self.byte_delta);
+            token.end_byte = token.end_byte.saturating_add_signed(self.byte_delta);
+            token.start_utf16 = token.start_utf16.saturating_add_signed(self.utf16_delta);
+            token.end_utf16 = token.end_utf16.saturating_add_signed(self.utf16_delta);
+        }
+    }
+}
+
+/// Re-tokenize the text of `previous` after `edit`.
+///
+/// `previous` must be the complete token list of the text before the edit,
+/// as returned by [`tokenize`](super::tokenize); the text is reconstructed
+/// from it. Only the tokens around the edit are tokenized again, and only
+/// those that differ are returned. Edit positions past the end of the text
+/// are clamped to it.
+pub fn retokenize(previous: &[Token], edit: &TextEdit) -> TokenChange {
+    let text_len = previous.last().map_or(0, |t| t.pos + t.token_len);
+    let edit_end = edit.end.min(text_len);
+    let edit_start = edit.start.min(edit_end);
+    let inserted: Vec<char> = edit.text.chars().collect();
+    let shorter_len = text_len.min(text_len - (edit_end - edit_start) + inserted.len());
+
+    // Tokens up to a whitespace token that ends before the edit are unaffected
+    let before_edit = previous.partition_point(|t| {
+        t.pos + t.token_len < edit_start && t.pos + MIN_REMAINING_CHARS <= shorter_len
+    });
+    let start = previous[..before_edit]
+        .iter()
+        .rposition(|t| t.token_type == TokenType::Whitespace)
+        .map_or(0, |i| i + 1);
+    let (restart_pos, mut byte_pos, mut utf16_pos) = match previous.get(start) {
+        Some(t) => (t.pos, t.start_byte, t.start_utf16),
+        None => previous
+            .last()
+            .map_or((0, 0, 0), |t| (text_len, t.end_byte, t.end_utf16)),
+    };
+
+    // Edited text from the restart position to the end
+    let mut text: Vec<char> = Vec::with_capacity(text_len - restart_pos + inserted.len());
+    let mut old_pos = restart_pos;
+    for token in &previous[start..] {
+        for c in token.text.chars() {
+            if old_pos == edit_start {
+                text.extend_from_slice(&inserted);
+            }
+            if old_pos < edit_start || old_pos >= edit_end {
+                text.push(c);
+            }
+            old_pos += 1;
+        }
+    }
+    if old_pos == edit_start {
+        text.extend_from_slice(&inserted);
+    }
+
+    let char_delta = inserted.len() as isize - (edit_end - edit_start) as isize;
+    let new_edit_end = edit_start + inserted.len();
+    let mut tokens = Vec::new();
+    let mut old_index = start;
+    let mut i = 0;
+    while i < text.len() {
+        let pos = restart_pos + i;
+        if pos >= new_edit_end {
+            // Past the edit, the rest is unchanged from an old token boundary on
+            let old = pos.wrapping_add_signed(-char_delta);
+            while old_index < previous.len() && previous[old_index].pos < old {
+                old_index += 1;
+            }
+            if let Some(token) = previous.get(old_index).filter(|t| t.pos == old) {
+                return TokenChange {
+                    start,
+                    removed: old_index - start,
+                    tokens,
+                    char_delta,
+                    byte_delta: byte_pos as isize - token.start_byte as isize,
+                    utf16_delta: utf16_pos as isize - token.start_utf16 as isize,
+                };
+            }
+        }
+        let (token_type, token_len) = next_token(&text, text.len(), i);
+        if token_type == TokenType::None || token_len == 0 {
+            break;
+        }
+        let token_text: String = text[i..i + token_len].iter().collect();
+        let token = Token::new(token_type, token_text, pos).at_offsets(byte_pos, utf16_pos);
+        byte_pos = token.end_byte;
+        utf16_pos = token.end_utf16;
+        tokens.push(token);
+        i += token_len;
+    }
+
+    TokenChange {
+        start,
+        removed: previous.len() - start,
+        tokens,
+        char_delta,
+        byte_delta: 0,
+        utf16_delta: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenize;
+
+    /// Apply `edit` to `before` and check the result against tokenizing the
+    /// edited text from scratch. Returns the change.
+    fn check_edit(before: &str, edit: TextEdit) -> TokenChange {
+        let chars: Vec<char> = before.chars().collect();
+        let after: String = chars[..edit.start]
+            .iter()
+            .chain(edit.text.chars().collect::<Vec<_>>().iter())
+            .chain(chars[edit.end..].iter())
+            .collect();
+        let mut tokens = tokenize(before);
+        let change = retokenize(&tokens, &edit);
+        change.apply(&mut tokens);
+        assert_eq!(tokens, tokenize(&after), "{before:?} -> {after:?}");
+        change
+    }
+
+    #[test]
+    fn insertion_changes_only_the_edited_word() {
+        let change = check_edit(
+            "Koira juoksi pihalla ja kissa istui puussa.",
+            TextEdit::new(8, 8, "o"),
+        );
+        assert_eq!(change.tokens.len(), 1);
+        assert_eq!(change.tokens[0].text, "juooksi");
+        assert_eq!(change.removed, 1);
+        assert_eq!(change.char_delta, 1);
+    }
+
+    #[test]
+    fn edits_that_split_and_join_tokens() {
+        let text = "Koira juoksi pihalla ja kissa istui puussa.";
+        check_edit(text, TextEdit::new(5, 6, ""));
+        check_edit(text, TextEdit::new(2, 2, " "));
+        check_edit(text, TextEdit::new(0, 43, "Uusi teksti"));
+        check_edit(text, TextEdit::new(42, 43, "!"));
+        check_edit(text, TextEdit::new(43, 43, " Lisää."));
+        check_edit(text, TextEdit::new(0, 0, "- "));
+        check_edit(text, TextEdit::new(24, 29, "http://www.example.com"));
+    }
+
+    #[test]
+    fn edits_with_multibyte_characters() {
+        let text = "Äiti söi \u{1D7CE} omenaa, ja isä myös.";
+        let change = check_edit(text, TextEdit::new(5, 8, "soi"));
+        assert_eq!(change.byte_delta, -1);
+        check_edit(text, TextEdit::new(9, 10, "\u{1D7CF}\u{1D7D0}"));
+        check_edit(text, TextEdit::new(10, 10, "x"));
+    }
+
+    #[test]
+    fn edits_seen_by_tokenizer_lookahead() {
+        // The apostrophe joins the words only when followed by a letter
+        check_edit("vaa' an vaaka", TextEdit::new(4, 5, ""));
+        check_edit("a.b c", TextEdit::new(2, 3, " "));
+        check_edit("a@b.fi x", TextEdit::new(6, 8, ""));
+        // Completing an email address joins the tokens before the edit
+        check_edit("pitkäsanainenkoira@esim x", TextEdit::new(23, 23, ".fi"));
+    }
+
+    #[test]
+    fn every_edit_of_a_sample_matches_full_tokenization() {
+        let text = "Ks. s. 1,5 - vaa'an a@b.fi; http://x.fi/a";
+        let len = text.chars().count();
+        for start in 0..=len {
+            for end in start..=len {
+                for replacement in ["", " ", "x", ".", "'a", "-"] {
+                    check_edit(text, TextEdit::new(start, end, replacement));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_text_and_clamped_edits() {
+        check_edit("", TextEdit::new(0, 0, "koira"));
+        check_edit("koira", TextEdit::new(0, 5, ""));
+        let mut tokens = tokenize("koira");
+        retokenize(&tokens, &TextEdit::new(10, 20, " kissa")).apply(&mut tokens);
+        assert_eq!(tokens, tokenize("koira kissa"));
+    }
+}
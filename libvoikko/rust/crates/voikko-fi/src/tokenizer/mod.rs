@@ -3,11 +3,16 @@
 
 use voikko_core::character::{CharType, get_char_type, is_finnish_quotation_mark};
 use voikko_core::enums::{SentenceType, TokenType};
+use voikko_core::token::Token;
+
+mod incremental;
+
+pub use incremental::{TextEdit, TokenChange, retokenize};
 
 /// Callback type for spell-checking a word (used in sentence detection for
 /// abbreviation recognition). Returns `true` if the word is a valid word
 /// in the dictionary.
-type SpellCheckFn<'a> = Option<&'a dyn Fn(&[char]) -> bool>;
+pub type SpellCheckFn<'a> = Option<&'a dyn Fn(&[char]) -> bool>;
 
 // ============================================================================
 // URL / Email detection
@@ -63,7 +68,6 @@ fn find_url_or_email(text: &[char]) -> usize {
     let start = if is_https { 8 } else { 7 };
     for i in start..textlen {
         match get_char_type(text[i]) {
-            CharType::Whitespace => return i,
             CharType::Unknown => {
                 if !is_url_unknown_char(text[i]) {
                     return i;
@@ -80,6 +84,8 @@ fn find_url_or_email(text: &[char]) -> usize {
                 }
                 // All other punctuation is allowed inside URLs.
             }
+            // Whitespace (or a character type added later) ends the URL.
+            _ => return i,
         }
     }
     textlen
@@ -100,12 +106,6 @@ fn find_email(text: &[char]) -> usize {
 
     for i in 0..textlen {
         match get_char_type(text[i]) {
-            CharType::Whitespace => {
-                if found_at && found_dot {
-                    return i;
-                }
-                return 0;
-            }
             CharType::Unknown => {
                 if text[i] == '@' {
                     if found_at {
@@ -136,6 +136,13 @@ fn find_email(text: &[char]) -> usize {
                     return 0;
                 }
             }
+            // Whitespace (or a character type added later) ends the address.
+            _ => {
+                if found_at && found_dot {
+                    return i;
+                }
+                return 0;
+            }
         }
     }
 
@@ -189,9 +196,6 @@ fn word_length(text: &[char], ignore_dot: bool) -> usize {
                 processing_number = true;
                 wlen += 1;
             }
-            CharType::Whitespace | CharType::Unknown => {
-                return wlen;
-            }
             CharType::Punctuation => {
                 match text[wlen] {
                     // Apostrophe, right single quotation mark, colon:
@@ -219,15 +223,16 @@ fn word_length(text: &[char], ignore_dot: bool) -> usize {
                             CharType::Letter | CharType::Digit => {
                                 wlen += 1;
                             }
-                            CharType::Whitespace | CharType::Unknown => {
-                                return wlen + 1;
-                            }
                             CharType::Punctuation => {
                                 if text[wlen + 1] == ',' {
                                     return wlen + 1;
                                 }
                                 return wlen;
                             }
+                            // Whitespace, Unknown (or a later character type).
+                            _ => {
+                                return wlen + 1;
+                            }
                         }
                     }
 
@@ -248,7 +253,9 @@ fn word_length(text: &[char], ignore_dot: bool) -> usize {
                                 }
                                 wlen += 1;
                             }
-                            CharType::Whitespace | CharType::Unknown | CharType::Punctuation => {
+                            // Whitespace, Unknown, Punctuation (or a later
+                            // character type).
+                            _ => {
                                 return wlen + adot;
                             }
                         }
@@ -276,6 +283,10 @@ fn word_length(text: &[char], ignore_dot: bool) -> usize {
                     }
                 }
             }
+            // Whitespace, Unknown (or a character type added later).
+            _ => {
+                return wlen;
+            }
         }
     }
     textlen
@@ -350,10 +361,39 @@ pub fn next_token_with_options(
 
             (TokenType::Punctuation, 1)
         }
-        CharType::Unknown => (TokenType::Unknown, 1),
+        // Unknown (or a character type added later).
+        _ => (TokenType::Unknown, 1),
     }
 }
 
+/// Split `text` into tokens.
+///
+/// Each token carries its character position plus its UTF-8 byte and UTF-16
+/// code unit ranges, all computed in the same pass.
+///
+/// Origin: voikkoNextTokenCstr (looped over the whole text)
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let text_chars: Vec<char> = text.chars().collect();
+    let text_len = text_chars.len();
+    let mut result = Vec::new();
+    let mut pos = 0;
+    let mut byte_pos = 0;
+    let mut utf16_pos = 0;
+    while pos < text_len {
+        let (token_type, token_len) = next_token(&text_chars, text_len, pos);
+        if token_type == TokenType::None || token_len == 0 {
+            break;
+        }
+        let token_text: String = text_chars[pos..pos + token_len].iter().collect();
+        let token = Token::new(token_type, token_text, pos).at_offsets(byte_pos, utf16_pos);
+        byte_pos = token.end_byte;
+        utf16_pos = token.end_utf16;
+        result.push(token);
+        pos += token_len;
+    }
+    result
+}
+
 // ============================================================================
 // Sentence detection
 // Origin: Sentence.cpp:38-142 (Sentence::next)
@@ -410,6 +450,10 @@ fn dot_part_of_word(text: &[char], spell_check: SpellCheckFn<'_>) -> bool {
 /// from `pos` to the end of the sentence (including trailing whitespace up
 /// to but not including the next sentence's first token).
 ///
+/// A line break is whitespace like a space: it does not end a sentence, a
+/// hard-wrapped sentence continues on the next line, and it counts as one
+/// character in the returned length (`\r\n` as two).
+///
 /// This version uses heuristic-only abbreviation detection (initials and
 /// ordinal numbers). Use `next_sentence_with_speller` for speller-backed
 /// abbreviation detection, or `next_sentence_with_spell_check` for a
@@ -453,6 +497,30 @@ pub fn next_sentence_with_spell_check(
     text_len: usize,
     pos: usize,
     spell_check: SpellCheckFn<'_>,
+) -> (SentenceType, usize) {
+    next_sentence_with_options(text, text_len, pos, spell_check, false)
+}
+
+/// Return whether `c` closes a quotation or a parenthetical.
+fn is_closing_punctuation(c: char) -> bool {
+    is_finnish_quotation_mark(c) || matches!(c, ')' | ']' | '}' | '\'' | '\u{2019}')
+}
+
+/// Find the next sentence boundary, with explicit control over closing
+/// punctuation.
+///
+/// If `include_closing` is true, closing quotation marks and brackets that
+/// directly follow the terminal punctuation (`?”`, `.)`, `!’”`) end the
+/// sentence with it, as typographic convention has it. Otherwise the
+/// boundary is placed before them, as in libvoikko.
+///
+/// Origin: Sentence.cpp:72-142 (Sentence::next)
+pub fn next_sentence_with_options(
+    text: &[char],
+    text_len: usize,
+    pos: usize,
+    spell_check: SpellCheckFn<'_>,
+    include_closing: bool,
 ) -> (SentenceType, usize) {
     let remaining = text_len.saturating_sub(pos);
     if remaining == 0 {
@@ -485,7 +553,19 @@ pub fn next_sentence_with_spell_check(
         }
 
         if end_found && !in_quotation {
-            if token != TokenType::Whitespace {
+            if include_closing
+                && token == TokenType::Punctuation
+                && previous_token_type == TokenType::Punctuation
+                && is_closing_punctuation(slice[slen])
+            {
+                // Closing mark of a quotation or parenthetical that started
+                // before this sentence; as with a closing quote above, a
+                // comma right after it means the sentence goes on.
+                if slen + 1 < remaining && slice[slen + 1] == ',' {
+                    end_found = false;
+                    possible_end_punctuation = false;
+                }
+            } else if token != TokenType::Whitespace {
                 // Sentence boundary found. Determine type.
                 let stype = if end_dotword
                     || possible_end_punctuation
@@ -1179,6 +1259,65 @@ mod tests {
         assert!(sentence_text.contains("l\u{00E4}hti."));
     }
 
+    /// Split `s` into sentence texts, including closing punctuation.
+    fn split_with_closing(s: &str) -> Vec<String> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut sentences = Vec::new();
+        let mut pos = 0;
+        while pos < chars.len() {
+            let (_, slen) = next_sentence_with_options(&chars, chars.len(), pos, None, true);
+            sentences.push(chars[pos..pos + slen].iter().collect());
+            pos += slen;
+        }
+        sentences
+    }
+
+    #[test]
+    fn closing_punctuation_ends_the_sentence() {
+        assert_eq!(
+            split_with_closing("Hän lähti (vihdoin.) Sitten satoi."),
+            ["Hän lähti (vihdoin.) ", "Sitten satoi."]
+        );
+        // The quotation opened before this sentence
+        assert_eq!(
+            split_with_closing("Nyt heti!\u{201D} Hän huusi."),
+            ["Nyt heti!\u{201D} ", "Hän huusi."]
+        );
+        assert_eq!(
+            split_with_closing("Tule tänne?» Ei."),
+            ["Tule tänne?» ", "Ei."]
+        );
+        // Without the option the boundary stays before the closing mark
+        let chars: Vec<char> = "Hän lähti (vihdoin.) Sitten.".chars().collect();
+        let (_, slen) = next_sentence(&chars, chars.len(), 0);
+        assert_eq!(slen, 19);
+    }
+
+    #[test]
+    fn nested_closing_punctuation_ends_the_sentence() {
+        assert_eq!(
+            split_with_closing("Hän sanoi ’ei käy.’\u{201D} Sitten hän lähti."),
+            ["Hän sanoi ’ei käy.’\u{201D} ", "Sitten hän lähti."]
+        );
+        assert_eq!(
+            split_with_closing("(Katso kuva [liite 2].)) Seuraava."),
+            ["(Katso kuva [liite 2].)) ", "Seuraava."]
+        );
+        // An opening quote after whitespace starts the next sentence
+        assert_eq!(
+            split_with_closing("Hän tuli. \"Moi\", hän sanoi."),
+            ["Hän tuli. ", "\"Moi\", hän sanoi."]
+        );
+    }
+
+    #[test]
+    fn closing_punctuation_followed_by_comma_continues() {
+        assert_eq!(
+            split_with_closing("Mitä?\u{201D}, hän kysyi. Ei mitään."),
+            ["Mitä?\u{201D}, hän kysyi. ", "Ei mitään."]
+        );
+    }
+
     #[test]
     fn consecutive_sentences() {
         // Parse two consecutive sentences.
@@ -1335,6 +1474,21 @@ mod tests {
         assert_eq!(sentence_text, "Toinen. ");
     }
 
+    #[test]
+    fn sentence_continues_over_line_break() {
+        let s = "Kissa istuu\npuussa. Koira\r\nhaukkuu.\nLintu";
+        let chars: Vec<char> = s.chars().collect();
+
+        let (stype1, slen1) = next_sentence(&chars, chars.len(), 0);
+        assert_eq!(stype1, SentenceType::Probable);
+        assert_eq!(slen1, "Kissa istuu\npuussa. ".chars().count());
+
+        let (stype2, slen2) = next_sentence(&chars, chars.len(), slen1);
+        assert_eq!(stype2, SentenceType::Probable);
+        let sentence_text: String = chars[slen1..slen1 + slen2].iter().collect();
+        assert_eq!(sentence_text, "Koira\r\nhaukkuu.\n");
+    }
+
     #[test]
     fn complex_sentence_with_mixed_punctuation() {
         let s = "Hei! Miten menee? Hyvin, kiitos. Ja sinulla?";
@@ -1449,4 +1603,30 @@ mod tests {
         let first_text: String = chars[..slen].iter().collect();
         assert_eq!(first_text, "Koira juoksi. ");
     }
+
+    #[test]
+    fn tokenize_reports_byte_and_utf16_offsets() {
+        // "ä" is 2 bytes / 1 unit, "𝟐" is 4 bytes / 2 units.
+        let text = "Sää 𝟐 kertaa.";
+        let tokens = tokenize(text);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["Sää", " ", "𝟐", " ", "kertaa", "."]);
+
+        for t in &tokens {
+            assert_eq!(&text[t.start_byte..t.end_byte], t.text);
+        }
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        for t in &tokens {
+            let units: Vec<u16> = t.text.encode_utf16().collect();
+            assert_eq!(&utf16[t.start_utf16..t.end_utf16], units.as_slice());
+        }
+        assert_eq!(
+            (tokens[2].pos, tokens[2].start_byte, tokens[2].start_utf16),
+            (4, 6, 4)
+        );
+        assert_eq!(
+            (tokens[4].pos, tokens[4].start_byte, tokens[4].start_utf16),
+            (6, 11, 7)
+        );
+    }
 }
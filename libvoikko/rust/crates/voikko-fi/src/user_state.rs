@@ -0,0 +1,287 @@
+// User personalization state: custom words, ignored words and corrections.
+//
+// The state is kept separate from the dictionary data so that applications
+// can persist it and sync it across devices. It serializes to a small,
+// line-oriented UTF-8 text blob:
+//
+//   voikko-user-state 1
+//   W<TAB>word          (user dictionary entry)
+//   W<TAB>word<TAB>hint (user dictionary entry with an inflection class hint)
+//   I<TAB>word          (ignored word)
+//   C<TAB>from<TAB>to   (accepted correction)
+//
+// Backslash, tab, carriage return and newline inside values are escaped
+// as `\\`, `\t`, `\r` and `\n`. Entries are written in sorted order so
+// that exporting the same state always produces the same blob.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::speller::user_dictionary::{UserDictionary, parse_entry, push_escaped, unescape};
+
+/// Header line identifying the blob format and its version.
+const HEADER_PREFIX: &str = "voikko-user-state ";
+
+/// Current blob format version.
+pub const USER_STATE_VERSION: u32 = 1;
+
+/// Error type for user state import failures.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum UserStateError {
+    /// The blob does not start with the expected header line.
+    #[error("missing user state header")]
+    MissingHeader,
+
+    /// The blob was written by a newer, unsupported format version.
+    #[error("unsupported user state version: {0}")]
+    UnsupportedVersion(String),
+
+    /// A line could not be parsed. `line` is 1-based.
+    #[error("invalid user state entry on line {line}: {reason}")]
+    InvalidEntry { line: usize, reason: String },
+}
+
+/// Personalization state of a handle.
+///
+/// - `user_words`: words accepted by the spell checker in addition to the
+///   dictionary, with optional inflection class hints.
+/// - `ignored_words`: words the user chose to ignore (also accepted by the
+///   spell checker, but kept apart so applications can present them
+///   differently).
+/// - `corrections`: misspelling -> replacement pairs accepted by the user.
+///   The replacement is offered as the first suggestion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserState {
+    pub user_words: UserDictionary,
+    pub ignored_words: BTreeSet<String>,
+    pub corrections: BTreeMap<String, String>,
+}
+
+impl UserState {
+    /// Create an empty user state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `true` if the state has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.user_words.is_empty() && self.ignored_words.is_empty() && self.corrections.is_empty()
+    }
+
+    /// Return `true` if `word` is a user word or an ignored word.
+    ///
+    /// An entry written entirely in lowercase also accepts the word with
+    /// other capitalization (e.g. the entry "voikko" accepts "Voikko" and
+    /// "VOIKKO"), mirroring how dictionary words are accepted.
+    pub fn accepts(&self, word: &str) -> bool {
+        if self.user_words.accepts(word) || self.ignored_words.contains(word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        lower != word && self.ignored_words.contains(&lower)
+    }
+
+    /// Return the accepted correction for `word`, if any.
+    pub fn correction(&self, word: &str) -> Option<&str> {
+        self.corrections.get(word).map(String::as_str)
+    }
+
+    /// Add all entries of `other` to this state. Corrections in `other`
+    /// replace existing corrections for the same word.
+    pub fn merge(&mut self, other: UserState) {
+        self.user_words.merge(other.user_words);
+        self.ignored_words.extend(other.ignored_words);
+        self.corrections.extend(other.corrections);
+    }
+
+    /// Serialize the state into a text blob.
+    pub fn to_blob(&self) -> String {
+        let mut out = format!("{HEADER_PREFIX}{USER_STATE_VERSION}\n");
+        for (word, hint) in self.user_words.iter() {
+            out.push_str("W\t");
+            push_escaped(&mut out, word);
+            if let Some(hint) = hint {
+                out.push('\t');
+                push_escaped(&mut out, hint);
+            }
+            out.push('\n');
+        }
+        for word in &self.ignored_words {
+            out.push_str("I\t");
+            push_escaped(&mut out, word);
+            out.push('\n');
+        }
+        for (from, to) in &self.corrections {
+            out.push_str("C\t");
+            push_escaped(&mut out, from);
+            out.push('\t');
+            push_escaped(&mut out, to);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse a text blob produced by [`UserState::to_blob`].
+    ///
+    /// Empty lines are skipped. Unknown entry kinds are rejected so that a
+    /// blob from a newer writer is never silently truncated.
+    pub fn from_blob(blob: &str) -> Result<Self, UserStateError> {
+        let mut lines = blob.lines().enumerate();
+
+        let header = lines
+            .next()
+            .map(|(_, l)| l.trim_end_matches('\r'))
+            .ok_or(UserStateError::MissingHeader)?;
+        let version = header
+            .strip_prefix(HEADER_PREFIX)
+            .ok_or(UserStateError::MissingHeader)?;
+        if version.parse::<u32>().ok() != Some(USER_STATE_VERSION) {
+            return Err(UserStateError::UnsupportedVersion(version.to_string()));
+        }
+
+        let mut state = UserState::new();
+        for (idx, line) in lines {
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |reason: &str| UserStateError::InvalidEntry {
+                line: idx + 1,
+                reason: reason.to_string(),
+            };
+            if let Some(entry) = line.strip_prefix("W\t") {
+                let (word, hint) = parse_entry(entry.split('\t')).map_err(invalid)?;
+                state.user_words.add(&word, hint.as_deref());
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                ["I", word] => {
+                    state
+                        .ignored_words
+                        .insert(unescape(word).ok_or_else(|| invalid("bad escape"))?);
+                }
+                ["C", from, to] => {
+                    let from = unescape(from).ok_or_else(|| invalid("bad escape"))?;
+                    let to = unescape(to).ok_or_else(|| invalid("bad escape"))?;
+                    state.corrections.insert(from, to);
+                }
+                _ => return Err(invalid("unrecognized entry")),
+            }
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> UserState {
+        let mut state = UserState::new();
+        state.user_words.add("voikko", None);
+        state.user_words.add("Kotus", None);
+        state.ignored_words.insert("xyzzy".to_string());
+        state
+            .corrections
+            .insert("koirra".to_string(), "koira".to_string());
+        state
+    }
+
+    #[test]
+    fn empty_state_roundtrip() {
+        let state = UserState::new();
+        assert!(state.is_empty());
+        let blob = state.to_blob();
+        assert_eq!(blob, "voikko-user-state 1\n");
+        assert_eq!(UserState::from_blob(&blob).unwrap(), state);
+    }
+
+    #[test]
+    fn roundtrip_preserves_all_entries() {
+        let state = sample();
+        let restored = UserState::from_blob(&state.to_blob()).unwrap();
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let a = sample();
+        let mut b = UserState::new();
+        b.corrections
+            .insert("koirra".to_string(), "koira".to_string());
+        b.ignored_words.insert("xyzzy".to_string());
+        b.user_words.add("Kotus", None);
+        b.user_words.add("voikko", None);
+        assert_eq!(a.to_blob(), b.to_blob());
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        let mut state = UserState::new();
+        state.user_words.add("a\tb\\c\nd\re", None);
+        let blob = state.to_blob();
+        assert_eq!(blob.lines().count(), 2);
+        assert_eq!(UserState::from_blob(&blob).unwrap(), state);
+    }
+
+    #[test]
+    fn crlf_blob_is_accepted() {
+        let blob = "voikko-user-state 1\r\nW\tvoikko\r\n\r\nC\tkoirra\tkoira\r\n";
+        let state = UserState::from_blob(blob).unwrap();
+        assert!(state.user_words.contains("voikko"));
+        assert_eq!(state.correction("koirra"), Some("koira"));
+    }
+
+    #[test]
+    fn missing_header_is_rejected() {
+        assert_eq!(UserState::from_blob(""), Err(UserStateError::MissingHeader));
+        assert_eq!(
+            UserState::from_blob("W\tvoikko\n"),
+            Err(UserStateError::MissingHeader)
+        );
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        assert_eq!(
+            UserState::from_blob("voikko-user-state 2\n"),
+            Err(UserStateError::UnsupportedVersion("2".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        let err = UserState::from_blob("voikko-user-state 1\nW\tok\nX\tbad\n").unwrap_err();
+        assert!(matches!(err, UserStateError::InvalidEntry { line: 3, .. }));
+        let err = UserState::from_blob("voikko-user-state 1\nC\tonly-one\n").unwrap_err();
+        assert!(matches!(err, UserStateError::InvalidEntry { line: 2, .. }));
+        let err = UserState::from_blob("voikko-user-state 1\nW\tbad\\q\n").unwrap_err();
+        assert!(matches!(err, UserStateError::InvalidEntry { line: 2, .. }));
+    }
+
+    #[test]
+    fn accepts_user_and_ignored_words() {
+        let state = sample();
+        assert!(state.accepts("voikko"));
+        assert!(state.accepts("Voikko"));
+        assert!(state.accepts("VOIKKO"));
+        assert!(state.accepts("Kotus"));
+        assert!(!state.accepts("kotus"));
+        assert!(state.accepts("xyzzy"));
+        assert!(!state.accepts("koira"));
+    }
+
+    #[test]
+    fn merge_adds_entries_and_overrides_corrections() {
+        let mut state = sample();
+        let mut other = UserState::new();
+        other.user_words.add("uusi", None);
+        other
+            .corrections
+            .insert("koirra".to_string(), "koiraa".to_string());
+        state.merge(other);
+        assert!(state.user_words.contains("uusi"));
+        assert!(state.user_words.contains("voikko"));
+        assert_eq!(state.correction("koirra"), Some("koiraa"));
+    }
+}
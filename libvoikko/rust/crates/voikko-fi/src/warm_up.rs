@@ -0,0 +1,258 @@
+// Pre-populating the caches with the words checked first
+//
+// The first checks after startup are slow: the speller and analysis caches
+// are empty, and the pages of the transducer the words need may not have
+// been read yet. An application can spell and analyze the words it expects
+// to see (or a built-in list of frequent Finnish word forms) while it shows
+// a splash screen, so that the first checks of them are answered from the
+// caches. The words can be split among scoped threads sharing the handle;
+// a word whose cache shard is busy at that moment may be left out.
+
+use std::thread;
+
+use crate::handle::VoikkoHandle;
+
+/// Word lists shorter than this many words per thread are warmed up on the
+/// calling thread, as spawning would cost more than it saves.
+const MIN_WORDS_PER_THREAD: usize = 32;
+
+/// Frequent Finnish word forms, for [`VoikkoHandle::warm_up_default`].
+pub(crate) const FREQUENT_WORDS: &[&str] = &[
+    "ja",
+    "on",
+    "ei",
+    "se",
+    "että",
+    "hän",
+    "oli",
+    "ovat",
+    "mutta",
+    "kun",
+    "niin",
+    "joka",
+    "myös",
+    "tai",
+    "jos",
+    "sen",
+    "ne",
+    "kuin",
+    "voi",
+    "ole",
+    "ollut",
+    "olla",
+    "vain",
+    "sitä",
+    "mukaan",
+    "mitä",
+    "tämä",
+    "nyt",
+    "jo",
+    "siitä",
+    "jälkeen",
+    "sekä",
+    "hänen",
+    "kanssa",
+    "kaikki",
+    "vielä",
+    "tässä",
+    "tämän",
+    "mikä",
+    "noin",
+    "kuitenkin",
+    "he",
+    "me",
+    "te",
+    "minä",
+    "sinä",
+    "jossa",
+    "joiden",
+    "jonka",
+    "koska",
+    "sitten",
+    "aina",
+    "sillä",
+    "vuoden",
+    "vuonna",
+    "vuotta",
+    "paljon",
+    "hyvin",
+    "enemmän",
+    "nämä",
+    "niiden",
+    "siihen",
+    "pitää",
+    "saada",
+    "tulee",
+    "tulla",
+    "tehdä",
+    "tehty",
+    "sanoi",
+    "sanoo",
+    "ennen",
+    "aikana",
+    "yli",
+    "alle",
+    "kautta",
+    "vastaan",
+    "lisäksi",
+    "ehkä",
+    "eikä",
+    "eivät",
+    "emme",
+    "en",
+    "et",
+    "olivat",
+    "olisi",
+    "olen",
+    "olet",
+    "olemme",
+    "olette",
+    "olleet",
+    "ihmiset",
+    "ihmisten",
+    "aika",
+    "aikaa",
+    "päivä",
+    "päivää",
+    "vuosi",
+    "työ",
+    "työtä",
+    "asia",
+    "asiaa",
+    "maa",
+    "maan",
+    "kaupunki",
+    "kaupungin",
+    "suomi",
+    "suomen",
+    "suomalainen",
+    "suomalaiset",
+    "hallitus",
+    "presidentti",
+    "yritys",
+    "yrityksen",
+    "osa",
+    "osaa",
+    "koko",
+    "uusi",
+    "uuden",
+    "suuri",
+    "suuren",
+    "hyvä",
+    "hyvää",
+    "pieni",
+    "toinen",
+    "toisen",
+    "ensimmäinen",
+    "viime",
+    "tänään",
+    "huomenna",
+    "eilen",
+    "täällä",
+    "siellä",
+    "missä",
+    "miten",
+    "miksi",
+    "milloin",
+    "kuka",
+    "kenen",
+    "mihin",
+    "mistä",
+    "kotona",
+    "koulu",
+    "koulun",
+    "talo",
+    "talon",
+    "auto",
+    "auton",
+    "koira",
+    "kissa",
+    "lapsi",
+    "lapset",
+    "lapsen",
+    "äiti",
+    "isä",
+    "mies",
+    "nainen",
+    "ihminen",
+    "vesi",
+    "ruoka",
+    "raha",
+    "rahaa",
+    "kieli",
+    "kielen",
+    "sana",
+    "sanaa",
+    "kirja",
+    "kirjan",
+];
+
+/// Spell and analyze `words` with `handle`, on up to `threads` threads.
+pub(crate) fn warm_up_words(handle: &VoikkoHandle, words: &[&str], threads: usize) {
+    let warm_up = |word: &&str| {
+        handle.spell(word);
+        handle.analyze(word);
+    };
+    let threads = threads.min(words.len() / MIN_WORDS_PER_THREAD).max(1);
+    if threads == 1 {
+        words.iter().for_each(warm_up);
+        return;
+    }
+    let chunk_size = words.len().div_ceil(threads);
+    let warm_up = &warm_up;
+    thread::scope(|scope| {
+        for chunk in words.chunks(chunk_size) {
+            scope.spawn(move || chunk.iter().for_each(warm_up));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::empty_handle;
+
+    #[test]
+    fn frequent_words_are_distinct() {
+        let mut words = FREQUENT_WORDS.to_vec();
+        words.sort_unstable();
+        words.dedup();
+        assert_eq!(words.len(), FREQUENT_WORDS.len());
+    }
+
+    #[test]
+    fn warmed_up_words_are_cached() {
+        let handle = empty_handle();
+        handle.warm_up_default(1).unwrap();
+        assert_eq!(handle.analysis_cache_stats().len, FREQUENT_WORDS.len());
+        let misses = handle.analysis_cache_stats().misses;
+        for word in FREQUENT_WORDS {
+            handle.analyze(word);
+        }
+        assert_eq!(handle.analysis_cache_stats().misses, misses);
+    }
+
+    #[test]
+    fn warm_up_on_several_threads() {
+        let handle = empty_handle();
+        handle.warm_up_words(FREQUENT_WORDS, 4).unwrap();
+        assert!(handle.analysis_cache_stats().len > 0);
+        handle.warm_up_words(&[], 4).unwrap();
+    }
+
+    #[test]
+    #[ignore = "requires mor.vfst dictionary file"]
+    fn integration_frequent_words_are_correct() {
+        let mor_data = std::fs::read(
+            std::env::var("VOIKKO_MOR_VFST").unwrap_or_else(|_| "../../test-data/mor.vfst".into()),
+        )
+        .expect("failed to read mor.vfst");
+        let handle = VoikkoHandle::from_bytes_lazy(&mor_data, None, "fi").unwrap();
+        handle.warm_up_default(4).unwrap();
+        let hits = handle.cache_stats().hits;
+        for word in FREQUENT_WORDS {
+            assert!(handle.spell(word), "{word}");
+        }
+        assert!(handle.cache_stats().hits > hits);
+    }
+}
@@ -0,0 +1,101 @@
+//! Memory envelope of the lite handle profile.
+//!
+//! A counting global allocator records the peak heap use while a handle is
+//! created and used. Both profiles are measured in one test so that no
+//! other test allocates concurrently.
+//!
+//! Run: VOIKKO_MOR_VFST=/path/to/mor.vfst cargo test -p voikko-fi --test lite_profile -- --include-ignored
+
+#![cfg(feature = "handle")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use voikko_fi::handle::{HandleProfile, VoikkoHandle};
+
+/// Heap a lite handle may use on top of the size of `mor.vfst`, the memory
+/// envelope documented in voikko-wasm/CLAUDE.md.
+const LITE_HEAP_OVERHEAD: usize = 2 * 1024 * 1024;
+
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+const TEXT: &str = "Koira juoksi juoksi pihalla ja kissa istui puussa.";
+
+const SYLLABLES: [&str; 20] = [
+    "ka", "ko", "ku", "ki", "ta", "to", "tu", "ti", "sa", "so", "su", "si", "pa", "po", "pu", "pi",
+    "la", "lo", "lu", "li",
+];
+
+/// Heap use above the current use while a `profile` handle is created and
+/// used: the peak, and what the handle holds at the end.
+fn heap_use(mor_data: &[u8], autocorr_data: &[u8], profile: HandleProfile) -> (usize, usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let retained;
+    {
+        let handle =
+            VoikkoHandle::from_bytes_with_profile(mor_data, Some(autocorr_data), "fi", profile)
+                .expect("failed to create VoikkoHandle");
+        assert_eq!(handle.profile(), profile);
+        for word in TEXT.split(|c: char| !c.is_alphabetic()) {
+            if !word.is_empty() {
+                handle.spell(word);
+                handle.analyze(word);
+            }
+        }
+        // Enough distinct short words to fill the speller cache.
+        for a in SYLLABLES {
+            for b in SYLLABLES {
+                for c in SYLLABLES {
+                    handle.spell(&format!("{a}{b}{c}"));
+                }
+            }
+        }
+        handle.suggest("koirra");
+        handle.hyphenate("kissanpentu");
+        let errors = handle.grammar_errors(TEXT);
+        assert_eq!(errors.is_empty(), profile == HandleProfile::Lite);
+        retained = CURRENT.load(Ordering::Relaxed) - base;
+    }
+    (PEAK.load(Ordering::Relaxed) - base, retained)
+}
+
+#[test]
+#[ignore = "requires mor.vfst dictionary file"]
+fn lite_profile_stays_within_memory_envelope() {
+    let path = std::env::var("VOIKKO_MOR_VFST").expect("set VOIKKO_MOR_VFST");
+    let mor_data = std::fs::read(&path).expect("failed to read mor.vfst");
+    let autocorr_path = std::path::Path::new(&path).with_file_name("autocorr.vfst");
+    let autocorr_data = std::fs::read(autocorr_path).expect("failed to read autocorr.vfst");
+
+    // Warm up one-time allocations so that neither measurement pays them.
+    heap_use(&mor_data, &autocorr_data, HandleProfile::Standard);
+    let (lite_peak, lite) = heap_use(&mor_data, &autocorr_data, HandleProfile::Lite);
+    let (standard_peak, standard) = heap_use(&mor_data, &autocorr_data, HandleProfile::Standard);
+    eprintln!("heap: lite {lite} (peak {lite_peak}), standard {standard} (peak {standard_peak})");
+    let limit = mor_data.len() + LITE_HEAP_OVERHEAD;
+    assert!(lite_peak <= limit, "lite peak {lite_peak} exceeds {limit}");
+    assert!(lite < standard, "lite {lite} >= standard {standard}");
+}
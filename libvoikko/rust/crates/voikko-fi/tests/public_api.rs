@@ -0,0 +1,203 @@
+//! Public API snapshot: lists the `pub` items of the crate's public modules
+//! and compares them with `tests/public_api.txt`, so that changes to the
+//! public surface show up in review instead of slipping through.
+//!
+//! The listing is taken from the source text, so it covers every feature
+//! and needs no external tooling. Modules declared `pub(crate)` or
+//! `#[doc(hidden)]`, and test modules, are internal and not listed.
+//!
+//! After an intended API change, regenerate the snapshot:
+//! UPDATE_PUBLIC_API=1 cargo test -p voikko-fi --test public_api
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Collapse runs of whitespace to single spaces.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Item header up to its body, e.g. `pub fn spell(&self, word: &str) -> bool`.
+fn signature(text: &str) -> String {
+    let text = normalize(text).replace("( ", "(").replace(", )", ")");
+    let text = text.split(" {").next().unwrap_or(&text);
+    text.trim_end_matches(['{', ';', ','])
+        .trim_end()
+        .to_string()
+}
+
+/// File of child module `name` declared in `parent`.
+fn child_path(parent: &Path, name: &str) -> PathBuf {
+    let dir = match parent.file_name().and_then(|f| f.to_str()) {
+        Some("lib.rs" | "mod.rs") => parent.parent().unwrap().to_path_buf(),
+        _ => parent.with_extension(""),
+    };
+    let file = dir.join(format!("{name}.rs"));
+    if file.is_file() {
+        file
+    } else {
+        dir.join(name).join("mod.rs")
+    }
+}
+
+/// Append the public items of module `module` (in `path`) and its
+/// non-internal submodules to `out`.
+fn collect(path: &Path, module: &str, out: &mut Vec<String>) {
+    let source = fs::read_to_string(path).unwrap();
+    let mut lines = source.lines();
+    let mut attributes: Vec<&str> = Vec::new();
+    // Header of the enclosing top-level impl, struct, enum or trait
+    let mut context: Option<String> = None;
+    // Whether the enclosing top-level item is internal
+    let mut hidden = false;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if indent == 0 {
+            if line.starts_with("#[") {
+                attributes.push(line);
+                continue;
+            }
+            let internal = attributes
+                .iter()
+                .any(|a| *a == "#[cfg(test)]" || *a == "#[doc(hidden)]");
+            attributes.clear();
+
+            if line == "}" {
+                context = None;
+                hidden = false;
+                continue;
+            }
+            if internal && (line.starts_with("mod ") || line.starts_with("pub mod ")) {
+                if line.ends_with('{') {
+                    // Inline test module: nothing public follows it
+                    return;
+                }
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix("pub mod ")
+                .or_else(|| line.strip_prefix("mod "))
+                .and_then(|rest| rest.strip_suffix(';'))
+            {
+                let child = child_path(path, name);
+                collect(&child, &format!("{module}::{name}"), out);
+                continue;
+            }
+            if line.starts_with("impl")
+                || ["pub struct ", "pub enum ", "pub trait "]
+                    .iter()
+                    .any(|p| line.starts_with(p))
+            {
+                context = Some(signature(line));
+                hidden = false;
+            } else if !line.starts_with("pub ") && line.ends_with('{') {
+                context = None;
+                hidden = true;
+            }
+        } else if hidden {
+            continue;
+        }
+
+        let is_pub = trimmed.starts_with("pub ") && !trimmed.starts_with("pub mod ");
+        let is_member = indent == 4
+            && context.as_deref().is_some_and(|c| {
+                (c.starts_with("pub enum ") && trimmed.starts_with(char::is_uppercase))
+                    || (c.starts_with("pub trait ")
+                        && (trimmed.starts_with("fn ") || trimmed.starts_with("type ")))
+            });
+        if !is_pub && !is_member {
+            continue;
+        }
+
+        // Join multi-line signatures up to the body or terminator; fields
+        // and variants also end at a comma, re-export lists only at `;`
+        let is_use = trimmed.starts_with("pub use ");
+        let is_fn = trimmed.starts_with("fn ") || trimmed.contains(" fn ");
+        let mut item = line.to_string();
+        while !item.ends_with(';')
+            && (is_use || !item.contains('{'))
+            && (is_use || is_fn || !item.ends_with(','))
+        {
+            match lines.next() {
+                Some(next) => {
+                    item.push(' ');
+                    item.push_str(next.trim());
+                }
+                None => break,
+            }
+        }
+        if is_use {
+            out.push(format!(
+                "{module}: {}",
+                normalize(&item).replace("{ ", "{").replace(", }", "}")
+            ));
+            continue;
+        }
+        let entry = match (&context, indent) {
+            (Some(context), 4) => format!("{module}: {context} :: {}", signature(&item)),
+            _ => format!("{module}: {}", signature(&item)),
+        };
+        out.push(entry);
+        if indent == 0 && item.contains('{') && !item.trim_end().ends_with('}') {
+            context = Some(signature(&item));
+        }
+    }
+}
+
+#[test]
+fn public_api_matches_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut items = Vec::new();
+    collect(&root.join("src/lib.rs"), "voikko_fi", &mut items);
+    let listing = items.join("\n") + "\n";
+
+    let snapshot_path = root.join("tests/public_api.txt");
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        fs::write(&snapshot_path, &listing).unwrap();
+        return;
+    }
+    let snapshot = fs::read_to_string(&snapshot_path).unwrap_or_default();
+    if snapshot != listing {
+        let old: Vec<&str> = snapshot.lines().collect();
+        let new: Vec<&str> = listing.lines().collect();
+        let removed: Vec<&&str> = old.iter().filter(|l| !new.contains(l)).collect();
+        let added: Vec<&&str> = new.iter().filter(|l| !old.contains(l)).collect();
+        panic!(
+            "public API changed; review and rerun with UPDATE_PUBLIC_API=1\n\
+             removed: {removed:#?}\nadded: {added:#?}"
+        );
+    }
+}
+
+/// Pin the signatures of the main entry points, so that breaking them fails
+/// to compile rather than only changing the snapshot.
+#[cfg(feature = "handle")]
+#[test]
+fn prelude_signatures() {
+    use voikko_fi::prelude::*;
+
+    type Constructor = fn(&[u8], Option<&[u8]>, &str) -> Result<VoikkoHandle, VoikkoError>;
+
+    let _: Constructor = VoikkoHandle::from_bytes;
+    let _: Constructor = VoikkoHandle::from_bytes_lazy;
+    let _: fn(&VoikkoHandle, &str) -> bool = VoikkoHandle::spell;
+    let _: fn(&VoikkoHandle, &str) -> WordCheck = VoikkoHandle::check_word;
+    let _: fn(&VoikkoHandle, &str) -> Vec<String> = VoikkoHandle::suggest;
+    let _: fn(&VoikkoHandle, &str, &SuggestionBudget) -> Vec<String> =
+        VoikkoHandle::suggest_with_budget;
+    let _: fn(&VoikkoHandle, &str) -> Vec<Analysis> = VoikkoHandle::analyze;
+    let _: fn(&VoikkoHandle, &str) -> String = VoikkoHandle::hyphenate;
+    let _: fn(&VoikkoHandle, &str) -> HyphenExplanation = VoikkoHandle::explain_hyphenation;
+    let _: fn(&VoikkoHandle, &str) -> Vec<GrammarError> = VoikkoHandle::grammar_errors;
+    let _: fn(&VoikkoHandle, &str) -> Vec<Token> = VoikkoHandle::tokens;
+    let _: fn(&VoikkoHandle, &str) -> Vec<Sentence> = VoikkoHandle::sentences;
+    let _: fn(&mut VoikkoHandle, InputMapping) = VoikkoHandle::set_input_mapping;
+    let _: fn(&mut VoikkoHandle, RankingWeights) = VoikkoHandle::set_suggestion_ranking;
+    let _: fn(&VoikkoHandle) -> &UserDictionary = VoikkoHandle::user_dictionary;
+    let _: fn(&VoikkoHandle) -> &UserState = VoikkoHandle::user_state;
+    let _: fn(&VoikkoHandle) -> LoadState = VoikkoHandle::load_state;
+    let _: fn(&VoikkoHandle, &str) -> Option<CompoundInfo> = VoikkoHandle::compound_info;
+}
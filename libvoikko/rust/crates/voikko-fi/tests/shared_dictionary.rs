@@ -0,0 +1,96 @@
+//! Single residency of dictionary data shared between handles.
+//!
+//! A counting global allocator records what each handle keeps on the heap.
+//! Handles created with `from_shared` read `mor.vfst` from the caller's
+//! `Arc`, so none of them holds a copy of it; a handle created with
+//! `from_bytes` holds exactly one.
+//!
+//! Run: VOIKKO_MOR_VFST=/path/to/mor.vfst cargo test -p voikko-fi --test shared_dictionary -- --include-ignored
+
+#![cfg(feature = "handle")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use voikko_fi::handle::VoikkoHandle;
+
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            CURRENT.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Create a handle with `create`, use it, and return it with the heap it
+/// keeps.
+fn retained(create: impl FnOnce() -> VoikkoHandle) -> (VoikkoHandle, usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    let handle = create();
+    assert!(handle.spell("kissanpentu"));
+    assert!(!handle.analyze("koiralle").is_empty());
+    (handle, CURRENT.load(Ordering::Relaxed) - base)
+}
+
+#[test]
+#[ignore = "requires mor.vfst dictionary file"]
+fn handles_share_one_copy_of_the_dictionary() {
+    let path = std::env::var("VOIKKO_MOR_VFST").expect("set VOIKKO_MOR_VFST");
+    let mor_data: Arc<[u8]> = std::fs::read(&path)
+        .expect("failed to read mor.vfst")
+        .into();
+    let autocorr_path = std::path::Path::new(&path).with_file_name("autocorr.vfst");
+    let autocorr_data: Arc<[u8]> = std::fs::read(autocorr_path)
+        .expect("failed to read autocorr.vfst")
+        .into();
+    let create_shared = || {
+        VoikkoHandle::from_shared(
+            Arc::clone(&mor_data),
+            Some(Arc::clone(&autocorr_data)),
+            "fi",
+        )
+        .expect("failed to create VoikkoHandle")
+    };
+
+    // Warm up one-time allocations so that no measurement pays them.
+    drop(retained(create_shared));
+    let (first, first_heap) = retained(create_shared);
+    let (second, second_heap) = retained(create_shared);
+    let (copied, copied_heap) = retained(|| {
+        VoikkoHandle::from_bytes(&mor_data, Some(&autocorr_data), "fi")
+            .expect("failed to create VoikkoHandle")
+    });
+    eprintln!(
+        "heap: shared {first_heap} and {second_heap}, copied {copied_heap}, mor.vfst {}",
+        mor_data.len()
+    );
+
+    // Each shared handle holds a reference to both files, not a copy.
+    assert_eq!(Arc::strong_count(&mor_data), 3);
+    assert_eq!(Arc::strong_count(&autocorr_data), 3);
+    let copy = mor_data.len() + autocorr_data.len();
+    for heap in [first_heap, second_heap] {
+        assert!(heap < mor_data.len() / 2, "shared handle keeps {heap}");
+    }
+    // The copying handle holds the dictionary once.
+    assert!(copied_heap >= copy, "copying handle keeps {copied_heap}");
+    assert!(copied_heap < 2 * copy, "copying handle keeps {copied_heap}");
+
+    drop((first, second, copied));
+    assert_eq!(Arc::strong_count(&mor_data), 1);
+}
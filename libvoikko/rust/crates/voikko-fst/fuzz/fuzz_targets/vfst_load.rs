@@ -0,0 +1,65 @@
+// Fuzz target: strict loading and traversal of arbitrary VFST data.
+//
+// Any input must either fail to load with a `VfstError` or give a
+// transducer that traverses without panicking. Run from this directory:
+//
+//   cargo +nightly fuzz run vfst_load -- -max_total_time=60
+//
+// Seed the corpus with real dictionaries, e.g. `cp ../../../../js/dict/*.vfst corpus/vfst_load/`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use voikko_fst::Transducer;
+use voikko_fst::symbols::SymbolTable;
+use voikko_fst::unweighted::UnweightedTransducer;
+use voikko_fst::weighted::WeightedTransducer;
+
+/// Input words made of the first single-character symbols of the table.
+fn inputs(symbols: &SymbolTable) -> Vec<Vec<char>> {
+    let chars: Vec<char> = symbols
+        .iter()
+        .filter_map(|info| {
+            let mut chars = info.text.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        })
+        .take(4)
+        .collect();
+    let mut words = vec![Vec::new()];
+    for &a in &chars {
+        words.push(vec![a]);
+        for &b in &chars {
+            words.push(vec![a, b, a]);
+        }
+    }
+    words
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut output = String::new();
+    if let Ok(t) = UnweightedTransducer::from_bytes_strict(data) {
+        let mut config = t.new_config(32);
+        for word in inputs(t.symbols()) {
+            t.prepare(&mut config, &word);
+            while t.next(&mut config, &mut output) {
+                t.trace(&config);
+            }
+            t.prepare(&mut config, &word);
+            let mut prefix_length = 0;
+            while t.next_prefix(&mut config, &mut output, &mut prefix_length) {}
+        }
+        t.generate(&mut config, 10_000, |_| true, |_, _| true);
+        t.enumerate_outputs(100, "");
+    }
+    if let Ok(t) = WeightedTransducer::from_bytes_strict(data) {
+        let mut config = t.new_config(32);
+        for word in inputs(t.symbols()) {
+            if t.prepare(&mut config, &word) {
+                while t.next(&mut config, &mut output) {
+                    t.trace(&config);
+                }
+            }
+            t.best_first_outputs(&word, Some(8)).take(100).for_each(drop);
+        }
+    }
+});
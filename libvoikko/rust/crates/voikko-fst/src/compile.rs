@@ -0,0 +1,310 @@
+// Compile string pairs into an unweighted VFST transducer.
+//
+// The inputs are stored as a trie, one transition per character with an
+// empty output. Each output is a chain of epsilon-input transitions that
+// emit its characters and end in the single final state; chains with the
+// same tail share it, so outputs that end alike (such as analyses with the
+// same tags) cost little. The result loads with
+// `UnweightedTransducer::from_bytes` and is looked up like any dictionary.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::format::{COOKIE1, COOKIE2, HEADER_SIZE};
+use crate::transition::{OverflowCell, Transition, UNWEIGHTED_FINAL_SYM, WeightedTransition};
+
+/// Largest transition index a target state can have (24 bits).
+const MAX_TARGET: usize = 0x00FF_FFFF;
+
+/// Error type for lexicon compilation.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CompileError {
+    /// There are no entries to compile.
+    #[error("no entries to compile")]
+    Empty,
+    /// A character cannot be represented: NUL anywhere, `[` in an input
+    /// (it starts the multi-character symbols), or `@` as the only
+    /// character (it would be read as a flag diacritic).
+    #[error("unsupported character {0:?}")]
+    UnsupportedChar(char),
+    /// More distinct characters than the symbol table can hold.
+    #[error("too many distinct characters ({0})")]
+    TooManySymbols(usize),
+    /// More transitions than target states can address.
+    #[error("too many transitions ({0})")]
+    TooManyTransitions(usize),
+}
+
+/// A state under construction: its transitions as (input symbol, output
+/// symbol, target state) and whether it is final.
+#[derive(Default)]
+struct State {
+    arcs: Vec<(u16, u16, usize)>,
+    is_final: bool,
+}
+
+/// Compile `(input, output)` pairs into an unweighted VFST transducer that
+/// maps each input to its outputs, in the order given. Repeated pairs are
+/// kept once.
+pub fn compile_unweighted<'a>(
+    entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Vec<u8>, CompileError> {
+    let mut pairs: Vec<(&str, &str)> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for pair in entries {
+        if seen.insert(pair) {
+            pairs.push(pair);
+        }
+    }
+    if pairs.is_empty() {
+        return Err(CompileError::Empty);
+    }
+    let symbols = symbol_list(&pairs)?;
+    let symbol_of: HashMap<char, u16> = symbols
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &c)| (c, i as u16))
+        .collect();
+
+    // State 0 is the root of the trie, state 1 the final state.
+    let mut states = vec![
+        State::default(),
+        State {
+            arcs: Vec::new(),
+            is_final: true,
+        },
+    ];
+    let mut children: HashMap<(usize, u16), usize> = HashMap::new();
+    let mut tails: HashMap<(u16, usize), usize> = HashMap::new();
+    let mut tail_of_output: HashMap<&str, usize> = HashMap::new();
+    for &(input, output) in &pairs {
+        let mut state = 0;
+        for c in input.chars() {
+            let sym = symbol_of[&c];
+            state = *children.entry((state, sym)).or_insert_with(|| {
+                states.push(State::default());
+                let child = states.len() - 1;
+                states[state].arcs.push((sym, 0, child));
+                child
+            });
+        }
+        if output.is_empty() {
+            states[state].is_final = true;
+            continue;
+        }
+        // The chain emitting all but the first character, shared by tail
+        let tail = *tail_of_output.entry(output).or_insert_with(|| {
+            let mut next = 1;
+            for c in output.chars().skip(1).collect::<Vec<_>>().into_iter().rev() {
+                let sym = symbol_of[&c];
+                next = *tails.entry((sym, next)).or_insert_with(|| {
+                    states.push(State {
+                        arcs: vec![(0, sym, next)],
+                        is_final: false,
+                    });
+                    states.len() - 1
+                });
+            }
+            next
+        });
+        let first = symbol_of[&output.chars().next().unwrap_or_default()];
+        states[state].arcs.push((0, first, tail));
+    }
+
+    let mut data = header_and_symbols(&symbols, false);
+    write_transitions(&states, &mut data)?;
+    Ok(data)
+}
+
+/// The header and symbol table of a transducer with `symbols`, padded to
+/// the alignment of the transitions that follow.
+pub(crate) fn header_and_symbols(symbols: &[char], weighted: bool) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&COOKIE1.to_le_bytes());
+    data.extend_from_slice(&COOKIE2.to_le_bytes());
+    data.push(u8::from(weighted));
+    data.resize(HEADER_SIZE, 0);
+    data.extend_from_slice(&(symbols.len() as u16).to_le_bytes());
+    for (i, c) in symbols.iter().enumerate() {
+        if i > 0 {
+            let mut buf = [0u8; 4];
+            data.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+        data.push(0);
+    }
+    let alignment = if weighted {
+        size_of::<WeightedTransition>()
+    } else {
+        size_of::<Transition>()
+    };
+    data.resize(data.len().next_multiple_of(alignment), 0);
+    data
+}
+
+/// The symbols of the characters of `pairs`. Inputs cannot contain `[`.
+fn symbol_list(pairs: &[(&str, &str)]) -> Result<Vec<char>, CompileError> {
+    let mut chars = BTreeSet::new();
+    for (input, output) in pairs {
+        if input.contains('[') {
+            return Err(CompileError::UnsupportedChar('['));
+        }
+        chars.extend(input.chars().chain(output.chars()));
+    }
+    char_symbols(&chars)
+}
+
+/// The symbols of `chars`: epsilon, then the characters in order, with `@`
+/// moved after the others so that it is not read as a flag diacritic and
+/// `[` last so that the characters before it are single-character symbols.
+pub(crate) fn char_symbols(chars: &BTreeSet<char>) -> Result<Vec<char>, CompileError> {
+    if chars.contains(&'\0') {
+        return Err(CompileError::UnsupportedChar('\0'));
+    }
+    let mut symbols = vec!['\0'];
+    symbols.extend(chars.iter().filter(|&&c| c != '@' && c != '['));
+    if chars.contains(&'@') {
+        if symbols.len() == 1 {
+            return Err(CompileError::UnsupportedChar('@'));
+        }
+        symbols.push('@');
+    }
+    if chars.contains(&'[') {
+        symbols.push('[');
+    }
+    // The largest symbol index must stay below the final marker
+    if symbols.len() >= usize::from(UNWEIGHTED_FINAL_SYM) {
+        return Err(CompileError::TooManySymbols(symbols.len() - 1));
+    }
+    Ok(symbols)
+}
+
+/// Append the transition table of `states` to `data`. Each state is a run
+/// of transitions starting with its final marker, if any; a state with
+/// more than 255 transitions has an overflow cell in its second slot.
+/// Every state has at least one transition: the root has one per entry
+/// and the others lead somewhere or are final.
+fn write_transitions(states: &[State], data: &mut Vec<u8>) -> Result<(), CompileError> {
+    let counts: Vec<usize> = states
+        .iter()
+        .map(|s| s.arcs.len() + usize::from(s.is_final))
+        .collect();
+    let mut offsets = Vec::with_capacity(states.len());
+    let mut total = 0;
+    for &count in &counts {
+        offsets.push(total);
+        total += if count > 255 { count + 1 } else { count };
+    }
+    if total > MAX_TARGET {
+        return Err(CompileError::TooManyTransitions(total));
+    }
+
+    let transition = |sym_in: u16, sym_out: u16, target: usize, more: u8| Transition {
+        sym_in,
+        sym_out,
+        trans_info: target as u32 | (u32::from(more) << 24),
+    };
+    for (state, &count) in states.iter().zip(&counts) {
+        let more = if count > 255 {
+            255
+        } else {
+            count.saturating_sub(1) as u8
+        };
+        let mut cells: Vec<Transition> = Vec::with_capacity(count + 1);
+        if state.is_final {
+            cells.push(transition(UNWEIGHTED_FINAL_SYM, 0, 0, 0));
+        }
+        for &(sym_in, sym_out, target) in &state.arcs {
+            cells.push(transition(sym_in, sym_out, offsets[target], 0));
+        }
+        if count > 255 {
+            let overflow = OverflowCell {
+                more_transitions: (count - 1) as u32,
+                _padding: 0,
+            };
+            cells.insert(1, bytemuck::cast(overflow));
+        }
+        cells[0].trans_info = (cells[0].trans_info & 0x00FF_FFFF) | (u32::from(more) << 24);
+        data.extend_from_slice(bytemuck::cast_slice(&cells));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Transducer;
+    use crate::unweighted::UnweightedTransducer;
+
+    fn lookup(t: &UnweightedTransducer, input: &str) -> Vec<String> {
+        let input: Vec<char> = input.chars().collect();
+        t.outputs(&input).collect()
+    }
+
+    #[test]
+    fn compiled_lexicon_maps_inputs_to_outputs() {
+        let data = compile_unweighted([
+            ("koira", "koira\tnimisana"),
+            ("koiralle", "koira\tnimisana allatiivi"),
+            ("kuusi", "kuusi\tlukusana"),
+            ("kuusi", "kuusi\tnimisana"),
+            ("kuusi", "kuusi\tnimisana"),
+        ])
+        .unwrap();
+        let t = UnweightedTransducer::from_bytes_strict(&data).unwrap();
+        assert_eq!(lookup(&t, "koira"), ["koira\tnimisana"]);
+        assert_eq!(lookup(&t, "koiralle"), ["koira\tnimisana allatiivi"]);
+        assert_eq!(lookup(&t, "kuusi"), ["kuusi\tlukusana", "kuusi\tnimisana"]);
+        assert!(lookup(&t, "koir").is_empty());
+        assert!(lookup(&t, "kissa").is_empty());
+    }
+
+    #[test]
+    fn empty_output_accepts_the_input() {
+        let data = compile_unweighted([("ab", ""), ("a@b", "x")]).unwrap();
+        let t = UnweightedTransducer::from_bytes_strict(&data).unwrap();
+        assert_eq!(lookup(&t, "ab"), [""]);
+        assert_eq!(lookup(&t, "a@b"), ["x"]);
+    }
+
+    #[test]
+    fn outputs_share_their_tails() {
+        let one = compile_unweighted([("a", "a\tnimisana")]).unwrap();
+        let two = compile_unweighted([("a", "a\tnimisana"), ("b", "b\tnimisana")]).unwrap();
+        // "b" adds a trie state, one emitting state and a symbol
+        assert!(two.len() - one.len() <= 4 * size_of::<Transition>());
+    }
+
+    #[test]
+    fn state_with_many_transitions_uses_an_overflow_cell() {
+        let words: Vec<String> = (0..300u32)
+            .map(|i| char::from_u32(0x4E00 + i).unwrap().to_string())
+            .collect();
+        let data = compile_unweighted(words.iter().map(|w| (w.as_str(), w.as_str()))).unwrap();
+        let t = UnweightedTransducer::from_bytes_strict(&data).unwrap();
+        for w in [&words[0], &words[254], &words[255], &words[299]] {
+            assert_eq!(lookup(&t, w), [w.as_str()]);
+        }
+    }
+
+    #[test]
+    fn rejects_unrepresentable_entries() {
+        let none: [(&str, &str); 0] = [];
+        assert_eq!(compile_unweighted(none), Err(CompileError::Empty));
+        assert_eq!(
+            compile_unweighted([("a[", "x")]),
+            Err(CompileError::UnsupportedChar('['))
+        );
+        assert_eq!(
+            compile_unweighted([("a\0", "x")]),
+            Err(CompileError::UnsupportedChar('\0'))
+        );
+        assert_eq!(
+            compile_unweighted([("@", "@")]),
+            Err(CompileError::UnsupportedChar('@'))
+        );
+        // "[" is fine in outputs
+        let data = compile_unweighted([("a", "[x]")]).unwrap();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        assert_eq!(lookup(&t, "a"), ["[x]"]);
+    }
+}
@@ -4,6 +4,56 @@
 // This module unifies the C++ Configuration and WeightedConfiguration into
 // separate Rust types, each tailored to its traversal variant's needs.
 
+use std::time::Instant;
+
+use crate::MAX_LOOP_COUNT;
+
+/// Limits on a traversal, replacing the fixed [`MAX_LOOP_COUNT`].
+///
+/// A configuration carries its budget across `prepare` calls; set it with
+/// [`UnweightedConfig::set_budget`] or [`WeightedConfig::set_budget`]. When
+/// a limit is reached, [`Transducer::next_status`](crate::Transducer::next_status)
+/// returns [`NextStatus::BudgetExceeded`](crate::NextStatus::BudgetExceeded)
+/// instead of [`NextStatus::Exhausted`](crate::NextStatus::Exhausted). The
+/// default budget behaves like the C++ engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraversalBudget {
+    /// Transitions followed or backtracked per `next` call. Defaults to
+    /// [`MAX_LOOP_COUNT`].
+    pub max_steps: u32,
+    /// Outputs per traversal, counted from `prepare`. Unlimited by default.
+    pub max_outputs: u32,
+    /// Time after which `next` stops. The clock is read every 1024 steps.
+    pub deadline: Option<Instant>,
+}
+
+impl Default for TraversalBudget {
+    fn default() -> Self {
+        Self {
+            max_steps: MAX_LOOP_COUNT,
+            max_outputs: u32::MAX,
+            deadline: None,
+        }
+    }
+}
+
+impl TraversalBudget {
+    /// Steps between two readings of the clock.
+    const DEADLINE_CHECK_INTERVAL: u32 = 1024;
+
+    /// Return `true` if the deadline has passed. Only reads the clock at
+    /// `step` 0 and every `DEADLINE_CHECK_INTERVAL` steps after it.
+    #[inline]
+    pub(crate) fn past_deadline(&self, step: u32) -> bool {
+        match self.deadline {
+            Some(deadline) => {
+                step % Self::DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline
+            }
+            None => false,
+        }
+    }
+}
+
 /// Traversal configuration for the unweighted transducer.
 ///
 /// Holds the explicit DFS stack and flag diacritic undo state. The unweighted
@@ -34,6 +84,11 @@ pub struct UnweightedConfig {
     pub flag_undo_value: Vec<u16>,
     /// Which feature was updated at each flag_depth (for undo).
     pub flag_undo_feature: Vec<u16>,
+
+    /// Limits on the traversal; kept across `prepare` calls.
+    pub budget: TraversalBudget,
+    /// Outputs yielded since the last `prepare`.
+    pub output_count: u32,
 }
 
 impl UnweightedConfig {
@@ -64,9 +119,16 @@ impl UnweightedConfig {
             } else {
                 Vec::new()
             },
+            budget: TraversalBudget::default(),
+            output_count: 0,
         }
     }
 
+    /// Set the limits on the traversals with this configuration.
+    pub fn set_budget(&mut self, budget: TraversalBudget) {
+        self.budget = budget;
+    }
+
     /// Reset depths to initial state (called at the start of `prepare`).
     #[inline]
     pub fn reset(&mut self) {
@@ -74,6 +136,7 @@ impl UnweightedConfig {
         self.flag_depth = 0;
         self.input_depth = 0;
         self.input_length = 0;
+        self.output_count = 0;
         self.state_index_stack[0] = 0;
         self.current_transition_stack[0] = 0;
         // Reset flag values to neutral
@@ -113,6 +176,11 @@ pub struct WeightedConfig {
     pub flag_value_stack: Vec<u32>,
     /// Number of flag diacritic features (used to index into flag_value_stack).
     pub flag_feature_count: u32,
+
+    /// Limits on the traversal; kept across `prepare` calls.
+    pub budget: TraversalBudget,
+    /// Outputs yielded since the last `prepare`.
+    pub output_count: u32,
 }
 
 impl WeightedConfig {
@@ -138,9 +206,16 @@ impl WeightedConfig {
                 Vec::new()
             },
             flag_feature_count: fc,
+            budget: TraversalBudget::default(),
+            output_count: 0,
         }
     }
 
+    /// Set the limits on the traversals with this configuration.
+    pub fn set_budget(&mut self, budget: TraversalBudget) {
+        self.budget = budget;
+    }
+
     /// Reset depths to initial state (called at the start of `prepare`).
     #[inline]
     pub fn reset(&mut self) {
@@ -148,6 +223,7 @@ impl WeightedConfig {
         self.flag_depth = 0;
         self.input_depth = 0;
         self.input_length = 0;
+        self.output_count = 0;
         self.state_index_stack[0] = 0;
         self.current_transition_stack[0] = 0;
         // Reset initial flag row to neutral
@@ -229,8 +305,13 @@ mod tests {
         config.flag_depth = 5;
         config.input_depth = 3;
         config.input_length = 7;
+        config.output_count = 4;
         config.current_flag_values[0] = 42;
         config.current_flag_values[1] = 99;
+        config.set_budget(TraversalBudget {
+            max_outputs: 1,
+            ..TraversalBudget::default()
+        });
 
         config.reset();
 
@@ -238,8 +319,11 @@ mod tests {
         assert_eq!(config.flag_depth, 0);
         assert_eq!(config.input_depth, 0);
         assert_eq!(config.input_length, 0);
+        assert_eq!(config.output_count, 0);
         assert_eq!(config.current_flag_values[0], 0);
         assert_eq!(config.current_flag_values[1], 0);
+        // The budget outlives the traversal
+        assert_eq!(config.budget.max_outputs, 1);
     }
 
     #[test]
@@ -0,0 +1,298 @@
+// Generate an edit-distance error model as a weighted VFST transducer.
+//
+// Suggestions from VFST dictionaries come from an error model (`err.vfst`)
+// traversed in tandem with the acceptor: each output of the error model is
+// a candidate, and candidates the acceptor rejects prune the error model
+// search. Dictionary packs without `err.vfst` can use a generic model built
+// here instead, with a configurable alphabet, distance and edit weights.
+//
+// The model has one state per number of edits made so far, and all of them
+// are final. Every state maps each alphabet character to itself for free.
+// Below the maximum distance it can also substitute, delete (output
+// epsilon) or insert (input epsilon) a character, moving to the next state
+// at the weight of the edit. A transposition "ab" -> "ba" passes through an
+// intermediate state per character pair.
+
+use std::collections::BTreeSet;
+
+use crate::compile::{self, CompileError};
+use crate::transition::{WEIGHTED_FINAL_SYM, WeightedOverflowCell, WeightedTransition};
+
+/// Default weight of a single edit.
+const DEFAULT_EDIT_WEIGHT: i16 = 10;
+
+/// A transition under construction: input symbol, output symbol, target
+/// state and weight.
+type Edge = (u32, u32, usize, i16);
+
+/// A generic edit-distance error model.
+///
+/// [`compile`](Self::compile) turns it into weighted VFST data that maps a
+/// word to every string within `max_distance` edits, weighted by the sum of
+/// the edit weights. Weights add to those of the acceptor, so they should be
+/// on the same scale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditDistanceModel {
+    /// Characters that can be inserted, deleted, substituted and
+    /// transposed. A word with a character outside the alphabet has no
+    /// outputs.
+    pub alphabet: Vec<char>,
+    /// Maximum number of edits per word.
+    pub max_distance: usize,
+    /// Weight of replacing a character with another.
+    pub substitution_weight: i16,
+    /// Weight of inserting a character.
+    pub insertion_weight: i16,
+    /// Weight of deleting a character.
+    pub deletion_weight: i16,
+    /// Weight of swapping two adjacent characters, as one edit. `None`
+    /// leaves transpositions to two substitutions.
+    pub transposition_weight: Option<i16>,
+}
+
+impl EditDistanceModel {
+    /// A model over `alphabet` that allows two edits of weight 10 each,
+    /// transpositions included.
+    pub fn new(alphabet: impl IntoIterator<Item = char>) -> Self {
+        Self {
+            alphabet: alphabet.into_iter().collect(),
+            max_distance: 2,
+            substitution_weight: DEFAULT_EDIT_WEIGHT,
+            insertion_weight: DEFAULT_EDIT_WEIGHT,
+            deletion_weight: DEFAULT_EDIT_WEIGHT,
+            transposition_weight: Some(DEFAULT_EDIT_WEIGHT),
+        }
+    }
+
+    /// Compile the model into weighted VFST data, to be loaded with
+    /// [`WeightedTransducer::from_bytes`](crate::weighted::WeightedTransducer::from_bytes).
+    ///
+    /// Fails with [`CompileError::Empty`] for an empty alphabet and with
+    /// [`CompileError::UnsupportedChar`] for NUL, `[` or an alphabet of
+    /// only `@`.
+    pub fn compile(&self) -> Result<Vec<u8>, CompileError> {
+        let chars: BTreeSet<char> = self.alphabet.iter().copied().collect();
+        if chars.is_empty() {
+            return Err(CompileError::Empty);
+        }
+        if chars.contains(&'[') {
+            return Err(CompileError::UnsupportedChar('['));
+        }
+        let symbols = compile::char_symbols(&chars)?;
+        let letters = 1..symbols.len() as u32;
+
+        // States 0..=max_distance count the edits; transposition states
+        // follow them.
+        let levels = self.max_distance + 1;
+        let mut states: Vec<Vec<Edge>> = vec![Vec::new(); levels];
+        for level in 0..levels {
+            let next = level + 1;
+            let can_edit = level < self.max_distance;
+            // Epsilon inputs come first, then the inputs in symbol order.
+            let mut arcs = Vec::new();
+            if can_edit {
+                arcs.extend(letters.clone().map(|b| (0, b, next, self.insertion_weight)));
+            }
+            for a in letters.clone() {
+                arcs.push((a, a, level, 0));
+                if !can_edit {
+                    continue;
+                }
+                for b in letters.clone().filter(|&b| b != a) {
+                    arcs.push((a, b, next, self.substitution_weight));
+                }
+                arcs.push((a, 0, next, self.deletion_weight));
+                if let Some(weight) = self.transposition_weight {
+                    for b in letters.clone().filter(|&b| b != a) {
+                        states.push(vec![(b, a, next, 0)]);
+                        arcs.push((a, b, states.len() - 1, weight));
+                    }
+                }
+            }
+            states[level] = arcs;
+        }
+
+        let mut data = compile::header_and_symbols(&symbols, true);
+        write_transitions(&states, levels, &mut data)?;
+        Ok(data)
+    }
+}
+
+/// Append the weighted transition table of `states` to `data`. The first
+/// `finals` states are final with weight 0, marked in their first slot; a
+/// state with more than 255 transitions has an overflow cell in its second
+/// slot.
+fn write_transitions(
+    states: &[Vec<Edge>],
+    finals: usize,
+    data: &mut Vec<u8>,
+) -> Result<(), CompileError> {
+    let counts: Vec<usize> = states
+        .iter()
+        .enumerate()
+        .map(|(i, arcs)| arcs.len() + usize::from(i < finals))
+        .collect();
+    let mut offsets = Vec::with_capacity(states.len());
+    let mut total = 0;
+    for &count in &counts {
+        offsets.push(total);
+        total += if count > 255 { count + 1 } else { count };
+    }
+    if u32::try_from(total).is_err() {
+        return Err(CompileError::TooManyTransitions(total));
+    }
+
+    let transition = |sym_in: u32, sym_out: u32, target: usize, weight: i16| WeightedTransition {
+        sym_in,
+        sym_out,
+        target_state: target as u32,
+        weight,
+        more_transitions: 0,
+        _reserved: 0,
+    };
+    for (i, (arcs, &count)) in states.iter().zip(&counts).enumerate() {
+        let mut cells: Vec<WeightedTransition> = Vec::with_capacity(count + 1);
+        if i < finals {
+            cells.push(transition(WEIGHTED_FINAL_SYM, 0, 0, 0));
+        }
+        for &(sym_in, sym_out, target, weight) in arcs {
+            cells.push(transition(sym_in, sym_out, offsets[target], weight));
+        }
+        if count > 255 {
+            let overflow = WeightedOverflowCell {
+                more_transitions: (count - 1) as u32,
+                _short_padding: 0,
+                _padding: 0,
+            };
+            cells.insert(1, bytemuck::cast(overflow));
+            cells[0].more_transitions = 255;
+        } else {
+            cells[0].more_transitions = (count - 1) as u8;
+        }
+        data.extend_from_slice(bytemuck::cast_slice(&cells));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weighted::WeightedTransducer;
+    use std::collections::BTreeMap;
+
+    /// The outputs for `input` with the cheapest weight of each.
+    fn outputs(model: &EditDistanceModel, input: &str) -> BTreeMap<String, f32> {
+        let data = model.compile().unwrap();
+        let t = WeightedTransducer::from_bytes_strict(&data).unwrap();
+        let input: Vec<char> = input.chars().collect();
+        let mut outputs = BTreeMap::new();
+        for (output, weight) in t.weighted_outputs(&input) {
+            let entry = outputs.entry(output).or_insert(weight);
+            *entry = entry.min(weight);
+        }
+        outputs
+    }
+
+    #[test]
+    fn distance_one_reaches_every_single_edit() {
+        let mut model = EditDistanceModel::new("ab".chars());
+        model.max_distance = 1;
+        model.transposition_weight = None;
+        let found = outputs(&model, "ab");
+        let expected = [
+            ("ab", 0.0),
+            // substitutions
+            ("bb", 10.0),
+            ("aa", 10.0),
+            // deletions
+            ("b", 10.0),
+            ("a", 10.0),
+            // insertions
+            ("aab", 10.0),
+            ("bab", 10.0),
+            ("abb", 10.0),
+            ("aba", 10.0),
+        ];
+        assert_eq!(
+            found,
+            expected
+                .iter()
+                .map(|&(s, w)| (s.to_string(), w))
+                .collect::<BTreeMap<_, _>>()
+        );
+        // Two edits are out of reach
+        assert!(!found.contains_key("ba"));
+    }
+
+    #[test]
+    fn edits_are_weighted_separately() {
+        let mut model = EditDistanceModel::new("abc".chars());
+        model.substitution_weight = 3;
+        model.insertion_weight = 5;
+        model.deletion_weight = 7;
+        model.transposition_weight = Some(2);
+        let found = outputs(&model, "abc");
+        assert_eq!(found["abc"], 0.0);
+        assert_eq!(found["bac"], 2.0);
+        assert_eq!(found["abb"], 3.0);
+        assert_eq!(found["abcc"], 5.0);
+        assert_eq!(found["ac"], 7.0);
+        // Two edits
+        assert_eq!(found["bab"], 5.0);
+        assert_eq!(found["cbac"], 7.0);
+        // Three are too many
+        assert!(!found.contains_key("aaaaa"));
+        assert!(!found.contains_key(""));
+    }
+
+    #[test]
+    fn distance_zero_is_the_identity() {
+        let mut model = EditDistanceModel::new("abc".chars());
+        model.max_distance = 0;
+        let found = outputs(&model, "cab");
+        assert_eq!(
+            found.into_iter().collect::<Vec<_>>(),
+            [("cab".to_string(), 0.0)]
+        );
+    }
+
+    #[test]
+    fn characters_outside_the_alphabet_have_no_outputs() {
+        let model = EditDistanceModel::new("ab".chars());
+        assert!(outputs(&model, "ax").is_empty());
+    }
+
+    #[test]
+    fn large_alphabet_uses_overflow_cells() {
+        let alphabet: Vec<char> = (0..300u32)
+            .map(|i| char::from_u32(0x4E00 + i).unwrap())
+            .collect();
+        let mut model = EditDistanceModel::new(alphabet.iter().copied());
+        model.max_distance = 1;
+        model.transposition_weight = None;
+        let input: String = [alphabet[0], alphabet[299]].iter().collect();
+        let found = outputs(&model, &input);
+        // Identity, 2 * 299 substitutions, 2 deletions and 3 * 300
+        // insertions, of which two pairs coincide
+        assert_eq!(found.len(), 1 + 2 * 299 + 2 + 3 * 300 - 2);
+        let substituted: String = [alphabet[0], alphabet[254]].iter().collect();
+        assert_eq!(found[&substituted], 10.0);
+    }
+
+    #[test]
+    fn rejects_unrepresentable_alphabets() {
+        let none: [char; 0] = [];
+        assert_eq!(
+            EditDistanceModel::new(none).compile(),
+            Err(CompileError::Empty)
+        );
+        assert_eq!(
+            EditDistanceModel::new("a[".chars()).compile(),
+            Err(CompileError::UnsupportedChar('['))
+        );
+        assert_eq!(
+            EditDistanceModel::new("@".chars()).compile(),
+            Err(CompileError::UnsupportedChar('@'))
+        );
+    }
+}
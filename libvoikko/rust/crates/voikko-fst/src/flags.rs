@@ -52,6 +52,110 @@ impl Default for OpFeatureValue {
     }
 }
 
+/// A flag diacritic feature declared by a transducer, with the values its
+/// operations name, in order of first appearance in the symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagFeature {
+    /// Feature name, e.g. `CASE` for `@P.CASE.NOM@`.
+    pub name: String,
+    /// Values set, required or disallowed for the feature, e.g. `NOM`.
+    pub values: Vec<String>,
+}
+
+/// A flag diacritic symbol decomposed into its operation, feature and value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagDiacritic<'a> {
+    /// The operation.
+    pub op: FlagOp,
+    /// Feature name, e.g. `CASE` for `@U.CASE.NOM@`.
+    pub feature: &'a str,
+    /// Value named by the operation, or `None` if it names none (`@C.CASE@`,
+    /// or `@R.CASE@` for any value).
+    pub value: Option<&'a str>,
+}
+
+/// A flag diacritic operation that fired on an accepted path, and its
+/// effect on the feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagEvent {
+    /// The operation.
+    pub op: FlagOp,
+    /// Feature name.
+    pub feature: String,
+    /// Value named by the operation, or `None` if it names none (`@C.CASE@`,
+    /// or `@R.CASE@` for any value).
+    pub value: Option<String>,
+    /// Value of the feature before the operation, or `None` if neutral.
+    pub before: Option<String>,
+    /// Value of the feature after the operation, or `None` if neutral.
+    pub after: Option<String>,
+}
+
+/// What is inconsistent about a flag diacritic, as found by
+/// [`SymbolTable::validate_flags`](crate::symbols::SymbolTable::validate_flags).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlagDiagnosticKind {
+    /// An `R` or `D` operation names a value that no `P` or `U` operation
+    /// sets for the feature: `@R.CASE.GEN@` with only `@P.CASE.NOM@`.
+    ValueNeverSet,
+    /// An `R`, `D` or `C` operation names a feature that no `P` or `U`
+    /// operation sets.
+    FeatureNeverSet,
+    /// A `P` operation sets a feature that no `R`, `D` or `U` operation
+    /// tests, so the value has no effect.
+    FeatureNeverTested,
+}
+
+/// An inconsistency in the flag diacritics of a symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagDiagnostic {
+    /// What is inconsistent.
+    pub kind: FlagDiagnosticKind,
+    /// The flag diacritic symbol, e.g. `@R.CASE.GEN@`. For
+    /// [`FeatureNeverTested`](FlagDiagnosticKind::FeatureNeverTested), the
+    /// first symbol setting the feature.
+    pub symbol: String,
+    /// The operation of the symbol.
+    pub op: FlagOp,
+    /// Feature name.
+    pub feature: String,
+    /// Value named by the operation, or `None` if it names none.
+    pub value: Option<String>,
+}
+
+impl FlagDiagnostic {
+    /// Whether the inconsistency makes paths impossible: an `R` operation
+    /// that can never pass. The other diagnostics are operations without
+    /// effect.
+    pub fn is_error(&self) -> bool {
+        self.op == FlagOp::R && self.kind != FlagDiagnosticKind::FeatureNeverTested
+    }
+}
+
+impl std::fmt::Display for FlagDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let feature = &self.feature;
+        match self.kind {
+            FlagDiagnosticKind::ValueNeverSet => write!(
+                f,
+                "{}: value {} of feature {feature} is never set",
+                self.symbol,
+                self.value.as_deref().unwrap_or("")
+            ),
+            FlagDiagnosticKind::FeatureNeverSet => {
+                write!(f, "{}: feature {feature} is never set", self.symbol)
+            }
+            FlagDiagnosticKind::FeatureNeverTested => {
+                write!(f, "{}: feature {feature} is never tested", self.symbol)
+            }
+        }?;
+        if self.is_error() {
+            write!(f, "; paths through it are never accepted")?;
+        }
+        Ok(())
+    }
+}
+
 /// Result of a flag diacritic check: whether the transition is allowed,
 /// and if so, whether the flag state should be updated.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -153,6 +257,20 @@ impl FlagDiacriticParser {
         self.features.len() as u16
     }
 
+    /// Consume the parser, returning the feature names and the value names,
+    /// each indexed like the parsed operations. Value 0 is the neutral value
+    /// (`""`) and value 1 the any value (`"@"`).
+    pub fn into_names(self) -> (Vec<String>, Vec<String>) {
+        let by_index = |map: HashMap<String, u16>| {
+            let mut names = vec![String::new(); map.len()];
+            for (name, index) in map {
+                names[index as usize] = name;
+            }
+            names
+        };
+        (by_index(self.features), by_index(self.values))
+    }
+
     /// Parse a flag diacritic symbol string like `@P.FEATURE.VALUE@` or `@C.FEATURE@`.
     ///
     /// Returns the parsed operation with feature and value indices. Features and values
@@ -471,6 +589,17 @@ mod tests {
         assert_eq!(ofv3.value, 2);
     }
 
+    #[test]
+    fn into_names_indexes_features_and_values() {
+        let mut parser = FlagDiacriticParser::new();
+        parser.parse("@P.CASE.NOM@").unwrap();
+        parser.parse("@U.NUM.SG@").unwrap();
+        parser.parse("@R.CASE.GEN@").unwrap();
+        let (features, values) = parser.into_names();
+        assert_eq!(features, ["CASE", "NUM"]);
+        assert_eq!(values, ["", "@", "NOM", "SG", "GEN"]);
+    }
+
     #[test]
     fn reject_too_short_symbol() {
         let mut parser = FlagDiacriticParser::new();
@@ -4,8 +4,8 @@
 use crate::VfstError;
 
 /// VFST header magic constants (little-endian).
-const COOKIE1: u32 = 0x0001_3A6E;
-const COOKIE2: u32 = 0x0003_51FA;
+pub(crate) const COOKIE1: u32 = 0x0001_3A6E;
+pub(crate) const COOKIE2: u32 = 0x0003_51FA;
 
 /// Size of the VFST binary header in bytes.
 pub const HEADER_SIZE: usize = 16;
@@ -0,0 +1,131 @@
+// Iterator adapters over transducer traversal
+// Wraps the `prepare` + `next` coroutine pattern in standard iterators, so
+// callers can use `.take(n)`, `.collect()` and friends. Each iterator owns
+// one configuration and reuses it across `next` calls and across inputs
+// (see `reset`), keeping the traversal itself allocation-free.
+
+use crate::Transducer;
+use crate::config::WeightedConfig;
+use crate::weighted::{WeightedResult, WeightedTransducer};
+
+/// Default traversal stack depth for iterator configurations.
+/// Matches BUFFER_SIZE used by the analyzers and spellers.
+pub const DEFAULT_BUFFER_SIZE: usize = 2000;
+
+/// Stack depth needed for `input_len` input symbols.
+fn buffer_size_for(input_len: usize) -> usize {
+    DEFAULT_BUFFER_SIZE.max(input_len + 1)
+}
+
+/// Iterator over the outputs of a transducer for one input.
+///
+/// Created by [`Transducer::outputs`]. If the input contains symbols unknown
+/// to the transducer, the iterator yields nothing.
+pub struct OutputIter<'a, T: Transducer> {
+    transducer: &'a T,
+    config: T::Config,
+    buffer_size: usize,
+    output: String,
+    done: bool,
+}
+
+impl<'a, T: Transducer> OutputIter<'a, T> {
+    pub(crate) fn new(transducer: &'a T, input: &[char]) -> Self {
+        let buffer_size = buffer_size_for(input.len());
+        let mut iter = Self {
+            transducer,
+            config: transducer.new_config(buffer_size),
+            buffer_size,
+            output: String::new(),
+            done: true,
+        };
+        iter.reset(input);
+        iter
+    }
+
+    /// Restart the iterator for a new input, reusing its configuration.
+    pub fn reset(&mut self, input: &[char]) {
+        if input.len() >= self.buffer_size {
+            self.buffer_size = buffer_size_for(input.len());
+            self.config = self.transducer.new_config(self.buffer_size);
+        }
+        self.done = !self.transducer.prepare(&mut self.config, input);
+    }
+}
+
+impl<T: Transducer> Iterator for OutputIter<'_, T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.done {
+            return None;
+        }
+        if self.transducer.next(&mut self.config, &mut self.output) {
+            Some(self.output.clone())
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
+
+/// Iterator over the outputs of a weighted transducer together with their
+/// accumulated weights.
+///
+/// Created by [`WeightedTransducer::weighted_outputs`]. The weight is the
+/// log-domain path weight (lower is better), as in [`WeightedResult`].
+pub struct WeightedOutputIter<'a> {
+    transducer: &'a WeightedTransducer,
+    config: WeightedConfig,
+    buffer_size: usize,
+    output: String,
+    result: WeightedResult,
+    done: bool,
+}
+
+impl<'a> WeightedOutputIter<'a> {
+    pub(crate) fn new(transducer: &'a WeightedTransducer, input: &[char]) -> Self {
+        let buffer_size = buffer_size_for(input.len());
+        let mut iter = Self {
+            transducer,
+            config: transducer.new_config(buffer_size),
+            buffer_size,
+            output: String::new(),
+            result: WeightedResult {
+                weight: 0,
+                first_not_reached_position: 0,
+            },
+            done: true,
+        };
+        iter.reset(input);
+        iter
+    }
+
+    /// Restart the iterator for a new input, reusing its configuration.
+    pub fn reset(&mut self, input: &[char]) {
+        if input.len() >= self.buffer_size {
+            self.buffer_size = buffer_size_for(input.len());
+            self.config = self.transducer.new_config(self.buffer_size);
+        }
+        self.done = !self.transducer.prepare(&mut self.config, input);
+    }
+}
+
+impl Iterator for WeightedOutputIter<'_> {
+    type Item = (String, f32);
+
+    fn next(&mut self) -> Option<(String, f32)> {
+        if self.done {
+            return None;
+        }
+        if self
+            .transducer
+            .next_weighted(&mut self.config, &mut self.output, &mut self.result)
+        {
+            Some((self.output.clone(), f32::from(self.result.weight)))
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
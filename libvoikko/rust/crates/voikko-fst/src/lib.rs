@@ -6,24 +6,54 @@
 //!
 //! # Architecture
 //!
+//! - [`compile`] -- Compiling string pairs into an unweighted transducer
 //! - [`format`] -- Binary header parsing and validation
 //! - [`transition`] -- Zero-copy transition struct layout
-//! - [`symbols`] -- Symbol table (char-to-index and index-to-string mapping)
+//! - [`symbols`] -- Symbol table (lookup by string or char, reverse lookup, iteration, flag diacritics)
 //! - [`flags`] -- Flag diacritic operations (P, C, U, R, D)
 //! - [`config`] -- Traversal configuration (explicit DFS stack)
+//! - [`error_model`] -- Generating an edit-distance error model transducer
+//! - [`iter`] -- Iterator adapters over traversal
+//! - [`trace`] -- Path traces of accepted outputs for debugging
 //! - [`unweighted`] -- Unweighted transducer loading and traversal
+//! - [`validate`] -- Transition table checks for strict loading
 //! - [`weighted`] -- Weighted transducer loading and traversal
+//!
+//! # Truncated data
+//!
+//! Data that ends too early, such as a partial download, fails to load
+//! with an error whose [`VfstError::truncation`] tells the section it ends
+//! in and how many bytes are missing at least. A caller can fetch more of
+//! the file and retry:
+//!
+//! ```
+//! use voikko_fst::{VfstSection, unweighted::UnweightedTransducer};
+//!
+//! let err = UnweightedTransducer::from_bytes(&[0x6E, 0x3A, 0x01, 0x00]).unwrap_err();
+//! let truncation = err.truncation().unwrap();
+//! assert_eq!(truncation.section, VfstSection::Header);
+//! assert_eq!(truncation.missing(), 12);
+//! ```
 
+pub mod compile;
 pub mod config;
+pub mod error_model;
 pub mod flags;
 pub mod format;
+pub mod iter;
 pub mod symbols;
+pub mod trace;
 pub mod transition;
 pub mod unweighted;
+pub mod validate;
 pub mod weighted;
 
+use validate::TransitionDefect;
+
 /// Error type for VFST parsing and loading.
-#[derive(Debug, thiserror::Error)]
+///
+/// [`section`](Self::section) tells which part of the file is damaged.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum VfstError {
     #[error("invalid magic number in VFST header")]
     InvalidMagic,
@@ -33,25 +63,125 @@ pub enum VfstError {
     TypeMismatch { expected: bool, actual: bool },
     #[error("invalid symbol table: {0}")]
     InvalidSymbolTable(String),
+    /// The file ends within the symbol table.
+    #[error("symbol table truncated: expected at least {expected} bytes, got {actual}")]
+    TruncatedSymbolTable { expected: usize, actual: usize },
     #[error("invalid flag diacritic: {0}")]
     InvalidFlagDiacritic(String),
     #[error("transition table alignment error")]
     AlignmentError,
+    /// The file ends before the first transition or within a transition.
+    #[error("transition table truncated: expected at least {expected} bytes, got {actual}")]
+    TruncatedTransitions { expected: usize, actual: usize },
+    /// A transition reachable from the start state would make traversal
+    /// index out of bounds. Only reported by strict loading
+    /// (`from_bytes_strict`).
+    #[error("corrupt transition {index}: {defect}")]
+    CorruptTransition {
+        index: usize,
+        defect: TransitionDefect,
+    },
+}
+
+/// The part of a VFST file a [`VfstError`] was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VfstSection {
+    /// The 16-byte header: magic number and weighted flag.
+    Header,
+    /// The symbol table, including flag diacritics.
+    SymbolTable,
+    /// The transition table.
+    Transitions,
+}
+
+impl VfstError {
+    /// Return the part of the file the error was found in.
+    pub fn section(&self) -> VfstSection {
+        match self {
+            Self::InvalidMagic | Self::TooShort { .. } | Self::TypeMismatch { .. } => {
+                VfstSection::Header
+            }
+            Self::InvalidSymbolTable(_)
+            | Self::TruncatedSymbolTable { .. }
+            | Self::InvalidFlagDiacritic(_) => VfstSection::SymbolTable,
+            Self::AlignmentError
+            | Self::TruncatedTransitions { .. }
+            | Self::CorruptTransition { .. } => VfstSection::Transitions,
+        }
+    }
+
+    /// If the error says that the data ends too early, return where and by
+    /// how much. A file cut exactly between two transitions is not
+    /// recognized as truncated: it loads, and strict loading reports the
+    /// transitions that point past its end as corrupt.
+    pub fn truncation(&self) -> Option<Truncation> {
+        let (expected, actual) = match *self {
+            Self::TooShort { expected, actual }
+            | Self::TruncatedSymbolTable { expected, actual }
+            | Self::TruncatedTransitions { expected, actual } => (expected, actual),
+            _ => return None,
+        };
+        Some(Truncation {
+            section: self.section(),
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Where VFST data ends too early, from [`VfstError::truncation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Truncation {
+    /// The section the data ends in.
+    pub section: VfstSection,
+    /// The length the data needs at least; the whole file may be longer.
+    pub expected: usize,
+    /// The length of the data.
+    pub actual: usize,
+}
+
+impl Truncation {
+    /// The number of bytes missing at least.
+    pub fn missing(&self) -> usize {
+        self.expected.saturating_sub(self.actual)
+    }
 }
 
 /// Maximum number of outer-loop iterations in the traversal algorithm.
-/// Acts as a safety limit to prevent infinite loops.
+/// Acts as a safety limit to prevent infinite loops. This is the default
+/// [`max_steps`](config::TraversalBudget::max_steps) of a traversal budget.
 ///
 /// Origin: Transducer.hpp:57
 pub const MAX_LOOP_COUNT: u32 = 100_000;
 
+/// Result of [`Transducer::next_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NextStatus {
+    /// An output was written.
+    Output,
+    /// There are no more outputs.
+    Exhausted,
+    /// The traversal stopped on its [`TraversalBudget`](config::TraversalBudget)
+    /// or on the stack depth of the configuration; there may be more
+    /// outputs. After running out of `max_steps`, calling `next_status`
+    /// again continues the traversal with a fresh step budget.
+    BudgetExceeded,
+}
+
 /// Trait for transducer traversal, abstracting over weighted/unweighted variants.
 ///
 /// The `prepare` + `next` pattern is a coroutine-like interface: `prepare` sets up
 /// the configuration for a new input, and each `next` call yields one output string.
+/// [`outputs`](Self::outputs) wraps the pattern in an [`Iterator`].
 pub trait Transducer {
     type Config;
 
+    /// Create a new configuration suitable for this transducer.
+    ///
+    /// `buffer_size` is the maximum traversal stack depth, which also bounds
+    /// the input length.
+    fn new_config(&self, buffer_size: usize) -> Self::Config;
+
     /// Prepare the configuration for traversing with the given input characters.
     ///
     /// Returns `true` if all input characters are known symbols.
@@ -61,9 +191,38 @@ pub trait Transducer {
     /// and no traversal is possible.
     fn prepare(&self, config: &mut Self::Config, input: &[char]) -> bool;
 
+    /// Prepare the configuration for traversing with the given UTF-8 input.
+    ///
+    /// Equivalent to [`prepare`](Self::prepare) but tokenizes the string
+    /// directly, without collecting it into a `char` buffer first. Multi-character
+    /// symbols of the transducer (e.g. `[Ln]`) are recognized in the input;
+    /// see [`SymbolTable::match_symbol`](symbols::SymbolTable::match_symbol).
+    ///
+    /// Returns `false` if the input has too many symbols for the
+    /// configuration; traversal then yields nothing.
+    fn prepare_str(&self, config: &mut Self::Config, input: &str) -> bool;
+
+    /// Yield the next output from the transducer, telling the end of the
+    /// outputs from a traversal stopped by its budget.
+    fn next_status(&self, config: &mut Self::Config, output: &mut String) -> NextStatus;
+
     /// Yield the next output from the transducer.
     ///
     /// Returns `true` if an output was found, `false` if no more outputs exist
-    /// (or if the loop limit was reached).
-    fn next(&self, config: &mut Self::Config, output: &mut String) -> bool;
+    /// (or if the traversal budget was exceeded; see
+    /// [`next_status`](Self::next_status)).
+    fn next(&self, config: &mut Self::Config, output: &mut String) -> bool {
+        self.next_status(config, output) == NextStatus::Output
+    }
+
+    /// Return an iterator over all outputs for the given input.
+    ///
+    /// The iterator owns a configuration sized for the input and yields
+    /// nothing if the input contains unknown symbols.
+    fn outputs(&self, input: &[char]) -> iter::OutputIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        iter::OutputIter::new(self, input)
+    }
 }
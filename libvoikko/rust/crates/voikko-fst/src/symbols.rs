@@ -2,9 +2,16 @@
 // Origin: UnweightedTransducer.cpp:125-189, WeightedTransducer.cpp:130-194
 
 use crate::VfstError;
-use crate::flags::{FlagDiacriticParser, OpFeatureValue};
+use crate::flags::{
+    self, FLAG_VALUE_ANY, FLAG_VALUE_NEUTRAL, FlagCheckResult, FlagDiacritic, FlagDiacriticParser,
+    FlagDiagnostic, FlagDiagnosticKind, FlagEvent, FlagFeature, FlagOp, OpFeatureValue,
+};
 use hashbrown::HashMap;
 
+/// Characters below this code point are looked up in a dense table instead of
+/// `char_to_symbol`. The range covers the Latin, Greek and Cyrillic blocks.
+const DENSE_CHAR_LIMIT: usize = 0x800;
+
 /// Parsed symbol table from a VFST binary file.
 ///
 /// Symbols are ordered in the binary as:
@@ -13,6 +20,31 @@ use hashbrown::HashMap;
 /// 3. Normal single-character symbols -- regular characters (e.g., `a`, `b`)
 /// 4. Multi-character symbols -- strings starting with `[` (e.g., `[Ln]`, `[Bc]`)
 ///
+/// The index of a symbol is the value stored in transitions.
+/// [`symbol_index`](Self::symbol_index) and [`char_index`](Self::char_index)
+/// look symbols up, [`symbol_string`](Self::symbol_string) and
+/// [`get`](Self::get) go the other way, [`iter`](Self::iter) lists the
+/// table and [`flag_diacritic`](Self::flag_diacritic) decomposes a flag
+/// diacritic symbol:
+///
+/// ```
+/// use voikko_fst::flags::FlagOp;
+/// use voikko_fst::symbols::{SymbolKind, parse_symbol_table};
+///
+/// // Symbol count, then NUL-terminated symbols
+/// let data = b"\x04\x00\x00@U.CASE.NOM@\x00a\x00[Ln]\x00";
+/// let (table, _) = parse_symbol_table(data, 0).unwrap();
+///
+/// assert_eq!(table.symbol_index("[Ln]"), Some(3));
+/// assert_eq!(table.char_index('a'), Some(2));
+/// assert_eq!(table.symbol_string(1), "@U.CASE.NOM@");
+/// assert_eq!(table.kind(3), Some(SymbolKind::MultiChar));
+///
+/// let flag = table.flag_diacritic(1).unwrap();
+/// assert_eq!((flag.op, flag.feature, flag.value), (FlagOp::U, "CASE", Some("NOM")));
+/// assert_eq!(table.iter().count(), 4);
+/// ```
+///
 /// Origin: UnweightedTransducer.cpp:125-189
 pub struct SymbolTable {
     /// Maps symbol index to its string representation.
@@ -30,6 +62,310 @@ pub struct SymbolTable {
     pub first_multi_char: u16,
     /// Number of distinct flag diacritic features.
     pub flag_feature_count: u16,
+    /// Flag diacritic feature names, indexed by feature.
+    pub flag_feature_names: Vec<String>,
+    /// Flag diacritic value names, indexed by value (`""` is neutral, `"@"`
+    /// any value).
+    pub flag_value_names: Vec<String>,
+    /// Symbol index of each character below `DENSE_CHAR_LIMIT`, 0 if the
+    /// character is not a symbol. Built from `char_to_symbol` at load time.
+    char_table: Vec<u16>,
+    /// Maps the epsilon, flag diacritic and multi-character symbol strings
+    /// to their indices.
+    string_to_symbol: HashMap<String, u16>,
+    /// Maps the first character of each multi-character symbol to the
+    /// indices of the symbols starting with it, longest first.
+    multi_char_prefixes: HashMap<char, Vec<u16>>,
+}
+
+/// The kind of a symbol, given by its position in the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// The empty symbol (index 0).
+    Epsilon,
+    /// A flag diacritic such as `@P.CASE.NOM@`.
+    FlagDiacritic,
+    /// A single character matched against the input.
+    Char,
+    /// A multi-character symbol such as `[Ln]`.
+    MultiChar,
+}
+
+/// A symbol of a [`SymbolTable`] with its index and kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolInfo<'a> {
+    /// Index of the symbol, as used in transitions.
+    pub index: u16,
+    /// The symbol string (empty for epsilon).
+    pub text: &'a str,
+    /// Kind of the symbol.
+    pub kind: SymbolKind,
+}
+
+/// Counts and sizes of a [`SymbolTable`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SymbolTableStats {
+    /// Number of symbols, including epsilon.
+    pub count: usize,
+    /// Number of flag diacritic symbols.
+    pub flag_diacritics: usize,
+    /// Number of distinct flag diacritic features.
+    pub flag_features: usize,
+    /// Number of single-character symbols.
+    pub chars: usize,
+    /// Number of multi-character symbols.
+    pub multi_chars: usize,
+    /// Length of the longest symbol in UTF-8 bytes.
+    pub max_utf8_len: usize,
+    /// Length of the longest symbol in characters.
+    pub max_char_len: usize,
+}
+
+impl SymbolTable {
+    /// Number of symbols, including epsilon.
+    pub fn len(&self) -> usize {
+        self.symbol_strings.len()
+    }
+
+    /// Return `true` if the table has no symbols, not even epsilon.
+    pub fn is_empty(&self) -> bool {
+        self.symbol_strings.is_empty()
+    }
+
+    /// Kind of the symbol at `index`, or `None` if out of range.
+    pub fn kind(&self, index: u16) -> Option<SymbolKind> {
+        let i = index as usize;
+        if i >= self.symbol_strings.len() {
+            None
+        } else if index == 0 {
+            Some(SymbolKind::Epsilon)
+        } else if self.first_normal_char == 0 || index < self.first_normal_char {
+            Some(SymbolKind::FlagDiacritic)
+        } else if index >= self.first_multi_char {
+            Some(SymbolKind::MultiChar)
+        } else {
+            Some(SymbolKind::Char)
+        }
+    }
+
+    /// The symbol at `index`, or `None` if out of range.
+    pub fn get(&self, index: u16) -> Option<SymbolInfo<'_>> {
+        Some(SymbolInfo {
+            index,
+            text: self.symbol_strings.get(index as usize)?,
+            kind: self.kind(index)?,
+        })
+    }
+
+    /// Symbol index of the single-character symbol `ch`, or `None` if `ch`
+    /// is not a symbol. Common characters are found without hashing.
+    #[inline]
+    pub fn char_index(&self, ch: char) -> Option<u16> {
+        match self.char_table.get(ch as usize) {
+            Some(&0) => None,
+            Some(&index) => Some(index),
+            None => self.char_to_symbol.get(&ch).copied(),
+        }
+    }
+
+    /// Index of the symbol whose string is exactly `symbol`, of any kind
+    /// (`""` is epsilon), or `None` if there is no such symbol.
+    pub fn symbol_index(&self, symbol: &str) -> Option<u16> {
+        let mut chars = symbol.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            if let Some(index) = self.char_index(ch) {
+                return Some(index);
+            }
+        }
+        self.string_to_symbol.get(symbol).copied()
+    }
+
+    /// The string of the symbol at `index` (empty for epsilon).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range; see [`get`](Self::get) for a
+    /// checked lookup.
+    pub fn symbol_string(&self, index: u16) -> &str {
+        &self.symbol_strings[index as usize]
+    }
+
+    /// The flag diacritic operation of the symbol at `index`, or `None` if
+    /// the symbol is not a flag diacritic.
+    pub fn flag_diacritic(&self, index: u16) -> Option<FlagDiacritic<'_>> {
+        if self.kind(index)? != SymbolKind::FlagDiacritic {
+            return None;
+        }
+        let ofv = self.symbol_to_diacritic.get(index as usize)?;
+        Some(FlagDiacritic {
+            op: ofv.op,
+            feature: &self.flag_feature_names[ofv.feature as usize],
+            value: self.flag_value_name(ofv.value),
+        })
+    }
+
+    /// Iterate over all symbols in index order.
+    pub fn iter(&self) -> impl Iterator<Item = SymbolInfo<'_>> {
+        (0..self.symbol_strings.len()).filter_map(|i| self.get(i as u16))
+    }
+
+    /// Count the symbols by kind and measure the longest one.
+    pub fn stats(&self) -> SymbolTableStats {
+        let mut stats = SymbolTableStats {
+            count: self.len(),
+            flag_features: self.flag_feature_count as usize,
+            ..Default::default()
+        };
+        for symbol in self.iter() {
+            match symbol.kind {
+                SymbolKind::Epsilon => {}
+                SymbolKind::FlagDiacritic => stats.flag_diacritics += 1,
+                SymbolKind::Char => stats.chars += 1,
+                SymbolKind::MultiChar => stats.multi_chars += 1,
+            }
+            stats.max_utf8_len = stats.max_utf8_len.max(symbol.text.len());
+            stats.max_char_len = stats
+                .max_char_len
+                .max(self.symbol_lengths[symbol.index as usize]);
+        }
+        stats
+    }
+
+    /// Match the input symbol at the start of `input`.
+    ///
+    /// Multi-character symbols (e.g. `[Ln]` or `+Pl`) starting with the
+    /// first character are tried first, longest match wins; otherwise the
+    /// first character is looked up in `char_to_symbol`. Returns the symbol
+    /// index (`None` for an unknown character) and the number of bytes
+    /// consumed. `input` must not be empty.
+    pub fn match_symbol(&self, input: &str) -> (Option<u16>, usize) {
+        let ch = input.chars().next().expect("input must not be empty");
+        if let Some(candidates) = self.multi_char_prefixes.get(&ch) {
+            for &i in candidates {
+                let sym = &self.symbol_strings[i as usize];
+                if input.starts_with(sym.as_str()) {
+                    return (Some(i), sym.len());
+                }
+            }
+        }
+        (self.char_index(ch), ch.len_utf8())
+    }
+
+    /// List the flag diacritic features and the values their operations
+    /// name, in order of first appearance.
+    pub fn flag_features(&self) -> Vec<FlagFeature> {
+        let mut features: Vec<FlagFeature> = self
+            .flag_feature_names
+            .iter()
+            .map(|name| FlagFeature {
+                name: name.clone(),
+                values: Vec::new(),
+            })
+            .collect();
+        for ofv in self.symbol_to_diacritic.iter().skip(1) {
+            if let Some(value) = self.flag_value_name(ofv.value) {
+                let values = &mut features[ofv.feature as usize].values;
+                if !values.iter().any(|v| v == value) {
+                    values.push(value.to_string());
+                }
+            }
+        }
+        features
+    }
+
+    /// Check the flag diacritics for operations that can never pass or
+    /// have no effect: a value required or disallowed but never set, a
+    /// feature tested or cleared but never set, and a feature set but never
+    /// tested. The traversal accepts such tables, but the paths through an
+    /// `R` that can never pass are silently lost. Diagnostics are in symbol
+    /// order, with the features never tested last; a consistent table has
+    /// none.
+    pub fn validate_flags(&self) -> Vec<FlagDiagnostic> {
+        let flags: Vec<(u16, OpFeatureValue)> = self
+            .iter()
+            .filter(|s| s.kind == SymbolKind::FlagDiacritic)
+            .filter_map(|s| Some((s.index, *self.symbol_to_diacritic.get(s.index as usize)?)))
+            .collect();
+        let feature_count = self.flag_feature_names.len();
+        let mut set_values: Vec<Vec<u16>> = vec![Vec::new(); feature_count];
+        let mut first_set: Vec<Option<u16>> = vec![None; feature_count];
+        let mut tested = vec![false; feature_count];
+        for &(index, ofv) in &flags {
+            let feature = ofv.feature as usize;
+            if matches!(ofv.op, FlagOp::P | FlagOp::U) {
+                set_values[feature].push(ofv.value);
+                first_set[feature].get_or_insert(index);
+            }
+            if matches!(ofv.op, FlagOp::R | FlagOp::D | FlagOp::U) {
+                tested[feature] = true;
+            }
+        }
+
+        let diagnostic = |kind, index: u16, ofv: &OpFeatureValue| FlagDiagnostic {
+            kind,
+            symbol: self.symbol_strings[index as usize].clone(),
+            op: ofv.op,
+            feature: self.flag_feature_names[ofv.feature as usize].clone(),
+            value: self.flag_value_name(ofv.value).map(str::to_string),
+        };
+        let mut diagnostics = Vec::new();
+        for (index, ofv) in &flags {
+            let set = &set_values[ofv.feature as usize];
+            let kind = match ofv.op {
+                FlagOp::R | FlagOp::D | FlagOp::C if set.is_empty() => {
+                    FlagDiagnosticKind::FeatureNeverSet
+                }
+                FlagOp::R | FlagOp::D
+                    if ofv.value != FLAG_VALUE_ANY
+                        && !set.contains(&ofv.value)
+                        && !set.contains(&FLAG_VALUE_ANY) =>
+                {
+                    FlagDiagnosticKind::ValueNeverSet
+                }
+                _ => continue,
+            };
+            diagnostics.push(diagnostic(kind, *index, ofv));
+        }
+        for (feature, first) in first_set.iter().enumerate() {
+            if let (Some(index), false) = (*first, tested[feature]) {
+                let ofv = &self.symbol_to_diacritic[index as usize];
+                diagnostics.push(diagnostic(
+                    FlagDiagnosticKind::FeatureNeverTested,
+                    index,
+                    ofv,
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    /// Apply the flag diacritic `symbol` of an accepted path to the feature
+    /// `values`, describing the operation and its effect.
+    pub(crate) fn flag_event(&self, values: &mut [u16], symbol: u16) -> FlagEvent {
+        let ofv = &self.symbol_to_diacritic[symbol as usize];
+        let before = values[ofv.feature as usize];
+        let after = match flags::check_flag(ofv, before) {
+            FlagCheckResult::AcceptAndUpdate { value, .. } => value,
+            FlagCheckResult::AcceptNoUpdate { .. } | FlagCheckResult::Reject => before,
+        };
+        values[ofv.feature as usize] = after;
+        let name = |value: u16| self.flag_value_name(value).map(str::to_string);
+        FlagEvent {
+            op: ofv.op,
+            feature: self.flag_feature_names[ofv.feature as usize].clone(),
+            value: name(ofv.value),
+            before: name(before),
+            after: name(after),
+        }
+    }
+
+    /// Name of a flag value, or `None` for the neutral and the any value.
+    fn flag_value_name(&self, value: u16) -> Option<&str> {
+        match value {
+            FLAG_VALUE_NEUTRAL | FLAG_VALUE_ANY => None,
+            _ => Some(&self.flag_value_names[value as usize]),
+        }
+    }
 }
 
 /// Parse the symbol table from the VFST binary data starting at offset 16 (after header).
@@ -41,7 +377,7 @@ pub struct SymbolTable {
 /// Origin: UnweightedTransducer.cpp:125-189, WeightedTransducer.cpp:130-194
 pub fn parse_symbol_table(data: &[u8], offset: usize) -> Result<(SymbolTable, usize), VfstError> {
     if offset + 2 > data.len() {
-        return Err(VfstError::TooShort {
+        return Err(VfstError::TruncatedSymbolTable {
             expected: offset + 2,
             actual: data.len(),
         });
@@ -66,9 +402,12 @@ pub fn parse_symbol_table(data: &[u8], offset: usize) -> Result<(SymbolTable, us
             pos += 1;
         }
         if pos >= data.len() {
-            return Err(VfstError::InvalidSymbolTable(
-                "unterminated symbol string".to_string(),
-            ));
+            // The terminator of this symbol and at least one byte for each
+            // symbol after it are missing
+            return Err(VfstError::TruncatedSymbolTable {
+                expected: data.len() + usize::from(symbol_count - i),
+                actual: data.len(),
+            });
         }
 
         let symbol_bytes = &data[str_start..pos];
@@ -120,6 +459,37 @@ pub fn parse_symbol_table(data: &[u8], offset: usize) -> Result<(SymbolTable, us
     }
 
     let flag_feature_count = flag_parser.feature_count();
+    let (flag_feature_names, flag_value_names) = flag_parser.into_names();
+
+    let mut string_to_symbol = HashMap::new();
+    for (i, symbol) in symbol_strings.iter().enumerate() {
+        let i = i as u16;
+        let is_char = first_normal_char > 0 && i >= first_normal_char && i < first_multi_char;
+        if !is_char {
+            string_to_symbol.entry(symbol.clone()).or_insert(i);
+        }
+    }
+
+    let mut multi_char_prefixes: HashMap<char, Vec<u16>> = HashMap::new();
+    if first_normal_char > 0 {
+        for i in first_normal_char..symbol_count {
+            let symbol = &symbol_strings[i as usize];
+            if symbol_lengths[i as usize] > 1 {
+                let first = symbol.chars().next().expect("symbol is not empty");
+                multi_char_prefixes.entry(first).or_default().push(i);
+            }
+        }
+    }
+    for candidates in multi_char_prefixes.values_mut() {
+        candidates.sort_by_key(|&i| std::cmp::Reverse(symbol_strings[i as usize].len()));
+    }
+
+    let mut char_table = vec![0; DENSE_CHAR_LIMIT];
+    for (&ch, &index) in &char_to_symbol {
+        if let Some(slot) = char_table.get_mut(ch as usize) {
+            *slot = index;
+        }
+    }
 
     Ok((
         SymbolTable {
@@ -130,6 +500,11 @@ pub fn parse_symbol_table(data: &[u8], offset: usize) -> Result<(SymbolTable, us
             first_normal_char,
             first_multi_char,
             flag_feature_count,
+            flag_feature_names,
+            flag_value_names,
+            char_table,
+            string_to_symbol,
+            multi_char_prefixes,
         },
         pos,
     ))
@@ -195,6 +570,236 @@ mod tests {
         assert!(!table.char_to_symbol.contains_key(&'['));
     }
 
+    #[test]
+    fn symbols_are_listed_with_kinds() {
+        let data = make_symbol_table(&["", "@P.CASE.NOM@", "@C.NUM@", "a", "ä", "[Ln]", "[Bc]"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        let kinds: Vec<(u16, &str, SymbolKind)> =
+            table.iter().map(|s| (s.index, s.text, s.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (0, "", SymbolKind::Epsilon),
+                (1, "@P.CASE.NOM@", SymbolKind::FlagDiacritic),
+                (2, "@C.NUM@", SymbolKind::FlagDiacritic),
+                (3, "a", SymbolKind::Char),
+                (4, "ä", SymbolKind::Char),
+                (5, "[Ln]", SymbolKind::MultiChar),
+                (6, "[Bc]", SymbolKind::MultiChar),
+            ]
+        );
+        assert_eq!(table.get(7), None);
+        assert_eq!(
+            table.stats(),
+            SymbolTableStats {
+                count: 7,
+                flag_diacritics: 2,
+                flag_features: 2,
+                chars: 2,
+                multi_chars: 2,
+                max_utf8_len: 12,
+                max_char_len: 12,
+            }
+        );
+    }
+
+    #[test]
+    fn table_of_only_flags_and_chars() {
+        let data = make_symbol_table(&["", "@D.X@"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        assert_eq!(table.kind(1), Some(SymbolKind::FlagDiacritic));
+
+        let data = make_symbol_table(&["", "ä", "b"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        let stats = table.stats();
+        assert_eq!((stats.chars, stats.multi_chars), (2, 0));
+        assert_eq!((stats.max_utf8_len, stats.max_char_len), (2, 1));
+    }
+
+    #[test]
+    fn flag_features_list_names_and_values() {
+        let data = make_symbol_table(&[
+            "",
+            "@P.CASE.NOM@",
+            "@R.CASE.GEN@",
+            "@C.NUM@",
+            "@D.CASE.NOM@",
+            "@R.NUM@",
+            "a",
+        ]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        let features = table.flag_features();
+        assert_eq!(
+            features,
+            [
+                FlagFeature {
+                    name: "CASE".to_string(),
+                    values: vec!["NOM".to_string(), "GEN".to_string()],
+                },
+                FlagFeature {
+                    name: "NUM".to_string(),
+                    values: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_flags_reports_inconsistent_features() {
+        let data = make_symbol_table(&[
+            "",
+            "@P.CASE.NOM@",
+            "@R.CASE.GEN@",
+            "@D.CASE.NOM@",
+            "@R.NUM@",
+            "@C.NUM@",
+            "@D.PERS.1@",
+            "@P.MOOD.IMP@",
+            "@C.MOOD@",
+            "@U.DEG.POS@",
+            "@R.DEG.POS@",
+            "a",
+        ]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        let diagnostics = table.validate_flags();
+        let found: Vec<(FlagDiagnosticKind, &str, bool)> = diagnostics
+            .iter()
+            .map(|d| (d.kind, d.symbol.as_str(), d.is_error()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (FlagDiagnosticKind::ValueNeverSet, "@R.CASE.GEN@", true),
+                (FlagDiagnosticKind::FeatureNeverSet, "@R.NUM@", true),
+                (FlagDiagnosticKind::FeatureNeverSet, "@C.NUM@", false),
+                (FlagDiagnosticKind::FeatureNeverSet, "@D.PERS.1@", false),
+                (
+                    FlagDiagnosticKind::FeatureNeverTested,
+                    "@P.MOOD.IMP@",
+                    false
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[0].feature, "CASE");
+        assert_eq!(diagnostics[0].value.as_deref(), Some("GEN"));
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "@R.CASE.GEN@: value GEN of feature CASE is never set; \
+             paths through it are never accepted"
+        );
+        assert_eq!(diagnostics[1].value, None);
+    }
+
+    #[test]
+    fn validate_flags_accepts_consistent_tables() {
+        let data = make_symbol_table(&[
+            "",
+            "@P.CASE.NOM@",
+            "@R.CASE.NOM@",
+            "@D.CASE@",
+            "@C.CASE@",
+            "a",
+        ]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        assert!(table.validate_flags().is_empty());
+        let (table, _) = parse_symbol_table(&make_symbol_table(&["", "a"]), 0).unwrap();
+        assert!(table.validate_flags().is_empty());
+    }
+
+    #[test]
+    fn flag_event_replays_the_operation() {
+        let data = make_symbol_table(&["", "@P.CASE.NOM@", "@C.CASE@", "@R.CASE@", "a"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+        let mut values = vec![FLAG_VALUE_NEUTRAL; 1];
+
+        let set = table.flag_event(&mut values, 1);
+        assert_eq!(set.op, flags::FlagOp::P);
+        assert_eq!(set.feature, "CASE");
+        assert_eq!(set.value.as_deref(), Some("NOM"));
+        assert_eq!(set.before, None);
+        assert_eq!(set.after.as_deref(), Some("NOM"));
+
+        let require = table.flag_event(&mut values, 3);
+        assert_eq!(require.value, None);
+        assert_eq!(require.after.as_deref(), Some("NOM"));
+
+        let clear = table.flag_event(&mut values, 2);
+        assert_eq!(clear.before.as_deref(), Some("NOM"));
+        assert_eq!(clear.after, None);
+    }
+
+    #[test]
+    fn match_symbol_single_and_multi_char() {
+        let data = make_symbol_table(&["", "a", "[Ln]", "[Lnx]"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+
+        assert_eq!(table.match_symbol("ab"), (Some(1), 1));
+        assert_eq!(table.match_symbol("[Ln]a"), (Some(2), 4));
+        // Longest multi-char symbol wins
+        assert_eq!(table.match_symbol("[Lnx]"), (Some(3), 5));
+        // No multi-char symbol matches: '[' is an unknown single character
+        assert_eq!(table.match_symbol("[Bc]"), (None, 1));
+        // Unknown character consumes its full UTF-8 length
+        assert_eq!(table.match_symbol("äa"), (None, 2));
+    }
+
+    #[test]
+    fn match_symbol_without_brackets() {
+        let data = make_symbol_table(&["", "a", "+", "[Ln]", "+Pl", "+Pl+Sg", "<N>"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+
+        assert_eq!(table.match_symbol("+Pl+Sg"), (Some(5), 6));
+        assert_eq!(table.match_symbol("+Pl+Px"), (Some(4), 3));
+        assert_eq!(table.match_symbol("<N>a"), (Some(6), 3));
+        // A prefix of a multi-char symbol is matched as single characters
+        assert_eq!(table.match_symbol("+P"), (Some(2), 1));
+        assert_eq!(table.match_symbol("<a"), (None, 1));
+    }
+
+    #[test]
+    fn char_index_dense_and_hashed() {
+        let data = make_symbol_table(&["", "a", "ä", "\u{3042}", "[Ln]"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+
+        assert_eq!(table.char_index('a'), Some(1));
+        assert_eq!(table.char_index('ä'), Some(2));
+        // Above the dense range: looked up in `char_to_symbol`
+        assert_eq!(table.char_index('\u{3042}'), Some(3));
+        assert_eq!(table.char_index('b'), None);
+        assert_eq!(table.char_index('\u{3044}'), None);
+    }
+
+    #[test]
+    fn symbol_lookup_both_ways() {
+        let data = make_symbol_table(&["", "@C.CASE@", "@R.CASE.NOM@", "a", "[Ln]"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+
+        for info in table.iter() {
+            assert_eq!(table.symbol_index(info.text), Some(info.index));
+            assert_eq!(table.symbol_string(info.index), info.text);
+        }
+        assert_eq!(table.symbol_index("b"), None);
+        assert_eq!(table.symbol_index("[Lx]"), None);
+    }
+
+    #[test]
+    fn flag_diacritic_classification() {
+        let data = make_symbol_table(&["", "@C.CASE@", "@R.CASE.NOM@", "a"]);
+        let (table, _) = parse_symbol_table(&data, 0).unwrap();
+
+        let clear = table.flag_diacritic(1).unwrap();
+        assert_eq!(
+            (clear.op, clear.feature, clear.value),
+            (flags::FlagOp::C, "CASE", None)
+        );
+        let require = table.flag_diacritic(2).unwrap();
+        assert_eq!(require.op, flags::FlagOp::R);
+        assert_eq!(require.value, Some("NOM"));
+        assert_eq!(table.flag_diacritic(0), None);
+        assert_eq!(table.flag_diacritic(3), None);
+        assert_eq!(table.flag_diacritic(9), None);
+    }
+
     #[test]
     fn parse_epsilon_only() {
         let data = make_symbol_table(&[""]);
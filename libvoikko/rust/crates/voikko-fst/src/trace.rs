@@ -0,0 +1,47 @@
+// Path traces of accepted outputs, for debugging transducers.
+
+use crate::flags::FlagEvent;
+
+/// One transition on an accepted path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    /// State the transition leaves from.
+    pub source_state: u32,
+    /// State the transition leads to.
+    pub target_state: u32,
+    /// Input symbol: a character, a multi-character symbol, a flag
+    /// diacritic such as `@P.CASE.NOM@`, or empty for epsilon.
+    pub input: String,
+    /// Output symbol, or empty for epsilon and flag diacritics.
+    pub output: String,
+    /// Whether the input symbol is a flag diacritic.
+    pub is_flag: bool,
+    /// For a flag diacritic, the operation and how it changed the feature.
+    pub flag: Option<FlagEvent>,
+    /// Weight of the transition (always 0 in unweighted transducers).
+    pub weight: i16,
+}
+
+/// The path of an accepted output, as returned by `trace` right after
+/// `next` yielded the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTrace {
+    /// Transitions from the start state to the final state.
+    pub steps: Vec<TraceStep>,
+    /// The final state.
+    pub final_state: u32,
+    /// Weight of the final state (always 0 in unweighted transducers).
+    pub final_weight: i16,
+}
+
+impl PathTrace {
+    /// The flag diacritic operations that fired on the path, in order.
+    pub fn flag_events(&self) -> impl Iterator<Item = &FlagEvent> {
+        self.steps.iter().filter_map(|s| s.flag.as_ref())
+    }
+
+    /// Total weight of the path: the transition weights plus the final weight.
+    pub fn weight(&self) -> i32 {
+        self.steps.iter().map(|s| i32::from(s.weight)).sum::<i32>() + i32::from(self.final_weight)
+    }
+}
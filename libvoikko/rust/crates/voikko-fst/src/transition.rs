@@ -1,6 +1,9 @@
 // Transition and WeightedTransition structs for zero-copy access to VFST binary data.
 // Origin: Transition.hpp, WeightedTransition.hpp
 
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
 use bytemuck::{Pod, Zeroable};
 
 /// Unweighted transition (8 bytes).
@@ -127,6 +130,62 @@ pub fn weighted_max_tc(transitions: &[WeightedTransition], state_index: u32) ->
     }
 }
 
+/// The transition table of a loaded transducer.
+///
+/// The table is a view into the shared VFST data, so that every transducer
+/// built from the same `Arc<[u8]>` uses one copy of it. Data that is not
+/// aligned for `T` is copied instead.
+pub(crate) enum TransitionTable<T> {
+    /// `range` of `data` holds the transitions.
+    Shared {
+        data: Arc<[u8]>,
+        range: Range<usize>,
+    },
+    /// An aligned copy of the transitions.
+    Copied(Vec<T>),
+}
+
+impl<T: Pod> TransitionTable<T> {
+    /// The `count` transitions at `offset` in `data`.
+    pub(crate) fn new(data: &Arc<[u8]>, offset: usize, count: usize) -> Self {
+        let range = offset..offset + count * size_of::<T>();
+        let bytes = &data[range.clone()];
+        if bytemuck::try_cast_slice::<u8, T>(bytes).is_ok() {
+            Self::Shared {
+                data: Arc::clone(data),
+                range,
+            }
+        } else {
+            let mut transitions = vec![T::zeroed(); count];
+            bytemuck::cast_slice_mut::<T, u8>(&mut transitions).copy_from_slice(bytes);
+            Self::Copied(transitions)
+        }
+    }
+
+    /// The transitions as a slice. Traversal takes the slice once rather
+    /// than indexing the table.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        match self {
+            Self::Shared { data, range } => bytemuck::cast_slice(&data[range.clone()]),
+            Self::Copied(transitions) => transitions,
+        }
+    }
+
+    /// Whether the table is a view into the shared data.
+    pub(crate) fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared { .. })
+    }
+}
+
+impl<T: Pod> Deref for TransitionTable<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
 // Static assertions for struct sizes
 const _: () = assert!(size_of::<Transition>() == 8);
 const _: () = assert!(size_of::<OverflowCell>() == 8);
@@ -294,6 +353,30 @@ mod tests {
         assert_eq!(unweighted_max_tc(&transitions, 0), 301);
     }
 
+    #[test]
+    fn transition_table_copies_misaligned_data() {
+        let t = Transition {
+            sym_in: 3,
+            sym_out: 4,
+            trans_info: 5,
+        };
+        let mut bytes = vec![0u8; 9];
+        bytes[1..].copy_from_slice(bytemuck::bytes_of(&t));
+        let data: Arc<[u8]> = bytes.into();
+        // Arc data is aligned to at least 4 bytes, so offset 1 is not
+        let copied = TransitionTable::<Transition>::new(&data, 1, 1);
+        assert!(!copied.is_shared());
+        assert_eq!(copied[0].sym_in, 3);
+        assert_eq!(copied[0].trans_info, 5);
+
+        let mut bytes = vec![0u8; 16];
+        bytes[8..].copy_from_slice(bytemuck::bytes_of(&t));
+        let data: Arc<[u8]> = bytes.into();
+        let shared = TransitionTable::<Transition>::new(&data, 8, 1);
+        assert!(shared.is_shared());
+        assert_eq!(shared[0].sym_out, 4);
+    }
+
     #[test]
     fn weighted_max_tc_simple() {
         let transitions = vec![WeightedTransition {
@@ -1,22 +1,33 @@
 // Unweighted transducer loading and traversal.
 // Origin: UnweightedTransducer.cpp
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
 use crate::config::UnweightedConfig;
-use crate::flags::{self, FlagCheckResult};
+use crate::flags::{self, FLAG_VALUE_NEUTRAL, FlagCheckResult, FlagDiagnostic, FlagFeature};
 use crate::format::{self, HEADER_SIZE};
 use crate::symbols::{self, SymbolTable};
-use crate::transition::{Transition, UNWEIGHTED_FINAL_SYM, unweighted_max_tc};
-use crate::{MAX_LOOP_COUNT, Transducer, VfstError};
+use crate::trace::{PathTrace, TraceStep};
+use crate::transition::{Transition, TransitionTable, UNWEIGHTED_FINAL_SYM, unweighted_max_tc};
+use crate::validate;
+use crate::{NextStatus, Transducer, VfstError};
+
+/// Maximum length in transitions of the paths followed by
+/// [`UnweightedTransducer::enumerate_outputs`].
+pub const ENUMERATION_MAX_PATH_LENGTH: usize = 200;
 
 /// Unweighted VFST transducer.
 ///
-/// Loaded from a `&[u8]` slice (the raw binary VFST data), this struct
-/// provides the `prepare`/`next` traversal interface.
+/// Loaded from the raw binary VFST data, this struct provides the
+/// `prepare`/`next` traversal interface. Transducers loaded with
+/// [`from_shared`](Self::from_shared) from the same data share its
+/// transition table.
 ///
 /// Origin: UnweightedTransducer.hpp, UnweightedTransducer.cpp
 pub struct UnweightedTransducer {
-    /// The transition table as a zero-copy slice of the backing data.
-    transitions: Vec<Transition>,
+    /// The transition table, a view into the backing data.
+    transitions: TransitionTable<Transition>,
     /// Symbol table.
     symbols: SymbolTable,
     /// Sentinel symbol index for unknown input characters.
@@ -38,22 +49,47 @@ impl UnweightedTransducer {
     /// Load an unweighted transducer from raw VFST binary data.
     ///
     /// The data is typically loaded from a `mor.vfst` or `autocorr.vfst` file.
-    /// The transition table is copied into an owned `Vec<Transition>` for
-    /// alignment safety (the source `&[u8]` may not be 8-byte aligned).
+    /// It is copied once; use [`from_shared`](Self::from_shared) to load
+    /// several transducers from one copy.
     ///
     /// Origin: UnweightedTransducer::UnweightedTransducer() -- UnweightedTransducer.cpp:125-189
     pub fn from_bytes(data: &[u8]) -> Result<Self, VfstError> {
-        let header = format::parse_header(data)?;
+        Self::from_shared(Arc::from(data))
+    }
+
+    /// Load an unweighted transducer from shared VFST data without copying
+    /// it. The transducer keeps `data` alive and reads its transitions in
+    /// place, unless the data is not aligned for them, in which case they
+    /// are copied.
+    pub fn from_shared(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        let header = format::parse_header(&data)?;
         if header.weighted {
             return Err(VfstError::TypeMismatch {
                 expected: false,
                 actual: true,
             });
         }
-        Self::from_bytes_inner(data)
+        Self::from_shared_inner(&data)
     }
 
-    fn from_bytes_inner(data: &[u8]) -> Result<Self, VfstError> {
+    /// Load an unweighted transducer from untrusted data.
+    ///
+    /// Like [`from_bytes`](Self::from_bytes), but also checks the target
+    /// state, symbols and extent of every transition reachable from the
+    /// start state, failing with [`VfstError::CorruptTransition`]. No input
+    /// can then make a traversal index out of bounds, whatever the data.
+    /// The check visits each reachable transition once.
+    pub fn from_bytes_strict(data: &[u8]) -> Result<Self, VfstError> {
+        let transducer = Self::from_bytes(data)?;
+        validate::validate_unweighted(
+            &transducer.transitions,
+            transducer.symbols.symbol_strings.len(),
+        )?;
+        Ok(transducer)
+    }
+
+    fn from_shared_inner(shared: &Arc<[u8]>) -> Result<Self, VfstError> {
+        let data: &[u8] = shared;
         let (symbols, sym_end) = symbols::parse_symbol_table(data, HEADER_SIZE)?;
 
         // Align to 8-byte boundary (sizeof(Transition))
@@ -65,8 +101,8 @@ impl UnweightedTransducer {
         };
 
         if transition_offset > data.len() {
-            return Err(VfstError::TooShort {
-                expected: transition_offset,
+            return Err(VfstError::TruncatedTransitions {
+                expected: transition_offset + size_of::<Transition>(),
                 actual: data.len(),
             });
         }
@@ -74,25 +110,16 @@ impl UnweightedTransducer {
         let remaining = &data[transition_offset..];
         let transition_count = remaining.len() / size_of::<Transition>();
 
-        if transition_count == 0 {
-            return Err(VfstError::TooShort {
-                expected: transition_offset + size_of::<Transition>(),
+        // A file cut within a transition ends in a partial transition
+        if transition_count == 0 || remaining.len() % size_of::<Transition>() != 0 {
+            return Err(VfstError::TruncatedTransitions {
+                expected: transition_offset + (transition_count + 1) * size_of::<Transition>(),
                 actual: data.len(),
             });
         }
 
-        // Copy transition data into an aligned Vec<Transition> for safety.
-        // The source slice may not be properly aligned for zero-copy cast.
-        let mut transitions = vec![
-            Transition {
-                sym_in: 0,
-                sym_out: 0,
-                trans_info: 0
-            };
-            transition_count
-        ];
-        let dst_bytes = bytemuck::cast_slice_mut::<Transition, u8>(&mut transitions);
-        dst_bytes.copy_from_slice(&remaining[..transition_count * size_of::<Transition>()]);
+        let transitions =
+            TransitionTable::<Transition>::new(shared, transition_offset, transition_count);
 
         let unknown_symbol_ordinal = symbols.symbol_strings.len() as u16;
 
@@ -108,16 +135,68 @@ impl UnweightedTransducer {
         &self.symbols
     }
 
+    /// Whether the transition table is read in place from the data the
+    /// transducer was loaded from, rather than copied.
+    pub fn shares_data(&self) -> bool {
+        self.transitions.is_shared()
+    }
+
     /// Return the number of flag diacritic features.
     pub fn flag_feature_count(&self) -> u16 {
         self.symbols.flag_feature_count
     }
 
+    /// List the flag diacritic features and their values; see
+    /// [`SymbolTable::flag_features`].
+    pub fn flag_features(&self) -> Vec<FlagFeature> {
+        self.symbols.flag_features()
+    }
+
+    /// Check the flag diacritics for inconsistent features; see
+    /// [`SymbolTable::validate_flags`]. Loading does not run the check.
+    pub fn validate_flags(&self) -> Vec<FlagDiagnostic> {
+        self.symbols.validate_flags()
+    }
+
     /// Create a new configuration suitable for this transducer.
     pub fn new_config(&self, buffer_size: usize) -> UnweightedConfig {
         UnweightedConfig::new(self.symbols.flag_feature_count, buffer_size)
     }
 
+    /// Describe the path of the output most recently yielded by
+    /// [`next`](Transducer::next) or [`next_prefix`](Self::next_prefix) with
+    /// `config`: the states, symbols and flag diacritics along it.
+    pub fn trace(&self, config: &UnweightedConfig) -> PathTrace {
+        let strings = &self.symbols.symbol_strings;
+        let first_normal = self.symbols.first_normal_char;
+        let mut flag_values = vec![FLAG_VALUE_NEUTRAL; self.symbols.flag_feature_count as usize];
+        let steps = (0..config.stack_depth)
+            .map(|depth| {
+                let transition = &self.transitions[config.current_transition_stack[depth] as usize];
+                let is_flag = transition.sym_in != 0 && transition.sym_in < first_normal;
+                TraceStep {
+                    source_state: config.state_index_stack[depth],
+                    target_state: transition.target_state(),
+                    input: strings[transition.sym_in as usize].clone(),
+                    output: if transition.sym_out >= first_normal {
+                        strings[transition.sym_out as usize].clone()
+                    } else {
+                        String::new()
+                    },
+                    is_flag,
+                    flag: is_flag
+                        .then(|| self.symbols.flag_event(&mut flag_values, transition.sym_in)),
+                    weight: 0,
+                }
+            })
+            .collect();
+        PathTrace {
+            steps,
+            final_state: config.state_index_stack[config.stack_depth],
+            final_weight: 0,
+        }
+    }
+
     /// Yield the next prefix match from the transducer.
     ///
     /// Like `next()`, but also returns the length of the input prefix that was
@@ -130,7 +209,7 @@ impl UnweightedTransducer {
         output: &mut String,
         prefix_length: &mut usize,
     ) -> bool {
-        self.next_inner(config, output, Some(prefix_length))
+        self.next_inner(config, output, Some(prefix_length)) == NextStatus::Output
     }
 
     /// Core traversal: iterative DFS with backtracking.
@@ -147,14 +226,21 @@ impl UnweightedTransducer {
         config: &mut UnweightedConfig,
         output: &mut String,
         mut prefix_length: Option<&mut usize>,
-    ) -> bool {
-        let transitions = &self.transitions;
+    ) -> NextStatus {
+        let transitions = self.transitions.as_slice();
         let first_normal = self.symbols.first_normal_char;
-        let flag_feature_count = self.symbols.flag_feature_count;
+        let budget = config.budget;
+        if config.output_count >= budget.max_outputs {
+            return NextStatus::BudgetExceeded;
+        }
 
         let mut loop_counter: u32 = 0;
 
-        'outer: while loop_counter < MAX_LOOP_COUNT {
+        'outer: while loop_counter < budget.max_steps {
+            if budget.past_deadline(loop_counter) {
+                return NextStatus::BudgetExceeded;
+            }
+
             let state_idx = config.state_index_stack[config.stack_depth];
             let current_idx = config.current_transition_stack[config.stack_depth];
             let start_transition_index = current_idx - state_idx;
@@ -186,7 +272,8 @@ impl UnweightedTransducer {
                         if let Some(ref mut pl) = prefix_length {
                             **pl = config.input_depth;
                         }
-                        return true;
+                        config.output_count += 1;
+                        return NextStatus::Output;
                     }
                 } else if (config.input_depth < config.input_length
                     && config.input_symbol_stack[config.input_depth] == current_transition.sym_in)
@@ -196,7 +283,7 @@ impl UnweightedTransducer {
                     // Push down
                     if config.stack_depth + 2 == config.buffer_size {
                         // Max stack depth reached
-                        return false;
+                        return NextStatus::BudgetExceeded;
                     }
 
                     config.output_symbol_stack[config.stack_depth] =
@@ -224,7 +311,7 @@ impl UnweightedTransducer {
 
             // All transitions exhausted at this depth
             if config.stack_depth == 0 {
-                return false;
+                return NextStatus::Exhausted;
             }
 
             // Pop (backtrack up)
@@ -233,20 +320,317 @@ impl UnweightedTransducer {
             let previous_sym_in = transitions[prev_trans_idx as usize].sym_in;
             if previous_sym_in >= first_normal {
                 config.input_depth -= 1;
-            } else if flag_feature_count > 0 && previous_sym_in != 0 {
-                config.flag_depth -= 1;
-                let undo_feature = config.flag_undo_feature[config.flag_depth] as usize;
-                let undo_value = config.flag_undo_value[config.flag_depth];
-                config.current_flag_values[undo_feature] = undo_value;
+            } else {
+                self.undo_flag(config, previous_sym_in);
             }
             config.current_transition_stack[config.stack_depth] += 1;
 
             loop_counter += 1;
         }
 
+        NextStatus::BudgetExceeded
+    }
+
+    /// Enumerate accepted paths in the output-to-input direction.
+    ///
+    /// The input side is unconstrained: the traversal explores every path
+    /// and builds both its input and its output. Whenever a path's output
+    /// grows, `keep` is called with the output so far; returning `false`
+    /// prunes the path. `emit` receives the input and output of each
+    /// accepted path; returning `false` stops the traversal. Flag diacritics
+    /// are honored as in [`next`](Transducer::next).
+    ///
+    /// The search is bounded by the configuration's buffer size (path
+    /// length) and by `max_steps` transitions, so cyclic transducers
+    /// terminate. Returns `false` if the step limit was reached.
+    pub fn generate(
+        &self,
+        config: &mut UnweightedConfig,
+        max_steps: u32,
+        mut keep: impl FnMut(&str) -> bool,
+        mut emit: impl FnMut(&str, &str) -> bool,
+    ) -> bool {
+        let transitions = self.transitions.as_slice();
+        let strings = &self.symbols.symbol_strings;
+        let first_normal = self.symbols.first_normal_char;
+
+        config.reset();
+        let mut input = String::new();
+        let mut output = String::new();
+        let mut steps: u32 = 0;
+
+        'outer: while steps < max_steps {
+            let state_idx = config.state_index_stack[config.stack_depth];
+            let current_idx = config.current_transition_stack[config.stack_depth];
+            let max_tc = unweighted_max_tc(transitions, state_idx);
+
+            let mut tc = current_idx - state_idx;
+            let mut trans_idx = current_idx;
+
+            while tc <= max_tc {
+                if tc == 1 && max_tc >= 255 {
+                    // Skip overflow cell
+                    tc += 1;
+                    trans_idx += 1;
+                }
+
+                let current_transition = &transitions[trans_idx as usize];
+                let sym_in = current_transition.sym_in;
+
+                if sym_in == UNWEIGHTED_FINAL_SYM {
+                    if !emit(&input, &output) {
+                        return true;
+                    }
+                } else if config.stack_depth + 2 < config.buffer_size
+                    && (sym_in >= first_normal || self.flag_diacritic_check(config, sym_in))
+                {
+                    let in_sym = if sym_in >= first_normal { sym_in } else { 0 };
+                    let out_sym = if current_transition.sym_out >= first_normal {
+                        current_transition.sym_out
+                    } else {
+                        0
+                    };
+                    input.push_str(&strings[in_sym as usize]);
+                    output.push_str(&strings[out_sym as usize]);
+
+                    if out_sym == 0 || keep(&output) {
+                        // Push down
+                        config.input_symbol_stack[config.stack_depth] = in_sym;
+                        config.output_symbol_stack[config.stack_depth] = out_sym;
+                        config.current_transition_stack[config.stack_depth] = trans_idx;
+                        config.stack_depth += 1;
+                        config.state_index_stack[config.stack_depth] =
+                            current_transition.target_state();
+                        config.current_transition_stack[config.stack_depth] =
+                            current_transition.target_state();
+                        steps += 1;
+                        continue 'outer;
+                    }
+
+                    // Pruned: undo the symbols and any flag update
+                    input.truncate(input.len() - strings[in_sym as usize].len());
+                    output.truncate(output.len() - strings[out_sym as usize].len());
+                    if in_sym == 0 {
+                        self.undo_flag(config, sym_in);
+                    }
+                }
+
+                tc += 1;
+                trans_idx += 1;
+            }
+
+            // All transitions exhausted at this depth
+            if config.stack_depth == 0 {
+                return true;
+            }
+
+            // Pop (backtrack up)
+            config.stack_depth -= 1;
+            let depth = config.stack_depth;
+            let in_sym = config.input_symbol_stack[depth] as usize;
+            let out_sym = config.output_symbol_stack[depth] as usize;
+            input.truncate(input.len() - strings[in_sym].len());
+            output.truncate(output.len() - strings[out_sym].len());
+            let prev_trans_idx = config.current_transition_stack[depth];
+            let previous_sym_in = transitions[prev_trans_idx as usize].sym_in;
+            if previous_sym_in < first_normal {
+                self.undo_flag(config, previous_sym_in);
+            }
+            config.current_transition_stack[depth] += 1;
+
+            steps += 1;
+        }
+
         false
     }
 
+    /// List every input string of 1 to `max_len` characters that begins a
+    /// path from the start state.
+    ///
+    /// Flag diacritics are followed like epsilons, without checking them,
+    /// so the list may contain strings no accepted input begins with, but
+    /// every input that [`next`](Transducer::next) accepts begins with a
+    /// listed string or is itself one. Multi-character symbols, which no
+    /// input character matches, are not followed. Strings are listed in
+    /// sorted order.
+    pub fn input_prefixes(&self, max_len: usize) -> Vec<String> {
+        let transitions = self.transitions.as_slice();
+        let first_normal = self.symbols.first_normal_char;
+        let first_multi = self.symbols.first_multi_char;
+        let mut closure = StateClosure::new(transitions.len());
+
+        // Depth first, so that only the states of one path of prefixes and
+        // their siblings are held at a time
+        let mut prefixes = Vec::new();
+        let mut pending = vec![(String::new(), 0, vec![0])];
+        while let Some((prefix, len, states)) = pending.pop() {
+            if len == max_len {
+                continue;
+            }
+            let mut targets: BTreeMap<u16, Vec<u32>> = BTreeMap::new();
+            for state in closure.of(transitions, first_normal, states) {
+                for idx in state_transitions(transitions, state) {
+                    let transition = &transitions[idx];
+                    if (first_normal..first_multi).contains(&transition.sym_in) {
+                        targets
+                            .entry(transition.sym_in)
+                            .or_default()
+                            .push(transition.target_state());
+                    }
+                }
+            }
+            for (symbol, states) in targets {
+                let mut extended = prefix.clone();
+                extended.push_str(&self.symbols.symbol_strings[symbol as usize]);
+                prefixes.push(extended.clone());
+                pending.push((extended, len + 1, states));
+            }
+        }
+        prefixes.sort_unstable();
+        prefixes
+    }
+
+    /// Enumerate the accepted input strings that begin with `prefix`, with
+    /// the output of each path accepting them, up to `limit` paths.
+    ///
+    /// An input accepted along several paths (a word with several analyses)
+    /// is listed once per path. Flag diacritics are honored. Shorter paths
+    /// come first, so the simple words of a dictionary are listed before
+    /// the compounds built from them; paths of the same length are listed
+    /// in transition order. Paths longer than
+    /// [`ENUMERATION_MAX_PATH_LENGTH`] transitions are not followed, so the
+    /// enumeration of a cyclic transducer ends.
+    pub fn enumerate_outputs(&self, limit: usize, prefix: &str) -> Vec<(String, String)> {
+        let mut config = self.new_config(ENUMERATION_MAX_PATH_LENGTH);
+        let mut paths = Vec::new();
+        // The states and flag values from which no path is accepted within
+        // the given number of transitions. Each round searches the paths of
+        // the previous rounds again, but not their dead ends.
+        let mut dead_ends = HashMap::new();
+        for length in 0..=ENUMERATION_MAX_PATH_LENGTH {
+            if paths.len() >= limit {
+                break;
+            }
+            let cut = self.enumerate_paths(
+                &mut config,
+                length,
+                prefix,
+                limit,
+                &mut dead_ends,
+                &mut paths,
+            );
+            if !cut {
+                // No path is longer than this
+                break;
+            }
+        }
+        paths
+    }
+
+    /// Add to `paths` the paths of exactly `length` transitions accepting
+    /// an input that begins with `prefix`, until there are `limit` paths.
+    /// Returns `true` if some path was cut at `length` transitions.
+    fn enumerate_paths(
+        &self,
+        config: &mut UnweightedConfig,
+        length: usize,
+        prefix: &str,
+        limit: usize,
+        dead_ends: &mut HashMap<(u32, Vec<u16>), usize>,
+        paths: &mut Vec<(String, String)>,
+    ) -> bool {
+        let transitions = self.transitions.as_slice();
+        let strings = &self.symbols.symbol_strings;
+        let first_normal = self.symbols.first_normal_char;
+
+        config.reset();
+        let mut input = String::new();
+        let mut output = String::new();
+        let mut accepted: usize = 0;
+        let mut cut = false;
+        let mut frames = vec![EnumerationFrame::new(transitions, 0, 0, 0, 0, config, 0)];
+        while let Some(frame) = frames.last_mut() {
+            let Some(idx) = frame.transitions.next() else {
+                let Some(frame) = frames.pop() else { break };
+                // The transitions left after reaching the state
+                let remaining = length - frames.len();
+                if accepted == frame.accepted_before && input.starts_with(prefix) {
+                    let known = dead_ends.entry(frame.key).or_insert(0);
+                    *known = (*known).max(remaining);
+                }
+                input.truncate(frame.input_len);
+                output.truncate(frame.output_len);
+                if frame.flag != 0 {
+                    self.undo_flag(config, frame.flag);
+                }
+                continue;
+            };
+            let transition = &transitions[idx];
+            let sym_in = transition.sym_in;
+            if sym_in == UNWEIGHTED_FINAL_SYM {
+                accepted += 1;
+                if frames.len() == length + 1 && input.starts_with(prefix) {
+                    paths.push((input.clone(), output.clone()));
+                    if paths.len() >= limit {
+                        return cut;
+                    }
+                }
+                continue;
+            }
+            let remaining = length + 1 - frames.len();
+            if remaining == 0 {
+                cut = true;
+                continue;
+            }
+            if sym_in < first_normal && !self.flag_diacritic_check(config, sym_in) {
+                continue;
+            }
+            let flag = if sym_in >= first_normal { 0 } else { sym_in };
+            let (input_len, output_len) = (input.len(), output.len());
+            if flag == 0 {
+                input.push_str(&strings[sym_in as usize]);
+            }
+            if transition.sym_out >= first_normal {
+                output.push_str(&strings[transition.sym_out as usize]);
+            }
+            let frame = EnumerationFrame::new(
+                transitions,
+                transition.target_state(),
+                input_len,
+                output_len,
+                flag,
+                config,
+                accepted,
+            );
+            let viable = if input.starts_with(prefix) {
+                dead_ends
+                    .get(&frame.key)
+                    .is_none_or(|&dead_within| dead_within < remaining - 1)
+            } else {
+                prefix.starts_with(input.as_str())
+            };
+            if viable {
+                frames.push(frame);
+            } else {
+                input.truncate(input_len);
+                output.truncate(output_len);
+                if flag != 0 {
+                    self.undo_flag(config, flag);
+                }
+            }
+        }
+        cut
+    }
+
+    /// Undo the flag update recorded by `flag_diacritic_check` for `symbol`.
+    fn undo_flag(&self, config: &mut UnweightedConfig, symbol: u16) {
+        if self.symbols.flag_feature_count > 0 && symbol != 0 {
+            config.flag_depth -= 1;
+            let undo_feature = config.flag_undo_feature[config.flag_depth] as usize;
+            config.current_flag_values[undo_feature] = config.flag_undo_value[config.flag_depth];
+        }
+    }
+
     /// Check flag diacritic and update state if allowed.
     ///
     /// Returns `true` if the transition is allowed.
@@ -286,9 +670,129 @@ impl UnweightedTransducer {
     }
 }
 
+/// Indices of the transitions of `state`, without the overflow cell. Empty
+/// for a state outside the table.
+fn state_transitions(transitions: &[Transition], state: u32) -> StateTransitions {
+    let start = state as usize;
+    let (end, overflow) = if start < transitions.len() {
+        let max_tc = unweighted_max_tc(transitions, state) as usize;
+        let overflow = if max_tc >= 255 { start + 1 } else { usize::MAX };
+        ((start + max_tc + 1).min(transitions.len()), overflow)
+    } else {
+        (start, usize::MAX)
+    };
+    StateTransitions {
+        next: start,
+        end,
+        overflow,
+    }
+}
+
+/// Iterator over the transition indices of a state (see
+/// [`state_transitions`]).
+struct StateTransitions {
+    next: usize,
+    end: usize,
+    overflow: usize,
+}
+
+impl Iterator for StateTransitions {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next == self.overflow {
+            self.next += 1;
+        }
+        if self.next >= self.end {
+            return None;
+        }
+        self.next += 1;
+        Some(self.next - 1)
+    }
+}
+
+/// A state on the path being extended by
+/// [`UnweightedTransducer::enumerate_outputs`].
+struct EnumerationFrame {
+    /// The transitions of the state not yet followed.
+    transitions: StateTransitions,
+    /// The state and the flag values on entering it.
+    key: (u32, Vec<u16>),
+    /// Lengths of the input and output before the transition to the state.
+    input_len: usize,
+    output_len: usize,
+    /// The flag diacritic of the transition to the state, or 0.
+    flag: u16,
+    /// Number of paths accepted before entering the state.
+    accepted_before: usize,
+}
+
+impl EnumerationFrame {
+    fn new(
+        transitions: &[Transition],
+        state: u32,
+        input_len: usize,
+        output_len: usize,
+        flag: u16,
+        config: &UnweightedConfig,
+        accepted_before: usize,
+    ) -> Self {
+        Self {
+            transitions: state_transitions(transitions, state),
+            key: (state, config.current_flag_values.clone()),
+            input_len,
+            output_len,
+            flag,
+            accepted_before,
+        }
+    }
+}
+
+/// Computes the states reachable through epsilon and flag transitions.
+struct StateClosure {
+    /// Whether each state was reached by the closure being computed.
+    reached: Vec<bool>,
+}
+
+impl StateClosure {
+    fn new(transition_count: usize) -> Self {
+        Self {
+            reached: vec![false; transition_count],
+        }
+    }
+
+    /// `states` and every state reachable from them without consuming
+    /// input.
+    fn of(&mut self, transitions: &[Transition], first_normal: u16, states: Vec<u32>) -> Vec<u32> {
+        let mut closure = Vec::new();
+        let mut pending = states;
+        while let Some(state) = pending.pop() {
+            match self.reached.get_mut(state as usize) {
+                Some(reached) if !*reached => *reached = true,
+                _ => continue,
+            }
+            closure.push(state);
+            for idx in state_transitions(transitions, state) {
+                let transition = &transitions[idx];
+                if transition.sym_in < first_normal {
+                    pending.push(transition.target_state());
+                }
+            }
+        }
+        for &state in &closure {
+            self.reached[state as usize] = false;
+        }
+        closure
+    }
+}
+
 impl Transducer for UnweightedTransducer {
     type Config = UnweightedConfig;
 
+    fn new_config(&self, buffer_size: usize) -> Self::Config {
+        UnweightedConfig::new(self.symbols.flag_feature_count, buffer_size)
+    }
+
     /// Prepare the configuration for traversing with the given input characters.
     ///
     /// Returns `true` if all input characters are known symbols.
@@ -300,8 +804,8 @@ impl Transducer for UnweightedTransducer {
         config.reset();
         let mut all_known = true;
         for &ch in input {
-            match self.symbols.char_to_symbol.get(&ch) {
-                Some(&sym_idx) => {
+            match self.symbols.char_index(ch) {
+                Some(sym_idx) => {
                     config.input_symbol_stack[config.input_length] = sym_idx;
                 }
                 None => {
@@ -314,13 +818,40 @@ impl Transducer for UnweightedTransducer {
         all_known
     }
 
+    /// Prepare the configuration for traversing with the given UTF-8 input.
+    ///
+    /// Unknown characters are handled as in [`prepare`](Self::prepare). If
+    /// the input has too many symbols for the configuration, returns `false`
+    /// and leaves a single unknown symbol as the input, so that traversal
+    /// yields nothing.
+    fn prepare_str(&self, config: &mut Self::Config, input: &str) -> bool {
+        config.reset();
+        let mut all_known = true;
+        let mut rest = input;
+        while !rest.is_empty() {
+            if config.input_length + 1 >= config.buffer_size {
+                config.input_symbol_stack[0] = self.unknown_symbol_ordinal;
+                config.input_length = 1;
+                return false;
+            }
+            let (sym, len) = self.symbols.match_symbol(rest);
+            config.input_symbol_stack[config.input_length] = sym.unwrap_or_else(|| {
+                all_known = false;
+                self.unknown_symbol_ordinal
+            });
+            config.input_length += 1;
+            rest = &rest[len..];
+        }
+        all_known
+    }
+
     /// Yield the next complete output from the transducer.
     ///
     /// Only matches when the entire input has been consumed. For prefix matching,
     /// use [`next_prefix`](Self::next_prefix).
     ///
     /// Origin: UnweightedTransducer::next() -- UnweightedTransducer.cpp:285-287
-    fn next(&self, config: &mut Self::Config, output: &mut String) -> bool {
+    fn next_status(&self, config: &mut Self::Config, output: &mut String) -> NextStatus {
         self.next_inner(config, output, None)
     }
 }
@@ -328,6 +859,8 @@ impl Transducer for UnweightedTransducer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::validate::TransitionDefect;
+    use crate::{MAX_LOOP_COUNT, VfstSection};
 
     /// Build a minimal VFST binary for testing.
     ///
@@ -375,7 +908,7 @@ mod tests {
     ///
     /// Structure:
     /// - State 0: epsilon transition -> State 1 (output epsilon)
-    ///            more_transitions = 1 (2 transitions total)
+    ///   more_transitions = 1 (2 transitions total)
     /// - State 1: transition on 'a' -> State 2 (output 'a')
     /// - State 2: final transition
     fn build_epsilon_vfst() -> Vec<u8> {
@@ -445,6 +978,18 @@ mod tests {
         assert_eq!(t.symbols.first_normal_char, 1);
     }
 
+    #[test]
+    fn transducers_share_one_copy_of_the_data() {
+        let data: Arc<[u8]> = build_simple_vfst().into();
+        let a = UnweightedTransducer::from_shared(Arc::clone(&data)).unwrap();
+        let b = UnweightedTransducer::from_shared(Arc::clone(&data)).unwrap();
+        assert!(a.shares_data() && b.shares_data());
+        assert_eq!(Arc::strong_count(&data), 3);
+        assert_eq!(a.transitions.as_ptr(), b.transitions.as_ptr());
+        drop((a, b));
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
     #[test]
     fn reject_weighted_data() {
         let mut data = build_simple_vfst();
@@ -453,6 +998,123 @@ mod tests {
         assert!(matches!(err, VfstError::TypeMismatch { .. }));
     }
 
+    #[test]
+    fn errors_name_the_damaged_section() {
+        let data = build_simple_vfst();
+        let err = UnweightedTransducer::from_bytes(&data[..10]).unwrap_err();
+        assert_eq!(err.section(), VfstSection::Header);
+        let err = UnweightedTransducer::from_bytes(&data[..19]).unwrap_err();
+        assert_eq!(err.section(), VfstSection::SymbolTable);
+        let end = data.len() - 3 * size_of::<Transition>();
+        let err = UnweightedTransducer::from_bytes(&data[..end]).unwrap_err();
+        assert!(matches!(err, VfstError::TruncatedTransitions { .. }));
+        assert_eq!(err.section(), VfstSection::Transitions);
+    }
+
+    #[test]
+    fn truncated_data_reports_the_missing_bytes() {
+        // Header 0..16, symbol table 16..27, padding, 3 transitions 32..56
+        let data = build_simple_vfst();
+        let truncation = |len: usize| {
+            UnweightedTransducer::from_bytes(&data[..len])
+                .unwrap_err()
+                .truncation()
+                .map(|t| (t.section, t.missing()))
+        };
+        assert_eq!(truncation(10), Some((VfstSection::Header, 6)));
+        assert_eq!(truncation(17), Some((VfstSection::SymbolTable, 1)));
+        // The rest of "a" and a byte for each of the 3 symbols after it
+        assert_eq!(truncation(20), Some((VfstSection::SymbolTable, 4)));
+        assert_eq!(truncation(30), Some((VfstSection::Transitions, 10)));
+        assert_eq!(truncation(45), Some((VfstSection::Transitions, 3)));
+
+        let mut corrupt = data.clone();
+        corrupt[0] = 0xFF;
+        let err = UnweightedTransducer::from_bytes(&corrupt).unwrap_err();
+        assert_eq!(err.truncation(), None);
+        // Cut between transitions, the data loads
+        assert!(UnweightedTransducer::from_bytes(&data[..48]).is_ok());
+    }
+
+    /// Replace transition `index` of `data`, which has `count` transitions.
+    fn patch_transition(data: &mut [u8], count: usize, index: usize, t: Transition) {
+        let start = data.len() - (count - index) * size_of::<Transition>();
+        data[start..start + size_of::<Transition>()].copy_from_slice(bytemuck::bytes_of(&t));
+    }
+
+    #[test]
+    fn strict_load_rejects_corrupt_transitions() {
+        assert!(UnweightedTransducer::from_bytes_strict(&build_simple_vfst()).is_ok());
+        assert!(UnweightedTransducer::from_bytes_strict(&build_epsilon_vfst()).is_ok());
+
+        let cases = [
+            (
+                make_transition(2, 4, 9, 0),
+                TransitionDefect::TargetOutOfRange {
+                    target: 9,
+                    count: 3,
+                },
+            ),
+            (
+                make_transition(7, 4, 2, 0),
+                TransitionDefect::InputSymbolOutOfRange {
+                    symbol: 7,
+                    count: 5,
+                },
+            ),
+            (
+                make_transition(2, 5, 2, 0),
+                TransitionDefect::OutputSymbolOutOfRange {
+                    symbol: 5,
+                    count: 5,
+                },
+            ),
+            (
+                make_transition(2, 4, 2, 2),
+                TransitionDefect::StateOverrunsTable { transitions: 3 },
+            ),
+        ];
+        for (transition, defect) in cases {
+            let mut data = build_simple_vfst();
+            patch_transition(&mut data, 3, 1, transition);
+            // The lenient loader accepts what strict loading rejects.
+            assert!(UnweightedTransducer::from_bytes(&data).is_ok());
+            let err = UnweightedTransducer::from_bytes_strict(&data).unwrap_err();
+            assert_eq!(err.section(), VfstSection::Transitions);
+            match err {
+                VfstError::CorruptTransition { index, defect: d } => {
+                    assert_eq!((index, d), (1, defect));
+                }
+                other => panic!("unexpected error {other}"),
+            }
+        }
+    }
+
+    #[test]
+    fn strictly_loaded_corrupt_data_never_panics() {
+        let inputs: [&[char]; 4] = [&['a', 'b'], &['a'], &[], &['b', 'a', 'x']];
+        for data in [build_simple_vfst(), build_epsilon_vfst()] {
+            for mutant in crate::validate::mutants(&data, 0x5EED, 2000) {
+                let Ok(t) = UnweightedTransducer::from_bytes_strict(&mutant) else {
+                    continue;
+                };
+                let mut config = t.new_config(16);
+                let mut output = String::new();
+                for input in inputs {
+                    t.prepare(&mut config, input);
+                    while t.next_status(&mut config, &mut output) == NextStatus::Output {}
+                    t.prepare(&mut config, input);
+                    let mut prefix_length = 0;
+                    while t.next_prefix(&mut config, &mut output, &mut prefix_length) {
+                        t.trace(&config);
+                    }
+                }
+                t.generate(&mut config, 1000, |_| true, |_, _| true);
+                t.enumerate_outputs(100, "");
+            }
+        }
+    }
+
     #[test]
     fn traverse_simple_ab_to_xy() {
         let data = build_simple_vfst();
@@ -470,6 +1132,36 @@ mod tests {
         assert!(!t.next(&mut config, &mut output));
     }
 
+    #[test]
+    fn trace_describes_the_accepted_path() {
+        let data = build_epsilon_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+        assert!(t.prepare(&mut config, &['a']));
+        let mut output = String::new();
+
+        assert!(t.next(&mut config, &mut output));
+        let trace = t.trace(&config);
+        let path: Vec<(u32, u32, &str, &str)> = trace
+            .steps
+            .iter()
+            .map(|s| {
+                (
+                    s.source_state,
+                    s.target_state,
+                    s.input.as_str(),
+                    s.output.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(path, [(0, 2, "", ""), (2, 3, "a", "a")]);
+        assert_eq!(trace.final_state, 3);
+        assert_eq!(trace.weight(), 0);
+
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(t.trace(&config).steps.len(), 1);
+    }
+
     #[test]
     fn traverse_unknown_input() {
         let data = build_simple_vfst();
@@ -485,6 +1177,102 @@ mod tests {
         assert!(!t.next(&mut config, &mut output));
     }
 
+    #[test]
+    fn traverse_prepare_str_matches_prepare() {
+        let data = build_simple_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+
+        assert!(t.prepare_str(&mut config, "ab"));
+        let mut output = String::new();
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "xy");
+
+        // Unknown characters are mapped to the sentinel, as with prepare()
+        assert!(!t.prepare_str(&mut config, "äb"));
+        assert_eq!(config.input_length, 2);
+        assert!(!t.next(&mut config, &mut output));
+    }
+
+    #[test]
+    fn traverse_prepare_str_rejects_input_longer_than_config() {
+        let data = build_simple_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(4);
+
+        assert!(t.prepare_str(&mut config, "aba"));
+        assert!(!t.prepare_str(&mut config, "ababab"));
+        let mut output = String::new();
+        assert!(!t.next(&mut config, &mut output));
+
+        // The configuration is still usable for shorter input
+        assert!(t.prepare_str(&mut config, "ab"));
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "xy");
+    }
+
+    #[test]
+    fn traverse_prepare_str_multi_char_symbol() {
+        // Transducer accepting "[Ln]a" where "[Ln]" is a single input symbol
+        let symbols: &[&str] = &["", "a", "[Ln]"];
+        let header = build_header(false);
+        let sym_table = build_symbol_table(symbols);
+        let mut data = Vec::new();
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&sym_table);
+        let partial = data.len() % 8;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 8 - partial));
+        }
+
+        // State 0: '[Ln]' -> state 1
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(2, 2, 1, 0)));
+        // State 1: 'a' -> state 2
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(1, 1, 2, 0)));
+        // State 2: final
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+
+        assert!(t.prepare_str(&mut config, "[Ln]a"));
+        assert_eq!(config.input_length, 2);
+        let mut output = String::new();
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "[Ln]a");
+    }
+
+    #[test]
+    fn traverse_prepare_str_multi_char_symbol_without_bracket() {
+        // Transducer accepting "+Pla" where "+Pl" is a single input symbol
+        let symbols: &[&str] = &["", "a", "[Ln]", "+Pl"];
+        let header = build_header(false);
+        let sym_table = build_symbol_table(symbols);
+        let mut data = Vec::new();
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&sym_table);
+        let partial = data.len() % 8;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 8 - partial));
+        }
+
+        // State 0: '+Pl' -> state 1
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(3, 3, 1, 0)));
+        // State 1: 'a' -> state 2
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(1, 1, 2, 0)));
+        // State 2: final
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+
+        assert!(t.prepare_str(&mut config, "+Pla"));
+        assert_eq!(config.input_length, 2);
+        let mut output = String::new();
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "+Pla");
+    }
+
     #[test]
     fn traverse_partial_input_no_match() {
         let data = build_simple_vfst();
@@ -514,6 +1302,80 @@ mod tests {
         assert_eq!(output, "a");
     }
 
+    #[test]
+    fn next_status_reports_budget() {
+        use crate::config::TraversalBudget;
+        use NextStatus::{BudgetExceeded, Exhausted, Output};
+
+        let data = build_epsilon_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+        let mut output = String::new();
+        let mut statuses = |config: &mut UnweightedConfig| {
+            t.prepare(config, &['a']);
+            (0..3)
+                .map(|_| t.next_status(config, &mut output))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(statuses(&mut config), [Output, Output, Exhausted]);
+
+        config.set_budget(TraversalBudget {
+            max_outputs: 1,
+            ..TraversalBudget::default()
+        });
+        assert_eq!(
+            statuses(&mut config),
+            [Output, BudgetExceeded, BudgetExceeded]
+        );
+
+        // One step per call: the traversal resumes where it stopped
+        config.set_budget(TraversalBudget {
+            max_steps: 1,
+            ..TraversalBudget::default()
+        });
+        assert_eq!(
+            statuses(&mut config),
+            [BudgetExceeded, BudgetExceeded, Output]
+        );
+
+        config.set_budget(TraversalBudget {
+            deadline: Some(std::time::Instant::now()),
+            ..TraversalBudget::default()
+        });
+        assert_eq!(statuses(&mut config)[0], BudgetExceeded);
+    }
+
+    #[test]
+    fn outputs_iterator() {
+        let data = build_simple_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let input: Vec<char> = "ab".chars().collect();
+        let outputs: Vec<String> = t.outputs(&input).collect();
+        assert_eq!(outputs, vec!["xy"]);
+    }
+
+    #[test]
+    fn outputs_iterator_reset_reuses_config() {
+        let data = build_epsilon_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut iter = t.outputs(&['a']);
+        assert_eq!(iter.by_ref().take(1).count(), 1);
+
+        iter.reset(&['b']);
+        assert_eq!(iter.next(), None);
+
+        iter.reset(&['a']);
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn outputs_iterator_long_input() {
+        let data = build_simple_vfst();
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let input = vec!['a'; crate::iter::DEFAULT_BUFFER_SIZE + 10];
+        assert_eq!(t.outputs(&input).count(), 0);
+    }
+
     #[test]
     fn next_prefix_matches_prefix() {
         // Build a transducer that accepts just "a" (single char)
@@ -589,4 +1451,137 @@ mod tests {
 
         assert!(!t.next(&mut config, &mut output));
     }
+
+    #[test]
+    fn input_prefixes_lists_beginnings() {
+        let t = UnweightedTransducer::from_bytes(&build_simple_vfst()).unwrap();
+        assert_eq!(t.input_prefixes(1), ["a"]);
+        assert_eq!(t.input_prefixes(3), ["a", "ab"]);
+        assert!(t.input_prefixes(0).is_empty());
+
+        // The epsilon path and the direct path begin alike
+        let t = UnweightedTransducer::from_bytes(&build_epsilon_vfst()).unwrap();
+        assert_eq!(t.input_prefixes(2), ["a"]);
+    }
+
+    #[test]
+    fn generate_enumerates_inputs_guided_by_output() {
+        // Two paths: "a" -> "x" and "a" -> "y"; plus the simple ab -> xy.
+        let symbols: &[&str] = &["", "a", "b", "x", "y"];
+        let mut data = build_header(false);
+        data.extend_from_slice(&build_symbol_table(symbols));
+        let partial = data.len() % 8;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 8 - partial));
+        }
+        // State 0: 'a' -> state 2 output 'x'; 'b' -> state 3 output 'y'
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(1, 3, 2, 1)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(2, 4, 3, 0)));
+        // State 2: 'b' -> state 3 output 'y', or final
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(2, 4, 3, 1)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+        // State 3 (index 4): final
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+
+        let mut all = Vec::new();
+        let done = t.generate(
+            &mut config,
+            MAX_LOOP_COUNT,
+            |_| true,
+            |i, o| {
+                all.push((i.to_string(), o.to_string()));
+                true
+            },
+        );
+        assert!(done);
+        assert_eq!(
+            all,
+            [
+                ("ab".to_string(), "xy".to_string()),
+                ("a".to_string(), "x".to_string()),
+                ("b".to_string(), "y".to_string()),
+            ]
+        );
+
+        // Keep only outputs starting with "x"
+        let mut pruned = Vec::new();
+        t.generate(
+            &mut config,
+            MAX_LOOP_COUNT,
+            |o| o.starts_with('x'),
+            |i, _| {
+                pruned.push(i.to_string());
+                true
+            },
+        );
+        assert_eq!(pruned, ["ab", "a"]);
+
+        // The step limit is reported
+        assert!(!t.generate(&mut config, 1, |_| true, |_, _| true));
+    }
+
+    #[test]
+    fn enumerate_outputs_lists_shorter_paths_first() {
+        // State 0: 'a' -> state 2 output 'x'; 'b' -> state 3 output 'y'
+        // State 2: 'b' -> state 3 output 'y', or final; state 3: final
+        let symbols: &[&str] = &["", "a", "b", "x", "y"];
+        let mut data = build_header(false);
+        data.extend_from_slice(&build_symbol_table(symbols));
+        let partial = data.len() % 8;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 8 - partial));
+        }
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(1, 3, 2, 1)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(2, 4, 3, 0)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(2, 4, 3, 1)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+
+        let pairs = |paths: &[(&str, &str)]| -> Vec<(String, String)> {
+            paths
+                .iter()
+                .map(|&(i, o)| (i.to_string(), o.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            t.enumerate_outputs(10, ""),
+            pairs(&[("a", "x"), ("b", "y"), ("ab", "xy")])
+        );
+        assert_eq!(
+            t.enumerate_outputs(10, "a"),
+            pairs(&[("a", "x"), ("ab", "xy")])
+        );
+        assert_eq!(t.enumerate_outputs(10, "ab"), pairs(&[("ab", "xy")]));
+        assert!(t.enumerate_outputs(10, "ba").is_empty());
+        assert_eq!(t.enumerate_outputs(1, ""), pairs(&[("a", "x")]));
+        assert!(t.enumerate_outputs(0, "").is_empty());
+    }
+
+    #[test]
+    fn enumerate_outputs_ends_in_a_cycle() {
+        // State 0: 'a' -> state 0 output 'x', or final: accepts a*
+        let symbols: &[&str] = &["", "a", "x"];
+        let mut data = build_header(false);
+        data.extend_from_slice(&build_symbol_table(symbols));
+        let partial = data.len() % 8;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 8 - partial));
+        }
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(1, 2, 0, 1)));
+        data.extend_from_slice(bytemuck::bytes_of(&make_transition(0xFFFF, 0, 0, 0)));
+        let t = UnweightedTransducer::from_bytes(&data).unwrap();
+
+        let inputs: Vec<String> = t
+            .enumerate_outputs(3, "")
+            .into_iter()
+            .map(|(input, _)| input)
+            .collect();
+        assert_eq!(inputs, ["", "a", "aa"]);
+        let all = t.enumerate_outputs(usize::MAX, "");
+        assert_eq!(all.len(), ENUMERATION_MAX_PATH_LENGTH + 1);
+    }
 }
@@ -0,0 +1,233 @@
+// Up-front validation of transition tables for strict loading.
+//
+// Traversal trusts the transition table: it indexes the table with target
+// states and the symbol table with transition symbols without checking
+// them, as the C++ engine does. A corrupt or truncated file can therefore
+// make a traversal panic. `from_bytes_strict` runs `validate_table` first,
+// which walks every state reachable from state 0 and checks each index the
+// traversal will use, so that a strictly loaded transducer cannot index out
+// of bounds.
+
+use crate::VfstError;
+use crate::transition::{
+    OverflowCell, Transition, UNWEIGHTED_FINAL_SYM, WEIGHTED_FINAL_SYM, WeightedOverflowCell,
+    WeightedTransition,
+};
+
+/// What is wrong with a transition found by strict loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TransitionDefect {
+    /// The target state lies outside the transition table.
+    #[error("target state {target} is outside the table of {count} transitions")]
+    TargetOutOfRange { target: u32, count: usize },
+    /// The input symbol is neither in the symbol table nor the final marker.
+    #[error("input symbol {symbol} is not in the table of {count} symbols")]
+    InputSymbolOutOfRange { symbol: u32, count: usize },
+    /// The output symbol is not in the symbol table.
+    #[error("output symbol {symbol} is not in the table of {count} symbols")]
+    OutputSymbolOutOfRange { symbol: u32, count: usize },
+    /// The transitions of the state starting here (including an overflow
+    /// cell) run past the end of the table.
+    #[error("state with {transitions} transitions runs past the end of the table")]
+    StateOverrunsTable { transitions: u64 },
+}
+
+/// The fields of a transition that traversal uses as indices.
+struct Cell {
+    sym_in: u32,
+    sym_out: u32,
+    target: u32,
+    more_transitions: u8,
+}
+
+/// A transition table viewed independently of its transition size.
+trait Table {
+    const FINAL_SYM: u32;
+    fn len(&self) -> usize;
+    fn cell(&self, index: usize) -> Cell;
+    /// The count stored in the overflow cell at `index`.
+    fn overflow(&self, index: usize) -> u32;
+}
+
+impl Table for [Transition] {
+    const FINAL_SYM: u32 = UNWEIGHTED_FINAL_SYM as u32;
+
+    fn len(&self) -> usize {
+        <[Transition]>::len(self)
+    }
+
+    fn cell(&self, index: usize) -> Cell {
+        let t = &self[index];
+        Cell {
+            sym_in: t.sym_in.into(),
+            sym_out: t.sym_out.into(),
+            target: t.target_state(),
+            more_transitions: t.more_transitions(),
+        }
+    }
+
+    fn overflow(&self, index: usize) -> u32 {
+        bytemuck::cast::<Transition, OverflowCell>(self[index]).more_transitions
+    }
+}
+
+impl Table for [WeightedTransition] {
+    const FINAL_SYM: u32 = WEIGHTED_FINAL_SYM;
+
+    fn len(&self) -> usize {
+        <[WeightedTransition]>::len(self)
+    }
+
+    fn cell(&self, index: usize) -> Cell {
+        let t = &self[index];
+        Cell {
+            sym_in: t.sym_in,
+            sym_out: t.sym_out,
+            target: t.target_state,
+            more_transitions: t.more_transitions,
+        }
+    }
+
+    fn overflow(&self, index: usize) -> u32 {
+        bytemuck::cast::<WeightedTransition, WeightedOverflowCell>(self[index]).more_transitions
+    }
+}
+
+/// Check an unweighted transition table against a symbol table of
+/// `symbol_count` symbols.
+pub(crate) fn validate_unweighted(
+    transitions: &[Transition],
+    symbol_count: usize,
+) -> Result<(), VfstError> {
+    validate_table(transitions, symbol_count)
+}
+
+/// Check a weighted transition table against a symbol table of
+/// `symbol_count` symbols.
+pub(crate) fn validate_weighted(
+    transitions: &[WeightedTransition],
+    symbol_count: usize,
+) -> Result<(), VfstError> {
+    validate_table(transitions, symbol_count)
+}
+
+/// Walk the states reachable from state 0 and check that each one fits in
+/// the table and that each of its transitions has valid symbols and a
+/// target inside the table.
+fn validate_table<T: Table + ?Sized>(table: &T, symbol_count: usize) -> Result<(), VfstError> {
+    let count = table.len();
+    let corrupt = |index: usize, defect| VfstError::CorruptTransition { index, defect };
+    if count == 0 {
+        return Ok(());
+    }
+    let mut visited = vec![false; count];
+    visited[0] = true;
+    let mut pending = vec![0u32];
+
+    while let Some(state) = pending.pop() {
+        let state = state as usize;
+        // The last transition index of the state, as traversal computes it.
+        let more = table.cell(state).more_transitions;
+        let max_tc = if more == 255 {
+            if state + 1 >= count {
+                return Err(corrupt(
+                    state,
+                    TransitionDefect::StateOverrunsTable { transitions: 256 },
+                ));
+            }
+            u64::from(table.overflow(state + 1)) + 1
+        } else {
+            u64::from(more)
+        };
+        if state as u64 + max_tc >= count as u64 {
+            return Err(corrupt(
+                state,
+                TransitionDefect::StateOverrunsTable {
+                    transitions: max_tc + 1,
+                },
+            ));
+        }
+
+        for tc in 0..=max_tc as usize {
+            if tc == 1 && more == 255 {
+                // Overflow cell, not a transition
+                continue;
+            }
+            let index = state + tc;
+            let cell = table.cell(index);
+            if cell.sym_in == T::FINAL_SYM {
+                continue;
+            }
+            if cell.sym_in as usize >= symbol_count {
+                return Err(corrupt(
+                    index,
+                    TransitionDefect::InputSymbolOutOfRange {
+                        symbol: cell.sym_in,
+                        count: symbol_count,
+                    },
+                ));
+            }
+            if cell.sym_out as usize >= symbol_count {
+                return Err(corrupt(
+                    index,
+                    TransitionDefect::OutputSymbolOutOfRange {
+                        symbol: cell.sym_out,
+                        count: symbol_count,
+                    },
+                ));
+            }
+            let target = cell.target as usize;
+            if target >= count {
+                return Err(corrupt(
+                    index,
+                    TransitionDefect::TargetOutOfRange {
+                        target: cell.target,
+                        count,
+                    },
+                ));
+            }
+            if !visited[target] {
+                visited[target] = true;
+                pending.push(cell.target);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deterministic corruption of VFST data for the strict loading tests: the
+/// same seed gives the same mutants, so a failure can be replayed.
+#[cfg(test)]
+pub(crate) fn mutants(data: &[u8], seed: u64, count: usize) -> Vec<Vec<u8>> {
+    let mut state = seed | 1;
+    let mut random = move || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..count)
+        .map(|_| {
+            let mut mutant = data.to_vec();
+            for _ in 0..=random() % 3 {
+                let len = mutant.len();
+                if len == 0 {
+                    break;
+                }
+                let pos = (random() % len as u64) as usize;
+                match random() % 4 {
+                    0 => mutant[pos] ^= 1 << (random() % 8),
+                    1 => mutant[pos] = random() as u8,
+                    2 => {
+                        let end = (pos + 4).min(len);
+                        let bytes = (random() as u32).to_le_bytes();
+                        mutant[pos..end].copy_from_slice(&bytes[..end - pos]);
+                    }
+                    _ => mutant.truncate(pos),
+                }
+            }
+            mutant
+        })
+        .collect()
+}
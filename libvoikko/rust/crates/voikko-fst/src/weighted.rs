@@ -1,22 +1,33 @@
 // Weighted transducer loading and traversal.
 // Origin: WeightedTransducer.cpp
 
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::rc::Rc;
+use std::sync::Arc;
+
 use crate::config::WeightedConfig;
-use crate::flags::{self, FlagCheckResult};
+use crate::flags::{self, FLAG_VALUE_NEUTRAL, FlagCheckResult, FlagDiagnostic, FlagFeature};
 use crate::format::{self, HEADER_SIZE};
+use crate::iter::WeightedOutputIter;
 use crate::symbols::{self, SymbolTable};
-use crate::transition::{WEIGHTED_FINAL_SYM, WeightedTransition, weighted_max_tc};
-use crate::{MAX_LOOP_COUNT, Transducer, VfstError};
+use crate::trace::{PathTrace, TraceStep};
+use crate::transition::{TransitionTable, WEIGHTED_FINAL_SYM, WeightedTransition, weighted_max_tc};
+use crate::validate;
+use crate::{MAX_LOOP_COUNT, NextStatus, Transducer, VfstError};
 
 /// Weighted VFST transducer.
 ///
-/// Loaded from a `&[u8]` slice, this struct provides the `prepare`/`next`
-/// traversal interface with weight tracking and binary search optimization.
+/// Loaded from the raw binary VFST data, this struct provides the
+/// `prepare`/`next` traversal interface with weight tracking and binary
+/// search optimization. Transducers loaded with
+/// [`from_shared`](Self::from_shared) from the same data share its
+/// transition table.
 ///
 /// Origin: WeightedTransducer.hpp, WeightedTransducer.cpp
 pub struct WeightedTransducer {
-    /// The transition table (owned, copied from source data for alignment safety).
-    transitions: Vec<WeightedTransition>,
+    /// The transition table, a view into the backing data.
+    transitions: TransitionTable<WeightedTransition>,
     /// Symbol table.
     symbols: SymbolTable,
 }
@@ -42,21 +53,48 @@ pub struct WeightedResult {
 impl WeightedTransducer {
     /// Load a weighted transducer from raw VFST binary data.
     ///
-    /// The data is typically loaded from `spl.vfst` or `err.vfst`.
+    /// The data is typically loaded from `spl.vfst` or `err.vfst`. It is
+    /// copied once; use [`from_shared`](Self::from_shared) to load several
+    /// transducers from one copy.
     ///
     /// Origin: WeightedTransducer::WeightedTransducer() -- WeightedTransducer.cpp:130-194
     pub fn from_bytes(data: &[u8]) -> Result<Self, VfstError> {
-        let header = format::parse_header(data)?;
+        Self::from_shared(Arc::from(data))
+    }
+
+    /// Load a weighted transducer from shared VFST data without copying
+    /// it. The transducer keeps `data` alive and reads its transitions in
+    /// place, unless the data is not aligned for them, in which case they
+    /// are copied.
+    pub fn from_shared(data: Arc<[u8]>) -> Result<Self, VfstError> {
+        let header = format::parse_header(&data)?;
         if !header.weighted {
             return Err(VfstError::TypeMismatch {
                 expected: true,
                 actual: false,
             });
         }
-        Self::from_bytes_inner(data)
+        Self::from_shared_inner(&data)
+    }
+
+    /// Load a weighted transducer from untrusted data.
+    ///
+    /// Like [`from_bytes`](Self::from_bytes), but also checks the target
+    /// state, symbols and extent of every transition reachable from the
+    /// start state, failing with [`VfstError::CorruptTransition`]. No input
+    /// can then make a traversal index out of bounds, whatever the data.
+    /// The check visits each reachable transition once.
+    pub fn from_bytes_strict(data: &[u8]) -> Result<Self, VfstError> {
+        let transducer = Self::from_bytes(data)?;
+        validate::validate_weighted(
+            &transducer.transitions,
+            transducer.symbols.symbol_strings.len(),
+        )?;
+        Ok(transducer)
     }
 
-    fn from_bytes_inner(data: &[u8]) -> Result<Self, VfstError> {
+    fn from_shared_inner(shared: &Arc<[u8]>) -> Result<Self, VfstError> {
+        let data: &[u8] = shared;
         let (symbols, sym_end) = symbols::parse_symbol_table(data, HEADER_SIZE)?;
 
         // Align to 16-byte boundary (sizeof(WeightedTransition))
@@ -68,8 +106,8 @@ impl WeightedTransducer {
         };
 
         if transition_offset > data.len() {
-            return Err(VfstError::TooShort {
-                expected: transition_offset,
+            return Err(VfstError::TruncatedTransitions {
+                expected: transition_offset + size_of::<WeightedTransition>(),
                 actual: data.len(),
             });
         }
@@ -77,27 +115,17 @@ impl WeightedTransducer {
         let remaining = &data[transition_offset..];
         let transition_count = remaining.len() / size_of::<WeightedTransition>();
 
-        if transition_count == 0 {
-            return Err(VfstError::TooShort {
-                expected: transition_offset + size_of::<WeightedTransition>(),
+        // A file cut within a transition ends in a partial transition
+        if transition_count == 0 || remaining.len() % size_of::<WeightedTransition>() != 0 {
+            return Err(VfstError::TruncatedTransitions {
+                expected: transition_offset
+                    + (transition_count + 1) * size_of::<WeightedTransition>(),
                 actual: data.len(),
             });
         }
 
-        // Copy into aligned Vec
-        let mut transitions = vec![
-            WeightedTransition {
-                sym_in: 0,
-                sym_out: 0,
-                target_state: 0,
-                weight: 0,
-                more_transitions: 0,
-                _reserved: 0,
-            };
-            transition_count
-        ];
-        let dst_bytes = bytemuck::cast_slice_mut::<WeightedTransition, u8>(&mut transitions);
-        dst_bytes.copy_from_slice(&remaining[..transition_count * size_of::<WeightedTransition>()]);
+        let transitions =
+            TransitionTable::<WeightedTransition>::new(shared, transition_offset, transition_count);
 
         Ok(Self {
             transitions,
@@ -110,16 +138,53 @@ impl WeightedTransducer {
         &self.symbols
     }
 
+    /// Whether the transition table is read in place from the data the
+    /// transducer was loaded from, rather than copied.
+    pub fn shares_data(&self) -> bool {
+        self.transitions.is_shared()
+    }
+
     /// Return the number of flag diacritic features.
     pub fn flag_feature_count(&self) -> u16 {
         self.symbols.flag_feature_count
     }
 
+    /// List the flag diacritic features and their values; see
+    /// [`SymbolTable::flag_features`].
+    pub fn flag_features(&self) -> Vec<FlagFeature> {
+        self.symbols.flag_features()
+    }
+
+    /// Check the flag diacritics for inconsistent features; see
+    /// [`SymbolTable::validate_flags`]. Loading does not run the check.
+    pub fn validate_flags(&self) -> Vec<FlagDiagnostic> {
+        self.symbols.validate_flags()
+    }
+
     /// Create a new configuration suitable for this transducer.
     pub fn new_config(&self, buffer_size: usize) -> WeightedConfig {
         WeightedConfig::new(self.symbols.flag_feature_count, buffer_size)
     }
 
+    /// Return an iterator over all outputs for the given input, each paired
+    /// with its accumulated weight.
+    pub fn weighted_outputs(&self, input: &[char]) -> WeightedOutputIter<'_> {
+        WeightedOutputIter::new(self, input)
+    }
+
+    /// Return an iterator over all outputs for the given input in increasing
+    /// weight order, cheapest first. See [`BestFirstOutputs`].
+    ///
+    /// `beam_width` bounds the number of partial paths kept during the
+    /// search; `None` keeps them all.
+    pub fn best_first_outputs(
+        &self,
+        input: &[char],
+        beam_width: Option<usize>,
+    ) -> BestFirstOutputs<'_> {
+        BestFirstOutputs::new(self, input, beam_width)
+    }
+
     /// Yield the next output with its accumulated weight.
     ///
     /// Returns `true` if an output was found. The weight and first-not-reached
@@ -132,14 +197,35 @@ impl WeightedTransducer {
         output: &mut String,
         result: &mut WeightedResult,
     ) -> bool {
-        let transitions = &self.transitions;
+        self.next_weighted_status(config, output, result) == NextStatus::Output
+    }
+
+    /// Like [`next_weighted`](Self::next_weighted), but tells the end of the
+    /// outputs from a traversal stopped by its budget.
+    ///
+    /// Origin: WeightedTransducer::next() -- WeightedTransducer.cpp:298-406
+    pub fn next_weighted_status(
+        &self,
+        config: &mut WeightedConfig,
+        output: &mut String,
+        result: &mut WeightedResult,
+    ) -> NextStatus {
+        let transitions = self.transitions.as_slice();
         let first_normal = self.symbols.first_normal_char as u32;
         let flag_feature_count = self.symbols.flag_feature_count;
+        let budget = config.budget;
 
         let mut loop_counter: u32 = 0;
         result.first_not_reached_position = config.input_depth;
+        if config.output_count >= budget.max_outputs {
+            return NextStatus::BudgetExceeded;
+        }
+
+        'outer: while loop_counter < budget.max_steps {
+            if budget.past_deadline(loop_counter) {
+                return NextStatus::BudgetExceeded;
+            }
 
-        'outer: while loop_counter < MAX_LOOP_COUNT {
             let state_idx = config.state_index_stack[config.stack_depth];
             let current_idx = config.current_transition_stack[config.stack_depth];
             let start_transition_index = current_idx - state_idx;
@@ -177,11 +263,15 @@ impl WeightedTransducer {
                         // Compute total weight
                         let mut total_weight = ct.weight;
                         for i in 0..config.stack_depth {
-                            total_weight +=
-                                transitions[config.current_transition_stack[i] as usize].weight;
+                            // Wraps like the release build instead of
+                            // panicking on weights of a corrupt file.
+                            total_weight = total_weight.wrapping_add(
+                                transitions[config.current_transition_stack[i] as usize].weight,
+                            );
                         }
                         result.weight = total_weight;
-                        return true;
+                        config.output_count += 1;
+                        return NextStatus::Output;
                     }
                 } else if input_sym == 0 && ct.sym_in >= first_normal {
                     // Only normal transitions left but input is exhausted
@@ -192,7 +282,7 @@ impl WeightedTransducer {
                 {
                     // Push down
                     if config.stack_depth + 2 == config.buffer_size {
-                        return false;
+                        return NextStatus::BudgetExceeded;
                     }
 
                     config.output_symbol_stack[config.stack_depth] = if ct.sym_out >= first_normal {
@@ -237,7 +327,7 @@ impl WeightedTransducer {
 
             // All transitions exhausted
             if config.stack_depth == 0 {
-                return false;
+                return NextStatus::Exhausted;
             }
 
             // Pop (backtrack up)
@@ -254,7 +344,45 @@ impl WeightedTransducer {
             loop_counter += 1;
         }
 
-        false
+        NextStatus::BudgetExceeded
+    }
+
+    /// Describe the path of the output most recently yielded by
+    /// [`next_weighted`](Self::next_weighted) with `config`: the states,
+    /// symbols, flag diacritics and weights along it.
+    pub fn trace(&self, config: &WeightedConfig) -> PathTrace {
+        let strings = &self.symbols.symbol_strings;
+        let first_normal = self.symbols.first_normal_char as u32;
+        let mut flag_values = vec![FLAG_VALUE_NEUTRAL; self.symbols.flag_feature_count as usize];
+        let steps = (0..config.stack_depth)
+            .map(|depth| {
+                let transition = &self.transitions[config.current_transition_stack[depth] as usize];
+                let is_flag = transition.sym_in != 0 && transition.sym_in < first_normal;
+                TraceStep {
+                    source_state: config.state_index_stack[depth],
+                    target_state: transition.target_state,
+                    input: strings[transition.sym_in as usize].clone(),
+                    output: if transition.sym_out >= first_normal {
+                        strings[transition.sym_out as usize].clone()
+                    } else {
+                        String::new()
+                    },
+                    is_flag,
+                    flag: is_flag.then(|| {
+                        self.symbols
+                            .flag_event(&mut flag_values, transition.sym_in as u16)
+                    }),
+                    weight: transition.weight,
+                }
+            })
+            .collect();
+        // `next_weighted` leaves the index just past the final transition
+        let final_index = config.current_transition_stack[config.stack_depth] as usize - 1;
+        PathTrace {
+            steps,
+            final_state: config.state_index_stack[config.stack_depth],
+            final_weight: self.transitions[final_index].weight,
+        }
     }
 
     /// Backtrack the traversal state to a specific output depth.
@@ -265,7 +393,7 @@ impl WeightedTransducer {
     /// Origin: WeightedTransducer::backtrackToOutputDepth() -- WeightedTransducer.cpp:408-428
     pub fn backtrack_to_output_depth(&self, config: &mut WeightedConfig, depth: usize) {
         let first_normal = self.symbols.first_normal_char as u32;
-        let transitions = &self.transitions;
+        let transitions = self.transitions.as_slice();
 
         let mut output_depth: usize = 0;
         let mut stack_index: usize = 0;
@@ -290,6 +418,27 @@ impl WeightedTransducer {
         }
     }
 
+    /// Flag values after taking an epsilon or flag diacritic transition with
+    /// input `symbol` from a path with `flags`, or `None` if the flag
+    /// diacritic rejects it. Used by
+    /// [`BestFirstOutputs`], where each queued path owns its flag values.
+    fn apply_flag(&self, flags: &Rc<[u32]>, symbol: u32) -> Option<Rc<[u32]>> {
+        if self.symbols.flag_feature_count == 0 || symbol == 0 {
+            return Some(Rc::clone(flags));
+        }
+
+        let ofv = &self.symbols.symbol_to_diacritic[symbol as usize];
+        match flags::check_flag(ofv, flags[ofv.feature as usize] as u16) {
+            FlagCheckResult::Reject => None,
+            FlagCheckResult::AcceptAndUpdate { feature, value } => {
+                let mut updated = flags.to_vec();
+                updated[feature as usize] = u32::from(value);
+                Some(updated.into())
+            }
+            FlagCheckResult::AcceptNoUpdate { .. } => Some(Rc::clone(flags)),
+        }
+    }
+
     /// Check flag diacritic and update state if allowed (copy-on-push variant).
     ///
     /// Origin: flagDiacriticCheck() -- WeightedTransducer.cpp:230-286
@@ -321,9 +470,216 @@ impl WeightedTransducer {
     }
 }
 
+/// Iterator over the outputs of a weighted transducer, cheapest first.
+///
+/// Created by [`WeightedTransducer::best_first_outputs`]. Partial paths are
+/// kept in a priority queue and the cheapest one is expanded next. This is
+/// A* with a zero heuristic, since the transition table gives no bound on
+/// the remaining weight of a path. Outputs are therefore yielded in
+/// increasing weight order as long as no transition has a negative weight.
+/// The same output is yielded once per accepting path.
+///
+/// With a beam width, only that many of the cheapest queued paths survive
+/// each expansion, trading completeness for bounded work. As in
+/// [`next_weighted`](WeightedTransducer::next_weighted), the search stops
+/// after [`MAX_LOOP_COUNT`] expansions. Weights are summed in `i32`, so
+/// long paths do not overflow the `i16` transition weights.
+pub struct BestFirstOutputs<'a> {
+    transducer: &'a WeightedTransducer,
+    input: Vec<u32>,
+    beam_width: Option<usize>,
+    queue: BinaryHeap<Reverse<SearchEntry>>,
+    /// Output symbols of all queued paths as `(previous link, symbol)`
+    /// links; each path refers to the link of its last output symbol.
+    links: Vec<(Option<usize>, u32)>,
+    sequence: u64,
+    expansions: u32,
+}
+
+/// A queued path: either a partial path ending in `state`, or a complete
+/// path whose final weight has been added.
+struct SearchEntry {
+    weight: i32,
+    /// Insertion order, so equal weights are expanded first-in first-out.
+    sequence: u64,
+    state: u32,
+    input_depth: usize,
+    flags: Rc<[u32]>,
+    link: Option<usize>,
+    complete: bool,
+}
+
+impl PartialEq for SearchEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SearchEntry {}
+
+impl PartialOrd for SearchEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SearchEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.weight, self.sequence).cmp(&(other.weight, other.sequence))
+    }
+}
+
+impl<'a> BestFirstOutputs<'a> {
+    fn new(transducer: &'a WeightedTransducer, input: &[char], beam_width: Option<usize>) -> Self {
+        let mut search = Self {
+            transducer,
+            input: Vec::with_capacity(input.len()),
+            beam_width: beam_width.map(|width| width.max(1)),
+            queue: BinaryHeap::new(),
+            links: Vec::new(),
+            sequence: 0,
+            expansions: 0,
+        };
+        for ch in input {
+            // Unknown input symbols leave the queue empty, as `prepare` fails
+            let Some(sym_idx) = transducer.symbols.char_index(*ch) else {
+                return search;
+            };
+            search.input.push(sym_idx as u32);
+        }
+        let flag_count = transducer.symbols.flag_feature_count as usize;
+        search.push(SearchEntry {
+            weight: 0,
+            sequence: 0,
+            state: 0,
+            input_depth: 0,
+            flags: vec![0; flag_count].into(),
+            link: None,
+            complete: false,
+        });
+        search
+    }
+
+    fn push(&mut self, mut entry: SearchEntry) {
+        entry.sequence = self.sequence;
+        self.sequence += 1;
+        self.queue.push(Reverse(entry));
+    }
+
+    /// Queue the successors of the partial path `entry`.
+    fn expand(&mut self, entry: &SearchEntry) {
+        let transducer = self.transducer;
+        let transitions = transducer.transitions.as_slice();
+        let first_normal = transducer.symbols.first_normal_char as u32;
+        let max_tc = weighted_max_tc(transitions, entry.state);
+        let input_exhausted = entry.input_depth == self.input.len();
+        let input_sym = if input_exhausted {
+            0
+        } else {
+            self.input[entry.input_depth]
+        };
+
+        for tc in 0..=max_tc {
+            if tc == 1 && max_tc >= 255 {
+                // Overflow cell, not a transition
+                continue;
+            }
+            let ct = &transitions[(entry.state + tc) as usize];
+            let weight = entry.weight + i32::from(ct.weight);
+            if ct.sym_in == WEIGHTED_FINAL_SYM {
+                if input_exhausted {
+                    self.push(SearchEntry {
+                        weight,
+                        flags: Rc::clone(&entry.flags),
+                        complete: true,
+                        ..*entry
+                    });
+                }
+                continue;
+            }
+            let input_depth = if ct.sym_in < first_normal {
+                entry.input_depth
+            } else if ct.sym_in == input_sym && !input_exhausted {
+                entry.input_depth + 1
+            } else if ct.sym_in > input_sym {
+                // Transitions are sorted; no more matches possible
+                break;
+            } else {
+                continue;
+            };
+            let flags = if ct.sym_in < first_normal {
+                match transducer.apply_flag(&entry.flags, ct.sym_in) {
+                    Some(flags) => flags,
+                    None => continue,
+                }
+            } else {
+                Rc::clone(&entry.flags)
+            };
+            let link = if ct.sym_out >= first_normal {
+                self.links.push((entry.link, ct.sym_out));
+                Some(self.links.len() - 1)
+            } else {
+                entry.link
+            };
+            self.push(SearchEntry {
+                weight,
+                sequence: 0,
+                state: ct.target_state,
+                input_depth,
+                flags,
+                link,
+                complete: false,
+            });
+        }
+
+        if let Some(width) = self.beam_width.filter(|&width| self.queue.len() > width) {
+            let mut entries = std::mem::take(&mut self.queue).into_vec();
+            entries.select_nth_unstable_by(width, |Reverse(a), Reverse(b)| a.cmp(b));
+            entries.truncate(width);
+            self.queue = entries.into();
+        }
+    }
+
+    /// Build the output string of a path from its output symbol links.
+    fn output(&self, mut link: Option<usize>) -> String {
+        let mut symbols = Vec::new();
+        while let Some(index) = link {
+            let (previous, symbol) = self.links[index];
+            symbols.push(symbol);
+            link = previous;
+        }
+        let strings = &self.transducer.symbols.symbol_strings;
+        symbols
+            .iter()
+            .rev()
+            .map(|&symbol| strings[symbol as usize].as_str())
+            .collect()
+    }
+}
+
+impl Iterator for BestFirstOutputs<'_> {
+    type Item = (String, i32);
+
+    fn next(&mut self) -> Option<(String, i32)> {
+        while self.expansions < MAX_LOOP_COUNT {
+            let Reverse(entry) = self.queue.pop()?;
+            if entry.complete {
+                return Some((self.output(entry.link), entry.weight));
+            }
+            self.expand(&entry);
+            self.expansions += 1;
+        }
+        None
+    }
+}
+
 impl Transducer for WeightedTransducer {
     type Config = WeightedConfig;
 
+    fn new_config(&self, buffer_size: usize) -> Self::Config {
+        WeightedConfig::new(self.symbols.flag_feature_count, buffer_size)
+    }
+
     /// Prepare for traversal with the given input characters.
     ///
     /// Returns `false` immediately if any input character is unknown (no
@@ -333,8 +689,8 @@ impl Transducer for WeightedTransducer {
     fn prepare(&self, config: &mut Self::Config, input: &[char]) -> bool {
         config.reset();
         for &ch in input {
-            match self.symbols.char_to_symbol.get(&ch) {
-                Some(&sym_idx) => {
+            match self.symbols.char_index(ch) {
+                Some(sym_idx) => {
                     config.input_symbol_stack[config.input_length] = sym_idx as u32;
                 }
                 None => {
@@ -346,21 +702,46 @@ impl Transducer for WeightedTransducer {
         true
     }
 
+    /// Prepare the configuration for traversing with the given UTF-8 input.
+    ///
+    /// As with [`prepare`](Self::prepare), an unknown character causes an
+    /// immediate `false` return, as does input with too many symbols for the
+    /// configuration.
+    fn prepare_str(&self, config: &mut Self::Config, input: &str) -> bool {
+        config.reset();
+        let mut rest = input;
+        while !rest.is_empty() {
+            if config.input_length + 1 >= config.buffer_size {
+                return false;
+            }
+            let (Some(sym_idx), len) = self.symbols.match_symbol(rest) else {
+                return false;
+            };
+            config.input_symbol_stack[config.input_length] = sym_idx as u32;
+            config.input_length += 1;
+            rest = &rest[len..];
+        }
+        true
+    }
+
     /// Yield the next output from the weighted transducer (discarding weight info).
     ///
     /// For weight-aware traversal, use [`next_weighted`](Self::next_weighted).
-    fn next(&self, config: &mut Self::Config, output: &mut String) -> bool {
+    fn next_status(&self, config: &mut Self::Config, output: &mut String) -> NextStatus {
         let mut result = WeightedResult {
             weight: 0,
             first_not_reached_position: 0,
         };
-        self.next_weighted(config, output, &mut result)
+        self.next_weighted_status(config, output, &mut result)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::VfstSection;
+    use crate::flags::FlagOp;
+    use crate::validate::TransitionDefect;
 
     fn build_header(weighted: bool) -> Vec<u8> {
         let mut buf = vec![0u8; 16];
@@ -397,6 +778,139 @@ mod tests {
         }
     }
 
+    fn build_weighted_vfst(symbols: &[&str], transitions: &[WeightedTransition]) -> Vec<u8> {
+        let mut data = build_header(true);
+        data.extend_from_slice(&build_symbol_table(symbols));
+        let partial = data.len() % 16;
+        if partial > 0 {
+            data.extend(std::iter::repeat_n(0u8, 16 - partial));
+        }
+        for t in transitions {
+            data.extend_from_slice(bytemuck::bytes_of(t));
+        }
+        data
+    }
+
+    /// "ab" -> "xb" (weight 0 + 10) or "yb" (weight 1 + 0): the cheaper
+    /// path starts with the more expensive transition.
+    fn build_detour_vfst() -> Vec<u8> {
+        let symbols: &[&str] = &["", "a", "b", "x", "y"];
+        build_weighted_vfst(
+            symbols,
+            &[
+                // State 0: 'a' -> 'x' (state 2, weight 0), 'a' -> 'y' (state 4, weight 1)
+                make_weighted_transition(1, 3, 2, 0, 1),
+                make_weighted_transition(1, 4, 4, 1, 0),
+                // State 2: 'b' -> 'b', weight 10
+                make_weighted_transition(2, 2, 3, 10, 0),
+                // State 3: final
+                make_weighted_transition(0xFFFFFFFF, 0, 0, 0, 0),
+                // State 4: 'b' -> 'b', weight 0
+                make_weighted_transition(2, 2, 5, 0, 0),
+                // State 5: final
+                make_weighted_transition(0xFFFFFFFF, 0, 0, 0, 0),
+            ],
+        )
+    }
+
+    #[test]
+    fn best_first_yields_cheapest_first() {
+        let t = WeightedTransducer::from_bytes(&build_detour_vfst()).unwrap();
+        let input: Vec<char> = "ab".chars().collect();
+
+        let depth_first: Vec<(String, f32)> = t.weighted_outputs(&input).collect();
+        assert_eq!(depth_first[0].0, "xb");
+
+        let best_first: Vec<(String, i32)> = t.best_first_outputs(&input, None).collect();
+        assert_eq!(
+            best_first,
+            vec![("yb".to_string(), 1), ("xb".to_string(), 10)]
+        );
+    }
+
+    #[test]
+    fn best_first_beam_drops_expensive_prefixes() {
+        let t = WeightedTransducer::from_bytes(&build_detour_vfst()).unwrap();
+        let input: Vec<char> = "ab".chars().collect();
+
+        // After the first step only the "x" prefix (weight 0) fits the beam
+        let narrow: Vec<(String, i32)> = t.best_first_outputs(&input, Some(1)).collect();
+        assert_eq!(narrow, vec![("xb".to_string(), 10)]);
+
+        let wide: Vec<(String, i32)> = t.best_first_outputs(&input, Some(2)).collect();
+        assert_eq!(wide.len(), 2);
+    }
+
+    #[test]
+    fn best_first_unknown_input_is_empty() {
+        let t = WeightedTransducer::from_bytes(&build_detour_vfst()).unwrap();
+        assert_eq!(t.best_first_outputs(&['a', 'z'], None).count(), 0);
+        // Input that is known but not accepted
+        assert_eq!(t.best_first_outputs(&['a'], None).count(), 0);
+    }
+
+    /// Epsilon branches: @P.X.A@ @R.X.A@ 'a' (weight 5), and
+    /// @P.X.B@ @R.X.A@ 'b' (weight 1), which the require flag rejects.
+    fn build_flag_vfst() -> Vec<u8> {
+        let symbols: &[&str] = &["", "@P.X.A@", "@P.X.B@", "@R.X.A@", "a", "b"];
+        build_weighted_vfst(
+            symbols,
+            &[
+                // State 0: two flag branches
+                make_weighted_transition(1, 0, 2, 0, 1),
+                make_weighted_transition(2, 0, 5, 0, 0),
+                // State 2: require A, then 'a' -> 'a'
+                make_weighted_transition(3, 0, 3, 0, 0),
+                make_weighted_transition(4, 4, 4, 5, 0),
+                // State 4: final
+                make_weighted_transition(0xFFFFFFFF, 0, 0, 0, 0),
+                // State 5: require A, then 'a' -> 'b'
+                make_weighted_transition(3, 0, 6, 0, 0),
+                make_weighted_transition(4, 5, 7, 1, 0),
+                // State 7: final
+                make_weighted_transition(0xFFFFFFFF, 0, 0, 0, 0),
+            ],
+        )
+    }
+
+    #[test]
+    fn best_first_applies_flag_diacritics() {
+        let t = WeightedTransducer::from_bytes(&build_flag_vfst()).unwrap();
+
+        let depth_first: Vec<String> = t.outputs(&['a']).collect();
+        let best_first: Vec<(String, i32)> = t.best_first_outputs(&['a'], None).collect();
+        assert_eq!(depth_first, vec!["a"]);
+        assert_eq!(best_first, vec![("a".to_string(), 5)]);
+    }
+
+    #[test]
+    fn trace_records_flag_operations() {
+        let t = WeightedTransducer::from_bytes(&build_flag_vfst()).unwrap();
+        assert_eq!(t.flag_features()[0].values, ["A", "B"]);
+
+        let mut config = t.new_config(100);
+        assert!(t.prepare(&mut config, &['a']));
+        let mut output = String::new();
+        let mut result = WeightedResult {
+            weight: 0,
+            first_not_reached_position: 0,
+        };
+        assert!(t.next_weighted(&mut config, &mut output, &mut result));
+        let trace = t.trace(&config);
+        let events: Vec<(FlagOp, Option<&str>, Option<&str>)> = trace
+            .flag_events()
+            .map(|e| (e.op, e.before.as_deref(), e.after.as_deref()))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                (FlagOp::P, None, Some("A")),
+                (FlagOp::R, Some("A"), Some("A"))
+            ]
+        );
+        assert!(trace.steps[2].flag.is_none());
+    }
+
     /// Build a minimal weighted VFST: "ab" -> "xy" with weights.
     fn build_simple_weighted_vfst() -> Vec<u8> {
         let symbols: &[&str] = &["", "a", "b", "x", "y"];
@@ -428,6 +942,69 @@ mod tests {
         data
     }
 
+    #[test]
+    fn strict_load_rejects_corrupt_transitions() {
+        let data = build_simple_weighted_vfst();
+        assert!(WeightedTransducer::from_bytes_strict(&data).is_ok());
+
+        let symbols: &[&str] = &["", "a", "b", "x", "y"];
+        let data = build_weighted_vfst(
+            symbols,
+            &[
+                make_weighted_transition(1, 3, 1, 10, 0),
+                make_weighted_transition(2, 4, 7, 20, 0),
+                make_weighted_transition(0xFFFFFFFF, 0, 0, 5, 0),
+            ],
+        );
+        assert!(WeightedTransducer::from_bytes(&data).is_ok());
+        let err = WeightedTransducer::from_bytes_strict(&data).unwrap_err();
+        assert_eq!(err.section(), VfstSection::Transitions);
+        assert!(matches!(
+            err,
+            VfstError::CorruptTransition {
+                index: 1,
+                defect: TransitionDefect::TargetOutOfRange {
+                    target: 7,
+                    count: 3
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn partial_transition_is_truncation() {
+        let data = build_simple_weighted_vfst();
+        let size = size_of::<WeightedTransition>();
+        let err = WeightedTransducer::from_bytes(&data[..data.len() - size / 2]).unwrap_err();
+        let truncation = err.truncation().unwrap();
+        assert_eq!(truncation.section, VfstSection::Transitions);
+        assert_eq!(truncation.missing(), size / 2);
+        assert_eq!(truncation.expected, data.len());
+    }
+
+    #[test]
+    fn strictly_loaded_corrupt_data_never_panics() {
+        let inputs: [&[char]; 3] = [&['a', 'b'], &['a'], &[]];
+        let data = build_simple_weighted_vfst();
+        for mutant in crate::validate::mutants(&data, 0x5EED, 2000) {
+            let Ok(t) = WeightedTransducer::from_bytes_strict(&mutant) else {
+                continue;
+            };
+            let mut config = t.new_config(16);
+            let mut output = String::new();
+            for input in inputs {
+                if t.prepare(&mut config, input) {
+                    while t.next_status(&mut config, &mut output) == NextStatus::Output {
+                        t.trace(&config);
+                    }
+                }
+                t.best_first_outputs(input, Some(4))
+                    .take(100)
+                    .for_each(drop);
+            }
+        }
+    }
+
     #[test]
     fn load_weighted_transducer() {
         let data = build_simple_weighted_vfst();
@@ -465,6 +1042,28 @@ mod tests {
         assert_eq!(result.weight, 35);
     }
 
+    #[test]
+    fn trace_weighted_path() {
+        let data = build_simple_weighted_vfst();
+        let t = WeightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+        assert!(t.prepare(&mut config, &['a', 'b']));
+        let mut output = String::new();
+        let mut result = WeightedResult {
+            weight: 0,
+            first_not_reached_position: 0,
+        };
+        assert!(t.next_weighted(&mut config, &mut output, &mut result));
+
+        let trace = t.trace(&config);
+        let weights: Vec<i16> = trace.steps.iter().map(|s| s.weight).collect();
+        assert_eq!(weights, [10, 20]);
+        assert_eq!(trace.steps[1].input, "b");
+        assert_eq!(trace.steps[1].output, "y");
+        assert_eq!((trace.final_state, trace.final_weight), (2, 5));
+        assert_eq!(trace.weight(), i32::from(result.weight));
+    }
+
     #[test]
     fn traverse_weighted_unknown_input() {
         let data = build_simple_weighted_vfst();
@@ -476,6 +1075,35 @@ mod tests {
         assert!(!t.prepare(&mut config, &input));
     }
 
+    #[test]
+    fn traverse_weighted_prepare_str() {
+        let data = build_simple_weighted_vfst();
+        let t = WeightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(100);
+
+        assert!(t.prepare_str(&mut config, "ab"));
+        let mut output = String::new();
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "xy");
+
+        assert!(!t.prepare_str(&mut config, "az"));
+    }
+
+    #[test]
+    fn traverse_weighted_prepare_str_rejects_input_longer_than_config() {
+        let data = build_simple_weighted_vfst();
+        let t = WeightedTransducer::from_bytes(&data).unwrap();
+        let mut config = t.new_config(4);
+
+        assert!(t.prepare_str(&mut config, "aba"));
+        assert!(!t.prepare_str(&mut config, "ababab"));
+
+        assert!(t.prepare_str(&mut config, "ab"));
+        let mut output = String::new();
+        assert!(t.next(&mut config, &mut output));
+        assert_eq!(output, "xy");
+    }
+
     #[test]
     fn traverse_weighted_via_trait() {
         let data = build_simple_weighted_vfst();
@@ -550,6 +1178,28 @@ mod tests {
         assert!(!t.next_weighted(&mut config, &mut output, &mut result));
     }
 
+    #[test]
+    fn weighted_outputs_iterator() {
+        let data = build_simple_weighted_vfst();
+        let t = WeightedTransducer::from_bytes(&data).unwrap();
+        let input: Vec<char> = "ab".chars().collect();
+
+        let outputs: Vec<(String, f32)> = t.weighted_outputs(&input).collect();
+        assert_eq!(outputs, vec![("xy".to_string(), 35.0)]);
+
+        let plain: Vec<String> = t.outputs(&input).collect();
+        assert_eq!(plain, vec!["xy"]);
+    }
+
+    #[test]
+    fn weighted_outputs_unknown_input_is_empty() {
+        let data = build_simple_weighted_vfst();
+        let t = WeightedTransducer::from_bytes(&data).unwrap();
+        let input: Vec<char> = "az".chars().collect();
+        assert_eq!(t.weighted_outputs(&input).count(), 0);
+        assert_eq!(t.outputs(&input).count(), 0);
+    }
+
     #[test]
     fn weighted_early_break_on_exhausted_input() {
         // Input "a", but state has transitions for both 'a' and 'b'
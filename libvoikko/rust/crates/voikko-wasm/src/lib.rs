@@ -8,19 +8,40 @@
 // Usage from JavaScript:
 //
 //   const voikko = new WasmVoikko(morVfstBytes, autocorrVfstBytes);
+//   // or, on a memory-constrained device: new WasmVoikko(morVfstBytes, { profile: "lite" });
+//   // or: await WasmVoikko.fromUrls("dict/mor.vfst.gz", "dict/autocorr.vfst");
 //   voikko.spell("koira");       // => true
 //   voikko.suggest("koirra");    // => ["koira", ...]
+//   voikko.checkWord("koirra");  // => { correct: false, suggestions: ["koira", ...] }
 //   voikko.analyze("koira");     // => [{ CLASS: "nimisana", ... }, ...]
+//   voikko.generate("koira", { SIJAMUOTO: "omanto", NUMBER: "plural" }); // => ["koirain", "koirien"]
+//   voikko.complete("koira", 10); // => ["koira", "koiraa", "koiraan", ...]
 //   voikko.hyphenate("koira");   // => "   - "
 //   voikko.grammarErrors("...");  // => [{ errorCode: 2, ... }, ...]
 //   voikko.tokens("Koira.");     // => [{ tokenType: "Word", ... }, ...]
+//   voikko.checkText("Koirra haukkuu.", 3); // => [{ start: 0, end: 6, word: "Koirra", suggestions: [...] }]
 //   voikko.sentences("A. B.");    // => [{ sentenceType: "Probable", ... }, ...]
+//   voikko.sentenceSpans("A. B."); // => [{ sentenceType: "Probable", text: "A. ", start: 0, ... }, ...]
+//   voikko.addUserWord("blogi", "paperi"); // accepts "blogi", "blogeissa", ...
+//   voikko.grammarErrorsChunked(text, (errors, start, end) => postMessage(errors));
+//   voikko.checkTextJson(new TextEncoder().encode(text)); // => Uint8Array of JSON
 //   voikko.terminate();           // optional cleanup
 
-use serde::Serialize;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 
-use voikko_fi::handle::{VoikkoError, VoikkoHandle};
+use voikko_core::analysis::Analysis;
+use voikko_core::grammar_error::{GrammarError, GrammarErrorSeverity};
+use voikko_core::token::{SentenceWhitespace, Token};
+use voikko_fi::grammar::numbers::NumberStyle;
+use voikko_fi::handle::{HandleProfile, SpellStatus, VoikkoError, VoikkoHandle};
+use voikko_fi::input_map::InputMapping;
+use voikko_fi::invisible::InvisibleChars;
+use voikko_fi::suggestion::RankingWeights;
 
 // ============================================================================
 // Serde-serializable DTO types for JS interop
@@ -34,7 +55,55 @@ struct JsGrammarError {
     start_pos: usize,
     error_len: usize,
     suggestions: Vec<String>,
+    replacements: Vec<JsReplacement>,
     short_description: String,
+    severity: String,
+    category: String,
+}
+
+/// Serializable edit that applies one grammar error suggestion.
+#[derive(Serialize)]
+struct JsReplacement {
+    start: usize,
+    len: usize,
+    text: String,
+}
+
+/// Serializable result of a word check.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsWordCheck {
+    correct: bool,
+    suggestions: Option<Vec<String>>,
+    too_long: bool,
+}
+
+/// Serializable misspelled word of a checked text.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsTextIssue {
+    start: usize,
+    end: usize,
+    word: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestions: Option<Vec<String>>,
+}
+
+/// Serializable user dictionary entry.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsUserWord {
+    word: String,
+    hint: Option<String>,
+}
+
+/// Serializable counts of lookups cut short by the traversal budget.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsTraversalStats {
+    steps: u64,
+    outputs: u64,
+    deadline: u64,
 }
 
 /// Serializable representation of a token.
@@ -45,6 +114,10 @@ struct JsToken {
     text: String,
     token_len: usize,
     pos: usize,
+    start_byte: usize,
+    end_byte: usize,
+    start_utf16: usize,
+    end_utf16: usize,
 }
 
 /// Serializable representation of a sentence boundary.
@@ -53,28 +126,63 @@ struct JsToken {
 struct JsSentence {
     sentence_type: String,
     sentence_len: usize,
+    trimmed_len: usize,
+}
+
+/// Serializable representation of a sentence with its offsets.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsSentenceSpan {
+    sentence_type: String,
+    text: String,
+    start: usize,
+    end: usize,
+    start_byte: usize,
+    end_byte: usize,
+    start_utf16: usize,
+    end_utf16: usize,
+    trimmed_end: usize,
+    trimmed_end_byte: usize,
+    trimmed_end_utf16: usize,
+}
+
+/// Construction options: `{ profile, analysisAttributes }`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct JsInitOptions {
+    profile: Option<String>,
+    analysis_attributes: Option<Vec<String>>,
 }
 
 // ============================================================================
 // Conversion helpers
 // ============================================================================
 
-fn token_type_to_string(tt: voikko_core::enums::TokenType) -> String {
-    match tt {
-        voikko_core::enums::TokenType::None => "None".to_string(),
-        voikko_core::enums::TokenType::Word => "Word".to_string(),
-        voikko_core::enums::TokenType::Punctuation => "Punctuation".to_string(),
-        voikko_core::enums::TokenType::Whitespace => "Whitespace".to_string(),
-        voikko_core::enums::TokenType::Unknown => "Unknown".to_string(),
+fn load_state_to_string(state: voikko_fi::morphology::LoadState) -> String {
+    match state {
+        voikko_fi::morphology::LoadState::Pending => "Pending".to_string(),
+        voikko_fi::morphology::LoadState::Ready => "Ready".to_string(),
+        voikko_fi::morphology::LoadState::Failed => "Failed".to_string(),
     }
 }
 
-fn sentence_type_to_string(st: voikko_core::enums::SentenceType) -> String {
-    match st {
-        voikko_core::enums::SentenceType::None => "None".to_string(),
-        voikko_core::enums::SentenceType::NoStart => "NoStart".to_string(),
-        voikko_core::enums::SentenceType::Probable => "Probable".to_string(),
-        voikko_core::enums::SentenceType::Possible => "Possible".to_string(),
+fn severity_to_string(sev: voikko_core::grammar_error::GrammarErrorSeverity) -> String {
+    match sev {
+        voikko_core::grammar_error::GrammarErrorSeverity::Error => "Error".to_string(),
+        voikko_core::grammar_error::GrammarErrorSeverity::Warning => "Warning".to_string(),
+    }
+}
+
+fn category_to_string(cat: voikko_core::grammar_error::GrammarErrorCategory) -> String {
+    match cat {
+        voikko_core::grammar_error::GrammarErrorCategory::Spelling => "Spelling".to_string(),
+        voikko_core::grammar_error::GrammarErrorCategory::Punctuation => "Punctuation".to_string(),
+        voikko_core::grammar_error::GrammarErrorCategory::Capitalization => {
+            "Capitalization".to_string()
+        }
+        voikko_core::grammar_error::GrammarErrorCategory::Grammar => "Grammar".to_string(),
+        voikko_core::grammar_error::GrammarErrorCategory::Style => "Style".to_string(),
+        voikko_core::grammar_error::GrammarErrorCategory::RealWord => "RealWord".to_string(),
     }
 }
 
@@ -82,6 +190,234 @@ fn voikko_error_to_js(e: VoikkoError) -> JsError {
     JsError::new(&e.to_string())
 }
 
+/// The `code` property of the errors thrown by the `try*` methods.
+fn error_code(e: &VoikkoError) -> &'static str {
+    match e {
+        VoikkoError::WordTooLong { .. } => "WordTooLong",
+        VoikkoError::Cancelled => "Cancelled",
+        VoikkoError::TimedOut => "TimedOut",
+        VoikkoError::MorphologyLoad(_) => "DictionaryLoad",
+        _ => "Other",
+    }
+}
+
+/// Convert the failure of a `try_*` handle method to a JavaScript `Error`
+/// with a `code` property telling the kind of failure.
+fn try_error_to_js(e: VoikkoError) -> JsValue {
+    let error = js_sys::Error::new(&e.to_string());
+    // Setting a property of a new plain Error object cannot fail.
+    let _ = js_sys::Reflect::set(&error, &JsValue::from_str("code"), &error_code(&e).into());
+    error.into()
+}
+
+/// Convert analyses to an array of attribute objects, keeping only the
+/// `keep` attributes if given.
+fn analyses_to_js(analyses: &[Analysis], keep: Option<&[String]>) -> Result<JsValue, JsError> {
+    let arr = js_sys::Array::new();
+    for a in analyses {
+        let obj = js_sys::Object::new();
+        for (k, v) in a.attributes() {
+            if keep.is_some_and(|keep| !keep.iter().any(|name| name == k)) {
+                continue;
+            }
+            js_sys::Reflect::set(&obj, &JsValue::from_str(k), &JsValue::from_str(v))
+                .map_err(|e| JsError::new(&format!("{e:?}")))?;
+        }
+        arr.push(&obj);
+    }
+    Ok(arr.into())
+}
+
+/// Keep only the `keep` attributes of `analyses`, if given, as sorted maps.
+fn analysis_maps<'a>(
+    analyses: &'a [Analysis],
+    keep: Option<&[String]>,
+) -> Vec<BTreeMap<&'a str, &'a str>> {
+    analyses
+        .iter()
+        .map(|a| {
+            a.attributes()
+                .iter()
+                .filter(|(k, _)| keep.is_none_or(|keep| keep.iter().any(|name| name == *k)))
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect()
+        })
+        .collect()
+}
+
+fn grammar_error_dtos(errors: Vec<GrammarError>) -> Vec<JsGrammarError> {
+    errors
+        .into_iter()
+        .map(|e| JsGrammarError {
+            error_code: e.error_code,
+            start_pos: e.start_pos,
+            error_len: e.error_len,
+            suggestions: e.suggestions,
+            replacements: e
+                .replacements
+                .into_iter()
+                .map(|r| JsReplacement {
+                    start: r.start,
+                    len: r.len,
+                    text: r.text,
+                })
+                .collect(),
+            short_description: e.short_description,
+            severity: severity_to_string(e.severity),
+            category: category_to_string(e.category),
+        })
+        .collect()
+}
+
+fn grammar_errors_to_js(errors: Vec<GrammarError>) -> Result<JsValue, JsError> {
+    serde_wasm_bindgen::to_value(&grammar_error_dtos(errors))
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn token_dtos(tokens: Vec<Token>) -> Vec<JsToken> {
+    tokens
+        .into_iter()
+        .map(|t| JsToken {
+            token_type: t.token_type.name().to_string(),
+            text: t.text,
+            token_len: t.token_len,
+            pos: t.pos,
+            start_byte: t.start_byte,
+            end_byte: t.end_byte,
+            start_utf16: t.start_utf16,
+            end_utf16: t.end_utf16,
+        })
+        .collect()
+}
+
+// ============================================================================
+// Byte-oriented results for web workers
+// ============================================================================
+
+/// The text of a UTF-8 `Uint8Array` argument.
+fn utf8_text(bytes: &[u8]) -> Result<&str, JsError> {
+    std::str::from_utf8(bytes).map_err(|e| JsError::new(&format!("text is not valid UTF-8: {e}")))
+}
+
+/// Serialize `value` as UTF-8 JSON, returned to JavaScript as a
+/// `Uint8Array` that can be posted as a transferable.
+fn json_bytes(value: &impl Serialize) -> Result<Vec<u8>, JsError> {
+    serde_json::to_vec(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// The paragraphs of `text` that can be checked on their own: the runs of
+/// lines between empty lines, as (first character, text). Lines are
+/// paragraphs of their own unless paragraphs are reflowed, and reflowed
+/// paragraphs end at an empty line, so checking the runs one at a time
+/// finds the errors `grammar_errors_from_text` finds.
+fn paragraph_runs(text: &str) -> Vec<(usize, &str)> {
+    let mut runs = Vec::new();
+    let mut run: Option<(usize, usize)> = None;
+    let (mut byte, mut chars) = (0, 0);
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() {
+            if let Some((start_byte, start_char)) = run.take() {
+                runs.push((
+                    start_char,
+                    text[start_byte..byte].trim_end_matches(['\n', '\r']),
+                ));
+            }
+        } else if run.is_none() {
+            run = Some((byte, chars));
+        }
+        byte += line.len();
+        chars += line.chars().count();
+    }
+    if let Some((start_byte, start_char)) = run {
+        runs.push((
+            start_char,
+            text[start_byte..].trim_end_matches(['\n', '\r']),
+        ));
+    }
+    runs
+}
+
+// ============================================================================
+// Fetching dictionary files
+// ============================================================================
+
+/// Leading bytes of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `fetch`, available in browsers, workers and Node.js 18+.
+    #[wasm_bindgen(catch, js_name = fetch)]
+    fn global_fetch(url: &str) -> Result<js_sys::Promise, JsValue>;
+
+    /// `DecompressionStream`, bound here because web-sys only exposes it
+    /// behind its unstable-API flag.
+    type DecompressionStream;
+
+    #[wasm_bindgen(catch, constructor)]
+    fn new(format: &str) -> Result<DecompressionStream, JsValue>;
+}
+
+/// The message of a JS exception or rejection value.
+fn js_value_message(value: &JsValue) -> String {
+    match value.dyn_ref::<js_sys::Error>() {
+        Some(error) => String::from(error.message()),
+        None => value.as_string().unwrap_or_else(|| format!("{value:?}")),
+    }
+}
+
+/// Fetch `url` and return its body, gunzipped if it is gzip data.
+///
+/// A body served with `Content-Encoding: gzip` has already been decoded by
+/// the runtime; a `.vfst.gz` file served as is is recognised by its magic
+/// bytes.
+async fn fetch_dictionary_file(url: &str) -> Result<Vec<u8>, JsError> {
+    let fail =
+        |what: &str, e: JsValue| JsError::new(&format!("{what} {url}: {}", js_value_message(&e)));
+
+    let promise = global_fetch(url).map_err(|e| fail("cannot fetch", e))?;
+    let response: web_sys::Response = JsFuture::from(promise)
+        .await
+        .map_err(|e| fail("cannot fetch", e))?
+        .unchecked_into();
+    if !response.ok() {
+        return Err(JsError::new(&format!(
+            "cannot fetch {url}: HTTP {} {}",
+            response.status(),
+            response.status_text()
+        )));
+    }
+    let mut bytes = response_bytes(&response)
+        .await
+        .map_err(|e| fail("cannot read", e))?;
+
+    if bytes.starts_with(&GZIP_MAGIC) {
+        bytes = gunzip(&mut bytes)
+            .await
+            .map_err(|e| fail("cannot decompress", e))?;
+    }
+    Ok(bytes)
+}
+
+/// Read the whole body of `response`.
+async fn response_bytes(response: &web_sys::Response) -> Result<Vec<u8>, JsValue> {
+    let buffer = JsFuture::from(response.array_buffer()?).await?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Decompress gzip data with the runtime's `DecompressionStream`.
+async fn gunzip(data: &mut [u8]) -> Result<Vec<u8>, JsValue> {
+    let compressed = web_sys::Response::new_with_opt_u8_array(Some(data))?;
+    let body = compressed
+        .body()
+        .ok_or_else(|| JsValue::from_str("empty body"))?;
+    let decompressor = DecompressionStream::new("gzip")?;
+    let stream = body.pipe_through(decompressor.unchecked_ref());
+    let decompressed = web_sys::Response::new_with_opt_readable_stream(Some(&stream))?;
+    response_bytes(&decompressed).await
+}
+
 // ============================================================================
 // WasmVoikko
 // ============================================================================
@@ -93,6 +429,76 @@ fn voikko_error_to_js(e: VoikkoError) -> JsError {
 #[wasm_bindgen]
 pub struct WasmVoikko {
     handle: VoikkoHandle,
+    /// Attributes `analyze` returns, or `None` for all of them.
+    analysis_attributes: Option<Vec<String>>,
+}
+
+/// Attributes `analyze` returns by default with the lite profile.
+const LITE_ANALYSIS_ATTRIBUTES: [&str; 2] = ["BASEFORM", "CLASS"];
+
+/// Parse the options object of the constructor and `fromUrls`.
+fn parse_init_options(options: JsValue) -> Result<JsInitOptions, JsError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(JsInitOptions::default());
+    }
+    serde_wasm_bindgen::from_value(options).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn parse_profile(profile: Option<&str>) -> Result<HandleProfile, JsError> {
+    match profile {
+        None | Some("standard") => Ok(HandleProfile::Standard),
+        Some("lite") => Ok(HandleProfile::Lite),
+        Some(other) => Err(JsError::new(&format!(
+            "unknown profile \"{other}\", expected \"standard\" or \"lite\""
+        ))),
+    }
+}
+
+impl WasmVoikko {
+    fn with_handle(handle: VoikkoHandle) -> Self {
+        WasmVoikko {
+            handle,
+            analysis_attributes: None,
+        }
+    }
+
+    /// Create an instance with the given construction options.
+    fn with_options(
+        mor_data: &[u8],
+        autocorr_data: Option<&[u8]>,
+        options: JsInitOptions,
+    ) -> Result<Self, JsError> {
+        let profile = parse_profile(options.profile.as_deref())?;
+        let handle = VoikkoHandle::from_bytes_with_profile(mor_data, autocorr_data, "fi", profile)
+            .map_err(voikko_error_to_js)?;
+        let analysis_attributes = options.analysis_attributes.or_else(|| {
+            (profile == HandleProfile::Lite)
+                .then(|| LITE_ANALYSIS_ATTRIBUTES.map(String::from).to_vec())
+        });
+        Ok(WasmVoikko {
+            handle,
+            analysis_attributes,
+        })
+    }
+
+    /// The misspelled words of `text` with at most `max_suggestions`
+    /// suggestions each, if given.
+    fn text_issues(&self, text: &str, max_suggestions: Option<u32>) -> Vec<JsTextIssue> {
+        self.handle
+            .misspelled_tokens(text)
+            .into_iter()
+            .map(|t| JsTextIssue {
+                start: t.start_utf16,
+                end: t.end_utf16,
+                suggestions: max_suggestions.map(|max| {
+                    let mut suggestions = self.handle.suggest(&t.text);
+                    suggestions.truncate(max as usize);
+                    suggestions
+                }),
+                word: t.text,
+            })
+            .collect()
+    }
 }
 
 #[wasm_bindgen]
@@ -100,12 +506,164 @@ impl WasmVoikko {
     /// Create a new WasmVoikko instance from raw dictionary data.
     ///
     /// - `mor_data`: contents of `mor.vfst` (morphology transducer, required)
-    /// - `autocorr_data`: contents of `autocorr.vfst` (autocorrect transducer, optional)
+    /// - `autocorr_or_options`: contents of `autocorr.vfst` (autocorrect
+    ///   transducer, optional), or the options object in its place
+    /// - `options`: `{ profile, analysisAttributes }` (optional)
+    ///
+    /// `profile` is `"standard"` (the default) or `"lite"` for
+    /// memory-constrained devices such as mobile browsers: smaller speller
+    /// cache, no autocorrect transducer and no grammar checking
+    /// (`grammarErrors` returns an empty array). `analysisAttributes` lists
+    /// the attributes `analyze` returns; it defaults to all of them, or to
+    /// `["BASEFORM", "CLASS"]` with the lite profile.
     #[wasm_bindgen(constructor)]
-    pub fn new(mor_data: &[u8], autocorr_data: Option<Vec<u8>>) -> Result<WasmVoikko, JsError> {
-        let handle = VoikkoHandle::from_bytes(mor_data, autocorr_data.as_deref(), "fi")
+    pub fn new(
+        mor_data: &[u8],
+        autocorr_or_options: JsValue,
+        options: JsValue,
+    ) -> Result<WasmVoikko, JsError> {
+        let (autocorr_data, options) = match autocorr_or_options.dyn_ref::<js_sys::Uint8Array>() {
+            Some(bytes) => (Some(bytes.to_vec()), options),
+            None if autocorr_or_options.is_undefined() || autocorr_or_options.is_null() => {
+                (None, options)
+            }
+            None => (None, autocorr_or_options),
+        };
+        WasmVoikko::with_options(
+            mor_data,
+            autocorr_data.as_deref(),
+            parse_init_options(options)?,
+        )
+    }
+
+    /// Fetch the dictionary files and create an instance from them.
+    ///
+    /// - `mor_url`: URL of `mor.vfst` (required)
+    /// - `autocorr_url`: URL of `autocorr.vfst` (optional)
+    /// - `options`: construction options as for the constructor (optional)
+    ///
+    /// Files compressed with gzip (`mor.vfst.gz`) are decompressed. The
+    /// promise rejects with an error naming the URL if a request fails, the
+    /// server answers with an error status or the data is not a valid
+    /// transducer.
+    #[wasm_bindgen(js_name = "fromUrls")]
+    pub async fn from_urls(
+        mor_url: String,
+        autocorr_url: Option<String>,
+        options: JsValue,
+    ) -> Result<WasmVoikko, JsError> {
+        let options = parse_init_options(options)?;
+        let mor_data = fetch_dictionary_file(&mor_url).await?;
+        let autocorr_data = match &autocorr_url {
+            Some(url) => Some(fetch_dictionary_file(url).await?),
+            None => None,
+        };
+        WasmVoikko::with_options(&mor_data, autocorr_data.as_deref(), options)
+    }
+
+    /// Create an instance from a dictionary pack: a zip archive (such as a
+    /// `Uint8Array` from `fetch`) holding `mor.vfst` and optionally
+    /// `autocorr.vfst` and `index.txt`, at the top level or in a directory
+    /// such as `5/mor-standard/`. Requires the `dictpack` feature.
+    #[cfg(feature = "dictpack")]
+    #[wasm_bindgen(js_name = "fromDictPack")]
+    pub fn from_dict_pack(archive: &[u8]) -> Result<WasmVoikko, JsError> {
+        let handle = VoikkoHandle::from_dict_pack(archive).map_err(voikko_error_to_js)?;
+        Ok(WasmVoikko::with_handle(handle))
+    }
+
+    /// Create an instance that defers building the morphology transducer.
+    ///
+    /// Returns quickly; call `warmUp()` (e.g. from a `setTimeout` callback)
+    /// to build the transducer before the first check, and `loadState()`
+    /// to show progress.
+    #[wasm_bindgen(js_name = "createLazy")]
+    pub fn create_lazy(
+        mor_data: &[u8],
+        autocorr_data: Option<Vec<u8>>,
+    ) -> Result<WasmVoikko, JsError> {
+        let handle = VoikkoHandle::from_bytes_lazy(mor_data, autocorr_data.as_deref(), "fi")
             .map_err(voikko_error_to_js)?;
-        Ok(WasmVoikko { handle })
+        Ok(WasmVoikko::with_handle(handle))
+    }
+
+    /// Build the morphology transducer of a lazily created instance.
+    #[wasm_bindgen(js_name = "warmUp")]
+    pub fn warm_up(&self) -> Result<(), JsError> {
+        self.handle.warm_up().map_err(voikko_error_to_js)
+    }
+
+    /// Spell and analyze `words` (by default, a built-in list of frequent
+    /// Finnish word forms) so that the first checks of them are answered
+    /// from the caches, building the transducer of a lazy instance first.
+    ///
+    /// The words are checked before the call returns, so call it once the
+    /// page has been painted (e.g. from a `setTimeout` callback) or in a
+    /// worker. Returns a promise that resolves when the words are cached,
+    /// or rejects with the load error of a lazy instance; `onDone()`, if
+    /// given, is called asynchronously before it resolves.
+    #[wasm_bindgen(js_name = "warmUpWords")]
+    pub fn warm_up_words(
+        &self,
+        words: Option<Vec<String>>,
+        on_done: Option<js_sys::Function>,
+    ) -> js_sys::Promise {
+        let result = match &words {
+            Some(words) => {
+                let words: Vec<&str> = words.iter().map(String::as_str).collect();
+                self.handle.warm_up_words(&words, 1)
+            }
+            None => self.handle.warm_up_default(1),
+        };
+        wasm_bindgen_futures::future_to_promise(async move {
+            result.map_err(|e| JsValue::from(voikko_error_to_js(e)))?;
+            if let Some(on_done) = on_done {
+                on_done.call0(&JsValue::NULL)?;
+            }
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Return "Pending", "Ready" or "Failed".
+    #[wasm_bindgen(js_name = "loadState")]
+    pub fn load_state(&self) -> String {
+        load_state_to_string(self.handle.load_state())
+    }
+
+    /// Number of dictionary lookups cut short by the traversal budget, as
+    /// `{ steps, outputs, deadline }` by the limit that stopped them.
+    #[wasm_bindgen(js_name = "traversalStats")]
+    pub fn traversal_stats(&self) -> Result<JsValue, JsError> {
+        let stats = self.handle.traversal_stats();
+        let js_stats = JsTraversalStats {
+            steps: stats.steps,
+            outputs: stats.outputs,
+            deadline: stats.deadline,
+        };
+        serde_wasm_bindgen::to_value(&js_stats).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Set whether lookups cut short by the traversal budget are recorded
+    /// with the word (see `truncatedLookups()`).
+    #[wasm_bindgen(js_name = "setTruncationDiagnostics")]
+    pub fn set_truncation_diagnostics(&mut self, value: bool) {
+        self.handle.set_truncation_diagnostics(value);
+    }
+
+    /// The most recent words whose lookup was cut short, oldest first.
+    #[wasm_bindgen(js_name = "truncatedLookups")]
+    pub fn truncated_lookups(&self) -> Vec<String> {
+        self.handle
+            .truncated_lookups()
+            .into_iter()
+            .map(|lookup| lookup.word)
+            .collect()
+    }
+
+    /// Reset the traversal counters and discard the recorded words.
+    #[wasm_bindgen(js_name = "resetTraversalStats")]
+    pub fn reset_traversal_stats(&self) {
+        self.handle.reset_traversal_stats();
     }
 
     /// Check whether a word is correctly spelled.
@@ -113,6 +671,22 @@ impl WasmVoikko {
         self.handle.spell(word)
     }
 
+    /// Spell check a word, telling a misspelled word from one too long to
+    /// check.
+    ///
+    /// Returns "Correct", "Misspelled", "TooLong" or "Truncated" (rejected
+    /// after a dictionary lookup ran out of its traversal budget).
+    #[wasm_bindgen(js_name = "spellStatus")]
+    pub fn spell_status(&self, word: &str) -> String {
+        match self.handle.spell_status(word) {
+            SpellStatus::Correct => "Correct",
+            SpellStatus::Misspelled => "Misspelled",
+            SpellStatus::TooLong => "TooLong",
+            SpellStatus::Truncated => "Truncated",
+        }
+        .to_string()
+    }
+
     /// Generate spelling suggestions for a misspelled word.
     ///
     /// Returns an array of suggested corrections, sorted by priority (best first).
@@ -120,23 +694,67 @@ impl WasmVoikko {
         self.handle.suggest(word)
     }
 
+    /// Spell check a word and, if it is misspelled, suggest corrections.
+    ///
+    /// Returns `{ correct, suggestions, tooLong }`, where `suggestions` is
+    /// `null` for a correct word and `tooLong` is `true` for a word longer
+    /// than the maximum word length, which is not checked.
+    #[wasm_bindgen(js_name = "checkWord")]
+    pub fn check_word(&self, word: &str) -> Result<JsValue, JsError> {
+        let check = self.handle.check_word(word);
+        let js_check = JsWordCheck {
+            correct: check.correct,
+            suggestions: check.suggestions,
+            too_long: check.too_long,
+        };
+        serde_wasm_bindgen::to_value(&js_check).map_err(|e| JsError::new(&e.to_string()))
+    }
+
     /// Perform morphological analysis on a word.
     ///
     /// Returns a JavaScript array of analysis objects. Each object contains
     /// string key-value pairs for morphological attributes (CLASS, BASEFORM,
     /// STRUCTURE, etc.).
     pub fn analyze(&self, word: &str) -> Result<JsValue, JsError> {
-        let analyses = self.handle.analyze(word);
-        let arr = js_sys::Array::new();
-        for a in &analyses {
-            let obj = js_sys::Object::new();
-            for (k, v) in a.attributes() {
-                js_sys::Reflect::set(&obj, &JsValue::from_str(k), &JsValue::from_str(v))
-                    .map_err(|e| JsError::new(&format!("{e:?}")))?;
-            }
-            arr.push(&obj);
+        analyses_to_js(
+            &self.handle.analyze(word),
+            self.analysis_attributes.as_deref(),
+        )
+    }
+
+    /// Set the attributes `analyze` and `tryAnalyze` return; `null` or
+    /// `undefined` returns all of them.
+    #[wasm_bindgen(js_name = "setAnalysisAttributes")]
+    pub fn set_analysis_attributes(&mut self, attributes: Option<Vec<String>>) {
+        self.analysis_attributes = attributes;
+    }
+
+    /// Return the resource profile, `"standard"` or `"lite"`.
+    pub fn profile(&self) -> String {
+        match self.handle.profile() {
+            HandleProfile::Standard => "standard".to_string(),
+            HandleProfile::Lite => "lite".to_string(),
+        }
+    }
+
+    /// Generate the forms of `baseform` with the given analysis attributes.
+    ///
+    /// `attributes` is an object of attribute names and values, e.g.
+    /// `{ SIJAMUOTO: "omanto", NUMBER: "plural" }`.
+    pub fn generate(&self, baseform: &str, attributes: JsValue) -> Result<Vec<String>, JsError> {
+        let attributes: BTreeMap<String, String> =
+            serde_wasm_bindgen::from_value(attributes).map_err(|e| JsError::new(&e.to_string()))?;
+        let mut target = Analysis::new();
+        for (key, value) in &attributes {
+            target.set(key, value);
         }
-        Ok(arr.into())
+        Ok(self.handle.generate(baseform, &target))
+    }
+
+    /// Complete `prefix` to at most `max` dictionary words, sorted
+    /// alphabetically.
+    pub fn complete(&self, prefix: &str, max: usize) -> Vec<String> {
+        self.handle.complete(prefix, max)
     }
 
     /// Hyphenate a word.
@@ -153,58 +771,87 @@ impl WasmVoikko {
     /// Check a paragraph of text for grammar errors.
     ///
     /// Returns a JavaScript array of grammar error objects with fields:
-    /// `errorCode`, `startPos`, `errorLen`, `suggestions`.
+    /// `errorCode`, `startPos`, `errorLen`, `suggestions`, `replacements`
+    /// (`{ start, len, text }` for each suggestion), `shortDescription`,
+    /// `severity` ("Error", "Warning"), `category`.
     #[wasm_bindgen(js_name = "grammarErrors")]
     pub fn grammar_errors(&self, text: &str) -> Result<JsValue, JsError> {
-        let errors = self.handle.grammar_errors(text);
-        let js_errors: Vec<JsGrammarError> = errors
-            .into_iter()
-            .map(|e| JsGrammarError {
-                error_code: e.error_code,
-                start_pos: e.start_pos,
-                error_len: e.error_len,
-                suggestions: e.suggestions,
-                short_description: e.short_description,
-            })
-            .collect();
-        serde_wasm_bindgen::to_value(&js_errors).map_err(|e| JsError::new(&e.to_string()))
+        grammar_errors_to_js(self.handle.grammar_errors(text))
     }
 
     /// Tokenize text into a list of tokens.
     ///
     /// Returns a JavaScript array of token objects with fields:
     /// `tokenType` ("Word", "Punctuation", "Whitespace", "Unknown"),
-    /// `text`, `tokenLen`, `pos`.
+    /// `text`, `tokenLen`, `pos`, and the offset ranges `startByte`/`endByte`
+    /// (UTF-8) and `startUtf16`/`endUtf16` (JavaScript string indices).
     pub fn tokens(&self, text: &str) -> Result<JsValue, JsError> {
-        let tokens = self.handle.tokens(text);
-        let js_tokens: Vec<JsToken> = tokens
-            .into_iter()
-            .map(|t| JsToken {
-                token_type: token_type_to_string(t.token_type),
-                text: t.text,
-                token_len: t.token_len,
-                pos: t.pos,
-            })
-            .collect();
-        serde_wasm_bindgen::to_value(&js_tokens).map_err(|e| JsError::new(&e.to_string()))
+        serde_wasm_bindgen::to_value(&token_dtos(self.handle.tokens(text)))
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Spell check every word of `text` in one call.
+    ///
+    /// Returns a JavaScript array with an object for each misspelled word:
+    /// `start` and `end` (JavaScript string indices), `word`, and if
+    /// `maxSuggestions` is given, `suggestions` with at most that many
+    /// suggestions, best first. Words are found as by `tokens()` and
+    /// checked as by `spell()`.
+    #[wasm_bindgen(js_name = "checkText")]
+    pub fn check_text(&self, text: &str, max_suggestions: Option<u32>) -> Result<JsValue, JsError> {
+        serde_wasm_bindgen::to_value(&self.text_issues(text, max_suggestions))
+            .map_err(|e| JsError::new(&e.to_string()))
     }
 
     /// Detect sentence boundaries in text.
     ///
     /// Returns a JavaScript array of sentence objects with fields:
-    /// `sentenceType` ("Probable", "Possible", "None"), `sentenceLen`.
+    /// `sentenceType` ("Probable", "Possible", "None"), `sentenceLen` (see
+    /// `setSentenceWhitespace`) and `trimmedLen` (without the whitespace
+    /// that follows the sentence).
     pub fn sentences(&self, text: &str) -> Result<JsValue, JsError> {
         let sentences = self.handle.sentences(text);
         let js_sentences: Vec<JsSentence> = sentences
             .into_iter()
             .map(|s| JsSentence {
-                sentence_type: sentence_type_to_string(s.sentence_type),
+                sentence_type: s.sentence_type.name().to_string(),
                 sentence_len: s.sentence_len,
+                trimmed_len: s.trimmed_len,
             })
             .collect();
         serde_wasm_bindgen::to_value(&js_sentences).map_err(|e| JsError::new(&e.to_string()))
     }
 
+    /// Detect sentences in text, with their text and offsets.
+    ///
+    /// Returns a JavaScript array of objects with fields: `sentenceType`,
+    /// `text`, `start`, `end` (characters), `startByte`, `endByte` (UTF-8)
+    /// and `startUtf16`, `endUtf16` (usable with `String.prototype.slice`),
+    /// and `trimmedEnd`, `trimmedEndByte`, `trimmedEndUtf16`, the ends
+    /// without the whitespace that follows the sentence.
+    #[wasm_bindgen(js_name = "sentenceSpans")]
+    pub fn sentence_spans(&self, text: &str) -> Result<JsValue, JsError> {
+        let js_spans: Vec<JsSentenceSpan> = self
+            .handle
+            .sentence_spans(text)
+            .into_iter()
+            .map(|s| JsSentenceSpan {
+                sentence_type: s.sentence_type.name().to_string(),
+                text: s.text,
+                start: s.start,
+                end: s.end,
+                start_byte: s.start_byte,
+                end_byte: s.end_byte,
+                start_utf16: s.start_utf16,
+                end_utf16: s.end_utf16,
+                trimmed_end: s.trimmed_end,
+                trimmed_end_byte: s.trimmed_end_byte,
+                trimmed_end_utf16: s.trimmed_end_utf16,
+            })
+            .collect();
+        serde_wasm_bindgen::to_value(&js_spans).map_err(|e| JsError::new(&e.to_string()))
+    }
+
     /// Hyphenate a word with the given separator inserted at hyphenation points.
     ///
     /// - `separator`: string to insert at hyphenation points (e.g. "-", "\u{00AD}")
@@ -235,18 +882,157 @@ impl WasmVoikko {
     /// relative to the full input text.
     #[wasm_bindgen(js_name = "grammarErrorsFromText")]
     pub fn grammar_errors_from_text(&self, text: &str) -> Result<JsValue, JsError> {
-        let errors = self.handle.grammar_errors_from_text(text);
-        let js_errors: Vec<JsGrammarError> = errors
-            .into_iter()
-            .map(|e| JsGrammarError {
-                error_code: e.error_code,
-                start_pos: e.start_pos,
-                error_len: e.error_len,
-                suggestions: e.suggestions,
-                short_description: e.short_description,
-            })
-            .collect();
-        serde_wasm_bindgen::to_value(&js_errors).map_err(|e| JsError::new(&e.to_string()))
+        grammar_errors_to_js(self.handle.grammar_errors_from_text(text))
+    }
+
+    /// Check whether a word is correctly spelled, throwing instead of
+    /// returning `false` if it cannot be checked.
+    ///
+    /// The thrown `Error` has a `code` property: "WordTooLong" for a word
+    /// longer than the maximum word length, "DictionaryLoad" if the
+    /// dictionary of an instance from `createLazy` could not be built.
+    #[wasm_bindgen(js_name = "trySpell")]
+    pub fn try_spell(&self, word: &str) -> Result<bool, JsValue> {
+        self.handle.try_spell(word).map_err(try_error_to_js)
+    }
+
+    /// Generate spelling suggestions, throwing as `trySpell` does instead
+    /// of returning an empty array.
+    #[wasm_bindgen(js_name = "trySuggest")]
+    pub fn try_suggest(&self, word: &str) -> Result<Vec<String>, JsValue> {
+        self.handle.try_suggest(word).map_err(try_error_to_js)
+    }
+
+    /// Analyze a word, throwing as `trySpell` does instead of returning no
+    /// analyses.
+    #[wasm_bindgen(js_name = "tryAnalyze")]
+    pub fn try_analyze(&self, word: &str) -> Result<JsValue, JsValue> {
+        let analyses = self.handle.try_analyze(word).map_err(try_error_to_js)?;
+        Ok(analyses_to_js(
+            &analyses,
+            self.analysis_attributes.as_deref(),
+        )?)
+    }
+
+    /// Hyphenate a word, throwing as `trySpell` does instead of returning a
+    /// pattern without hyphenation points.
+    #[wasm_bindgen(js_name = "tryHyphenate")]
+    pub fn try_hyphenate(&self, word: &str) -> Result<String, JsValue> {
+        self.handle.try_hyphenate(word).map_err(try_error_to_js)
+    }
+
+    /// Check a paragraph for grammar errors, throwing with code
+    /// "DictionaryLoad" if the dictionary could not be built.
+    #[wasm_bindgen(js_name = "tryGrammarErrors")]
+    pub fn try_grammar_errors(&self, text: &str) -> Result<JsValue, JsValue> {
+        let errors = self
+            .handle
+            .try_grammar_errors(text)
+            .map_err(try_error_to_js)?;
+        Ok(grammar_errors_to_js(errors)?)
+    }
+
+    /// Check text for grammar errors one line at a time, throwing as
+    /// `tryGrammarErrors` does.
+    #[wasm_bindgen(js_name = "tryGrammarErrorsFromText")]
+    pub fn try_grammar_errors_from_text(&self, text: &str) -> Result<JsValue, JsValue> {
+        let errors = self
+            .handle
+            .try_grammar_errors_from_text(text)
+            .map_err(try_error_to_js)?;
+        Ok(grammar_errors_to_js(errors)?)
+    }
+
+    /// Check text for grammar errors one paragraph at a time, calling
+    /// `onChunk(errors, start, end)` after each paragraph.
+    ///
+    /// Paragraphs are the runs of lines between empty lines; `errors` is an
+    /// array as from `grammarErrors`, with positions relative to the full
+    /// text like those of `grammarErrorsFromText`, and `start` and `end`
+    /// are the character range of the paragraph. Together the chunks hold
+    /// the errors `grammarErrorsFromText` returns. A worker can post each
+    /// chunk as it arrives so that the page shows the first errors of a
+    /// long document early. Returning `false` from `onChunk` stops the
+    /// check; an exception it throws is rethrown. Returns the number of
+    /// errors reported.
+    #[wasm_bindgen(js_name = "grammarErrorsChunked")]
+    pub fn grammar_errors_chunked(
+        &self,
+        text: &str,
+        on_chunk: &js_sys::Function,
+    ) -> Result<usize, JsValue> {
+        let mut total = 0;
+        for (start, paragraph) in paragraph_runs(text) {
+            let mut errors = self.handle.grammar_errors_from_text(paragraph);
+            for error in &mut errors {
+                error.shift(start);
+            }
+            total += errors.len();
+            let end = start + paragraph.chars().count();
+            let result = on_chunk.call3(
+                &JsValue::NULL,
+                &grammar_errors_to_js(errors)?,
+                &JsValue::from(start),
+                &JsValue::from(end),
+            )?;
+            if result == JsValue::FALSE {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Analyze a word given as UTF-8 bytes, returning the analyses as
+    /// UTF-8 JSON bytes.
+    ///
+    /// The JSON is an array of objects as from `analyze`. The `*Json`
+    /// methods take their text as a `Uint8Array` (e.g. from `TextEncoder`)
+    /// and return a `Uint8Array`, so that a web worker can receive the
+    /// text and post the result back as transferables without copying;
+    /// decode the result with `TextDecoder` and `JSON.parse`. They throw
+    /// if the text is not valid UTF-8.
+    #[wasm_bindgen(js_name = "analyzeJson")]
+    pub fn analyze_json(&self, word: &[u8]) -> Result<Vec<u8>, JsError> {
+        let analyses = self.handle.analyze(utf8_text(word)?);
+        json_bytes(&analysis_maps(
+            &analyses,
+            self.analysis_attributes.as_deref(),
+        ))
+    }
+
+    /// Check a paragraph given as UTF-8 bytes for grammar errors, returning
+    /// the errors of `grammarErrors` as UTF-8 JSON bytes.
+    #[wasm_bindgen(js_name = "grammarErrorsJson")]
+    pub fn grammar_errors_json(&self, text: &[u8]) -> Result<Vec<u8>, JsError> {
+        let errors = self.handle.grammar_errors(utf8_text(text)?);
+        json_bytes(&grammar_error_dtos(errors))
+    }
+
+    /// Check text given as UTF-8 bytes for grammar errors one line at a
+    /// time, returning the errors of `grammarErrorsFromText` as UTF-8 JSON
+    /// bytes.
+    #[wasm_bindgen(js_name = "grammarErrorsFromTextJson")]
+    pub fn grammar_errors_from_text_json(&self, text: &[u8]) -> Result<Vec<u8>, JsError> {
+        let errors = self.handle.grammar_errors_from_text(utf8_text(text)?);
+        json_bytes(&grammar_error_dtos(errors))
+    }
+
+    /// Spell check text given as UTF-8 bytes, returning the misspelled
+    /// words of `checkText` as UTF-8 JSON bytes.
+    #[wasm_bindgen(js_name = "checkTextJson")]
+    pub fn check_text_json(
+        &self,
+        text: &[u8],
+        max_suggestions: Option<u32>,
+    ) -> Result<Vec<u8>, JsError> {
+        json_bytes(&self.text_issues(utf8_text(text)?, max_suggestions))
+    }
+
+    /// Tokenize text given as UTF-8 bytes, returning the tokens of
+    /// `tokens` as UTF-8 JSON bytes.
+    #[wasm_bindgen(js_name = "tokensJson")]
+    pub fn tokens_json(&self, text: &[u8]) -> Result<Vec<u8>, JsError> {
+        json_bytes(&token_dtos(self.handle.tokens(utf8_text(text)?)))
     }
 
     /// Get the library version string.
@@ -261,6 +1047,157 @@ impl WasmVoikko {
         self.handle.set_speller_cache_size(size);
     }
 
+    /// Set how many words the analysis cache shared by spelling,
+    /// hyphenation, grammar checking and `analyze` holds; 0 disables it.
+    #[wasm_bindgen(js_name = "setAnalysisCacheSize")]
+    pub fn set_analysis_cache_size(&mut self, words: usize) {
+        self.handle.set_analysis_cache_size(words);
+    }
+
+    /// Load a JSON grammar rule pack. Returns the number of rules loaded.
+    #[wasm_bindgen(js_name = "loadGrammarRules")]
+    pub fn load_grammar_rules(&mut self, json: &str) -> Result<usize, JsError> {
+        self.handle
+            .load_grammar_rules(json)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Remove all grammar rules loaded with `loadGrammarRules()`.
+    #[wasm_bindgen(js_name = "clearGrammarRules")]
+    pub fn clear_grammar_rules(&mut self) {
+        self.handle.clear_grammar_rules();
+    }
+
+    /// Add message catalog entries (`[language]` sections of `key = value`
+    /// lines). Returns the number of entries loaded.
+    #[wasm_bindgen(js_name = "loadMessageCatalog")]
+    pub fn load_message_catalog(&mut self, text: &str) -> Result<usize, JsError> {
+        self.handle
+            .load_message_catalog(text)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Short description of grammar error `code` in `language`, or
+    /// `undefined` if the catalog has none.
+    #[wasm_bindgen(js_name = "errorDescription")]
+    pub fn error_description(&self, code: i32, language: &str) -> Option<String> {
+        self.handle
+            .message_catalog()
+            .description(language, code)
+            .map(str::to_string)
+    }
+
+    /// Fill the message template `key` (e.g. "did_you_mean") in `language`
+    /// with `args`, or return `undefined` if there is no such template.
+    #[wasm_bindgen(js_name = "formatMessage")]
+    pub fn format_message(&self, key: &str, language: &str, args: Vec<String>) -> Option<String> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.handle.message_catalog().message(language, key, &args)
+    }
+
+    /// Add an autocorrect rule replacing `from` (literal text or a pattern
+    /// between slashes) with `to`. Checked before the autocorrect transducer.
+    #[wasm_bindgen(js_name = "addAutocorrectRule")]
+    pub fn add_autocorrect_rule(&mut self, from: &str, to: &str) -> Result<(), JsError> {
+        self.handle
+            .add_autocorrect_rule(from, to)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Remove the autocorrect rule for `from`. Returns true if there was one.
+    #[wasm_bindgen(js_name = "removeAutocorrectRule")]
+    pub fn remove_autocorrect_rule(&mut self, from: &str) -> bool {
+        self.handle.remove_autocorrect_rule(from)
+    }
+
+    /// Remove all rules added with `addAutocorrectRule()`.
+    #[wasm_bindgen(js_name = "clearAutocorrectRules")]
+    pub fn clear_autocorrect_rules(&mut self) {
+        self.handle.clear_autocorrect_rules();
+    }
+
+    /// Hyphenate a word as given, e.g. "Voik-ko", instead of by analysis.
+    #[wasm_bindgen(js_name = "addHyphenationException")]
+    pub fn add_hyphenation_exception(&mut self, entry: &str) -> Result<(), JsError> {
+        self.handle
+            .add_hyphenation_exception(entry)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Load words with a fixed hyphenation, one per line. Returns the number
+    /// of entries loaded.
+    #[wasm_bindgen(js_name = "loadHyphenationExceptions")]
+    pub fn load_hyphenation_exceptions(&mut self, text: &str) -> Result<usize, JsError> {
+        self.handle
+            .load_hyphenation_exceptions(text)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Remove all hyphenation exceptions.
+    #[wasm_bindgen(js_name = "clearHyphenationExceptions")]
+    pub fn clear_hyphenation_exceptions(&mut self) {
+        self.handle.clear_hyphenation_exceptions();
+    }
+
+    /// Add a word to the user dictionary. `hint` is an optional dictionary
+    /// word that inflects the same way, e.g. "paperi" for "blogi"; with it,
+    /// inflected forms of the word are accepted too.
+    #[wasm_bindgen(js_name = "addUserWord")]
+    pub fn add_user_word(&mut self, word: &str, hint: Option<String>) {
+        self.handle.add_user_word(word, hint.as_deref());
+    }
+
+    /// Remove a word from the user dictionary. Returns whether it was present.
+    #[wasm_bindgen(js_name = "removeUserWord")]
+    pub fn remove_user_word(&mut self, word: &str) -> bool {
+        self.handle.remove_user_word(word)
+    }
+
+    /// List the user dictionary entries, sorted, as `{ word, hint }` objects.
+    #[wasm_bindgen(js_name = "userWords")]
+    pub fn user_words(&self) -> Result<JsValue, JsError> {
+        let js_words: Vec<JsUserWord> = self
+            .handle
+            .user_dictionary()
+            .iter()
+            .map(|(word, hint)| JsUserWord {
+                word: word.to_string(),
+                hint: hint.map(str::to_string),
+            })
+            .collect();
+        serde_wasm_bindgen::to_value(&js_words).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Serialize the user dictionary into its text file format.
+    #[wasm_bindgen(js_name = "exportUserDictionary")]
+    pub fn export_user_dictionary(&self) -> String {
+        self.handle.export_user_dictionary()
+    }
+
+    /// Add the entries of a user dictionary file. Returns the number of
+    /// entries in the file.
+    #[wasm_bindgen(js_name = "loadUserDictionary")]
+    pub fn load_user_dictionary(&mut self, text: &str) -> Result<usize, JsError> {
+        self.handle
+            .load_user_dictionary(text)
+            .map_err(voikko_error_to_js)
+    }
+
+    /// Export the user state (user dictionary, ignore list, accepted
+    /// corrections) as a text blob for storing or syncing.
+    #[wasm_bindgen(js_name = "exportUserState")]
+    pub fn export_user_state(&self) -> String {
+        self.handle.export_user_state()
+    }
+
+    /// Replace the user state with a blob produced by `exportUserState()`.
+    #[wasm_bindgen(js_name = "importUserState")]
+    pub fn import_user_state(&mut self, blob: &str) -> Result<(), JsError> {
+        self.handle
+            .import_user_state(blob)
+            .map_err(voikko_error_to_js)
+    }
+
     /// Release resources held by this instance.
     ///
     /// After calling this method, the instance should not be used.
@@ -358,6 +1295,115 @@ impl WasmVoikko {
         self.handle.set_accept_bulleted_lists_in_gc(value);
     }
 
+    /// Set whether sentence detection uses the speller to recognize abbreviations.
+    #[wasm_bindgen(js_name = "setSentenceSpellCheck")]
+    pub fn set_sentence_spell_check(&mut self, value: bool) {
+        self.handle.set_sentence_spell_check(value);
+    }
+
+    /// Set whether closing quotes and brackets after the terminal punctuation
+    /// belong to the ending sentence.
+    #[wasm_bindgen(js_name = "setSentenceClosingPunctuation")]
+    pub fn set_sentence_closing_punctuation(&mut self, value: bool) {
+        self.handle.set_sentence_closing_punctuation(value);
+    }
+
+    /// Set whether `sentences` lengths and `sentenceSpans` ends and texts
+    /// include the whitespace up to the next sentence:
+    /// "IncludeTrailingWhitespace" (the default) or "TrimEnd".
+    #[wasm_bindgen(js_name = "setSentenceWhitespace")]
+    pub fn set_sentence_whitespace(&mut self, policy: &str) -> Result<(), JsError> {
+        self.handle.set_sentence_whitespace(match policy {
+            "IncludeTrailingWhitespace" => SentenceWhitespace::IncludeTrailingWhitespace,
+            "TrimEnd" => SentenceWhitespace::TrimEnd,
+            other => {
+                return Err(JsError::new(&format!(
+                    "unknown sentence whitespace policy: {other}"
+                )));
+            }
+        });
+        Ok(())
+    }
+
+    /// Set whether `grammarErrorsFromText` joins hard-wrapped lines into
+    /// paragraphs separated by blank lines.
+    #[wasm_bindgen(js_name = "setReflowParagraphs")]
+    pub fn set_reflow_paragraphs(&mut self, value: bool) {
+        self.handle.set_reflow_paragraphs(value);
+    }
+
+    /// Set whether to report overlong compound words (opt-in style check).
+    #[wasm_bindgen(js_name = "setCheckOverlongCompounds")]
+    pub fn set_check_overlong_compounds(&mut self, value: bool) {
+        self.handle.set_check_overlong_compounds(value);
+    }
+
+    /// Set whether to report dates, clock times, ordinal numbers and units
+    /// written against the number style (opt-in check).
+    #[wasm_bindgen(js_name = "setCheckNumberFormats")]
+    pub fn set_check_number_formats(&mut self, value: bool) {
+        self.handle.set_check_number_formats(value);
+    }
+
+    /// Set whether the number format check expects technical conventions
+    /// ("12:30", leading zeros, ISO dates) instead of standard ones
+    /// ("klo 12.30", "1.2.2024").
+    #[wasm_bindgen(js_name = "setTechnicalNumberStyle")]
+    pub fn set_technical_number_style(&mut self, value: bool) {
+        self.handle.set_number_style(if value {
+            NumberStyle::Technical
+        } else {
+            NumberStyle::Standard
+        });
+    }
+
+    /// Set whether to run the embedded default grammar rule pack.
+    #[wasm_bindgen(js_name = "setCheckDefaultGrammarRules")]
+    pub fn set_check_default_grammar_rules(&mut self, value: bool) {
+        self.handle.set_check_default_grammar_rules(value);
+    }
+
+    /// Set whether to report runs of sentences starting with the same word
+    /// and quotation marks left unpaired across sentences (opt-in check).
+    #[wasm_bindgen(js_name = "setCheckDiscourse")]
+    pub fn set_check_discourse(&mut self, value: bool) {
+        self.handle.set_check_discourse(value);
+    }
+
+    /// Set whether to report "sekä" without "että" and "joko" without "tai"
+    /// in a sentence (opt-in check).
+    #[wasm_bindgen(js_name = "setCheckPairedConjunctions")]
+    pub fn set_check_paired_conjunctions(&mut self, value: bool) {
+        self.handle.set_check_paired_conjunctions(value);
+    }
+
+    /// Set whether to report correctly spelled words in a case their context
+    /// does not allow, such as "kohti kaupunki" (opt-in check).
+    #[wasm_bindgen(js_name = "setCheckRealWordErrors")]
+    pub fn set_check_real_word_errors(&mut self, value: bool) {
+        self.handle.set_check_real_word_errors(value);
+    }
+
+    /// Set whether to report finite verbs that do not agree with the
+    /// nominative subject before them, such as "koirat juoksee" (opt-in,
+    /// experimental check).
+    #[wasm_bindgen(js_name = "setCheckSubjectVerbAgreement")]
+    pub fn set_check_subject_verb_agreement(&mut self, value: bool) {
+        self.handle.set_check_subject_verb_agreement(value);
+    }
+
+    /// Set the maximum number of compound components before a word is reported.
+    #[wasm_bindgen(js_name = "setMaxCompoundParts")]
+    pub fn set_max_compound_parts(&mut self, value: usize) {
+        self.handle.set_max_compound_parts(value);
+    }
+
+    /// Set the maximum compound word length (in characters) before it is reported.
+    #[wasm_bindgen(js_name = "setMaxCompoundChars")]
+    pub fn set_max_compound_chars(&mut self, value: usize) {
+        self.handle.set_max_compound_chars(value);
+    }
+
     /// Set the minimum word length for hyphenation.
     #[wasm_bindgen(js_name = "setMinHyphenatedWordLength")]
     pub fn set_min_hyphenated_word_length(&mut self, value: usize) {
@@ -369,4 +1415,86 @@ impl WasmVoikko {
     pub fn set_max_suggestions(&mut self, value: usize) {
         self.handle.set_max_suggestions(value);
     }
+
+    /// Set the maximum word length in characters (at most 255, the
+    /// default). Longer words are not checked: `spellStatus` returns
+    /// "TooLong", and analysis, suggestions and hyphenation are empty.
+    #[wasm_bindgen(js_name = "setMaxWordChars")]
+    pub fn set_max_word_chars(&mut self, value: usize) {
+        self.handle.set_max_word_chars(value);
+    }
+
+    /// Set the coefficients of the suggestion ranking costs: edit distance,
+    /// weighted transducer path weight and morphological priority.
+    #[wasm_bindgen(js_name = "setSuggestionRanking")]
+    pub fn set_suggestion_ranking(&mut self, edit: f32, fst: f32, morph: f32) {
+        self.handle
+            .set_suggestion_ranking(RankingWeights { edit, fst, morph });
+    }
+
+    /// Set whether suggestions for a capitalized word list proper nouns
+    /// before common words.
+    #[wasm_bindgen(js_name = "setPreferProperNouns")]
+    pub fn set_prefer_proper_nouns(&mut self, value: bool) {
+        self.handle.set_prefer_proper_nouns(value);
+    }
+
+    /// Set whether suggestions follow the case of the misspelled word
+    /// ("KOIRRA" gets "KOIRA"). Enabled by default.
+    #[wasm_bindgen(js_name = "setMatchSuggestionCase")]
+    pub fn set_match_suggestion_case(&mut self, value: bool) {
+        self.handle.set_match_suggestion_case(value);
+    }
+
+    /// Set which typographic character classes (superscripts, subscripts,
+    /// fractions, full-width and mathematical digits) are mapped to plain
+    /// characters before spell checking and analysis.
+    #[wasm_bindgen(js_name = "setInputMapping")]
+    pub fn set_input_mapping(
+        &mut self,
+        superscripts: bool,
+        subscripts: bool,
+        fractions: bool,
+        typographic_digits: bool,
+    ) {
+        self.handle.set_input_mapping(InputMapping {
+            superscripts,
+            subscripts,
+            fractions,
+            typographic_digits,
+        });
+    }
+
+    /// Set whether zero-width and control characters (such as those in text
+    /// copied from web pages) are removed before tokenization and checking.
+    /// Positions still refer to the original text.
+    #[wasm_bindgen(js_name = "setStripInvisibleChars")]
+    pub fn set_strip_invisible_chars(&mut self, value: bool) {
+        self.handle.set_invisible_chars(if value {
+            InvisibleChars::Strip
+        } else {
+            InvisibleChars::Keep
+        });
+    }
+
+    /// Report grammar errors with `code` at `severity` ("Error" or
+    /// "Warning"). `null` restores the code's default severity.
+    #[wasm_bindgen(js_name = "setGrammarErrorSeverity")]
+    pub fn set_grammar_error_severity(
+        &mut self,
+        code: i32,
+        severity: Option<String>,
+    ) -> Result<(), JsError> {
+        match severity.as_deref() {
+            None => self.handle.reset_grammar_error_severity(code),
+            Some("Error") => self
+                .handle
+                .set_grammar_error_severity(code, GrammarErrorSeverity::Error),
+            Some("Warning") => self
+                .handle
+                .set_grammar_error_severity(code, GrammarErrorSeverity::Warning),
+            Some(other) => return Err(JsError::new(&format!("unknown severity: {other}"))),
+        }
+        Ok(())
+    }
 }